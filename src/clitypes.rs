@@ -111,6 +111,28 @@ macro_rules! fail_OOM_clierror {
     }};
 }
 
+/// write to stderr and log::error, using CliError::MalformedCsv
+macro_rules! fail_malformedcsv_clierror {
+    ($($t:tt)*) => {{
+        use log::error;
+        use crate::CliError;
+        let err = format!($($t)*);
+        error!("{err}");
+        Err(CliError::MalformedCsv(err))
+    }};
+}
+
+/// write to stderr and log::error, using CliError::InvalidSchema
+macro_rules! fail_invalidschema_clierror {
+    ($($t:tt)*) => {{
+        use log::error;
+        use crate::CliError;
+        let err = format!($($t)*);
+        error!("{err}");
+        Err(CliError::InvalidSchema(err))
+    }};
+}
+
 /// write to stderr and log::error, using a format string
 macro_rules! fail_format {
     ($($t:tt)*) => {{
@@ -131,6 +153,9 @@ pub enum QsvExitCode {
     NetworkError   = 3,
     OutOfMemory    = 4,
     EncodingError  = 5,
+    MalformedCsv   = 6,
+    InvalidSchema  = 7,
+    IoError        = 8,
     Warning        = 255,
 }
 
@@ -152,6 +177,10 @@ pub enum CliError {
     Network(String),
     OutOfMemory(String),
     Encoding(String),
+    MalformedCsv(String),
+    InvalidSchema(String),
+    /// an I/O error of validate, which has its own exit code
+    ValidateIo(io::Error),
     Other(String),
 }
 
@@ -160,11 +189,13 @@ impl fmt::Display for CliError {
         match *self {
             CliError::Flag(ref e) => e.fmt(f),
             CliError::Csv(ref e) => e.fmt(f),
-            CliError::Io(ref e) => e.fmt(f),
+            CliError::Io(ref e) | CliError::ValidateIo(ref e) => e.fmt(f),
             CliError::NoMatch() => f.write_str("no_match"),
             CliError::Other(ref s)
             | CliError::IncorrectUsage(ref s)
             | CliError::Encoding(ref s)
+            | CliError::MalformedCsv(ref s)
+            | CliError::InvalidSchema(ref s)
             | CliError::OutOfMemory(ref s)
             | CliError::Network(ref s) => f.write_str(s),
         }
@@ -291,6 +291,11 @@ apply options:
 Common options:
     -h, --help                  Display this message
     -o, --output <file>         Write output to <file> instead of stdout.
+    --in-place                  Apply the transformation to <input> in-place. The output is
+                                written to a tempfile that atomically replaces <input> when done.
+                                Cannot be used with stdin, a remote URL or --output.
+    --backup <suffix>           With --in-place, keep a copy of the original <input> with
+                                <suffix> appended to its file name (e.g. .bak).
     -n, --no-headers            When set, the first row will not be interpreted
                                 as headers.
     -d, --delimiter <arg>       The field delimiter for reading CSV data.
@@ -399,6 +404,8 @@ struct Args {
     flag_jobs:        Option<usize>,
     flag_new_column:  Option<String>,
     flag_output:      Option<String>,
+    flag_in_place:    bool,
+    flag_backup:      Option<String>,
     flag_no_headers:  bool,
     flag_delimiter:   Option<Delimiter>,
     flag_progressbar: bool,
@@ -443,8 +450,19 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .no_headers(args.flag_no_headers)
         .select(args.arg_column);
 
+    let in_place = util::InPlace::new(
+        args.flag_in_place,
+        args.arg_input.as_deref(),
+        args.flag_output.as_deref(),
+        args.flag_backup.as_deref(),
+    )?;
+    let output = match in_place {
+        Some(ref in_place) => Some(in_place.output()),
+        None => args.flag_output.clone(),
+    };
+
     let mut rdr = rconfig.reader()?;
-    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut wtr = Config::new(&output).writer()?;
 
     let headers = rdr.byte_headers()?.clone();
     let sel = rconfig.selection(&headers)?;
@@ -719,7 +737,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     if show_progress {
         util::finish_progress(&progress);
     }
-    Ok(wtr.flush()?)
+    wtr.flush()?;
+
+    if let Some(in_place) = in_place {
+        in_place.commit()?;
+    }
+    Ok(())
 }
 
 // validate apply operations for required options
@@ -0,0 +1,307 @@
+static USAGE: &str = r#"
+Discretizes a numeric column into bins, appending a column with the bin of each row -
+e.g. to turn ages into age groups, or amounts into deciles.
+
+The --method of binning is one of:
+  * equal-width: --bins bins of the same width, from the min to the max of the column.
+  * quantile: --bins bins of about the same number of rows.
+  * custom:<edges>: the bins between the given increasing edges, e.g. custom:0,10,50,100
+    for the three bins 0-10, 10-50 & 50-100. --bins is ignored.
+
+Each bin includes its lower edge, and the last bin includes its upper edge too. The bins
+are numbered from 1, or labelled with their edges with --label (e.g. "[10, 50)" and
+"[50, 100]"). The rows with an empty value, or a value outside of the custom edges, have
+an empty bin.
+
+The rows are streamed in a single pass when the bounds are known upfront: with custom
+edges, and with equal-width bins when the input has a current stats cache - i.e. a
+<input>.stats.csv.data.jsonl file, created with 'qsv stats --stats-jsonl'. Otherwise, a
+first pass over the column finds its min & max (stdin being spooled to a scratch file).
+The quantile bins need the values of the column, which are loaded in memory.
+
+Examples:
+
+  # the deciles of the amounts, in an amount_bin column
+  $ qsv bin amount --bins 10 --method quantile orders.csv
+
+  # age groups, labelled
+  $ qsv bin age --method custom:0,18,35,65,120 --label people.csv
+
+  # 5 bins of equal width, with their bounds from the stats cache
+  $ qsv stats --stats-jsonl data.csv > /dev/null
+  $ qsv bin price --bins 5 data.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_bin.rs.
+
+Usage:
+    qsv bin [options] <column> [<input>]
+    qsv bin --help
+
+bin options:
+    --bins <n>             The number of equal-width or quantile bins.
+                           [default: 10]
+    -m, --method <arg>     The binning method: equal-width, quantile or
+                           custom:<edges>.
+                           [default: equal-width]
+    --label                Write the edges of the bins instead of their numbers.
+    --round <places>       Round the edges of the labels to <places> decimal places.
+                           [default: 4]
+    --name <name>          The name of the new column. Defaults to <column>_bin.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use csv::ByteRecord;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column:      SelectColumns,
+    arg_input:       Option<String>,
+    flag_bins:       usize,
+    flag_method:     String,
+    flag_label:      bool,
+    flag_round:      u32,
+    flag_name:       Option<String>,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+enum Method {
+    EqualWidth,
+    Quantile,
+    Custom(Vec<f64>),
+}
+
+impl Method {
+    fn parse(method: &str) -> CliResult<Method> {
+        match method.trim().to_ascii_lowercase().as_str() {
+            "equal-width" => Ok(Method::EqualWidth),
+            "quantile" => Ok(Method::Quantile),
+            method => {
+                let Some(edges) = method.strip_prefix("custom:") else {
+                    return fail_incorrectusage_clierror!(
+                        "Invalid --method: {method}. Expected equal-width, quantile or \
+                         custom:<edges>."
+                    );
+                };
+                let edges = edges
+                    .split(',')
+                    .map(|edge| edge.trim().parse::<f64>())
+                    .collect::<Result<Vec<_>, _>>();
+                match edges {
+                    Ok(edges)
+                        if edges.len() >= 2
+                            && edges.iter().all(|edge| edge.is_finite())
+                            && edges.windows(2).all(|pair| pair[0] < pair[1]) =>
+                    {
+                        Ok(Method::Custom(edges))
+                    },
+                    _ => fail_incorrectusage_clierror!(
+                        "Invalid custom edges: {method}. Expected at least two increasing \
+                         numbers, e.g. custom:0,10,50,100."
+                    ),
+                }
+            },
+        }
+    }
+}
+
+/// parse a value of the binned column, None when it's empty
+fn parse_value(field: &[u8]) -> CliResult<Option<f64>> {
+    let field = field.trim_ascii();
+    if field.is_empty() {
+        return Ok(None);
+    }
+    match simdutf8::basic::from_utf8(field)
+        .ok()
+        .and_then(|field| field.parse::<f64>().ok())
+    {
+        Some(value) if value.is_finite() => Ok(Some(value)),
+        _ => fail_clierror!(
+            "Invalid value to bin: \"{}\" is not a number.",
+            String::from_utf8_lossy(field)
+        ),
+    }
+}
+
+/// the non-empty values of the column, in a first pass
+fn scan_values(rconfig: &Config, column: usize) -> CliResult<Vec<f64>> {
+    let mut rdr = rconfig.reader()?;
+    let mut values = Vec::new();
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        if let Some(value) = parse_value(record.get(column).unwrap_or_default())? {
+            values.push(value);
+        }
+    }
+    Ok(values)
+}
+
+/// the min & max of the column, from the stats cache if it's current
+fn cached_bounds(args: &Args, column: usize) -> CliResult<Option<(f64, f64)>> {
+    // the stats cache is computed with headers
+    if args.flag_no_headers {
+        return Ok(None);
+    }
+    let csv_stats = util::get_cached_stats_records(&args.arg_input, args.flag_delimiter)?;
+    let Some(stats) = csv_stats.get(column) else {
+        return Ok(None);
+    };
+    if stats.r#type != "Integer" && stats.r#type != "Float" {
+        if stats.r#type == "NULL" {
+            return Ok(None);
+        }
+        return fail_clierror!(
+            "Cannot bin {}: its type is {}, not a number.",
+            stats.field,
+            stats.r#type
+        );
+    }
+    let bound = |bound: &Option<String>| bound.as_ref().and_then(|b| b.parse::<f64>().ok());
+    Ok(bound(&stats.min).zip(bound(&stats.max)))
+}
+
+/// the edges of n bins of the same width between min & max
+fn equal_width_edges(min: f64, max: f64, n: usize) -> Vec<f64> {
+    let width = (max - min) / n as f64;
+    let mut edges: Vec<f64> = (0..n).map(|i| (i as f64).mul_add(width, min)).collect();
+    edges.push(max);
+    edges
+}
+
+/// the edges of n bins of about the same number of values, by linear interpolation
+/// between the sorted values. Bins with the same edges are merged.
+fn quantile_edges(mut values: Vec<f64>, n: usize) -> Vec<f64> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+    values.sort_unstable_by(f64::total_cmp);
+    let last = (values.len() - 1) as f64;
+    let mut edges: Vec<f64> = (0..=n)
+        .map(|i| {
+            let pos = last * i as f64 / n as f64;
+            let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+            (values[hi] - values[lo]).mul_add(pos - lo as f64, values[lo])
+        })
+        .collect();
+    edges.dedup();
+    if edges.len() == 1 {
+        // all the values are the same, so they're in a single bin
+        edges.push(edges[0]);
+    }
+    edges
+}
+
+/// the 1-based bin of the value, None when it's outside of the edges
+fn bin_of(edges: &[f64], value: f64) -> Option<usize> {
+    let last = edges.len().checked_sub(1)?;
+    if last == 0 || value < edges[0] || value > edges[last] {
+        return None;
+    }
+    // the number of edges <= value is the bin of the value, and the max is in the last bin
+    Some(edges.partition_point(|&edge| edge <= value).min(last))
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let method = Method::parse(&args.flag_method)?;
+    if args.flag_bins == 0 {
+        return fail_incorrectusage_clierror!("--bins must be greater than 0.");
+    }
+
+    let mut rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column.clone());
+
+    // the bounds of stdin are found in a first pass, so it's spooled
+    let _stdin_spool = if rconfig.is_stdin() && !matches!(method, Method::Custom(_)) {
+        let (spooled_rconfig, spool_dir) = util::spool_stdin(&rconfig)?;
+        rconfig = spooled_rconfig;
+        Some(spool_dir)
+    } else {
+        None
+    };
+
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    if sel.len() != 1 {
+        return fail_incorrectusage_clierror!("<column> must select a single column.");
+    }
+    let column = sel[0];
+
+    let edges = match method {
+        Method::Custom(edges) => edges,
+        Method::Quantile => quantile_edges(scan_values(&rconfig, column)?, args.flag_bins),
+        Method::EqualWidth => {
+            let bounds = match cached_bounds(&args, column)? {
+                Some(bounds) => Some(bounds),
+                None => scan_values(&rconfig, column)?
+                    .into_iter()
+                    .fold(None, |bounds, value| match bounds {
+                        Some((min, max)) => Some((value.min(min), value.max(max))),
+                        None => Some((value, value)),
+                    }),
+            };
+            match bounds {
+                Some((min, max)) => equal_width_edges(min, max, args.flag_bins),
+                None => Vec::new(),
+            }
+        },
+    };
+
+    let labels: Vec<String> = edges
+        .windows(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            if args.flag_label {
+                let close = if i + 2 == edges.len() { ']' } else { ')' };
+                format!(
+                    "[{}, {}{close}",
+                    util::round_num(pair[0], args.flag_round),
+                    util::round_num(pair[1], args.flag_round)
+                )
+            } else {
+                (i + 1).to_string()
+            }
+        })
+        .collect();
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if !rconfig.no_headers {
+        let name = match args.flag_name {
+            Some(ref name) => name.clone(),
+            None => format!("{}_bin", String::from_utf8_lossy(&headers[column])),
+        };
+        let mut out_headers = headers.clone();
+        out_headers.push_field(name.as_bytes());
+        wtr.write_byte_record(&out_headers)?;
+    }
+
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        let bin = parse_value(record.get(column).unwrap_or_default())?
+            .and_then(|value| bin_of(&edges, value));
+        match bin {
+            Some(bin) => record.push_field(labels[bin - 1].as_bytes()),
+            None => record.push_field(b""),
+        }
+        wtr.write_byte_record(&record)?;
+    }
+    Ok(wtr.flush()?)
+}
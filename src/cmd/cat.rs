@@ -128,7 +128,7 @@ fn get_parentdir_and_file(path: &Path, stem_only: bool) -> String {
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
 
-    let tmpdir = tempfile::tempdir()?;
+    let tmpdir = util::scratch_dir()?;
     args.arg_input = util::process_input(args.arg_input, &tmpdir, "")?;
     if args.cmd_rows {
         args.cat_rows()
@@ -212,7 +212,7 @@ impl Args {
         // we're creating a temp_dir in case we have stdin input, as we need to save it to a
         // file named "stdin" under the temp_dir. This is required as we need to scan
         // the files twice. temp_dir will be automatically deleted when it goes out of scope.
-        let temp_dir = tempfile::tempdir()?;
+        let temp_dir = util::scratch_dir()?;
         let mut stdin_tempfilename = std::path::PathBuf::new();
 
         // we need to create a temporary header in case --no-headers is set
@@ -0,0 +1,158 @@
+static USAGE: &str = r#"
+Removes the orphaned scratch directories left behind by qsv runs that crashed or
+were killed.
+
+Every qsv run that needs temporary disk space (e.g. extsort, or validate with
+snappy-compressed input) creates its own "qsv-run-*" scratch directory in QSV_TMPDIR,
+or in the system temp dir if QSV_TMPDIR is not set. The scratch directory is removed
+when the run ends, but not when qsv is killed or crashes.
+
+A scratch directory is orphaned when the qsv process that created it is no longer
+running. Scratch directories created on other hosts (e.g. when QSV_TMPDIR is on a
+shared filesystem) are only removed with --all-hosts, as their processes cannot be
+checked from this host.
+
+The path of each removed scratch directory is written to stdout.
+
+Examples:
+
+  # list the orphaned scratch directories, without removing them
+  $ qsv cleanup --dry-run
+
+  # remove the orphaned scratch directories in a shared scratch area
+  $ QSV_TMPDIR=/scratch/qsv qsv cleanup
+
+Usage:
+    qsv cleanup [options]
+    qsv cleanup --help
+
+cleanup options:
+    --dry-run              List the orphaned scratch directories without removing them.
+    --min-age <minutes>    Only remove orphaned scratch directories that were last
+                           modified at least <minutes> ago. [default: 0]
+    --all-hosts            Also remove the scratch directories created on other hosts
+                           that are older than --min-age, without checking whether their
+                           qsv runs are still running.
+
+Common options:
+    -h, --help             Display this message
+"#;
+
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use serde::Deserialize;
+use sysinfo::{Pid, ProcessRefreshKind, RefreshKind, System};
+
+use crate::{util, CliResult};
+
+#[derive(Deserialize)]
+struct Args {
+    flag_dry_run:   bool,
+    flag_min_age:   u64,
+    flag_all_hosts: bool,
+}
+
+// how long a scratch dir may be without an owner marker before it's considered orphaned
+const UNOWNED_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// the owner of a scratch directory, from its owner marker file
+struct Owner {
+    pid:  Option<u32>,
+    host: String,
+}
+
+fn read_owner(scratch_dir: &Path) -> Owner {
+    let contents =
+        fs::read_to_string(scratch_dir.join(util::SCRATCH_OWNER_FILE)).unwrap_or_default();
+    let mut lines = contents.lines();
+    Owner {
+        pid:  lines.next().and_then(|pid| pid.trim().parse().ok()),
+        host: lines.next().unwrap_or_default().trim().to_string(),
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let base_dir = util::scratch_base_dir();
+    let entries = match fs::read_dir(&base_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            winfo!("No scratch directories found in {}.", base_dir.display());
+            return Ok(());
+        },
+        Err(e) => return fail_clierror!("Cannot read {}: {e}", base_dir.display()),
+    };
+
+    let sys =
+        System::new_with_specifics(RefreshKind::new().with_processes(ProcessRefreshKind::new()));
+    let this_host = System::host_name().unwrap_or_default();
+    let min_age = Duration::from_secs(args.flag_min_age * 60);
+    let now = SystemTime::now();
+
+    let mut removed: u64 = 0;
+    let mut skipped: u64 = 0;
+    for entry in entries {
+        let entry = entry?;
+        let is_scratch_dir = entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(util::SCRATCH_DIR_PREFIX)
+            && entry.file_type().is_ok_and(|ft| ft.is_dir());
+        if !is_scratch_dir {
+            continue;
+        }
+        let scratch_dir = entry.path();
+
+        let age = entry
+            .metadata()
+            .and_then(|md| md.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age < min_age {
+            skipped += 1;
+            continue;
+        }
+
+        // a scratch dir without an owner marker is either being set up right now,
+        // or was never fully set up by a run that crashed. Otherwise, it's orphaned
+        // if its owner process is no longer running.
+        let owner = read_owner(&scratch_dir);
+        let orphaned = match owner.pid {
+            None => age >= UNOWNED_GRACE_PERIOD,
+            Some(_) if owner.host != this_host => args.flag_all_hosts,
+            Some(pid) => sys.process(Pid::from_u32(pid)).is_none(),
+        };
+        if !orphaned {
+            skipped += 1;
+            continue;
+        }
+
+        if !args.flag_dry_run {
+            if let Err(e) = fs::remove_dir_all(&scratch_dir) {
+                wwarn!("Cannot remove {}: {e}", scratch_dir.display());
+                continue;
+            }
+        }
+        println!("{}", scratch_dir.display());
+        removed += 1;
+    }
+
+    winfo!(
+        "{} {removed} orphaned scratch director{} in {}. Skipped {skipped} in use, too recent \
+         or on other hosts.",
+        if args.flag_dry_run {
+            "Found"
+        } else {
+            "Removed"
+        },
+        if removed == 1 { "y" } else { "ies" },
+        base_dir.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,175 @@
+static USAGE: &str = r#"
+Performs set operations on the values of a column (or a tuple of columns) across two
+CSV files, answering questions like "Which IDs are in A but not in B?" without
+a join & filter dance.
+
+The subcommands are:
+    intersect  The distinct values found in both <input1> and <input2>.
+    union      The distinct values found in either <input1> or <input2>.
+    except     The distinct values found in <input1> but not in <input2>.
+
+The output is a CSV with the selected columns only, listing each distinct value
+(or tuple of values) once, in the order it first appears in <input1>
+(and <input2> for union). The headers of <input1>'s selected columns are used.
+
+Both files are streamed and the values are tracked using memory-mapped, on-disk
+hash tables (like the 'extdedup' command) when they don't fit in the memory buffer,
+so this command works with arbitrarily large files.
+
+Examples:
+
+  # which customer IDs placed orders in 2023 but not in 2024?
+  qsv colops except --select customer_id orders-2023.csv orders-2024.csv
+
+  # the (first_name, last_name) pairs found in both files
+  qsv colops intersect -s first_name,last_name a.csv b.csv
+
+  # when the key columns are named differently in the second file
+  qsv colops union -s id --select2 customer_id a.csv b.csv -o all-ids.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_colops.rs.
+
+Usage:
+    qsv colops intersect [options] <input1> <input2>
+    qsv colops union [options] <input1> <input2>
+    qsv colops except [options] <input1> <input2>
+    qsv colops --help
+
+colops options:
+    -s, --select <arg>     The column/s to compare. Columns can be referenced by name
+                           or index, starting at 1. (See 'qsv select --help' for the
+                           full syntax.) If not set, whole rows are compared.
+    --select2 <arg>        The column/s to compare in <input2>, when they differ from
+                           the --select columns. Must select the same number of columns.
+    --memory-limit <arg>   The maximum amount of memory to buffer the on-disk hash tables.
+                           This is a percentage of total memory. [default: 10]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::fmt::Write;
+
+use serde::Deserialize;
+use sysinfo::System;
+
+use crate::{
+    config::{Config, Delimiter},
+    odhtcache::ExtDedupCache,
+    select::{SelectColumns, Selection},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    cmd_intersect:     bool,
+    cmd_union:         bool,
+    cmd_except:        bool,
+    arg_input1:        String,
+    arg_input2:        String,
+    flag_select:       SelectColumns,
+    flag_select2:      Option<SelectColumns>,
+    flag_memory_limit: Option<u8>,
+    flag_output:       Option<String>,
+    flag_no_headers:   bool,
+    flag_delimiter:    Option<Delimiter>,
+}
+
+const MEMORY_LIMITED_BUFFER: u64 = 100 * 1_000_000; // 100 MB
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf1 = Config::new(&Some(args.arg_input1.clone()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select.clone());
+    let rconf2 = Config::new(&Some(args.arg_input2.clone()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select2.unwrap_or(args.flag_select));
+
+    let mut rdr1 = rconf1.reader()?;
+    let mut rdr2 = rconf2.reader()?;
+    let headers1 = rdr1.byte_headers()?.clone();
+    let sel1 = rconf1.selection(&headers1)?;
+    let sel2 = rconf2.selection(rdr2.byte_headers()?)?;
+    if sel1.len() != sel2.len() {
+        return fail_incorrectusage_clierror!(
+            "Column selections must have the same number of columns, but found column \
+             selections with {} and {} columns.",
+            sel1.len(),
+            sel2.len()
+        );
+    }
+
+    // the memory buffer is shared by the (up to) two on-disk hash tables.
+    // If we cannot detect the total memory, use a default of 100 MB
+    let mem_limited_buffer = if sysinfo::IS_SUPPORTED_SYSTEM {
+        let mut sys = System::new();
+        sys.refresh_memory();
+        sys.total_memory() / 100 * u64::from(u8::min(args.flag_memory_limit.unwrap_or(10), 50))
+    } else {
+        MEMORY_LIMITED_BUFFER
+    };
+    log::info!("{mem_limited_buffer} bytes used for memory buffer for on-disk hash tables...");
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if !rconf1.no_headers {
+        wtr.write_record(sel1.select(&headers1))?;
+    }
+
+    // the values already written
+    let mut seen = ExtDedupCache::new(mem_limited_buffer / 2);
+    let mut key = String::new();
+    let mut record = csv::ByteRecord::new();
+
+    if args.cmd_union {
+        for (rdr, sel) in [(&mut rdr1, &sel1), (&mut rdr2, &sel2)] {
+            while rdr.read_byte_record(&mut record)? {
+                row_key(sel, &record, &mut key);
+                if !seen.contains(&key) {
+                    seen.insert(&key);
+                    wtr.write_record(sel.select(&record))?;
+                }
+            }
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    // intersect & except only need the values of <input2>
+    let mut values2 = ExtDedupCache::new(mem_limited_buffer / 2);
+    while rdr2.read_byte_record(&mut record)? {
+        row_key(&sel2, &record, &mut key);
+        values2.insert(&key);
+    }
+
+    while rdr1.read_byte_record(&mut record)? {
+        row_key(&sel1, &record, &mut key);
+        let in_input2 = values2.contains(&key);
+        if ((args.cmd_intersect && in_input2) || (args.cmd_except && !in_input2))
+            && !seen.contains(&key)
+        {
+            seen.insert(&key);
+            wtr.write_record(sel1.select(&record))?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
+
+/// Encode the selected values of a record into `key`.
+/// Each value is prefixed with its length so tuples can't collide,
+/// e.g. ("a,b", "c") and ("a", "b,c").
+#[inline]
+fn row_key(sel: &Selection, record: &csv::ByteRecord, key: &mut String) {
+    key.clear();
+    for field in sel.select(record) {
+        let _ = write!(key, "{}:{}", field.len(), String::from_utf8_lossy(field));
+    }
+}
@@ -0,0 +1,289 @@
+static USAGE: &str = r#"
+Computes the pairwise correlation matrix of the numeric columns of a CSV.
+
+The output has a row & a column per numeric column, with the correlation coefficient of
+each pair of columns, between -1 and 1. Each pair is correlated over the rows where both
+columns have a value, so NULLs are skipped pairwise. A coefficient is empty when it's
+undefined, i.e. when one of the columns is constant over these rows. The coefficient of a
+column with itself is always 1.
+
+Columns with values that are not numbers are skipped with a warning.
+
+The correlation methods are:
+  * pearson   the linear correlation, computed in a single pass in constant memory
+              with Welford-style co-moment accumulators.
+  * spearman  the rank correlation, i.e. the pearson correlation of the ranks of the
+              values, with tied values ranked at their average rank. As the values have
+              to be ranked, the numeric columns are loaded in memory.
+
+Examples:
+
+  # the pearson correlation matrix of all the numeric columns
+  $ qsv corr data.csv
+
+  # the spearman rank correlation matrix of a few columns
+  $ qsv corr --method spearman --select price,sqft,bedrooms houses.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_corr.rs.
+
+Usage:
+    qsv corr [options] [<input>]
+    qsv corr --help
+
+corr options:
+    -s, --select <arg>     Select the columns to correlate.
+                           See 'qsv select --help' for the format details.
+    -m, --method <arg>     The correlation method, pearson or spearman.
+                           [default: pearson]
+    --round <places>       Round the coefficients to <places> decimal places.
+                           [default: 4]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. The columns are then named by their index.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_select:     SelectColumns,
+    flag_method:     String,
+    flag_round:      u32,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Method {
+    Pearson,
+    Spearman,
+}
+
+/// the streaming co-moments of a pair of columns, updated with Welford's method
+#[derive(Clone, Default)]
+struct CoMoments {
+    n:      f64,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x:   f64,
+    m2_y:   f64,
+    c_xy:   f64,
+}
+
+impl CoMoments {
+    #[inline]
+    fn add(&mut self, x: f64, y: f64) {
+        self.n += 1.0;
+        let dx = x - self.mean_x;
+        self.mean_x += dx / self.n;
+        let dy = y - self.mean_y;
+        self.mean_y += dy / self.n;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    /// the pearson correlation coefficient, if defined
+    fn correlation(&self) -> Option<f64> {
+        let denominator = (self.m2_x * self.m2_y).sqrt();
+        if denominator > 0.0 {
+            // rounding errors can put the coefficient slightly out of bounds
+            Some((self.c_xy / denominator).clamp(-1.0, 1.0))
+        } else {
+            None
+        }
+    }
+}
+
+/// the ranks of the values, tied values being ranked at their average rank
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_unstable_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]].total_cmp(&values[order[start]]).is_eq() {
+            end += 1;
+        }
+        // the average of the 1-based ranks start + 1 ..= end
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &idx in &order[start..end] {
+            ranks[idx] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// the spearman correlation coefficient of the rows where both columns have a value
+fn spearman(xs: &[Option<f64>], ys: &[Option<f64>]) -> Option<f64> {
+    let (xs, ys): (Vec<f64>, Vec<f64>) = xs
+        .iter()
+        .zip(ys)
+        .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+        .unzip();
+    let mut comoments = CoMoments::default();
+    for (x, y) in ranks(&xs).into_iter().zip(ranks(&ys)) {
+        comoments.add(x, y);
+    }
+    comoments.correlation()
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let method = match args.flag_method.to_ascii_lowercase().as_str() {
+        "pearson" => Method::Pearson,
+        "spearman" => Method::Spearman,
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --method: {}. Must be pearson or spearman.",
+                args.flag_method
+            )
+        },
+    };
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let ncols = sel.len();
+
+    // whether each column only has numbers so far, and whether it has any
+    let mut numeric = vec![true; ncols];
+    let mut has_values = vec![false; ncols];
+    // the pearson co-moments of each pair of columns i < j, at i * ncols + j
+    let mut comoments = if method == Method::Pearson {
+        vec![CoMoments::default(); ncols * ncols]
+    } else {
+        Vec::new()
+    };
+    // the values of each column, for spearman
+    let mut columns: Vec<Vec<Option<f64>>> = if method == Method::Spearman {
+        vec![Vec::new(); ncols]
+    } else {
+        Vec::new()
+    };
+
+    let mut record = csv::ByteRecord::new();
+    let mut values: Vec<Option<f64>> = vec![None; ncols];
+    while rdr.read_byte_record(&mut record)? {
+        for (i, field) in sel.select(&record).enumerate() {
+            let field = field.trim_ascii();
+            values[i] = if field.is_empty() || !numeric[i] {
+                None
+            } else {
+                let value = simdutf8::basic::from_utf8(field)
+                    .ok()
+                    .and_then(|s| s.parse::<f64>().ok())
+                    .filter(|value| value.is_finite());
+                if value.is_some() {
+                    has_values[i] = true;
+                } else {
+                    numeric[i] = false;
+                }
+                value
+            };
+        }
+
+        match method {
+            Method::Pearson => {
+                for (i, x) in values.iter().enumerate() {
+                    let Some(x) = *x else {
+                        continue;
+                    };
+                    for (j, y) in values.iter().enumerate().skip(i + 1) {
+                        if let Some(y) = *y {
+                            comoments[i * ncols + j].add(x, y);
+                        }
+                    }
+                }
+            },
+            Method::Spearman => {
+                for (column, value) in columns.iter_mut().zip(&values) {
+                    column.push(*value);
+                }
+            },
+        }
+    }
+
+    // the columns with only numbers
+    let mut corr_cols = Vec::with_capacity(ncols);
+    for (i, &idx) in sel.iter().enumerate() {
+        if numeric[i] && has_values[i] {
+            corr_cols.push(i);
+        } else {
+            wwarn!(
+                "Skipping non-numeric column {}.",
+                if args.flag_no_headers {
+                    idx.to_string()
+                } else {
+                    String::from_utf8_lossy(&headers[idx]).into_owned()
+                }
+            );
+        }
+    }
+    if corr_cols.len() < 2 {
+        return fail_clierror!("corr requires at least two numeric columns.");
+    }
+
+    let field_names: Vec<Vec<u8>> = corr_cols
+        .iter()
+        .map(|&i| {
+            if args.flag_no_headers {
+                sel[i].to_string().into_bytes()
+            } else {
+                headers[sel[i]].to_vec()
+            }
+        })
+        .collect();
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut out_record = csv::ByteRecord::new();
+    out_record.push_field(b"field");
+    for field_name in &field_names {
+        out_record.push_field(field_name);
+    }
+    wtr.write_byte_record(&out_record)?;
+
+    for (row, &i) in corr_cols.iter().enumerate() {
+        out_record.clear();
+        out_record.push_field(&field_names[row]);
+        for &j in &corr_cols {
+            let correlation = if i == j {
+                Some(1.0)
+            } else {
+                let (a, b) = (i.min(j), i.max(j));
+                match method {
+                    Method::Pearson => comoments[a * ncols + b].correlation(),
+                    Method::Spearman => spearman(&columns[a], &columns[b]),
+                }
+            };
+            match correlation {
+                Some(correlation) => {
+                    out_record.push_field(util::round_num(correlation, args.flag_round).as_bytes())
+                },
+                None => out_record.push_field(b""),
+            }
+        }
+        wtr.write_byte_record(&out_record)?;
+    }
+    Ok(wtr.flush()?)
+}
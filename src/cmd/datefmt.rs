@@ -38,6 +38,22 @@ Get the day of the week for several date columns and store it in the correspondi
 
   $ qsv datefmt OpenDate,CloseDate --formatstr '%u' --rename Open_weekday,Close_weekday file.csv
 
+Convert the millisecond Unix timestamps in the ts column of a log export to readable dates:
+
+  $ qsv datefmt ts --ts-resolution milli --formatstr '%Y-%m-%d %H:%M:%S' --output-tz local log.csv
+
+Convert dates to Unix timestamps in milliseconds:
+
+  $ qsv datefmt OpenDate --to-epoch milli file.csv
+
+Describe dates relative to now (e.g. "3 days ago", "in 2 hours") in a new column:
+
+  $ qsv datefmt LastLogin --relative --new-column last_seen file.csv
+
+Describe dates relative to a fixed date instead, for reproducible reports:
+
+  $ qsv datefmt LastLogin --relative --now 2024-06-30T00:00:00Z file.csv
+
 For more extensive examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_datefmt.rs.
 
 Usage:
@@ -81,6 +97,14 @@ datefmt options:
     -R, --ts-resolution <res>   The resolution to use when parsing Unix timestamps.
                                 Valid values are "sec", "milli", "micro", "nano".
                                 [default: sec]
+    --to-epoch <res>            Output Unix timestamps instead of formatted dates, with the
+                                given resolution - "sec", "milli", "micro" or "nano".
+                                The --formatstr and --output-tz options are ignored.
+    --relative                  Output humanized dates relative to now instead of formatted
+                                dates, e.g. "3 days ago", "in 2 hours" or "just now".
+                                The --formatstr and --output-tz options are ignored.
+    --now <datetime>            The date to use as "now" with --relative, in any of the
+                                recognized date formats. Defaults to the current date and time.
     -j, --jobs <arg>            The number of jobs to run in parallel.
                                 When not set, the number of jobs is set to the number of CPUs detected.
     -b, --batch <size>          The number of rows per batch to load into memory, before running in parallel.
@@ -90,6 +114,11 @@ datefmt options:
 Common options:
     -h, --help                  Display this message
     -o, --output <file>         Write output to <file> instead of stdout.
+    --in-place                  Format the dates of <input> in-place. The output is written
+                                to a tempfile that atomically replaces <input> when done.
+                                Cannot be used with stdin, a remote URL or --output.
+    --backup <suffix>           With --in-place, keep a copy of the original <input> with
+                                <suffix> appended to its file name (e.g. .bak).
     -n, --no-headers            When set, the first row will not be interpreted
                                 as headers.
     -d, --delimiter <arg>       The field delimiter for reading CSV data.
@@ -133,10 +162,15 @@ struct Args {
     flag_default_tz:     Option<String>,
     flag_utc:            bool,
     flag_zulu:           bool,
+    flag_to_epoch:       Option<String>,
+    flag_relative:       bool,
+    flag_now:            Option<String>,
     flag_batch:          usize,
     flag_jobs:           Option<usize>,
     flag_new_column:     Option<String>,
     flag_output:         Option<String>,
+    flag_in_place:       bool,
+    flag_backup:         Option<String>,
     flag_no_headers:     bool,
     flag_delimiter:      Option<Delimiter>,
     flag_progressbar:    bool,
@@ -191,6 +225,49 @@ fn unix_timestamp(input: &str, resolution: TimestampResolution) -> Option<DateTi
     }
 }
 
+#[inline]
+fn to_unix_timestamp(date: &DateTime<Utc>, resolution: TimestampResolution) -> String {
+    match resolution {
+        TimestampResolution::Second => date.timestamp().to_string(),
+        TimestampResolution::Millisecond => date.timestamp_millis().to_string(),
+        TimestampResolution::Microsecond => date.timestamp_micros().to_string(),
+        TimestampResolution::Nanosecond => date
+            .timestamp_nanos_opt()
+            .map(|nanos| nanos.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// describe `date` relative to `now`, e.g. "3 days ago" or "in 2 hours"
+fn humanize_relative(date: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+
+    let delta = now.signed_duration_since(*date).num_seconds();
+    let secs = delta.abs();
+    if secs < MINUTE {
+        return "just now".to_string();
+    }
+    let (count, unit) = if secs < HOUR {
+        (secs / MINUTE, "minute")
+    } else if secs < DAY {
+        (secs / HOUR, "hour")
+    } else if secs < 30 * DAY {
+        (secs / DAY, "day")
+    } else if secs < 365 * DAY {
+        (secs / (30 * DAY), "month")
+    } else {
+        (secs / (365 * DAY), "year")
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    if delta > 0 {
+        format!("{count} {unit}{plural} ago")
+    } else {
+        format!("in {count} {unit}{plural}")
+    }
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let rconfig = Config::new(&args.arg_input)
@@ -198,13 +275,32 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .no_headers(args.flag_no_headers)
         .select(args.arg_column);
 
+    let in_place = util::InPlace::new(
+        args.flag_in_place,
+        args.arg_input.as_deref(),
+        args.flag_output.as_deref(),
+        args.flag_backup.as_deref(),
+    )?;
+    let output = match in_place {
+        Some(ref in_place) => Some(in_place.output()),
+        None => args.flag_output.clone(),
+    };
+
     let mut rdr = rconfig.reader()?;
-    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut wtr = Config::new(&output).writer()?;
 
     let headers = rdr.byte_headers()?.clone();
     let sel = rconfig.selection(&headers)?;
 
     let tsres = args.flag_ts_resolution.parse::<TimestampResolution>()?;
+    if args.flag_to_epoch.is_some() && args.flag_relative {
+        return fail_incorrectusage_clierror!("--to-epoch and --relative cannot be used together.");
+    }
+    let to_epoch = args
+        .flag_to_epoch
+        .as_deref()
+        .map(str::parse::<TimestampResolution>)
+        .transpose()?;
 
     let mut headers = rdr.headers()?.clone();
 
@@ -316,6 +412,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let is_output_utc = output_tz == chrono_tz::UTC;
 
+    let relative_now = match args.flag_now {
+        Some(ref now) if args.flag_relative => match unix_timestamp(now, tsres) {
+            Some(ts) => Some(ts),
+            None => match parse_with_preference_and_timezone(now, prefer_dmy, &input_tz) {
+                Ok(now) => Some(now),
+                Err(e) => return fail_incorrectusage_clierror!("Invalid --now date {now}: {e}"),
+            },
+        },
+        _ if args.flag_relative => Some(Utc::now()),
+        _ => None,
+    };
+
     // set RAYON_NUM_THREADS
     util::njobs(args.flag_jobs);
 
@@ -365,18 +473,24 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                             parse_with_preference_and_timezone(&cell, prefer_dmy, &input_tz)
                         };
                         if let Ok(format_date) = parsed_date {
-                            // don't need to call with_timezone() if output_tz is UTC
-                            // as format_date is already in UTC
-                            formatted_date = if is_output_utc {
-                                format_date.format(&flag_formatstr).to_string()
+                            if let Some(epoch_res) = to_epoch {
+                                cell = to_unix_timestamp(&format_date, epoch_res);
+                            } else if let Some(now) = relative_now {
+                                cell = humanize_relative(&format_date, &now);
                             } else {
-                                format_date_with_tz = format_date.with_timezone(&output_tz);
-                                format_date_with_tz.format(&flag_formatstr).to_string()
-                            };
-                            if !keep_zero_time && formatted_date.ends_with("T00:00:00+00:00") {
-                                formatted_date[..10].clone_into(&mut cell);
-                            } else {
-                                formatted_date.clone_into(&mut cell);
+                                // don't need to call with_timezone() if output_tz is UTC
+                                // as format_date is already in UTC
+                                formatted_date = if is_output_utc {
+                                    format_date.format(&flag_formatstr).to_string()
+                                } else {
+                                    format_date_with_tz = format_date.with_timezone(&output_tz);
+                                    format_date_with_tz.format(&flag_formatstr).to_string()
+                                };
+                                if !keep_zero_time && formatted_date.ends_with("T00:00:00+00:00") {
+                                    formatted_date[..10].clone_into(&mut cell);
+                                } else {
+                                    formatted_date.clone_into(&mut cell);
+                                }
                             }
                         }
                     }
@@ -407,5 +521,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     if show_progress {
         util::finish_progress(&progress);
     }
-    Ok(wtr.flush()?)
+    wtr.flush()?;
+
+    if let Some(in_place) = in_place {
+        in_place.commit()?;
+    }
+    Ok(())
 }
@@ -8,10 +8,25 @@ That is, unless the --sorted option is used to indicate the CSV is already sorte
 typically, with the sort cmd for more sorting options or the extsort cmd for larger
 than memory CSV files. This will make dedup run in streaming mode with constant memory.
 
-Either way, the output will not only be deduplicated, it will also be sorted.
+Either way, the output will not only be deduplicated, it will also be sorted - unless the
+row kept of each group of duplicates is chosen with --keep, in which case the kept rows
+are written in input order.
 
 A duplicate count will also be sent to <stderr>.
 
+With --similarity, near-duplicates are deduplicated too: rows whose --compare columns
+have a similarity of at least the given threshold (between 0 & 1) are duplicates. The
+rows are compared in input order with the first row of each cluster of duplicates, so
+only the first row of each cluster is kept, and the output is not sorted. The similarity
+metric is either:
+  levenshtein - the normalized edit distance of the values, so 0.9 allows about one
+                edit per 10 characters.
+  jaccard     - the number of common words of the values over their number of
+                distinct words.
+With --clusters, the clusters of near-duplicates are written instead, with a cluster
+column numbering them. Fuzzy deduplication compares every row with each cluster, so it
+is much slower than exact deduplication - the whole CSV is loaded in memory as well.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_dedup.rs.
 
 Usage:
@@ -28,8 +43,28 @@ dedup options:
     --sorted                   The input is already sorted. Do not load the CSV into
                                memory to sort it first. Meant to be used in tandem and
                                after an extsort.
+    --keep <strategy>          The row to keep of each group of duplicates, the kept
+                               rows being written in input order. The strategies are:
+                                 first   - the first row in input order.
+                                 last    - the last row in input order.
+                                 min:col - the row with the smallest value of col
+                                           (compared as numbers when they're numbers).
+                                 max:col - the row with the largest value of col.
+                               The rows with an empty col value are only kept when
+                               all the rows of the group are empty, and ties are
+                               resolved by keeping the first row.
+                               Cannot be used with --sorted or --similarity.
     -D, --dupes-output <file>  Write duplicates to <file>.
     -H, --human-readable       Comma separate duplicate count.
+    --similarity <threshold>   Also deduplicate near-duplicates, whose similarity is at
+                               least <threshold>, between 0 & 1.
+    --compare <cols>           The columns compared with --similarity, joined with a
+                               space. When not set, the --select columns.
+    --metric <name>            The --similarity metric: levenshtein or jaccard.
+                               [default: levenshtein]
+    --clusters                 With --similarity, write the clusters of
+                               near-duplicates (with at least two rows) instead of
+                               the deduplicated rows, with a "cluster" column first.
     -j, --jobs <arg>           The number of jobs to run in parallel when sorting
                                an unsorted CSV, before deduping.
                                When not set, the number of jobs is set to the
@@ -46,12 +81,15 @@ Common options:
                                appear as the header row in the output.
     -d, --delimiter <arg>      The field delimiter for reading CSV data.
                                Must be a single character. (default: ,)
+    --locked-schema <file>     Refuse to run if the input's columns or column types
+                               don't match the JSON Schema <file> (e.g. one generated
+                               by `qsv schema`), listing the differences.
     -Q, --quiet                Do not print duplicate count to stderr.
     --memcheck                 Check if there is enough memory to load the entire
                                CSV into memory using CONSERVATIVE heuristics.
 "#;
 
-use std::cmp;
+use std::{cmp, collections::HashSet};
 
 use csv::ByteRecord;
 use rayon::slice::ParallelSliceMut;
@@ -61,7 +99,7 @@ use simdutf8::basic::from_utf8;
 use crate::{
     cmd::sort::{iter_cmp, iter_cmp_num},
     config::{Config, Delimiter},
-    select::SelectColumns,
+    select::{SelectColumns, Selection},
     util, CliResult,
 };
 #[derive(Deserialize)]
@@ -71,11 +109,17 @@ struct Args {
     flag_numeric:        bool,
     flag_ignore_case:    bool,
     flag_sorted:         bool,
+    flag_keep:           Option<String>,
     flag_dupes_output:   Option<String>,
     flag_output:         Option<String>,
     flag_no_headers:     bool,
     flag_delimiter:      Option<Delimiter>,
+    flag_locked_schema:  Option<String>,
     flag_human_readable: bool,
+    flag_similarity:     Option<f64>,
+    flag_compare:        Option<SelectColumns>,
+    flag_metric:         String,
+    flag_clusters:       bool,
     flag_jobs:           Option<usize>,
     flag_quiet:          bool,
     flag_memcheck:       bool,
@@ -87,6 +131,20 @@ enum ComparisonMode {
     Normal,
 }
 
+/// the row to keep of a group of duplicates, the column indices of min & max
+enum Keep {
+    First,
+    Last,
+    Min(usize),
+    Max(usize),
+}
+
+#[derive(Clone, Copy)]
+enum Metric {
+    Levenshtein,
+    Jaccard,
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
@@ -97,12 +155,47 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     } else {
         ComparisonMode::Normal
     };
+    let metric = match args.flag_metric.to_ascii_lowercase().as_str() {
+        "levenshtein" => Metric::Levenshtein,
+        "jaccard" => Metric::Jaccard,
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --metric: {}. Must be levenshtein or jaccard.",
+                args.flag_metric
+            )
+        },
+    };
+    match args.flag_similarity {
+        Some(threshold) if !(0.0..=1.0).contains(&threshold) => {
+            return fail_incorrectusage_clierror!("--similarity must be between 0 and 1.");
+        },
+        #[cfg(not(any(feature = "feature_capable", feature = "lite")))]
+        Some(_) if matches!(metric, Metric::Levenshtein) => {
+            return fail_incorrectusage_clierror!("qsvdp only supports the jaccard --metric.");
+        },
+        Some(_) if args.flag_sorted || args.flag_numeric => {
+            return fail_incorrectusage_clierror!(
+                "--similarity cannot be used with --sorted or --numeric."
+            );
+        },
+        Some(_) if args.flag_keep.is_some() => {
+            return fail_incorrectusage_clierror!("--keep cannot be used with --similarity.");
+        },
+        None if args.flag_clusters || args.flag_compare.is_some() => {
+            return fail_incorrectusage_clierror!("--clusters & --compare require --similarity.");
+        },
+        _ => {},
+    }
 
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
+    if let Some(ref schema_path) = args.flag_locked_schema {
+        util::check_locked_schema(&rconfig, schema_path)?;
+    }
+
     let mut rdr = rconfig.reader()?;
     let mut wtr = Config::new(&args.flag_output).writer()?;
     let dupes_output = args.flag_dupes_output.is_some();
@@ -113,11 +206,121 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         dupewtr.write_byte_record(headers)?;
     }
     let sel = rconfig.selection(headers)?;
+    let compare_sel = match args.flag_compare {
+        Some(ref compare) => compare.selection(headers, !rconfig.no_headers)?,
+        None => sel.clone(),
+    };
+    let keep = match args.flag_keep.as_deref().map(str::trim) {
+        None => None,
+        Some(_) if args.flag_sorted => {
+            return fail_incorrectusage_clierror!("--keep cannot be used with --sorted.");
+        },
+        Some(strategy) if strategy.eq_ignore_ascii_case("first") => Some(Keep::First),
+        Some(strategy) if strategy.eq_ignore_ascii_case("last") => Some(Keep::Last),
+        Some(strategy) => {
+            let Some((min_max, column)) = strategy.split_once(':') else {
+                return fail_incorrectusage_clierror!(
+                    "Invalid --keep strategy: {strategy}. Must be first, last, min:col or \
+                     max:col."
+                );
+            };
+            let column_sel =
+                SelectColumns::parse(column.trim())?.selection(headers, !rconfig.no_headers)?;
+            if column_sel.len() != 1 {
+                return fail_incorrectusage_clierror!("The --keep column must be a single column.");
+            }
+            match min_max.trim().to_ascii_lowercase().as_str() {
+                "min" => Some(Keep::Min(column_sel[0])),
+                "max" => Some(Keep::Max(column_sel[0])),
+                _ => {
+                    return fail_incorrectusage_clierror!(
+                        "Invalid --keep strategy: {strategy}. Must be first, last, min:col or \
+                         max:col."
+                    )
+                },
+            }
+        },
+    };
 
-    rconfig.write_headers(&mut rdr, &mut wtr)?;
+    if args.flag_clusters {
+        if !rconfig.no_headers {
+            let mut cluster_headers = ByteRecord::from(vec!["cluster"]);
+            cluster_headers.extend(headers);
+            wtr.write_byte_record(&cluster_headers)?;
+        }
+    } else {
+        rconfig.write_headers(&mut rdr, &mut wtr)?;
+    }
     let mut dupe_count = 0_usize;
 
-    if args.flag_sorted {
+    if let Some(threshold) = args.flag_similarity {
+        // we're loading the entire file into memory, we need to check avail mem
+        if let Some(path) = rconfig.path.clone() {
+            util::mem_file_check(&path, false, args.flag_memcheck)?;
+        }
+
+        let all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
+        let clusters = fuzzy_clusters(all, &compare_sel, metric, threshold, args.flag_ignore_case);
+        let mut cluster_id = 0_u64;
+        let mut cluster_record = ByteRecord::new();
+        for rows in clusters {
+            dupe_count += rows.len() - 1;
+            if dupes_output {
+                for row in &rows[1..] {
+                    dupewtr.write_byte_record(row)?;
+                }
+            }
+            if !args.flag_clusters {
+                wtr.write_byte_record(&rows[0])?;
+            } else if rows.len() > 1 {
+                cluster_id += 1;
+                for row in &rows {
+                    cluster_record.clear();
+                    cluster_record.push_field(cluster_id.to_string().as_bytes());
+                    cluster_record.extend(row);
+                    wtr.write_byte_record(&cluster_record)?;
+                }
+            }
+        }
+    } else if let Some(ref keep) = keep {
+        // we're loading the entire file into memory, we need to check avail mem
+        if let Some(path) = rconfig.path.clone() {
+            util::mem_file_check(&path, false, args.flag_memcheck)?;
+        }
+
+        // set RAYON_NUM_THREADS for parallel sort
+        util::njobs(args.flag_jobs);
+
+        let all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
+        let cmp_rows = |i: &usize, j: &usize| {
+            let a = sel.select(&all[*i]);
+            let b = sel.select(&all[*j]);
+            match compare_mode {
+                ComparisonMode::Normal => iter_cmp(a, b),
+                ComparisonMode::Numeric => iter_cmp_num(a, b),
+                ComparisonMode::IgnoreCase => iter_cmp_ignore_case(a, b),
+            }
+        };
+        // sort the row indices - with a stable sort, so the duplicates stay in input order
+        let mut order: Vec<usize> = (0..all.len()).collect();
+        order.par_sort_by(cmp_rows);
+
+        let mut kept = Vec::with_capacity(order.len());
+        for group in order.chunk_by(|i, j| cmp_rows(i, j) == cmp::Ordering::Equal) {
+            let keep_idx = keep.pick(group, &all);
+            kept.push(keep_idx);
+            for &idx in group.iter().filter(|&&idx| idx != keep_idx) {
+                dupe_count += 1;
+                if dupes_output {
+                    dupewtr.write_byte_record(&all[idx])?;
+                }
+            }
+        }
+        kept.sort_unstable();
+        for idx in kept {
+            wtr.write_byte_record(&all[idx])?;
+        }
+    } else if args.flag_sorted {
         let mut record = ByteRecord::new();
         let mut next_record = ByteRecord::new();
 
@@ -247,6 +450,81 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+impl Keep {
+    /// the index of the row to keep of a group of duplicates, given in input order
+    fn pick(&self, group: &[usize], rows: &[ByteRecord]) -> usize {
+        let (column, min) = match *self {
+            Keep::First => return group[0],
+            Keep::Last => return group[group.len() - 1],
+            Keep::Min(column) => (column, true),
+            Keep::Max(column) => (column, false),
+        };
+        let value = |idx: usize| rows[idx].get(column).unwrap_or_default();
+        let candidates = group.iter().filter(|&&idx| !value(idx).is_empty());
+        let picked = if min {
+            candidates.min_by(|&&i, &&j| util::cmp_values(value(i), value(j)))
+        } else {
+            // max_by returns the last of the equal maxima, so the group is reversed to
+            // keep the first one
+            candidates
+                .rev()
+                .max_by(|&&i, &&j| util::cmp_values(value(i), value(j)))
+        };
+        picked.copied().unwrap_or(group[0])
+    }
+}
+
+/// group the records into clusters of near-duplicates, comparing each record in order with
+/// the first record of each cluster
+fn fuzzy_clusters(
+    records: Vec<ByteRecord>,
+    sel: &Selection,
+    metric: Metric,
+    threshold: f64,
+    ignore_case: bool,
+) -> Vec<Vec<ByteRecord>> {
+    let mut clusters: Vec<(String, Vec<ByteRecord>)> = Vec::new();
+    for record in records {
+        let mut value = sel
+            .select(&record)
+            .map(String::from_utf8_lossy)
+            .collect::<Vec<_>>()
+            .join(" ");
+        if ignore_case {
+            value = value.to_lowercase();
+        }
+        match clusters
+            .iter_mut()
+            .find(|(first, _)| similarity(metric, first, &value) >= threshold)
+        {
+            Some((_, rows)) => rows.push(record),
+            None => clusters.push((value, vec![record])),
+        }
+    }
+    clusters.into_iter().map(|(_, rows)| rows).collect()
+}
+
+/// the similarity of two values, between 0 & 1
+fn similarity(metric: Metric, a: &str, b: &str) -> f64 {
+    match metric {
+        #[cfg(any(feature = "feature_capable", feature = "lite"))]
+        Metric::Levenshtein => strsim::normalized_levenshtein(a, b),
+        // refused by run
+        #[cfg(not(any(feature = "feature_capable", feature = "lite")))]
+        Metric::Levenshtein => unreachable!("qsvdp doesn't support the levenshtein metric"),
+        Metric::Jaccard => {
+            let a: HashSet<&str> = a.split_whitespace().collect();
+            let b: HashSet<&str> = b.split_whitespace().collect();
+            let union = a.union(&b).count();
+            if union == 0 {
+                1.0
+            } else {
+                a.intersection(&b).count() as f64 / union as f64
+            }
+        },
+    }
+}
+
 /// Try comparing `a` and `b` ignoring the case
 #[inline]
 pub fn iter_cmp_ignore_case<'a, L, R>(mut a: L, mut b: R) -> cmp::Ordering
@@ -0,0 +1,194 @@
+static USAGE: &str = r#"
+Drops the duplicate rows of a continuous CSV feed in a single streaming pass, using
+bounded memory.
+
+Unlike the dedup and extdedup commands, which dedup the whole file, dedupstream only
+remembers the keys of the most recent rows - the window. A row is dropped if its key
+was seen in the window. Otherwise, it's written as soon as it's read, keeping the
+order of the input. This makes dedupstream suitable for never-ending inputs, like
+the ones piped from `tail -f`.
+
+The window counts all the rows read, including the dropped duplicates, so a key that
+keeps being repeated stays in the window. Only a 128-bit hash of each key is kept,
+so memory use is bounded by the window size, regardless of the width of the keys.
+
+A duplicate count is sent to <stderr> when the input ends.
+
+Examples:
+
+  # drop the events that were re-sent within the last 100,000 events of a live feed
+  $ tail -f -n +1 events.csv | qsv dedupstream --key id --line-buffered
+
+  # dedup on a composite key, with a smaller window
+  $ qsv dedupstream --key sensor,timestamp --window 5000 readings.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_dedupstream.rs.
+
+Usage:
+    qsv dedupstream [options] [<input>]
+    qsv dedupstream --help
+
+dedupstream options:
+    -k, --key <arg>            Select the columns that make up the key of a row.
+                               See 'qsv select --help' for the format details.
+                               (default: all the columns)
+    -w, --window <n>           The number of most recent rows whose keys are
+                               remembered. Must be greater than 0. [default: 100000]
+    -i, --ignore-case          Compare keys disregarding case.
+    -D, --dupes-output <file>  Write the dropped duplicates to <file>.
+    --line-buffered            Flush the output after every row, so that each row
+                               reaches the next command in the pipeline as soon as
+                               it's read.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -n, --no-headers           When set, the first row will not be interpreted
+                               as headers. That is, it will be deduped with the rest
+                               of the rows.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+    -Q, --quiet                Do not print the duplicate count to stderr.
+"#;
+
+use std::{borrow::Cow, collections::VecDeque};
+
+use ahash::AHashMap;
+use csv::ByteRecord;
+use serde::Deserialize;
+use simdutf8::basic::from_utf8;
+use xxhash_rust::xxh3::xxh3_128;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:          Option<String>,
+    flag_key:           SelectColumns,
+    flag_window:        usize,
+    flag_ignore_case:   bool,
+    flag_dupes_output:  Option<String>,
+    flag_line_buffered: bool,
+    flag_output:        Option<String>,
+    flag_no_headers:    bool,
+    flag_delimiter:     Option<Delimiter>,
+    flag_quiet:         bool,
+}
+
+/// the key hashes of the most recent rows, oldest first, with their number of occurrences
+struct Window {
+    size:   usize,
+    keys:   VecDeque<u128>,
+    counts: AHashMap<u128, u32>,
+}
+
+impl Window {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            keys: VecDeque::new(),
+            counts: AHashMap::new(),
+        }
+    }
+
+    /// add the key of the next row, evicting the oldest one if the window is full.
+    /// Returns true if the key was already in the window.
+    fn push(&mut self, key: u128) -> bool {
+        let count = self.counts.entry(key).or_insert(0);
+        let seen = *count > 0;
+        *count += 1;
+        self.keys.push_back(key);
+
+        if self.keys.len() > self.size {
+            if let Some(oldest) = self.keys.pop_front() {
+                if let Some(count) = self.counts.get_mut(&oldest) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.counts.remove(&oldest);
+                    }
+                }
+            }
+        }
+        seen
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_window == 0 {
+        return fail_incorrectusage_clierror!("--window must be greater than 0.");
+    }
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_key);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut dupewtr = if args.flag_dupes_output.is_some() {
+        Some(Config::new(&args.flag_dupes_output).writer()?)
+    } else {
+        None
+    };
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    if !rconfig.no_headers {
+        wtr.write_byte_record(&headers)?;
+        if let Some(ref mut dupewtr) = dupewtr {
+            dupewtr.write_byte_record(&headers)?;
+        }
+        if args.flag_line_buffered {
+            wtr.flush()?;
+        }
+    }
+
+    let mut window = Window::new(args.flag_window);
+    let mut record = ByteRecord::new();
+    let mut key = Vec::new();
+    let mut dupe_count = 0_u64;
+    while rdr.read_byte_record(&mut record)? {
+        // length-prefix the key values, so ("ab", "c") and ("a", "bc") are different keys
+        key.clear();
+        for field in sel.select(&record) {
+            let value = if args.flag_ignore_case {
+                match from_utf8(field) {
+                    Ok(s) => Cow::Owned(s.to_lowercase().into_bytes()),
+                    Err(_) => Cow::Owned(field.to_ascii_lowercase()),
+                }
+            } else {
+                Cow::Borrowed(field)
+            };
+            key.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            key.extend_from_slice(&value);
+        }
+
+        if window.push(xxh3_128(&key)) {
+            dupe_count += 1;
+            if let Some(ref mut dupewtr) = dupewtr {
+                dupewtr.write_byte_record(&record)?;
+            }
+        } else {
+            wtr.write_byte_record(&record)?;
+            if args.flag_line_buffered {
+                wtr.flush()?;
+            }
+        }
+    }
+
+    if let Some(ref mut dupewtr) = dupewtr {
+        dupewtr.flush()?;
+    }
+    wtr.flush()?;
+
+    if !args.flag_quiet {
+        eprintln!("{dupe_count}");
+    }
+    Ok(())
+}
@@ -0,0 +1,288 @@
+static USAGE: &str = r#"
+Detects the rows that changed in a full extract of a dataset since the previous run,
+using a persistent state snapshot - so only the inserted, updated & deleted rows of
+a daily full-extract feed have to be processed downstream.
+
+The state file is a snapshot of the previous run's input: a CSV with the --key
+columns of each row, and a hash of the whole row in a `_hash` column. Each row of
+<input> is compared against the snapshot by its key:
+  * inserted  the key is not in the snapshot.
+  * updated   the key is in the snapshot, but the row's hash changed.
+  * deleted   a key of the snapshot is no longer in <input>.
+Unchanged rows are not written. The output has the columns of <input>, preceded by
+a change type column. As the snapshot only has the keys of the rows, deleted rows
+only have their key columns filled in.
+
+When the state file doesn't exist yet, all the rows are inserted. Once the output is
+written, the state file is atomically replaced with the snapshot of <input>, unless the
+dry run option (--dry-run) is set. If the command fails (e.g. on a duplicate key), the
+state file is left as is, so the run can be retried.
+
+A count of the inserted, updated, deleted & unchanged rows is sent to <stderr>.
+
+Examples:
+
+  # the changes of today's extract since the last run, which is then the new snapshot
+  $ qsv delta --state customers.state.csv --key id customers-2024-06-02.csv
+
+  # with a composite key, and a custom change type column
+  $ qsv delta --state stock.state.csv --key store,sku --change-column op stock.csv
+
+  # preview the changes without updating the snapshot
+  $ qsv delta --state customers.state.csv --key id --dry-run customers.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_delta.rs.
+
+Usage:
+    qsv delta [options] --state <file> --key <arg> [<input>]
+    qsv delta --help
+
+delta options:
+    --state <file>             The state file with the snapshot of the previous run.
+    -k, --key <arg>            Select the columns that make up the key of a row.
+                               See 'qsv select --help' for the format details.
+                               The keys must be unique.
+    --change-column <name>     The name of the change type column.
+                               [default: change_type]
+    --dry-run                  Do not update the state file.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+    -Q, --quiet                Do not print the change counts to stderr.
+"#;
+
+use std::path::Path;
+
+use csv::ByteRecord;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliError, CliResult,
+};
+
+/// the column of the state file with the hash of each row
+const HASH_COLUMN: &str = "_hash";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:          Option<String>,
+    flag_state:         String,
+    flag_key:           SelectColumns,
+    flag_change_column: String,
+    flag_dry_run:       bool,
+    flag_output:        Option<String>,
+    flag_delimiter:     Option<Delimiter>,
+    flag_quiet:         bool,
+}
+
+/// the row hash of a key, and whether the key was found in the input
+struct StateEntry {
+    hash: u64,
+    seen: bool,
+}
+
+/// the length-prefixed values of the fields, so ("ab", "c") and ("a", "bc") differ
+fn encode_fields<'a>(fields: impl Iterator<Item = &'a [u8]>, encoded: &mut Vec<u8>) {
+    encoded.clear();
+    for field in fields {
+        encoded.extend_from_slice(&(field.len() as u64).to_le_bytes());
+        encoded.extend_from_slice(field);
+    }
+}
+
+/// the comma-separated field values, for messages
+fn display_fields<'a>(fields: impl Iterator<Item = &'a [u8]>) -> String {
+    fields
+        .map(String::from_utf8_lossy)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// the field values of a key encoded with `encode_fields`
+fn decode_fields(mut encoded: &[u8]) -> Vec<&[u8]> {
+    let mut fields = Vec::new();
+    while encoded.len() >= 8 {
+        let (len, rest) = encoded.split_at(8);
+        // safety: len has 8 bytes
+        let len = u64::from_le_bytes(len.try_into().unwrap()) as usize;
+        let (field, rest) = rest.split_at(len);
+        fields.push(field);
+        encoded = rest;
+    }
+    fields
+}
+
+/// load the snapshot of the state file, keyed by the encoded key values,
+/// checking that it has the same key columns
+fn load_state(
+    state_path: &Path,
+    key_headers: &ByteRecord,
+) -> CliResult<IndexMap<Vec<u8>, StateEntry>> {
+    let mut state = IndexMap::new();
+    if !state_path.exists() {
+        return Ok(state);
+    }
+
+    let mut rdr = csv::ReaderBuilder::new().from_path(state_path)?;
+    let state_headers = rdr.byte_headers()?.clone();
+    let hash_idx = state_headers.len().saturating_sub(1);
+    if state_headers.get(hash_idx) != Some(HASH_COLUMN.as_bytes()) {
+        return fail_clierror!(
+            "Invalid state file {}: its last column must be {HASH_COLUMN}.",
+            state_path.display()
+        );
+    }
+    if state_headers.iter().take(hash_idx).ne(key_headers.iter()) {
+        return fail_incorrectusage_clierror!(
+            "The --key columns ({}) are not the key columns of the state file {} ({}).",
+            display_fields(key_headers.iter()),
+            state_path.display(),
+            display_fields(state_headers.iter().take(hash_idx)),
+        );
+    }
+
+    let mut record = ByteRecord::new();
+    let mut key = Vec::new();
+    while rdr.read_byte_record(&mut record)? {
+        let hash = std::str::from_utf8(&record[hash_idx])
+            .ok()
+            .and_then(|hash| u64::from_str_radix(hash, 16).ok());
+        let Some(hash) = hash else {
+            return fail_clierror!(
+                "Invalid state file {}: invalid {HASH_COLUMN} in row {}.",
+                state_path.display(),
+                state.len() + 1
+            );
+        };
+        encode_fields(record.iter().take(hash_idx), &mut key);
+        state.insert(key.clone(), StateEntry { hash, seen: false });
+    }
+    Ok(state)
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .select(args.flag_key);
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let key_headers: ByteRecord = sel.select(&headers).collect();
+
+    let state_path = Path::new(&args.flag_state);
+    let mut state = load_state(state_path, &key_headers)?;
+    let prev_state_len = state.len();
+
+    // the new snapshot is written next to the state file, so it can be atomically renamed
+    let state_dir = match state_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let new_state = tempfile::Builder::new()
+        .prefix(".qsv-delta-")
+        .tempfile_in(state_dir)?;
+    let mut state_wtr = csv::Writer::from_writer(new_state.as_file());
+    let mut state_headers = key_headers.clone();
+    state_headers.push_field(HASH_COLUMN.as_bytes());
+    state_wtr.write_byte_record(&state_headers)?;
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut out_headers = ByteRecord::with_capacity(headers.as_slice().len(), headers.len() + 1);
+    out_headers.push_field(args.flag_change_column.as_bytes());
+    out_headers.extend(&headers);
+    wtr.write_byte_record(&out_headers)?;
+
+    let mut record = ByteRecord::new();
+    let mut out_record = ByteRecord::new();
+    let mut key = Vec::new();
+    let mut row_fields = Vec::new();
+    let (mut inserted, mut updated, mut unchanged) = (0_u64, 0_u64, 0_u64);
+    let mut row_number = 0_u64;
+    while rdr.read_byte_record(&mut record)? {
+        row_number += 1;
+        encode_fields(sel.select(&record), &mut key);
+        encode_fields(record.iter(), &mut row_fields);
+        let hash = xxh3_64(&row_fields);
+
+        let change = match state.get_mut(&key) {
+            Some(entry) if entry.seen => {
+                return fail_clierror!(
+                    "Duplicate key in row {row_number}: {}. The state file was not updated.",
+                    display_fields(sel.select(&record))
+                );
+            },
+            Some(entry) => {
+                entry.seen = true;
+                if entry.hash == hash {
+                    unchanged += 1;
+                    None
+                } else {
+                    updated += 1;
+                    Some("updated")
+                }
+            },
+            None => {
+                state.insert(key.clone(), StateEntry { hash, seen: true });
+                inserted += 1;
+                Some("inserted")
+            },
+        };
+        if let Some(change) = change {
+            out_record.clear();
+            out_record.push_field(change.as_bytes());
+            out_record.extend(&record);
+            wtr.write_byte_record(&out_record)?;
+        }
+
+        let mut state_record: ByteRecord = sel.select(&record).collect();
+        state_record.push_field(format!("{hash:016x}").as_bytes());
+        state_wtr.write_byte_record(&state_record)?;
+    }
+
+    // the keys of the previous snapshot that are no longer in the input, in snapshot order
+    let mut deleted = 0_u64;
+    for (key, _) in state
+        .iter()
+        .take(prev_state_len)
+        .filter(|(_, entry)| !entry.seen)
+    {
+        let key_values = decode_fields(key);
+        out_record.clear();
+        out_record.push_field(b"deleted");
+        for idx in 0..headers.len() {
+            match sel.iter().position(|&sel_idx| sel_idx == idx) {
+                Some(key_idx) => out_record.push_field(key_values[key_idx]),
+                None => out_record.push_field(b""),
+            }
+        }
+        wtr.write_byte_record(&out_record)?;
+        deleted += 1;
+    }
+
+    wtr.flush()?;
+    state_wtr.flush()?;
+    drop(state_wtr);
+
+    if !args.flag_dry_run {
+        new_state
+            .persist(state_path)
+            .map_err(|e| CliError::from(e.error))?;
+    }
+
+    if !args.flag_quiet {
+        eprintln!(
+            "inserted: {inserted}, updated: {updated}, deleted: {deleted}, unchanged: \
+             {unchanged}"
+        );
+    }
+    Ok(())
+}
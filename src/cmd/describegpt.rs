@@ -665,7 +665,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     // support stdin and auto-decompress snappy file
     // stdin/decompressed file is written to a temporary file in tmpdir
     // which is automatically deleted after the command finishes
-    let tmpdir = tempfile::tempdir()?;
+    let tmpdir = util::scratch_dir()?;
     let work_input = process_input(
         vec![PathBuf::from(
             // if no input file is specified, read from stdin "-"
@@ -37,6 +37,13 @@ Find the difference between two CSVs. Both CSVs have no headers, but the result
 headers, so generic headers will be used in the form of: _col_1, _col_2, etc.:
     qsv diff --no-headers-left --no-headers-right left.csv right.csv
 
+Find the difference between two CSVs row by row, with the names of the modified
+columns of each modified record:
+    qsv diff --positional --show-changes left.csv right.csv
+
+Find the difference between two CSVs, as a JSON array of patch operations:
+    qsv diff --key id --format json left.csv right.csv
+
 For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_diff.rs
 
 Usage:
@@ -81,6 +88,18 @@ diff options:
                                 left CSV's headers are used to match the column names
                                 and it is assumed that the right CSV has the same
                                 selected column names in the same order as the left CSV.
+    --positional                Compare the records by position instead of by --key:
+                                the Nth records of both CSVs are compared, and the extra
+                                records of the longer CSV are added or deleted.
+    --format <arg>              The format of the diff result, csv or json.
+                                json writes an array of patch operations: "add" &
+                                "remove" with the key & the record, and "replace" with
+                                the key & the old & new values of the modified columns.
+                                With --positional, the key is the row number.
+                                [default: csv]
+    --show-changes              Add a changed_columns column to the CSV diff result, with
+                                the names of the columns of the "modified" records that
+                                differ, separated by ';'.
     -j, --jobs <arg>            The number of jobs to run in parallel.
                                 When not set, the number of jobs is set to the number
                                 of CPUs detected.
@@ -92,11 +111,10 @@ Common options:
 
 use std::io::{self, Write};
 
-use csv_diff::{
-    csv_diff::CsvByteDiffBuilder, csv_headers::Headers, diff_result::DiffByteRecords,
-    diff_row::DiffByteRecord,
-};
+use csv::ByteRecord;
+use csv_diff::{csv_diff::CsvByteDiffBuilder, diff_row::DiffByteRecord};
 use serde::Deserialize;
+use serde_json::json;
 
 use super::rename::rename_headers_all_generic;
 use crate::{
@@ -119,6 +137,9 @@ struct Args {
     flag_delimiter_output:  Option<Delimiter>,
     flag_key:               Option<String>,
     flag_sort_columns:      Option<String>,
+    flag_positional:        bool,
+    flag_format:            String,
+    flag_show_changes:      bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -137,12 +158,39 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             "diff does not support stdin. A file path is required for both arguments."
         );
     }
+    if args.flag_positional && (args.flag_key.is_some() || args.flag_sort_columns.is_some()) {
+        return fail_incorrectusage_clierror!(
+            "--positional cannot be used with --key or --sort-columns."
+        );
+    }
+
+    let mut diff_writer: Box<dyn DiffWriter> = match args.flag_format.to_ascii_lowercase().as_str()
+    {
+        "csv" => Box::new(CsvDiffWriter::new(
+            Config::new(&args.flag_output)
+                .delimiter(args.flag_delimiter_output)
+                .writer()?,
+            args.flag_no_headers_output,
+            args.flag_show_changes,
+        )),
+        "json" => Box::new(JsonDiffWriter {
+            wtr:     io::BufWriter::new(Config::new(&args.flag_output).io_writer()?),
+            headers: ByteRecord::new(),
+            first:   true,
+        }),
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --format: {}. Must be csv or json.",
+                args.flag_format
+            )
+        },
+    };
 
     let mut csv_rdr_left = rconfig_left.reader()?;
     let mut csv_rdr_right = rconfig_right.reader()?;
 
-    let headers_left = csv_rdr_left.byte_headers()?;
-    let headers_right = csv_rdr_right.byte_headers()?;
+    let headers_left = csv_rdr_left.byte_headers()?.clone();
+    let headers_right = csv_rdr_right.byte_headers()?.clone();
 
     let primary_key_cols: Vec<usize> = match args.flag_key {
         None => vec![0],
@@ -234,15 +282,51 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         })
         .transpose()?;
 
-    let wtr = Config::new(&args.flag_output)
-        .delimiter(args.flag_delimiter_output)
-        .writer()?;
-
     // set RAYON_NUM_THREADS
     util::njobs(args.flag_jobs);
 
+    if args.flag_positional {
+        let result_headers = if !args.flag_no_headers_left {
+            Some(headers_left)
+        } else if !args.flag_no_headers_right {
+            Some(headers_right)
+        } else {
+            generic_headers(headers_left.len().max(headers_right.len()))?
+        };
+        diff_writer.write_headers(result_headers)?;
+
+        let mut record_left = ByteRecord::new();
+        let mut record_right = ByteRecord::new();
+        let mut row_number = 0_u64;
+        loop {
+            let has_left = csv_rdr_left.read_byte_record(&mut record_left)?;
+            let has_right = csv_rdr_right.read_byte_record(&mut record_right)?;
+            row_number += 1;
+            let change = match (has_left, has_right) {
+                (false, false) => break,
+                (true, false) => Change::Delete(record_left.clone()),
+                (false, true) => Change::Add(record_right.clone()),
+                (true, true) => {
+                    if record_left == record_right {
+                        continue;
+                    }
+                    let field_indices = (0..record_left.len().max(record_right.len()))
+                        .filter(|&i| record_left.get(i) != record_right.get(i))
+                        .collect();
+                    Change::Modify {
+                        delete: record_left.clone(),
+                        add: record_right.clone(),
+                        field_indices,
+                    }
+                },
+            };
+            diff_writer.write_change(&change, &|_| json!(row_number))?;
+        }
+        return diff_writer.finish();
+    }
+
     let Ok(csv_diff) = CsvByteDiffBuilder::new()
-        .primary_key_columns(primary_key_cols)
+        .primary_key_columns(primary_key_cols.clone())
         .build()
     else {
         return fail_clierror!("Cannot instantiate diff");
@@ -263,109 +347,259 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         },
     }
 
-    let mut csv_diff_writer = CsvDiffWriter::new(wtr, args.flag_no_headers_output);
-    Ok(csv_diff_writer.write_diff_byte_records(diff_byte_records)?)
+    // currently, `diff` can only handle two CSVs that have the same headers ordering,
+    // so when both have headers, we can either choose the left or right headers
+    let headers = diff_byte_records.headers();
+    let result_headers = match (headers.headers_left(), headers.headers_right()) {
+        (Some(bh), _) | (None, Some(bh)) => Some(bh.clone()),
+        (None, None) => generic_headers(diff_byte_records.num_columns().unwrap_or_default())?,
+    };
+    diff_writer.write_headers(result_headers)?;
+
+    let key = |record: &ByteRecord| {
+        json!(primary_key_cols
+            .iter()
+            .map(|&i| String::from_utf8_lossy(record.get(i).unwrap_or_default()))
+            .collect::<Vec<_>>())
+    };
+    for dbr in diff_byte_records {
+        diff_writer.write_change(&Change::from(dbr), &key)?;
+    }
+    diff_writer.finish()
+}
+
+/// the generic _col_1, _col_2, etc. headers of CSVs without headers
+fn generic_headers(num_columns: usize) -> csv::Result<Option<ByteRecord>> {
+    if num_columns == 0 {
+        return Ok(None);
+    }
+    let headers_generic = rename_headers_all_generic(num_columns);
+    let mut new_rdr = csv::Reader::from_reader(headers_generic.as_bytes());
+    Ok(Some(new_rdr.byte_headers()?.clone()))
+}
+
+/// the name of a column of the diff result
+fn column_name(headers: &ByteRecord, idx: usize) -> String {
+    match headers.get(idx) {
+        Some(name) => String::from_utf8_lossy(name).into_owned(),
+        None => format!("_col_{}", idx + 1),
+    }
+}
+
+/// a difference between the left & right CSVs
+enum Change {
+    Add(ByteRecord),
+    Delete(ByteRecord),
+    /// the left & right records with the same key or position, with the indices of the
+    /// fields that differ
+    Modify {
+        delete:        ByteRecord,
+        add:           ByteRecord,
+        field_indices: Vec<usize>,
+    },
+}
+
+impl From<DiffByteRecord> for Change {
+    fn from(diff_byte_record: DiffByteRecord) -> Self {
+        match diff_byte_record {
+            DiffByteRecord::Add(add) => Change::Add(add.byte_record().clone()),
+            DiffByteRecord::Delete(del) => Change::Delete(del.byte_record().clone()),
+            DiffByteRecord::Modify {
+                delete,
+                add,
+                field_indices,
+            } => Change::Modify {
+                delete:        delete.byte_record().clone(),
+                add:           add.byte_record().clone(),
+                field_indices: field_indices.iter().copied().collect(),
+            },
+        }
+    }
+}
+
+trait DiffWriter {
+    /// write the headers of the diff result, if any
+    fn write_headers(&mut self, headers: Option<ByteRecord>) -> CliResult<()>;
+
+    /// write a change, `key` returning the key of a record of the change
+    fn write_change(
+        &mut self,
+        change: &Change,
+        key: &dyn Fn(&ByteRecord) -> serde_json::Value,
+    ) -> CliResult<()>;
+
+    fn finish(&mut self) -> CliResult<()>;
 }
 
 struct CsvDiffWriter<W: Write> {
-    csv_writer: csv::Writer<W>,
-    no_headers: bool,
+    csv_writer:   csv::Writer<W>,
+    no_headers:   bool,
+    show_changes: bool,
+    headers:      ByteRecord,
 }
 
 impl<W: Write> CsvDiffWriter<W> {
-    const fn new(csv_writer: csv::Writer<W>, no_headers: bool) -> Self {
+    fn new(csv_writer: csv::Writer<W>, no_headers: bool, show_changes: bool) -> Self {
         Self {
             csv_writer,
             no_headers,
+            show_changes,
+            headers: ByteRecord::new(),
         }
     }
 
-    fn write_headers(&mut self, headers: &Headers, num_columns: Option<usize>) -> csv::Result<()> {
-        match (headers.headers_left(), headers.headers_right()) {
-            (Some(lbh), Some(_rbh)) => {
-                // currently, `diff` can only handle two CSVs that have the same
-                // headers ordering, so in this case we can either choose the left
-                // or right headers, because both are the same
-                if !self.no_headers {
-                    lbh.write_diffresult_header(&mut self.csv_writer)?;
-                }
-            },
-            (Some(bh), None) | (None, Some(bh)) => {
-                if !self.no_headers {
-                    bh.write_diffresult_header(&mut self.csv_writer)?;
-                }
-            },
-            (None, None) => {
-                if let (Some(num_cols), false) = (num_columns.filter(|&c| c > 0), self.no_headers) {
-                    let headers_generic = rename_headers_all_generic(num_cols);
-                    let mut new_rdr = csv::Reader::from_reader(headers_generic.as_bytes());
-                    let new_headers = new_rdr.byte_headers()?;
-                    new_headers.write_diffresult_header(&mut self.csv_writer)?;
-                }
-            },
+    fn write_diff_byte_record(
+        &mut self,
+        sign: &[u8],
+        record: &ByteRecord,
+        changed_columns: &[u8],
+    ) -> csv::Result<()> {
+        let mut vec = vec![sign];
+        vec.extend(record);
+        if self.show_changes {
+            vec.push(changed_columns);
         }
-
-        Ok(())
+        self.csv_writer.write_record(vec)
     }
+}
 
-    fn write_diff_byte_records(&mut self, diff_byte_records: DiffByteRecords) -> io::Result<()> {
-        self.write_headers(diff_byte_records.headers(), diff_byte_records.num_columns())?;
-        for dbr in diff_byte_records {
-            self.write_diff_byte_record(&dbr)?;
+impl<W: Write> DiffWriter for CsvDiffWriter<W> {
+    fn write_headers(&mut self, headers: Option<ByteRecord>) -> CliResult<()> {
+        let Some(headers) = headers else {
+            return Ok(());
+        };
+        if !self.no_headers && !headers.is_empty() {
+            let mut new_header = vec![&b"diffresult"[..]];
+            new_header.extend(&headers);
+            if self.show_changes {
+                new_header.push(b"changed_columns");
+            }
+            self.csv_writer.write_record(new_header)?;
         }
-        self.csv_writer.flush()?;
+        self.headers = headers;
         Ok(())
     }
 
-    fn write_diff_byte_record(&mut self, diff_byte_record: &DiffByteRecord) -> csv::Result<()> {
+    fn write_change(
+        &mut self,
+        change: &Change,
+        _key: &dyn Fn(&ByteRecord) -> serde_json::Value,
+    ) -> CliResult<()> {
         let add_sign: &[u8] = &b"+"[..];
         let remove_sign: &[u8] = &b"-"[..];
 
-        match diff_byte_record {
-            DiffByteRecord::Add(add) => {
-                let mut vec = vec![add_sign];
-                vec.extend(add.byte_record());
-                self.csv_writer.write_record(vec)
-            },
-            DiffByteRecord::Modify {
+        match change {
+            Change::Add(add) => self.write_diff_byte_record(add_sign, add, b"")?,
+            Change::Modify {
                 delete,
                 add,
-                // TODO: this should be used in the future to highlight the column where differences
-                // occur
-                field_indices: _field_indices,
+                field_indices,
             } => {
-                let mut vec_del = vec![remove_sign];
-                vec_del.extend(delete.byte_record());
-                self.csv_writer.write_record(vec_del)?;
-
-                let mut vec_add = vec![add_sign];
-                vec_add.extend(add.byte_record());
-                self.csv_writer.write_record(vec_add)
-            },
-            DiffByteRecord::Delete(del) => {
-                let mut vec = vec![remove_sign];
-                vec.extend(del.byte_record());
-                self.csv_writer.write_record(vec)
+                let changed_columns = if self.show_changes {
+                    field_indices
+                        .iter()
+                        .map(|&idx| column_name(&self.headers, idx))
+                        .collect::<Vec<_>>()
+                        .join(";")
+                } else {
+                    String::new()
+                };
+                self.write_diff_byte_record(remove_sign, delete, changed_columns.as_bytes())?;
+                self.write_diff_byte_record(add_sign, add, changed_columns.as_bytes())?;
             },
+            Change::Delete(del) => self.write_diff_byte_record(remove_sign, del, b"")?,
         }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> CliResult<()> {
+        Ok(self.csv_writer.flush()?)
     }
 }
 
-trait WriteDiffResultHeader {
-    fn write_diffresult_header<W: Write>(&self, csv_writer: &mut csv::Writer<W>)
-        -> csv::Result<()>;
+/// writes the diff result as a JSON array of patch operations
+struct JsonDiffWriter<W: Write> {
+    wtr:     W,
+    headers: ByteRecord,
+    first:   bool,
 }
 
-impl WriteDiffResultHeader for csv::ByteRecord {
-    fn write_diffresult_header<W: Write>(
-        &self,
-        csv_writer: &mut csv::Writer<W>,
-    ) -> csv::Result<()> {
-        if !self.is_empty() {
-            let mut new_header = vec![&b"diffresult"[..]];
-            new_header.extend(self);
-            csv_writer.write_record(new_header)?;
+impl<W: Write> JsonDiffWriter<W> {
+    fn record_object(&self, record: &ByteRecord) -> serde_json::Map<String, serde_json::Value> {
+        record
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                (
+                    column_name(&self.headers, idx),
+                    json!(String::from_utf8_lossy(field)),
+                )
+            })
+            .collect()
+    }
+
+    fn write_operation(&mut self, operation: &serde_json::Value) -> CliResult<()> {
+        self.wtr
+            .write_all(if self.first { b"[\n  " } else { b",\n  " })?;
+        self.first = false;
+        serde_json::to_writer(&mut self.wtr, operation)?;
+        Ok(())
+    }
+}
+
+impl<W: Write> DiffWriter for JsonDiffWriter<W> {
+    fn write_headers(&mut self, headers: Option<ByteRecord>) -> CliResult<()> {
+        if let Some(headers) = headers {
+            self.headers = headers;
         }
         Ok(())
     }
+
+    fn write_change(
+        &mut self,
+        change: &Change,
+        key: &dyn Fn(&ByteRecord) -> serde_json::Value,
+    ) -> CliResult<()> {
+        let operation = match change {
+            Change::Add(add) => json!({
+                "op": "add",
+                "key": key(add),
+                "record": self.record_object(add),
+            }),
+            Change::Delete(del) => json!({
+                "op": "remove",
+                "key": key(del),
+                "record": self.record_object(del),
+            }),
+            Change::Modify {
+                delete,
+                add,
+                field_indices,
+            } => {
+                let changes: serde_json::Map<String, serde_json::Value> = field_indices
+                    .iter()
+                    .map(|&idx| {
+                        let old = String::from_utf8_lossy(delete.get(idx).unwrap_or_default());
+                        let new = String::from_utf8_lossy(add.get(idx).unwrap_or_default());
+                        (
+                            column_name(&self.headers, idx),
+                            json!({"old": old, "new": new}),
+                        )
+                    })
+                    .collect();
+                json!({
+                    "op": "replace",
+                    "key": key(add),
+                    "changes": changes,
+                })
+            },
+        };
+        self.write_operation(&operation)
+    }
+
+    fn finish(&mut self) -> CliResult<()> {
+        self.wtr
+            .write_all(if self.first { b"[]\n" } else { b"\n]\n" })?;
+        Ok(self.wtr.flush()?)
+    }
 }
@@ -273,7 +273,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
     // accept spreadsheets from stdin
-    let tmpdir = tempfile::tempdir()?;
+    let tmpdir = util::scratch_dir()?;
     let path_string = if args.arg_input == "-" {
         let mut buffer = Vec::new();
         std::io::stdin().read_to_end(&mut buffer)?;
@@ -15,12 +15,25 @@ John,blue
 John,yellow
 Mary,red
 
+Values like "blue, yellow," can be cleaned up with --trim & --skip-empty.
+
+Examples:
+
+  # one row per tag, with the tags separated by "; "
+  $ qsv explode tags ";" --trim data.csv
+
+  # one row per color, ignoring the empty colors of "blue||yellow|"
+  $ qsv explode colors "|" --skip-empty --rename color data.csv
+
 Usage:
     qsv explode [options] <column> <separator> [<input>]
     qsv explode --help
 
 explode options:
     -r, --rename <name>    New name for the exploded column.
+    --trim                 Trim the leading & trailing whitespace of the values.
+    --skip-empty           Don't write rows for the empty values. A row with only
+                           empty values is still written once, with an empty value.
 
 Common options:
     -h, --help             Display this message
@@ -46,6 +59,8 @@ struct Args {
     arg_separator:   String,
     arg_input:       Option<String>,
     flag_rename:     Option<String>,
+    flag_trim:       bool,
+    flag_skip_empty: bool,
     flag_output:     Option<String>,
     flag_no_headers: bool,
     flag_delimiter:  Option<Delimiter>,
@@ -78,9 +93,19 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut record = csv::StringRecord::new();
 
     while rdr.read_record(&mut record)? {
+        let mut written = false;
         for val in record[column_index].split(&args.arg_separator) {
+            let val = if args.flag_trim { val.trim() } else { val };
+            if val.is_empty() && args.flag_skip_empty {
+                continue;
+            }
             let new_record = replace_column_value(&record, column_index, val);
             wtr.write_record(&new_record)?;
+            written = true;
+        }
+        if !written {
+            let new_record = replace_column_value(&record, column_index, "");
+            wtr.write_record(&new_record)?;
         }
     }
 
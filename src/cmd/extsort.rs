@@ -14,7 +14,17 @@ External sort option:
                            This is a percentage of total memory.
                            [default: 10]
     --tmp-dir <arg>        The directory to use for externally sorting file segments.
-                           [default: ./]
+                           When not set, a scratch directory for this run is created
+                           in QSV_TMPDIR (or the system temp dir if QSV_TMPDIR is not set).
+                           It is removed when the sort is done. Use 'qsv cleanup' to remove
+                           scratch directories left behind by crashed runs.
+    --max-temp <size>      The maximum amount of disk space the sorted file segments may
+                           use (e.g. 500M, 2G). As the segments are as large as the
+                           input, the sort fails right away if the input is larger.
+                           The check is skipped when reading from stdin. Note that
+                           this only applies to extsort - the temporary files of
+                           'qsv sort --external', 'qsv join --partitions' & the
+                           spilled groups of 'qsv groupby' are not limited.
     -j, --jobs <arg>       The number of jobs to run in parallel.
                            When not set, the number of jobs is set to the
                            number of CPUs detected.
@@ -46,6 +56,7 @@ struct Args {
     flag_jobs:         Option<usize>,
     flag_memory_limit: Option<u8>,
     flag_tmp_dir:      Option<String>,
+    flag_max_temp:     Option<String>,
     flag_no_headers:   bool,
 }
 
@@ -55,15 +66,37 @@ const RW_BUFFER_CAPACITY: usize = 1_000_000; // 1 MB
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
-    // check if tmp dir exists
+    // the sorted segments spill to disk, so fail fast if they would exceed --max-temp
+    if let Some(max_temp) = &args.flag_max_temp {
+        let max_temp_bytes = match util::parse_size(max_temp) {
+            Ok(bytes) => bytes,
+            Err(e) => return fail_incorrectusage_clierror!("--max-temp: {e}"),
+        };
+        if let Some(input_path) = &args.arg_input {
+            let input_size = fs::metadata(input_path)?.len();
+            if input_size > max_temp_bytes {
+                return fail_clierror!(
+                    "Sorting {input_path} ({input_size} bytes) needs more temporary disk space \
+                     than --max-temp {max_temp} ({max_temp_bytes} bytes) allows."
+                );
+            }
+        }
+    }
+
+    // check if tmp dir exists. If not set, use a scratch dir for this run, which is
+    // removed when it goes out of scope at the end of the sort
+    let scratch_dir;
     let tmp_dir = match &args.flag_tmp_dir {
         Some(tmp_dir) => {
             if !path::Path::new(tmp_dir).exists() {
                 return fail_clierror!("tmp-dir '{tmp_dir}' does not exist");
             }
-            tmp_dir.to_string()
+            path::PathBuf::from(tmp_dir)
+        },
+        None => {
+            scratch_dir = util::scratch_dir()?;
+            scratch_dir.path().to_path_buf()
         },
-        None => "./".to_string(),
     };
 
     // memory buffer to use for external merge sort,
@@ -116,7 +149,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let sorter: ExternalSorter<String, io::Error, MemoryLimitedBufferBuilder> =
         match ExternalSorterBuilder::new()
-            .with_tmp_dir(path::Path::new(&tmp_dir))
+            .with_tmp_dir(&tmp_dir)
             .with_buffer(MemoryLimitedBufferBuilder::new(mem_limited_buffer))
             .with_rw_buf_size(RW_BUFFER_CAPACITY)
             .with_threads_number(util::njobs(args.flag_jobs))
@@ -29,6 +29,19 @@ CSV is not sorted by the `--groupby` columns, rows may be
 re-ordered during output due to the buffering of rows
 collected before the first valid value.
 
+The option `--direction up` fills empty values using the next
+non-empty value in that column instead, e.g. for totals that
+are only given on the last row of a block.
+
+The option `--interpolate linear` fills the empty values between
+two numbers by linear interpolation, rounded to 4 decimal places,
+e.g. 1,,,4 is filled as 1,2,3,4. The empty values that are not
+between two numbers are left empty.
+
+With `--direction up` or `--interpolate`, the rows are loaded in
+memory, and `--default` only fills the values that are still
+empty afterwards.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_fill.rs.
 
 Usage:
@@ -40,6 +53,12 @@ fill options:
     -f --first             Fill using the first valid value of a column, instead of the latest.
     -b --backfill          Fill initial empty values with the first valid value.
     -v --default <value>   Fill using this default value.
+    --direction <dir>      Fill from the previous non-empty value (down)
+                           or from the next one (up).
+                           [default: down]
+    --interpolate <method>
+                           Fill the empty values between two numbers by
+                           interpolation. The only method is linear.
 
 Common options:
     -h, --help             Display this message
@@ -69,15 +88,17 @@ type BoxedReader = csv::Reader<Box<dyn io::Read + Send + 'static>>;
 
 #[derive(Deserialize)]
 struct Args {
-    arg_input:       Option<String>,
-    arg_selection:   SelectColumns,
-    flag_output:     Option<String>,
-    flag_no_headers: bool,
-    flag_delimiter:  Option<Delimiter>,
-    flag_groupby:    Option<SelectColumns>,
-    flag_first:      bool,
-    flag_backfill:   bool,
-    flag_default:    Option<String>,
+    arg_input:        Option<String>,
+    arg_selection:    SelectColumns,
+    flag_output:      Option<String>,
+    flag_no_headers:  bool,
+    flag_delimiter:   Option<Delimiter>,
+    flag_groupby:     Option<SelectColumns>,
+    flag_first:       bool,
+    flag_backfill:    bool,
+    flag_default:     Option<String>,
+    flag_direction:   String,
+    flag_interpolate: Option<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -104,9 +125,39 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         rconfig.write_headers(&mut rdr, &mut wtr)?;
     }
 
+    let fill_up = match args.flag_direction.to_ascii_lowercase().as_str() {
+        "down" => false,
+        "up" => true,
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --direction: {}. Must be down or up.",
+                args.flag_direction
+            )
+        },
+    };
+    let interpolate = match args.flag_interpolate {
+        None => false,
+        Some(ref method) if method.eq_ignore_ascii_case("linear") => true,
+        Some(ref method) => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --interpolate method: {method}. Must be linear."
+            )
+        },
+    };
+    if fill_up && interpolate {
+        return fail_incorrectusage_clierror!("--direction up cannot be used with --interpolate.");
+    }
+    if (fill_up || interpolate) && (args.flag_first || args.flag_backfill) {
+        return fail_incorrectusage_clierror!(
+            "--first and --backfill cannot be used with --direction up or --interpolate."
+        );
+    }
+
     let filler = Filler::new(groupby, select)
         .use_first_value(args.flag_first)
         .backfill_empty_values(args.flag_backfill)
+        .fill_up(fill_up)
+        .interpolate_linear(interpolate)
         .use_default_value(args.flag_default);
     filler.fill(&mut rdr, &mut wtr)
 }
@@ -224,6 +275,8 @@ struct Filler {
     buffer:        GroupBuffer,
     first:         bool,
     backfill:      bool,
+    up:            bool,
+    interpolate:   bool,
     default_value: Option<ByteString>,
 }
 
@@ -236,6 +289,8 @@ impl Filler {
             buffer: GroupBuffer::new(),
             first: false,
             backfill: false,
+            up: false,
+            interpolate: false,
             default_value: None,
         }
     }
@@ -250,12 +305,25 @@ impl Filler {
         self
     }
 
+    const fn fill_up(mut self, up: bool) -> Self {
+        self.up = up;
+        self
+    }
+
+    const fn interpolate_linear(mut self, interpolate: bool) -> Self {
+        self.interpolate = interpolate;
+        self
+    }
+
     fn use_default_value(mut self, value: Option<String>) -> Self {
         self.default_value = value.map(|v| v.as_bytes().to_vec());
         self
     }
 
     fn fill(mut self, rdr: &mut BoxedReader, wtr: &mut BoxedWriter) -> CliResult<()> {
+        if self.up || self.interpolate {
+            return self.fill_in_memory(rdr, wtr);
+        }
         let mut record = csv::ByteRecord::new();
 
         while rdr.read_byte_record(&mut record)? {
@@ -301,6 +369,79 @@ impl Filler {
         wtr.flush()?;
         Ok(())
     }
+
+    /// fill the empty values from the next non-empty value, or by linear interpolation,
+    /// which both require the rows of a group after the empty values
+    fn fill_in_memory(self, rdr: &mut BoxedReader, wtr: &mut BoxedWriter) -> CliResult<()> {
+        let mut rows: Vec<ByteRecord> = Vec::new();
+        // the indices of the rows of each group, in input order
+        let mut groups: AHashMap<GroupKey, Vec<usize>> = AHashMap::new();
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            groups
+                .entry(self.groupby.key(&record)?)
+                .or_default()
+                .push(rows.len());
+            rows.push(ByteRecord::from(&record));
+        }
+
+        for group_rows in groups.values() {
+            for &col in self.select.iter() {
+                if self.interpolate {
+                    interpolate_linear(&mut rows, group_rows, col);
+                } else {
+                    let mut next: Option<ByteString> = None;
+                    for &row in group_rows.iter().rev() {
+                        let Some(field) = rows[row].0.get_mut(col) else {
+                            continue;
+                        };
+                        if !field.is_empty() {
+                            next = Some(field.clone());
+                        } else if let Some(ref next) = next {
+                            next.clone_into(field);
+                        }
+                    }
+                }
+            }
+        }
+
+        for mut row in rows {
+            if let Some(ref default_value) = self.default_value {
+                for &col in self.select.iter() {
+                    if let Some(field) = row.0.get_mut(col).filter(|field| field.is_empty()) {
+                        default_value.clone_into(field);
+                    }
+                }
+            }
+            wtr.write_record(row.iter())?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// fill the empty values of a column between two numbers of a group by linear interpolation
+fn interpolate_linear(rows: &mut [ByteRecord], group_rows: &[usize], col: usize) {
+    // the position in the group & the value of the last number
+    let mut last: Option<(usize, f64)> = None;
+    for (pos, &row) in group_rows.iter().enumerate() {
+        let value = match rows[row].0.get(col) {
+            Some(field) if !field.is_empty() => simdutf8::basic::from_utf8(field)
+                .ok()
+                .and_then(|field| field.trim().parse::<f64>().ok()),
+            _ => continue,
+        };
+        if let (Some((last_pos, last_value)), Some(value)) = (last, value) {
+            for gap_pos in last_pos + 1..pos {
+                let interpolated = last_value
+                    + (value - last_value) * (gap_pos - last_pos) as f64 / (pos - last_pos) as f64;
+                if let Some(field) = rows[group_rows[gap_pos]].0.get_mut(col) {
+                    *field = util::round_num(interpolated, 4).into_bytes();
+                }
+            }
+        }
+        last = value.map(|value| (pos, value));
+    }
 }
 
 struct MapSelected<I, F> {
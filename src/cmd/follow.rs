@@ -0,0 +1,248 @@
+static USAGE: &str = r#"
+Follows an append-only CSV file like `tail -f`, running a qsv command on each batch of
+newly appended records.
+
+The file is checked for new data every --interval. Only complete records are passed on -
+a record that's still being written is held back until its line terminator is appended.
+Each batch of new records is piped, with the CSV's header row, to a new run of the qsv
+<command>, whose output is written to stdout as soon as it's done. This makes it possible
+to build incremental pipelines on CSV logs, e.g. to filter and convert the new entries
+of a log for a dashboard as they're logged.
+
+By default, only the records appended after follow starts are processed. Use --from-start
+to process the records already in the file first.
+
+If the file is truncated or replaced by a smaller file (e.g. when a log is rotated),
+it's followed from its start again.
+
+If the command fails with a usage error, follow stops. Other failures (e.g. search not
+finding a match in a batch) don't stop follow, with the command's error messages sent
+to stderr.
+
+Examples:
+
+  # convert the new log entries to JSONL as they're logged
+  $ qsv follow access-log.csv -- tojsonl
+
+  # only pass on the new errors, keeping just one header row
+  $ qsv follow --csv-output app-log.csv -- search --select level ERROR
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_follow.rs.
+
+Usage:
+    qsv follow [options] <input> [--] <command>...
+    qsv follow --help
+
+follow arguments:
+    <input>                The append-only CSV file to follow.
+    <command>...           The qsv command to run on each batch of new records, with
+                           its arguments, e.g. `search --select level ERROR`.
+                           The batch is read from stdin, so don't specify an input file.
+
+follow options:
+    --from-start           Process the records already in the file before following it.
+    --interval <ms>        How often to check the file for new records, in milliseconds.
+                           [default: 1000]
+    --csv-output           The command outputs CSV with a header row. Only the header
+                           row of the first batch's output is kept.
+    --idle-timeout <secs>  Stop following the file after no new records were appended
+                           for <secs> seconds. 0 means follow it forever. [default: 0]
+
+Common options:
+    -h, --help             Display this message
+"#;
+
+use std::{
+    env,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+
+use crate::{clitypes::QsvExitCode, config::DEFAULT_RDR_BUFFER_CAPACITY, util, CliResult};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:         String,
+    arg_command:       Vec<String>,
+    flag_from_start:   bool,
+    flag_interval:     u64,
+    flag_csv_output:   bool,
+    flag_idle_timeout: u64,
+}
+
+/// Splits the bytes read from the followed file into complete records, holding back
+/// the partial record at the end until the rest of it is read.
+#[derive(Default)]
+struct RecordSplitter {
+    pending:   Vec<u8>,
+    // how many of the pending bytes were scanned, and if they end within a quoted field
+    scanned:   usize,
+    in_quotes: bool,
+}
+
+impl RecordSplitter {
+    /// add the newly read bytes, returning the complete records read so far
+    fn push(&mut self, bytes: &[u8]) -> Vec<u8> {
+        self.pending.extend_from_slice(bytes);
+        let mut records_end = 0;
+        for (idx, &byte) in self.pending.iter().enumerate().skip(self.scanned) {
+            if byte == b'"' {
+                self.in_quotes = !self.in_quotes;
+            } else if byte == b'\n' && !self.in_quotes {
+                records_end = idx + 1;
+            }
+        }
+        self.scanned = self.pending.len() - records_end;
+
+        let partial_record = self.pending.split_off(records_end);
+        std::mem::replace(&mut self.pending, partial_record)
+    }
+}
+
+/// the end of the first record (e.g. the header row) of complete records
+fn first_record_end(records: &[u8]) -> Option<usize> {
+    let mut in_quotes = false;
+    for (idx, &byte) in records.iter().enumerate() {
+        if byte == b'"' {
+            in_quotes = !in_quotes;
+        } else if byte == b'\n' && !in_quotes {
+            return Some(idx + 1);
+        }
+    }
+    None
+}
+
+/// run the command on a batch of records, writing its output to stdout
+fn run_command(
+    qsv_path: &Path,
+    command: &[String],
+    header: &[u8],
+    records: &[u8],
+    skip_output_header: bool,
+) -> CliResult<()> {
+    let mut child = Command::new(qsv_path)
+        .args(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    // write the batch from another thread, so a command with a lot of output can't
+    // block us while we're still writing its input
+    let mut stdin = child.stdin.take().unwrap();
+    let output = thread::scope(|s| {
+        s.spawn(move || {
+            // the command may exit without reading all its input, e.g. search --quick
+            let _ = stdin
+                .write_all(header)
+                .and_then(|()| stdin.write_all(records));
+        });
+        child.wait_with_output()
+    })?;
+
+    if output.status.code() == Some(QsvExitCode::IncorrectUsage as i32) {
+        return fail_incorrectusage_clierror!(
+            "`qsv {}` failed with a usage error.",
+            command.join(" ")
+        );
+    }
+
+    let mut batch_output = output.stdout.as_slice();
+    if skip_output_header {
+        let header_end = first_record_end(batch_output).unwrap_or(batch_output.len());
+        batch_output = &batch_output[header_end..];
+    }
+    let mut out = io::stdout().lock();
+    out.write_all(batch_output)?;
+    Ok(out.flush()?)
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let path = Path::new(&args.arg_input);
+    if !path.is_file() {
+        return fail_incorrectusage_clierror!("{} is not a file.", path.display());
+    }
+    let qsv_path = env::current_exe()?;
+    let interval = Duration::from_millis(args.flag_interval.max(1));
+    let idle_timeout = Duration::from_secs(args.flag_idle_timeout);
+
+    let mut offset = 0_u64;
+    let mut splitter = RecordSplitter::default();
+    let mut header: Option<Vec<u8>> = None;
+    let mut skip_existing = !args.flag_from_start;
+    let mut first_batch = true;
+    let mut last_append = Instant::now();
+    let mut buffer = vec![0_u8; DEFAULT_RDR_BUFFER_CAPACITY];
+
+    loop {
+        // reopen the file every time, so we follow it when it's rotated
+        let mut file = match File::open(path) {
+            Ok(file) => Some(file),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return fail_clierror!("Cannot open {}: {e}", path.display()),
+        };
+        if let Some(ref file) = file {
+            if file.metadata()?.len() < offset {
+                wwarn!(
+                    "{} was truncated. Following it from the start.",
+                    path.display()
+                );
+                offset = 0;
+                splitter = RecordSplitter::default();
+                header = None;
+            }
+        }
+
+        let mut records = Vec::new();
+        if let Some(ref mut file) = file {
+            file.seek(SeekFrom::Start(offset))?;
+            loop {
+                let bytes_read = file.read(&mut buffer)?;
+                if bytes_read == 0 {
+                    break;
+                }
+                offset += bytes_read as u64;
+
+                let mut complete_records = splitter.push(&buffer[..bytes_read]);
+                if header.is_none() {
+                    if let Some(header_end) = first_record_end(&complete_records) {
+                        let rest = complete_records.split_off(header_end);
+                        header = Some(std::mem::replace(&mut complete_records, rest));
+                    }
+                }
+                if !skip_existing {
+                    records.extend_from_slice(&complete_records);
+                }
+            }
+        }
+        skip_existing = false;
+
+        match header {
+            Some(ref header_row) if !records.is_empty() => {
+                run_command(
+                    &qsv_path,
+                    &args.arg_command,
+                    header_row,
+                    &records,
+                    args.flag_csv_output && !first_batch,
+                )?;
+                first_batch = false;
+                last_append = Instant::now();
+            },
+            _ if !idle_timeout.is_zero() && last_append.elapsed() >= idle_timeout => {
+                return Ok(());
+            },
+            _ => {},
+        }
+
+        thread::sleep(interval);
+    }
+}
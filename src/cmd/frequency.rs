@@ -6,6 +6,11 @@ The frequency table is formatted as CSV data with the following columns:
     field,value,count,percentage
 
 With a row for the N most frequent values (default:10) for each column in the CSV.
+For Pareto-style analyses, the --cum-pct and --rank options add the following columns:
+
+    cum_percentage  the running total of the percentages of the rows of a column.
+    rank            the rank of the value by count, tied values having the same rank.
+                    e.g. counts 5,3,3,1 are ranked 1,2,2,4. The "Other" row has no rank.
 
 Since this command computes an exact frequency table, memory proportional to the
 cardinality of each column would be normally required.
@@ -37,6 +42,41 @@ STATS_MODE "none" NOTES:
     This is useful when you want to apply limits only to columns with a large number
     of unique items and not to columns with a small number of unique items.
 
+WEIGHTED FREQUENCIES:
+
+    With --weight, the count of a value is the sum of the --weight column values of its
+    rows instead of its number of rows, as pre-aggregated or sampled datasets require.
+    The counts are then rounded to 4 decimal places, and the percentages are shares of
+    the total weight. Rows whose weight is empty, not a number, zero or negative are
+    not counted.
+    As the weighted counts of a column with all unique values are not all 1, the stats
+    cache is not used to short-circuit them, --unq-limit is ignored and a negative limit
+    (--limit) is a minimum weighted count. Weighted frequencies are computed sequentially.
+
+JOINT FREQUENCIES:
+
+    With --joint, the selected columns are counted together, as a contingency table:
+    each combination of their values is a row, with a column per selected column
+    followed by the count & percentage columns, e.g.:
+
+        region,product,count,percentage
+
+    The --limit and "Other" options then apply to the combinations, and with --no-nulls,
+    the rows with a NULL in any of the selected columns are not counted.
+    Joint frequencies are computed sequentially, without the stats cache.
+
+TOKEN FREQUENCIES:
+
+    With --tokens, the words of the values of free-text columns are counted instead of
+    the values, as a quick profile of a corpus. Words are runs of letters & digits, so
+    "Don't stop!" has the words "Don", "t" & "stop". With --ngram N, the sequences of N
+    consecutive words of a value are counted instead, joined with spaces.
+    The --stopwords file has words not to count, one per line, matched regardless of case.
+    They are removed before the n-grams are formed. The --ignore-case, --no-trim and
+    the --unicode-normalize options apply to the values before they're tokenized, and the
+    percentages are shares of all the words (or n-grams) of a column.
+    Token frequencies are computed sequentially, without the stats cache.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_frequency.rs.
 
 Usage:
@@ -79,12 +119,29 @@ frequency options:
     --other-text <arg>      The text to use for the "Other" category. If set to "<NONE>",
                             the "Other" category will not be included in the frequency table.
                             [default: Other]
+    --other-threshold <n>   Collapse the values with a count < N into the "Other" category,
+                            regardless of --limit. Set to '0' to disable.
+                            [default: 0]
+    --suppress-below <n>    Drop the values with a count < N from the frequency table,
+                            without counting them in the "Other" category, e.g. to not
+                            publish small counts of privacy-sensitive data. The percentages
+                            of the other values are still shares of all the values.
+                            Set to '0' to disable.
+                            [default: 0]
     -a, --asc               Sort the frequency tables in ascending order by count.
                             The default is descending order.
+    --cum-pct               Add a cum_percentage column with the cumulative percentages.
+    --rank                  Add a rank column with the rank of each value by count.
     --no-trim               Don't trim whitespace from values when computing frequencies.
                             The default is to trim leading and trailing whitespaces.
     --no-nulls              Don't include NULLs in the frequency table.
     -i, --ignore-case       Ignore case when computing frequencies.
+    --unicode-normalize <form>
+                            Normalize values to the NFC or NFKC Unicode normalization
+                            form when computing frequencies, so composed & decomposed
+                            forms of a value (e.g. "é" as one or two code points) are
+                            counted together. NFKC also unifies compatibility forms,
+                            e.g. full-width letters or ligatures like "ﬁ".
     --stats-mode <arg>      The stats mode to use when computing frequencies with cardinalities.
                             Having column cardinalities short-circuits frequency compilation and
                             eliminates memory usage for columns with all unique values.
@@ -99,6 +156,16 @@ frequency options:
                             [default: auto]
    --all-unique-text <arg>  The text to use for the "<ALL_UNIQUE>" category.
                             [default: <ALL_UNIQUE>]
+    --weight <column>       Count the sum of the values of this column for each value,
+                            instead of its number of rows. See WEIGHTED FREQUENCIES above.
+    --joint                 Count the combinations of the values of the selected
+                            columns, instead of each column independently.
+                            See JOINT FREQUENCIES above.
+    --tokens                Count the words of the values of the selected columns,
+                            instead of the values. See TOKEN FREQUENCIES above.
+    --ngram <n>             With --tokens, count the sequences of N consecutive words.
+                            [default: 1]
+    --stopwords <file>      With --tokens, a file of words not to count, one per line.
     -j, --jobs <arg>        The number of jobs to run in parallel.
                             This works much faster when the given CSV data has
                             an index already created. Note that a file handle
@@ -121,11 +188,14 @@ Common options:
 
 use std::{fs, io, sync::OnceLock};
 
+use ahash::{AHashMap, AHashSet};
 use indicatif::HumanCount;
 use rust_decimal::prelude::*;
 use serde::Deserialize;
 use stats::{merge_all, Frequencies};
 use threadpool::ThreadPool;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+use unicode_normalization::UnicodeNormalization;
 
 use crate::{
     config::{Config, Delimiter},
@@ -139,28 +209,40 @@ use crate::{
 #[allow(clippy::unsafe_derive_deserialize)]
 #[derive(Clone, Deserialize)]
 pub struct Args {
-    pub arg_input:            Option<String>,
-    pub flag_select:          SelectColumns,
-    pub flag_limit:           isize,
-    pub flag_unq_limit:       usize,
-    pub flag_lmt_threshold:   usize,
-    pub flag_pct_dec_places:  isize,
-    pub flag_other_sorted:    bool,
-    pub flag_other_text:      String,
-    pub flag_asc:             bool,
-    pub flag_no_trim:         bool,
-    pub flag_no_nulls:        bool,
-    pub flag_ignore_case:     bool,
-    pub flag_stats_mode:      String,
-    pub flag_all_unique_text: String,
-    pub flag_jobs:            Option<usize>,
-    pub flag_output:          Option<String>,
-    pub flag_no_headers:      bool,
-    pub flag_delimiter:       Option<Delimiter>,
-    pub flag_memcheck:        bool,
+    pub arg_input:              Option<String>,
+    pub flag_select:            SelectColumns,
+    pub flag_limit:             isize,
+    pub flag_unq_limit:         usize,
+    pub flag_lmt_threshold:     usize,
+    pub flag_pct_dec_places:    isize,
+    pub flag_other_sorted:      bool,
+    pub flag_other_text:        String,
+    pub flag_other_threshold:   u64,
+    pub flag_suppress_below:    u64,
+    pub flag_asc:               bool,
+    pub flag_cum_pct:           bool,
+    pub flag_rank:              bool,
+    pub flag_no_trim:           bool,
+    pub flag_no_nulls:          bool,
+    pub flag_ignore_case:       bool,
+    pub flag_unicode_normalize: Option<String>,
+    pub flag_stats_mode:        String,
+    pub flag_all_unique_text:   String,
+    pub flag_weight:            Option<SelectColumns>,
+    pub flag_joint:             bool,
+    pub flag_tokens:            bool,
+    pub flag_ngram:             usize,
+    pub flag_stopwords:         Option<String>,
+    pub flag_jobs:              Option<usize>,
+    pub flag_output:            Option<String>,
+    pub flag_no_headers:        bool,
+    pub flag_delimiter:         Option<Delimiter>,
+    pub flag_memcheck:          bool,
 }
 
 const NULL_VAL: &[u8] = b"(NULL)";
+// the decimal places of the --weight-ed counts
+const WEIGHTED_COUNT_DEC_PLACES: u32 = 4;
 const NON_UTF8_ERR: &str = "<Non-UTF8 ERROR>";
 
 static UNIQUE_COLUMNS: OnceLock<Vec<usize>> = OnceLock::new();
@@ -175,8 +257,110 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         util::mem_file_check(&path, false, args.flag_memcheck)?;
     }
 
+    if let Some(ref form) = args.flag_unicode_normalize {
+        if !matches!(form.to_ascii_lowercase().as_str(), "nfc" | "nfkc") {
+            return fail_incorrectusage_clierror!(
+                "Invalid --unicode-normalize form: {form}. Must be NFC or NFKC."
+            );
+        }
+        #[cfg(not(any(feature = "feature_capable", feature = "lite")))]
+        return fail_incorrectusage_clierror!("--unicode-normalize is not supported by qsvdp.");
+    }
+
+    if args.flag_joint && args.flag_weight.is_some() {
+        return fail_incorrectusage_clierror!("--joint and --weight cannot be used together.");
+    }
+    if args.flag_tokens && (args.flag_joint || args.flag_weight.is_some()) {
+        return fail_incorrectusage_clierror!("--tokens cannot be used with --joint or --weight.");
+    }
+    if args.flag_ngram == 0 {
+        return fail_incorrectusage_clierror!("--ngram must be at least 1.");
+    }
+
     let mut wtr = Config::new(&args.flag_output).writer()?;
+    if args.flag_joint {
+        let (headers, ftab, row_count) = args.joint_ftable()?;
+        let mut record: csv::ByteRecord = if rconfig.no_headers {
+            (1..=headers.len()).map(|i| i.to_string()).collect()
+        } else {
+            headers.clone()
+        };
+        record.push_field(b"count");
+        record.push_field(b"percentage");
+        for extra_header in args.extra_headers() {
+            record.push_field(extra_header.as_bytes());
+        }
+        wtr.write_byte_record(&record)?;
+        if row_count == 0 {
+            return Ok(wtr.flush()?);
+        }
+
+        let mut counts = args.counts(&ftab);
+        // the "Other" row is the only one that isn't an encoded combination
+        if !args.flag_other_sorted
+            && counts
+                .first()
+                .is_some_and(|(value, _, _)| decode_combination(value, headers.len()).is_none())
+        {
+            counts.rotate_left(1);
+        }
+        let mut buffer = itoa::Buffer::new();
+        let mut running = RunningTotals::default();
+        for (value, count, percentage) in counts {
+            record.clear();
+            let values = decode_combination(&value, headers.len());
+            #[allow(clippy::cast_precision_loss)]
+            let extra_columns =
+                args.extra_columns(&mut running, count as f64, percentage, values.is_none());
+            match values {
+                Some(values) => {
+                    for value in values {
+                        record.push_field(if value.is_empty() { NULL_VAL } else { value });
+                    }
+                },
+                None => {
+                    record.push_field(&value);
+                    for _ in 1..headers.len() {
+                        record.push_field(b"");
+                    }
+                },
+            }
+            record.push_field(buffer.format(count).as_bytes());
+            record.push_field(args.format_percentage(percentage).as_bytes());
+            for extra_column in &extra_columns {
+                record.push_field(extra_column.as_bytes());
+            }
+            wtr.write_byte_record(&record)?;
+        }
+        return Ok(wtr.flush()?);
+    }
+    if args.flag_weight.is_some() {
+        let (headers, tables) = args.weighted_ftables()?;
+        wtr.write_record(args.output_headers())?;
+        let other_prefix = format!("{} (", args.flag_other_text);
+        for (i, (header, table)) in headers.iter().zip(tables).enumerate() {
+            let header = if rconfig.no_headers {
+                (i + 1).to_string().into_bytes()
+            } else {
+                header.to_vec()
+            };
+            let mut running = RunningTotals::default();
+            for (value, count, percentage) in args.weighted_counts(table) {
+                let is_other = value.starts_with(other_prefix.as_bytes());
+                let extra_columns = args.extra_columns(&mut running, count, percentage, is_other);
+                let count = util::round_num(count, WEIGHTED_COUNT_DEC_PLACES);
+                let percentage = args.format_percentage(percentage);
+                let mut row: Vec<&[u8]> =
+                    vec![&*header, &*value, count.as_bytes(), percentage.as_bytes()];
+                row.extend(extra_columns.iter().map(String::as_bytes));
+                wtr.write_record(row)?;
+            }
+        }
+        return Ok(wtr.flush()?);
+    }
+
     let (headers, tables) = match args.rconfig().indexed()? {
+        _ if args.flag_tokens => args.token_ftables(),
         Some(ref mut idx) if util::njobs(args.flag_jobs) > 1 => args.parallel_ftables(idx),
         _ => args.sequential_ftables(),
     }?;
@@ -184,12 +368,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     #[allow(unused_assignments)]
     let mut header_vec: Vec<u8> = Vec::with_capacity(tables.len());
     let mut buffer = itoa::Buffer::new();
-    let mut pct_decimal: Decimal;
-    let mut final_pct_decimal: Decimal;
     let mut pct_string: String;
-    let mut pct_scale: u32;
-    let mut current_scale: u32;
-    let abs_dec_places = args.flag_pct_dec_places.unsigned_abs() as u32;
     let mut row: Vec<&[u8]>;
     let mut all_unique_header: bool;
 
@@ -197,11 +376,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     // by sel_headers fn
     let all_unique_headers = UNIQUE_COLUMNS.get().unwrap();
 
-    wtr.write_record(vec!["field", "value", "count", "percentage"])?;
+    wtr.write_record(args.output_headers())?;
     let head_ftables = headers.iter().zip(tables);
     let row_count = *FREQ_ROW_COUNT.get().unwrap_or(&0);
 
     let all_unique_text = args.flag_all_unique_text.as_bytes();
+    let other_prefix = format!("{} (", args.flag_other_text);
 
     for (i, (header, ftab)) in head_ftables.enumerate() {
         header_vec = if rconfig.no_headers {
@@ -221,46 +401,31 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
             // if not --other_sorted and the first value is "Other (", rotate it to the end
             if !args.flag_other_sorted
-                && sorted_counts.first().is_some_and(|(value, _, _)| {
-                    value.starts_with(format!("{} (", args.flag_other_text).as_bytes())
-                })
+                && sorted_counts
+                    .first()
+                    .is_some_and(|(value, _, _)| value.starts_with(other_prefix.as_bytes()))
             {
                 sorted_counts.rotate_left(1);
             }
         };
 
+        let mut running = RunningTotals::default();
         for (value, count, percentage) in sorted_counts {
-            pct_decimal = Decimal::from_f64(percentage).unwrap_or_default();
-            pct_scale = if args.flag_pct_dec_places < 0 {
-                current_scale = pct_decimal.scale();
-                if current_scale > abs_dec_places {
-                    current_scale
-                } else {
-                    abs_dec_places
-                }
-            } else {
-                abs_dec_places
-            };
-            final_pct_decimal = pct_decimal
-                .round_dp_with_strategy(
-                    pct_scale,
-                    rust_decimal::RoundingStrategy::MidpointAwayFromZero,
-                )
-                .normalize();
-            pct_string = if final_pct_decimal.fract().to_string().len() > abs_dec_places as usize {
-                final_pct_decimal
-                    .round_dp_with_strategy(abs_dec_places, RoundingStrategy::MidpointAwayFromZero)
-                    .normalize()
-                    .to_string()
-            } else {
-                final_pct_decimal.to_string()
-            };
+            pct_string = args.format_percentage(percentage);
+            #[allow(clippy::cast_precision_loss)]
+            let extra_columns = args.extra_columns(
+                &mut running,
+                count as f64,
+                percentage,
+                value.starts_with(other_prefix.as_bytes()),
+            );
             row = vec![
                 &*header_vec,
                 &*value,
                 buffer.format(count).as_bytes(),
                 pct_string.as_bytes(),
             ];
+            row.extend(extra_columns.iter().map(String::as_bytes));
             wtr.write_record(row)?;
         }
     }
@@ -270,6 +435,57 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 type Headers = csv::ByteRecord;
 type FTable = Frequencies<Vec<u8>>;
 type FTables = Vec<Frequencies<Vec<u8>>>;
+type WeightedFTable = AHashMap<Vec<u8>, f64>;
+type WeightedFTables = Vec<WeightedFTable>;
+
+/// the running totals of the rows of a frequency table, for --cum-pct & --rank
+#[derive(Default)]
+struct RunningTotals {
+    rows:       u64,
+    rank:       u64,
+    last_count: f64,
+    cum_pct:    f64,
+}
+
+/// the values of a --joint combination, each prefixed with its length
+fn encode_value(value: &[u8], combination: &mut Vec<u8>) {
+    combination.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    combination.extend_from_slice(value);
+}
+
+/// the `ncols` values of a --joint combination, or None if it isn't one
+fn decode_combination(mut combination: &[u8], ncols: usize) -> Option<Vec<&[u8]>> {
+    let mut values = Vec::with_capacity(ncols);
+    while !combination.is_empty() {
+        let (len, rest) = combination.split_first_chunk::<8>()?;
+        let len = usize::try_from(u64::from_le_bytes(*len)).ok()?;
+        if len > rest.len() {
+            return None;
+        }
+        let (value, rest) = rest.split_at(len);
+        values.push(value);
+        combination = rest;
+    }
+    (values.len() == ncols).then_some(values)
+}
+
+/// the value in the --unicode-normalize form, if any
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+fn unicode_normalize(s: &str, form: Option<&str>) -> Vec<u8> {
+    match form {
+        Some(form) if form.eq_ignore_ascii_case("nfkc") => {
+            s.nfkc().collect::<String>().into_bytes()
+        },
+        Some(_) => s.nfc().collect::<String>().into_bytes(),
+        None => s.as_bytes().to_vec(),
+    }
+}
+
+/// qsvdp doesn't normalize values - --unicode-normalize is refused
+#[cfg(not(any(feature = "feature_capable", feature = "lite")))]
+fn unicode_normalize(s: &str, _form: Option<&str>) -> Vec<u8> {
+    s.as_bytes().to_vec()
+}
 
 impl Args {
     pub fn rconfig(&self) -> Config {
@@ -279,6 +495,84 @@ impl Args {
             .select(self.flag_select.clone())
     }
 
+    /// the output header row
+    fn output_headers(&self) -> Vec<&str> {
+        let mut headers = vec!["field", "value", "count", "percentage"];
+        headers.extend(self.extra_headers());
+        headers
+    }
+
+    /// the headers of the --cum-pct & --rank columns
+    fn extra_headers(&self) -> Vec<&str> {
+        let mut headers = Vec::new();
+        if self.flag_cum_pct {
+            headers.push("cum_percentage");
+        }
+        if self.flag_rank {
+            headers.push("rank");
+        }
+        headers
+    }
+
+    /// the --cum-pct & --rank columns of the next row of a frequency table.
+    /// The "Other" row has no rank.
+    fn extra_columns(
+        &self,
+        running: &mut RunningTotals,
+        count: f64,
+        percentage: f64,
+        is_other: bool,
+    ) -> Vec<String> {
+        running.cum_pct += percentage;
+        let mut columns = Vec::new();
+        if self.flag_cum_pct {
+            columns.push(self.format_percentage(running.cum_pct));
+        }
+        if self.flag_rank {
+            if is_other {
+                columns.push(String::new());
+            } else {
+                running.rows += 1;
+                if running.rows == 1 || count.total_cmp(&running.last_count).is_ne() {
+                    running.rank = running.rows;
+                    running.last_count = count;
+                }
+                columns.push(running.rank.to_string());
+            }
+        }
+        columns
+    }
+
+    /// the percentage rounded to --pct-dec-places
+    fn format_percentage(&self, percentage: f64) -> String {
+        let abs_dec_places = self.flag_pct_dec_places.unsigned_abs() as u32;
+        let pct_decimal = Decimal::from_f64(percentage).unwrap_or_default();
+        let pct_scale = if self.flag_pct_dec_places < 0 {
+            let current_scale = pct_decimal.scale();
+            if current_scale > abs_dec_places {
+                current_scale
+            } else {
+                abs_dec_places
+            }
+        } else {
+            abs_dec_places
+        };
+        let final_pct_decimal = pct_decimal
+            .round_dp_with_strategy(
+                pct_scale,
+                rust_decimal::RoundingStrategy::MidpointAwayFromZero,
+            )
+            .normalize();
+        if final_pct_decimal.fract().to_string().len() > abs_dec_places as usize {
+            final_pct_decimal
+                .round_dp_with_strategy(abs_dec_places, RoundingStrategy::MidpointAwayFromZero)
+                .normalize()
+                .to_string()
+        } else {
+            final_pct_decimal.to_string()
+        }
+    }
+
     #[inline]
     fn counts(&self, ftab: &FTable) -> Vec<(ByteString, u64, f64)> {
         let (mut counts, total_count) = if self.flag_asc {
@@ -289,18 +583,33 @@ impl Args {
             ftab.par_frequent(false)
         };
 
-        // check if we need to apply limits
         let unique_counts_len = counts.len();
+
+        // values with a count below --suppress-below are dropped, and not counted as "Other"
+        let mut suppressed_count = 0_u64;
+        if self.flag_suppress_below > 0 {
+            counts.retain(|(_, count)| {
+                let keep = *count >= self.flag_suppress_below;
+                if !keep {
+                    suppressed_count += count;
+                }
+                keep
+            });
+        }
+        let suppressed_unique_count = unique_counts_len - counts.len();
+
+        // check if we need to apply limits
         if self.flag_lmt_threshold == 0 || self.flag_lmt_threshold >= unique_counts_len {
             // check if the column has all unique values
             // do this by looking at the counts vec
             // and see if it has a count of 1, indicating all unique values
-            let all_unique = counts[if self.flag_asc {
-                unique_counts_len - 1
+            // (a column without values, e.g. with --tokens, has no counts)
+            let all_unique = if self.flag_asc {
+                counts.last()
             } else {
-                0
-            }]
-            .1 == 1;
+                counts.first()
+            }
+            .is_some_and(|(_, count)| *count == 1);
 
             let abs_limit = self.flag_limit.unsigned_abs();
             let unique_limited = if all_unique
@@ -325,6 +634,9 @@ impl Args {
                 counts.retain(|(_, count)| *count >= count_limit);
             }
         }
+        if self.flag_other_threshold > 0 {
+            counts.retain(|(_, count)| *count >= self.flag_other_threshold);
+        }
 
         let mut pct_sum = 0.0_f64;
         let mut pct = 0.0_f64;
@@ -350,9 +662,12 @@ impl Args {
             })
             .collect();
 
-        let other_count = total_count - count_sum;
+        let other_count = total_count - count_sum - suppressed_count;
         if other_count > 0 && self.flag_other_text != "<NONE>" {
-            let other_unique_count = unique_counts_len - counts_final.len();
+            let other_unique_count =
+                unique_counts_len - suppressed_unique_count - counts_final.len();
+            #[allow(clippy::cast_precision_loss)]
+            let other_pct = 100.0_f64 - pct_sum - suppressed_count as f64 * pct_factor;
             counts_final.push((
                 format!(
                     "{} ({})",
@@ -362,12 +677,227 @@ impl Args {
                 .as_bytes()
                 .to_vec(),
                 other_count,
-                100.0_f64 - pct_sum,
+                other_pct,
             ));
         }
         counts_final
     }
 
+    /// the --weight-ed counts of a column's values, sorted & limited like the counts
+    fn weighted_counts(&self, table: WeightedFTable) -> Vec<(ByteString, f64, f64)> {
+        let total_weight: f64 = table.values().sum();
+        let by_weight = |weight1: &f64, weight2: &f64| {
+            if self.flag_asc {
+                weight1.total_cmp(weight2)
+            } else {
+                weight2.total_cmp(weight1)
+            }
+        };
+        let mut counts: Vec<(ByteString, f64)> = table.into_iter().collect();
+        counts.sort_unstable_by(|(value1, weight1), (value2, weight2)| {
+            by_weight(weight1, weight2).then_with(|| value1.cmp(value2))
+        });
+
+        let unique_counts_len = counts.len();
+        #[allow(clippy::cast_precision_loss)]
+        let (other_threshold, suppress_below) = (
+            self.flag_other_threshold as f64,
+            self.flag_suppress_below as f64,
+        );
+        let mut suppressed_weight = 0.0_f64;
+        counts.retain(|(_, weight)| {
+            let keep = *weight >= suppress_below;
+            if !keep {
+                suppressed_weight += weight;
+            }
+            keep
+        });
+        let suppressed_unique_count = unique_counts_len - counts.len();
+
+        if self.flag_lmt_threshold == 0 || self.flag_lmt_threshold >= unique_counts_len {
+            if self.flag_limit > 0 {
+                counts.truncate(self.flag_limit.unsigned_abs());
+            } else if self.flag_limit < 0 {
+                #[allow(clippy::cast_precision_loss)]
+                let weight_limit = self.flag_limit.unsigned_abs() as f64;
+                counts.retain(|(_, weight)| *weight >= weight_limit);
+            }
+        }
+        counts.retain(|(_, weight)| *weight >= other_threshold);
+
+        let pct_factor = if total_weight > 0.0 {
+            100.0_f64 / total_weight
+        } else {
+            0.0_f64
+        };
+        let mut pct_sum = 0.0_f64;
+        let mut weight_sum = 0.0_f64;
+        let mut counts_final: Vec<(ByteString, f64, f64)> = counts
+            .into_iter()
+            .map(|(value, weight)| {
+                weight_sum += weight;
+                let pct = weight * pct_factor;
+                pct_sum += pct;
+                if value.is_empty() {
+                    (NULL_VAL.to_vec(), weight, pct)
+                } else {
+                    (value, weight, pct)
+                }
+            })
+            .collect();
+
+        let other_unique_count = unique_counts_len - suppressed_unique_count - counts_final.len();
+        if other_unique_count > 0 && self.flag_other_text != "<NONE>" {
+            counts_final.push((
+                format!(
+                    "{} ({})",
+                    self.flag_other_text,
+                    HumanCount(other_unique_count as u64)
+                )
+                .into_bytes(),
+                total_weight - weight_sum - suppressed_weight,
+                100.0_f64 - pct_sum - suppressed_weight * pct_factor,
+            ));
+            if self.flag_other_sorted {
+                counts_final
+                    .sort_by(|(_, weight1, _), (_, weight2, _)| by_weight(weight1, weight2));
+            }
+        }
+        counts_final
+    }
+
+    /// the --weight-ed frequency tables, computed sequentially
+    fn weighted_ftables(&self) -> CliResult<(Headers, WeightedFTables)> {
+        let mut rdr = self.rconfig().reader()?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+        // safety: weighted_ftables is only called with --weight
+        let weight_sel = self
+            .rconfig()
+            .select(self.flag_weight.clone().unwrap())
+            .selection(rdr.byte_headers()?)?;
+        if weight_sel.len() != 1 {
+            return fail_incorrectusage_clierror!("--weight must select a single column.");
+        }
+        let weight_idx = weight_sel[0];
+
+        let nsel = sel.normal();
+        let mut tables: WeightedFTables = (0..nsel.len()).map(|_| AHashMap::new()).collect();
+        let mut record = csv::ByteRecord::new();
+        let mut buf = String::new();
+        while rdr.read_byte_record(&mut record)? {
+            let weight = record
+                .get(weight_idx)
+                .and_then(|weight| simdutf8::basic::from_utf8(weight.trim_ascii()).ok())
+                .and_then(|weight| weight.parse::<f64>().ok())
+                .filter(|weight| weight.is_finite() && *weight > 0.0);
+            let Some(weight) = weight else {
+                continue;
+            };
+            for (table, field) in tables.iter_mut().zip(nsel.select(record.iter())) {
+                let value = if field.is_empty() {
+                    if self.flag_no_nulls {
+                        continue;
+                    }
+                    Vec::new()
+                } else {
+                    self.normalize_value(field, &mut buf)
+                };
+                *table.entry(value).or_default() += weight;
+            }
+        }
+        Ok((headers, tables))
+    }
+
+    /// the frequency table of the --joint combinations of the selected columns' values,
+    /// and the number of rows counted
+    fn joint_ftable(&self) -> CliResult<(Headers, FTable, u64)> {
+        let mut rdr = self.rconfig().reader()?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+
+        let mut ftab = FTable::new();
+        let mut record = csv::ByteRecord::new();
+        let mut buf = String::new();
+        let mut combination = Vec::new();
+        let mut row_count = 0_u64;
+        'rows: while rdr.read_byte_record(&mut record)? {
+            combination.clear();
+            for field in sel.select(&record) {
+                if field.is_empty() {
+                    if self.flag_no_nulls {
+                        continue 'rows;
+                    }
+                    encode_value(b"", &mut combination);
+                } else {
+                    encode_value(&self.normalize_value(field, &mut buf), &mut combination);
+                }
+            }
+            ftab.add(combination.clone());
+            row_count += 1;
+        }
+        Ok((headers, ftab, row_count))
+    }
+
+    /// the frequency tables of the --tokens (or --ngram) of the selected columns' values,
+    /// computed sequentially
+    fn token_ftables(&self) -> CliResult<(Headers, FTables)> {
+        let stopwords: AHashSet<String> = match self.flag_stopwords {
+            Some(ref path) => fs::read_to_string(path)
+                .map_err(|e| format!("Cannot read --stopwords file {path}: {e}"))?
+                .lines()
+                .map(|word| word.trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect(),
+            None => AHashSet::new(),
+        };
+
+        let mut rdr = self.rconfig().reader()?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+
+        let nsel = sel.normal();
+        let mut tables: FTables = (0..nsel.len()).map(|_| Frequencies::new()).collect();
+        let mut record = csv::ByteRecord::new();
+        let mut buf = String::new();
+        while rdr.read_byte_record(&mut record)? {
+            for (table, field) in tables.iter_mut().zip(nsel.select(record.iter())) {
+                if field.is_empty() {
+                    continue;
+                }
+                let value = self.normalize_value(field, &mut buf);
+                let value = String::from_utf8_lossy(&value);
+                let tokens: Vec<&str> = value
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|token| {
+                        !token.is_empty()
+                            && (stopwords.is_empty() || !stopwords.contains(&token.to_lowercase()))
+                    })
+                    .collect();
+                for ngram in tokens.windows(self.flag_ngram) {
+                    table.add(ngram.join(" ").into_bytes());
+                }
+            }
+        }
+        Ok((headers, tables))
+    }
+
+    /// the value of a field to count, trimmed & lowercased unless --no-trim & --ignore-case,
+    /// and in the --unicode-normalize form
+    fn normalize_value(&self, field: &[u8], buf: &mut String) -> ByteString {
+        match simdutf8::basic::from_utf8(field) {
+            Ok(s) => {
+                let s = if self.flag_no_trim { s } else { s.trim() };
+                let s = if self.flag_ignore_case {
+                    util::to_lowercase_into(s, buf);
+                    buf.as_str()
+                } else {
+                    s
+                };
+                unicode_normalize(s, self.flag_unicode_normalize.as_deref())
+            },
+            Err(_) if self.flag_no_trim => field.to_vec(),
+            Err(_) => util::trim_bs_whitespace(field).to_vec(),
+        }
+    }
+
     pub fn sequential_ftables(&self) -> CliResult<(Headers, FTables)> {
         let mut rdr = self.rconfig().reader()?;
         let (headers, sel) = self.sel_headers(&mut rdr)?;
@@ -434,7 +964,31 @@ impl Args {
             .map(|i| all_unique_headers.contains(&i))
             .collect();
 
-        if flag_ignore_case {
+        if self.flag_unicode_normalize.is_some() {
+            // unicode normalized, with the case & trim options
+            let mut buf = String::new();
+            for row in it {
+                // safety: we know the row is not empty
+                row_buffer.clone_from(&row.unwrap());
+                for (i, field) in nsel.select(row_buffer.into_iter()).enumerate() {
+                    if unsafe { *all_unique_flag_vec.get_unchecked(i) } {
+                        continue;
+                    }
+
+                    // safety: get_unchecked_mut on freq_tables for same safety reason below
+                    if !field.is_empty() {
+                        field_buffer = self.normalize_value(field, &mut buf);
+                        unsafe {
+                            freq_tables.get_unchecked_mut(i).add(field_buffer);
+                        }
+                    } else if !flag_no_nulls {
+                        unsafe {
+                            freq_tables.get_unchecked_mut(i).add(null.clone());
+                        }
+                    }
+                }
+            }
+        } else if flag_ignore_case {
             // case insensitive when computing frequencies
             let mut buf = String::new();
 
@@ -582,6 +1136,8 @@ impl Args {
             flag_delimiter:       self.flag_delimiter,
             arg_input:            self.arg_input.clone(),
             flag_memcheck:        false,
+            flag_no_enum:         None,
+            enum_threshold_overrides: Vec::new(),
         };
         let stats_mode = match self.flag_stats_mode.as_str() {
             "auto" => StatsMode::Frequency,
@@ -644,7 +1200,13 @@ impl Args {
         rdr: &mut csv::Reader<R>,
     ) -> CliResult<(csv::ByteRecord, Selection)> {
         let headers = rdr.byte_headers()?;
-        let all_unique_headers_vec = self.get_unique_headers(headers)?;
+        // the weighted, joint & token counts of unique values are not short-circuited
+        let all_unique_headers_vec =
+            if self.flag_weight.is_some() || self.flag_joint || self.flag_tokens {
+            Vec::new()
+        } else {
+            self.get_unique_headers(headers)?
+        };
 
         UNIQUE_COLUMNS
             .set(all_unique_headers_vec)
@@ -0,0 +1,66 @@
+static USAGE: &str = r#"
+Converts an Arrow IPC file - also known as Feather v2 - to CSV using the Pola.rs engine, to
+exchange data with the Python & R Arrow ecosystems (e.g. pyarrow.feather.write_feather,
+pandas.DataFrame.to_feather or arrow::write_feather).
+
+The Arrow file is scanned lazily: only the --select columns are read, and the rows are
+filtered with the --where expression as they're read. The --where expression is a SQL
+expression, as in the WHERE clause of a SQL query (see 'qsv sqlp --help'), e.g.
+"price > 10 AND city = 'Denver'". Dates & datetimes are written in ISO 8601 format, and
+nulls as empty values.
+
+Note that Feather v1 files, written by old versions of the Arrow libraries, aren't
+supported.
+
+Examples:
+
+  # convert data.feather to data.csv
+  $ qsv fromarrow data.feather --output data.csv
+
+  # only the id & price columns of the Denver rows
+  $ qsv fromarrow data.arrow --select id,price --where "city = 'Denver'"
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_fromarrow.rs.
+
+Usage:
+    qsv fromarrow [options] <input>
+    qsv fromarrow --help
+
+fromarrow options:
+    -s, --select <cols>    The comma-separated names of the columns to write, in that
+                           order. Defaults to all the columns.
+    -w, --where <expr>     Only write the rows matching this SQL expression.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for writing CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use polars::prelude::{LazyFrame, ScanArgsIpc};
+use serde::Deserialize;
+
+use crate::{cmd::fromparquet::write_csv, config::Delimiter, util, CliResult};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:      String,
+    flag_select:    Option<String>,
+    flag_where:     Option<String>,
+    flag_output:    Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let lf = LazyFrame::scan_ipc(&args.arg_input, ScanArgsIpc::default())?;
+    write_csv(
+        lf,
+        args.flag_select.as_deref(),
+        args.flag_where.as_deref(),
+        args.flag_output.as_deref(),
+        args.flag_delimiter,
+    )
+}
@@ -0,0 +1,72 @@
+static USAGE: &str = r#"
+Converts an Apache Avro object container file to CSV using the Pola.rs engine, e.g. to
+inspect the data of Kafka-adjacent pipelines with qsv.
+
+The Avro logical types are written as:
+  date:                          ISO 8601 dates, e.g. 2024-01-15
+  timestamp-millis/micros:       ISO 8601 datetimes, e.g. 2024-01-15T10:30:00.000
+  time-millis/micros:            times, e.g. 10:30:00
+  decimal:                       the exact decimal numbers, e.g. 1234.50
+Nulls are written as empty values.
+
+Unlike Parquet & Arrow files, Avro files are read in memory before the --select and
+the --where filters are applied. The --where expression is a SQL expression, as in the WHERE
+clause of a SQL query (see 'qsv sqlp --help'), e.g. "price > 10 AND city = 'Denver'".
+
+Examples:
+
+  # convert data.avro to data.csv
+  $ qsv fromavro data.avro --output data.csv
+
+  # only the id & price columns of the Denver rows
+  $ qsv fromavro data.avro --select id,price --where "city = 'Denver'"
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_fromavro.rs.
+
+Usage:
+    qsv fromavro [options] <input>
+    qsv fromavro --help
+
+fromavro options:
+    -s, --select <cols>    The comma-separated names of the columns to write, in that
+                           order. Defaults to all the columns.
+    -w, --where <expr>     Only write the rows matching this SQL expression.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for writing CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::fs::File;
+
+use polars::{
+    io::avro::AvroReader,
+    prelude::{IntoLazy, SerReader},
+};
+use serde::Deserialize;
+
+use crate::{cmd::fromparquet::write_csv, config::Delimiter, util, CliResult};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:      String,
+    flag_select:    Option<String>,
+    flag_where:     Option<String>,
+    flag_output:    Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let df = AvroReader::new(File::open(&args.arg_input)?).finish()?;
+    write_csv(
+        df.lazy(),
+        args.flag_select.as_deref(),
+        args.flag_where.as_deref(),
+        args.flag_output.as_deref(),
+        args.flag_delimiter,
+    )
+}
@@ -0,0 +1,302 @@
+static USAGE: &str = r#"
+Converts GeoJSON to CSV.
+
+Each Feature becomes a record, with its properties flattened into columns. Nested
+properties are flattened into dot-separated column names (e.g. address.city), and the
+columns are the union of the properties of all the Features, in order of first appearance.
+
+The input can be a FeatureCollection, a single Feature or newline-delimited Features
+(GeoJSON Text Sequences, as written by `qsv togeojson --jsonl`).
+
+The geometry of each Feature is written according to --geometry:
+  * wkt: as WKT in a "geometry" column (e.g. POINT (-0.1278 51.5074)).
+  * latlon: as "lat" and "lon" columns. Only Point geometries are converted,
+            the coordinates of other geometry types are left empty with a warning.
+  * drop: the geometry is not written.
+
+Examples:
+
+  # convert a FeatureCollection to CSV, with the geometry as WKT
+  $ qsv fromgeojson features.geojson > features.csv
+
+  # convert points to lat/lon columns
+  $ qsv fromgeojson --geometry latlon stops.geojson > stops.csv
+
+  # only keep the properties
+  $ qsv fromgeojson --geometry drop features.geojson
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_fromgeojson.rs.
+
+Usage:
+    qsv fromgeojson [options] [<input>]
+    qsv fromgeojson --help
+
+fromgeojson options:
+    --geometry <mode>      How to write the geometry - wkt, latlon or drop.
+                           [default: wkt]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The delimiter to use when writing CSV data.
+                           Must be a single character. [default: ,]
+"#;
+
+use std::io::Read;
+
+use indexmap::IndexSet;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    config::{Config, Delimiter},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:      Option<String>,
+    flag_geometry:  String,
+    flag_output:    Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+enum GeometryMode {
+    Wkt,
+    LatLon,
+    Drop,
+}
+
+/// collect the features of a FeatureCollection, a Feature or GeoJSON Text Sequences
+fn read_features(geojson: &str) -> CliResult<Vec<Value>> {
+    if let Ok(value) = serde_json::from_str::<Value>(geojson) {
+        return match value.get("type").and_then(Value::as_str) {
+            Some("FeatureCollection") => match value.get("features") {
+                Some(Value::Array(features)) => Ok(features.clone()),
+                _ => fail_clierror!("FeatureCollection has no \"features\" array."),
+            },
+            Some("Feature") => Ok(vec![value]),
+            Some(other) => fail_clierror!("Expected a Feature or FeatureCollection, got {other}."),
+            None => fail_clierror!("Input is not GeoJSON - it has no \"type\" member."),
+        };
+    }
+
+    // newline-delimited features, which may be prefixed with a record separator (RFC 8142)
+    let mut features = Vec::new();
+    for (idx, line) in geojson.lines().enumerate() {
+        let line = line.trim_start_matches('\x1e').trim();
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(feature) if feature.get("type").and_then(Value::as_str) == Some("Feature") => {
+                features.push(feature);
+            },
+            Ok(_) => return fail_clierror!("Line {} is not a GeoJSON Feature.", idx + 1),
+            Err(e) => return fail_clierror!("Cannot parse line {} as GeoJSON: {e}", idx + 1),
+        }
+    }
+    Ok(features)
+}
+
+/// the properties of a feature, or `empty` if it has none
+fn properties_of<'a>(feature: &'a Value, empty: &'a Map<String, Value>) -> &'a Map<String, Value> {
+    feature
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(empty)
+}
+
+/// collect the property paths of an object, flattening nested objects
+fn collect_paths(map: &Map<String, Value>, prefix: &[String], paths: &mut IndexSet<Vec<String>>) {
+    for (key, value) in map {
+        let mut path = prefix.to_vec();
+        path.push(key.clone());
+        if let Value::Object(nested) = value {
+            collect_paths(nested, &path, paths);
+        } else {
+            paths.insert(path);
+        }
+    }
+}
+
+/// the value at a property path, as a CSV field
+fn field_at_path(properties: &Map<String, Value>, path: &[String]) -> String {
+    let mut current = properties.get(&path[0]);
+    for key in &path[1..] {
+        current = current.and_then(|value| value.get(key));
+    }
+    match current {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Bool(b)) => b.to_string(),
+        Some(Value::Number(n)) => n.to_string(),
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        _ => String::new(),
+    }
+}
+
+/// a GeoJSON position as WKT coordinates, e.g. "-0.1278 51.5074"
+fn wkt_position(position: &Value) -> Result<String, String> {
+    match position.as_array() {
+        Some(coords) if coords.len() >= 2 && coords.iter().all(Value::is_number) => Ok(coords
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ")),
+        _ => Err(format!("invalid position: {position}")),
+    }
+}
+
+/// a list of positions (or nested lists of positions, `depth` levels deep) as WKT
+fn wkt_positions(positions: &Value, depth: usize) -> Result<String, String> {
+    let Some(items) = positions.as_array() else {
+        return Err(format!("invalid coordinates: {positions}"));
+    };
+    let wkt_items = items
+        .iter()
+        .map(|item| {
+            if depth == 0 {
+                wkt_position(item)
+            } else {
+                wkt_positions(item, depth - 1)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("({})", wkt_items.join(", ")))
+}
+
+/// a GeoJSON geometry as WKT
+fn wkt_geometry(geometry: &Value) -> Result<String, String> {
+    let geometry_type = geometry
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if geometry_type == "GeometryCollection" {
+        let Some(Value::Array(geometries)) = geometry.get("geometries") else {
+            return Err("GeometryCollection has no \"geometries\" array".to_string());
+        };
+        let wkt_geometries = geometries
+            .iter()
+            .map(wkt_geometry)
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(format!(
+            "GEOMETRYCOLLECTION ({})",
+            wkt_geometries.join(", ")
+        ));
+    }
+
+    let coordinates = geometry.get("coordinates").unwrap_or(&Value::Null);
+    let (wkt_type, wkt_coordinates) = match geometry_type {
+        "Point" => ("POINT", format!("({})", wkt_position(coordinates)?)),
+        "MultiPoint" | "LineString" => (
+            if geometry_type == "MultiPoint" {
+                "MULTIPOINT"
+            } else {
+                "LINESTRING"
+            },
+            wkt_positions(coordinates, 0)?,
+        ),
+        "MultiLineString" => ("MULTILINESTRING", wkt_positions(coordinates, 1)?),
+        "Polygon" => ("POLYGON", wkt_positions(coordinates, 1)?),
+        "MultiPolygon" => ("MULTIPOLYGON", wkt_positions(coordinates, 2)?),
+        other => return Err(format!("unsupported geometry type: {other}")),
+    };
+    Ok(format!("{wkt_type} {wkt_coordinates}"))
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let geometry_mode = match args.flag_geometry.to_ascii_lowercase().as_str() {
+        "wkt" => GeometryMode::Wkt,
+        "latlon" => GeometryMode::LatLon,
+        "drop" => GeometryMode::Drop,
+        other => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --geometry mode: {other}. Valid modes are wkt, latlon and drop."
+            )
+        },
+    };
+
+    let mut geojson = String::new();
+    Config::new(&args.arg_input)
+        .io_reader()?
+        .read_to_string(&mut geojson)?;
+    let features = read_features(&geojson)?;
+
+    let empty_properties = Map::new();
+    let mut paths: IndexSet<Vec<String>> = IndexSet::new();
+    for feature in &features {
+        collect_paths(properties_of(feature, &empty_properties), &[], &mut paths);
+    }
+
+    let mut wtr = Config::new(&args.flag_output)
+        .delimiter(args.flag_delimiter)
+        .writer()?;
+
+    let mut headers = csv::StringRecord::from(
+        paths
+            .iter()
+            .map(|path| path.join("."))
+            .collect::<Vec<String>>(),
+    );
+    match geometry_mode {
+        GeometryMode::Wkt => headers.push_field("geometry"),
+        GeometryMode::LatLon => {
+            headers.push_field("lat");
+            headers.push_field("lon");
+        },
+        GeometryMode::Drop => {},
+    }
+    wtr.write_record(&headers)?;
+
+    let mut non_points: u64 = 0;
+    let mut record = csv::StringRecord::with_capacity(256, headers.len());
+    for (idx, feature) in features.iter().enumerate() {
+        record.clear();
+        let properties = properties_of(feature, &empty_properties);
+        for path in &paths {
+            record.push_field(&field_at_path(properties, path));
+        }
+
+        let geometry = feature.get("geometry").filter(|g| !g.is_null());
+        match geometry_mode {
+            GeometryMode::Wkt => match geometry.map(wkt_geometry).transpose() {
+                Ok(wkt) => record.push_field(&wkt.unwrap_or_default()),
+                Err(e) => return fail_clierror!("Feature {}: {e}", idx + 1),
+            },
+            GeometryMode::LatLon => {
+                let point = geometry
+                    .filter(|g| g.get("type").and_then(Value::as_str) == Some("Point"))
+                    .and_then(|g| g.get("coordinates"))
+                    .and_then(Value::as_array);
+                match point {
+                    Some(coords) if coords.len() >= 2 => {
+                        record.push_field(&coords[1].to_string());
+                        record.push_field(&coords[0].to_string());
+                    },
+                    _ => {
+                        if geometry.is_some() {
+                            non_points += 1;
+                        }
+                        record.push_field("");
+                        record.push_field("");
+                    },
+                }
+            },
+            GeometryMode::Drop => {},
+        }
+        wtr.write_record(&record)?;
+    }
+    wtr.flush()?;
+
+    if non_points > 0 {
+        wwarn!("{non_points} non-Point geometries were left empty.");
+    }
+
+    Ok(())
+}
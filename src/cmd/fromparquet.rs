@@ -0,0 +1,134 @@
+static USAGE: &str = r#"
+Converts an Apache Parquet file to CSV using the Pola.rs engine, so qsv pipelines can start
+from lakehouse files.
+
+The Parquet file is scanned lazily with the streaming engine: only the --select columns are
+read (column pruning), and the --where expression is pushed down to the scan, so the row
+groups whose statistics rule out any match are skipped (predicate pushdown).
+
+The --where expression is a SQL expression, as in the WHERE clause of a SQL query (see
+'qsv sqlp --help'), e.g. "price > 10 AND city = 'Denver'". Dates & datetimes are written in
+ISO 8601 format, and nulls as empty values.
+
+Examples:
+
+  # convert data.parquet to data.csv
+  $ qsv fromparquet data.parquet --output data.csv
+
+  # only the id & price columns of the Denver rows
+  $ qsv fromparquet data.parquet --select id,price --where "city = 'Denver'"
+
+  # the stats of the large orders, from a Parquet file written by 'qsv toparquet'
+  $ qsv fromparquet orders.parquet --where "amount >= 1000" | qsv stats
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_fromparquet.rs.
+
+Usage:
+    qsv fromparquet [options] <input>
+    qsv fromparquet --help
+
+fromparquet options:
+    -s, --select <cols>    The comma-separated names of the columns to write, in that
+                           order. Defaults to all the columns.
+    -w, --where <expr>     Only write the rows matching this SQL expression.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for writing CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{
+    fs::File,
+    io::{self, Write},
+};
+
+use polars::prelude::{
+    col, CsvWriter, DataType, Expr, IntoLazy, LazyFrame, ScanArgsParquet, SerWriter,
+};
+use serde::Deserialize;
+
+use crate::{
+    cmd::joinp::tsvssv_delim,
+    config::{Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:      String,
+    flag_select:    Option<String>,
+    flag_where:     Option<String>,
+    flag_output:    Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+/// Write the rows of a lazily scanned file as CSV, filtered with the --where expression &
+/// keeping the --select columns, both pushed down to the scan by the query optimizer.
+/// Also used by the other commands that convert typed formats to CSV.
+pub fn write_csv(
+    mut lf: LazyFrame,
+    select: Option<&str>,
+    filter: Option<&str>,
+    output: Option<&str>,
+    delimiter: Option<Delimiter>,
+) -> CliResult<()> {
+    // the rows are filtered first, so --where can use the columns that aren't selected
+    if let Some(filter) = filter {
+        lf = lf.filter(polars::sql::sql_expr(filter)?);
+    }
+    if let Some(select) = select {
+        let columns: Vec<Expr> = select
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(col)
+            .collect();
+        if columns.is_empty() {
+            return fail_incorrectusage_clierror!("--select has no column names.");
+        }
+        lf = lf.select(columns);
+    }
+    let mut df = lf.with_streaming(true).collect()?;
+
+    // the decimals are written as their exact text, with their scale
+    let decimals: Vec<Expr> = df
+        .schema()
+        .iter()
+        .filter(|(_, dtype)| matches!(dtype, DataType::Decimal(_, _)))
+        .map(|(name, _)| col(name.as_str()).cast(DataType::String))
+        .collect();
+    if !decimals.is_empty() {
+        df = df.lazy().with_columns(decimals).collect()?;
+    }
+
+    let mut delim = delimiter.map_or(b',', |delimiter| delimiter.as_byte());
+    let w = match output {
+        Some(path) => {
+            delim = tsvssv_delim(path, delim);
+            Box::new(File::create(path)?) as Box<dyn Write>
+        },
+        None => Box::new(io::stdout()) as Box<dyn Write>,
+    };
+    let mut w = io::BufWriter::with_capacity(DEFAULT_WTR_BUFFER_CAPACITY, w);
+    CsvWriter::new(&mut w)
+        .include_header(true)
+        .with_separator(delim)
+        .include_bom(util::get_envvar_flag("QSV_OUTPUT_BOM"))
+        .finish(&mut df)?;
+    Ok(w.flush()?)
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let lf = LazyFrame::scan_parquet(&args.arg_input, ScanArgsParquet::default())?;
+    write_csv(
+        lf,
+        args.flag_select.as_deref(),
+        args.flag_where.as_deref(),
+        args.flag_output.as_deref(),
+        args.flag_delimiter,
+    )
+}
@@ -0,0 +1,97 @@
+static USAGE: &str = r#"
+Dumps a table of a SQLite database, or the results of a SQL query against it, to CSV.
+
+NULLs are written as empty values, and BLOBs as hexadecimal strings.
+
+Examples:
+
+  # dump the orders table of db.sqlite
+  $ qsv fromsqlite db.sqlite --table orders --output orders.csv
+
+  # the results of a query
+  $ qsv fromsqlite db.sqlite --query "SELECT customer_id, sum(amount) FROM orders GROUP BY 1"
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_fromsqlite.rs.
+
+Usage:
+    qsv fromsqlite [options] <database>
+    qsv fromsqlite --help
+
+fromsqlite options:
+    -t, --table <name>     The table to dump.
+    -q, --query <sql>      The SQL query whose results to dump.
+                           Exactly one of --table or --query is required.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for writing CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::fmt::Write;
+
+use rusqlite::{types::ValueRef, Connection, OpenFlags};
+use serde::Deserialize;
+
+use crate::{
+    cmd::schema::{sql_identifier, SqlDialect},
+    config::{Config, Delimiter},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_database:   String,
+    flag_table:     Option<String>,
+    flag_query:     Option<String>,
+    flag_output:    Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let sql = match (args.flag_table, args.flag_query) {
+        (Some(table), None) => format!(
+            "SELECT * FROM {}",
+            sql_identifier(SqlDialect::Sqlite, &table)
+        ),
+        (None, Some(query)) => query,
+        _ => {
+            return fail_incorrectusage_clierror!("Exactly one of --table or --query is required.")
+        },
+    };
+
+    let conn = Connection::open_with_flags(&args.arg_database, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare(&sql)?;
+    let column_count = stmt.column_count();
+
+    let mut wtr = Config::new(&args.flag_output)
+        .delimiter(args.flag_delimiter)
+        .writer()?;
+    wtr.write_record(stmt.column_names())?;
+
+    let mut itoa_buffer = itoa::Buffer::new();
+    let mut ryu_buffer = ryu::Buffer::new();
+    let mut hex = String::new();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        for i in 0..column_count {
+            match row.get_ref(i)? {
+                ValueRef::Null => wtr.write_field(b"")?,
+                ValueRef::Integer(value) => wtr.write_field(itoa_buffer.format(value))?,
+                ValueRef::Real(value) => wtr.write_field(ryu_buffer.format(value))?,
+                ValueRef::Text(value) => wtr.write_field(value)?,
+                ValueRef::Blob(value) => {
+                    hex.clear();
+                    for byte in value {
+                        write!(hex, "{byte:02x}").unwrap();
+                    }
+                    wtr.write_field(&hex)?;
+                },
+            }
+        }
+        wtr.write_record(None::<&[u8]>)?;
+    }
+    Ok(wtr.flush()?)
+}
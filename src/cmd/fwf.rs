@@ -0,0 +1,257 @@
+static USAGE: &str = r#"
+Converts a fixed-width file - as produced by mainframes, COBOL copybooks & many finance
+and government data drops - to CSV.
+
+The columns are either set with --widths, as the widths of consecutive columns, or with
+a --spec CSV file, with a row per column and "name", "start" & "length" columns, where start
+is the 1-based position of the first character of the column. With --spec, columns can be
+in any order, and the characters between or after them are ignored.
+
+Widths & positions are in characters, after decoding the lines from --encoding. Lines
+shorter than the columns have their missing columns empty, and blank lines are skipped.
+
+Examples:
+
+  # three columns of 10, 5 & 20 characters, named _col_1, _col_2 & _col_3
+  $ qsv fwf --widths 10,5,20 data.txt
+
+  # with column names, from a latin1-encoded file with a 2-line preamble
+  $ qsv fwf -w 10,5,20 --names id,state,name --encoding latin1 --skip-lines 2 data.txt
+
+  # with the columns of spec.csv, e.g.
+  #   name,start,length
+  #   id,1,10
+  #   name,16,20
+  $ qsv fwf --spec spec.csv data.txt --output data.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_fwf.rs.
+
+Usage:
+    qsv fwf [options] [<input>]
+    qsv fwf --help
+
+fwf options:
+    -w, --widths <list>    The comma-separated widths of the columns.
+    -s, --spec <file>      A CSV with the "name", "start" & "length" of each column.
+                           Exactly one of --widths or --spec is required.
+    -n, --names <list>     The comma-separated names of the --widths columns.
+                           Defaults to _col_N, where N is the 1-based column index.
+    --encoding <label>     The encoding of the input, e.g. utf-8, latin1, windows-1252
+                           or shift_jis. Invalid characters are replaced with U+FFFD.
+                           See https://encoding.spec.whatwg.org/#names-and-labels
+                           [default: utf-8]
+    --trim <mode>          Trim the whitespace around values: both, left, right or none.
+                           [default: both]
+    --skip-lines <n>       The number of preamble lines to skip.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for writing CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::io::{BufRead, BufReader};
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE};
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter, DEFAULT_RDR_BUFFER_CAPACITY},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_widths:     Option<String>,
+    flag_spec:       Option<String>,
+    flag_names:      Option<String>,
+    flag_encoding:   String,
+    flag_trim:       String,
+    flag_skip_lines: Option<usize>,
+    flag_output:     Option<String>,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Trim {
+    Both,
+    Left,
+    Right,
+    None,
+}
+
+/// a column of the fixed-width file, with its 0-based start & its length in characters
+struct Column {
+    name:   String,
+    start:  usize,
+    length: usize,
+}
+
+fn parse_positive(value: &str, what: &str) -> CliResult<usize> {
+    match value.trim().parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => fail_incorrectusage_clierror!("Invalid {what}: {value}. Expected a positive integer."),
+    }
+}
+
+/// the columns of consecutive --widths, named with --names
+fn columns_from_widths(widths: &str, names: Option<&str>) -> CliResult<Vec<Column>> {
+    let names: Vec<&str> = names
+        .map(|names| names.split(',').collect())
+        .unwrap_or_default();
+    let mut columns = Vec::new();
+    let mut start = 0;
+    for (i, width) in widths.split(',').enumerate() {
+        let length = parse_positive(width, "width")?;
+        let name = match names.get(i) {
+            Some(name) => name.to_string(),
+            None => format!("_col_{}", i + 1),
+        };
+        columns.push(Column {
+            name,
+            start,
+            length,
+        });
+        start += length;
+    }
+    if !names.is_empty() && names.len() != columns.len() {
+        return fail_incorrectusage_clierror!(
+            "--names has {} names, but --widths has {} columns.",
+            names.len(),
+            columns.len()
+        );
+    }
+    Ok(columns)
+}
+
+/// the columns of a --spec CSV, with name, start & length columns
+fn columns_from_spec(spec: &str) -> CliResult<Vec<Column>> {
+    let mut rdr = Config::new(&Some(spec.to_string())).reader()?;
+    let headers = rdr.headers()?.clone();
+    let position = |column: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(column))
+    };
+    let (Some(name_idx), Some(start_idx), Some(length_idx)) =
+        (position("name"), position("start"), position("length"))
+    else {
+        return fail_incorrectusage_clierror!(
+            "--spec {spec} must have \"name\", \"start\" & \"length\" columns."
+        );
+    };
+
+    let mut columns = Vec::new();
+    for record in rdr.records() {
+        let record = record?;
+        let field = |idx: usize| record.get(idx).unwrap_or_default();
+        columns.push(Column {
+            name:   field(name_idx).to_string(),
+            start:  parse_positive(field(start_idx), "start")? - 1,
+            length: parse_positive(field(length_idx), "length")?,
+        });
+    }
+    if columns.is_empty() {
+        return fail_incorrectusage_clierror!("--spec {spec} has no columns.");
+    }
+    Ok(columns)
+}
+
+fn trim_value(value: &str, trim: Trim) -> &str {
+    match trim {
+        Trim::Both => value.trim(),
+        Trim::Left => value.trim_start(),
+        Trim::Right => value.trim_end(),
+        Trim::None => value,
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let columns = match (args.flag_widths, args.flag_spec) {
+        (Some(widths), None) => columns_from_widths(&widths, args.flag_names.as_deref())?,
+        (None, Some(spec)) => {
+            if args.flag_names.is_some() {
+                return fail_incorrectusage_clierror!("--names can only be used with --widths.");
+            }
+            columns_from_spec(&spec)?
+        },
+        _ => {
+            return fail_incorrectusage_clierror!("Exactly one of --widths or --spec is required.")
+        },
+    };
+    let trim = match args.flag_trim.to_ascii_lowercase().as_str() {
+        "both" => Trim::Both,
+        "left" => Trim::Left,
+        "right" => Trim::Right,
+        "none" => Trim::None,
+        other => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --trim mode: {other}. Valid modes are both, left, right and none."
+            )
+        },
+    };
+    let Some(encoding) = Encoding::for_label(args.flag_encoding.as_bytes()) else {
+        return fail_incorrectusage_clierror!("Unknown --encoding: {}.", args.flag_encoding);
+    };
+    // the lines are split on newline bytes, which UTF-16 doesn't have
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        return fail_incorrectusage_clierror!("UTF-16 encoded input is not supported.");
+    }
+
+    let mut rdr = BufReader::with_capacity(
+        DEFAULT_RDR_BUFFER_CAPACITY,
+        Config::new(&args.arg_input).io_reader()?,
+    );
+    let mut wtr = Config::new(&args.flag_output)
+        .delimiter(args.flag_delimiter)
+        .writer()?;
+    wtr.write_record(columns.iter().map(|column| column.name.as_str()))?;
+
+    let mut line = Vec::new();
+    let mut chars: Vec<char> = Vec::new();
+    let mut value = String::new();
+    let mut skip_lines = args.flag_skip_lines.unwrap_or_default();
+    let mut first_line = true;
+    loop {
+        line.clear();
+        if rdr.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        if skip_lines > 0 {
+            skip_lines -= 1;
+            continue;
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        let decoded = if first_line {
+            first_line = false;
+            encoding.decode_with_bom_removal(&line).0
+        } else {
+            encoding.decode_without_bom_handling(&line).0
+        };
+        if decoded.trim().is_empty() {
+            continue;
+        }
+
+        chars.clear();
+        chars.extend(decoded.chars());
+        for column in &columns {
+            value.clear();
+            if column.start < chars.len() {
+                let end = (column.start + column.length).min(chars.len());
+                value.extend(&chars[column.start..end]);
+            }
+            wtr.write_field(trim_value(&value, trim))?;
+        }
+        wtr.write_record(None::<&[u8]>)?;
+    }
+    Ok(wtr.flush()?)
+}
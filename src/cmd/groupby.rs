@@ -0,0 +1,492 @@
+static USAGE: &str = r#"
+Groups the rows of a CSV by the values of the <keys> columns, and aggregates the other
+columns of each group - a rollup without the full SQL engine of sqlp.
+
+The output has a row per group, sorted by the group's key values, with the key columns
+followed by a column per aggregation. The aggregations are a comma-separated list of:
+  * count()    the number of rows of the group.
+  * count(c)   the number of non-empty values of column c.
+  * sum(c)     the sum of the values of column c, which must be numbers.
+  * mean(c)    the mean of the values of column c, which must be numbers.
+  * min(c)     the smallest value of column c.
+  * max(c)     the largest value of column c.
+  * first(c)   the first non-empty value of column c.
+  * last(c)    the last non-empty value of column c.
+Empty values are skipped. min & max compare the values as numbers when they're both
+numbers, and as text otherwise - so ISO 8601 dates are compared chronologically.
+The columns are named after their aggregation, e.g. sum_amount, or count for count().
+
+The groups are aggregated in a hash table in memory. When there are more groups than
+the --spill-groups limit, they are spilled to a sorted temporary file, in a scratch directory
+in QSV_TMPDIR (or the system temp dir if QSV_TMPDIR is not set), and the spilled files
+are merged at the end - so data with a large number of groups doesn't run out of memory.
+
+Examples:
+
+  # the number of rows, total amount, mean price & first order date of each customer
+  $ qsv groupby customer --agg "count(),sum(amount),mean(price),min(date)" orders.csv
+
+  # the sales per store & year
+  $ qsv groupby store,year --agg "sum(amount)" sales.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_groupby.rs.
+
+Usage:
+    qsv groupby [options] <keys> [<input>]
+    qsv groupby --help
+
+groupby arguments:
+    <keys>                 The columns to group by.
+                           See 'qsv select --help' for the format details.
+
+groupby options:
+    -a, --agg <aggs>       The aggregations, e.g. "count(),sum(amount),max(date)".
+                           The columns of the aggregations are selected like <keys>,
+                           but must select a single column.
+                           [default: count()]
+    --round <places>       Round the means to <places> decimal places.
+                           [default: 4]
+    --spill-groups <n>     The number of groups kept in memory before they're
+                           spilled to a temporary file.
+                           [default: 1000000]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{fs::File, path::PathBuf};
+
+use ahash::AHashMap;
+use csv::ByteRecord;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_keys:          SelectColumns,
+    arg_input:         Option<String>,
+    flag_agg:          String,
+    flag_round:        u32,
+    flag_spill_groups: usize,
+    flag_output:       Option<String>,
+    flag_delimiter:    Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Func {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    First,
+    Last,
+}
+
+/// an aggregation of a column, or of the rows for count()
+struct Agg {
+    func:   Func,
+    column: Option<usize>,
+    name:   String,
+}
+
+/// parse the --agg aggregations, e.g. "count(),sum(amount)"
+fn parse_aggs(aggs: &str, headers: &ByteRecord) -> CliResult<Vec<Agg>> {
+    let mut parsed = Vec::new();
+    for agg in aggs.split(',').map(str::trim).filter(|agg| !agg.is_empty()) {
+        let invalid = || format!("Invalid aggregation: {agg}. Expected e.g. sum(amount).");
+        let Some((func, column)) = agg.strip_suffix(')').and_then(|agg| agg.split_once('(')) else {
+            return fail_incorrectusage_clierror!("{}", invalid());
+        };
+        let func = match func.trim().to_ascii_lowercase().as_str() {
+            "count" => Func::Count,
+            "sum" => Func::Sum,
+            "mean" => Func::Mean,
+            "min" => Func::Min,
+            "max" => Func::Max,
+            "first" => Func::First,
+            "last" => Func::Last,
+            _ => {
+                return fail_incorrectusage_clierror!(
+                    "Invalid aggregation: {agg}. The functions are count, sum, mean, min, max, \
+                     first & last."
+                )
+            },
+        };
+        let column = column.trim();
+        if column.is_empty() {
+            if func != Func::Count {
+                return fail_incorrectusage_clierror!("{}", invalid());
+            }
+            parsed.push(Agg {
+                func,
+                column: None,
+                name: "count".to_string(),
+            });
+            continue;
+        }
+        let sel = SelectColumns::parse(column)?.selection(headers, true)?;
+        if sel.len() != 1 {
+            return fail_incorrectusage_clierror!(
+                "The column of aggregation {agg} must be a single column."
+            );
+        }
+        parsed.push(Agg {
+            func,
+            column: Some(sel[0]),
+            name: format!(
+                "{}_{}",
+                func.name(),
+                String::from_utf8_lossy(&headers[sel[0]])
+            ),
+        });
+    }
+    if parsed.is_empty() {
+        return fail_incorrectusage_clierror!("--agg requires at least one aggregation.");
+    }
+    Ok(parsed)
+}
+
+impl Func {
+    fn name(self) -> &'static str {
+        match self {
+            Func::Count => "count",
+            Func::Sum => "sum",
+            Func::Mean => "mean",
+            Func::Min => "min",
+            Func::Max => "max",
+            Func::First => "first",
+            Func::Last => "last",
+        }
+    }
+}
+
+/// the accumulated aggregate of a group
+enum Acc {
+    Count(u64),
+    Sum(f64),
+    Mean(f64, u64),
+    /// the min, max, first or last value, empty before the first value
+    Value(Vec<u8>),
+}
+
+fn parse_number(value: &[u8]) -> Option<f64> {
+    simdutf8::basic::from_utf8(value)
+        .ok()
+        .and_then(|value| value.trim().parse::<f64>().ok())
+}
+
+impl Acc {
+    fn new(func: Func) -> Self {
+        match func {
+            Func::Count => Acc::Count(0),
+            Func::Sum => Acc::Sum(0.0),
+            Func::Mean => Acc::Mean(0.0, 0),
+            Func::Min | Func::Max | Func::First | Func::Last => Acc::Value(Vec::new()),
+        }
+    }
+
+    /// add the value of a row, or the row itself for count()
+    fn add(&mut self, func: Func, value: Option<&[u8]>, row_number: u64) -> CliResult<()> {
+        let Some(value) = value else {
+            if let Acc::Count(count) = self {
+                *count += 1;
+            }
+            return Ok(());
+        };
+        if value.is_empty() {
+            return Ok(());
+        }
+        let number = || {
+            parse_number(value).ok_or_else(|| {
+                format!(
+                    "Invalid value in row {row_number} for {}: \"{}\" is not a number.",
+                    func.name(),
+                    String::from_utf8_lossy(value)
+                )
+            })
+        };
+        match self {
+            Acc::Count(count) => *count += 1,
+            Acc::Sum(sum) => *sum += number()?,
+            Acc::Mean(sum, count) => {
+                *sum += number()?;
+                *count += 1;
+            },
+            Acc::Value(current) => {
+                let replace = current.is_empty()
+                    || match func {
+                        Func::Min => util::cmp_values(value, current).is_lt(),
+                        Func::Max => util::cmp_values(value, current).is_gt(),
+                        Func::Last => true,
+                        _ => false,
+                    };
+                if replace {
+                    value.clone_into(current);
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// add the aggregate of later rows
+    fn merge(&mut self, func: Func, later: Acc) {
+        match (self, later) {
+            (Acc::Count(count), Acc::Count(later)) => *count += later,
+            (Acc::Sum(sum), Acc::Sum(later)) => *sum += later,
+            (Acc::Mean(sum, count), Acc::Mean(later_sum, later_count)) => {
+                *sum += later_sum;
+                *count += later_count;
+            },
+            (Acc::Value(current), Acc::Value(later)) => {
+                let replace = !later.is_empty()
+                    && (current.is_empty()
+                        || match func {
+                            Func::Min => util::cmp_values(&later, current).is_lt(),
+                            Func::Max => util::cmp_values(&later, current).is_gt(),
+                            Func::Last => true,
+                            _ => false,
+                        });
+                if replace {
+                    *current = later;
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// the aggregate's output value
+    fn to_field(&self, round: u32) -> Vec<u8> {
+        match self {
+            Acc::Count(count) => count.to_string().into_bytes(),
+            Acc::Sum(sum) => {
+                if sum.fract() == 0.0 && sum.abs() < 9.0e15 {
+                    (*sum as i64).to_string().into_bytes()
+                } else {
+                    util::round_num(*sum, 9999).into_bytes()
+                }
+            },
+            Acc::Mean(_, 0) => Vec::new(),
+            Acc::Mean(sum, count) => util::round_num(*sum / *count as f64, round).into_bytes(),
+            Acc::Value(value) => value.clone(),
+        }
+    }
+
+    /// the aggregate as a field of a spill file, keeping the sums exact
+    fn to_spilled(&self) -> Vec<u8> {
+        match self {
+            Acc::Count(count) => count.to_string().into_bytes(),
+            Acc::Sum(sum) => util::round_num(*sum, 9999).into_bytes(),
+            Acc::Mean(sum, count) => {
+                format!("{}/{count}", util::round_num(*sum, 9999)).into_bytes()
+            },
+            Acc::Value(value) => value.clone(),
+        }
+    }
+
+    /// the aggregate of a field of a spill file
+    fn from_spilled(func: Func, field: &[u8]) -> CliResult<Self> {
+        let invalid = || "Invalid groupby spill file.".to_string();
+        let number_field = || simdutf8::basic::from_utf8(field).map_err(|_| invalid());
+        Ok(match func {
+            Func::Count => Acc::Count(number_field()?.parse().map_err(|_| invalid())?),
+            Func::Sum => Acc::Sum(number_field()?.parse().map_err(|_| invalid())?),
+            Func::Mean => {
+                let (sum, count) = number_field()?.split_once('/').ok_or_else(invalid)?;
+                Acc::Mean(
+                    sum.parse().map_err(|_| invalid())?,
+                    count.parse().map_err(|_| invalid())?,
+                )
+            },
+            Func::Min | Func::Max | Func::First | Func::Last => Acc::Value(field.to_vec()),
+        })
+    }
+}
+
+/// the key values of a group
+type Key = Vec<Vec<u8>>;
+
+/// the groups, sorted by key
+fn sorted_groups(groups: AHashMap<Key, Vec<Acc>>) -> Vec<(Key, Vec<Acc>)> {
+    let mut groups: Vec<(Key, Vec<Acc>)> = groups.into_iter().collect();
+    groups.sort_unstable_by(|(key1, _), (key2, _)| key1.cmp(key2));
+    groups
+}
+
+/// the sorted spill files of the groups aggregated so far
+struct Spill {
+    scratch: Option<TempDir>,
+    runs:    Vec<PathBuf>,
+}
+
+impl Spill {
+    /// write the groups to a new spill file, sorted by key, and clear them
+    fn spill(&mut self, groups: &mut AHashMap<Key, Vec<Acc>>) -> CliResult<()> {
+        if self.scratch.is_none() {
+            self.scratch = Some(util::scratch_dir()?);
+        }
+        // safety: the scratch directory was just created if needed
+        let path = self
+            .scratch
+            .as_ref()
+            .unwrap()
+            .path()
+            .join(format!("groupby-{}.csv", self.runs.len()));
+        let mut wtr = csv::Writer::from_path(&path)?;
+        let mut record = ByteRecord::new();
+        for (key, accs) in sorted_groups(std::mem::take(groups)) {
+            record.clear();
+            for value in &key {
+                record.push_field(value);
+            }
+            for acc in &accs {
+                record.push_field(&acc.to_spilled());
+            }
+            wtr.write_byte_record(&record)?;
+        }
+        wtr.flush()?;
+        self.runs.push(path);
+        Ok(())
+    }
+}
+
+/// a spill file being merged, with its next record
+struct Run {
+    rdr:  csv::Reader<File>,
+    next: Option<ByteRecord>,
+}
+
+impl Run {
+    fn advance(&mut self) -> CliResult<()> {
+        let mut record = ByteRecord::new();
+        self.next = if self.rdr.read_byte_record(&mut record)? {
+            Some(record)
+        } else {
+            None
+        };
+        Ok(())
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_spill_groups == 0 {
+        return fail_incorrectusage_clierror!("--spill-groups must be greater than 0.");
+    }
+
+    let rconfig = Config::new(&args.arg_input).delimiter(args.flag_delimiter);
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let key_sel = args.arg_keys.selection(&headers, true)?;
+    let aggs = parse_aggs(&args.flag_agg, &headers)?;
+
+    let mut groups: AHashMap<Key, Vec<Acc>> = AHashMap::new();
+    let mut spill = Spill {
+        scratch: None,
+        runs:    Vec::new(),
+    };
+    let mut record = ByteRecord::new();
+    let mut key: Key = Vec::with_capacity(key_sel.len());
+    let mut row_number = 0_u64;
+    while rdr.read_byte_record(&mut record)? {
+        row_number += 1;
+        key.clear();
+        key.extend(key_sel.select(&record).map(<[u8]>::to_vec));
+        if !groups.contains_key(&key) {
+            if groups.len() >= args.flag_spill_groups {
+                spill.spill(&mut groups)?;
+            }
+            groups.insert(
+                key.clone(),
+                aggs.iter().map(|agg| Acc::new(agg.func)).collect(),
+            );
+        }
+        // safety: the group was just inserted if needed
+        let accs = groups.get_mut(&key).unwrap();
+        for (acc, agg) in accs.iter_mut().zip(&aggs) {
+            let value = agg
+                .column
+                .map(|column| record.get(column).unwrap_or_default());
+            acc.add(agg.func, value, row_number)?;
+        }
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut out_record: ByteRecord = key_sel.select(&headers).collect();
+    for agg in &aggs {
+        out_record.push_field(agg.name.as_bytes());
+    }
+    wtr.write_byte_record(&out_record)?;
+
+    let mut write_group = |key: &[Vec<u8>], accs: &[Acc]| -> CliResult<()> {
+        out_record.clear();
+        for value in key {
+            out_record.push_field(value);
+        }
+        for acc in accs {
+            out_record.push_field(&acc.to_field(args.flag_round));
+        }
+        Ok(wtr.write_byte_record(&out_record)?)
+    };
+
+    if spill.runs.is_empty() {
+        for (key, accs) in sorted_groups(groups) {
+            write_group(&key, &accs)?;
+        }
+        drop(write_group);
+        return Ok(wtr.flush()?);
+    }
+
+    // merge the sorted spill files, the earlier ones having the earlier rows
+    if !groups.is_empty() {
+        spill.spill(&mut groups)?;
+    }
+    let mut runs = Vec::with_capacity(spill.runs.len());
+    for path in &spill.runs {
+        let mut run = Run {
+            rdr:  csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_path(path)?,
+            next: None,
+        };
+        run.advance()?;
+        runs.push(run);
+    }
+    let key_len = key_sel.len();
+    loop {
+        let min_next = runs
+            .iter()
+            .filter_map(|run| run.next.as_ref())
+            .min_by(|a, b| a.iter().take(key_len).cmp(b.iter().take(key_len)));
+        let Some(min_next) = min_next else {
+            break;
+        };
+        let key: Key = min_next.iter().take(key_len).map(<[u8]>::to_vec).collect();
+
+        let mut accs: Vec<Acc> = aggs.iter().map(|agg| Acc::new(agg.func)).collect();
+        for run in &mut runs {
+            if let Some(next) = run
+                .next
+                .take_if(|next| next.iter().take(key_len).eq(key.iter().map(Vec::as_slice)))
+            {
+                for ((acc, agg), field) in accs.iter_mut().zip(&aggs).zip(next.iter().skip(key_len))
+                {
+                    acc.merge(agg.func, Acc::from_spilled(agg.func, field)?);
+                }
+                run.advance()?;
+            }
+        }
+        write_group(&key, &accs)?;
+    }
+    drop(write_group);
+    Ok(wtr.flush()?)
+}
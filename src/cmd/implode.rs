@@ -0,0 +1,161 @@
+static USAGE: &str = r#"
+Implodes the rows of a group into a single row, by joining the values of a column with
+the given separator - the inverse of explode.
+
+For instance the following CSV:
+
+name,colors
+John,blue
+John,yellow
+Mary,red
+
+Can be imploded on the "colors" <column> with the "|" <separator> to:
+
+name,colors
+John,blue|yellow
+Mary,red
+
+The rows are grouped by the --group-by columns, or by all the other columns when not set,
+and the groups are written in the order of their first row. The columns that are neither
+grouped by nor imploded have the value of the first row of their group.
+The groups are kept in memory.
+
+Examples:
+
+  # the tags of each id, separated by ";"
+  $ qsv implode tags ";" --group-by id data.csv
+
+  # the distinct colors of each name, without the empty ones
+  $ qsv implode colors "|" --group-by name --unique --skip-empty data.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_implode.rs.
+
+Usage:
+    qsv implode [options] <column> <separator> [<input>]
+    qsv implode --help
+
+implode options:
+    -g, --group-by <cols>  The columns to group the rows by. When not set, the rows
+                           are grouped by all the columns but <column>.
+                           See 'qsv select --help' for the format details.
+    -r, --rename <name>    New name for the imploded column.
+    --unique               Only join the first occurrence of each value of a group.
+    --skip-empty           Don't join the empty values.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use csv::ByteRecord;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column:      SelectColumns,
+    arg_separator:   String,
+    arg_input:       Option<String>,
+    flag_group_by:   Option<SelectColumns>,
+    flag_rename:     Option<String>,
+    flag_unique:     bool,
+    flag_skip_empty: bool,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+/// the first row of a group, and the values of its imploded column
+struct Group {
+    first:  ByteRecord,
+    values: Vec<Vec<u8>>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    if sel.len() != 1 {
+        return fail_incorrectusage_clierror!("<column> must select a single column.");
+    }
+    let column_index = sel[0];
+    let group_cols: Vec<usize> = match args.flag_group_by {
+        Some(ref group_by) => {
+            let group_sel = group_by.selection(&headers, !rconfig.no_headers)?;
+            if group_sel.contains(&column_index) {
+                return fail_incorrectusage_clierror!(
+                    "--group-by cannot include the imploded column."
+                );
+            }
+            group_sel.to_vec()
+        },
+        None => (0..headers.len())
+            .filter(|&idx| idx != column_index)
+            .collect(),
+    };
+
+    let mut groups: IndexMap<Vec<Vec<u8>>, Group> = IndexMap::new();
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        let key: Vec<Vec<u8>> = group_cols
+            .iter()
+            .map(|&idx| record.get(idx).unwrap_or_default().to_vec())
+            .collect();
+        let group = groups.entry(key).or_insert_with(|| Group {
+            first:  record.clone(),
+            values: Vec::new(),
+        });
+        let value = record.get(column_index).unwrap_or_default();
+        if (value.is_empty() && args.flag_skip_empty)
+            || (args.flag_unique && group.values.iter().any(|v| v == value))
+        {
+            continue;
+        }
+        group.values.push(value.to_vec());
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if !rconfig.no_headers {
+        let headers: ByteRecord = headers
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| match args.flag_rename {
+                Some(ref new_name) if idx == column_index => new_name.as_bytes(),
+                _ => name,
+            })
+            .collect();
+        wtr.write_byte_record(&headers)?;
+    }
+
+    let separator = args.arg_separator.as_bytes();
+    let mut out_record = ByteRecord::new();
+    for group in groups.into_values() {
+        let imploded = group.values.join(separator);
+        out_record.clear();
+        for (idx, value) in group.first.iter().enumerate() {
+            if idx == column_index {
+                out_record.push_field(&imploded);
+            } else {
+                out_record.push_field(value);
+            }
+        }
+        wtr.write_byte_record(&out_record)?;
+    }
+    Ok(wtr.flush()?)
+}
@@ -1,5 +1,5 @@
 static USAGE: &str = r#"
-Joins two sets of CSV data on the specified columns.
+Joins two or more sets of CSV data on the specified columns.
 
 The default join operation is an 'inner' join. This corresponds to the
 intersection of rows on the keys specified.
@@ -8,10 +8,21 @@ Joins are always done by ignoring leading and trailing whitespace. By default,
 joins are done case sensitively, but this can be disabled with the --ignore-case
 flag.
 
+With --on, more than two data sets can be joined on the same columns in one go - e.g.
+'qsv join --on id a.csv b.csv c.csv' joins a.csv with b.csv, and the result with c.csv.
+Only the inner and the --left joins (outer, anti & semi) can be used with --on.
+
+The second data set is indexed in memory. For data sets larger than memory, use the
+partitions option (--partitions) to hash partition both data sets by their join columns in
+temporary files first, so only a partition of the second data set is indexed at a time.
+The joined rows are then written partition by partition, instead of in the order of the
+first data set.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_join.rs.
 
 Usage:
     qsv join [options] <columns1> <input1> <columns2> <input2>
+    qsv join [options] --on <columns> <inputs>...
     qsv join --help
 
 input arguments:
@@ -28,7 +39,10 @@ input arguments:
     For <input1> and <input2>, specifying `-` indicates reading from stdin.
     e.g. 'qsv frequency -s Agency nyc311.csv | qsv join value - id nycagencyinfo.csv'
 
+    <inputs>                are the CSV data sets to join on the --on columns, in order.
+
 join options:
+    --on <columns>         The columns to join all the <inputs> on.
     -i, --ignore-case      When set, joins are done case insensitively.
     --left                 Do a 'left outer' join. This returns all rows in
                            first CSV data set, including rows with no
@@ -41,6 +55,12 @@ join options:
     --left-semi            Do a 'left semi' join. This returns all rows in
                            first CSV data set that has a match with the 
                            second data set.
+    --right-anti           Do a 'right anti' join. This returns all rows in
+                           second CSV data set that has no match with the
+                           first data set.
+    --right-semi           Do a 'right semi' join. This returns all rows in
+                           second CSV data set that has a match with the
+                           first data set.
     --right                Do a 'right outer' join. This returns all rows in
                            second CSV data set, including rows with no
                            corresponding row in the first data set. When no
@@ -56,10 +76,31 @@ join options:
                            data sets given. The number of rows return is
                            equal to N * M, where N and M correspond to the
                            number of rows in the given data sets, respectively.
+    --asof <strategy>      Do an 'asof' join. This is a left outer join that joins
+                           each row of the first data set to the row of the second
+                           one with the closest key, instead of an equal key - e.g.
+                           to align events with the last reading of a time series.
+                           The last of the join columns is the ordered key, with
+                           numbers or dates, and the other join columns must be
+                           equal. Unlike joinp, the data sets don't need to be
+                           sorted, as the second data set is loaded in memory.
+                           The strategies are:
+                             backward - the last row whose key is less than or
+                                        equal to the key of the first data set.
+                             forward  - the first row whose key is greater than
+                                        or equal to the key of the first data set.
+                             nearest  - the row with the closest key.
+    --tolerance <arg>      The maximum difference between the keys of an asof join.
+                           A number, or for dates, a duration in seconds or with a
+                           unit: 30s, 5m, 2h or 1d.
     --nulls                When set, joins will work on empty fields.
                            Otherwise, empty fields are completely ignored.
                            (In fact, any row that has an empty field in the
                            key specified is ignored.)
+    --partitions <n>       Hash partition the data sets into <n> partitions in
+                           temporary files, and join them partition by partition,
+                           so only a partition of the second data set has to fit
+                           in memory. Not for --cross & --asof joins.
 
 Common options:
     -h, --help             Display this message
@@ -71,10 +112,19 @@ Common options:
                            Must be a single character. (default: ,)
 "#;
 
-use std::{collections::hash_map::Entry, fmt, io, iter::repeat, str};
+use std::{
+    collections::hash_map::Entry,
+    fmt, fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    iter::repeat,
+    path::{Path, PathBuf},
+    str,
+};
 
 use ahash::AHashMap;
 use byteorder::{BigEndian, WriteBytesExt};
+use qsv_dateparser::parse_with_preference;
 use serde::Deserialize;
 
 use crate::{
@@ -88,16 +138,23 @@ use crate::{
 
 #[derive(Deserialize)]
 struct Args {
-    arg_columns1:     SelectColumns,
+    arg_columns1:     String,
     arg_input1:       String,
-    arg_columns2:     SelectColumns,
+    arg_columns2:     String,
     arg_input2:       String,
+    arg_inputs:       Vec<String>,
+    flag_on:          Option<String>,
     flag_left:        bool,
     flag_left_anti:   bool,
     flag_left_semi:   bool,
     flag_right:       bool,
+    flag_right_anti:  bool,
+    flag_right_semi:  bool,
     flag_full:        bool,
     flag_cross:       bool,
+    flag_asof:        Option<String>,
+    flag_tolerance:   Option<String>,
+    flag_partitions:  Option<usize>,
     flag_output:      Option<String>,
     flag_no_headers:  bool,
     flag_ignore_case: bool,
@@ -105,47 +162,102 @@ struct Args {
     flag_delimiter:   Option<Delimiter>,
 }
 
+#[derive(Clone, Copy)]
+enum JoinType {
+    Inner,
+    Left,
+    LeftAnti,
+    LeftSemi,
+    Right,
+    RightAnti,
+    RightSemi,
+    Full,
+    Cross,
+    Asof(AsofStrategy, Option<f64>),
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
-    let mut state = args.new_io_state()?;
-    match (
-        args.flag_left,
-        args.flag_left_anti,
-        args.flag_left_semi,
-        args.flag_right,
-        args.flag_full,
-        args.flag_cross,
-    ) {
-        (true, false, false, false, false, false) => {
-            state.write_headers()?;
-            state.outer_join(false)
-        },
-        (false, true, false, false, false, false) => {
-            state.write_headers1()?;
-            state.left_join(true)
-        },
-        (false, false, true, false, false, false) => {
-            state.write_headers1()?;
-            state.left_join(false)
-        },
-        (false, false, false, true, false, false) => {
-            state.write_headers()?;
-            state.outer_join(true)
-        },
-        (false, false, false, false, true, false) => {
-            state.write_headers()?;
-            state.full_outer_join()
-        },
-        (false, false, false, false, false, true) => {
-            state.write_headers()?;
-            state.cross_join()
+    let join_type = args.join_type()?;
+
+    // the data sets to join, with their join columns
+    let inputs: Vec<(String, String)> = match args.flag_on {
+        Some(ref on) => {
+            // with four data sets, docopt matches the first usage
+            let inputs = if args.arg_inputs.is_empty() {
+                vec![
+                    args.arg_columns1.clone(),
+                    args.arg_input1.clone(),
+                    args.arg_columns2.clone(),
+                    args.arg_input2.clone(),
+                ]
+            } else {
+                args.arg_inputs.clone()
+            };
+            if inputs.len() < 2 {
+                return fail_incorrectusage_clierror!("--on requires at least two inputs.");
+            }
+            inputs
+                .into_iter()
+                .map(|input| (on.clone(), input))
+                .collect()
         },
-        (false, false, false, false, false, false) => {
-            state.write_headers()?;
-            state.inner_join()
+        None => vec![
+            (args.arg_columns1.clone(), args.arg_input1.clone()),
+            (args.arg_columns2.clone(), args.arg_input2.clone()),
+        ],
+    };
+    if inputs.len() > 2
+        && !matches!(
+            join_type,
+            JoinType::Inner | JoinType::Left | JoinType::LeftAnti | JoinType::LeftSemi
+        )
+    {
+        return fail_incorrectusage_clierror!(
+            "Only the inner, --left, --left-anti & --left-semi joins can join more than two \
+             inputs."
+        );
+    }
+    match args.flag_partitions {
+        Some(0) => return fail_incorrectusage_clierror!("--partitions must be greater than 0."),
+        Some(_) if matches!(join_type, JoinType::Cross | JoinType::Asof(..)) => {
+            return fail_incorrectusage_clierror!(
+                "--partitions cannot be used with --cross or --asof joins."
+            )
         },
-        _ => fail_incorrectusage_clierror!("Please pick exactly one join operation."),
+        _ => {},
     }
+
+    // join the data sets from left to right, the intermediate results being written to
+    // scratch files
+    let scratch = if inputs.len() > 2 {
+        Some(util::scratch_dir()?)
+    } else {
+        None
+    };
+    let (columns1, mut input1) = inputs[0].clone();
+    for (i, (columns2, input2)) in inputs.iter().enumerate().skip(1) {
+        let intermediate = scratch
+            .as_ref()
+            .filter(|_| i < inputs.len() - 1)
+            .map(|scratch| scratch.path().join(format!("join-{i}.csv")));
+        let wtr = match intermediate {
+            Some(ref path) => Config::new(&None)
+                .delimiter(args.flag_delimiter)
+                .from_writer(Box::new(fs::File::create(path)?) as Box<dyn io::Write>),
+            None => Config::new(&args.flag_output).writer()?,
+        };
+        args.join_pair(
+            join_type,
+            (columns1.as_str(), input1.as_str()),
+            (columns2.as_str(), input2.as_str()),
+            wtr,
+        )?;
+        if let Some(path) = intermediate {
+            input1 = path.to_string_lossy().into_owned();
+        }
+    }
+    Ok(())
 }
 
 struct IoState<R, W: io::Write> {
@@ -154,12 +266,92 @@ struct IoState<R, W: io::Write> {
     sel1:       Selection,
     rdr2:       csv::Reader<R>,
     sel2:       Selection,
+    /// the number of columns of the first & second data sets
+    widths:     (usize, usize),
     no_headers: bool,
     casei:      bool,
     nulls:      bool,
+    partitions: Option<usize>,
 }
 
 impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
+    fn join(mut self, join_type: JoinType) -> CliResult<()> {
+        // the right anti & semi joins are the left ones, with the data sets swapped
+        let join_type = match join_type {
+            JoinType::RightAnti => {
+                self.swap_inputs();
+                JoinType::LeftAnti
+            },
+            JoinType::RightSemi => {
+                self.swap_inputs();
+                JoinType::LeftSemi
+            },
+            join_type => join_type,
+        };
+        match join_type {
+            JoinType::LeftAnti | JoinType::LeftSemi => self.write_headers1()?,
+            _ => self.write_headers()?,
+        }
+        if let Some(partitions) = self.partitions {
+            return self.partitioned_join(join_type, partitions);
+        }
+
+        match join_type {
+            JoinType::Inner => self.inner_join(),
+            JoinType::Left => self.outer_join(false),
+            JoinType::LeftAnti | JoinType::RightAnti => self.left_join(true),
+            JoinType::LeftSemi | JoinType::RightSemi => self.left_join(false),
+            JoinType::Right => self.outer_join(true),
+            JoinType::Full => self.full_outer_join(),
+            JoinType::Cross => self.cross_join(),
+            JoinType::Asof(strategy, tolerance) => self.asof_join(strategy, tolerance),
+        }
+    }
+
+    /// hash partition both data sets by key in scratch files, and join them partition by
+    /// partition - so only a partition of the second data set is indexed in memory
+    fn partitioned_join(mut self, join_type: JoinType, partitions: usize) -> CliResult<()> {
+        let scratch = util::scratch_dir()?;
+        let paths = |side: &str| -> Vec<PathBuf> {
+            (0..partitions)
+                .map(|i| scratch.path().join(format!("{side}-{i}.csv")))
+                .collect()
+        };
+        let (paths1, paths2) = (paths("left"), paths("right"));
+        partition(&mut self.rdr1, &self.sel1, self.casei, &paths1)?;
+        partition(&mut self.rdr2, &self.sel2, self.casei, &paths2)?;
+
+        let joined_path = scratch.path().join("joined.csv");
+        let mut joined = csv::ByteRecord::new();
+        for (path1, path2) in paths1.iter().zip(&paths2) {
+            let wtr = csv::WriterBuilder::new()
+                .flexible(true)
+                .from_writer(Box::new(fs::File::create(&joined_path)?) as Box<dyn io::Write>);
+            let state = IoState {
+                wtr,
+                rdr1:       open_partition(path1)?,
+                sel1:       self.sel1.clone(),
+                rdr2:       open_partition(path2)?,
+                sel2:       self.sel2.clone(),
+                widths:     self.widths,
+                no_headers: true,
+                casei:      self.casei,
+                nulls:      self.nulls,
+                partitions: None,
+            };
+            state.join(join_type)?;
+
+            let mut rdr = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_path(&joined_path)?;
+            while rdr.read_byte_record(&mut joined)? {
+                self.wtr.write_byte_record(&joined)?;
+            }
+        }
+        Ok(self.wtr.flush()?)
+    }
+
     fn write_headers(&mut self) -> CliResult<()> {
         if !self.no_headers {
             let mut headers = self.rdr1.byte_headers()?.clone();
@@ -197,14 +389,20 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
         Ok(self.wtr.flush()?)
     }
 
+    /// swap the first & second data sets, for the right joins
+    fn swap_inputs(&mut self) {
+        ::std::mem::swap(&mut self.rdr1, &mut self.rdr2);
+        ::std::mem::swap(&mut self.sel1, &mut self.sel2);
+        self.widths = (self.widths.1, self.widths.0);
+    }
+
     fn outer_join(mut self, right: bool) -> CliResult<()> {
         if right {
-            ::std::mem::swap(&mut self.rdr1, &mut self.rdr2);
-            ::std::mem::swap(&mut self.sel1, &mut self.sel2);
+            self.swap_inputs();
         }
 
         let mut scratch = csv::ByteRecord::new();
-        let (_, pad2) = self.get_padding()?;
+        let (_, pad2) = self.get_padding();
         let mut validx = ValueIndex::new(self.rdr2, &self.sel2, self.casei, self.nulls)?;
         let mut row = csv::ByteRecord::new();
         let mut key;
@@ -249,7 +447,7 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
 
     fn full_outer_join(mut self) -> CliResult<()> {
         let mut scratch = csv::ByteRecord::new();
-        let (pad1, pad2) = self.get_padding()?;
+        let (pad1, pad2) = self.get_padding();
         let mut validx = ValueIndex::new(self.rdr2, &self.sel2, self.casei, self.nulls)?;
 
         // Keep track of which rows we've written from rdr2.
@@ -303,42 +501,171 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
         Ok(self.wtr.flush()?)
     }
 
-    fn get_padding(&mut self) -> CliResult<(csv::ByteRecord, csv::ByteRecord)> {
-        let len1 = self.rdr1.byte_headers()?.len();
-        let len2 = self.rdr2.byte_headers()?.len();
-        Ok((
-            repeat(b"").take(len1).collect(),
-            repeat(b"").take(len2).collect(),
-        ))
+    fn asof_join(mut self, strategy: AsofStrategy, tolerance: Option<f64>) -> CliResult<()> {
+        let (_, pad2) = self.get_padding();
+        // the number of join columns that must be equal, before the asof key
+        let nby = self.sel1.len() - 1;
+
+        // the rows of the second data set with the same "by" keys, sorted by asof key
+        let mut groups: AHashMap<Vec<ByteString>, Vec<(f64, csv::ByteRecord)>> = AHashMap::new();
+        let mut row = csv::ByteRecord::new();
+        while self.rdr2.read_byte_record(&mut row)? {
+            let Some(key) = self.sel2.select(&row).nth(nby).and_then(asof_key) else {
+                continue;
+            };
+            let by_key = get_row_key(&self.sel2, &row, self.casei)[..nby].to_vec();
+            if !self.nulls && by_key.iter().any(Vec::is_empty) {
+                continue;
+            }
+            groups.entry(by_key).or_default().push((key, row.clone()));
+        }
+        for rows in groups.values_mut() {
+            // a stable sort, so the rows with the same key keep their order
+            rows.sort_by(|(key1, _), (key2, _)| key1.total_cmp(key2));
+        }
+
+        while self.rdr1.read_byte_record(&mut row)? {
+            let row_key = get_row_key(&self.sel1, &row, self.casei);
+            let by_key = &row_key[..nby];
+            let matched = self
+                .sel1
+                .select(&row)
+                .nth(nby)
+                .and_then(asof_key)
+                .and_then(|key| {
+                    let rows = groups.get(by_key)?;
+                    // the last row with a key <= key, and the first one with a key >= key
+                    let backward = rows
+                        .partition_point(|(row_key, _)| *row_key <= key)
+                        .checked_sub(1)
+                        .map(|i| &rows[i]);
+                    let forward = rows.get(rows.partition_point(|(row_key, _)| *row_key < key));
+                    let closest = match strategy {
+                        AsofStrategy::Backward => backward,
+                        AsofStrategy::Forward => forward,
+                        AsofStrategy::Nearest => match (backward, forward) {
+                            (Some(backward), Some(forward)) => {
+                                if key - backward.0 <= forward.0 - key {
+                                    Some(backward)
+                                } else {
+                                    Some(forward)
+                                }
+                            },
+                            (backward, forward) => backward.or(forward),
+                        },
+                    };
+                    closest.filter(|(row_key, _)| {
+                        tolerance.map_or(true, |tolerance| (row_key - key).abs() <= tolerance)
+                    })
+                });
+            match matched {
+                Some((_, row2)) => self.wtr.write_record(row.iter().chain(row2))?,
+                None => self.wtr.write_record(row.iter().chain(&pad2))?,
+            }
+        }
+        Ok(self.wtr.flush()?)
+    }
+
+    fn get_padding(&self) -> (csv::ByteRecord, csv::ByteRecord) {
+        (
+            repeat(b"").take(self.widths.0).collect(),
+            repeat(b"").take(self.widths.1).collect(),
+        )
     }
 }
 
 impl Args {
-    fn new_io_state(
+    fn join_type(&self) -> CliResult<JoinType> {
+        if let Some(ref strategy) = self.flag_asof {
+            let strategy = match strategy.to_ascii_lowercase().as_str() {
+                "backward" => AsofStrategy::Backward,
+                "forward" => AsofStrategy::Forward,
+                "nearest" => AsofStrategy::Nearest,
+                _ => {
+                    return fail_incorrectusage_clierror!(
+                        "Invalid --asof strategy: {strategy}. Must be backward, forward or \
+                         nearest."
+                    )
+                },
+            };
+            if self.flag_left
+                || self.flag_left_anti
+                || self.flag_left_semi
+                || self.flag_right
+                || self.flag_right_anti
+                || self.flag_right_semi
+                || self.flag_full
+                || self.flag_cross
+            {
+                return fail_incorrectusage_clierror!("Please pick exactly one join operation.");
+            }
+            let tolerance = self
+                .flag_tolerance
+                .as_deref()
+                .map(parse_tolerance)
+                .transpose()?;
+            return Ok(JoinType::Asof(strategy, tolerance));
+        } else if self.flag_tolerance.is_some() {
+            return fail_incorrectusage_clierror!("--tolerance requires --asof.");
+        }
+
+        match (
+            self.flag_left,
+            self.flag_left_anti,
+            self.flag_left_semi,
+            self.flag_right,
+            self.flag_right_anti,
+            self.flag_right_semi,
+            self.flag_full,
+            self.flag_cross,
+        ) {
+            (true, false, false, false, false, false, false, false) => Ok(JoinType::Left),
+            (false, true, false, false, false, false, false, false) => Ok(JoinType::LeftAnti),
+            (false, false, true, false, false, false, false, false) => Ok(JoinType::LeftSemi),
+            (false, false, false, true, false, false, false, false) => Ok(JoinType::Right),
+            (false, false, false, false, true, false, false, false) => Ok(JoinType::RightAnti),
+            (false, false, false, false, false, true, false, false) => Ok(JoinType::RightSemi),
+            (false, false, false, false, false, false, true, false) => Ok(JoinType::Full),
+            (false, false, false, false, false, false, false, true) => Ok(JoinType::Cross),
+            (false, false, false, false, false, false, false, false) => Ok(JoinType::Inner),
+            _ => fail_incorrectusage_clierror!("Please pick exactly one join operation."),
+        }
+    }
+
+    /// join two data sets, given with their join columns
+    fn join_pair(
         &self,
-    ) -> CliResult<IoState<Box<dyn SeekRead + 'static>, Box<dyn io::Write + 'static>>> {
-        let rconf1 = Config::new(&Some(self.arg_input1.clone()))
+        join_type: JoinType,
+        (columns1, input1): (&str, &str),
+        (columns2, input2): (&str, &str),
+        wtr: csv::Writer<Box<dyn io::Write + 'static>>,
+    ) -> CliResult<()> {
+        let rconf1 = Config::new(&Some(input1.to_string()))
             .delimiter(self.flag_delimiter)
             .no_headers(self.flag_no_headers)
-            .select(self.arg_columns1.clone());
-        let rconf2 = Config::new(&Some(self.arg_input2.clone()))
+            .select(SelectColumns::parse(columns1)?);
+        let rconf2 = Config::new(&Some(input2.to_string()))
             .delimiter(self.flag_delimiter)
             .no_headers(self.flag_no_headers)
-            .select(self.arg_columns2.clone());
+            .select(SelectColumns::parse(columns2)?);
 
         let mut rdr1 = rconf1.reader_file_stdin()?;
         let mut rdr2 = rconf2.reader_file_stdin()?;
         let (sel1, sel2) = self.get_selections(&rconf1, &mut rdr1, &rconf2, &mut rdr2)?;
-        Ok(IoState {
-            wtr: Config::new(&self.flag_output).writer()?,
+        let widths = (rdr1.byte_headers()?.len(), rdr2.byte_headers()?.len());
+        let state = IoState {
+            wtr,
             rdr1,
             sel1,
             rdr2,
             sel2,
+            widths,
             no_headers: rconf1.no_headers,
             casei: self.flag_ignore_case,
             nulls: self.flag_nulls,
-        })
+            partitions: self.flag_partitions,
+        };
+        state.join(join_type)
     }
 
     #[allow(clippy::unused_self)]
@@ -454,6 +781,77 @@ impl<R> fmt::Debug for ValueIndex<R> {
     }
 }
 
+#[derive(Clone, Copy)]
+enum AsofStrategy {
+    Backward,
+    Forward,
+    Nearest,
+}
+
+/// the ordered key of an asof join - a number, or a date as seconds since the epoch
+fn asof_key(value: &[u8]) -> Option<f64> {
+    let value = simdutf8::basic::from_utf8(value).ok()?.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(number) = value.parse::<f64>() {
+        return Some(number);
+    }
+    let datetime = parse_with_preference(value, false).ok()?;
+    Some(datetime.timestamp_millis() as f64 / 1000.0)
+}
+
+/// parse the --tolerance of an asof join, e.g. 10, 30s, 5m, 2h or 1d
+fn parse_tolerance(tolerance: &str) -> CliResult<f64> {
+    let tolerance = tolerance.trim();
+    let (number, unit) = match tolerance.char_indices().last() {
+        Some((i, unit @ ('s' | 'm' | 'h' | 'd'))) => (&tolerance[..i], unit),
+        _ => (tolerance, 's'),
+    };
+    let multiplier = match unit {
+        'm' => 60.0,
+        'h' => 3_600.0,
+        'd' => 86_400.0,
+        _ => 1.0,
+    };
+    match number.parse::<f64>() {
+        Ok(number) if number >= 0.0 => Ok(number * multiplier),
+        _ => fail_incorrectusage_clierror!(
+            "Invalid --tolerance: {tolerance}. Must be a positive number or a duration, e.g. 5m."
+        ),
+    }
+}
+
+/// write the rows of a data set to the partition files of the hashes of their keys
+fn partition<R: io::Read>(
+    rdr: &mut csv::Reader<R>,
+    sel: &Selection,
+    casei: bool,
+    paths: &[PathBuf],
+) -> CliResult<()> {
+    let mut wtrs = paths
+        .iter()
+        .map(|path| csv::WriterBuilder::new().flexible(true).from_path(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut row = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut row)? {
+        let mut hasher = DefaultHasher::new();
+        get_row_key(sel, &row, casei).hash(&mut hasher);
+        wtrs[(hasher.finish() % wtrs.len() as u64) as usize].write_byte_record(&row)?;
+    }
+    for mut wtr in wtrs {
+        wtr.flush()?;
+    }
+    Ok(())
+}
+
+fn open_partition(path: &Path) -> CliResult<csv::Reader<Box<dyn SeekRead>>> {
+    Ok(csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(Box::new(fs::File::open(path)?) as Box<dyn SeekRead>))
+}
+
 #[inline]
 fn get_row_key(sel: &Selection, row: &csv::ByteRecord, casei: bool) -> Vec<ByteString> {
     sel.select(row).map(|v| util::transform(v, casei)).collect()
@@ -193,7 +193,6 @@ use std::{
 
 use polars::{datatypes::AnyValue, prelude::*, sql::SQLContext};
 use serde::Deserialize;
-use tempfile::tempdir;
 
 use crate::{cmd::sqlp::compress_output_if_needed, config::Delimiter, util, CliResult};
 
@@ -247,7 +246,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         args.flag_try_parsedates = true;
     }
 
-    let tmpdir = tempdir()?;
+    let tmpdir = util::scratch_dir()?;
     let join = args.new_join(
         args.flag_try_parsedates,
         args.flag_infer_len,
@@ -0,0 +1,187 @@
+static USAGE: &str = r#"
+Appends the values of columns from the previous (lag) or next (lead) rows - e.g. the value
+of the previous reading of each sensor, to compute changes over time.
+
+With a --shift of n, the new columns have the values of the row n rows before, or n rows
+after for a negative shift, in the same partition. They're empty for the first (or last)
+n rows of each partition. The new columns are named <column>_lag<n> or <column>_lead<n>.
+
+The rows are shifted in the input order, so time series should be sorted by time.
+With --order-by, the rows are sorted by the --order-by columns first (as numbers when
+they're numbers, and as text otherwise - so ISO 8601 dates are sorted chronologically),
+and written in that order. Sorting, as well as a negative shift, requires loading the CSV
+in memory. Otherwise, the rows are streamed.
+
+Examples:
+
+  # the previous value of each id, by timestamp
+  $ qsv lag --columns value --partition-by id --order-by ts data.csv
+
+  # the price & volume of the row 2 rows after, on a CSV already sorted by time
+  $ qsv lag --columns price,volume --shift=-2 ticks.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_lag.rs.
+
+Usage:
+    qsv lag [options] --columns <cols> [<input>]
+    qsv lag --help
+
+lag options:
+    -c, --columns <cols>       The columns whose values are shifted.
+                               See 'qsv select --help' for the format details.
+    -s, --shift <n>            The number of rows to shift the values by. A negative
+                               shift takes the values of the next rows instead.
+                               [default: 1]
+    -p, --partition-by <cols>  The columns to partition the rows by. The values are
+                               only shifted between the rows of a partition.
+    --order-by <cols>          The columns to sort the rows by before shifting them.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::{cmp::Ordering, collections::VecDeque};
+
+use ahash::AHashMap;
+use csv::ByteRecord;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::{SelectColumns, Selection},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:         Option<String>,
+    flag_columns:      SelectColumns,
+    flag_shift:        isize,
+    flag_partition_by: Option<SelectColumns>,
+    flag_order_by:     Option<SelectColumns>,
+    flag_output:       Option<String>,
+    flag_delimiter:    Option<Delimiter>,
+}
+
+/// the key values of a partition
+type Key = Vec<Vec<u8>>;
+
+/// the key of the partition of a record
+fn partition_key(partition_sel: Option<&Selection>, record: &ByteRecord) -> Key {
+    match partition_sel {
+        Some(sel) => sel.select(record).map(<[u8]>::to_vec).collect(),
+        None => Vec::new(),
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_shift == 0 {
+        return fail_incorrectusage_clierror!("--shift cannot be 0.");
+    }
+    let shift = args.flag_shift.unsigned_abs();
+
+    let rconfig = Config::new(&args.arg_input).delimiter(args.flag_delimiter);
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let column_sel = args.flag_columns.selection(&headers, true)?;
+    let partition_sel = match args.flag_partition_by {
+        Some(ref partition_by) => Some(partition_by.selection(&headers, true)?),
+        None => None,
+    };
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut out_record = headers.clone();
+    for name in column_sel.select(&headers) {
+        let name = String::from_utf8_lossy(name);
+        if args.flag_shift > 0 {
+            out_record.push_field(format!("{name}_lag{shift}").as_bytes());
+        } else {
+            out_record.push_field(format!("{name}_lead{shift}").as_bytes());
+        }
+    }
+    wtr.write_byte_record(&out_record)?;
+
+    if args.flag_order_by.is_some() || args.flag_shift < 0 {
+        let mut records = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
+        if let Some(ref order_by) = args.flag_order_by {
+            let order_sel = order_by.selection(&headers, true)?;
+            // a stable sort, so rows with the same order keep their input order
+            records.sort_by(|a, b| {
+                order_sel
+                    .select(a)
+                    .zip(order_sel.select(b))
+                    .map(|(a, b)| util::cmp_values(a, b))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        // the rows of each partition, in order
+        let mut partitions: AHashMap<Key, Vec<usize>> = AHashMap::new();
+        for (row, record) in records.iter().enumerate() {
+            partitions
+                .entry(partition_key(partition_sel.as_ref(), record))
+                .or_default()
+                .push(row);
+        }
+        // the row each row takes the shifted values from
+        let mut shifted: Vec<Option<usize>> = vec![None; records.len()];
+        for rows in partitions.values() {
+            for (pos, &row) in rows.iter().enumerate() {
+                let other = if args.flag_shift > 0 {
+                    pos.checked_sub(shift)
+                } else {
+                    Some(pos + shift).filter(|&other| other < rows.len())
+                };
+                shifted[row] = other.map(|other| rows[other]);
+            }
+        }
+
+        for (record, other) in records.iter().zip(shifted) {
+            out_record.clone_from(record);
+            match other {
+                Some(other) => {
+                    for value in column_sel.select(&records[other]) {
+                        out_record.push_field(value);
+                    }
+                },
+                None => {
+                    for _ in 0..column_sel.len() {
+                        out_record.push_field(b"");
+                    }
+                },
+            }
+            wtr.write_byte_record(&out_record)?;
+        }
+    } else {
+        // the values of the last rows of each partition
+        let mut partitions: AHashMap<Key, VecDeque<Vec<Vec<u8>>>> = AHashMap::new();
+        let mut record = ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            let window = partitions
+                .entry(partition_key(partition_sel.as_ref(), &record))
+                .or_insert_with(|| VecDeque::with_capacity(shift + 1));
+            out_record.clone_from(&record);
+            if window.len() == shift {
+                // safety: the window has shift > 0 rows
+                for value in window.front().unwrap() {
+                    out_record.push_field(value);
+                }
+            } else {
+                for _ in 0..column_sel.len() {
+                    out_record.push_field(b"");
+                }
+            }
+            window.push_back(column_sel.select(&record).map(<[u8]>::to_vec).collect());
+            if window.len() > shift {
+                window.pop_front();
+            }
+            wtr.write_byte_record(&out_record)?;
+        }
+    }
+    Ok(wtr.flush()?)
+}
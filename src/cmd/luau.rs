@@ -136,6 +136,12 @@ There are more Luau helper functions in addition to "qsv_log" - "qsv_break", "qs
 the powerful "qsv_register_lookup" which allows you to "lookup" values against other
 CSVs on the filesystem, a URL, datHere's lookup repo or CKAN instances.
 
+To score the similarity of two strings (e.g. two columns of the same row) in map & filter
+scripts, there are the "qsv_levenshtein" (edit distance), "qsv_jaro_winkler" &
+"qsv_ngram_cosine" (similarity scores from 0.0 to 1.0) and "qsv_soundex_eq" (sounds alike)
+helpers, e.g. to only keep rows whose names are similar enough:
+  qsv luau filter "qsv_jaro_winkler(name, billing_name) >= 0.9" data.csv
+
 Detailed descriptions of these helpers can be found in the "setup_helpers" section at
 the bottom of this file.
 
@@ -245,6 +251,7 @@ use std::{
     time::Instant,
 };
 
+use ahash::AHashMap;
 use csv_index::RandomAccessSimple;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
@@ -484,7 +491,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     // we do this outside the "require_used" setup below as the tempdir
     // needs to persist until the end of the program.
     let temp_dir = if require_used {
-        match tempfile::tempdir() {
+        match util::scratch_dir() {
             Ok(temp_dir) => {
                 let temp_dir_path = temp_dir.into_path();
                 Some(temp_dir_path)
@@ -1525,6 +1532,64 @@ fn setup_helpers(
     })?;
     luau.globals().set("qsv_coalesce", qsv_coalesce)?;
 
+    // this is a helper function that can be called from Luau scripts
+    // to get the Levenshtein edit distance between two strings
+    //
+    //   qsv_levenshtein(string1, string2)
+    //      returns: the minimum number of single-character insertions, deletions &
+    //               substitutions to change string1 into string2
+    //
+    let qsv_levenshtein = luau.create_function(|_, (string1, string2): (String, String)| {
+        Ok(strsim::levenshtein(&string1, &string2))
+    })?;
+    luau.globals().set("qsv_levenshtein", qsv_levenshtein)?;
+
+    // this is a helper function that can be called from Luau scripts
+    // to get the Jaro-Winkler similarity of two strings, which favors common prefixes
+    //
+    //   qsv_jaro_winkler(string1, string2)
+    //      returns: a similarity score from 0.0 (no similarity) to 1.0 (identical)
+    //
+    let qsv_jaro_winkler = luau.create_function(|_, (string1, string2): (String, String)| {
+        Ok(strsim::jaro_winkler(&string1, &string2))
+    })?;
+    luau.globals().set("qsv_jaro_winkler", qsv_jaro_winkler)?;
+
+    // this is a helper function that can be called from Luau scripts
+    // to get the cosine similarity of the character n-grams of two strings (case-insensitive),
+    // which is not sensitive to word order
+    //
+    //   qsv_ngram_cosine(string1, string2, n)
+    //            n: the number of characters of the n-grams. Optional, defaults to 2 (bigrams)
+    //      returns: a similarity score from 0.0 (no n-grams in common) to 1.0 (same n-grams)
+    //               or Luau runtime error if n is 0
+    //
+    let qsv_ngram_cosine = luau.create_function(
+        |_, (string1, string2, n): (String, String, Option<usize>)| {
+            let n = n.unwrap_or(2);
+            if n == 0 {
+                return helper_err!("qsv_ngram_cosine", "n must be greater than 0.");
+            }
+            Ok(ngram_cosine(&string1, &string2, n))
+        },
+    )?;
+    luau.globals().set("qsv_ngram_cosine", qsv_ngram_cosine)?;
+
+    // this is a helper function that can be called from Luau scripts
+    // to check if two strings sound alike in English, using their American Soundex codes
+    //
+    //   qsv_soundex_eq(string1, string2)
+    //      returns: true if both strings have the same Soundex code,
+    //               false otherwise or if either string has no letters
+    //
+    let qsv_soundex_eq = luau.create_function(|_, (string1, string2): (String, String)| {
+        Ok(match (soundex(&string1), soundex(&string2)) {
+            (Some(code1), Some(code2)) => code1 == code2,
+            _ => false,
+        })
+    })?;
+    luau.globals().set("qsv_soundex_eq", qsv_soundex_eq)?;
+
     // this is a helper function that can be called from the BEGIN and MAIN script
     // to stop processing. All the parameters are concatenated and returned as a string.
     // The string is also stored in the global variable _QSV_BRKMSG.
@@ -2397,3 +2462,91 @@ fn setup_helpers(
 
     Ok(())
 }
+
+/// the cosine similarity of the counts of the character n-grams of two strings.
+/// A string shorter than n is a single n-gram.
+fn ngram_cosine(string1: &str, string2: &str, n: usize) -> f64 {
+    fn ngram_counts(string: &str, n: usize) -> AHashMap<Vec<char>, u32> {
+        let chars: Vec<char> = string.to_lowercase().chars().collect();
+        let mut counts = AHashMap::new();
+        if chars.len() < n {
+            if !chars.is_empty() {
+                counts.insert(chars, 1);
+            }
+        } else {
+            for ngram in chars.windows(n) {
+                *counts.entry(ngram.to_vec()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    if string1 == string2 {
+        return 1.0;
+    }
+    let counts1 = ngram_counts(string1, n);
+    let counts2 = ngram_counts(string2, n);
+    if counts1.is_empty() || counts2.is_empty() {
+        return 0.0;
+    }
+    let dot_product: f64 = counts1
+        .iter()
+        .filter_map(|(ngram, count1)| {
+            counts2
+                .get(ngram)
+                .map(|count2| f64::from(*count1) * f64::from(*count2))
+        })
+        .sum();
+    let norm = |counts: &AHashMap<Vec<char>, u32>| {
+        counts
+            .values()
+            .map(|count| f64::from(*count).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    };
+    dot_product / (norm(&counts1) * norm(&counts2))
+}
+
+/// the American Soundex code of a string, e.g. "R163" for "Robert" & "Rupert",
+/// ignoring its non-ASCII letters. None if it has no letters.
+fn soundex(string: &str) -> Option<String> {
+    // the digit of a letter, '0' for vowels (which separate letters with the same digit)
+    // and None for H & W (which don't)
+    fn digit(letter: char) -> Option<char> {
+        match letter {
+            'B' | 'F' | 'P' | 'V' => Some('1'),
+            'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some('2'),
+            'D' | 'T' => Some('3'),
+            'L' => Some('4'),
+            'M' | 'N' => Some('5'),
+            'R' => Some('6'),
+            'H' | 'W' => None,
+            _ => Some('0'),
+        }
+    }
+
+    let mut letters = string
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase());
+    let first_letter = letters.next()?;
+    let mut code = String::with_capacity(4);
+    code.push(first_letter);
+    let mut last_digit = digit(first_letter).unwrap_or('0');
+    for letter in letters {
+        let Some(letter_digit) = digit(letter) else {
+            continue;
+        };
+        if letter_digit != '0' && letter_digit != last_digit {
+            code.push(letter_digit);
+            if code.len() == 4 {
+                break;
+            }
+        }
+        last_digit = letter_digit;
+    }
+    while code.len() < 4 {
+        code.push('0');
+    }
+    Some(code)
+}
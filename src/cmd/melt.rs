@@ -0,0 +1,109 @@
+static USAGE: &str = r#"
+Reshapes wide data to long - the inverse of pivot: each row is split into a row per column
+of --value-vars, with the --id-vars columns, the name of the column in a variable
+column and its value in a value column. The rows are streamed, so the input can be
+of any size.
+
+For example, with --id-vars region:
+
+    region,2023,2024          region,variable,value
+    east,15,7          =>     east,2023,15
+    west,,3                   east,2024,7
+                              west,2023,
+                              west,2024,3
+
+Examples:
+
+  # unpivot all the columns but the id columns
+  $ qsv melt --id-vars region sales.csv
+
+  # unpivot some columns, with custom names for the variable & value columns
+  $ qsv melt -i id,name -v q1,q2,q3,q4 --variable-name quarter --value-name revenue data.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_melt.rs.
+
+Usage:
+    qsv melt [options] --id-vars <cols> [<input>]
+    qsv melt --help
+
+melt options:
+    -i, --id-vars <cols>        The columns that identify a row, repeated in each of its
+                                output rows.
+                                See 'qsv select --help' for the format details.
+    -v, --value-vars <cols>     The columns to unpivot. When not set, all the columns
+                                that are not --id-vars are unpivoted.
+    --variable-name <name>      The name of the variable column.
+                                [default: variable]
+    --value-name <name>         The name of the value column.
+                                [default: value]
+    --skip-empty                Don't write the rows of empty values.
+
+Common options:
+    -h, --help                  Display this message
+    -o, --output <file>         Write output to <file> instead of stdout.
+    -d, --delimiter <arg>       The field delimiter for reading CSV data.
+                                Must be a single character. (default: ,)
+"#;
+
+use csv::ByteRecord;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:          Option<String>,
+    flag_id_vars:       SelectColumns,
+    flag_value_vars:    Option<SelectColumns>,
+    flag_variable_name: String,
+    flag_value_name:    String,
+    flag_skip_empty:    bool,
+    flag_output:        Option<String>,
+    flag_delimiter:     Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconfig = Config::new(&args.arg_input).delimiter(args.flag_delimiter);
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let id_sel = args.flag_id_vars.selection(&headers, true)?;
+    let value_cols: Vec<usize> = match args.flag_value_vars {
+        Some(ref value_vars) => value_vars.selection(&headers, true)?.to_vec(),
+        None => (0..headers.len())
+            .filter(|idx| !id_sel.contains(idx))
+            .collect(),
+    };
+    if value_cols.is_empty() {
+        return fail_incorrectusage_clierror!("There are no columns to unpivot.");
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut out_record: ByteRecord = id_sel.select(&headers).collect();
+    out_record.push_field(args.flag_variable_name.as_bytes());
+    out_record.push_field(args.flag_value_name.as_bytes());
+    wtr.write_byte_record(&out_record)?;
+
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        for &idx in &value_cols {
+            let value = record.get(idx).unwrap_or_default();
+            if value.is_empty() && args.flag_skip_empty {
+                continue;
+            }
+            out_record.clear();
+            for id_value in id_sel.select(&record) {
+                out_record.push_field(id_value);
+            }
+            out_record.push_field(&headers[idx]);
+            out_record.push_field(value);
+            wtr.write_byte_record(&out_record)?;
+        }
+    }
+    Ok(wtr.flush()?)
+}
@@ -5,12 +5,24 @@ pub mod applydp;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod behead;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod bin;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod cat;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod cleanup;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod clipboard;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod colops;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod corr;
 pub mod count;
 pub mod datefmt;
 pub mod dedup;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod dedupstream;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod delta;
 pub mod describegpt;
 pub mod diff;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
@@ -36,12 +48,30 @@ pub mod fixlengths;
 pub mod flatten;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod fmt;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod follow;
 #[cfg(all(feature = "foreach", not(feature = "lite")))]
 pub mod foreach;
 pub mod frequency;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+pub mod fromarrow;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+pub mod fromavro;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod fromgeojson;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+pub mod fromparquet;
+#[cfg(all(feature = "to", feature = "feature_capable"))]
+pub mod fromsqlite;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod fwf;
 #[cfg(all(feature = "geocode", feature = "feature_capable"))]
 pub mod geocode;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod groupby;
 pub mod headers;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod implode;
 pub mod index;
 pub mod input;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
@@ -52,16 +82,32 @@ pub mod joinp;
 pub mod json;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod jsonl;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod lag;
 #[cfg(feature = "luau")]
 pub mod luau;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod melt;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod numformat;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod onehot;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod package;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod partition;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod pivot;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod prompt;
 pub mod pseudo;
 #[cfg(all(feature = "python", feature = "feature_capable"))]
 pub mod python;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod rank;
 pub mod rename;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod repl;
 pub mod replace;
 pub mod reverse;
 pub mod safenames;
@@ -71,6 +117,10 @@ pub mod schema;
 pub mod search;
 pub mod searchset;
 pub mod select;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod shareprep;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod sizeest;
 pub mod slice;
 pub mod snappy;
 pub mod sniff;
@@ -85,8 +135,30 @@ pub mod stats;
 pub mod table;
 #[cfg(all(feature = "to", feature = "feature_capable"))]
 pub mod to;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+pub mod toarrow;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+pub mod toavro;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod togeojson;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod tojsonl;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod topn;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+pub mod toparquet;
+#[cfg(all(feature = "to", feature = "feature_capable"))]
+pub mod topostgres;
+#[cfg(all(feature = "to", feature = "feature_capable"))]
+pub mod tosqlite;
+#[cfg(all(feature = "to", feature = "feature_capable"))]
+pub mod toxlsx;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod transform;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod transpose;
 pub mod validate;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod window;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod xml;
@@ -0,0 +1,270 @@
+static USAGE: &str = r#"
+Formats the numbers in CSV data for presentation - rounding to a fixed number of
+decimal places or significant figures, adding thousands separators and padding the
+numbers of each column so their decimal points line up.
+
+Only the values that are numbers (e.g. 1234, -0.5, 1.2e3) are formatted. Other values,
+including empty values, are left as is.
+
+Rounding uses the Midpoint Nearest Even Rounding Strategy AKA "Bankers Rounding",
+the same as the `apply operations round` command.
+
+Examples:
+
+  # round the price and tax columns to 2 decimal places, e.g. 3.14159 -> 3.14 and 2 -> 2.00
+  $ qsv numformat --select price,tax --decimals 2 sales.csv
+
+  # round all numbers to 3 significant figures, e.g. 123456 -> 123000 and 0.012345 -> 0.0123
+  $ qsv numformat --sig-figs 3 measurements.csv
+
+  # add thousands separators, e.g. 1234567.8 -> 1,234,567.8
+  $ qsv numformat --thousands , population.csv
+
+  # a presentation-ready table, with the decimal points of each column lined up
+  $ qsv numformat --decimals 2 --thousands , --pad-align report.csv | qsv table
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_numformat.rs.
+
+Usage:
+    qsv numformat [options] [<input>]
+    qsv numformat --help
+
+numformat options:
+    -s, --select <arg>     Select the columns to format. See 'qsv select --help'
+                           for the format details. (default: all the columns)
+    --decimals <n>         Round to <n> decimal places, padding with trailing
+                           zeros so every number has exactly <n> decimals.
+                           Must be between 0 and 28.
+    --sig-figs <n>         Round to <n> significant figures, keeping trailing
+                           zeros (e.g. 1.5 -> 1.50 with 3 significant figures).
+                           Cannot be used with --decimals.
+    --thousands <sep>      Group the digits of the integer part in thousands,
+                           separated by <sep>.
+    --pad-align            Pad the numbers of each selected column with spaces so
+                           that their decimal points line up. This reads all the
+                           data into memory.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_select:     SelectColumns,
+    flag_decimals:   Option<u32>,
+    flag_sig_figs:   Option<u32>,
+    flag_thousands:  Option<String>,
+    flag_pad_align:  bool,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+// the maximum number of decimal places of a rust_decimal Decimal
+const MAX_DECIMALS: u32 = 28;
+
+enum Rounding {
+    None,
+    Decimals(u32),
+    SigFigs(u32),
+}
+
+struct NumFormat {
+    rounding:  Rounding,
+    thousands: String,
+}
+
+impl NumFormat {
+    /// format a value, returning None if it's not a number. Also returns the width of the
+    /// integer part (including the sign and the thousands separators) for --pad-align.
+    fn format(&self, value: &str) -> Option<(String, usize)> {
+        let value = value.trim();
+        let mut num = Decimal::from_str(value)
+            .or_else(|_| Decimal::from_scientific(value))
+            .ok()?;
+
+        match self.rounding {
+            Rounding::None => {},
+            Rounding::Decimals(places) => {
+                num = num.round_dp_with_strategy(places, RoundingStrategy::MidpointNearestEven);
+                num.rescale(places);
+            },
+            Rounding::SigFigs(digits) => {
+                num = num.round_sf_with_strategy(digits, RoundingStrategy::MidpointNearestEven)?;
+                // keep the trailing zeros that are significant, e.g. 1.50 for 3 sig figs
+                let mantissa_digits = num.mantissa().unsigned_abs().to_string().len() as i64;
+                let exponent = mantissa_digits - 1 - i64::from(num.scale());
+                let places = (i64::from(digits) - 1 - exponent).max(0) as u32;
+                num.rescale(places.min(MAX_DECIMALS));
+            },
+        }
+        // don't write -0.00 when a small negative number is rounded to zero
+        if num.is_zero() {
+            num.set_sign_positive(true);
+        }
+
+        let formatted = num.to_string();
+        let (sign, unsigned) = match formatted.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", formatted.as_str()),
+        };
+        let (int_part, frac_part) = match unsigned.find('.') {
+            Some(dot) => unsigned.split_at(dot),
+            None => (unsigned, ""),
+        };
+        let int_part = if self.thousands.is_empty() {
+            int_part.to_string()
+        } else {
+            group_thousands(int_part, &self.thousands)
+        };
+        let int_width = sign.len() + int_part.chars().count();
+        Some((format!("{sign}{int_part}{frac_part}"), int_width))
+    }
+}
+
+/// insert `sep` between each group of three digits, from the right
+fn group_thousands(digits: &str, sep: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * sep.len());
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % 3 == 0 {
+            grouped.push_str(sep);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// the widths of the integer and fractional parts of the numbers of a column
+#[derive(Default, Clone, Copy)]
+struct Alignment {
+    int_width:  usize,
+    frac_width: usize,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rounding = match (args.flag_decimals, args.flag_sig_figs) {
+        (Some(_), Some(_)) => {
+            return fail_incorrectusage_clierror!(
+                "--decimals and --sig-figs cannot be used together."
+            )
+        },
+        (Some(places), None) if places > MAX_DECIMALS => {
+            return fail_incorrectusage_clierror!(
+                "--decimals must be between 0 and {MAX_DECIMALS}."
+            )
+        },
+        (Some(places), None) => Rounding::Decimals(places),
+        (None, Some(0)) => {
+            return fail_incorrectusage_clierror!("--sig-figs must be greater than 0.")
+        },
+        (None, Some(digits)) => Rounding::SigFigs(digits),
+        (None, None) => Rounding::None,
+    };
+    let numformat = NumFormat {
+        rounding,
+        thousands: args.flag_thousands.unwrap_or_default(),
+    };
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let mut selected = vec![false; headers.len()];
+    for &idx in sel.iter() {
+        selected[idx] = true;
+    }
+
+    if !rconfig.no_headers {
+        wtr.write_record(&headers)?;
+    }
+
+    // format a record, returning the (integer part, fractional part) widths of its numbers
+    let format_record =
+        |record: &csv::StringRecord| -> (csv::StringRecord, Vec<Option<(usize, usize)>>) {
+            let mut formatted =
+                csv::StringRecord::with_capacity(record.as_slice().len(), record.len());
+            let mut widths = vec![None; record.len()];
+            for (idx, field) in record.iter().enumerate() {
+                let number = if selected.get(idx).copied().unwrap_or_default() {
+                    numformat.format(field)
+                } else {
+                    None
+                };
+                match number {
+                    Some((number, int_width)) => {
+                        widths[idx] = Some((int_width, number.chars().count() - int_width));
+                        formatted.push_field(&number);
+                    },
+                    None => formatted.push_field(field),
+                }
+            }
+            (formatted, widths)
+        };
+
+    let mut record = csv::StringRecord::new();
+    if !args.flag_pad_align {
+        while rdr.read_record(&mut record)? {
+            wtr.write_record(&format_record(&record).0)?;
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    // with --pad-align, we need the widths of all the numbers of a column before writing
+    let mut formatted_records = Vec::new();
+    let mut alignments = vec![Alignment::default(); headers.len()];
+    while rdr.read_record(&mut record)? {
+        let (formatted, widths) = format_record(&record);
+        for (idx, width) in widths.iter().enumerate() {
+            if let Some((int_width, frac_width)) = *width {
+                let alignment = &mut alignments[idx];
+                alignment.int_width = alignment.int_width.max(int_width);
+                alignment.frac_width = alignment.frac_width.max(frac_width);
+            }
+        }
+        formatted_records.push((formatted, widths));
+    }
+
+    let mut padded = csv::StringRecord::new();
+    for (formatted, widths) in formatted_records {
+        padded.clear();
+        for (idx, field) in formatted.iter().enumerate() {
+            if let Some((int_width, frac_width)) = widths[idx] {
+                let alignment = alignments[idx];
+                padded.push_field(&format!(
+                    "{}{field}{}",
+                    " ".repeat(alignment.int_width - int_width),
+                    " ".repeat(alignment.frac_width - frac_width)
+                ));
+            } else {
+                padded.push_field(field);
+            }
+        }
+        wtr.write_record(&padded)?;
+    }
+    Ok(wtr.flush()?)
+}
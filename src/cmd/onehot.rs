@@ -0,0 +1,197 @@
+static USAGE: &str = r#"
+One-hot encodes categorical columns - i.e. replaces each selected column with a 0/1
+indicator column per category, as expected by most ML tooling.
+
+For instance, the following CSV:
+
+id,color
+1,red
+2,blue
+3,red
+
+Is encoded on the "color" column to:
+
+id,color_red,color_blue
+1,1,0
+2,0,1
+3,1,0
+
+The categories of each column are its --top-k most frequent values, from the most frequent
+one down (ties being sorted by value), found in a first pass over the CSV (stdin being
+spooled to a scratch file). The rows with a value that isn't one of the categories, or an
+empty value, have 0 in all the indicator columns of the column. The indicator columns are
+named <column>_<value>.
+
+Examples:
+
+  # encode the color & size columns
+  $ qsv onehot color,size data.csv
+
+  # encode the 5 most frequent countries, dropping the most frequent one
+  $ qsv onehot country --top-k 5 --drop-first data.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_onehot.rs.
+
+Usage:
+    qsv onehot [options] <selection> [<input>]
+    qsv onehot --help
+
+onehot arguments:
+    <selection>            The categorical columns to encode.
+                           See 'qsv select --help' for the format details.
+
+onehot options:
+    -k, --top-k <n>        The maximum number of categories of each column, i.e. of
+                           indicator columns. 0 for all the distinct values.
+                           [default: 20]
+    --drop-first           Drop the indicator column of the most frequent category of
+                           each column, e.g. to avoid collinearity in linear models.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use ahash::AHashMap;
+use csv::ByteRecord;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_selection:   SelectColumns,
+    arg_input:       Option<String>,
+    flag_top_k:      usize,
+    flag_drop_first: bool,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+/// the categories of an encoded column, i.e. its indicator columns
+struct Categories {
+    values: Vec<Vec<u8>>,
+    /// the index of each category in values
+    index:  AHashMap<Vec<u8>, usize>,
+}
+
+impl Categories {
+    /// the --top-k most frequent values, from the most frequent one down
+    fn from_counts(counts: AHashMap<Vec<u8>, u64>, top_k: usize, drop_first: bool) -> Self {
+        let mut counts: Vec<(Vec<u8>, u64)> = counts.into_iter().collect();
+        counts.sort_unstable_by(|(a, a_count), (b, b_count)| {
+            b_count.cmp(a_count).then_with(|| a.cmp(b))
+        });
+        if top_k > 0 {
+            counts.truncate(top_k);
+        }
+        let values: Vec<Vec<u8>> = counts
+            .into_iter()
+            .skip(usize::from(drop_first))
+            .map(|(value, _)| value)
+            .collect();
+        let index = values
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| (value.clone(), idx))
+            .collect();
+        Categories { values, index }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let mut rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_selection.clone());
+
+    // the categories of stdin are found in a first pass, so it's spooled
+    let _stdin_spool = if rconfig.is_stdin() {
+        let (spooled_rconfig, spool_dir) = util::spool_stdin(&rconfig)?;
+        rconfig = spooled_rconfig;
+        Some(spool_dir)
+    } else {
+        None
+    };
+
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let mut columns: Vec<usize> = sel.iter().copied().collect();
+    columns.sort_unstable();
+    columns.dedup();
+
+    // the first pass, counting the values of the encoded columns
+    let mut counts: Vec<AHashMap<Vec<u8>, u64>> = vec![AHashMap::new(); columns.len()];
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        for (counts, &column) in counts.iter_mut().zip(&columns) {
+            let value = record.get(column).unwrap_or_default();
+            if value.is_empty() {
+                continue;
+            }
+            match counts.get_mut(value) {
+                Some(count) => *count += 1,
+                None => {
+                    counts.insert(value.to_vec(), 1);
+                },
+            }
+        }
+    }
+    let categories: Vec<Categories> = counts
+        .into_iter()
+        .map(|counts| Categories::from_counts(counts, args.flag_top_k, args.flag_drop_first))
+        .collect();
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut out_record = ByteRecord::new();
+    if !rconfig.no_headers {
+        for (idx, name) in headers.iter().enumerate() {
+            match columns.binary_search(&idx) {
+                Ok(pos) => {
+                    for value in &categories[pos].values {
+                        let mut indicator = name.to_vec();
+                        indicator.push(b'_');
+                        indicator.extend_from_slice(value);
+                        out_record.push_field(&indicator);
+                    }
+                },
+                Err(_) => out_record.push_field(name),
+            }
+        }
+        wtr.write_byte_record(&out_record)?;
+    }
+
+    let mut rdr = rconfig.reader()?;
+    while rdr.read_byte_record(&mut record)? {
+        out_record.clear();
+        for (idx, field) in record.iter().enumerate() {
+            match columns.binary_search(&idx) {
+                Ok(pos) => {
+                    let category = categories[pos].index.get(field);
+                    for cat_idx in 0..categories[pos].values.len() {
+                        if category == Some(&cat_idx) {
+                            out_record.push_field(b"1");
+                        } else {
+                            out_record.push_field(b"0");
+                        }
+                    }
+                },
+                Err(_) => out_record.push_field(field),
+            }
+        }
+        wtr.write_byte_record(&out_record)?;
+    }
+    Ok(wtr.flush()?)
+}
@@ -0,0 +1,539 @@
+static USAGE: &str = r#"
+Creates & verifies Frictionless Data Package manifests (datapackage.json) for a directory
+of CSV files, to formalize multi-file data deliveries.
+See https://specs.frictionlessdata.io/data-package/
+
+It has two subcommands:
+    create:  Scan the CSV files in <dir> and its subdirectories, and write a
+             <dir>/datapackage.json manifest with a tabular data resource per file -
+             its path, size, SHA-256 hash, row count and a Table Schema of its fields
+             with their inferred types. Fields without empty values are marked required.
+    verify:  Re-check the resources of a datapackage.json against the files - that they
+             exist, and that their size, hash, row count and fields still match, with
+             every value complying with its field's type & required constraint.
+             A summary CSV with the resource, path, rows, status & errors columns is
+             sent to stdout. Returns exit code 1 if any resource fails verification.
+
+The inferred field types are integer, number, date, datetime & string. Only fields
+whose values all have the same type get that type, otherwise they're strings.
+Date & datetime types are only inferred with --infer-dates (see QSV_PREFER_DMY for
+ambiguous dates).
+
+The CSV files are the files with a csv, tsv, tab or ssv extension. Files & directories
+whose names start with a dot are skipped. The delimiter is inferred from the extension,
+and written in the resource's dialect when it's not a comma.
+
+Examples:
+
+  # create the manifest of a delivery
+  $ qsv package create --name q3-sales deliveries/q3
+
+  # verify the delivery when it's received
+  $ qsv package verify deliveries/q3/datapackage.json
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_package.rs.
+
+Usage:
+    qsv package create [options] <dir>
+    qsv package verify [options] <datapackage>
+    qsv package --help
+
+package arguments:
+    <dir>                  The directory of CSV files to package.
+    <datapackage>          The datapackage.json to verify, or its directory.
+
+create options:
+    --name <name>          The name of the data package. Lowercased, with characters
+                           other than letters, digits, ".", "_" & "-" replaced by "-".
+                           (default: the name of <dir>)
+    --infer-dates          Infer date & datetime fields.
+    --force                Overwrite an existing datapackage.json.
+
+Common options:
+    -h, --help             Display this message
+    -j, --jobs <arg>       The number of files to process in parallel.
+                           When not set, the number of jobs is set to the
+                           number of CPUs detected.
+"#;
+
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use ahash::AHashSet;
+use chrono::{SecondsFormat, Utc};
+use csv::ByteRecord;
+use qsv_dateparser::parse_with_preference;
+use rayon::prelude::*;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use stats::Commute;
+
+use crate::{
+    cmd::stats::FieldType,
+    config::{Config, Delimiter},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    cmd_create:       bool,
+    cmd_verify:       bool,
+    arg_dir:          Option<String>,
+    arg_datapackage:  Option<String>,
+    flag_name:        Option<String>,
+    flag_infer_dates: bool,
+    flag_force:       bool,
+    flag_jobs:        Option<usize>,
+}
+
+const DATAPACKAGE_FILE: &str = "datapackage.json";
+const CSV_EXTENSIONS: [&str; 4] = ["csv", "tsv", "tab", "ssv"];
+
+/// the CSV files in dir and its subdirectories, relative to dir and sorted
+fn find_csv_files(dir: &Path) -> CliResult<Vec<PathBuf>> {
+    let mut csv_files = Vec::new();
+    let mut dirs = vec![PathBuf::new()];
+    while let Some(rel_dir) = dirs.pop() {
+        for entry in fs::read_dir(dir.join(&rel_dir))? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let rel_path = rel_dir.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                dirs.push(rel_path);
+            } else if rel_path
+                .extension()
+                .and_then(std::ffi::OsStr::to_str)
+                .is_some_and(|ext| CSV_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+            {
+                csv_files.push(rel_path);
+            }
+        }
+    }
+    csv_files.sort_unstable();
+    Ok(csv_files)
+}
+
+/// a Frictionless name - lowercase, with only letters, digits, ".", "_" & "-"
+pub fn frictionless_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// the path of a resource in the manifest, always with / separators
+pub fn resource_path(rel_path: &Path) -> String {
+    rel_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// a reader computing the SHA-256 hash & size of everything read through it
+struct HashingReader<R> {
+    inner:  R,
+    hasher: Sha256,
+    bytes:  u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            bytes: 0,
+        }
+    }
+
+    fn finish(self) -> (u64, String) {
+        (self.bytes, format!("{:x}", self.hasher.finalize()))
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..bytes_read]);
+        self.bytes += bytes_read as u64;
+        Ok(bytes_read)
+    }
+}
+
+/// the Table Schema type of an inferred field type
+const fn table_schema_type(field_type: FieldType) -> &'static str {
+    match field_type {
+        FieldType::TInteger => "integer",
+        FieldType::TFloat => "number",
+        FieldType::TDate => "date",
+        FieldType::TDateTime => "datetime",
+        FieldType::TString | FieldType::TNull => "string",
+    }
+}
+
+/// whether a non-empty value complies with a Table Schema type
+fn complies(table_type: &str, value: &[u8], prefer_dmy: bool) -> bool {
+    let Ok(value) = simdutf8::basic::from_utf8(value) else {
+        return table_type == "string" || table_type == "any";
+    };
+    match table_type {
+        "integer" => atoi_simd::parse::<i64>(value.as_bytes()).is_ok(),
+        "number" => value.parse::<f64>().is_ok(),
+        "boolean" => matches!(
+            value.to_ascii_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "1" | "0"
+        ),
+        "date" | "datetime" => parse_with_preference(value, prefer_dmy).is_ok(),
+        _ => true,
+    }
+}
+
+/// the reader of a CSV file, hashing it while it's read
+fn csv_reader(
+    path: &Path,
+    delimiter: Option<Delimiter>,
+) -> CliResult<(csv::Reader<HashingReader<File>>, u8)> {
+    let rconfig = Config::new(&Some(path.to_string_lossy().into_owned())).delimiter(delimiter);
+    let rdr = rconfig.from_reader(HashingReader::new(File::open(path)?));
+    Ok((rdr, rconfig.get_delimiter()))
+}
+
+/// create the tabular data resource of a CSV file, scanning it in a single pass
+fn create_resource(
+    dir: &Path,
+    rel_path: &Path,
+    infer_dates: bool,
+    prefer_dmy: bool,
+) -> CliResult<Value> {
+    let (mut rdr, delimiter) = csv_reader(&dir.join(rel_path), None)?;
+    let headers = rdr.byte_headers()?.clone();
+    let mut field_types = vec![FieldType::TNull; headers.len()];
+    let mut required = vec![true; headers.len()];
+
+    let mut rows: u64 = 0;
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        rows += 1;
+        for (idx, value) in record.iter().enumerate().take(headers.len()) {
+            if value.is_empty() {
+                required[idx] = false;
+                continue;
+            }
+            let (value_type, _) =
+                FieldType::from_sample(infer_dates, prefer_dmy, value, field_types[idx]);
+            field_types[idx].merge(value_type);
+        }
+    }
+    let (bytes, hash) = rdr.into_inner().finish();
+
+    let fields: Vec<Value> = headers
+        .iter()
+        .zip(field_types)
+        .zip(required)
+        .map(|((header, field_type), required)| {
+            let mut field = json!({
+                "name": String::from_utf8_lossy(header),
+                "type": table_schema_type(field_type),
+            });
+            if required && rows > 0 {
+                field["constraints"] = json!({"required": true});
+            }
+            field
+        })
+        .collect();
+
+    let extension = rel_path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let mediatype = if delimiter == b'\t' {
+        "text/tab-separated-values"
+    } else {
+        "text/csv"
+    };
+    let mut resource = json!({
+        "name": frictionless_name(&resource_path(&rel_path.with_extension(""))),
+        "path": resource_path(rel_path),
+        "profile": "tabular-data-resource",
+        "format": extension,
+        "mediatype": mediatype,
+        "encoding": "utf-8",
+        "bytes": bytes,
+        "hash": format!("sha256:{hash}"),
+        "schema": {"fields": fields},
+        "stats": {"rows": rows},
+    });
+    if delimiter != b',' {
+        resource["dialect"] = json!({"delimiter": (delimiter as char).to_string()});
+    }
+    Ok(resource)
+}
+
+fn create(args: &Args, dir: &Path) -> CliResult<()> {
+    if !dir.is_dir() {
+        return fail_incorrectusage_clierror!("{} is not a directory.", dir.display());
+    }
+    let package_path = dir.join(DATAPACKAGE_FILE);
+    if package_path.exists() && !args.flag_force {
+        return fail_incorrectusage_clierror!(
+            "{} already exists. Use --force to overwrite it.",
+            package_path.display()
+        );
+    }
+
+    let csv_files = find_csv_files(dir)?;
+    if csv_files.is_empty() {
+        return fail_clierror!("No CSV files found in {}.", dir.display());
+    }
+
+    let prefer_dmy = util::get_envvar_flag("QSV_PREFER_DMY");
+    let resources: Vec<CliResult<Value>> = csv_files
+        .par_iter()
+        .map(|rel_path| create_resource(dir, rel_path, args.flag_infer_dates, prefer_dmy))
+        .collect();
+
+    // resource names must be unique in a package
+    let mut names = AHashSet::new();
+    let mut package_resources = Vec::with_capacity(resources.len());
+    for (rel_path, resource) in csv_files.iter().zip(resources) {
+        let mut resource = match resource {
+            Ok(resource) => resource,
+            Err(e) => return fail_clierror!("Cannot scan {}: {e}", rel_path.display()),
+        };
+        let base_name = resource["name"].as_str().unwrap_or_default().to_string();
+        let mut name = base_name.clone();
+        let mut suffix = 1;
+        while !names.insert(name.clone()) {
+            suffix += 1;
+            name = format!("{base_name}-{suffix}");
+        }
+        resource["name"] = json!(name);
+        package_resources.push(resource);
+    }
+
+    let package_name = match args.flag_name {
+        Some(ref name) => name.clone(),
+        None => fs::canonicalize(dir)?
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "datapackage".to_string()),
+    };
+    let package = json!({
+        "profile": "tabular-data-package",
+        "name": frictionless_name(&package_name),
+        "created": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        "resources": package_resources,
+    });
+    fs::write(
+        &package_path,
+        serde_json::to_string_pretty(&package)? + "\n",
+    )?;
+
+    winfo!(
+        "Wrote {} with {} resources.",
+        package_path.display(),
+        package_resources.len()
+    );
+    Ok(())
+}
+
+/// re-check a resource against its file, returning the rows read and the errors found,
+/// or None if the file is missing
+fn verify_resource(
+    base_dir: &Path,
+    resource: &Value,
+    prefer_dmy: bool,
+) -> CliResult<Option<(u64, Vec<String>)>> {
+    let Some(path) = resource["path"].as_str() else {
+        return fail_clierror!("only resources with a single path can be verified");
+    };
+    let path = base_dir.join(path);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let delimiter = match resource["dialect"]["delimiter"].as_str() {
+        Some(delimiter) => Some(Delimiter::decode_delimiter(delimiter)?),
+        None => Some(Delimiter(b',')),
+    };
+    let (mut rdr, _) = csv_reader(&path, delimiter)?;
+    let headers = rdr.byte_headers()?.clone();
+    let mut errors = Vec::new();
+
+    // the fields must be the same, in the same order
+    let no_fields = Vec::new();
+    let fields = resource["schema"]["fields"]
+        .as_array()
+        .unwrap_or(&no_fields);
+    let field_names: Vec<&str> = fields
+        .iter()
+        .map(|field| field["name"].as_str().unwrap_or_default())
+        .collect();
+    let header_names: Vec<String> = headers
+        .iter()
+        .map(|header| String::from_utf8_lossy(header).into_owned())
+        .collect();
+    if header_names != field_names {
+        errors.push(format!(
+            "fields: expected {}, found {}",
+            field_names.join(","),
+            header_names.join(",")
+        ));
+    }
+    let field_checks: Vec<(&str, &str, bool)> = fields
+        .iter()
+        .map(|field| {
+            (
+                field["name"].as_str().unwrap_or_default(),
+                field["type"].as_str().unwrap_or("string"),
+                field["constraints"]["required"]
+                    .as_bool()
+                    .unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    // only the first invalid value of each field is reported
+    let mut field_failed = vec![false; field_checks.len()];
+    let mut rows: u64 = 0;
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        rows += 1;
+        for (idx, ((name, table_type, required), value)) in
+            field_checks.iter().zip(record.iter()).enumerate()
+        {
+            if field_failed[idx] {
+                continue;
+            }
+            let error = if value.is_empty() {
+                required.then(|| format!("{name}: row {rows} is empty but the field is required"))
+            } else if complies(table_type, value, prefer_dmy) {
+                None
+            } else {
+                Some(format!(
+                    "{name}: \"{}\" in row {rows} is not a {table_type}",
+                    String::from_utf8_lossy(value)
+                ))
+            };
+            if let Some(error) = error {
+                errors.push(error);
+                field_failed[idx] = true;
+            }
+        }
+    }
+    let (bytes, hash) = rdr.into_inner().finish();
+
+    if let Some(expected) = resource["bytes"].as_u64() {
+        if expected != bytes {
+            errors.push(format!("bytes: expected {expected}, found {bytes}"));
+        }
+    }
+    if let Some(expected) = resource["hash"].as_str() {
+        match expected.strip_prefix("sha256:") {
+            Some(expected) if !expected.eq_ignore_ascii_case(&hash) => {
+                errors.push(format!(
+                    "hash: expected sha256:{expected}, found sha256:{hash}"
+                ));
+            },
+            Some(_) => {},
+            None => errors.push(format!(
+                "hash: only sha256 hashes can be verified, not {expected}"
+            )),
+        }
+    }
+    if let Some(expected) = resource["stats"]["rows"].as_u64() {
+        if expected != rows {
+            errors.push(format!("rows: expected {expected}, found {rows}"));
+        }
+    }
+    Ok(Some((rows, errors)))
+}
+
+fn verify(package_path: &Path) -> CliResult<()> {
+    let package_path = if package_path.is_dir() {
+        package_path.join(DATAPACKAGE_FILE)
+    } else {
+        package_path.to_path_buf()
+    };
+    let package: Value = match fs::read_to_string(&package_path) {
+        Ok(s) => serde_json::from_str(&s)?,
+        Err(e) => return fail_clierror!("Cannot read {}: {e}", package_path.display()),
+    };
+    let Some(resources) = package["resources"].as_array() else {
+        return fail_clierror!("{} has no resources.", package_path.display());
+    };
+    let base_dir = package_path.parent().unwrap_or_else(|| Path::new(""));
+
+    let prefer_dmy = util::get_envvar_flag("QSV_PREFER_DMY");
+    let outcomes: Vec<CliResult<Option<(u64, Vec<String>)>>> = resources
+        .par_iter()
+        .map(|resource| verify_resource(base_dir, resource, prefer_dmy))
+        .collect();
+
+    let mut wtr = Config::new(&None).writer()?;
+    wtr.write_record(["resource", "path", "rows", "status", "errors"])?;
+    let mut failed = 0_u64;
+    for (resource, outcome) in resources.iter().zip(outcomes) {
+        let name = resource["name"].as_str().unwrap_or_default();
+        let path = resource["path"].as_str().unwrap_or_default();
+        let (rows, status, errors) = match outcome {
+            Ok(Some((rows, errors))) if errors.is_empty() => (rows.to_string(), "valid", errors),
+            Ok(Some((rows, errors))) => (rows.to_string(), "invalid", errors),
+            Ok(None) => (String::new(), "missing", Vec::new()),
+            Err(e) => (String::new(), "error", vec![e.to_string()]),
+        };
+        if status != "valid" {
+            failed += 1;
+        }
+        wtr.write_record([
+            name,
+            path,
+            rows.as_str(),
+            status,
+            errors.join("; ").as_str(),
+        ])?;
+    }
+    wtr.flush()?;
+
+    if failed > 0 {
+        return fail_clierror!(
+            "{failed} of {} resources failed verification.",
+            resources.len()
+        );
+    }
+    Ok(())
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    // set RAYON_NUM_THREADS
+    util::njobs(args.flag_jobs);
+
+    if args.cmd_create {
+        // safety: docopt requires <dir> with create
+        create(
+            &args,
+            Path::new(args.arg_dir.as_deref().unwrap_or_default()),
+        )
+    } else if args.cmd_verify {
+        // safety: docopt requires <datapackage> with verify
+        verify(Path::new(
+            args.arg_datapackage.as_deref().unwrap_or_default(),
+        ))
+    } else {
+        unreachable!("docopt requires a subcommand")
+    }
+}
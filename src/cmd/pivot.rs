@@ -0,0 +1,401 @@
+static USAGE: &str = r#"
+Reshapes long data to wide: the distinct values of the --columns column become columns,
+with a row per distinct combination of the values of the --index columns, and the values
+of the --values column aggregated into the cells.
+
+For example, with --index region --columns year --values amount --agg sum:
+
+    region,year,amount          region,2023,2024
+    east,2023,10                east,15,7
+    east,2023,5          =>     west,,3
+    east,2024,7
+    west,2024,3
+
+The rows are sorted by their --index values, and the pivoted columns by their name.
+Cells without values are empty, empty --values values are skipped, and rows with an
+empty --columns value are pivoted into a "(NULL)" column.
+
+The aggregations are:
+  * sum    the sum of the values, which must be numbers.
+  * count  the number of values.
+  * first  the first value, in input order.
+
+The aggregated cells are kept in memory. When there are more than --spill-cells of them,
+they are spilled to a sorted temporary file, in a scratch directory in QSV_TMPDIR (or the
+system temp dir if QSV_TMPDIR is not set), and the spilled files are merged at the end -
+so pivoting data with a large number of distinct --index values doesn't run out of
+memory. The distinct --columns values are always kept in memory.
+
+Examples:
+
+  # the total amount of each region per year
+  $ qsv pivot --index region --columns year --values amount --agg sum sales.csv
+
+  # the number of orders per store & product category
+  $ qsv pivot -i store,city -c category -v order_id --agg count orders.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_pivot.rs.
+
+Usage:
+    qsv pivot [options] --index <cols> --columns <col> --values <col> [<input>]
+    qsv pivot --help
+
+pivot options:
+    -i, --index <cols>     The columns whose distinct values are the rows of the output.
+                           See 'qsv select --help' for the format details.
+    -c, --columns <col>    The column whose distinct values are the columns of the output.
+    -v, --values <col>     The column whose values are aggregated into the cells.
+    -a, --agg <func>       The aggregation, sum, count or first.
+                           [default: first]
+    --spill-cells <n>      The number of aggregated cells kept in memory before they're
+                           spilled to a temporary file.
+                           [default: 5000000]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{
+    collections::{hash_map::Entry, BTreeMap},
+    fs::File,
+    path::PathBuf,
+};
+
+use ahash::AHashMap;
+use csv::ByteRecord;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+/// the name of the column of the rows with an empty --columns value
+const NULL_COLUMN: &[u8] = b"(NULL)";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:        Option<String>,
+    flag_index:       SelectColumns,
+    flag_columns:     SelectColumns,
+    flag_values:      SelectColumns,
+    flag_agg:         String,
+    flag_spill_cells: usize,
+    flag_output:      Option<String>,
+    flag_delimiter:   Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Agg {
+    Sum,
+    Count,
+    First,
+}
+
+/// the aggregate of the values of a cell
+enum Cell {
+    Sum(f64),
+    Count(u64),
+    First(Vec<u8>),
+}
+
+impl Cell {
+    /// the aggregate of a first value, of the given row number
+    fn new(agg: Agg, value: &[u8], row_number: u64) -> CliResult<Self> {
+        Ok(match agg {
+            Agg::Sum => Cell::Sum(parse_number(value).ok_or_else(|| {
+                format!(
+                    "Invalid --values value in row {row_number}: \"{}\" is not a number.",
+                    String::from_utf8_lossy(value)
+                )
+            })?),
+            Agg::Count => Cell::Count(1),
+            Agg::First => Cell::First(value.to_vec()),
+        })
+    }
+
+    /// add the aggregate of later values
+    fn merge(&mut self, later: Cell) {
+        match (self, later) {
+            (Cell::Sum(sum), Cell::Sum(later)) => *sum += later,
+            (Cell::Count(count), Cell::Count(later)) => *count += later,
+            _ => {},
+        }
+    }
+
+    /// the cell value of the output
+    fn to_field(&self) -> Vec<u8> {
+        match self {
+            Cell::Sum(sum) => {
+                if sum.fract() == 0.0 && sum.abs() < 9.0e15 {
+                    (*sum as i64).to_string().into_bytes()
+                } else {
+                    util::round_num(*sum, 9999).into_bytes()
+                }
+            },
+            Cell::Count(count) => count.to_string().into_bytes(),
+            Cell::First(value) => value.clone(),
+        }
+    }
+
+    /// the cell as a field of a spill file, keeping the sums exact
+    fn to_spilled(&self) -> Vec<u8> {
+        match self {
+            Cell::Sum(sum) => util::round_num(*sum, 9999).into_bytes(),
+            _ => self.to_field(),
+        }
+    }
+
+    /// the cell of a field of a spill file
+    fn from_spilled(agg: Agg, field: &[u8]) -> CliResult<Self> {
+        let invalid = || "Invalid pivot spill file.".to_string();
+        Ok(match agg {
+            Agg::Sum => Cell::Sum(parse_number(field).ok_or_else(invalid)?),
+            Agg::Count => Cell::Count(
+                simdutf8::basic::from_utf8(field)
+                    .ok()
+                    .and_then(|count| count.parse().ok())
+                    .ok_or_else(invalid)?,
+            ),
+            Agg::First => Cell::First(field.to_vec()),
+        })
+    }
+}
+
+fn parse_number(value: &[u8]) -> Option<f64> {
+    simdutf8::basic::from_utf8(value)
+        .ok()
+        .and_then(|value| value.trim().parse::<f64>().ok())
+}
+
+/// the --index values of a row
+type Key = Vec<Vec<u8>>;
+
+/// the cells of the rows, keyed by their --index values, with the cells of a row keyed by
+/// the id of their pivoted column
+type Cells = BTreeMap<Key, AHashMap<usize, Cell>>;
+
+/// the sorted spill files of the cells aggregated so far
+struct Spill {
+    scratch: Option<TempDir>,
+    runs:    Vec<PathBuf>,
+}
+
+impl Spill {
+    /// write the cells to a new spill file, sorted by key, and clear them
+    fn spill(&mut self, cells: &mut Cells) -> CliResult<()> {
+        if self.scratch.is_none() {
+            self.scratch = Some(util::scratch_dir()?);
+        }
+        // safety: the scratch directory was just created if needed
+        let path = self
+            .scratch
+            .as_ref()
+            .unwrap()
+            .path()
+            .join(format!("pivot-{}.csv", self.runs.len()));
+        let mut wtr = csv::Writer::from_path(&path)?;
+        let mut record = ByteRecord::new();
+        for (key, row_cells) in std::mem::take(cells) {
+            for (column_id, cell) in row_cells {
+                record.clear();
+                for value in &key {
+                    record.push_field(value);
+                }
+                record.push_field(column_id.to_string().as_bytes());
+                record.push_field(&cell.to_spilled());
+                wtr.write_byte_record(&record)?;
+            }
+        }
+        wtr.flush()?;
+        self.runs.push(path);
+        Ok(())
+    }
+}
+
+/// a spill file being merged, with its next record
+struct Run {
+    rdr:  csv::Reader<File>,
+    next: Option<ByteRecord>,
+}
+
+impl Run {
+    fn advance(&mut self) -> CliResult<()> {
+        let mut record = ByteRecord::new();
+        self.next = if self.rdr.read_byte_record(&mut record)? {
+            Some(record)
+        } else {
+            None
+        };
+        Ok(())
+    }
+}
+
+/// write an output row, with the cells in the order of the pivoted columns
+fn write_row(
+    wtr: &mut csv::Writer<Box<dyn std::io::Write + 'static>>,
+    key: &[Vec<u8>],
+    row_cells: &AHashMap<usize, Cell>,
+    column_order: &[usize],
+    record: &mut ByteRecord,
+) -> CliResult<()> {
+    record.clear();
+    for value in key {
+        record.push_field(value);
+    }
+    for column_id in column_order {
+        match row_cells.get(column_id) {
+            Some(cell) => record.push_field(&cell.to_field()),
+            None => record.push_field(b""),
+        }
+    }
+    Ok(wtr.write_byte_record(record)?)
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let agg = match args.flag_agg.to_ascii_lowercase().as_str() {
+        "sum" => Agg::Sum,
+        "count" => Agg::Count,
+        "first" => Agg::First,
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --agg: {}. Must be sum, count or first.",
+                args.flag_agg
+            )
+        },
+    };
+    if args.flag_spill_cells == 0 {
+        return fail_incorrectusage_clierror!("--spill-cells must be greater than 0.");
+    }
+
+    let rconfig = Config::new(&args.arg_input).delimiter(args.flag_delimiter);
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let index_sel = args.flag_index.selection(&headers, true)?;
+    let columns_sel = args.flag_columns.selection(&headers, true)?;
+    let values_sel = args.flag_values.selection(&headers, true)?;
+    if columns_sel.len() != 1 || values_sel.len() != 1 {
+        return fail_incorrectusage_clierror!(
+            "--columns and --values must each select a single column."
+        );
+    }
+    let (columns_idx, values_idx) = (columns_sel[0], values_sel[0]);
+
+    // the names of the pivoted columns, by id
+    let mut column_ids: AHashMap<Vec<u8>, usize> = AHashMap::new();
+    let mut column_names: Vec<Vec<u8>> = Vec::new();
+
+    let mut cells = Cells::new();
+    let mut cells_len = 0_usize;
+    let mut spill = Spill {
+        scratch: None,
+        runs:    Vec::new(),
+    };
+    let mut record = ByteRecord::new();
+    let mut row_number = 0_u64;
+    while rdr.read_byte_record(&mut record)? {
+        row_number += 1;
+        let value = &record[values_idx];
+        if value.is_empty() {
+            continue;
+        }
+        let column_name = match &record[columns_idx] {
+            b"" => NULL_COLUMN,
+            column_name => column_name,
+        };
+        let column_id = match column_ids.get(column_name) {
+            Some(&column_id) => column_id,
+            None => {
+                column_names.push(column_name.to_vec());
+                column_ids.insert(column_name.to_vec(), column_names.len() - 1);
+                column_names.len() - 1
+            },
+        };
+
+        let key: Key = index_sel.select(&record).map(<[u8]>::to_vec).collect();
+        let cell = Cell::new(agg, value, row_number)?;
+        match cells.entry(key).or_default().entry(column_id) {
+            Entry::Occupied(mut entry) => entry.get_mut().merge(cell),
+            Entry::Vacant(entry) => {
+                entry.insert(cell);
+                cells_len += 1;
+            },
+        }
+        if cells_len >= args.flag_spill_cells {
+            spill.spill(&mut cells)?;
+            cells_len = 0;
+        }
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut column_order: Vec<usize> = (0..column_names.len()).collect();
+    column_order.sort_unstable_by(|&a, &b| column_names[a].cmp(&column_names[b]));
+    let mut out_record: ByteRecord = index_sel.select(&headers).collect();
+    for &column_id in &column_order {
+        out_record.push_field(&column_names[column_id]);
+    }
+    wtr.write_byte_record(&out_record)?;
+
+    if spill.runs.is_empty() {
+        for (key, row_cells) in &cells {
+            write_row(&mut wtr, key, row_cells, &column_order, &mut out_record)?;
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    // merge the sorted spill files, the cells of the earlier ones having the first values
+    if !cells.is_empty() {
+        spill.spill(&mut cells)?;
+    }
+    let mut runs = Vec::with_capacity(spill.runs.len());
+    for path in &spill.runs {
+        let mut run = Run {
+            rdr:  csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_path(path)?,
+            next: None,
+        };
+        run.advance()?;
+        runs.push(run);
+    }
+    let key_len = index_sel.len();
+    loop {
+        let min_next = runs
+            .iter()
+            .filter_map(|run| run.next.as_ref())
+            .min_by(|a, b| a.iter().take(key_len).cmp(b.iter().take(key_len)));
+        let Some(min_next) = min_next else {
+            break;
+        };
+        let key: Key = min_next.iter().take(key_len).map(<[u8]>::to_vec).collect();
+
+        let mut row_cells: AHashMap<usize, Cell> = AHashMap::new();
+        for run in &mut runs {
+            while let Some(next) = run
+                .next
+                .take_if(|next| next.iter().take(key_len).eq(key.iter().map(Vec::as_slice)))
+            {
+                let column_id: usize = simdutf8::basic::from_utf8(&next[key_len])
+                    .ok()
+                    .and_then(|column_id| column_id.parse().ok())
+                    .ok_or("Invalid pivot spill file.")?;
+                let cell = Cell::from_spilled(agg, &next[key_len + 1])?;
+                match row_cells.entry(column_id) {
+                    Entry::Occupied(mut entry) => entry.get_mut().merge(cell),
+                    Entry::Vacant(entry) => {
+                        entry.insert(cell);
+                    },
+                }
+                run.advance()?;
+            }
+        }
+        write_row(&mut wtr, &key, &row_cells, &column_order, &mut out_record)?;
+    }
+    Ok(wtr.flush()?)
+}
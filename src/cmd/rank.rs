@@ -0,0 +1,224 @@
+static USAGE: &str = r#"
+Appends a rank or percentile column, ranking the rows by the values of a column - e.g. the
+rank of each order by amount within its region.
+
+The --method of ranking is one of:
+  * min: the standard competition rank, equal values having the lowest rank of their
+    group, with gaps after ties - e.g. 1, 2, 2, 4.
+  * dense: like min, but without gaps after ties - e.g. 1, 2, 2, 3.
+  * percent: the percent rank, from 0 to 1, i.e. (min rank - 1) / (rows - 1), the rows
+    being those of the partition with a value.
+
+The rows are ranked from the smallest value up, or from the largest one down with --desc.
+The values of the --by column are compared as numbers when they're numbers, and as text
+otherwise. The rows with an empty --by value have an empty rank.
+
+The --by values are loaded in memory in a first pass, per partition (stdin being spooled to
+a scratch file), and the rows are then streamed in their input order with their rank.
+
+Examples:
+
+  # the rank of each order by amount, the largest being 1, within its region
+  $ qsv rank --by amount --partition-by region --desc orders.csv
+
+  # the percent rank of the scores
+  $ qsv rank --by score --method percent --name percentile scores.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_rank.rs.
+
+Usage:
+    qsv rank [options] --by <column> [<input>]
+    qsv rank --help
+
+rank options:
+    -b, --by <column>          The column to rank the rows by.
+    -p, --partition-by <cols>  The columns to partition the rows by. The rows of each
+                               distinct combination of their values are ranked
+                               separately.
+                               See 'qsv select --help' for the format details.
+    -m, --method <arg>         The ranking method: min, dense or percent.
+                               [default: min]
+    --desc                     Rank from the largest value down.
+    --name <name>              The name of the rank column.
+                               [default: rank]
+    --round <places>           Round the percent ranks to <places> decimal places.
+                               [default: 4]
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -n, --no-headers           When set, the first row will not be interpreted
+                               as headers.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::cmp::Ordering;
+
+use ahash::AHashMap;
+use csv::ByteRecord;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:         Option<String>,
+    flag_by:           SelectColumns,
+    flag_partition_by: Option<SelectColumns>,
+    flag_method:       String,
+    flag_desc:         bool,
+    flag_name:         String,
+    flag_round:        u32,
+    flag_output:       Option<String>,
+    flag_no_headers:   bool,
+    flag_delimiter:    Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Method {
+    Min,
+    Dense,
+    Percent,
+}
+
+/// a --by value, with its row
+struct Ranked {
+    row:    usize,
+    number: Option<f64>,
+    value:  Vec<u8>,
+}
+
+impl Ranked {
+    /// compare the --by values, as numbers if they're both numbers, and as text otherwise
+    fn cmp_value(&self, other: &Ranked) -> Ordering {
+        match (self.number, other.number) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            _ => self.value.cmp(&other.value),
+        }
+    }
+}
+
+/// rank the values of a partition, setting the rank of their rows
+fn rank_partition(mut values: Vec<Ranked>, method: Method, desc: bool, ranks: &mut [f64]) {
+    values.sort_unstable_by(|a, b| {
+        let ordering = a.cmp_value(b);
+        if desc {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    let count = values.len();
+    let mut rank = 0;
+    for (pos, ranked) in values.iter().enumerate() {
+        if pos == 0 || ranked.cmp_value(&values[pos - 1]).is_ne() {
+            rank = match method {
+                Method::Dense => rank + 1,
+                Method::Min | Method::Percent => pos + 1,
+            };
+        }
+        ranks[ranked.row] = match method {
+            Method::Percent if count == 1 => 0.0,
+            Method::Percent => (rank - 1) as f64 / (count - 1) as f64,
+            Method::Min | Method::Dense => rank as f64,
+        };
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let method = match args.flag_method.to_ascii_lowercase().as_str() {
+        "min" => Method::Min,
+        "dense" => Method::Dense,
+        "percent" => Method::Percent,
+        method => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --method: {method}. Expected min, dense or percent."
+            )
+        },
+    };
+
+    let mut rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    // the values of stdin are ranked in a first pass, so it's spooled
+    let _stdin_spool = if rconfig.is_stdin() {
+        let (spooled_rconfig, spool_dir) = util::spool_stdin(&rconfig)?;
+        rconfig = spooled_rconfig;
+        Some(spool_dir)
+    } else {
+        None
+    };
+
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let by_sel = args.flag_by.selection(&headers, !rconfig.no_headers)?;
+    if by_sel.len() != 1 {
+        return fail_incorrectusage_clierror!("--by must select a single column.");
+    }
+    let by_col = by_sel[0];
+    let partition_sel = match args.flag_partition_by {
+        Some(ref partition_by) => Some(partition_by.selection(&headers, !rconfig.no_headers)?),
+        None => None,
+    };
+
+    // the first pass, collecting the --by values of each partition
+    let mut partitions: AHashMap<Vec<Vec<u8>>, Vec<Ranked>> = AHashMap::new();
+    let mut record = ByteRecord::new();
+    let mut row_count = 0;
+    while rdr.read_byte_record(&mut record)? {
+        let row = row_count;
+        row_count += 1;
+        let value = record.get(by_col).unwrap_or_default().trim_ascii();
+        if value.is_empty() {
+            continue;
+        }
+        let key: Vec<Vec<u8>> = match partition_sel {
+            Some(ref sel) => sel.select(&record).map(<[u8]>::to_vec).collect(),
+            None => Vec::new(),
+        };
+        partitions.entry(key).or_default().push(Ranked {
+            row,
+            number: simdutf8::basic::from_utf8(value)
+                .ok()
+                .and_then(|value| value.parse::<f64>().ok()),
+            value: value.to_vec(),
+        });
+    }
+
+    // the rank of each row, NaN for the rows without a value
+    let mut ranks = vec![f64::NAN; row_count];
+    for values in partitions.into_values() {
+        rank_partition(values, method, args.flag_desc, &mut ranks);
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if !rconfig.no_headers {
+        let mut out_headers = headers.clone();
+        out_headers.push_field(args.flag_name.as_bytes());
+        wtr.write_byte_record(&out_headers)?;
+    }
+
+    let mut rdr = rconfig.reader()?;
+    let mut itoa_buffer = itoa::Buffer::new();
+    for rank in ranks {
+        if !rdr.read_byte_record(&mut record)? {
+            return fail_clierror!("The input changed while it was being ranked.");
+        }
+        if rank.is_nan() {
+            record.push_field(b"");
+        } else if method == Method::Percent {
+            record.push_field(util::round_num(rank, args.flag_round).as_bytes());
+        } else {
+            record.push_field(itoa_buffer.format(rank as u64).as_bytes());
+        }
+        wtr.write_byte_record(&record)?;
+    }
+    Ok(wtr.flush()?)
+}
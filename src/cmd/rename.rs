@@ -33,6 +33,11 @@ rename arguments:
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
+    --in-place             Rename the columns of <input> in-place. The output is written
+                           to a tempfile that atomically replaces <input> when done.
+                           Cannot be used with stdin, a remote URL or --output.
+    --backup <suffix>      With --in-place, keep a copy of the original <input> with
+                           <suffix> appended to its file name (e.g. .bak).
     -n, --no-headers       When set, the header will be inserted on top.    
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
@@ -50,6 +55,8 @@ struct Args {
     arg_input:       Option<String>,
     arg_headers:     String,
     flag_output:     Option<String>,
+    flag_in_place:   bool,
+    flag_backup:     Option<String>,
     flag_no_headers: bool,
     flag_delimiter:  Option<Delimiter>,
 }
@@ -61,8 +68,19 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers);
 
+    let in_place = util::InPlace::new(
+        args.flag_in_place,
+        args.arg_input.as_deref(),
+        args.flag_output.as_deref(),
+        args.flag_backup.as_deref(),
+    )?;
+    let output = match in_place {
+        Some(ref in_place) => Some(in_place.output()),
+        None => args.flag_output.clone(),
+    };
+
     let mut rdr = rconfig.reader()?;
-    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut wtr = Config::new(&output).writer()?;
     let headers = rdr.byte_headers()?;
 
     if args.arg_headers.to_lowercase() == "_all_generic" {
@@ -86,7 +104,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     while rdr.read_byte_record(&mut record)? {
         wtr.write_record(&record)?;
     }
-    Ok(wtr.flush()?)
+    wtr.flush()?;
+
+    if let Some(in_place) = in_place {
+        in_place.commit()?;
+    }
+    Ok(())
 }
 
 pub fn rename_headers_all_generic(num_of_cols: usize) -> String {
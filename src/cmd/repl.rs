@@ -0,0 +1,417 @@
+static USAGE: &str = r#"
+Interactively try out qsv commands on a sample of a CSV, with an instant preview of
+their output, before running them on the whole file. No more edit-run-wait loops
+on big files.
+
+The first --sample-size records of <input> are loaded in memory. At the prompt, enter
+a qsv command without "qsv" and without an input file - e.g. `select 1-3`,
+`search -s status active` or `sort -s amount -N`. It's run on the sample (piped to it
+through stdin), and the first --preview rows of its output are shown as a table.
+
+Once a command does what you want, `:keep` it. Its output then becomes the sample the
+next commands are run on, so a pipeline of commands is built step by step. When the
+pipeline is done, `:run` it on the whole <input>, or print it as a shell command line
+with `:pipeline`.
+
+REPL commands:
+    :keep            Keep the output of the last command as the sample of the
+                     next commands, adding the command to the pipeline.
+    :undo            Remove the last command from the pipeline.
+    :show            Preview the current sample.
+    :pipeline        Print the pipeline as a shell command line.
+    :run [<output>]  Run the pipeline on the whole <input>, writing its output
+                     to <output>, or to stdout if not set.
+    :help            Show the REPL commands.
+    :quit            Exit the REPL. Also on end of input (e.g. Ctrl-D).
+
+The commands are read from stdin, so they can also be scripted, e.g.
+  $ printf 'select 1,4\n:keep\nsort -s 2\n:keep\n:run sorted.csv\n' | qsv repl data.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_repl.rs.
+
+Usage:
+    qsv repl [options] <input>
+    qsv repl --help
+
+repl options:
+    --sample-size <n>      The number of records of <input> to load as the sample.
+                           [default: 1000]
+    --preview <n>          The number of output rows to preview. [default: 10]
+    --condense <n>         Condense the previewed values longer than <n> characters.
+                           [default: 40]
+
+Common options:
+    -h, --help             Display this message
+    -d, --delimiter <arg>  The field delimiter for reading <input>.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{
+    borrow::Cow,
+    env,
+    fs::File,
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    thread,
+};
+
+use serde::Deserialize;
+use tabwriter::TabWriter;
+
+use crate::{
+    config::{Config, Delimiter},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:        String,
+    flag_sample_size: usize,
+    flag_preview:     usize,
+    flag_condense:    usize,
+    flag_delimiter:   Option<Delimiter>,
+}
+
+const REPL_HELP: &str = r#"Enter a qsv command without "qsv" and without an input file,
+e.g. `select 1-3`, to preview its output on the sample. REPL commands:
+    :keep            Keep the output of the last command as the sample of the
+                     next commands, adding the command to the pipeline.
+    :undo            Remove the last command from the pipeline.
+    :show            Preview the current sample.
+    :pipeline        Print the pipeline as a shell command line.
+    :run [<output>]  Run the pipeline on the whole input.
+    :help            Show the REPL commands.
+    :quit            Exit the REPL."#;
+
+/// a command kept in the pipeline, with the sample it output
+struct Step {
+    command: Vec<String>,
+    output:  Vec<u8>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let input_path = Path::new(&args.arg_input);
+    if !input_path.is_file() {
+        return fail_incorrectusage_clierror!("{} is not a file.", input_path.display());
+    }
+    let rconfig = Config::new(&Some(args.arg_input.clone()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(true)
+        .flexible(true);
+    let qsv_path = env::current_exe()?;
+
+    // the sample always has a comma delimiter, as that's what the commands read from stdin
+    let sample = read_sample(&rconfig, args.flag_sample_size + 1)?;
+    let mut pipeline: Vec<Step> = Vec::new();
+    let mut last_output: Option<Step> = None;
+
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        winfo!(
+            "Loaded a sample of up to {} records of {}. Enter :help for help.",
+            args.flag_sample_size,
+            args.arg_input
+        );
+    }
+
+    let mut line = String::new();
+    let mut stdin = io::stdin().lock();
+    loop {
+        if interactive {
+            eprint!("qsv> ");
+            io::stderr().flush()?;
+        }
+        line.clear();
+        if stdin.read_line(&mut line)? == 0 {
+            break;
+        }
+        let words = match split_words(&line) {
+            Ok(words) => words,
+            Err(e) => {
+                wwarn!("{e}");
+                continue;
+            },
+        };
+        let Some(first_word) = words.first() else {
+            continue;
+        };
+        let current_sample = pipeline.last().map_or(&sample, |step| &step.output);
+
+        match first_word.as_str() {
+            ":quit" | ":q" | ":exit" => break,
+            ":help" => eprintln!("{REPL_HELP}"),
+            ":show" => preview(current_sample, &args)?,
+            ":keep" => match last_output.take() {
+                Some(step) => {
+                    winfo!("Kept `{}`.", shell_words(&step.command));
+                    pipeline.push(step);
+                },
+                None => wwarn!("There's no successful command output to keep."),
+            },
+            ":undo" => match pipeline.pop() {
+                Some(step) => {
+                    winfo!("Removed `{}`.", shell_words(&step.command));
+                    last_output = None;
+                },
+                None => wwarn!("The pipeline is empty."),
+            },
+            ":pipeline" => {
+                if pipeline.is_empty() {
+                    wwarn!("The pipeline is empty.");
+                } else {
+                    println!("{}", pipeline_command_line(&args, &rconfig, &pipeline));
+                }
+            },
+            ":run" => {
+                if pipeline.is_empty() {
+                    wwarn!("The pipeline is empty. :keep a command first.");
+                } else {
+                    let output = words.get(1).map(PathBuf::from);
+                    run_pipeline(&qsv_path, &rconfig, &pipeline, output.as_deref())?;
+                }
+            },
+            repl_command if repl_command.starts_with(':') => {
+                wwarn!("Unknown REPL command: {repl_command}. Enter :help for help.");
+            },
+            "repl" => wwarn!("repl can't be run from the REPL."),
+            _ => {
+                last_output = None;
+                match run_command(&qsv_path, &words, current_sample)? {
+                    Some(output) => {
+                        preview(&output, &args)?;
+                        last_output = Some(Step {
+                            command: words,
+                            output,
+                        });
+                    },
+                    None => wwarn!("`{}` failed.", shell_words(&words)),
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// read the first max_records records (including the header row) of the input
+/// as CSV with a comma delimiter
+fn read_sample(rconfig: &Config, max_records: usize) -> CliResult<Vec<u8>> {
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    let mut record = csv::ByteRecord::new();
+    let mut records = 0_usize;
+    while records < max_records && rdr.read_byte_record(&mut record)? {
+        wtr.write_byte_record(&record)?;
+        records += 1;
+    }
+    Ok(wtr.into_inner().map_err(csv::IntoInnerError::into_error)?)
+}
+
+/// run a qsv command on the sample, returning its output if it succeeded
+fn run_command(qsv_path: &Path, command: &[String], sample: &[u8]) -> CliResult<Option<Vec<u8>>> {
+    let mut child = Command::new(qsv_path)
+        .args(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    // write the sample from another thread, so a command with a lot of output can't
+    // block us while we're still writing its input
+    let mut stdin = child.stdin.take().unwrap();
+    let output = thread::scope(|s| {
+        s.spawn(move || {
+            // the command may exit without reading all its input, e.g. slice
+            let _ = stdin.write_all(sample);
+        });
+        child.wait_with_output()
+    })?;
+
+    Ok(output.status.success().then_some(output.stdout))
+}
+
+/// preview the first rows of a command's output as a table
+fn preview(output: &[u8], args: &Args) -> CliResult<()> {
+    let mut stdout = io::stdout().lock();
+
+    // not CSV output, e.g. from tojsonl or sniff --json
+    if matches!(output.first(), Some(b'{' | b'[')) {
+        for line in output.split(|b| *b == b'\n').take(args.flag_preview) {
+            stdout.write_all(line)?;
+            stdout.write_all(b"\n")?;
+        }
+        return Ok(stdout.flush()?);
+    }
+
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(output);
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .flexible(true)
+        .from_writer(TabWriter::new(&mut stdout));
+    let mut record = csv::ByteRecord::new();
+    let mut rows = 0_usize;
+    while rdr.read_byte_record(&mut record)? {
+        // the header row and the previewed rows
+        if rows <= args.flag_preview {
+            wtr.write_record(
+                record
+                    .iter()
+                    .map(|f| util::condense(Cow::Borrowed(f), Some(args.flag_condense))),
+            )?;
+        }
+        rows += 1;
+    }
+    wtr.flush()?;
+    drop(wtr);
+
+    let data_rows = rows.saturating_sub(1);
+    if data_rows > args.flag_preview {
+        writeln!(stdout, "... {} more rows", data_rows - args.flag_preview)?;
+    }
+    Ok(stdout.flush()?)
+}
+
+/// run the pipeline on the whole input, chaining the commands through pipes
+fn run_pipeline(
+    qsv_path: &Path,
+    rconfig: &Config,
+    pipeline: &[Step],
+    output: Option<&Path>,
+) -> CliResult<()> {
+    let mut children: Vec<Child> = Vec::with_capacity(pipeline.len());
+    for (idx, step) in pipeline.iter().enumerate() {
+        let stdin = match children.last_mut() {
+            Some(previous) => Stdio::from(previous.stdout.take().unwrap()),
+            None => Stdio::piped(),
+        };
+        let stdout = match output {
+            Some(output_path) if idx == pipeline.len() - 1 => {
+                Stdio::from(File::create(output_path)?)
+            },
+            _ if idx == pipeline.len() - 1 => Stdio::inherit(),
+            _ => Stdio::piped(),
+        };
+        children.push(
+            Command::new(qsv_path)
+                .args(&step.command)
+                .stdin(stdin)
+                .stdout(stdout)
+                .stderr(Stdio::inherit())
+                .spawn()?,
+        );
+    }
+
+    // stream the whole input to the first command
+    let mut first_stdin = children[0].stdin.take().unwrap();
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = csv::Writer::from_writer(&mut first_stdin);
+    let mut record = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        // the command may exit without reading all its input, e.g. slice
+        if wtr.write_byte_record(&record).is_err() {
+            break;
+        }
+    }
+    let _ = wtr.flush();
+    drop(wtr);
+    drop(first_stdin);
+
+    let mut failed = false;
+    for (child, step) in children.iter_mut().zip(pipeline) {
+        if !child.wait()?.success() {
+            wwarn!("`{}` failed.", shell_words(&step.command));
+            failed = true;
+        }
+    }
+    if !failed {
+        if let Some(output_path) = output {
+            winfo!("Pipeline output written to {}.", output_path.display());
+        }
+    }
+    Ok(())
+}
+
+/// the pipeline as a shell command line, e.g.
+/// qsv select 1-3 < data.csv | qsv sort -s 2
+fn pipeline_command_line(args: &Args, rconfig: &Config, pipeline: &[Step]) -> String {
+    let mut commands: Vec<String> = pipeline
+        .iter()
+        .map(|step| format!("qsv {}", shell_words(&step.command)))
+        .collect();
+    let input = shell_words(&[args.arg_input.clone()]);
+    if rconfig.get_delimiter() == b',' {
+        commands[0] = format!("{} < {input}", commands[0]);
+    } else {
+        // the commands read stdin with a comma delimiter
+        let delimiter = shell_words(&[(rconfig.get_delimiter() as char).to_string()]);
+        commands.insert(0, format!("qsv fmt --delimiter {delimiter} {input}"));
+    }
+    commands.join(" | ")
+}
+
+/// split a line into shell-like words, with single & double quotes and backslash escapes
+fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.trim_end_matches(['\r', '\n']).chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"') | None, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+                in_word = true;
+            },
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            },
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            },
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            },
+        }
+    }
+    if quote.is_some() {
+        return Err("Unterminated quote.".to_string());
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// join words into a shell command line, single-quoting the words that need it
+fn shell_words(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|word| {
+            if !word.is_empty()
+                && word
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_.,:/=+@%".contains(c))
+            {
+                word.clone()
+            } else {
+                format!("'{}'", word.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
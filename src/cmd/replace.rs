@@ -46,11 +46,20 @@ replace options:
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
+    --in-place             Replace in <input> in-place. The output is written to a
+                           tempfile that atomically replaces <input> when done.
+                           Cannot be used with stdin, a remote URL or --output.
+    --backup <suffix>      With --in-place, keep a copy of the original <input> with
+                           <suffix> appended to its file name (e.g. .bak).
     -n, --no-headers       When set, the first row will not be interpreted
                            as headers. (i.e., They are not searched, analyzed,
                            sliced, etc.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+    --locked-schema <file>
+                           Refuse to run if the input's columns or column types
+                           don't match the JSON Schema <file> (e.g. one generated
+                           by `qsv schema`), listing the differences.
     -p, --progressbar      Show progress bars. Not valid for stdin.
     -Q, --quiet            Do not print number of replacements to stderr.
 
@@ -78,8 +87,11 @@ struct Args {
     flag_select:         SelectColumns,
     flag_unicode:        bool,
     flag_output:         Option<String>,
+    flag_in_place:       bool,
+    flag_backup:         Option<String>,
     flag_no_headers:     bool,
     flag_delimiter:      Option<Delimiter>,
+    flag_locked_schema:  Option<String>,
     flag_ignore_case:    bool,
     flag_literal:        bool,
     flag_size_limit:     usize,
@@ -121,8 +133,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
+    if let Some(ref schema_path) = args.flag_locked_schema {
+        util::check_locked_schema(&rconfig, schema_path)?;
+    }
+
+    let in_place = util::InPlace::new(
+        args.flag_in_place,
+        args.arg_input.as_deref(),
+        args.flag_output.as_deref(),
+        args.flag_backup.as_deref(),
+    )?;
+    let output = match in_place {
+        Some(ref in_place) => Some(in_place.output()),
+        None => args.flag_output.clone(),
+    };
+
     let mut rdr = rconfig.reader()?;
-    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut wtr = Config::new(&output).writer()?;
 
     let headers = rdr.byte_headers()?.clone();
     let sel = rconfig.selection(&headers)?;
@@ -195,6 +222,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     }
 
     wtr.flush()?;
+    if let Some(in_place) = in_place {
+        in_place.commit()?;
+    }
 
     #[cfg(any(feature = "feature_capable", feature = "lite"))]
     if show_progress {
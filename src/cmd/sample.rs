@@ -10,6 +10,15 @@ which is necessary to provide a uniform random sample (reservoir sampling).
 If you wish to limit the number of records visited, use the 'qsv slice' command
 to pipe into 'qsv sample'.
 
+Reservoir sampling also works on stdin. As a percentage sample needs the row count
+first, stdin is then spooled to a scratch file (in QSV_TMPDIR, or the system temp dir).
+
+With --stratify, the rows are sampled within each group of the values of a column,
+so every group is represented: a <sample-size> of 1 or more is the number of rows
+sampled in each group (or all the rows of smaller groups), and a <sample-size> between
+0 and 1 samples that percentage of each group (rounded to the nearest row). The
+stratified sample is written in input order.
+
 This command is intended to provide a means to sample from a CSV data set that
 is too big to fit into memory (for example, for use with commands like
 'qsv stats' with the '--everything' option). 
@@ -33,7 +42,10 @@ sample arguments:
                            visiting every record in the CSV.
 
 sample options:
+    --stratify <column>    Sample within each group of the values of <column>.
+                           Does not use the index.
     --seed <number>        Random Number Generator (RNG) seed.
+                           If not set, the QSV_SEED environment variable is used.
     --rng <kind>           The RNG algorithm to use.
                            Three RNGs are supported:
                             - standard: Use the standard RNG.
@@ -66,7 +78,8 @@ Common options:
 
 use std::{io, str::FromStr};
 
-use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use ahash::AHashMap;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, RngCore, SeedableRng};
 use rand_hc::Hc128Rng;
 use rand_xoshiro::Xoshiro256Plus;
 use serde::Deserialize;
@@ -76,6 +89,7 @@ use url::Url;
 
 use crate::{
     config::{Config, Delimiter},
+    select::SelectColumns,
     util, CliResult,
 };
 
@@ -86,6 +100,7 @@ struct Args {
     flag_output:     Option<String>,
     flag_no_headers: bool,
     flag_delimiter:  Option<Delimiter>,
+    flag_stratify:   Option<SelectColumns>,
     flag_seed:       Option<u64>,
     flag_rng:        String,
     flag_user_agent: Option<String>,
@@ -102,6 +117,7 @@ enum RngKind {
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
+    args.flag_seed = util::rng_seed(args.flag_seed)?;
 
     if args.arg_sample_size.is_sign_negative() {
         return fail_incorrectusage_clierror!("Sample size cannot be negative.");
@@ -141,17 +157,60 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         None => None,
     };
 
-    let rconfig = Config::new(&args.arg_input)
+    let mut rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers);
 
+    // a percentage sample of stdin needs its row count first, so it's spooled
+    let _stdin_spool = if rconfig.is_stdin() && args.arg_sample_size < 1.0 {
+        let (spooled_rconfig, spool_dir) = util::spool_stdin(&rconfig)?;
+        rconfig = spooled_rconfig;
+        Some(spool_dir)
+    } else {
+        None
+    };
+
     let mut sample_size = args.arg_sample_size;
 
     let mut wtr = Config::new(&args.flag_output)
         .delimiter(args.flag_delimiter)
         .writer()?;
 
-    if let Some(mut idx) = rconfig.indexed()? {
+    if let Some(ref stratify) = args.flag_stratify {
+        let mut rdr = rconfig.reader()?;
+        let headers = rdr.byte_headers()?.clone();
+        let sel = stratify.selection(&headers, !rconfig.no_headers)?;
+        if sel.len() != 1 {
+            return fail_incorrectusage_clierror!("--stratify must select a single column.");
+        }
+        // a percentage of each group needs the group sizes first
+        let group_sizes = if sample_size < 1.0 {
+            let mut group_sizes: AHashMap<Vec<u8>, u64> = AHashMap::new();
+            let mut count_rdr = rconfig.reader()?;
+            let mut row = csv::ByteRecord::new();
+            while count_rdr.read_byte_record(&mut row)? {
+                *group_sizes
+                    .entry(row.get(sel[0]).unwrap_or_default().to_vec())
+                    .or_default() += 1;
+            }
+            Some(group_sizes)
+        } else {
+            None
+        };
+
+        rconfig.write_headers(&mut rdr, &mut wtr)?;
+        let mut rng = new_rng(&rng_kind, args.flag_seed);
+        let sampled = sample_stratified(
+            &mut rdr,
+            sel[0],
+            sample_size,
+            group_sizes.as_ref(),
+            &mut *rng,
+        )?;
+        for row in sampled {
+            wtr.write_byte_record(&row)?;
+        }
+    } else if let Some(mut idx) = rconfig.indexed()? {
         // the index is present, so we can use random indexing
         #[allow(clippy::cast_precision_loss)]
         if sample_size < 1.0 {
@@ -231,6 +290,87 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(wtr.flush()?)
 }
 
+/// the RNG of the given kind, seeded with the seed if given
+fn new_rng(rng_kind: &RngKind, seed: Option<u64>) -> Box<dyn RngCore> {
+    match *rng_kind {
+        RngKind::Standard => match seed {
+            None => Box::new(StdRng::from_rng(rand::thread_rng()).unwrap()),
+            Some(seed) => Box::new(StdRng::seed_from_u64(seed)), //DevSkim: ignore DS148264
+        },
+        RngKind::Faster => match seed {
+            None => Box::new(Xoshiro256Plus::from_rng(rand::thread_rng()).unwrap()),
+            Some(seed) => Box::new(Xoshiro256Plus::seed_from_u64(seed)), //DevSkim: ignore DS148264
+        },
+        RngKind::Cryptosecure => match seed {
+            None => Box::new(Hc128Rng::from_rng(rand::thread_rng()).unwrap()),
+            Some(seed) => {
+                let mut seed_32 = [0u8; 32];
+                seed_32[..8].copy_from_slice(&seed.to_le_bytes());
+                Box::new(Hc128Rng::from_seed(seed_32))
+            },
+        },
+    }
+}
+
+/// the reservoir sample of a group of rows, with the row numbers of the sampled rows
+struct Stratum {
+    seen:   u64,
+    size:   u64,
+    sample: Vec<(usize, csv::ByteRecord)>,
+}
+
+/// reservoir sampling within each group of the values of the column, of sample_size rows
+/// per group, or of a percentage of the group sizes when sample_size is less than 1
+fn sample_stratified<R: io::Read>(
+    rdr: &mut csv::Reader<R>,
+    column: usize,
+    sample_size: f64,
+    group_sizes: Option<&AHashMap<Vec<u8>, u64>>,
+    rng: &mut dyn RngCore,
+) -> CliResult<Vec<csv::ByteRecord>> {
+    let mut strata: AHashMap<Vec<u8>, Stratum> = AHashMap::new();
+    for (i, row) in rdr.byte_records().enumerate() {
+        let row = row?;
+        let key = row.get(column).unwrap_or_default();
+        if !strata.contains_key(key) {
+            #[allow(clippy::cast_precision_loss)]
+            let size = match group_sizes {
+                Some(group_sizes) => {
+                    let group_size = group_sizes.get(key).copied().unwrap_or_default();
+                    (sample_size * group_size as f64).round() as u64
+                },
+                None => sample_size as u64,
+            };
+            strata.insert(
+                key.to_vec(),
+                Stratum {
+                    seen: 0,
+                    size,
+                    sample: Vec::new(),
+                },
+            );
+        }
+        // safety: the stratum was just inserted if it didn't exist
+        let stratum = strata.get_mut(key).unwrap();
+        stratum.seen += 1;
+        if (stratum.sample.len() as u64) < stratum.size {
+            stratum.sample.push((i, row));
+        } else {
+            let random = rng.gen_range(0..stratum.seen);
+            if random < stratum.size {
+                stratum.sample[random as usize] = (i, row);
+            }
+        }
+    }
+
+    let mut sampled: Vec<(usize, csv::ByteRecord)> = strata
+        .into_values()
+        .flat_map(|stratum| stratum.sample)
+        .collect();
+    sampled.sort_unstable_by_key(|(i, _)| *i);
+    Ok(sampled.into_iter().map(|(_, row)| row).collect())
+}
+
 fn sample_reservoir<R: io::Read>(
     rdr: &mut csv::Reader<R>,
     sample_size: u64,
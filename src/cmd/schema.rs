@@ -26,16 +26,137 @@ exists and is current (i.e. stats generated with --cardinality and --infer-dates
 Otherwise, it will run the `stats` command to generate the `stats.csv.data.jsonl` file first,
 and then use that to generate the schema file.
 
+UPDATING A SCHEMA:
+With --update <file>, the schema is inferred from the input as usual, then merged into
+an existing JSON Schema file instead of replacing it, so manual curation survives
+re-inferring the schema from the latest data:
+  * hand-written titles, descriptions & custom keywords of the schema and its columns
+    are kept, as are custom constraints (e.g. a hand-written pattern, or a non-date
+    format like "email").
+  * the inferred keywords of each column - type, minimum, maximum, minLength, maxLength,
+    enum, the date/date-time format & x-date-format - are refreshed from the data,
+    and removed if they're no longer inferred. Patterns are only refreshed for the
+    columns selected with --pattern-columns.
+  * new columns are added with their inferred definition, and columns that are no
+    longer in the data are removed.
+  * columns that were removed from "required" by hand stay optional.
+The updated schema is written back to <file>, unless --output or --stdout is set.
+Updating only applies to JSON Schemas, and not with --compose.
+
+SCHEMA DIFF:
+With --diff <old-schema>, the inferred JSON Schema is compared against the <old-schema>
+file instead of being written, reporting the drift of the data contract:
+  * added & removed columns.
+  * type changes, ignoring nullability.
+  * tightened & loosened constraints - e.g. a greater minimum or a smaller maxLength
+    is tightened, while a column that's no longer required or becomes nullable, an enum
+    with more values or a removed pattern is loosened. Changed patterns & formats are
+    reported as changed.
+If the <input> is a JSON Schema file (with a .json extension), it's compared as is
+instead, so two schemas can be compared. The report is sent to stdout, or to --output,
+in the --diff-format - either "human" (a line for each change) or "json" (an array of
+change objects with column, change, keyword, old & new properties).
+
+DATE FORMATS:
+When a column is inferred as date/datetime, the strftime pattern/s its values are written
+in are detected (e.g. "%m/%d/%Y %H:%M") and recorded in the column's `x-date-format`
+property - a string, or an array of patterns by descending frequency if the column mixes
+date formats. `validate` checks that the column's values parse with one of the patterns.
+Columns whose values are all RFC-3339 dates/datetimes also get the "date"/"date-time"
+format, even without --strict-dates. Ambiguous day & month orders (e.g. 01/02/2024)
+are resolved with --prefer-dmy.
+
+MULTI-FILE INFERENCE:
+If several inputs are given (or a directory or an `.infile-list` file), a single unified
+schema is inferred across ALL of them. Each file is profiled separately and the
+per-file definitions are merged:
+  * columns are the union of all columns, in order of first appearance. Only columns
+    present in every file are marked as required.
+  * types are widened as needed (e.g. integer + number = number, number + string = string).
+  * min/max values and lengths are the bounds across all files.
+  * enum constraints are only kept if every file produced one, and their union is still
+    within --enum-threshold.
+Per-file deviations from the unified schema (missing columns, type conflicts) are
+reported to stderr, and written to a `<output>.deviations.tsv` file with
+file, field & deviation columns when the schema is written to a file.
+In multi-file mode, either --output or --stdout is required.
+
+With --compose, the unified schema is factored into a maintainable schema set instead
+of repeating every definition:
+  * the definitions of columns found in more than one file are put in `$defs`, and
+    referenced with `$ref` - e.g. `#/$defs/id` for the id column.
+  * the columns found in every file are grouped in a `$defs/common` object schema.
+  * each file gets its own object schema in `$defs` (named after the file), composed
+    of the common schema via `allOf` and the file's other columns.
+The root schema still describes all the columns, so it can be used with `validate`
+as is.
+
+DATA PACKAGE:
+With `--format datapackage`, a Frictionless Data Package descriptor is generated instead,
+so qsv output can be published directly to open-data platforms that support it.
+See https://specs.frictionlessdata.io/data-package/
+It has a tabular data resource per input file with its path, format, dialect
+(delimiter & header) and a Table Schema of its fields. The field types & constraints
+(required, minimum, maximum, minLength, maxLength, enum & pattern) are inferred from
+stats & frequency, the same way as the JSON Schema. Date & datetime field types are
+emitted with --strict-dates, for RFC-3339 dates, and for dates with a single detected
+date format, which is used as the field's format pattern. In multi-file mode, all the
+resources share the unified schema.
+The descriptor is written to a `datapackage.json` file in the input file's directory,
+unless --output or --stdout is set. Resource paths are relative to the descriptor's
+directory when the input files are in it.
+
+SQL DDL:
+With `--format sql`, a `CREATE TABLE` statement for the --dialect is generated instead,
+with the column types sized from the stats of a single input file:
+  * integers get the smallest integer type that holds their min/max range.
+  * decimals get a DECIMAL(precision, scale) type fitting their integer digits &
+    max precision, or a double precision float if that's over 38 digits.
+  * strings get a VARCHAR(max length) type (TEXT for SQLite).
+  * dates & datetimes get DATE & TIMESTAMP types (DATETIME for MySQL, TEXT for SQLite).
+  * columns without empty values are NOT NULL.
+The table is named after the input file, and the statement is written to
+<input>.sql unless --output or --stdout is set.
+
+AVRO & ARROW:
+With `--format avro` or `--format arrow`, the inferred column types of a single input
+file are written as an Avro schema (.avsc) or an Arrow schema (in the JSON format of
+Arrow's integration tests) instead, so conversion tools (e.g. to Avro or Parquet) can
+use qsv's type inference as is:
+  * integers are int/long Avro types and Arrow int types sized from their min/max range.
+  * decimals are doubles, strings are strings/utf8, and booleans are booleans.
+  * dates & datetimes use the date & timestamp (microseconds) logical types.
+  * columns with empty values are nullable (a union with "null" in Avro).
+Avro names only allow letters, digits & underscores, so other characters of the column
+names are replaced by underscores, the original name being kept in the field's "doc".
+The record is named after the input file, and the schema is written to <input>.avsc or
+<input>.arrow-schema.json unless --output or --stdout is set.
+
+SAMPLING:
+With --sample or --sample-pct, the schema is inferred from a random sample of the records
+instead of computing stats over all of them - trading accuracy for speed when a draft
+schema of a huge file is all that's needed. If the input is indexed, only the sampled
+records are read. Otherwise, the input is read once, without loading it in memory.
+As the values outside the sample are not seen, the inferred types may be too narrow and
+the enum, minimum/maximum & length constraints too strict - review the schema before
+validating with it. Set --seed (or QSV_SEED) to sample the same records every time.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_schema.rs.
 
 Usage:
-    qsv schema [options] [<input>]
+    qsv schema [options] [<input>...]
     qsv schema --help
 
 Schema options:
-    --enum-threshold <num>     Cardinality threshold for adding enum constraints.
+    --enum-threshold <arg>     Cardinality threshold for adding enum constraints.
                                Enum constraints are compiled for String & Integer types.
+                               Per-column thresholds can follow the default threshold
+                               as <column>:<threshold> pairs, e.g. to use 10 for the
+                               status column: 50,status:10,country:300
                                [default: 50]
+    --no-enum <cols>           Select columns that never get enum constraints, like
+                               ID-like columns. Columns are selected using `select`
+                               syntax (see `qsv select --help` for details).
     -i, --ignore-case          Ignore case when compiling unique values for enum constraints.
                                Do note however that the `validate` command is case-sensitive
                                when validating against enum constraints.
@@ -60,7 +181,35 @@ Schema options:
                                Otherwise, use mdy format.
     --force                    Force recomputing cardinality and unique values
                                even if stats cache file exists and is current.
+    --format <format>          The format of the generated schema. One of "jsonschema",
+                               "datapackage" (a Frictionless Data Package descriptor)
+                               "sql" (a CREATE TABLE statement), "avro" (an Avro
+                               schema) or "arrow" (an Arrow schema).
+                               [default: jsonschema]
+    --dialect <dialect>        The SQL dialect of `--format sql`. One of postgres,
+                               sqlite, mysql or duckdb. [default: postgres]
+    --sample <n>               Infer the schema from a random sample of <n> records
+                               instead of all the records. See SAMPLING above.
+    --sample-pct <pct>         Infer the schema from a random sample of <pct> percent
+                               (greater than 0, up to 100) of the records.
+    --seed <number>            The RNG seed of --sample & --sample-pct, to infer the
+                               same schema every time. If not set, the QSV_SEED
+                               environment variable is used.
+    --compose                  In multi-file mode, factor the shared column definitions
+                               into `$defs` and compose per-file schemas with
+                               `$ref` & `allOf`. See MULTI-FILE INFERENCE above.
+    --update <file>            Merge the inferred schema into the existing JSON Schema
+                               <file>, keeping its manual curation, and write it back
+                               to <file>. See UPDATING A SCHEMA above.
+    --diff <old-schema>        Compare the inferred JSON Schema (or the <input> JSON
+                               Schema) against <old-schema> and report the changes
+                               instead of writing the schema. See SCHEMA DIFF above.
+    --diff-format <format>     The format of the --diff report, "human" or "json".
+                               [default: human]
     --stdout                   Send generated JSON schema file to stdout instead.
+    -o, --output <file>        Write the generated JSON schema to <file> instead of
+                               <input>.schema.json. Required in multi-file mode
+                               unless --stdout is set.
     -j, --jobs <arg>           The number of jobs to run in parallel.
                                When not set, the number of jobs is set to the
                                number of CPUs detected.
@@ -77,63 +226,448 @@ Common options:
                                CSV into memory using CONSERVATIVE heuristics.
 "#;
 
-use std::{fs::File, io::Write, path::Path};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use ahash::{AHashMap, AHashSet};
 use csv::ByteRecord;
 use grex::RegExpBuilder;
+use indexmap::IndexMap;
 use itertools::Itertools;
 use log::{debug, error, info, warn};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use rayon::slice::ParallelSliceMut;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, value::Number, Map, Value};
 use stats::Frequencies;
-
-use crate::{cmd::stats::StatsData, config::Config, util, util::StatsMode, CliResult};
+use strum_macros::EnumString;
+use tempfile::TempDir;
+
+use crate::{
+    cmd::{package, stats::StatsData},
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util,
+    util::StatsMode,
+    CliResult,
+};
 
 const STDIN_CSV: &str = "stdin.csv";
+const DATAPACKAGE_FILE: &str = "datapackage.json";
+
+#[derive(Deserialize, Clone)]
+struct Args {
+    flag_enum_threshold:  String,
+    flag_no_enum:         Option<SelectColumns>,
+    flag_ignore_case:     bool,
+    flag_strict_dates:    bool,
+    flag_pattern_columns: SelectColumns,
+    flag_dates_whitelist: String,
+    flag_prefer_dmy:      bool,
+    flag_force:           bool,
+    flag_format:          String,
+    flag_dialect:         String,
+    flag_compose:         bool,
+    flag_update:          Option<String>,
+    flag_diff:            Option<String>,
+    flag_diff_format:     String,
+    flag_sample:          Option<u64>,
+    flag_sample_pct:      Option<f64>,
+    flag_seed:            Option<u64>,
+    flag_stdout:          bool,
+    flag_output:          Option<String>,
+    flag_jobs:            Option<usize>,
+    flag_no_headers:      bool,
+    flag_delimiter:       Option<Delimiter>,
+    flag_memcheck:        bool,
+    arg_input:            Vec<PathBuf>,
+    /// the default --enum-threshold, and its per-column overrides
+    #[serde(skip)]
+    enum_threshold:       u64,
+    #[serde(skip)]
+    enum_threshold_overrides: Vec<(String, u64)>,
+}
+
+impl Args {
+    /// the per-file args used to infer the schema of `input`
+    fn schema_args(&self, input: Option<String>) -> util::SchemaArgs {
+        util::SchemaArgs {
+            flag_enum_threshold:  self.enum_threshold,
+            flag_ignore_case:     self.flag_ignore_case,
+            flag_strict_dates:    self.flag_strict_dates,
+            flag_pattern_columns: self.flag_pattern_columns.clone(),
+            flag_dates_whitelist: self.flag_dates_whitelist.clone(),
+            flag_prefer_dmy:      self.flag_prefer_dmy,
+            flag_force:           self.flag_force,
+            flag_stdout:          self.flag_stdout,
+            flag_jobs:            self.flag_jobs,
+            flag_no_headers:      self.flag_no_headers,
+            flag_delimiter:       self.flag_delimiter,
+            arg_input:            input,
+            flag_memcheck:        self.flag_memcheck,
+            flag_no_enum:         self.flag_no_enum.clone(),
+            enum_threshold_overrides: self.enum_threshold_overrides.clone(),
+        }
+    }
+
+    /// the per-file args used to infer the schema of `input` from a random sample of its
+    /// records if --sample or --sample-pct is set. The sample is written to the returned
+    /// temporary directory, which must be kept until the schema is inferred.
+    fn sampled_schema_args(&self, input: &str) -> CliResult<(util::SchemaArgs, Option<TempDir>)> {
+        let Some((sample_dir, sample_path)) = sample_input(self, input)? else {
+            return Ok((self.schema_args(Some(input.to_string())), None));
+        };
+        let mut schema_args = self.schema_args(Some(sample_path));
+        // the sample is written with a comma delimiter
+        schema_args.flag_delimiter = None;
+        Ok((schema_args, Some(sample_dir)))
+    }
+
+    /// multi-file mode is used if there is more than one input,
+    /// or if the single input is a directory or an .infile-list file
+    fn is_multi_input(&self) -> bool {
+        match self.arg_input.as_slice() {
+            [] => false,
+            [input] => {
+                input.is_dir()
+                    || input
+                        .extension()
+                        .and_then(std::ffi::OsStr::to_str)
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("infile-list"))
+            },
+            _ => true,
+        }
+    }
+
+    fn is_datapackage(&self) -> bool {
+        self.flag_format == "datapackage"
+    }
+
+    /// the formats generated from the stats of a single file
+    fn is_stats_format(&self) -> bool {
+        ["sql", "avro", "arrow"].contains(&self.flag_format.as_str())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum SqlDialect {
+    Postgres,
+    Sqlite,
+    Mysql,
+    Duckdb,
+}
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let mut args: util::SchemaArgs = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
+
+    // we can do this directly here, since args is mutable and
+    // Config has not been created yet at this point
+    args.flag_prefer_dmy = args.flag_prefer_dmy || util::get_envvar_flag("QSV_PREFER_DMY");
+    if args.flag_prefer_dmy {
+        winfo!("Prefer DMY set.");
+    }
+
+    args.flag_format = args.flag_format.to_lowercase();
+    if !["jsonschema", "datapackage", "sql", "avro", "arrow"].contains(&args.flag_format.as_str()) {
+        return fail_incorrectusage_clierror!(
+            "Invalid --format: {}. Must be one of jsonschema, datapackage, sql, avro or arrow.",
+            args.flag_format
+        );
+    }
+    let Ok(sql_dialect) = SqlDialect::from_str(&args.flag_dialect) else {
+        return fail_incorrectusage_clierror!(
+            "Invalid --dialect: {}. Must be one of postgres, sqlite, mysql or duckdb.",
+            args.flag_dialect
+        );
+    };
+
+    (args.enum_threshold, args.enum_threshold_overrides) =
+        match parse_enum_threshold(&args.flag_enum_threshold) {
+            Ok(thresholds) => thresholds,
+            Err(e) => return fail_incorrectusage_clierror!("Invalid --enum-threshold: {e}"),
+        };
+
+    args.flag_seed = util::rng_seed(args.flag_seed)?;
+    if args.flag_sample.is_some() && args.flag_sample_pct.is_some() {
+        return fail_incorrectusage_clierror!("--sample and --sample-pct are mutually exclusive.");
+    }
+    if args.flag_sample == Some(0) {
+        return fail_incorrectusage_clierror!("--sample must be greater than 0.");
+    }
+    if args
+        .flag_sample_pct
+        .is_some_and(|pct| !(pct > 0.0 && pct <= 100.0))
+    {
+        return fail_incorrectusage_clierror!(
+            "--sample-pct must be greater than 0 and at most 100."
+        );
+    }
+
+    if args.flag_compose && (!args.is_multi_input() || args.is_datapackage()) {
+        return fail_incorrectusage_clierror!(
+            "--compose only applies to JSON Schemas inferred from multiple files."
+        );
+    }
+
+    if args.flag_update.is_some() && (args.flag_format != "jsonschema" || args.flag_compose) {
+        return fail_incorrectusage_clierror!(
+            "--update only applies to JSON Schemas, and cannot be used with --compose."
+        );
+    }
+
+    if args.flag_diff.is_some() {
+        if args.flag_format != "jsonschema" || args.flag_compose {
+            return fail_incorrectusage_clierror!(
+                "--diff only applies to JSON Schemas, and cannot be used with --compose."
+            );
+        }
+        if !["human", "json"].contains(&args.flag_diff_format.as_str()) {
+            return fail_incorrectusage_clierror!(
+                "Invalid --diff-format: {}. Must be human or json.",
+                args.flag_diff_format
+            );
+        }
+        // compare two JSON Schema files
+        if let [input] = args.arg_input.as_slice() {
+            if input
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+            {
+                let schema = read_json_schema(&input.to_string_lossy())?;
+                return write_schema_diff(&args, &schema);
+            }
+        }
+    }
+
+    if args.is_multi_input() {
+        if args.is_stats_format() {
+            return fail_incorrectusage_clierror!(
+                "--format {} only supports a single input file.",
+                args.flag_format
+            );
+        }
+        return run_multi_file(&args);
+    }
 
     // if using stdin, we create a stdin.csv file as stdin is not seekable and we need to
     // open the file multiple times to compile stats/unique values, etc.
     // We use a fixed "stdin.csv" filename instead of a temporary file with random characters
     // so the name of the generated schema.json file is readable and predictable
     // (stdin.csv.schema.json)
-    let (input_path, input_filename) = if args.arg_input.is_none() {
+    let (input_path, input_filename) = if let Some(input) = args.arg_input.first() {
+        let filename = input.file_name().unwrap().to_string_lossy().to_string();
+        (input.to_string_lossy().to_string(), filename)
+    } else {
         let mut stdin_file = File::create(STDIN_CSV)?;
         let stdin = std::io::stdin();
         let mut stdin_handle = stdin.lock();
         std::io::copy(&mut stdin_handle, &mut stdin_file)?;
         drop(stdin_handle);
-        args.arg_input = Some(STDIN_CSV.to_string());
         (STDIN_CSV.to_string(), STDIN_CSV.to_string())
-    } else {
-        let filename = Path::new(args.arg_input.as_ref().unwrap())
-            .file_name()
-            .unwrap()
-            .to_string_lossy()
-            .to_string();
-        (args.arg_input.clone().unwrap(), filename)
     };
+    let (schema_args, _sample_dir) = args.sampled_schema_args(&input_path)?;
 
-    // we're loading the entire file into memory, we need to check avail mem
+    // we're loading the entire file (or its sample) into memory, we need to check avail mem
     util::mem_file_check(
-        &std::path::PathBuf::from(&input_path),
+        &std::path::PathBuf::from(schema_args.arg_input.as_deref().unwrap_or(&input_path)),
         false,
         args.flag_memcheck,
     )?;
 
-    // we can do this directly here, since args is mutable and
-    // Config has not been created yet at this point
-    args.flag_prefer_dmy = args.flag_prefer_dmy || util::get_envvar_flag("QSV_PREFER_DMY");
-    if args.flag_prefer_dmy {
-        winfo!("Prefer DMY set.");
+    if args.is_stats_format() {
+        let table_name = Path::new(&input_filename)
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let (csv_fields, csv_stats) = util::get_stats_records(&schema_args, StatsMode::Schema)?;
+        if args.flag_format == "sql" {
+            let ddl = create_table_sql(sql_dialect, &table_name, &csv_fields, &csv_stats)?;
+            let output_path = args
+                .flag_output
+                .clone()
+                .unwrap_or_else(|| input_path + ".sql");
+            return write_output(&args, &ddl, &output_path);
+        }
+        let (schema, extension) = if args.flag_format == "avro" {
+            (avro_schema(&table_name, &csv_fields, &csv_stats)?, ".avsc")
+        } else {
+            (arrow_schema(&csv_fields, &csv_stats)?, ".arrow-schema.json")
+        };
+        let output_path = args
+            .flag_output
+            .clone()
+            .unwrap_or_else(|| input_path + extension);
+        return write_schema(&args, &schema, &output_path);
+    }
+
+    let properties_map = infer_properties(&schema_args, &input_filename)?;
+
+    if args.is_datapackage() {
+        let output_path = args.flag_output.clone().unwrap_or_else(|| {
+            Path::new(&input_path)
+                .with_file_name(DATAPACKAGE_FILE)
+                .to_string_lossy()
+                .to_string()
+        });
+        let package = datapackage_json(
+            &args,
+            &[PathBuf::from(&input_path)],
+            &properties_map,
+            &output_path,
+        );
+        return write_schema(&args, &package, &output_path);
+    }
+
+    // generate list of required fields
+    let required_fields = get_required_fields(&properties_map);
+
+    let mut schema = schema_json(
+        &format!("JSON Schema for {input_filename}"),
+        properties_map,
+        required_fields,
+    );
+    if let Some(update_path) = &args.flag_update {
+        schema = update_schema(update_path, schema)?;
+    }
+    if args.flag_diff.is_some() {
+        return write_schema_diff(&args, &schema);
+    }
+
+    let output_path = args
+        .flag_output
+        .clone()
+        .or_else(|| args.flag_update.clone())
+        .unwrap_or_else(|| input_path + ".schema.json");
+    write_schema(&args, &schema, &output_path)?;
+
+    Ok(())
+}
+
+/// parse --enum-threshold, e.g. "50,status:10,country:300", into the default threshold
+/// and the per-column overrides
+fn parse_enum_threshold(arg: &str) -> Result<(u64, Vec<(String, u64)>), String> {
+    let parse_threshold = |threshold: &str| {
+        threshold
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| format!("{threshold} is not a valid threshold."))
+    };
+    let mut default_threshold: Option<u64> = None;
+    let mut overrides: Vec<(String, u64)> = Vec::new();
+    for part in arg.split(',') {
+        // column names can have colons, the threshold can't
+        if let Some((column, threshold)) = part.rsplit_once(':') {
+            if column.is_empty() {
+                return Err(format!("{part} has no column name."));
+            }
+            overrides.push((column.to_string(), parse_threshold(threshold)?));
+        } else if default_threshold.is_some() {
+            return Err("only one default threshold can be set.".to_string());
+        } else {
+            default_threshold = Some(parse_threshold(part)?);
+        }
+    }
+    Ok((default_threshold.unwrap_or(50), overrides))
+}
+
+/// write a random sample of the records of `input` to a `sample.csv` file with a comma
+/// delimiter in a temporary directory, if --sample or --sample-pct is set
+fn sample_input(args: &Args, input: &str) -> CliResult<Option<(TempDir, String)>> {
+    if args.flag_sample.is_none() && args.flag_sample_pct.is_none() {
+        return Ok(None);
+    }
+    let rconfig = Config::new(&Some(input.to_string()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let mut rng: StdRng = match args.flag_seed {
+        None => StdRng::from_rng(rand::thread_rng()).unwrap(),
+        Some(seed) => StdRng::seed_from_u64(seed), //DevSkim: ignore DS148264
+    };
+
+    let sample_dir = tempfile::tempdir()?;
+    let sample_path = sample_dir
+        .path()
+        .join("sample.csv")
+        .to_string_lossy()
+        .to_string();
+    let mut wtr = Config::new(&Some(sample_path.clone())).writer()?;
+    let mut sampled = 0_u64;
+
+    if let Some(mut idx) = rconfig.indexed()? {
+        // the index is present, so only the sampled records are read
+        let count = idx.count();
+        #[allow(clippy::cast_precision_loss)]
+        let sample_size = match args.flag_sample_pct {
+            Some(pct) => (count as f64 * pct / 100.0).ceil() as u64,
+            None => args.flag_sample.unwrap_or_default(),
+        }
+        .min(count);
+        rconfig.write_headers(&mut *idx, &mut wtr)?;
+        let mut indices: Vec<usize> =
+            rand::seq::index::sample(&mut rng, count as usize, sample_size as usize).into_vec();
+        // seek forward through the input
+        indices.sort_unstable();
+        for i in indices {
+            idx.seek(i as u64)?;
+            if let Some(record) = idx.byte_records().next() {
+                wtr.write_byte_record(&record?)?;
+                sampled += 1;
+            }
+        }
+    } else {
+        let mut rdr = rconfig.reader()?;
+        rconfig.write_headers(&mut rdr, &mut wtr)?;
+        if let Some(pct) = args.flag_sample_pct {
+            // each record is sampled with a probability of pct percent
+            for record in rdr.byte_records() {
+                let record = record?;
+                if rng.gen_bool(pct / 100.0) {
+                    wtr.write_byte_record(&record)?;
+                    sampled += 1;
+                }
+            }
+        } else {
+            // reservoir sampling, see https://en.wikipedia.org/wiki/Reservoir_sampling
+            let sample_size = args.flag_sample.unwrap_or_default() as usize;
+            let mut reservoir: Vec<ByteRecord> = Vec::new();
+            for (i, record) in rdr.byte_records().enumerate() {
+                let record = record?;
+                if i < sample_size {
+                    reservoir.push(record);
+                } else {
+                    let random = rng.gen_range(0..=i);
+                    if random < sample_size {
+                        reservoir[random] = record;
+                    }
+                }
+            }
+            for record in &reservoir {
+                wtr.write_byte_record(record)?;
+            }
+            sampled = reservoir.len() as u64;
+        }
     }
+    wtr.flush()?;
+
+    winfo!("Inferring schema from a sample of {sampled} records of {input}.");
+    Ok(Some((sample_dir, sample_path)))
+}
 
+/// infer the "properties" object of a file's JSON Schema,
+/// enriched with regex pattern constraints for the selected --pattern-columns
+fn infer_properties(
+    schema_args: &util::SchemaArgs,
+    input_filename: &str,
+) -> CliResult<Map<String, Value>> {
     // build schema for each field by their inferred type, min/max value/length, and unique values
     let mut properties_map: Map<String, Value> =
-        match infer_schema_from_stats(&args, &input_filename) {
+        match infer_schema_from_stats(schema_args, input_filename) {
             Ok(map) => map,
             Err(e) => {
                 return fail_clierror!(
@@ -143,7 +677,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         };
 
     // generate regex pattern for selected String columns
-    let pattern_map = generate_string_patterns(&args, &properties_map)?;
+    let pattern_map = generate_string_patterns(schema_args, &properties_map)?;
 
     // enrich properties map with pattern constraint for String fields
     for (field_name, field_def) in &mut properties_map {
@@ -156,40 +690,769 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
-    // generate list of required fields
-    let required_fields = get_required_fields(&properties_map);
+    Ok(properties_map)
+}
 
-    // create final JSON object for output
-    let schema = json!({
+/// create final JSON Schema object for output
+fn schema_json(title: &str, properties_map: Map<String, Value>, required: Vec<Value>) -> Value {
+    json!({
         "$schema": "https://json-schema.org/draft-07/schema",
-        "title": format!("JSON Schema for {input_filename}"),
+        "title": title,
         "description": "Inferred JSON Schema from QSV schema command",
         "type": "object",
         "properties": Value::Object(properties_map),
-        "required": Value::Array(required_fields)
+        "required": Value::Array(required)
+    })
+}
+
+/// read an existing JSON Schema file, for --update & --diff
+fn read_json_schema(path: &str) -> CliResult<Value> {
+    match fs::read_to_string(path) {
+        Ok(contents) => match serde_json::from_str(&contents) {
+            Ok(schema) => Ok(schema),
+            Err(e) => fail_incorrectusage_clierror!("Invalid JSON Schema {path}: {e}"),
+        },
+        Err(e) => fail_incorrectusage_clierror!("Cannot read {path}: {e}"),
+    }
+}
+
+/// the column keywords inferred from the data, refreshed by --update
+const INFERRED_KEYWORDS: &[&str] = &[
+    "type",
+    "minimum",
+    "maximum",
+    "minLength",
+    "maxLength",
+    "enum",
+    "x-date-format",
+];
+
+/// merge an inferred JSON Schema into the existing JSON Schema file of --update,
+/// keeping its hand-written keywords while refreshing the inferred ones
+fn update_schema(update_path: &str, inferred: Value) -> CliResult<Value> {
+    let Value::Object(mut schema) = read_json_schema(update_path)? else {
+        return fail_incorrectusage_clierror!(
+            "Invalid JSON Schema {update_path}: expected an object."
+        );
+    };
+    let existing_properties = match schema.get("properties") {
+        Some(Value::Object(properties)) => properties.clone(),
+        None => Map::new(),
+        Some(_) => {
+            return fail_incorrectusage_clierror!(
+                "Invalid JSON Schema {update_path}: \"properties\" is not an object."
+            )
+        },
+    };
+    let existing_required: Vec<String> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| {
+            required
+                .iter()
+                .filter_map(|field| field.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let Value::Object(inferred) = inferred else {
+        unreachable!("the inferred schema is an object");
+    };
+
+    let mut properties = Map::new();
+    if let Some(Value::Object(inferred_properties)) = inferred.get("properties") {
+        for (field_name, inferred_def) in inferred_properties {
+            let field_def = match existing_properties.get(field_name) {
+                Some(Value::Object(existing_def)) if existing_def.contains_key("$ref") => {
+                    return fail_incorrectusage_clierror!(
+                        "--update doesn't support the $ref definition of column \
+                         {field_name} in {update_path}."
+                    );
+                },
+                Some(Value::Object(existing_def)) => {
+                    update_field_def(existing_def, inferred_def.as_object().unwrap())
+                },
+                _ => {
+                    winfo!("Added column: {field_name}");
+                    inferred_def.clone()
+                },
+            };
+            properties.insert(field_name.clone(), field_def);
+        }
+    }
+    for field_name in existing_properties.keys() {
+        if !properties.contains_key(field_name) {
+            wwarn!("Removed column no longer in the data: {field_name}");
+        }
+    }
+
+    // columns made optional by hand stay optional
+    let required: Vec<Value> = inferred
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| {
+            required
+                .iter()
+                .filter(|field| {
+                    let field_name = field.as_str().unwrap_or_default();
+                    !existing_properties.contains_key(field_name)
+                        || existing_required.iter().any(|name| name == field_name)
+                })
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // the root keywords of the existing schema (e.g. its title) are kept
+    for (keyword, value) in inferred {
+        schema.entry(keyword).or_insert(value);
+    }
+    schema.insert("properties".to_string(), Value::Object(properties));
+    schema.insert("required".to_string(), Value::Array(required));
+    Ok(Value::Object(schema))
+}
+
+/// refresh the inferred keywords of an existing column definition, keeping its
+/// hand-written description, custom constraints & other keywords
+fn update_field_def(existing_def: &Map<String, Value>, inferred_def: &Map<String, Value>) -> Value {
+    let mut field_map: Map<String, Value> = Map::with_capacity(existing_def.len());
+    for (keyword, value) in existing_def {
+        let refreshed = match keyword.as_str() {
+            // date formats are inferred, other formats (e.g. "email") are custom constraints
+            "format" if matches!(value.as_str(), Some("date" | "date-time")) => {
+                inferred_def.get(keyword)
+            },
+            // patterns are only inferred for --pattern-columns
+            "pattern" => inferred_def.get(keyword).or(Some(value)),
+            keyword if INFERRED_KEYWORDS.contains(&keyword) => inferred_def.get(keyword),
+            _ => Some(value),
+        };
+        if let Some(refreshed) = refreshed {
+            field_map.insert(keyword.clone(), refreshed.clone());
+        }
+    }
+    for (keyword, value) in inferred_def {
+        // newly inferred keywords
+        if !existing_def.contains_key(keyword) {
+            field_map.insert(keyword.clone(), value.clone());
+        }
+    }
+    Value::Object(field_map)
+}
+
+/// a change of a column between two JSON Schemas, reported by --diff
+#[derive(Serialize)]
+struct SchemaChange {
+    column:  String,
+    /// added, removed, type, tightened, loosened or changed
+    change:  &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keyword: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old:     Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new:     Option<Value>,
+}
+
+/// the non-null types of a column definition, and whether it's nullable
+fn column_types(field_def: &Value) -> (Vec<&str>, bool) {
+    let types: Vec<&str> = match field_def.get("type") {
+        Some(Value::String(field_type)) => vec![field_type.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let nullable = types.contains(&"null");
+    let mut types: Vec<&str> = types.into_iter().filter(|t| *t != "null").collect();
+    types.sort_unstable();
+    (types, nullable)
+}
+
+/// the changes of the columns of the `new` JSON Schema from the `old` one
+fn diff_schemas(old: &Value, new: &Value) -> Vec<SchemaChange> {
+    let empty = Map::new();
+    let old_properties = old["properties"].as_object().unwrap_or(&empty);
+    let new_properties = new["properties"].as_object().unwrap_or(&empty);
+    let is_required = |schema: &Value, column: &str| {
+        schema["required"]
+            .as_array()
+            .is_some_and(|required| required.iter().any(|field| field == column))
+    };
+
+    let mut changes = Vec::new();
+    let mut change = |column: &str, kind, keyword, old: Option<&Value>, new: Option<&Value>| {
+        changes.push(SchemaChange {
+            column: column.to_string(),
+            change: kind,
+            keyword,
+            old: old.cloned(),
+            new: new.cloned(),
+        });
+    };
+
+    for column in old_properties.keys() {
+        if !new_properties.contains_key(column) {
+            change(column, "removed", None, None, None);
+        }
+    }
+    for (column, new_def) in new_properties {
+        let Some(old_def) = old_properties.get(column) else {
+            change(column, "added", None, None, None);
+            continue;
+        };
+
+        let (old_types, old_nullable) = column_types(old_def);
+        let (new_types, new_nullable) = column_types(new_def);
+        if old_types != new_types {
+            change(
+                column,
+                "type",
+                Some("type"),
+                old_def.get("type"),
+                new_def.get("type"),
+            );
+        }
+        if old_nullable != new_nullable {
+            let old_value = Value::Bool(old_nullable);
+            let new_value = Value::Bool(new_nullable);
+            let kind = if new_nullable {
+                "loosened"
+            } else {
+                "tightened"
+            };
+            change(
+                column,
+                kind,
+                Some("nullable"),
+                Some(&old_value),
+                Some(&new_value),
+            );
+        }
+        let (old_required, new_required) = (is_required(old, column), is_required(new, column));
+        if old_required != new_required {
+            let old_value = Value::Bool(old_required);
+            let new_value = Value::Bool(new_required);
+            let kind = if new_required {
+                "tightened"
+            } else {
+                "loosened"
+            };
+            change(
+                column,
+                kind,
+                Some("required"),
+                Some(&old_value),
+                Some(&new_value),
+            );
+        }
+
+        // bounds, with whether a greater value is a tighter constraint
+        for (keyword, greater_is_tighter) in [
+            ("minimum", true),
+            ("minLength", true),
+            ("maximum", false),
+            ("maxLength", false),
+        ] {
+            let (old_bound, new_bound) = (old_def.get(keyword), new_def.get(keyword));
+            let kind = match (
+                old_bound.and_then(Value::as_f64),
+                new_bound.and_then(Value::as_f64),
+            ) {
+                (None, None) => continue,
+                (Some(_), None) => "loosened",
+                (None, Some(_)) => "tightened",
+                (Some(old_value), Some(new_value)) if new_value > old_value => {
+                    if greater_is_tighter {
+                        "tightened"
+                    } else {
+                        "loosened"
+                    }
+                },
+                (Some(old_value), Some(new_value)) if new_value < old_value => {
+                    if greater_is_tighter {
+                        "loosened"
+                    } else {
+                        "tightened"
+                    }
+                },
+                _ => continue,
+            };
+            change(column, kind, Some(keyword), old_bound, new_bound);
+        }
+
+        let (old_enum, new_enum) = (old_def.get("enum"), new_def.get("enum"));
+        let kind = match (
+            old_enum.and_then(Value::as_array),
+            new_enum.and_then(Value::as_array),
+        ) {
+            (None, None) => None,
+            (Some(_), None) => Some("loosened"),
+            (None, Some(_)) => Some("tightened"),
+            (Some(old_values), Some(new_values)) => {
+                let is_subset =
+                    |values: &[Value], of: &[Value]| values.iter().all(|value| of.contains(value));
+                match (
+                    is_subset(new_values, old_values),
+                    is_subset(old_values, new_values),
+                ) {
+                    (true, true) => None,
+                    (true, false) => Some("tightened"),
+                    (false, true) => Some("loosened"),
+                    (false, false) => Some("changed"),
+                }
+            },
+        };
+        if let Some(kind) = kind {
+            change(column, kind, Some("enum"), old_enum, new_enum);
+        }
+
+        for keyword in ["pattern", "format", "x-date-format"] {
+            let (old_value, new_value) = (old_def.get(keyword), new_def.get(keyword));
+            let kind = match (old_value, new_value) {
+                (Some(_), None) => "loosened",
+                (None, Some(_)) => "tightened",
+                (Some(old_value), Some(new_value)) if old_value != new_value => "changed",
+                _ => continue,
+            };
+            change(column, kind, Some(keyword), old_value, new_value);
+        }
+    }
+    changes
+}
+
+/// compare a JSON Schema against the --diff schema, and write the changes
+/// in the --diff-format to stdout or --output
+fn write_schema_diff(args: &Args, schema: &Value) -> CliResult<()> {
+    // safety: --diff is set
+    let old_schema = read_json_schema(args.flag_diff.as_deref().unwrap())?;
+    let changes = diff_schemas(&old_schema, schema);
+
+    let report = if args.flag_diff_format == "json" {
+        match serde_json::to_string_pretty(&changes) {
+            Ok(report) => report + "\n",
+            Err(e) => return fail_clierror!("Cannot serialize the schema diff: {e}"),
+        }
+    } else if changes.is_empty() {
+        "No schema changes.\n".to_string()
+    } else {
+        let display = |value: &Option<Value>| {
+            value
+                .as_ref()
+                .map_or_else(|| "(none)".to_string(), Value::to_string)
+        };
+        let mut report = String::new();
+        for change in &changes {
+            report.push_str(&format!("{:<10} {}", change.change, change.column));
+            if let Some(keyword) = change.keyword {
+                report.push_str(&format!(
+                    ": {keyword} {} -> {}",
+                    display(&change.old),
+                    display(&change.new)
+                ));
+            }
+            report.push('\n');
+        }
+        report
+    };
+
+    if let Some(output_path) = &args.flag_output {
+        fs::write(output_path, report)?;
+    } else {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(report.as_bytes())?;
+        stdout.flush()?;
+    }
+    winfo!("{} schema change/s found.", changes.len());
+    Ok(())
+}
+
+/// create a Frictionless Data Package descriptor with a tabular data resource
+/// for each input, all sharing the Table Schema derived from the JSON Schema properties
+fn datapackage_json(
+    args: &Args,
+    inputs: &[PathBuf],
+    properties_map: &Map<String, Value>,
+    output_path: &str,
+) -> Value {
+    let fields: Vec<Value> = properties_map
+        .iter()
+        .map(|(field_name, field_def)| table_schema_field(field_name, field_def))
+        .collect();
+
+    let package_dir = match Path::new(output_path).parent() {
+        Some(dir) if !args.flag_stdout && !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    // resource names must be unique in a package
+    let mut names = AHashSet::new();
+    let mut resources = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let base_name =
+            package::frictionless_name(&input.file_stem().unwrap_or_default().to_string_lossy());
+        let mut name = base_name.clone();
+        let mut suffix = 1;
+        while !names.insert(name.clone()) {
+            suffix += 1;
+            name = format!("{base_name}-{suffix}");
+        }
+
+        let delimiter = Config::new(&Some(input.to_string_lossy().to_string()))
+            .delimiter(args.flag_delimiter)
+            .get_delimiter();
+        let mediatype = if delimiter == b'\t' {
+            "text/tab-separated-values"
+        } else {
+            "text/csv"
+        };
+        let format = input
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        resources.push(json!({
+            "name": name,
+            "path": datapackage_resource_path(input, package_dir),
+            "profile": "tabular-data-resource",
+            "format": format,
+            "mediatype": mediatype,
+            "encoding": "utf-8",
+            "dialect": {
+                "delimiter": (delimiter as char).to_string(),
+                "header": !args.flag_no_headers,
+            },
+            "schema": {
+                "fields": fields,
+                "missingValues": [""],
+            },
+        }));
+    }
+
+    let package_name = match inputs {
+        [input] => input.file_stem().unwrap_or_default().to_string_lossy(),
+        _ => Path::new(output_path)
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy(),
+    };
+    json!({
+        "profile": "tabular-data-package",
+        "name": package::frictionless_name(&package_name),
+        "description": "Inferred Data Package from QSV schema command",
+        "resources": resources,
+    })
+}
+
+/// the path of an input in a Data Package - relative to the package's directory
+/// if the input is in it, otherwise the input path as given
+fn datapackage_resource_path(input: &Path, package_dir: &Path) -> String {
+    if let (Ok(input_path), Ok(dir)) = (fs::canonicalize(input), fs::canonicalize(package_dir)) {
+        if let Ok(rel_path) = input_path.strip_prefix(dir) {
+            return package::resource_path(rel_path);
+        }
+    }
+    package::resource_path(input)
+}
+
+/// convert a JSON Schema field definition to a Table Schema field descriptor
+/// See https://specs.frictionlessdata.io/table-schema/
+fn table_schema_field(field_name: &str, field_def: &Value) -> Value {
+    let empty_def = Map::new();
+    let def = field_def.as_object().unwrap_or(&empty_def);
+
+    // a single detected date format that's also a valid Table Schema (i.e. Python strptime)
+    // pattern, unlike chrono's fractional seconds & colon-separated offsets
+    let date_pattern = def
+        .get("x-date-format")
+        .and_then(Value::as_str)
+        .filter(|pattern| !pattern.contains("%.f") && !pattern.contains("%:z"));
+
+    let field_type = match (base_type(def), def.get("format").and_then(Value::as_str)) {
+        ("integer", _) => "integer",
+        ("number", _) => "number",
+        ("boolean", _) => "boolean",
+        ("string", Some("date")) => "date",
+        ("string", Some("date-time")) => "datetime",
+        ("null", _) => "any",
+        _ => match date_pattern {
+            Some(pattern) if pattern.contains("%H") || pattern.contains("%I") => "datetime",
+            Some(_) => "date",
+            None => "string",
+        },
+    };
+    let mut field = json!({
+        "name": field_name,
+        "type": field_type,
     });
+    if let Some(pattern) = date_pattern {
+        field["format"] = Value::String(pattern.to_string());
+    }
+    if let Some(description) = def.get("description") {
+        field["description"] = description.clone();
+    }
+
+    let mut constraints = Map::new();
+    let required = def
+        .get("type")
+        .and_then(Value::as_array)
+        .is_some_and(|types| !types.contains(&Value::String("null".to_string())));
+    if required {
+        constraints.insert("required".to_string(), Value::Bool(true));
+    }
+    // the JSON Schema & Table Schema constraints have the same names
+    for key in ["minimum", "maximum", "minLength", "maxLength", "pattern"] {
+        if let Some(value) = def.get(key) {
+            constraints.insert(key.to_string(), value.clone());
+        }
+    }
+    if let Some(values) = def.get("enum").and_then(Value::as_array) {
+        let enum_list: Vec<Value> = values.iter().filter(|v| !v.is_null()).cloned().collect();
+        constraints.insert("enum".to_string(), Value::Array(enum_list));
+    }
+    if !constraints.is_empty() {
+        field["constraints"] = Value::Object(constraints);
+    }
+    field
+}
+
+/// create the CREATE TABLE statement of a file, with column types sized from its stats.
+/// Also used by the commands that load CSVs into databases
+pub fn create_table_sql(
+    dialect: SqlDialect,
+    table_name: &str,
+    csv_fields: &ByteRecord,
+    csv_stats: &[StatsData],
+) -> CliResult<String> {
+    let mut columns: Vec<String> = Vec::with_capacity(csv_fields.len());
+    for (header, stats_record) in csv_fields.iter().zip(csv_stats) {
+        let column_name = convert_to_string(header)?;
+        let mut column = format!(
+            "    {} {}",
+            sql_identifier(dialect, &column_name),
+            sql_column_type(dialect, stats_record)
+        );
+        if !is_nullable(stats_record) {
+            column.push_str(" NOT NULL");
+        }
+        columns.push(column);
+    }
+    Ok(format!(
+        "CREATE TABLE {} (\n{}\n);\n",
+        sql_identifier(dialect, table_name),
+        columns.join(",\n")
+    ))
+}
+
+/// quote an SQL identifier, escaping its quotes
+pub fn sql_identifier(dialect: SqlDialect, name: &str) -> String {
+    if dialect == SqlDialect::Mysql {
+        format!("`{}`", name.replace('`', "``"))
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// the SQL type of a column, sized from its stats
+fn sql_column_type(dialect: SqlDialect, stats_record: &StatsData) -> String {
+    let text_type = match dialect {
+        SqlDialect::Duckdb => "VARCHAR",
+        _ => "TEXT",
+    };
+    let double_type = match dialect {
+        SqlDialect::Postgres => "DOUBLE PRECISION",
+        SqlDialect::Sqlite => "REAL",
+        SqlDialect::Mysql | SqlDialect::Duckdb => "DOUBLE",
+    };
+
+    match stats_record.r#type.as_str() {
+        "Integer" if dialect == SqlDialect::Sqlite => "INTEGER".to_string(),
+        "Integer" => match integer_bits(stats_record) {
+            16 => "SMALLINT",
+            32 if dialect == SqlDialect::Mysql => "INT",
+            32 => "INTEGER",
+            _ => "BIGINT",
+        }
+        .to_string(),
+        "Float" if dialect == SqlDialect::Sqlite => double_type.to_string(),
+        "Float" => {
+            let float_bound = |bound: &Option<String>| {
+                bound
+                    .as_deref()
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .map_or(0.0, f64::abs)
+            };
+            let max_abs = float_bound(&stats_record.min).max(float_bound(&stats_record.max));
+            let scale = stats_record.max_precision.unwrap_or_default();
+            // the number of digits before the decimal point, at least one
+            let integer_digits = if max_abs >= 1.0 {
+                max_abs.log10().floor() as u32 + 1
+            } else {
+                1
+            };
+            let precision = integer_digits + scale;
+            if scale == 0 || precision > 38 {
+                double_type.to_string()
+            } else if dialect == SqlDialect::Postgres {
+                format!("NUMERIC({precision}, {scale})")
+            } else {
+                format!("DECIMAL({precision}, {scale})")
+            }
+        },
+        "String" => match (dialect, stats_record.max_length) {
+            (SqlDialect::Sqlite, _) => text_type.to_string(),
+            (SqlDialect::Duckdb, _) => "VARCHAR".to_string(),
+            // longer VARCHARs don't fit in a MySQL row with utf8mb4
+            (SqlDialect::Mysql, Some(max_length)) if max_length > 16_383 => "TEXT".to_string(),
+            (_, Some(max_length)) if max_length > 0 => format!("VARCHAR({max_length})"),
+            _ => text_type.to_string(),
+        },
+        "Boolean" if dialect == SqlDialect::Sqlite => "INTEGER".to_string(),
+        "Boolean" => "BOOLEAN".to_string(),
+        "Date" if dialect == SqlDialect::Sqlite => text_type.to_string(),
+        "Date" => "DATE".to_string(),
+        "DateTime" => match dialect {
+            SqlDialect::Sqlite => text_type.to_string(),
+            SqlDialect::Mysql => "DATETIME".to_string(),
+            SqlDialect::Postgres | SqlDialect::Duckdb => "TIMESTAMP".to_string(),
+        },
+        _ => text_type.to_string(),
+    }
+}
+
+/// the number of bits (16, 32 or 64) of the smallest signed integer type
+/// that holds the min/max range of an integer column
+fn integer_bits(stats_record: &StatsData) -> u8 {
+    let int_bound = |bound: &Option<String>| {
+        bound
+            .as_deref()
+            .and_then(|value| atoi_simd::parse::<i64>(value.as_bytes()).ok())
+    };
+    let (Some(min), Some(max)) = (int_bound(&stats_record.min), int_bound(&stats_record.max))
+    else {
+        return 64;
+    };
+    if min >= i64::from(i16::MIN) && max <= i64::from(i16::MAX) {
+        16
+    } else if min >= i64::from(i32::MIN) && max <= i64::from(i32::MAX) {
+        32
+    } else {
+        64
+    }
+}
+
+/// whether a column has empty values, and so must be nullable
+fn is_nullable(stats_record: &StatsData) -> bool {
+    stats_record.nullcount > 0 || stats_record.r#type == "NULL"
+}
+
+/// an Avro name, only made of letters, digits & underscores and not starting with a digit
+fn avro_name(name: &str) -> String {
+    let mut avro_name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if avro_name.is_empty() || avro_name.starts_with(|c: char| c.is_ascii_digit()) {
+        avro_name.insert(0, '_');
+    }
+    avro_name
+}
+
+/// create the Avro schema of a file, a record with a field per column
+fn avro_schema(
+    record_name: &str,
+    csv_fields: &ByteRecord,
+    csv_stats: &[StatsData],
+) -> CliResult<Value> {
+    let mut field_names: AHashSet<String> = AHashSet::with_capacity(csv_fields.len());
+    let mut fields: Vec<Value> = Vec::with_capacity(csv_fields.len());
+    for (header, stats_record) in csv_fields.iter().zip(csv_stats) {
+        let column_name = convert_to_string(header)?;
+        // field names must be unique once sanitized
+        let base_name = avro_name(&column_name);
+        let mut field_name = base_name.clone();
+        let mut suffix = 1;
+        while field_names.contains(&field_name) {
+            suffix += 1;
+            field_name = format!("{base_name}_{suffix}");
+        }
+        field_names.insert(field_name.clone());
+
+        let avro_type = match stats_record.r#type.as_str() {
+            "Integer" if integer_bits(stats_record) < 64 => json!("int"),
+            "Integer" => json!("long"),
+            "Float" => json!("double"),
+            "Boolean" => json!("boolean"),
+            "Date" => json!({"type": "int", "logicalType": "date"}),
+            "DateTime" => json!({"type": "long", "logicalType": "timestamp-micros"}),
+            "NULL" => json!("null"),
+            _ => json!("string"),
+        };
+        let mut field = if !is_nullable(stats_record) || avro_type == "null" {
+            json!({"name": field_name, "type": avro_type})
+        } else {
+            json!({"name": field_name, "type": ["null", avro_type], "default": null})
+        };
+        if field_name != column_name {
+            field["doc"] = json!(column_name);
+        }
+        fields.push(field);
+    }
+    Ok(json!({
+        "type": "record",
+        "name": avro_name(record_name),
+        "fields": fields
+    }))
+}
+
+/// create the Arrow schema of a file, in the JSON format of Arrow's integration tests
+fn arrow_schema(csv_fields: &ByteRecord, csv_stats: &[StatsData]) -> CliResult<Value> {
+    let mut fields: Vec<Value> = Vec::with_capacity(csv_fields.len());
+    for (header, stats_record) in csv_fields.iter().zip(csv_stats) {
+        let arrow_type = match stats_record.r#type.as_str() {
+            "Integer" => {
+                json!({"name": "int", "bitWidth": integer_bits(stats_record), "isSigned": true})
+            },
+            "Float" => json!({"name": "floatingpoint", "precision": "DOUBLE"}),
+            "Boolean" => json!({"name": "bool"}),
+            "Date" => json!({"name": "date", "unit": "DAY"}),
+            "DateTime" => json!({"name": "timestamp", "unit": "MICROSECOND"}),
+            "NULL" => json!({"name": "null"}),
+            _ => json!({"name": "utf8"}),
+        };
+        fields.push(json!({
+            "name": convert_to_string(header)?,
+            "nullable": is_nullable(stats_record),
+            "type": arrow_type,
+            "children": []
+        }));
+    }
+    Ok(json!({ "fields": fields }))
+}
 
-    let schema_pretty = match serde_json::to_string_pretty(&schema) {
+/// write the schema to stdout if --stdout is set, otherwise to output_path
+fn write_schema(args: &Args, schema: &Value, output_path: &str) -> CliResult<()> {
+    let schema_pretty = match serde_json::to_string_pretty(schema) {
         Ok(s) => s,
         Err(e) => return fail_clierror!("Cannot prettify schema json: {e}"),
     };
+    write_output(args, &schema_pretty, output_path)
+}
 
+/// write the generated schema/DDL to stdout if --stdout is set, otherwise to output_path
+fn write_output(args: &Args, output: &str, output_path: &str) -> CliResult<()> {
     if args.flag_stdout {
         let stdout = std::io::stdout();
         let mut handle = stdout.lock();
 
-        handle.write_all(schema_pretty.as_bytes())?;
+        handle.write_all(output.as_bytes())?;
         handle.flush()?;
 
         info!("Schema written to stdout");
     } else {
-        let schema_output_filename = input_path + ".schema.json";
-        let mut schema_output_file = File::create(&schema_output_filename)?;
+        let mut schema_output_file = File::create(output_path)?;
 
-        schema_output_file.write_all(schema_pretty.as_bytes())?;
+        schema_output_file.write_all(output.as_bytes())?;
         schema_output_file.flush()?;
 
-        woutinfo!("Schema written to {schema_output_filename}");
+        woutinfo!("Schema written to {output_path}");
     }
 
     Ok(())
@@ -210,6 +1473,22 @@ pub fn infer_schema_from_stats(
     // invoke cmd::stats
     let (csv_fields, csv_stats) = util::get_stats_records(args, StatsMode::Schema)?;
 
+    // the enum cardinality threshold of each column, 0 for the --no-enum columns
+    let no_enum_columns: Vec<usize> = match args.flag_no_enum {
+        Some(ref no_enum) => no_enum
+            .selection(&csv_fields, !args.flag_no_headers)?
+            .to_vec(),
+        None => Vec::new(),
+    };
+    let mut enum_thresholds: Vec<u64> = Vec::with_capacity(csv_fields.len());
+    for (i, header) in csv_fields.iter().enumerate() {
+        enum_thresholds.push(if no_enum_columns.contains(&i) {
+            0
+        } else {
+            args.enum_threshold(&convert_to_string(header)?)
+        });
+    }
+
     // amortize memory allocation
     let mut low_cardinality_column_indices: Vec<u64> =
         Vec::with_capacity(args.flag_enum_threshold as usize);
@@ -217,13 +1496,17 @@ pub fn infer_schema_from_stats(
     // build column selector arg to invoke cmd::frequency with
     let column_select_arg: String = build_low_cardinality_column_selector_arg(
         &mut low_cardinality_column_indices,
-        args.flag_enum_threshold,
+        &enum_thresholds,
         &csv_fields,
         &csv_stats,
     );
 
     // invoke cmd::frequency to get unique values for each field
-    let unique_values_map = get_unique_values(args, &column_select_arg)?;
+    let enum_limit = enum_thresholds.iter().copied().max().unwrap_or_default();
+    let unique_values_map = get_unique_values(args, &column_select_arg, enum_limit)?;
+
+    // the strftime patterns of the date & datetime columns
+    let date_formats = detect_date_formats(args, &csv_stats)?;
 
     // map holds "properties" object of json schema
     let mut properties_map: Map<String, Value> = Map::with_capacity(csv_fields.len());
@@ -345,18 +1628,34 @@ pub fn infer_schema_from_stats(
             "NULL" => {
                 type_list.push(Value::String("null".to_string()));
             },
-            "Date" => {
+            "Date" | "DateTime" => {
                 type_list.push(Value::String("string".to_string()));
 
-                if args.flag_strict_dates {
-                    field_map.insert("format".to_string(), Value::String("date".to_string()));
+                let formats = date_formats.get(&i);
+                let format = if args.flag_strict_dates {
+                    Some(if col_type == "Date" {
+                        "date"
+                    } else {
+                        "date-time"
+                    })
+                } else {
+                    formats.and_then(|formats| rfc3339_format(formats))
+                };
+                if let Some(format) = format {
+                    field_map.insert("format".to_string(), Value::String(format.to_string()));
                 }
-            },
-            "DateTime" => {
-                type_list.push(Value::String("string".to_string()));
 
-                if args.flag_strict_dates {
-                    field_map.insert("format".to_string(), Value::String("date-time".to_string()));
+                match formats.map(Vec::as_slice) {
+                    Some([pattern]) => {
+                        field_map.insert(
+                            "x-date-format".to_string(),
+                            Value::String((*pattern).to_string()),
+                        );
+                    },
+                    Some(patterns) => {
+                        field_map.insert("x-date-format".to_string(), json!(patterns));
+                    },
+                    None => {},
                 }
             },
             _ => {
@@ -397,10 +1696,145 @@ pub fn infer_schema_from_stats(
     Ok(properties_map)
 }
 
+/// the strftime patterns `schema` detects in date & datetime columns, with whether they put
+/// the day before the month. Two-digit year patterns come before their four-digit year
+/// counterparts, as %Y also parses two-digit years.
+const DATE_FORMATS: &[(&str, bool)] = &[
+    ("%Y-%m-%d", false),
+    ("%Y/%m/%d", false),
+    ("%m/%d/%y", false),
+    ("%d/%m/%y", true),
+    ("%m/%d/%Y", false),
+    ("%d/%m/%Y", true),
+    ("%m-%d-%Y", false),
+    ("%d-%m-%Y", true),
+    ("%d.%m.%Y", true),
+    ("%b %d, %Y", false),
+    ("%B %d, %Y", false),
+    ("%d %b %Y", true),
+    ("%d %B %Y", true),
+    ("%Y-%m-%dT%H:%M:%S%.f%:z", false),
+    ("%Y-%m-%dT%H:%M:%S%.fZ", false),
+    ("%Y-%m-%dT%H:%M:%S%.f", false),
+    ("%Y-%m-%d %H:%M:%S%.f%:z", false),
+    ("%Y-%m-%d %H:%M:%S%.f", false),
+    ("%Y-%m-%d %H:%M", false),
+    ("%Y/%m/%d %H:%M:%S", false),
+    ("%m/%d/%y %H:%M", false),
+    ("%d/%m/%y %H:%M", true),
+    ("%m/%d/%Y %H:%M:%S", false),
+    ("%d/%m/%Y %H:%M:%S", true),
+    ("%m/%d/%Y %H:%M", false),
+    ("%d/%m/%Y %H:%M", true),
+    ("%m/%d/%Y %I:%M:%S %p", false),
+    ("%m/%d/%Y %I:%M %p", false),
+    ("%a, %d %b %Y %H:%M:%S %z", false),
+];
+
+/// detect the strftime patterns of the date & datetime columns, keyed by column index.
+/// A column gets the preferred pattern that parses all its values if there's one,
+/// otherwise the fewest patterns that do, by descending frequency. Columns with values
+/// that none of the DATE_FORMATS parse get no patterns.
+fn detect_date_formats(
+    args: &util::SchemaArgs,
+    csv_stats: &[StatsData],
+) -> CliResult<AHashMap<usize, Vec<&'static str>>> {
+    let date_columns: Vec<usize> = csv_stats
+        .iter()
+        .enumerate()
+        .filter(|(_, stats)| stats.r#type == "Date" || stats.r#type == "DateTime")
+        .map(|(i, _)| i)
+        .collect();
+    if date_columns.is_empty() {
+        return Ok(AHashMap::new());
+    }
+
+    // the DATE_FORMATS indices by preference, e.g. dmy patterns first with --prefer-dmy
+    let mut preference: Vec<usize> = (0..DATE_FORMATS.len()).collect();
+    preference.sort_by_key(|&idx| (DATE_FORMATS[idx].1 != args.flag_prefer_dmy, idx));
+
+    // the number of values of each column that parse with each set of patterns,
+    // as bitmasks of the DATE_FORMATS indices
+    let mut pattern_sets: Vec<AHashMap<u64, u64>> = vec![AHashMap::new(); date_columns.len()];
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let mut rdr = rconfig.reader()?;
+    let mut record = csv::ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        for (col, counts) in date_columns.iter().zip(pattern_sets.iter_mut()) {
+            let value = String::from_utf8_lossy(record.get(*col).unwrap_or_default());
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            let mask = DATE_FORMATS
+                .iter()
+                .enumerate()
+                .filter(|(_, (pattern, _))| util::matches_date_format(value, pattern))
+                .fold(0_u64, |mask, (idx, _)| mask | (1_u64 << idx));
+            *counts.entry(mask).or_insert(0) += 1;
+        }
+    }
+
+    let mut date_formats: AHashMap<usize, Vec<&'static str>> = AHashMap::new();
+    for (col, mut counts) in date_columns.into_iter().zip(pattern_sets) {
+        if counts.is_empty() || counts.contains_key(&0) {
+            continue;
+        }
+        let mut patterns: Vec<&'static str> = Vec::new();
+        while !counts.is_empty() {
+            let common = counts.keys().fold(u64::MAX, |common, mask| common & mask);
+            let idx = if common == 0 {
+                // the pattern that parses the most remaining values
+                // safety: the pattern sets aren't empty, so there's a best pattern
+                *preference
+                    .iter()
+                    .rev()
+                    .max_by_key(|&&idx| {
+                        counts
+                            .iter()
+                            .filter(|(mask, _)| *mask & (1_u64 << idx) != 0)
+                            .map(|(_, count)| count)
+                            .sum::<u64>()
+                    })
+                    .unwrap()
+            } else {
+                // safety: common has a bit set, so a preferred pattern is in it
+                *preference
+                    .iter()
+                    .find(|&&idx| common & (1_u64 << idx) != 0)
+                    .unwrap()
+            };
+            patterns.push(DATE_FORMATS[idx].0);
+            counts.retain(|mask, _| mask & (1_u64 << idx) == 0);
+        }
+        debug!("date formats of column {col}: {patterns:?}");
+        date_formats.insert(col, patterns);
+    }
+    Ok(date_formats)
+}
+
+/// the JSON Schema format of date patterns that are all RFC-3339 dates or datetimes
+fn rfc3339_format(patterns: &[&str]) -> Option<&'static str> {
+    const RFC3339_DATETIMES: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f%:z", "%Y-%m-%dT%H:%M:%S%.fZ"];
+
+    if patterns.iter().all(|pattern| *pattern == "%Y-%m-%d") {
+        Some("date")
+    } else if patterns
+        .iter()
+        .all(|pattern| RFC3339_DATETIMES.contains(pattern))
+    {
+        Some("date-time")
+    } else {
+        None
+    }
+}
+
 /// get column selector argument string for low cardinality columns
 fn build_low_cardinality_column_selector_arg(
     low_cardinality_column_indices: &mut Vec<u64>,
-    enum_cardinality_threshold: u64,
+    enum_cardinality_thresholds: &[u64],
     csv_fields: &ByteRecord,
     csv_stats: &[StatsData],
 ) -> String {
@@ -412,7 +1846,7 @@ fn build_low_cardinality_column_selector_arg(
         // get Cardinality
         let col_cardinality = csv_stats[i].cardinality;
 
-        if col_cardinality > 0 && col_cardinality <= enum_cardinality_threshold {
+        if col_cardinality > 0 && col_cardinality <= enum_cardinality_thresholds[i] {
             // column selector uses 1-based index
             low_cardinality_column_indices.push((i + 1) as u64);
         };
@@ -428,34 +1862,45 @@ fn build_low_cardinality_column_selector_arg(
     column_select_arg
 }
 
-/// get frequency tables from `cmd::frequency`
+/// get frequency tables from `cmd::frequency`, with up to enum_limit values per column
 /// returns map of unique values keyed by header
 fn get_unique_values(
     args: &util::SchemaArgs,
     column_select_arg: &str,
+    enum_limit: u64,
 ) -> CliResult<AHashMap<String, Vec<String>>> {
     // prepare arg for invoking cmd::frequency
     let freq_args = crate::cmd::frequency::Args {
-        arg_input:            args.arg_input.clone(),
-        flag_select:          crate::select::SelectColumns::parse(column_select_arg).unwrap(),
-        flag_limit:           args.flag_enum_threshold as isize,
-        flag_unq_limit:       args.flag_enum_threshold as usize,
-        flag_lmt_threshold:   0,
-        flag_pct_dec_places:  -5,
-        flag_other_sorted:    false,
-        flag_other_text:      "Other".to_string(),
-        flag_asc:             false,
-        flag_no_nulls:        true,
-        flag_no_trim:         false,
-        flag_ignore_case:     args.flag_ignore_case,
+        arg_input:              args.arg_input.clone(),
+        flag_select:            crate::select::SelectColumns::parse(column_select_arg).unwrap(),
+        flag_limit:             enum_limit as isize,
+        flag_unq_limit:         enum_limit as usize,
+        flag_lmt_threshold:     0,
+        flag_pct_dec_places:    -5,
+        flag_other_sorted:      false,
+        flag_other_text:        "Other".to_string(),
+        flag_other_threshold:   0,
+        flag_suppress_below:    0,
+        flag_asc:               false,
+        flag_cum_pct:           false,
+        flag_rank:              false,
+        flag_no_nulls:          true,
+        flag_no_trim:           false,
+        flag_ignore_case:       args.flag_ignore_case,
+        flag_unicode_normalize: None,
         // internal mode for getting frequency tables
-        flag_stats_mode:      "_schema".to_string(),
-        flag_all_unique_text: "<ALL UNIQUE>".to_string(),
-        flag_jobs:            Some(util::njobs(args.flag_jobs)),
-        flag_output:          None,
-        flag_no_headers:      args.flag_no_headers,
-        flag_delimiter:       args.flag_delimiter,
-        flag_memcheck:        args.flag_memcheck,
+        flag_stats_mode:        "_schema".to_string(),
+        flag_all_unique_text:   "<ALL UNIQUE>".to_string(),
+        flag_weight:            None,
+        flag_joint:             false,
+        flag_tokens:            false,
+        flag_ngram:             1,
+        flag_stopwords:         None,
+        flag_jobs:              Some(util::njobs(args.flag_jobs)),
+        flag_output:            None,
+        flag_no_headers:        args.flag_no_headers,
+        flag_delimiter:         args.flag_delimiter,
+        flag_memcheck:          args.flag_memcheck,
     };
 
     let (headers, ftables) = match freq_args.rconfig().indexed()? {
@@ -614,3 +2059,485 @@ fn should_emit_pattern_constraint(field_def: &Value) -> bool {
 
     type_list.contains(&Value::String("string".to_string())) && !has_enum
 }
+
+/// a deviation of one input file from the unified multi-file schema
+struct Deviation {
+    file:      String,
+    field:     String,
+    deviation: String,
+}
+
+/// infer a single unified schema across all the input files
+fn run_multi_file(args: &Args) -> CliResult<()> {
+    if !args.flag_stdout
+        && args.flag_output.is_none()
+        && args.flag_update.is_none()
+        && args.flag_diff.is_none()
+    {
+        return fail_incorrectusage_clierror!(
+            "--output, --update, --diff or --stdout is required when inferring a schema from \
+             multiple files."
+        );
+    }
+
+    let tmpdir = util::scratch_dir()?;
+    let inputs = util::process_input(args.arg_input.clone(), &tmpdir, "")?;
+
+    let filenames: Vec<String> = inputs
+        .iter()
+        .map(|p| {
+            p.file_name()
+                .unwrap_or(p.as_os_str())
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect();
+    let dataset_label = format!("{} files", inputs.len());
+
+    // infer the schema of each file separately
+    let mut file_properties: Vec<Map<String, Value>> = Vec::with_capacity(inputs.len());
+    for (input, filename) in inputs.iter().zip(&filenames) {
+        winfo!("Inferring schema for {filename}...");
+        let (schema_args, _sample_dir) = args.sampled_schema_args(&input.to_string_lossy())?;
+        util::mem_file_check(
+            Path::new(schema_args.arg_input.as_deref().unwrap_or_default()),
+            false,
+            args.flag_memcheck,
+        )?;
+        file_properties.push(infer_properties(&schema_args, &input.to_string_lossy())?);
+    }
+
+    let schema_args = args.schema_args(None);
+    let (properties_map, required_fields, deviations) =
+        merge_properties(&file_properties, &filenames, |column| {
+            schema_args.enum_threshold(column)
+        });
+
+    let output_path = args
+        .flag_output
+        .clone()
+        .or_else(|| args.flag_update.clone())
+        .unwrap_or_default();
+    let mut schema = if args.is_datapackage() {
+        datapackage_json(args, &inputs, &properties_map, &output_path)
+    } else if args.flag_compose {
+        composed_schema_json(
+            &format!("JSON Schema for {dataset_label}"),
+            &properties_map,
+            &required_fields,
+            &file_properties,
+            &filenames,
+        )
+    } else {
+        schema_json(
+            &format!("JSON Schema for {dataset_label}"),
+            properties_map,
+            required_fields,
+        )
+    };
+
+    if let Some(update_path) = &args.flag_update {
+        schema = update_schema(update_path, schema)?;
+    }
+    if args.flag_diff.is_some() {
+        return write_schema_diff(args, &schema);
+    }
+
+    if deviations.is_empty() {
+        winfo!("No deviations from the unified schema found.");
+    } else {
+        for dev in &deviations {
+            wwarn!("{}: {}: {}", dev.file, dev.field, dev.deviation);
+        }
+    }
+
+    write_schema(args, &schema, &output_path)?;
+
+    if !args.flag_stdout && !deviations.is_empty() {
+        write_deviations_report(&output_path, &deviations)?;
+    }
+
+    Ok(())
+}
+
+/// create the unified JSON Schema of several files as a schema set - shared column
+/// definitions in `$defs` referenced with `$ref`, and a per-file schema in `$defs`
+/// composed of the columns common to all files with `allOf`
+fn composed_schema_json(
+    title: &str,
+    properties_map: &Map<String, Value>,
+    required_fields: &[Value],
+    file_properties: &[Map<String, Value>],
+    filenames: &[String],
+) -> Value {
+    let mut defs: Map<String, Value> = Map::new();
+    let mut def_names: AHashSet<String> = AHashSet::new();
+    def_names.insert("common".to_string());
+
+    // the definitions of columns found in more than one file are shared
+    let mut column_refs: AHashMap<&str, Value> = AHashMap::new();
+    for (field_name, field_def) in properties_map {
+        let file_count = file_properties
+            .iter()
+            .filter(|properties| properties.contains_key(field_name))
+            .count();
+        if file_count > 1 {
+            let def_name = unique_def_name(&mut def_names, field_name);
+            defs.insert(def_name.clone(), field_def.clone());
+            column_refs.insert(
+                field_name.as_str(),
+                json!({"$ref": format!("#/$defs/{def_name}")}),
+            );
+        }
+    }
+    let property = |field_name: &str| {
+        column_refs
+            .get(field_name)
+            .cloned()
+            .unwrap_or_else(|| properties_map[field_name].clone())
+    };
+    let object_schema = |field_names: &[&str]| {
+        let properties: Map<String, Value> = field_names
+            .iter()
+            .map(|field_name| ((*field_name).to_string(), property(field_name)))
+            .collect();
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": field_names,
+        })
+    };
+
+    // the columns found in every file
+    let common_fields: Vec<&str> = required_fields.iter().filter_map(Value::as_str).collect();
+    if !common_fields.is_empty() {
+        defs.insert("common".to_string(), object_schema(&common_fields));
+    }
+
+    for (properties, filename) in file_properties.iter().zip(filenames) {
+        let file_fields: Vec<&str> = properties
+            .keys()
+            .map(String::as_str)
+            .filter(|field_name| !common_fields.contains(field_name))
+            .collect();
+        let mut file_schema = object_schema(&file_fields);
+        if !common_fields.is_empty() {
+            file_schema["allOf"] = json!([{"$ref": "#/$defs/common"}]);
+        }
+        defs.insert(unique_def_name(&mut def_names, filename), file_schema);
+    }
+
+    let root_properties: Map<String, Value> = properties_map
+        .keys()
+        .map(|field_name| (field_name.clone(), property(field_name)))
+        .collect();
+    let mut schema = schema_json(title, root_properties, required_fields.to_vec());
+    schema["$defs"] = Value::Object(defs);
+    schema
+}
+
+/// a `$defs` name that can be used as is in a `$ref` - only letters, digits,
+/// ".", "_" & "-", with a numeric suffix if it's already taken
+fn unique_def_name(def_names: &mut AHashSet<String>, name: &str) -> String {
+    let base_name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut def_name = base_name.clone();
+    let mut suffix = 1;
+    while !def_names.insert(def_name.clone()) {
+        suffix += 1;
+        def_name = format!("{base_name}-{suffix}");
+    }
+    def_name
+}
+
+/// merge per-file "properties" objects into a single unified "properties" object.
+/// Returns the unified properties, the required fields (i.e. columns present in ALL files)
+/// and the per-file deviations from the unified schema.
+fn merge_properties(
+    file_properties: &[Map<String, Value>],
+    filenames: &[String],
+    enum_threshold: impl Fn(&str) -> u64,
+) -> (Map<String, Value>, Vec<Value>, Vec<Deviation>) {
+    // collect the definitions of each column across files, in order of first appearance
+    let mut column_defs: IndexMap<&str, Vec<(usize, &Map<String, Value>)>> = IndexMap::new();
+    for (file_idx, properties) in file_properties.iter().enumerate() {
+        for (field_name, field_def) in properties {
+            if let Some(def) = field_def.as_object() {
+                column_defs
+                    .entry(field_name.as_str())
+                    .or_default()
+                    .push((file_idx, def));
+            }
+        }
+    }
+
+    let mut properties_map: Map<String, Value> = Map::with_capacity(column_defs.len());
+    let mut required_fields: Vec<Value> = Vec::with_capacity(column_defs.len());
+    let mut deviations: Vec<Deviation> = Vec::new();
+
+    for (field_name, defs) in column_defs {
+        if defs.len() == file_properties.len() {
+            required_fields.push(Value::String(field_name.to_string()));
+        } else {
+            for (file_idx, filename) in filenames.iter().enumerate() {
+                if !defs.iter().any(|(idx, _)| *idx == file_idx) {
+                    deviations.push(Deviation {
+                        file:      filename.clone(),
+                        field:     field_name.to_string(),
+                        deviation: "missing column".to_string(),
+                    });
+                }
+            }
+        }
+
+        let unified_type = unify_types(&defs);
+        for (file_idx, def) in &defs {
+            let file_type = base_type(def);
+            if file_type != "null" && file_type != unified_type {
+                deviations.push(Deviation {
+                    file:      filenames[*file_idx].clone(),
+                    field:     field_name.to_string(),
+                    deviation: format!("type conflict: {file_type}, unified as {unified_type}"),
+                });
+            }
+        }
+
+        properties_map.insert(
+            field_name.to_string(),
+            Value::Object(merge_field_defs(
+                field_name,
+                &defs,
+                filenames,
+                unified_type,
+                enum_threshold(field_name),
+            )),
+        );
+    }
+
+    (properties_map, required_fields, deviations)
+}
+
+/// the non-null JSON type of a field definition, "null" if the column is all empty
+fn base_type(field_def: &Map<String, Value>) -> &str {
+    field_def
+        .get("type")
+        .and_then(Value::as_array)
+        .and_then(|types| {
+            types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|t| *t != "null")
+        })
+        .unwrap_or("null")
+}
+
+/// the narrowest JSON type that can hold the values of the column in all files
+fn unify_types(defs: &[(usize, &Map<String, Value>)]) -> &'static str {
+    let mut unified = "null";
+    for (_, def) in defs {
+        unified = match (unified, base_type(def)) {
+            (_, "null") => unified,
+            ("null", "integer") | ("integer", "integer") => "integer",
+            ("null" | "integer" | "number", "integer" | "number") => "number",
+            ("null" | "boolean", "boolean") => "boolean",
+            _ => "string",
+        };
+    }
+    unified
+}
+
+/// merge the definitions of a column across files.
+/// Constraints are only kept if all the files have a comparable constraint.
+fn merge_field_defs(
+    field_name: &str,
+    defs: &[(usize, &Map<String, Value>)],
+    filenames: &[String],
+    unified_type: &str,
+    enum_threshold: u64,
+) -> Map<String, Value> {
+    let mut field_map: Map<String, Value> = Map::with_capacity(10);
+
+    // the descriptions are inferred with the path of each file, so they're replaced
+    // with the names of the files of the column - unless all the files agree on it
+    if let Some(description) = defs.first().and_then(|(_, def)| def.get("description")) {
+        let description = if defs
+            .iter()
+            .all(|(_, def)| def.get("description") == Some(description))
+        {
+            description.clone()
+        } else {
+            let column_files: Vec<&str> = defs
+                .iter()
+                .map(|(file_idx, _)| filenames[*file_idx].as_str())
+                .collect();
+            Value::String(format!(
+                "{field_name} column from {}",
+                column_files.join(", ")
+            ))
+        };
+        field_map.insert("description".to_string(), description);
+    }
+
+    let null_type = Value::String("null".to_string());
+    let nullable = defs.iter().any(|(_, def)| {
+        def.get("type")
+            .and_then(Value::as_array)
+            .is_some_and(|types| types.contains(&null_type))
+    });
+
+    // all-null columns in a file don't constrain the unified definition
+    let typed_defs: Vec<&Map<String, Value>> = defs
+        .iter()
+        .map(|(_, def)| *def)
+        .filter(|def| base_type(def) != "null")
+        .collect();
+    let same_type = typed_defs.iter().all(|def| base_type(def) == unified_type);
+
+    if unified_type == "integer" || unified_type == "number" {
+        for (key, highest) in [("minimum", false), ("maximum", true)] {
+            if let Some(bound) = bound_across(&typed_defs, key, highest) {
+                field_map.insert(key.to_string(), bound);
+            }
+        }
+    } else if unified_type == "string" && same_type {
+        for (key, highest) in [("minLength", false), ("maxLength", true)] {
+            if let Some(bound) = bound_across(&typed_defs, key, highest) {
+                field_map.insert(key.to_string(), bound);
+            }
+        }
+
+        // only keep the format if all the files agree on it
+        if let Some(format) = typed_defs.first().and_then(|def| def.get("format")) {
+            if typed_defs
+                .iter()
+                .all(|def| def.get("format") == Some(format))
+            {
+                field_map.insert("format".to_string(), format.clone());
+            }
+        }
+
+        // combine the per-file date formats, if every file has some
+        if !typed_defs.is_empty()
+            && typed_defs
+                .iter()
+                .all(|def| def.get("x-date-format").is_some())
+        {
+            let mut patterns: Vec<Value> = Vec::new();
+            for def in &typed_defs {
+                let def_patterns = match &def["x-date-format"] {
+                    Value::Array(def_patterns) => def_patterns.clone(),
+                    pattern => vec![pattern.clone()],
+                };
+                for pattern in def_patterns {
+                    if !patterns.contains(&pattern) {
+                        patterns.push(pattern);
+                    }
+                }
+            }
+            let patterns = if patterns.len() == 1 {
+                patterns.remove(0)
+            } else {
+                Value::Array(patterns)
+            };
+            field_map.insert("x-date-format".to_string(), patterns);
+        }
+    }
+
+    let mut type_list = vec![Value::String(unified_type.to_string())];
+    if nullable && unified_type != "null" {
+        type_list.push(null_type);
+    }
+    field_map.insert("type".to_string(), Value::Array(type_list));
+
+    // enum constraint - only if every file has one and their union is within the threshold
+    if !typed_defs.is_empty()
+        && (same_type || unified_type == "number")
+        && typed_defs.iter().all(|def| def.get("enum").is_some())
+    {
+        let mut enum_list: Vec<Value> = Vec::new();
+        for def in &typed_defs {
+            for value in def["enum"].as_array().into_iter().flatten() {
+                if !value.is_null() && !enum_list.contains(value) {
+                    enum_list.push(value.clone());
+                }
+            }
+        }
+        if enum_list.len() as u64 <= enum_threshold {
+            // sort the values so the schema can be diff'ed between runs
+            enum_list.sort_by(|a, b| match (a.as_f64(), b.as_f64()) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                _ => a.as_str().cmp(&b.as_str()),
+            });
+            if nullable {
+                enum_list.push(Value::Null);
+            }
+            field_map.insert("enum".to_string(), Value::Array(enum_list));
+        }
+    }
+
+    // pattern constraint - combine the per-file patterns
+    if unified_type == "string"
+        && same_type
+        && !field_map.contains_key("enum")
+        && !typed_defs.is_empty()
+        && typed_defs.iter().all(|def| def.get("pattern").is_some())
+    {
+        let patterns: Vec<&str> = typed_defs
+            .iter()
+            .filter_map(|def| def.get("pattern").and_then(Value::as_str))
+            .unique()
+            .collect();
+        let pattern = if patterns.len() == 1 {
+            patterns[0].to_string()
+        } else {
+            patterns.iter().map(|p| format!("({p})")).join("|")
+        };
+        field_map.insert("pattern".to_string(), Value::String(pattern));
+    }
+
+    field_map
+}
+
+/// the lowest (or highest) value of a numeric constraint across field definitions.
+/// Returns None if any of the definitions doesn't have the constraint.
+fn bound_across(defs: &[&Map<String, Value>], key: &str, highest: bool) -> Option<Value> {
+    let mut bound: Option<(&Value, f64)> = None;
+    for def in defs {
+        let value = def.get(key)?;
+        let num = value.as_f64()?;
+        bound = match bound {
+            Some((_, current)) if (highest && num > current) || (!highest && num < current) => {
+                Some((value, num))
+            },
+            None => Some((value, num)),
+            keep => keep,
+        };
+    }
+    bound.map(|(value, _)| value.clone())
+}
+
+/// write the per-file deviations from the unified schema as a TSV file
+fn write_deviations_report(output_path: &str, deviations: &[Deviation]) -> CliResult<()> {
+    let report_path = format!("{output_path}.deviations.tsv");
+    let mut report_wtr = BufWriter::new(File::create(&report_path)?);
+
+    report_wtr.write_all(b"file\tfield\tdeviation\n")?;
+    for dev in deviations {
+        writeln!(report_wtr, "{}\t{}\t{}", dev.file, dev.field, dev.deviation)?;
+    }
+    report_wtr.flush()?;
+
+    winfo!(
+        "{} deviation/s from the unified schema written to {report_path}",
+        deviations.len()
+    );
+    Ok(())
+}
@@ -76,6 +76,10 @@ Common options:
                            sliced, etc.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+    --locked-schema <file>
+                           Refuse to run if the input's columns or column types
+                           don't match the JSON Schema <file> (e.g. one generated
+                           by `qsv schema`), listing the differences.
     -p, --progressbar      Show progress bars. Not valid for stdin.
     -Q, --quiet            Do not return number of matches to stderr.
 "#;
@@ -102,6 +106,7 @@ struct Args {
     flag_output:         Option<String>,
     flag_no_headers:     bool,
     flag_delimiter:      Option<Delimiter>,
+    flag_locked_schema:  Option<String>,
     flag_invert_match:   bool,
     flag_unicode:        bool,
     flag_ignore_case:    bool,
@@ -143,6 +148,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
+    if let Some(ref schema_path) = args.flag_locked_schema {
+        util::check_locked_schema(&rconfig, schema_path)?;
+    }
+
     // args struct booleans in hot loop assigned to local variables
     // to help the compiler optimize the code & hopefully use registers
     let flag_quick = args.flag_quick;
@@ -74,6 +74,7 @@ These options only apply to the `select` command, not the `--select` option in o
 
     -R, --random           Randomly shuffle the columns in the selection.
     --seed <number>        Seed for the random number generator.
+                           If not set, the QSV_SEED environment variable is used.
 
     -S, --sort             Sort the selected columns lexicographically,
                            i.e. by their byte values.
@@ -86,6 +87,10 @@ Common options:
                            sliced, etc.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character. (default: ,)
+    --locked-schema <file>
+                           Refuse to run if the input's columns or column types
+                           don't match the JSON Schema <file> (e.g. one generated
+                           by `qsv schema`), listing the differences.
 "#;
 
 use rand::{seq::SliceRandom, SeedableRng};
@@ -99,18 +104,20 @@ use crate::{
 
 #[derive(Deserialize)]
 struct Args {
-    arg_input:       Option<String>,
-    arg_selection:   SelectColumns,
-    flag_random:     bool,
-    flag_seed:       Option<u64>,
-    flag_sort:       bool,
-    flag_output:     Option<String>,
-    flag_no_headers: bool,
-    flag_delimiter:  Option<Delimiter>,
+    arg_input:          Option<String>,
+    arg_selection:      SelectColumns,
+    flag_random:        bool,
+    flag_seed:          Option<u64>,
+    flag_sort:          bool,
+    flag_output:        Option<String>,
+    flag_no_headers:    bool,
+    flag_delimiter:     Option<Delimiter>,
+    flag_locked_schema: Option<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
+    args.flag_seed = util::rng_seed(args.flag_seed)?;
 
     if args.flag_sort && args.flag_random {
         return fail_clierror!("Cannot use both --random and --sort options.");
@@ -121,6 +128,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .no_headers(args.flag_no_headers)
         .select(args.arg_selection);
 
+    if let Some(ref schema_path) = args.flag_locked_schema {
+        util::check_locked_schema(&rconfig, schema_path)?;
+    }
+
     let mut rdr = rconfig.reader()?;
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
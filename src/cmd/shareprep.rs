@@ -0,0 +1,531 @@
+static USAGE: &str = r#"
+Prepares a safe sample of a CSV for sharing with a vendor or partner, in a single pass.
+
+The input is sampled (optionally stratified), the columns listed in an anonymization
+spec are anonymized, and the sample is validated against a JSON Schema. The sample is
+then packaged in a zip file with the schema and a manifest:
+
+  data.csv       the anonymized sample, in the order of the input.
+  schema.json    the JSON Schema, updated for the anonymized columns - dropped columns
+                 are removed, and the other anonymized columns become nullable strings.
+  manifest.json  the source & sample row counts, how the sample was drawn, the
+                 anonymized columns, and the size & SHA-256 checksum of each file.
+
+If any sampled row doesn't validate against the (updated) schema, the invalid rows are
+listed and no package is written.
+
+SAMPLING:
+Rows are drawn using reservoir sampling. With --stratify, a reservoir is kept for each
+distinct value of the stratify column (so memory use is proportional to the number of
+strata times --sample-size), and the sample is allocated to the strata in proportion
+to their size. If there are no more strata than --sample-size, every stratum gets at
+least one row, so small strata are always represented.
+
+ANONYMIZATION SPEC:
+A CSV file with a "column" & a "method" column, with one row per column to anonymize.
+The methods are:
+
+  drop     remove the column.
+  redact   replace the values with REDACTED.
+  hash     replace the values with the first 16 hex digits of their salted SHA-256 hash.
+           The same value always gets the same hash in a package, so the column can
+           still be used to join & group.
+  pseudo   replace the values with incremental identifiers (1, 2, 3...). The same value
+           always gets the same identifier in a package.
+  mask:N   replace all but the last N characters of the values with *, e.g. mask:4
+           for credit card numbers. Values with N characters or less are fully masked.
+
+Empty values are left empty, except in dropped columns.
+
+Examples:
+
+  # share 1,000 random rows, with the email column hashed and the name column dropped
+  $ printf 'column,method\nemail,hash\nname,drop\n' > spec.csv
+  $ qsv shareprep --anonymize spec.csv customers.csv customers.schema.json
+
+  # share 500 rows stratified by region, reproducibly, in customers-share.zip
+  $ qsv shareprep --sample-size 500 --stratify region --seed 42 --anonymize spec.csv \
+      customers.csv customers.schema.json
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_shareprep.rs.
+
+Usage:
+    qsv shareprep [options] <input> <json-schema>
+    qsv shareprep --help
+
+shareprep arguments:
+    <input>                The CSV file to sample. It must have a header row.
+    <json-schema>          The JSON Schema file the sample must comply with.
+
+shareprep options:
+    --sample-size <n>      The number of rows to sample. If the input has fewer rows,
+                           all of them are packaged. [default: 1000]
+    --stratify <column>    Draw a stratified sample on the values of <column>.
+    --seed <number>        The seed of the random number generator, to draw the same
+                           sample every time. If not set, the QSV_SEED environment
+                           variable is used.
+    --anonymize <file>     The anonymization spec. See ANONYMIZATION SPEC above.
+                           (default: no column is anonymized)
+    --salt <string>        The salt of the hash method. Use the same salt to get the same
+                           hashes across packages. (default: a random salt, so the hashes
+                           can't be matched with the ones of other packages)
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    The zip file to write. (default: <input>-share.zip,
+                           without the input's extension)
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use ahash::AHashMap;
+use chrono::{SecondsFormat, Utc};
+use csv::ByteRecord;
+use indexmap::IndexMap;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    cmd::validate::RecordValidator,
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:        String,
+    arg_json_schema:  String,
+    flag_sample_size: u64,
+    flag_stratify:    Option<SelectColumns>,
+    flag_seed:        Option<u64>,
+    flag_anonymize:   Option<String>,
+    flag_salt:        Option<String>,
+    flag_output:      Option<String>,
+    flag_delimiter:   Option<Delimiter>,
+}
+
+// the maximum number of invalid rows listed when the sample doesn't validate
+const MAX_INVALID_ROWS_LISTED: usize = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Method {
+    Drop,
+    Redact,
+    Hash,
+    Pseudo,
+    Mask(usize),
+}
+
+impl FromStr for Method {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let method = s.trim().to_ascii_lowercase();
+        match method.as_str() {
+            "drop" => Ok(Method::Drop),
+            "redact" => Ok(Method::Redact),
+            "hash" => Ok(Method::Hash),
+            "pseudo" => Ok(Method::Pseudo),
+            _ => match method.strip_prefix("mask:").map(str::parse::<usize>) {
+                Some(Ok(keep)) => Ok(Method::Mask(keep)),
+                _ => Err(format!(
+                    "Invalid anonymization method: {s}. Valid methods are drop, redact, hash, \
+                     pseudo and mask:N."
+                )),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Method::Drop => write!(f, "drop"),
+            Method::Redact => write!(f, "redact"),
+            Method::Hash => write!(f, "hash"),
+            Method::Pseudo => write!(f, "pseudo"),
+            Method::Mask(keep) => write!(f, "mask:{keep}"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SpecRow {
+    column: String,
+    method: String,
+}
+
+/// load the anonymization spec, returning the anonymization method of each input column
+fn load_spec(spec_path: &str, headers: &ByteRecord) -> CliResult<Vec<Option<Method>>> {
+    let mut methods = vec![None; headers.len()];
+    let mut rdr = Config::new(&Some(spec_path.to_string())).reader()?;
+    for row in rdr.deserialize() {
+        let row: SpecRow = row?;
+        let Some(idx) = headers.iter().position(|h| h == row.column.as_bytes()) else {
+            return fail_incorrectusage_clierror!(
+                "Column \"{}\" of the anonymization spec is not in the input.",
+                row.column
+            );
+        };
+        if methods[idx].is_some() {
+            return fail_incorrectusage_clierror!(
+                "Column \"{}\" is listed more than once in the anonymization spec.",
+                row.column
+            );
+        }
+        match row.method.parse() {
+            Ok(method) => methods[idx] = Some(method),
+            Err(e) => return fail_incorrectusage_clierror!("{e}"),
+        }
+    }
+    Ok(methods)
+}
+
+/// the schema of the shared data - without the dropped columns, and with the other
+/// anonymized columns as nullable strings
+fn share_schema(schema: &Value, headers: &ByteRecord, methods: &[Option<Method>]) -> Value {
+    let mut schema = schema.clone();
+    for (header, method) in headers.iter().zip(methods) {
+        let Some(method) = method else {
+            continue;
+        };
+        let column = String::from_utf8_lossy(header);
+        if *method == Method::Drop {
+            if let Some(required) = schema.get_mut("required").and_then(Value::as_array_mut) {
+                required.retain(|r| r.as_str() != Some(column.as_ref()));
+            }
+        }
+        let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) else {
+            continue;
+        };
+        if *method == Method::Drop {
+            properties.shift_remove(column.as_ref());
+            continue;
+        }
+
+        let mut property = json!({
+            "type": ["string", "null"],
+            "$comment": format!("anonymized with {method}"),
+        });
+        // keep the documentation of the column
+        if let Some(original) = properties.get(column.as_ref()) {
+            for key in ["title", "description"] {
+                if let Some(value) = original.get(key) {
+                    property[key] = value.clone();
+                }
+            }
+        }
+        properties.insert(column.into_owned(), property);
+    }
+    schema
+}
+
+/// split the sample size between the strata, in proportion to their number of rows
+fn allocate(counts: &[u64], sample_size: u64) -> Vec<u64> {
+    let total: u64 = counts.iter().sum();
+    if total <= sample_size {
+        return counts.to_vec();
+    }
+
+    // give every stratum a row first if they all fit, so small strata are represented
+    let strata = counts.len() as u64;
+    let base = u64::from(strata <= sample_size);
+    let remaining = u128::from(sample_size - base * strata);
+    let pool = u128::from(total - base * strata);
+
+    let mut quotas = Vec::with_capacity(counts.len());
+    let mut remainders = Vec::with_capacity(counts.len());
+    for (idx, count) in counts.iter().enumerate() {
+        let share = remaining * u128::from(count - base);
+        quotas.push(base + (share / pool) as u64);
+        remainders.push((share % pool, idx));
+    }
+
+    // hand out the rows left over from rounding down to the largest remainders
+    let left_over = sample_size - quotas.iter().sum::<u64>();
+    remainders.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for &(_, idx) in remainders.iter().take(left_over as usize) {
+        quotas[idx] += 1;
+    }
+    quotas
+}
+
+/// the rows of a stratum, with a reservoir sample of them
+#[derive(Default)]
+struct Stratum {
+    rows:      u64,
+    reservoir: Vec<(u64, ByteRecord)>,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// anonymize the values of a column of a sampled row
+struct Anonymizer {
+    salt:       Vec<u8>,
+    pseudo_ids: Vec<AHashMap<Vec<u8>, u64>>,
+}
+
+impl Anonymizer {
+    fn anonymize(&mut self, column: usize, method: Method, value: &[u8]) -> Vec<u8> {
+        if value.is_empty() {
+            return Vec::new();
+        }
+        match method {
+            Method::Drop => unreachable!("dropped columns are not written"),
+            Method::Redact => b"REDACTED".to_vec(),
+            Method::Hash => {
+                let digest = Sha256::new()
+                    .chain_update(&self.salt)
+                    .chain_update(value)
+                    .finalize();
+                format!("{digest:x}")[..16].as_bytes().to_vec()
+            },
+            Method::Pseudo => {
+                let ids = &mut self.pseudo_ids[column];
+                let next_id = ids.len() as u64 + 1;
+                let id = *ids.entry(value.to_vec()).or_insert(next_id);
+                id.to_string().into_bytes()
+            },
+            Method::Mask(keep) => {
+                let value = String::from_utf8_lossy(value);
+                let len = value.chars().count();
+                let masked_len = if len <= keep { len } else { len - keep };
+                let mut masked = "*".repeat(masked_len);
+                masked.extend(value.chars().skip(masked_len));
+                masked.into_bytes()
+            },
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let mut args: Args = util::get_args(USAGE, argv)?;
+    args.flag_seed = util::rng_seed(args.flag_seed)?;
+
+    if args.flag_sample_size == 0 {
+        return fail_incorrectusage_clierror!("--sample-size must be greater than 0.");
+    }
+    let output_path = match args.flag_output {
+        Some(ref output) => output.clone(),
+        None if args.arg_input == "-" => {
+            return fail_incorrectusage_clierror!("--output is required when reading stdin.");
+        },
+        None => {
+            let input_path = Path::new(&args.arg_input);
+            let stem = input_path.file_stem().unwrap_or_default().to_string_lossy();
+            input_path
+                .with_file_name(format!("{stem}-share.zip"))
+                .to_string_lossy()
+                .into_owned()
+        },
+    };
+
+    let schema_json: Value = match fs::read_to_string(&args.arg_json_schema) {
+        Ok(s) => match serde_json::from_str(&s) {
+            Ok(json) => json,
+            Err(e) => return fail_clierror!("Unable to parse schema json. error: {e}"),
+        },
+        Err(e) => return fail_clierror!("Cannot read {}: {e}", args.arg_json_schema),
+    };
+
+    let mut rconfig = Config::new(&Some(args.arg_input.clone())).delimiter(args.flag_delimiter);
+    if let Some(ref stratify) = args.flag_stratify {
+        rconfig = rconfig.select(stratify.clone());
+    }
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let stratify_idx = if args.flag_stratify.is_some() {
+        let sel = rconfig.selection(&headers)?;
+        if sel.len() != 1 {
+            return fail_incorrectusage_clierror!("--stratify must select a single column.");
+        }
+        Some(sel[0])
+    } else {
+        None
+    };
+    let methods = match args.flag_anonymize {
+        Some(ref spec_path) => load_spec(spec_path, &headers)?,
+        None => vec![None; headers.len()],
+    };
+
+    // draw a reservoir sample of each stratum in a single pass
+    let mut rng: StdRng = match args.flag_seed {
+        None => StdRng::from_rng(rand::thread_rng()).unwrap(),
+        Some(seed) => StdRng::seed_from_u64(seed), //DevSkim: ignore DS148264
+    };
+    let reservoir_size = args.flag_sample_size as usize;
+    let mut strata: IndexMap<Vec<u8>, Stratum> = IndexMap::new();
+    let mut record = ByteRecord::new();
+    let mut row_idx = 0_u64;
+    while rdr.read_byte_record(&mut record)? {
+        let key = stratify_idx.map_or(&b""[..], |idx| &record[idx]);
+        if !strata.contains_key(key) {
+            strata.insert(key.to_vec(), Stratum::default());
+        }
+        let stratum = strata.get_mut(key).unwrap();
+        if stratum.reservoir.len() < reservoir_size {
+            stratum.reservoir.push((row_idx, record.clone()));
+        } else {
+            let random = rng.gen_range(0..=stratum.rows) as usize;
+            if random < reservoir_size {
+                stratum.reservoir[random] = (row_idx, record.clone());
+            }
+        }
+        stratum.rows += 1;
+        row_idx += 1;
+    }
+    let source_rows = row_idx;
+
+    let counts: Vec<u64> = strata.values().map(|stratum| stratum.rows).collect();
+    let quotas = allocate(&counts, args.flag_sample_size);
+    if quotas.contains(&0) {
+        wwarn!(
+            "There are more strata than --sample-size. {} strata are not in the sample.",
+            quotas.iter().filter(|&&quota| quota == 0).count()
+        );
+    }
+    let mut sample: Vec<(u64, ByteRecord)> = Vec::with_capacity(reservoir_size);
+    let mut strata_rows = serde_json::Map::new();
+    for ((key, mut stratum), quota) in strata.into_iter().zip(quotas) {
+        if (quota as usize) < stratum.reservoir.len() {
+            stratum.reservoir.shuffle(&mut rng);
+            stratum.reservoir.truncate(quota as usize);
+        }
+        sample.append(&mut stratum.reservoir);
+        strata_rows.insert(String::from_utf8_lossy(&key).into_owned(), json!(quota));
+    }
+    sample.sort_unstable_by_key(|(idx, _)| *idx);
+
+    // anonymize & validate the sample
+    let salt = match args.flag_salt {
+        Some(ref salt) => salt.as_bytes().to_vec(),
+        None => rand::thread_rng().gen::<[u8; 16]>().to_vec(),
+    };
+    let mut anonymizer = Anonymizer {
+        salt,
+        pseudo_ids: vec![AHashMap::new(); headers.len()],
+    };
+    let kept_columns: Vec<usize> = (0..headers.len())
+        .filter(|&idx| methods[idx] != Some(Method::Drop))
+        .collect();
+    let share_headers: ByteRecord = kept_columns.iter().map(|&idx| &headers[idx]).collect();
+    let share_schema = share_schema(&schema_json, &headers, &methods);
+    let validator = RecordValidator::new(&share_schema, &share_headers)?;
+
+    let mut data_wtr = csv::Writer::from_writer(Vec::new());
+    data_wtr.write_byte_record(&share_headers)?;
+    let mut share_record = ByteRecord::with_capacity(256, kept_columns.len());
+    let mut invalid_rows: Vec<String> = Vec::new();
+    for (row_idx, record) in &sample {
+        share_record.clear();
+        for &idx in &kept_columns {
+            match methods[idx] {
+                Some(method) => {
+                    share_record.push_field(&anonymizer.anonymize(idx, method, &record[idx]))
+                },
+                None => share_record.push_field(&record[idx]),
+            }
+        }
+        if let Some(errors) = validator.validate(&share_record) {
+            invalid_rows.extend(
+                errors
+                    .into_iter()
+                    .map(|(field, error)| format!("row {}: {field}: {error}", row_idx + 1)),
+            );
+            continue;
+        }
+        data_wtr.write_byte_record(&share_record)?;
+    }
+    if !invalid_rows.is_empty() {
+        return fail_clierror!(
+            "The sample doesn't validate against {}. No package was written.\n{}",
+            args.arg_json_schema,
+            invalid_rows
+                .iter()
+                .take(MAX_INVALID_ROWS_LISTED)
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+    let data = match data_wtr.into_inner() {
+        Ok(data) => data,
+        Err(e) => return fail_clierror!("Cannot write the sample: {e}"),
+    };
+    let schema_bytes = serde_json::to_vec_pretty(&share_schema)?;
+
+    let manifest = json!({
+        "created_at": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        "qsv_version": env!("CARGO_PKG_VERSION"),
+        "source": {
+            "file": Path::new(&args.arg_input).file_name().map(|f| f.to_string_lossy()),
+            "rows": source_rows,
+        },
+        "sample": {
+            "rows": sample.len(),
+            "stratify": stratify_idx.map(|idx| String::from_utf8_lossy(&headers[idx]).into_owned()),
+            "strata": stratify_idx.map(|_| strata_rows),
+            "seed": args.flag_seed,
+        },
+        "anonymized": headers
+            .iter()
+            .zip(&methods)
+            .filter_map(|(header, method)| method.map(|method| json!({
+                "column": String::from_utf8_lossy(header),
+                "method": method.to_string(),
+            })))
+            .collect::<Vec<_>>(),
+        "files": [
+            {
+                "name": "data.csv",
+                "rows": sample.len(),
+                "bytes": data.len(),
+                "sha256": sha256_hex(&data),
+            },
+            {
+                "name": "schema.json",
+                "bytes": schema_bytes.len(),
+                "sha256": sha256_hex(&schema_bytes),
+            },
+        ],
+    });
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+
+    // package the sample
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(&output_path)?));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, bytes) in [
+        ("data.csv", &data),
+        ("schema.json", &schema_bytes),
+        ("manifest.json", &manifest_bytes),
+    ] {
+        if let Err(e) = zip.start_file(name, options) {
+            return fail_clierror!("Cannot write {name} to {output_path}: {e}");
+        }
+        zip.write_all(bytes)?;
+    }
+    match zip.finish() {
+        Ok(mut wtr) => wtr.flush()?,
+        Err(e) => return fail_clierror!("Cannot write {output_path}: {e}"),
+    }
+
+    winfo!(
+        "Packaged {} of {source_rows} rows in {output_path}.",
+        sample.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,373 @@
+static USAGE: &str = r#"
+Estimates the on-disk size of a CSV file in other formats and compression codecs, and
+how long converting it would take - to guide storage decisions before converting large
+datasets.
+
+The first --sample-size records are converted to each of the --formats, measuring the
+compression ratio and the throughput. These are extrapolated to the whole file, so the
+estimates are only as good as the first records are representative of the rest.
+
+The supported formats are:
+  csv:                   CSV, as rewritten by qsv
+  csv.gz:                gzip-compressed CSV
+  csv.sz:                Snappy-compressed CSV (see `qsv snappy`)
+  csv.zst (or zstd):     Zstandard-compressed CSV
+  parquet:               Parquet, with zstd compression
+  parquet.<codec>:       Parquet, with the snappy, gzip, lz4 or uncompressed codec
+The Parquet formats require qsv to be built with the polars feature. They're skipped
+with a warning otherwise.
+
+The estimates are written as CSV with the format, estimated_bytes, estimated_size,
+ratio (the estimated size / the input file size) and estimated_secs columns.
+
+With --columns, the estimated size of each column in each format is written instead,
+with the column, format, estimated_bytes, estimated_size and share (of all the columns
+in the format) columns. This shows which columns are worth re-encoding or dropping.
+
+Examples:
+
+  # compare gzip, zstd and parquet for a large CSV
+  $ qsv sizeest --formats csv.gz,zstd,parquet data.csv
+
+  # the largest columns when stored as zstd-compressed Parquet
+  $ qsv sizeest --columns --formats parquet data.csv | qsv sort -NR -s estimated_bytes
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_sizeest.rs.
+
+Usage:
+    qsv sizeest [options] <input>
+    qsv sizeest --help
+
+sizeest arguments:
+    <input>                The CSV file to estimate. Snappy-compressed files are
+                           not supported, as the estimates are based on its size.
+
+sizeest options:
+    -f, --formats <list>   The comma-separated list of formats to estimate.
+                           [default: csv.gz,zstd,parquet]
+    --sample-size <n>      The number of records to sample. [default: 100000]
+    --columns              Estimate the size of each column in each format.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{
+    fs,
+    io::Write,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use flate2::{write::GzEncoder, Compression};
+use indicatif::HumanBytes;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:        String,
+    flag_formats:     String,
+    flag_sample_size: usize,
+    flag_columns:     bool,
+    flag_output:      Option<String>,
+    flag_no_headers:  bool,
+    flag_delimiter:   Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ParquetCodec {
+    Zstd,
+    Snappy,
+    Gzip,
+    Lz4,
+    Uncompressed,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(not(feature = "polars"), allow(dead_code))]
+enum Format {
+    Csv,
+    CsvGz,
+    CsvSz,
+    CsvZst,
+    Parquet(ParquetCodec),
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "csv.gz" | "gz" | "gzip" => Ok(Format::CsvGz),
+            "csv.sz" | "sz" | "snappy" => Ok(Format::CsvSz),
+            "csv.zst" | "zst" | "zstd" => Ok(Format::CsvZst),
+            "parquet" | "parquet.zstd" => Ok(Format::Parquet(ParquetCodec::Zstd)),
+            "parquet.snappy" => Ok(Format::Parquet(ParquetCodec::Snappy)),
+            "parquet.gzip" => Ok(Format::Parquet(ParquetCodec::Gzip)),
+            "parquet.lz4" => Ok(Format::Parquet(ParquetCodec::Lz4)),
+            "parquet.uncompressed" => Ok(Format::Parquet(ParquetCodec::Uncompressed)),
+            _ => Err(format!(
+                "Invalid format: {s}. Valid formats are csv, csv.gz, csv.sz, csv.zst, parquet, \
+                 parquet.snappy, parquet.gzip, parquet.lz4 and parquet.uncompressed."
+            )),
+        }
+    }
+}
+
+impl Format {
+    const fn name(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::CsvGz => "csv.gz",
+            Format::CsvSz => "csv.sz",
+            Format::CsvZst => "csv.zst",
+            Format::Parquet(ParquetCodec::Zstd) => "parquet",
+            Format::Parquet(ParquetCodec::Snappy) => "parquet.snappy",
+            Format::Parquet(ParquetCodec::Gzip) => "parquet.gzip",
+            Format::Parquet(ParquetCodec::Lz4) => "parquet.lz4",
+            Format::Parquet(ParquetCodec::Uncompressed) => "parquet.uncompressed",
+        }
+    }
+}
+
+/// the size of CSV data compressed with a CSV format
+fn compressed_csv_size(format: Format, data: &[u8]) -> CliResult<usize> {
+    Ok(match format {
+        Format::CsvGz => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()?.len()
+        },
+        Format::CsvSz => {
+            let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+            encoder.write_all(data)?;
+            encoder.into_inner().map_err(|e| e.to_string())?.len()
+        },
+        Format::CsvZst => zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL)?.len(),
+        Format::Csv | Format::Parquet(_) => data.len(),
+    })
+}
+
+#[cfg(feature = "polars")]
+mod parquet {
+    use std::path::Path;
+
+    use polars::prelude::{
+        DataFrame, LazyCsvReader, LazyFileListReader, ParquetCompression, ParquetWriter,
+    };
+
+    use super::ParquetCodec;
+    use crate::CliResult;
+
+    /// load the sample, rewritten as a CSV file, into a DataFrame
+    pub fn load_sample(path: &Path, has_header: bool) -> CliResult<DataFrame> {
+        Ok(LazyCsvReader::new(path)
+            .with_has_header(has_header)
+            .with_infer_schema_length(None)
+            .finish()?
+            .collect()?)
+    }
+
+    /// the size of the sample written as Parquet
+    pub fn parquet_size(
+        df: &mut DataFrame,
+        codec: ParquetCodec,
+        column: Option<&str>,
+    ) -> CliResult<usize> {
+        let compression = match codec {
+            ParquetCodec::Zstd => ParquetCompression::Zstd(None),
+            ParquetCodec::Snappy => ParquetCompression::Snappy,
+            ParquetCodec::Gzip => ParquetCompression::Gzip(None),
+            ParquetCodec::Lz4 => ParquetCompression::Lz4Raw,
+            ParquetCodec::Uncompressed => ParquetCompression::Uncompressed,
+        };
+        let mut buffer = Vec::new();
+        let writer = ParquetWriter::new(&mut buffer).with_compression(compression);
+        match column {
+            Some(column) => writer.finish(&mut df.select([column])?)?,
+            None => writer.finish(df)?,
+        };
+        Ok(buffer.len())
+    }
+}
+
+/// an estimate for the whole file, extrapolated from the sample
+struct Estimate {
+    bytes: u64,
+    secs:  f64,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.arg_input.to_ascii_lowercase().ends_with(".sz") {
+        return fail_incorrectusage_clierror!(
+            "Snappy-compressed files are not supported. Decompress it first with `qsv snappy \
+             decompress`."
+        );
+    }
+    if args.flag_sample_size == 0 {
+        return fail_incorrectusage_clierror!("--sample-size must be greater than 0.");
+    }
+    let mut formats = Vec::new();
+    for format in args.flag_formats.split(',') {
+        let format: Format = match format.parse() {
+            Ok(format) => format,
+            Err(e) => return fail_incorrectusage_clierror!("{e}"),
+        };
+        if matches!(format, Format::Parquet(_)) && cfg!(not(feature = "polars")) {
+            wwarn!(
+                "Skipping {}, as it requires qsv to be built with the polars feature.",
+                format.name()
+            );
+            continue;
+        }
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+
+    let rconfig = Config::new(&Some(args.arg_input.clone()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let file_size = fs::metadata(&args.arg_input)?.len();
+
+    // read the sample, rewriting it as CSV. Also keep the values of each column for --columns
+    let read_start = Instant::now();
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let mut sample_wtr = csv::Writer::from_writer(Vec::new());
+    let mut column_values: Vec<Vec<u8>> = vec![Vec::new(); headers.len()];
+    let mut add_record = |record: &csv::ByteRecord| -> CliResult<()> {
+        sample_wtr.write_byte_record(record)?;
+        if args.flag_columns {
+            for (values, field) in column_values.iter_mut().zip(record.iter()) {
+                values.extend_from_slice(field);
+                values.push(b'\n');
+            }
+        }
+        Ok(())
+    };
+    if !rconfig.no_headers {
+        add_record(&headers)?;
+    }
+    let mut record = csv::ByteRecord::new();
+    let mut sampled = 0;
+    while sampled < args.flag_sample_size && rdr.read_byte_record(&mut record)? {
+        add_record(&record)?;
+        sampled += 1;
+    }
+    let sample_input_bytes = rdr.position().byte();
+    let sample_is_whole_file = !rdr.read_byte_record(&mut record)?;
+    let sample = sample_wtr.into_inner().map_err(|e| e.to_string())?;
+    let read_time = read_start.elapsed();
+
+    // how much bigger the whole file is than the sample
+    let scale = if sample_is_whole_file || sample_input_bytes == 0 {
+        1.0
+    } else {
+        file_size as f64 / sample_input_bytes as f64
+    };
+    let estimate = |sample_bytes: usize, elapsed: Duration| Estimate {
+        bytes: (sample_bytes as f64 * scale).round() as u64,
+        secs:  elapsed.as_secs_f64() * scale,
+    };
+
+    #[cfg(feature = "polars")]
+    let mut sample_df = if formats.iter().any(|f| matches!(f, Format::Parquet(_))) {
+        let scratch_dir = util::scratch_dir()?;
+        let sample_path = scratch_dir.path().join("sample.csv");
+        fs::write(&sample_path, &sample)?;
+        let load_start = Instant::now();
+        let df = parquet::load_sample(&sample_path, !rconfig.no_headers)?;
+        Some((df, load_start.elapsed()))
+    } else {
+        None
+    };
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if args.flag_columns {
+        wtr.write_record([
+            "column",
+            "format",
+            "estimated_bytes",
+            "estimated_size",
+            "share",
+        ])?;
+    } else {
+        wtr.write_record([
+            "format",
+            "estimated_bytes",
+            "estimated_size",
+            "ratio",
+            "estimated_secs",
+        ])?;
+    }
+
+    for format in formats {
+        if !args.flag_columns {
+            let convert_start = Instant::now();
+            let (sample_bytes, load_time) = match format {
+                #[cfg(feature = "polars")]
+                Format::Parquet(codec) => {
+                    // safety: sample_df is loaded when there are parquet formats
+                    let (df, load_time) = sample_df.as_mut().unwrap();
+                    (parquet::parquet_size(df, codec, None)?, *load_time)
+                },
+                _ => (compressed_csv_size(format, &sample)?, read_time),
+            };
+            let estimate = estimate(sample_bytes, load_time + convert_start.elapsed());
+            wtr.write_record([
+                format.name(),
+                &estimate.bytes.to_string(),
+                &HumanBytes(estimate.bytes).to_string(),
+                &format!("{:.3}", estimate.bytes as f64 / file_size.max(1) as f64),
+                &format!("{:.2}", estimate.secs),
+            ])?;
+            continue;
+        }
+
+        let mut column_bytes = Vec::with_capacity(headers.len());
+        for (idx, values) in column_values.iter().enumerate() {
+            let sample_bytes = match format {
+                #[cfg(feature = "polars")]
+                Format::Parquet(codec) => {
+                    // safety: sample_df is loaded when there are parquet formats
+                    let (df, _) = sample_df.as_mut().unwrap();
+                    let column = df.get_column_names()[idx].to_string();
+                    parquet::parquet_size(df, codec, Some(&column))?
+                },
+                _ => compressed_csv_size(format, values)?,
+            };
+            column_bytes.push(estimate(sample_bytes, Duration::ZERO).bytes);
+        }
+        let total_bytes: u64 = column_bytes.iter().sum();
+        for (idx, (name, bytes)) in headers.iter().zip(column_bytes).enumerate() {
+            let name = if rconfig.no_headers {
+                (idx + 1).to_string()
+            } else {
+                String::from_utf8_lossy(name).to_string()
+            };
+            wtr.write_record([
+                name.as_str(),
+                format.name(),
+                &bytes.to_string(),
+                &HumanBytes(bytes).to_string(),
+                &format!("{:.3}", bytes as f64 / total_bytes.max(1) as f64),
+            ])?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -728,7 +728,7 @@ async fn sniff_main(mut args: Args) -> CliResult<()> {
     }
 
     let sniffed_ts = chrono::Utc::now().to_rfc3339();
-    let tmpdir = tempfile::tempdir()?;
+    let tmpdir = util::scratch_dir()?;
 
     let future = get_file_to_sniff(&args, &tmpdir);
     let sfile_info = block_on(future)?;
@@ -5,9 +5,21 @@ reverse, unique or random (with optional seed) order.
 The sort is done in lexicographical order.
 https://en.wikipedia.org/wiki/Lexicographic_order
 
+By default, values are compared byte by byte, which orders human-facing text poorly -
+e.g. "file10" before "file2", or "Zebra" before "apple" and accented letters after "z".
+The --natural option compares the runs of digits of the values as numbers (so "file2"
+comes before "file10"), and --locale compares the values with the collation rules of a
+language, e.g. --locale de or --locale sv.
+
+With --key, the rows are sorted by several columns in one pass, each with its own
+direction & comparison type, e.g. --key "date:desc:date,amount:numeric,name" sorts by
+descending date, then by ascending amount, then by name.
+
 Note that this requires reading all of the CSV data into memory. If
-you need to sort a large file that may not fit into memory, use the
-extsort command instead.
+you need to sort a large file that may not fit into memory, use the external
+sort option (--external), which spills sorted runs of rows to disk before merging
+them, with the same sort options. (Or the extsort command, which sorts any
+text file line by line.)
 
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_sort.rs.
 
@@ -21,11 +33,24 @@ sort options:
     -N, --numeric           Compare according to string numerical value
     -R, --reverse           Reverse order
     -i, --ignore-case       Compare strings disregarding case
+    -k, --key <keys>        The comma-separated sort keys, each being a column,
+                            optionally followed by asc or desc (default: asc) and
+                            by text, numeric, date or natural (default: text),
+                            separated by colons, e.g. "date:desc:date,name".
+                            The text keys are compared with the --ignore-case and
+                            the --locale options. Empty & invalid numbers and dates
+                            are first. Replaces --select, and cannot be used with
+                            the --numeric, --reverse, --natural or --random options.
+    --natural               Compare in natural order, the runs of digits of the
+                            values being compared as numbers.
+    --locale <tag>          Compare with the collation rules of the BCP 47 language
+                            tag <tag>, e.g. en, de, sv or es-u-co-trad.
     -u, --unique            When set, identical consecutive lines will be dropped
                             to keep only one line per sorted value.
 
     --random                Random order
     --seed <number>         Random Number Generator (RNG) seed to use if --random is set
+                            If not set, the QSV_SEED environment variable is used.
     --rng <kind>            The RNG algorithm to use if --random is set.
                             Three RNGs are supported:
                             - standard: Use the standard RNG.
@@ -49,6 +74,17 @@ sort options:
                             which is useful for sorting large files that will 
                             otherwise NOT fit in memory using the default allocating
                             stable sort.
+    --external              Do an external merge sort: the rows are sorted in runs
+                            of up to --memory-limit, written to temporary files
+                            and merged, so CSVs larger than memory can be sorted.
+                            Cannot be used with --random.
+    --memory-limit <size>   The maximum size of the rows of an --external sort run
+                            (e.g. 500M, 2G).
+                            [default: 1G]
+    --tmp-dir <dir>         The directory of the --external sort runs. When not
+                            set, a scratch directory for this run is created in
+                            QSV_TMPDIR (or the system temp dir if QSV_TMPDIR is
+                            not set), and removed when the sort is done.
 
 Common options:
     -h, --help              Display this message
@@ -59,13 +95,26 @@ Common options:
                             appear as the header row in the output.
     -d, --delimiter <arg>   The field delimiter for reading CSV data.
                             Must be a single character. (default: ,)
+    --locked-schema <file>  Refuse to run if the input's columns or column types
+                            don't match the JSON Schema <file> (e.g. one generated
+                            by `qsv schema`), listing the differences.
     --memcheck              Check if there is enough memory to load the entire
                             CSV into memory using CONSERVATIVE heuristics.
 "#;
 
-use std::{cmp, str::FromStr};
+use std::{
+    cmp, io,
+    iter::once,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 // use fastrand; //DevSkim: ignore DS148264
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+use icu_collator::{Collator, CollatorOptions, Strength};
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+use icu_locid::Locale;
+use qsv_dateparser::parse_with_preference;
 use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use rand_hc::Hc128Rng;
 use rand_xoshiro::Xoshiro256Plus;
@@ -84,21 +133,28 @@ use crate::{
 
 #[derive(Deserialize)]
 struct Args {
-    arg_input:        Option<String>,
-    flag_select:      SelectColumns,
-    flag_numeric:     bool,
-    flag_reverse:     bool,
-    flag_ignore_case: bool,
-    flag_unique:      bool,
-    flag_random:      bool,
-    flag_seed:        Option<u64>,
-    flag_rng:         String,
-    flag_jobs:        Option<usize>,
-    flag_faster:      bool,
-    flag_output:      Option<String>,
-    flag_no_headers:  bool,
-    flag_delimiter:   Option<Delimiter>,
-    flag_memcheck:    bool,
+    arg_input:          Option<String>,
+    flag_select:        SelectColumns,
+    flag_numeric:       bool,
+    flag_reverse:       bool,
+    flag_ignore_case:   bool,
+    flag_key:           Option<String>,
+    flag_natural:       bool,
+    flag_locale:        Option<String>,
+    flag_unique:        bool,
+    flag_random:        bool,
+    flag_seed:          Option<u64>,
+    flag_rng:           String,
+    flag_jobs:          Option<usize>,
+    flag_faster:        bool,
+    flag_external:      bool,
+    flag_memory_limit:  String,
+    flag_tmp_dir:       Option<String>,
+    flag_output:        Option<String>,
+    flag_no_headers:    bool,
+    flag_delimiter:     Option<Delimiter>,
+    flag_locked_schema: Option<String>,
+    flag_memcheck:      bool,
 }
 
 #[derive(Debug, EnumString, PartialEq)]
@@ -110,7 +166,8 @@ enum RngKind {
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
+    args.flag_seed = util::rng_seed(args.flag_seed)?;
     let numeric = args.flag_numeric;
     let reverse = args.flag_reverse;
     let random = args.flag_random;
@@ -120,6 +177,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
+    if let Some(ref schema_path) = args.flag_locked_schema {
+        util::check_locked_schema(&rconfig, schema_path)?;
+    }
+
     let Ok(rng_kind) = RngKind::from_str(&args.flag_rng) else {
         return fail_incorrectusage_clierror!(
             "Invalid RNG algorithm `{}`. Supported RNGs are: standard, faster, cryptosecure.",
@@ -131,7 +192,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     if let Some(path) = rconfig.path.clone() {
         // we only check if we're doing a stable sort and its not --random
         // coz with --faster option, the sort algorithm sorts in-place (non-allocating)
-        if !faster && !random {
+        if !faster && !random && !args.flag_external {
             util::mem_file_check(&path, false, args.flag_memcheck)?;
         }
     }
@@ -149,6 +210,124 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let ignore_case = args.flag_ignore_case;
 
+    let collation = match args.flag_locale {
+        Some(_) if args.flag_natural => {
+            return fail_incorrectusage_clierror!("--natural cannot be used with --locale.");
+        },
+        #[cfg(any(feature = "feature_capable", feature = "lite"))]
+        Some(ref tag) => {
+            let Ok(locale) = tag.parse::<Locale>() else {
+                return fail_incorrectusage_clierror!("Invalid --locale language tag: {tag}.");
+            };
+            let mut options = CollatorOptions::new();
+            if ignore_case {
+                // the secondary strength ignores the case, but not the accents
+                options.strength = Some(Strength::Secondary);
+            }
+            match Collator::try_new(&(&locale).into(), options) {
+                Ok(collator) => Some(Collation::Locale(collator)),
+                Err(e) => return fail_clierror!("Cannot load the --locale {tag} collation: {e}"),
+            }
+        },
+        #[cfg(not(any(feature = "feature_capable", feature = "lite")))]
+        Some(_) => {
+            return fail_incorrectusage_clierror!("--locale is not supported by qsvdp.");
+        },
+        None if args.flag_natural => Some(Collation::Natural),
+        None => None,
+    };
+    if collation.is_some() && (numeric || random) {
+        return fail_incorrectusage_clierror!(
+            "--natural & --locale cannot be used with --numeric or --random."
+        );
+    }
+    let sort_keys = match args.flag_key {
+        Some(_) if numeric || reverse || random || args.flag_natural => {
+            return fail_incorrectusage_clierror!(
+                "--key cannot be used with --numeric, --reverse, --natural or --random."
+            );
+        },
+        Some(ref keys) => parse_sort_keys(keys, &headers, !rconfig.no_headers)?,
+        None => Vec::new(),
+    };
+    // with --unique, rows are duplicates when their sort columns are identical
+    let same_row = |r1: &csv::ByteRecord, r2: &csv::ByteRecord| {
+        if sort_keys.is_empty() {
+            iter_cmp(sel.select(r1), sel.select(r2)).is_eq()
+        } else {
+            sort_keys
+                .iter()
+                .all(|key| r1.get(key.column) == r2.get(key.column))
+        }
+    };
+
+    if args.flag_external {
+        if random {
+            return fail_incorrectusage_clierror!("--external cannot be used with --random.");
+        }
+        let memory_limit = match util::parse_size(&args.flag_memory_limit) {
+            Ok(bytes) => bytes,
+            Err(e) => return fail_incorrectusage_clierror!("--memory-limit: {e}"),
+        };
+        // if not set, use a scratch dir for this run, which is removed when it goes out
+        // of scope at the end of the sort
+        let scratch_dir;
+        let tmp_dir = match args.flag_tmp_dir {
+            Some(ref tmp_dir) => {
+                if !Path::new(tmp_dir).exists() {
+                    return fail_clierror!("tmp-dir '{tmp_dir}' does not exist");
+                }
+                PathBuf::from(tmp_dir)
+            },
+            None => {
+                scratch_dir = util::scratch_dir()?;
+                scratch_dir.path().to_path_buf()
+            },
+        };
+
+        let cmp_records = |r1: &csv::ByteRecord, r2: &csv::ByteRecord| {
+            if !sort_keys.is_empty() {
+                return cmp_sort_keys(r1, r2, &sort_keys, collation.as_ref(), ignore_case);
+            }
+            let a = sel.select(r1);
+            let b = sel.select(r2);
+            let ordering = if let Some(ref collation) = collation {
+                iter_cmp_collated(a, b, collation, ignore_case)
+            } else if numeric {
+                iter_cmp_num(a, b)
+            } else if ignore_case {
+                iter_cmp_ignore_case(a, b)
+            } else {
+                iter_cmp(a, b)
+            };
+            if reverse {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        };
+
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+        rconfig.write_headers(&mut rdr, &mut wtr)?;
+        let mut prev: Option<csv::ByteRecord> = None;
+        let external_sort = ExternalSort {
+            cmp_records,
+            memory_limit,
+            faster,
+            tmp_dir: &tmp_dir,
+        };
+        external_sort.sort(&mut rdr, |record| {
+            if args.flag_unique {
+                if prev.as_ref().is_some_and(|prev| same_row(record, prev)) {
+                    return Ok(());
+                }
+                prev = Some(record.clone());
+            }
+            Ok(wtr.write_byte_record(record)?)
+        })?;
+        return Ok(wtr.flush()?);
+    }
+
     let mut all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
     match (numeric, reverse, random, faster) {
         // --random sort
@@ -189,6 +368,38 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             }
         },
 
+        // --key sort
+        (false, false, false, _) if !sort_keys.is_empty() => {
+            let cmp_records = |r1: &csv::ByteRecord, r2: &csv::ByteRecord| {
+                cmp_sort_keys(r1, r2, &sort_keys, collation.as_ref(), ignore_case)
+            };
+            if faster {
+                all.par_sort_unstable_by(cmp_records);
+            } else {
+                all.par_sort_by(cmp_records);
+            }
+        },
+
+        // --natural or --locale sort
+        (_, _, false, _) if collation.is_some() => {
+            // safety: the match guard checked there is a collation
+            let collation = collation.as_ref().unwrap();
+            let cmp_records = |r1: &csv::ByteRecord, r2: &csv::ByteRecord| {
+                let a = sel.select(r1);
+                let b = sel.select(r2);
+                if reverse {
+                    iter_cmp_collated(b, a, collation, ignore_case)
+                } else {
+                    iter_cmp_collated(a, b, collation, ignore_case)
+                }
+            };
+            if faster {
+                all.par_sort_unstable_by(cmp_records);
+            } else {
+                all.par_sort_by(cmp_records);
+            }
+        },
+
         // default stable parallel sort
         (false, false, false, false) => all.par_sort_by(|r1, r2| {
             let a = sel.select(r1);
@@ -264,11 +475,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     for r in all {
         if args.flag_unique {
             match prev {
-                Some(other_r) => match iter_cmp(sel.select(&r), sel.select(&other_r)) {
-                    cmp::Ordering::Equal => (),
-                    _ => {
+                Some(other_r) => {
+                    if !same_row(&r, &other_r) {
                         wtr.write_byte_record(&r)?;
-                    },
+                    }
                 },
                 None => {
                     wtr.write_byte_record(&r)?;
@@ -283,6 +493,115 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(wtr.flush()?)
 }
 
+/// the estimated memory overhead of a row, besides its fields
+const RECORD_OVERHEAD: u64 = 64;
+
+/// an external merge sort of CSV rows
+struct ExternalSort<'a, F> {
+    cmp_records:  F,
+    /// the maximum size of the rows of a sorted run
+    memory_limit: u64,
+    faster:       bool,
+    tmp_dir:      &'a Path,
+}
+
+impl<F> ExternalSort<'_, F>
+where
+    F: Fn(&csv::ByteRecord, &csv::ByteRecord) -> cmp::Ordering + Sync,
+{
+    /// sort the rows in runs of up to memory_limit, written to temporary files, and merge
+    /// the runs, passing the sorted rows to `emit`
+    fn sort<R: io::Read>(
+        &self,
+        rdr: &mut csv::Reader<R>,
+        mut emit: impl FnMut(&csv::ByteRecord) -> CliResult<()>,
+    ) -> CliResult<()> {
+        let mut runs: Vec<tempfile::TempPath> = Vec::new();
+        let mut chunk: Vec<csv::ByteRecord> = Vec::new();
+        let mut chunk_size = 0_u64;
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            chunk_size += record.as_slice().len() as u64 + RECORD_OVERHEAD;
+            chunk.push(record.clone());
+            if chunk_size >= self.memory_limit {
+                runs.push(self.write_run(&mut chunk)?);
+                chunk_size = 0;
+            }
+        }
+
+        // the rows fit in memory
+        if runs.is_empty() {
+            self.sort_chunk(&mut chunk);
+            for record in &chunk {
+                emit(record)?;
+            }
+            return Ok(());
+        }
+        if !chunk.is_empty() {
+            runs.push(self.write_run(&mut chunk)?);
+        }
+
+        let mut rdrs = runs
+            .iter()
+            .map(|run| {
+                csv::ReaderBuilder::new()
+                    .has_headers(false)
+                    .flexible(true)
+                    .from_path(run)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut heads = vec![csv::ByteRecord::new(); rdrs.len()];
+        // the runs with rows left, in descending order of their next row - so the next row
+        // of the merge is the one of the last run. The runs are in input order, so the
+        // earlier run goes first on ties, keeping the sort stable.
+        let mut pending: Vec<usize> = Vec::with_capacity(rdrs.len());
+        let insert = |pending: &mut Vec<usize>, heads: &[csv::ByteRecord], run: usize| {
+            let pos = pending.partition_point(|&other| {
+                match (self.cmp_records)(&heads[other], &heads[run]) {
+                    cmp::Ordering::Equal => other > run,
+                    ordering => ordering.is_gt(),
+                }
+            });
+            pending.insert(pos, run);
+        };
+        for (run, rdr) in rdrs.iter_mut().enumerate() {
+            if rdr.read_byte_record(&mut heads[run])? {
+                insert(&mut pending, &heads, run);
+            }
+        }
+        while let Some(run) = pending.pop() {
+            emit(&heads[run])?;
+            if rdrs[run].read_byte_record(&mut heads[run])? {
+                insert(&mut pending, &heads, run);
+            }
+        }
+        Ok(())
+    }
+
+    fn sort_chunk(&self, chunk: &mut [csv::ByteRecord]) {
+        if self.faster {
+            chunk.par_sort_unstable_by(&self.cmp_records);
+        } else {
+            chunk.par_sort_by(&self.cmp_records);
+        }
+    }
+
+    /// sort the chunk & write it to a temporary file, emptying it
+    fn write_run(&self, chunk: &mut Vec<csv::ByteRecord>) -> CliResult<tempfile::TempPath> {
+        self.sort_chunk(chunk);
+        let run = tempfile::Builder::new()
+            .prefix("sort-run-")
+            .tempfile_in(self.tmp_dir)?
+            .into_temp_path();
+        let mut wtr = csv::WriterBuilder::new().flexible(true).from_path(&run)?;
+        for record in chunk.drain(..) {
+            wtr.write_byte_record(&record)?;
+        }
+        wtr.flush()?;
+        Ok(run)
+    }
+}
+
 /// Order `a` and `b` lexicographically using `Ord`
 #[inline]
 pub fn iter_cmp<A, L, R>(mut a: L, mut b: R) -> cmp::Ordering
@@ -304,6 +623,200 @@ where
     }
 }
 
+/// a --key sort key
+struct SortKey {
+    column:     usize,
+    descending: bool,
+    kind:       KeyKind,
+}
+
+/// how the values of a sort key are compared
+#[derive(Clone, Copy)]
+enum KeyKind {
+    Text,
+    Numeric,
+    Date,
+    Natural,
+}
+
+/// parse the --key sort keys, e.g. "date:desc:date,amount:numeric,name"
+fn parse_sort_keys(
+    keys: &str,
+    headers: &csv::ByteRecord,
+    use_names: bool,
+) -> CliResult<Vec<SortKey>> {
+    let mut sort_keys = Vec::new();
+    for key in keys.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+        let mut parts = key.split(':').map(str::trim);
+        // safety: split always returns at least one part
+        let column = parts.next().unwrap();
+        let sel = SelectColumns::parse(column)?.selection(headers, use_names)?;
+        if sel.len() != 1 {
+            return fail_incorrectusage_clierror!(
+                "The --key column {column} must be a single column."
+            );
+        }
+        let mut sort_key = SortKey {
+            column:     sel[0],
+            descending: false,
+            kind:       KeyKind::Text,
+        };
+        for part in parts {
+            match part.to_ascii_lowercase().as_str() {
+                "asc" => sort_key.descending = false,
+                "desc" => sort_key.descending = true,
+                "text" => sort_key.kind = KeyKind::Text,
+                "numeric" => sort_key.kind = KeyKind::Numeric,
+                "date" => sort_key.kind = KeyKind::Date,
+                "natural" => sort_key.kind = KeyKind::Natural,
+                _ => {
+                    return fail_incorrectusage_clierror!(
+                        "Invalid --key {key}: {part} is not asc, desc, text, numeric, date or \
+                         natural."
+                    )
+                },
+            }
+        }
+        sort_keys.push(sort_key);
+    }
+    if sort_keys.is_empty() {
+        return fail_incorrectusage_clierror!("--key requires at least one column.");
+    }
+    Ok(sort_keys)
+}
+
+/// Order the records by their sort keys
+fn cmp_sort_keys(
+    r1: &csv::ByteRecord,
+    r2: &csv::ByteRecord,
+    sort_keys: &[SortKey],
+    collation: Option<&Collation>,
+    ignore_case: bool,
+) -> cmp::Ordering {
+    for key in sort_keys {
+        let a = r1.get(key.column).unwrap_or_default();
+        let b = r2.get(key.column).unwrap_or_default();
+        let ordering = match key.kind {
+            KeyKind::Text => match collation {
+                Some(collation) => iter_cmp_collated(once(a), once(b), collation, ignore_case),
+                None if ignore_case => iter_cmp_ignore_case(once(a), once(b)),
+                None => a.cmp(b),
+            },
+            KeyKind::Numeric => iter_cmp_num(once(a), once(b)),
+            KeyKind::Date => cmp_dates(a, b),
+            KeyKind::Natural => natural_cmp(a, b, ignore_case),
+        };
+        let ordering = if key.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    cmp::Ordering::Equal
+}
+
+/// Order `a` and `b` as dates, the empty & invalid dates being first
+fn cmp_dates(a: &[u8], b: &[u8]) -> cmp::Ordering {
+    let timestamp = |value: &[u8]| {
+        from_utf8(value)
+            .ok()
+            .and_then(|value| parse_with_preference(value.trim(), false).ok())
+            .map(|datetime| datetime.timestamp_millis())
+    };
+    match (timestamp(a), timestamp(b)) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        (None, Some(_)) => cmp::Ordering::Less,
+        (Some(_), None) => cmp::Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// a sort order for human-facing text
+enum Collation {
+    /// the runs of digits compared as numbers, e.g. file2 < file10
+    Natural,
+    /// the collation rules of a language
+    #[cfg(any(feature = "feature_capable", feature = "lite"))]
+    Locale(Collator),
+}
+
+/// Order `a` and `b` with a natural order or a locale's collation
+#[inline]
+fn iter_cmp_collated<'a, L, R>(
+    mut a: L,
+    mut b: R,
+    collation: &Collation,
+    ignore_case: bool,
+) -> cmp::Ordering
+where
+    L: Iterator<Item = &'a [u8]>,
+    R: Iterator<Item = &'a [u8]>,
+{
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return cmp::Ordering::Equal,
+            (None, _) => return cmp::Ordering::Less,
+            (_, None) => return cmp::Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ordering = match collation {
+                    Collation::Natural => natural_cmp(x, y, ignore_case),
+                    #[cfg(any(feature = "feature_capable", feature = "lite"))]
+                    Collation::Locale(collator) => {
+                        collator.compare(&String::from_utf8_lossy(x), &String::from_utf8_lossy(y))
+                    },
+                };
+                if ordering.is_ne() {
+                    return ordering;
+                }
+            },
+        }
+    }
+}
+
+/// Order `a` and `b` in natural order, comparing their runs of digits as numbers
+fn natural_cmp(a: &[u8], b: &[u8], ignore_case: bool) -> cmp::Ordering {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i].is_ascii_digit() && b[j].is_ascii_digit() {
+            let (start_a, start_b) = (i, j);
+            while i < a.len() && a[i].is_ascii_digit() {
+                i += 1;
+            }
+            while j < b.len() && b[j].is_ascii_digit() {
+                j += 1;
+            }
+            // without their leading zeros, the longer number is the greater one
+            let num_a = trim_leading_zeros(&a[start_a..i]);
+            let num_b = trim_leading_zeros(&b[start_b..j]);
+            let ordering = num_a.len().cmp(&num_b.len()).then_with(|| num_a.cmp(num_b));
+            if ordering.is_ne() {
+                return ordering;
+            }
+        } else {
+            let (x, y) = if ignore_case {
+                (a[i].to_ascii_lowercase(), b[j].to_ascii_lowercase())
+            } else {
+                (a[i], b[j])
+            };
+            if x != y {
+                return x.cmp(&y);
+            }
+            i += 1;
+            j += 1;
+        }
+    }
+    (a.len() - i).cmp(&(b.len() - j))
+}
+
+#[inline]
+fn trim_leading_zeros(digits: &[u8]) -> &[u8] {
+    let zeros = digits.iter().take_while(|&&digit| digit == b'0').count();
+    &digits[zeros..]
+}
+
 /// Try parsing `a` and `b` as numbers when ordering
 #[inline]
 pub fn iter_cmp_num<'a, L, R>(mut a: L, mut b: R) -> cmp::Ordering
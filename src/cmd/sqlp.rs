@@ -67,6 +67,10 @@ Example queries:
    qsv sqlp people.csv "WITH millennials AS (SELECT * FROM people WHERE age >= 25 and age <= 40) \
      SELECT * FROM millennials WHERE STARTS_WITH(name,'C')"
 
+  # window functions, e.g. the share of each sale in the total of its region
+   qsv sqlp sales.csv "SELECT region, amount, \
+     amount / SUM(amount) OVER (PARTITION BY region) AS region_share FROM sales"
+
   # CASE statement
    qsv sqlp data.csv "select CASE WHEN col1 > 10 THEN 'foo' WHEN col1 > 5 THEN 'bar' ELSE 'baz' END from data"
    qsv sqlp data.csv "select CASE col*5 WHEN 10 THEN 'foo' WHEN 5 THEN 'bar' ELSE 'baz' END from _t_1"
@@ -136,13 +140,18 @@ Example queries:
   # automatic snappy decompression/compression
    qsv sqlp data.csv.sz 'select * from data where col1 > 10' --output result.csv.sz
 
+  # register the global functions of a Luau script as SQL scalar functions
+  # e.g. if udf.luau contains:
+  #   function tier(amount) if tonumber(amount) > 1000 then return "gold" end return "std" end
+   qsv sqlp data.csv "select name, tier(amount) as tier from data" --udf udf.luau
+
   # explain query plan
    qsv sqlp data.csv 'explain select * from data where col1 > 10 order by col2 desc limit 20'
 
 For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_sqlp.rs.
 
 Usage:
-    qsv sqlp [options] <input>... <sql>
+    qsv sqlp [--codec <col=enc>...] [options] <input>... <sql>
     qsv sqlp --help
 
 sqlp arguments:
@@ -175,6 +184,11 @@ sqlp options:
                                 arrow    Apache Arrow IPC
                                 avro     Apache Avro
                               [default: csv]
+    --udf <file>              A Luau script whose global functions are registered as
+                              user-defined scalar functions callable in the SQL query/ies.
+                              Arguments are passed to the functions as strings (or nil
+                              for nulls) and the return values are converted to strings.
+                              Requires the luau feature.
 
                               POLARS CSV INPUT PARSING OPTIONS:
     --try-parsedates          Automatically try to parse dates/datetimes and time.
@@ -240,6 +254,20 @@ sqlp options:
                               Higher compression levels are slower.
                               The zstd default is 3, and the gzip default is 6.
     --statistics              Compute column statistics when writing parquet files.
+    --auto-encode             Use the column statistics of the query result to pick a more
+                              compact encoding for each column before writing:
+                                dictionary  string columns whose distinct values are at most
+                                            10% of the rows are written as dictionary-encoded
+                                            categoricals.
+                                narrow      integer columns are narrowed to the smallest
+                                            integer type that holds their min & max values
+                                            (e.g. i64 -> u8).
+                                plain       the other columns are written as is.
+                              The encoding picked for each column is reported to stderr.
+    --codec <col=enc>         Override the encoding of a column, where <enc> is
+                              dictionary, narrow or plain. Can be repeated.
+                              Can be used without --auto-encode to only encode the
+                              given columns.
     
 Common options:
     -h, --help             Display this message
@@ -265,12 +293,18 @@ use polars::{
     datatypes::PlSmallStr,
     io::avro::{AvroWriter, Compression as AvroCompression},
     prelude::{
-        CsvWriter, DataFrame, GzipLevel, IpcCompression, IpcWriter, JsonFormat, JsonWriter,
-        LazyCsvReader, LazyFileListReader, NullValues, OptFlags, ParquetCompression, ParquetWriter,
+        col, CategoricalOrdering, CsvWriter, DataFrame, DataType, GzipLevel, IntoLazy,
+        IpcCompression, IpcWriter, JsonFormat, JsonWriter, LazyCsvReader, LazyFileListReader,
+        NullValues, OptFlags, ParquetCompression, ParquetWriter, PolarsError, PolarsResult,
         SerWriter, StatisticsOptions, ZstdLevel,
     },
     sql::SQLContext,
 };
+#[cfg(feature = "luau")]
+use polars::{
+    prelude::{GetOutput, Series, UserDefinedFunction},
+    sql::function_registry::{DefaultFunctionRegistry, FunctionRegistry},
+};
 use regex::Regex;
 use serde::Deserialize;
 
@@ -290,6 +324,7 @@ struct Args {
     arg_input:                  Vec<PathBuf>,
     arg_sql:                    String,
     flag_format:                String,
+    flag_udf:                   Option<String>,
     flag_try_parsedates:        bool,
     flag_infer_len:             usize,
     flag_streaming:             bool,
@@ -307,6 +342,8 @@ struct Args {
     flag_compression:           String,
     flag_compress_level:        Option<i32>,
     flag_statistics:            bool,
+    flag_auto_encode:           bool,
+    flag_codec:                 Vec<String>,
     flag_output:                Option<String>,
     flag_delimiter:             Option<Delimiter>,
     flag_quiet:                 bool,
@@ -411,6 +448,20 @@ impl OutputMode {
                         }
                     };
 
+                    if args.flag_auto_encode || !args.flag_codec.is_empty() {
+                        // safety: the --codec overrides were already checked in run()
+                        let overrides = parse_codec_overrides(&args.flag_codec).unwrap_or_default();
+                        let (encoded_df, encodings) = encode_columns(
+                            std::mem::take(&mut df),
+                            args.flag_auto_encode,
+                            &overrides,
+                        )?;
+                        df = encoded_df;
+                        if !args.flag_quiet {
+                            eprintln!("Parquet column encodings:\n{}", encodings.join("\n"));
+                        }
+                    }
+
                     ParquetWriter::new(&mut w)
                         .with_row_group_size(Some(768 ^ 2))
                         .with_statistics(statistics_options)
@@ -507,6 +558,198 @@ enum QsvAvroCompression {
     Snappy,
 }
 
+/// the encoding of a column when writing Parquet with --auto-encode or --codec
+#[derive(Copy, Clone, PartialEq)]
+enum ColumnEncoding {
+    Plain,
+    Dictionary,
+    Narrow,
+}
+
+impl FromStr for ColumnEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" => Ok(ColumnEncoding::Plain),
+            "dictionary" => Ok(ColumnEncoding::Dictionary),
+            "narrow" => Ok(ColumnEncoding::Narrow),
+            _ => Err(format!(
+                "Invalid column encoding: {s}. Valid encodings are dictionary, narrow and plain."
+            )),
+        }
+    }
+}
+
+// the maximum ratio of distinct values to rows for --auto-encode to dictionary encode a column
+const DICTIONARY_MAX_DISTINCT_RATIO: f64 = 0.1;
+
+/// parse the --codec <column>=<encoding> overrides
+fn parse_codec_overrides(specs: &[String]) -> Result<HashMap<String, ColumnEncoding>, String> {
+    specs
+        .iter()
+        .map(|spec| {
+            let Some((column, encoding)) = spec.rsplit_once('=') else {
+                return Err(format!(
+                    "Invalid --codec {spec}. Expected <column>=<encoding>."
+                ));
+            };
+            Ok((column.to_string(), encoding.parse()?))
+        })
+        .collect()
+}
+
+/// the size in bytes of an integer type
+fn int_type_size(dtype: &DataType) -> usize {
+    match dtype {
+        DataType::Int8 | DataType::UInt8 => 1,
+        DataType::Int16 | DataType::UInt16 => 2,
+        DataType::Int32 | DataType::UInt32 => 4,
+        _ => 8,
+    }
+}
+
+/// the smallest integer type that holds all the values between min and max
+fn narrowest_int_type(min: i64, max: i64) -> DataType {
+    if min >= 0 {
+        if max <= u8::MAX.into() {
+            DataType::UInt8
+        } else if max <= u16::MAX.into() {
+            DataType::UInt16
+        } else if max <= u32::MAX.into() {
+            DataType::UInt32
+        } else {
+            DataType::UInt64
+        }
+    } else if min >= i8::MIN.into() && max <= i8::MAX.into() {
+        DataType::Int8
+    } else if min >= i16::MIN.into() && max <= i16::MAX.into() {
+        DataType::Int16
+    } else if min >= i32::MIN.into() && max <= i32::MAX.into() {
+        DataType::Int32
+    } else {
+        DataType::Int64
+    }
+}
+
+/// Pick the encoding of each column of the query result from its statistics (with
+/// --auto-encode) and the --codec overrides, and cast the columns accordingly.
+/// Returns the encoded DataFrame and a description of the encoding of each column.
+fn encode_columns(
+    df: DataFrame,
+    auto_encode: bool,
+    overrides: &HashMap<String, ColumnEncoding>,
+) -> PolarsResult<(DataFrame, Vec<String>)> {
+    let schema = df.schema();
+    for column in overrides.keys() {
+        if schema.get(column.as_str()).is_none() {
+            return Err(PolarsError::ColumnNotFound(
+                format!("--codec column {column} is not in the query result.").into(),
+            ));
+        }
+    }
+
+    // compute the statistics we need for all the columns in a single pass
+    let mut stat_exprs = Vec::new();
+    for (idx, (name, dtype)) in schema.iter().enumerate() {
+        if dtype == &DataType::String {
+            stat_exprs.push(
+                col(name.as_str())
+                    .n_unique()
+                    .alias(format!("{idx}_n_unique").as_str()),
+            );
+        } else if dtype.is_integer() {
+            stat_exprs.push(
+                col(name.as_str())
+                    .min()
+                    .alias(format!("{idx}_min").as_str()),
+            );
+            stat_exprs.push(
+                col(name.as_str())
+                    .max()
+                    .alias(format!("{idx}_max").as_str()),
+            );
+        }
+    }
+    let stats = if stat_exprs.is_empty() {
+        DataFrame::default()
+    } else {
+        df.clone().lazy().select(stat_exprs).collect()?
+    };
+    let stat = |name: String| -> Option<i64> {
+        stats
+            .column(&name)
+            .ok()
+            .and_then(|column| column.get(0).ok())
+            .and_then(|value| value.extract::<i64>())
+    };
+
+    #[allow(clippy::cast_precision_loss)]
+    let max_dictionary_size = (df.height() as f64 * DICTIONARY_MAX_DISTINCT_RATIO) as i64;
+    let mut casts = Vec::new();
+    let mut encodings = Vec::with_capacity(schema.len());
+    for (idx, (name, dtype)) in schema.iter().enumerate() {
+        let n_unique = stat(format!("{idx}_n_unique"));
+        let min_max = stat(format!("{idx}_min")).zip(stat(format!("{idx}_max")));
+        let encoding = match overrides.get(name.as_str()) {
+            Some(encoding) => *encoding,
+            None if !auto_encode => continue,
+            None if n_unique.is_some_and(|n_unique| n_unique <= max_dictionary_size) => {
+                ColumnEncoding::Dictionary
+            },
+            None if min_max.is_some() => ColumnEncoding::Narrow,
+            None => ColumnEncoding::Plain,
+        };
+
+        match encoding {
+            ColumnEncoding::Dictionary => {
+                if dtype != &DataType::String {
+                    return Err(PolarsError::InvalidOperation(
+                        format!(
+                            "--codec {name}=dictionary: only string columns can be dictionary \
+                             encoded."
+                        )
+                        .into(),
+                    ));
+                }
+                casts.push(
+                    col(name.as_str())
+                        .cast(DataType::Categorical(None, CategoricalOrdering::Physical)),
+                );
+                encodings.push(format!(
+                    "  {name}: dictionary ({} distinct values)",
+                    n_unique.unwrap_or_default()
+                ));
+            },
+            ColumnEncoding::Narrow => {
+                if !dtype.is_integer() {
+                    return Err(PolarsError::InvalidOperation(
+                        format!("--codec {name}=narrow: only integer columns can be narrowed.")
+                            .into(),
+                    ));
+                }
+                // an all-null column has no min/max, and stays as is
+                let narrow_type = min_max.map(|(min, max)| narrowest_int_type(min, max));
+                match narrow_type {
+                    Some(narrow_type) if int_type_size(&narrow_type) < int_type_size(dtype) => {
+                        casts.push(col(name.as_str()).cast(narrow_type.clone()));
+                        encodings.push(format!("  {name}: narrow ({dtype} -> {narrow_type})"));
+                    },
+                    _ => encodings.push(format!("  {name}: plain ({dtype} is already narrowest)")),
+                }
+            },
+            ColumnEncoding::Plain => encodings.push(format!("  {name}: plain")),
+        }
+    }
+
+    let df = if casts.is_empty() {
+        df
+    } else {
+        df.lazy().with_columns(casts).collect()?
+    };
+    Ok((df, encodings))
+}
+
 impl FromStr for PqtCompression {
     type Err = String;
 
@@ -548,10 +791,116 @@ impl FromStr for QsvAvroCompression {
     }
 }
 
+/// Builds a SQL function registry from the global functions defined in a Luau script.
+///
+/// mlua's Luau VM is not Send, so each UDF invocation (one per Series chunk, not per row)
+/// spins up its own VM from the script source.
+#[cfg(feature = "luau")]
+fn luau_udf_registry(udf_script: &str) -> CliResult<DefaultFunctionRegistry> {
+    let script = std::fs::read_to_string(udf_script)
+        .map_err(|e| format!("Cannot read --udf script {udf_script}: {e}"))?;
+
+    // the globals a fresh VM starts with, so we only register the script's own functions
+    let luau = mlua::Lua::new();
+    let builtin_globals = luau
+        .globals()
+        .pairs::<String, mlua::Value>()
+        .filter_map(Result::ok)
+        .map(|(name, _)| name)
+        .collect::<std::collections::HashSet<String>>();
+    luau.load(&script).set_name(udf_script).exec()?;
+
+    let mut registry = DefaultFunctionRegistry::default();
+    let mut udf_count = 0_usize;
+    for pair in luau.globals().pairs::<String, mlua::Value>() {
+        let (name, value) = pair?;
+        if !matches!(value, mlua::Value::Function(_)) || builtin_globals.contains(&name) {
+            continue;
+        }
+        log::debug!("registering Luau UDF: {name}");
+
+        let udf_source = script.clone();
+        let udf_name = name.clone();
+        let udf = UserDefinedFunction::new(
+            PlSmallStr::from_str(&name),
+            vec![],
+            GetOutput::from_type(DataType::String),
+            move |columns: &mut [Series]| call_luau_udf(&udf_source, &udf_name, columns),
+        );
+        if let Err(e) = registry.register(&name, udf) {
+            return fail_clierror!("Cannot register UDF {name}: {e}");
+        }
+        udf_count += 1;
+    }
+    if udf_count == 0 {
+        return fail_incorrectusage_clierror!("--udf script {udf_script} defines no functions.");
+    }
+    Ok(registry)
+}
+
+/// Calls the Luau function `udf_name` row by row over `columns`.
+/// Literal arguments arrive as length-1 Series and are broadcast.
+#[cfg(feature = "luau")]
+fn call_luau_udf(
+    udf_source: &str,
+    udf_name: &str,
+    columns: &mut [Series],
+) -> PolarsResult<Option<Series>> {
+    let luau_err = |e: mlua::Error| PolarsError::ComputeError(format!("{udf_name}: {e}").into());
+
+    let luau = mlua::Lua::new();
+    luau.load(udf_source).exec().map_err(luau_err)?;
+    let udf: mlua::Function = luau.globals().get(udf_name).map_err(luau_err)?;
+
+    let string_columns = columns
+        .iter()
+        .map(|column| column.cast(&DataType::String))
+        .collect::<PolarsResult<Vec<Series>>>()?;
+    let string_columns = string_columns
+        .iter()
+        .map(Series::str)
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let height = string_columns.iter().map(|ca| ca.len()).max().unwrap_or(0);
+
+    let mut results: Vec<Option<String>> = Vec::with_capacity(height);
+    let mut udf_args = Vec::with_capacity(string_columns.len());
+    for row in 0..height {
+        udf_args.clear();
+        for ca in &string_columns {
+            let idx = if ca.len() == 1 { 0 } else { row };
+            udf_args.push(match ca.get(idx) {
+                Some(val) => mlua::Value::String(luau.create_string(val).map_err(luau_err)?),
+                None => mlua::Value::Nil,
+            });
+        }
+        let retval: mlua::Value = udf
+            .call(mlua::MultiValue::from_vec(udf_args.clone()))
+            .map_err(luau_err)?;
+        results.push(match retval {
+            mlua::Value::Nil => None,
+            mlua::Value::Boolean(b) => Some(b.to_string()),
+            mlua::Value::Integer(i) => Some(i.to_string()),
+            mlua::Value::Number(n) => Some(n.to_string()),
+            mlua::Value::String(s) => Some(s.to_string_lossy().to_string()),
+            other => {
+                return Err(PolarsError::ComputeError(
+                    format!(
+                        "{udf_name} returned an unsupported {} value",
+                        other.type_name()
+                    )
+                    .into(),
+                ))
+            },
+        });
+    }
+
+    Ok(Some(Series::new(PlSmallStr::from_str(udf_name), results)))
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
 
-    let tmpdir = tempfile::tempdir()?;
+    let tmpdir = util::scratch_dir()?;
 
     let mut skip_input = false;
     args.arg_input = if args.arg_input == [PathBuf::from_str("SKIP_INPUT").unwrap()] {
@@ -581,6 +930,16 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     };
 
     let output_mode: OutputMode = args.flag_format.parse().unwrap_or(OutputMode::Csv);
+    if args.flag_auto_encode || !args.flag_codec.is_empty() {
+        if output_mode != OutputMode::Parquet {
+            return fail_incorrectusage_clierror!(
+                "--auto-encode and --codec can only be used with --format parquet."
+            );
+        }
+        if let Err(e) = parse_codec_overrides(&args.flag_codec) {
+            return fail_incorrectusage_clierror!("{e}");
+        }
+    }
     let no_output: OutputMode = OutputMode::None;
 
     let delim = if let Some(delimiter) = args.flag_delimiter {
@@ -653,6 +1012,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     }
 
     let mut ctx = SQLContext::new();
+    if let Some(ref udf_script) = args.flag_udf {
+        #[cfg(feature = "luau")]
+        {
+            ctx = ctx.with_function_registry(std::sync::Arc::new(luau_udf_registry(udf_script)?));
+        }
+        #[cfg(not(feature = "luau"))]
+        {
+            return fail_incorrectusage_clierror!(
+                "--udf {udf_script} requires qsv to be built with the luau feature."
+            );
+        }
+    }
     let mut table_aliases = HashMap::with_capacity(args.arg_input.len());
     let mut lossy_table_name = Cow::default();
     let mut table_name;
@@ -28,9 +28,73 @@ It returns "*ALL" if all the values are unique, and only returns a preview of th
 If you need all the antimode values of a column, run the `frequency` command with --limit set
 to zero. The resulting frequency table will have all the antimode values.
 
+As the exact cardinality requires loading all the values of the columns in memory, it can
+exhaust the memory of very wide or high-cardinality data. With --approx-cardinality, the
+cardinality is estimated instead with a HyperLogLog sketch per column, using a fixed amount
+of memory - 16KB per column with the default --cardinality-error of 1%. A cardinality_mode
+column is then added after the other columns, set to "approximate" when the cardinality was
+estimated, or "exact" when it was computed from the values already loaded in memory for
+the modes (with --mode or --everything).
+
+With --percentiles, arbitrary percentiles (e.g. the p95 & p99 of latency-style columns) are
+computed, adding a p<N> column for each requested percentile after the other columns.
+They're estimated in a single pass with constant memory using a t-digest, whose estimates
+are most accurate at the extreme percentiles. With --quartiles or --everything, as all the
+values are already loaded in memory, they're computed exactly instead, interpolating linearly
+between the closest ranks.
+
+With --outliers, each Integer & Float column is screened for outliers, adding the
+outlier_lower_fence, outlier_upper_fence & outlier_count columns after the other columns.
+Values below the lower fence or above the upper fence are outliers. The fences are:
+  * iqr     the inner fences - q1 - 1.5 * IQR & q3 + 1.5 * IQR. This enables --quartiles.
+  * zscore  mean - 3 * stddev & mean + 3 * stddev, i.e. values with a z-score above 3.
+As the fences are only known once the stats are computed, the outliers are counted in
+a second pass over the input. With --outliers-output, the outliers are also written to
+a CSV with their row (the 1-based record number), field & value columns.
+
+With --histogram, the distribution of each column is also written to a sidecar file,
+<FILESTEM>.stats.histogram.csv by default, computed in a second pass over the input:
+  * Integer & Float columns  the counts of the values in --histogram equal-width bins
+                             between the column's min & max, with each bin's lower &
+                             upper edges. A value equal to an upper edge is in the next bin,
+                             except for the max, which is in the last bin.
+  * other columns            the --histogram most frequent values & their counts, most
+                             frequent first. The distinct values are loaded in memory.
+NULLs are not counted. The CSV sidecar has the field, lower, upper, value & count columns.
+If --histogram-file ends with .json, it's written as a JSON object instead, with the
+"edges" & "counts" of the bins, or the "values" & "counts" of the top values of each field.
+
+With --group-by, the stats are computed for each group of rows with the same values in its
+columns, with a row per group & column preceded by the group's values, instead of
+having to partition the input first. The groups are aggregated in memory in a single pass.
+Once --group-limit groups are in memory, the rows of the new groups are spilled to temporary
+partitions on disk, which are then aggregated in turn - so the groups are written in the order
+they first appear in the input, but when groups are spilled, one partition at a time.
+The --group-by columns are not summarized, and grouped stats are not cached.
+
+With --weight, the values of each row are weighted by the value of the --weight column, as
+survey & sampling datasets require. The sum, mean, stddev, variance, cv, median, quartiles &
+percentiles are then weighted, while the other stats (e.g. min/max, MAD, modes & cardinality)
+are not. The SEM is computed with the effective sample size (sum of weights)^2 / sum of
+squared weights. A weighted quantile is the smallest value whose cumulative weight reaches
+that quantile of the total weight, averaged with the next value when it's reached exactly.
+As the values are loaded in memory for the weighted quantiles, weighted --percentiles are
+computed exactly instead of being estimated with a t-digest.
+Rows whose weight is empty, not a number, zero or negative are left out of the weighted stats.
+The --weight column itself is summarized unweighted, so its sum is the total weight.
+
 Summary statistics for dates are also computed when --infer-dates is enabled, with DateTime
 results in rfc3339 format and Date results in "yyyy-mm-dd" format in the UTC timezone.
 Date range, stddev, variance, MAD & IQR are returned in days, not timestamp milliseconds.
+With --date-details, these columns are also added after the other columns, for Date &
+DateTime columns:
+  * date_range_human   the range as a duration in words with its two largest units, e.g.
+                       "2 years 45 days" - years being 365 days.
+  * date_granularity   the most common precision of the values: yearly (January 1st),
+                       monthly (the 1st of a month), daily, hourly, minutely, secondly
+                       or subsecond.
+  * date_tz_offsets    the distinct UTC offsets of the values that have one, e.g. +02:00.
+  * date_future_count  the number of values after the time of the stats run.
 
 Each column's data type is also inferred (NULL, Integer, String, Float, Date, DateTime and
 Boolean with --infer-boolean option).
@@ -58,6 +122,14 @@ The arguments used to generate the cached stats are saved in <FILESTEM>.stats.cs
 If stats have already been computed for the input file with similar arguments and the file
 hasn't changed, the stats will be loaded from the cache instead of recomputing it.
 
+When all the stats are computed in bounded memory (i.e. without --everything, --mode, an
+exact --cardinality, --median, --mad or --quartiles), the state of the stats is also cached,
+in <FILESTEM>.stats.csv.state.json. If the input file has since only grown - as with the
+log-style CSVs that are appended to constantly - the stats are then updated incrementally
+from the appended rows instead of being recomputed from scratch. The input is deemed to
+only have grown if it's larger, and the first & last 64KB of the previously summarized
+bytes, which must end with a newline, are unchanged.
+
 These cached stats are also used by other qsv commands (currently `schema` & `tojsonl`) to
 load the stats into memory faster. If the cached stats are not current (i.e., the input file
 is newer than the cached stats), the cached stats will be ignored and recomputed. For example,
@@ -137,6 +209,13 @@ stats options:
                               This requires loading all CSV data in memory.
     --cardinality             Compute the cardinality.
                               This requires loading all CSV data in memory.
+    --approx-cardinality      Estimate the cardinality with a HyperLogLog sketch in bounded
+                              memory, instead of computing it exactly.
+                              This enables the --cardinality option.
+    --cardinality-error <pct>  The target relative standard error of the estimated
+                              cardinality, in percent. Must be at least 0.21.
+                              The smaller it is, the more memory the sketches use.
+                              [default: 1]
     --median                  Compute the median.
                               This requires loading all CSV data in memory.
     --mad                     Compute the median absolute deviation (MAD).
@@ -144,6 +223,30 @@ stats options:
     --quartiles               Compute the quartiles, the IQR, the lower/upper inner/outer
                               fences and skewness.
                               This requires loading all CSV data in memory.
+    --percentiles <list>      Compute the comma-separated percentiles, e.g. 1,5,25,75,95,99.
+                              Percentiles must be between 0 and 100, and can have decimals
+                              (e.g. 99.9). They are estimated with a streaming t-digest,
+                              unless --quartiles or --everything is set, in which case they
+                              are exact.
+    --outliers <method>       Count the outliers of Integer & Float columns, and report their
+                              fences. The method is either "iqr" or "zscore".
+                              See the outliers section above.
+    --outliers-output <file>  Also write the outliers to <file>, with their row, field
+                              & value. Requires --outliers.
+    --histogram <bins>        Also write the distribution of each column, with <bins> bins
+                              for Integer & Float columns, and the top <bins> values for
+                              the other columns. See the histogram section above.
+    --histogram-file <file>   Write the histograms to <file> instead of the
+                              <FILESTEM>.stats.histogram.csv sidecar. Written as JSON
+                              if <file> ends with .json. Required when reading from stdin.
+    -g, --group-by <arg>      Compute the stats for each group of rows with the same values
+                              in these columns. See 'qsv select --help' for the format details.
+                              See the grouped stats section above.
+    --group-limit <n>         The number of groups aggregated in memory with --group-by,
+                              before the rows of the new groups are spilled to disk.
+                              [default: 10000]
+    --weight <column>         Weight the values of each row by the value of this column.
+                              See the weighted stats section above.
     --round <decimal_places>  Round statistics to <decimal_places>. Rounding is done following
                               Midpoint Nearest Even (aka "Bankers Rounding") rule.
                               https://docs.rs/rust_decimal/latest/rust_decimal/enum.RoundingStrategy.html
@@ -176,6 +279,9 @@ stats options:
                               [default: date,time,due,open,close,created]
     --prefer-dmy              Parse dates in dmy format. Otherwise, use mdy format.
                               Ignored if --infer-dates is false.
+    --date-details            Also report the humanized range, granularity, UTC offsets
+                              & future-dated count of Date & DateTime columns.
+                              Requires --infer-dates. See the date section above.
     --force                   Force recomputing stats even if valid precomputed stats
                               cache exists.
     -j, --jobs <arg>          The number of jobs to run in parallel.
@@ -190,6 +296,11 @@ stats options:
                               You can preemptively create the stats-jsonl file by using
                               this option BEFORE running the `frequency`, `schema` & `tojsonl`
                               commands and they will automatically use it.
+    --format <arg>            The output format: csv, json or jsonl. With json & jsonl,
+                              there's an object per row of the CSV output, whose stats
+                              are typed - numbers are numbers & empty stats are null.
+                              The cached stats are always CSV.
+                              [default: csv]
  -c, --cache-threshold <arg>  When greater than 1, the threshold in milliseconds before caching
                               stats results. If a stats run takes longer than this threshold,
                               the stats results will be cached.
@@ -237,15 +348,19 @@ with ~500 tests.
 */
 
 use std::{
+    collections::BTreeSet,
     default::Default,
     fmt, fs, io,
-    io::Write,
+    io::{Read, Seek, SeekFrom, Write},
     iter::repeat,
     path::{Path, PathBuf},
     str,
     sync::OnceLock,
 };
 
+use ahash::AHashMap;
+use chrono::Datelike;
+use indexmap::IndexMap;
 use itertools::Itertools;
 use qsv_dateparser::parse_with_preference;
 use serde::{Deserialize, Serialize};
@@ -254,6 +369,7 @@ use simdutf8::basic::from_utf8;
 use stats::{merge_all, Commute, MinMax, OnlineStats, Unsorted};
 use tempfile::NamedTempFile;
 use threadpool::ThreadPool;
+use xxhash_rust::xxh3::{xxh3_64, xxh3_64_with_seed};
 
 use self::FieldType::{TDate, TDateTime, TFloat, TInteger, TNull, TString};
 use crate::{
@@ -265,29 +381,41 @@ use crate::{
 #[allow(clippy::unsafe_derive_deserialize)]
 #[derive(Clone, Deserialize)]
 pub struct Args {
-    pub arg_input:            Option<String>,
-    pub flag_select:          SelectColumns,
-    pub flag_everything:      bool,
-    pub flag_typesonly:       bool,
-    pub flag_infer_boolean:   bool,
-    pub flag_mode:            bool,
-    pub flag_cardinality:     bool,
-    pub flag_median:          bool,
-    pub flag_mad:             bool,
-    pub flag_quartiles:       bool,
-    pub flag_round:           u32,
-    pub flag_nulls:           bool,
-    pub flag_infer_dates:     bool,
-    pub flag_dates_whitelist: String,
-    pub flag_prefer_dmy:      bool,
-    pub flag_force:           bool,
-    pub flag_jobs:            Option<usize>,
-    pub flag_stats_jsonl:     bool,
-    pub flag_cache_threshold: isize,
-    pub flag_output:          Option<String>,
-    pub flag_no_headers:      bool,
-    pub flag_delimiter:       Option<Delimiter>,
-    pub flag_memcheck:        bool,
+    pub arg_input:               Option<String>,
+    pub flag_select:             SelectColumns,
+    pub flag_everything:         bool,
+    pub flag_typesonly:          bool,
+    pub flag_infer_boolean:      bool,
+    pub flag_mode:               bool,
+    pub flag_cardinality:        bool,
+    pub flag_approx_cardinality: bool,
+    pub flag_cardinality_error:  f64,
+    pub flag_median:             bool,
+    pub flag_mad:                bool,
+    pub flag_quartiles:          bool,
+    pub flag_percentiles:        Option<String>,
+    pub flag_outliers:           Option<String>,
+    pub flag_outliers_output:    Option<String>,
+    pub flag_histogram:          Option<usize>,
+    pub flag_histogram_file:     Option<String>,
+    pub flag_group_by:           Option<SelectColumns>,
+    pub flag_group_limit:        usize,
+    pub flag_weight:             Option<SelectColumns>,
+    pub flag_round:              u32,
+    pub flag_nulls:              bool,
+    pub flag_infer_dates:        bool,
+    pub flag_dates_whitelist:    String,
+    pub flag_prefer_dmy:         bool,
+    pub flag_date_details:       bool,
+    pub flag_force:              bool,
+    pub flag_jobs:               Option<usize>,
+    pub flag_stats_jsonl:        bool,
+    pub flag_format:             String,
+    pub flag_cache_threshold:    isize,
+    pub flag_output:             Option<String>,
+    pub flag_no_headers:         bool,
+    pub flag_delimiter:          Option<Delimiter>,
+    pub flag_memcheck:           bool,
 }
 
 // this struct is used to serialize/deserialize the stats to
@@ -295,77 +423,108 @@ pub struct Args {
 // if we can skip recomputing stats.
 #[derive(Clone, Serialize, Deserialize, PartialEq, Default)]
 struct StatsArgs {
-    arg_input:            String,
-    flag_select:          String,
-    flag_everything:      bool,
-    flag_typesonly:       bool,
-    flag_infer_boolean:   bool,
-    flag_mode:            bool,
-    flag_cardinality:     bool,
-    flag_median:          bool,
-    flag_mad:             bool,
-    flag_quartiles:       bool,
-    flag_round:           u32,
-    flag_nulls:           bool,
-    flag_infer_dates:     bool,
-    flag_dates_whitelist: String,
-    flag_prefer_dmy:      bool,
-    flag_no_headers:      bool,
-    flag_delimiter:       String,
-    flag_output_snappy:   bool,
-    canonical_input_path: String,
-    canonical_stats_path: String,
-    record_count:         u64,
-    date_generated:       String,
-    compute_duration_ms:  u64,
-    qsv_version:          String,
+    arg_input:               String,
+    flag_select:             String,
+    flag_everything:         bool,
+    flag_typesonly:          bool,
+    flag_infer_boolean:      bool,
+    flag_mode:               bool,
+    flag_cardinality:        bool,
+    flag_approx_cardinality: bool,
+    flag_cardinality_error:  f64,
+    flag_median:             bool,
+    flag_mad:                bool,
+    flag_quartiles:          bool,
+    flag_percentiles:        String,
+    flag_outliers:           String,
+    flag_weight:             String,
+    flag_round:              u32,
+    flag_nulls:              bool,
+    flag_infer_dates:        bool,
+    flag_dates_whitelist:    String,
+    flag_prefer_dmy:         bool,
+    flag_date_details:       bool,
+    flag_no_headers:         bool,
+    flag_delimiter:          String,
+    flag_output_snappy:      bool,
+    canonical_input_path:    String,
+    canonical_stats_path:    String,
+    record_count:            u64,
+    date_generated:          String,
+    compute_duration_ms:     u64,
+    // the size & fingerprint of the input, to check if it was only appended to since
+    input_size:              u64,
+    input_hash:              String,
+    qsv_version:             String,
 }
 
 impl StatsArgs {
     fn from_owned_value(value: &OwnedValue) -> Result<Self, Box<dyn std::error::Error>> {
         Ok(Self {
-            arg_input:            value["arg_input"].as_str().unwrap_or_default().to_string(),
-            flag_select:          value["flag_select"]
+            arg_input:               value["arg_input"].as_str().unwrap_or_default().to_string(),
+            flag_select:             value["flag_select"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            flag_everything:         value["flag_everything"].as_bool().unwrap_or_default(),
+            flag_typesonly:          value["flag_typesonly"].as_bool().unwrap_or_default(),
+            flag_infer_boolean:      value["flag_infer_boolean"].as_bool().unwrap_or_default(),
+            flag_mode:               value["flag_mode"].as_bool().unwrap_or_default(),
+            flag_cardinality:        value["flag_cardinality"].as_bool().unwrap_or_default(),
+            flag_approx_cardinality: value["flag_approx_cardinality"]
+                .as_bool()
+                .unwrap_or_default(),
+            flag_cardinality_error:  value["flag_cardinality_error"].as_f64().unwrap_or_default(),
+            flag_median:             value["flag_median"].as_bool().unwrap_or_default(),
+            flag_mad:                value["flag_mad"].as_bool().unwrap_or_default(),
+            flag_quartiles:          value["flag_quartiles"].as_bool().unwrap_or_default(),
+            flag_percentiles:        value["flag_percentiles"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            flag_outliers:           value["flag_outliers"]
                 .as_str()
                 .unwrap_or_default()
                 .to_string(),
-            flag_everything:      value["flag_everything"].as_bool().unwrap_or_default(),
-            flag_typesonly:       value["flag_typesonly"].as_bool().unwrap_or_default(),
-            flag_infer_boolean:   value["flag_infer_boolean"].as_bool().unwrap_or_default(),
-            flag_mode:            value["flag_mode"].as_bool().unwrap_or_default(),
-            flag_cardinality:     value["flag_cardinality"].as_bool().unwrap_or_default(),
-            flag_median:          value["flag_median"].as_bool().unwrap_or_default(),
-            flag_mad:             value["flag_mad"].as_bool().unwrap_or_default(),
-            flag_quartiles:       value["flag_quartiles"].as_bool().unwrap_or_default(),
-            flag_round:           value["flag_round"].as_u64().unwrap_or_default() as u32,
-            flag_nulls:           value["flag_nulls"].as_bool().unwrap_or_default(),
-            flag_infer_dates:     value["flag_infer_dates"].as_bool().unwrap_or_default(),
-            flag_dates_whitelist: value["flag_dates_whitelist"]
+            flag_weight:             value["flag_weight"]
                 .as_str()
                 .unwrap_or_default()
                 .to_string(),
-            flag_prefer_dmy:      value["flag_prefer_dmy"].as_bool().unwrap_or_default(),
-            flag_no_headers:      value["flag_no_headers"].as_bool().unwrap_or_default(),
-            flag_delimiter:       value["flag_delimiter"]
+            flag_round:              value["flag_round"].as_u64().unwrap_or_default() as u32,
+            flag_nulls:              value["flag_nulls"].as_bool().unwrap_or_default(),
+            flag_infer_dates:        value["flag_infer_dates"].as_bool().unwrap_or_default(),
+            flag_dates_whitelist:    value["flag_dates_whitelist"]
                 .as_str()
                 .unwrap_or_default()
                 .to_string(),
-            flag_output_snappy:   value["flag_output_snappy"].as_bool().unwrap_or_default(),
-            canonical_input_path: value["canonical_input_path"]
+            flag_prefer_dmy:         value["flag_prefer_dmy"].as_bool().unwrap_or_default(),
+            flag_date_details:       value["flag_date_details"].as_bool().unwrap_or_default(),
+            flag_no_headers:         value["flag_no_headers"].as_bool().unwrap_or_default(),
+            flag_delimiter:          value["flag_delimiter"]
                 .as_str()
                 .unwrap_or_default()
                 .to_string(),
-            canonical_stats_path: value["canonical_stats_path"]
+            flag_output_snappy:      value["flag_output_snappy"].as_bool().unwrap_or_default(),
+            canonical_input_path:    value["canonical_input_path"]
                 .as_str()
                 .unwrap_or_default()
                 .to_string(),
-            record_count:         value["record_count"].as_u64().unwrap_or_default(),
-            date_generated:       value["date_generated"]
+            canonical_stats_path:    value["canonical_stats_path"]
                 .as_str()
                 .unwrap_or_default()
                 .to_string(),
-            compute_duration_ms:  value["compute_duration_ms"].as_u64().unwrap_or_default(),
-            qsv_version:          value["qsv_version"]
+            record_count:            value["record_count"].as_u64().unwrap_or_default(),
+            date_generated:          value["date_generated"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            compute_duration_ms:     value["compute_duration_ms"].as_u64().unwrap_or_default(),
+            input_size:              value["input_size"].as_u64().unwrap_or_default(),
+            input_hash:              value["input_hash"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            qsv_version:             value["qsv_version"]
                 .as_str()
                 .unwrap_or_default()
                 .to_string(),
@@ -465,6 +624,14 @@ pub static STATSDATA_TYPES_ARRAY: [JsonTypes; MAX_STAT_COLUMNS] = [
 
 static INFER_DATE_FLAGS: OnceLock<Vec<bool>> = OnceLock::new();
 static RECORD_COUNT: OnceLock<u64> = OnceLock::new();
+// the --percentiles to compute
+static PERCENTILES: OnceLock<Vec<f64>> = OnceLock::new();
+// the precision of the --approx-cardinality HyperLogLog sketches
+static HLL_PRECISION: OnceLock<u8> = OnceLock::new();
+// the index of the --weight column in the input records
+static WEIGHT_COLUMN: OnceLock<usize> = OnceLock::new();
+// the time of the stats run, to count the --date-details future-dated values
+static RUN_TIMESTAMP: OnceLock<i64> = OnceLock::new();
 
 // number of milliseconds per day
 const MS_IN_DAY: f64 = 86_400_000.0;
@@ -476,6 +643,21 @@ const DAY_DECIMAL_PLACES: u32 = 5;
 // maximum number of output columns
 const MAX_STAT_COLUMNS: usize = 35;
 
+// the compression of the t-digest used to estimate --percentiles.
+// Higher values are more accurate, but keep more centroids.
+const TDIGEST_COMPRESSION: f64 = 100.0;
+// the number of values buffered before they're merged into the t-digest's centroids
+const TDIGEST_BUFFER_SIZE: usize = 500;
+
+// the z-score above which values are --outliers zscore outliers
+const OUTLIER_ZSCORE: f64 = 3.0;
+
+// the number of bytes at the start & end of the input that are fingerprinted
+const INPUT_FINGERPRINT_LEN: u64 = 65_536;
+
+// the number of partitions the rows of the groups beyond --group-limit are spilled to
+const GROUP_SPILL_PARTITIONS: usize = 16;
+
 // maximum number of antimodes to display
 const MAX_ANTIMODES: usize = 10;
 // maximum length of antimode string before truncating and appending "..."
@@ -490,6 +672,70 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         args.flag_median = false;
         args.flag_quartiles = false;
         args.flag_mad = false;
+        args.flag_percentiles = None;
+        args.flag_outliers = None;
+        args.flag_histogram = None;
+        args.flag_approx_cardinality = false;
+        args.flag_weight = None;
+        args.flag_date_details = false;
+    }
+
+    let output_format = match args.flag_format.to_ascii_lowercase().as_str() {
+        "csv" => OutputFormat::Csv,
+        "json" => OutputFormat::Json,
+        "jsonl" => OutputFormat::Jsonl,
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --format: {}. Must be csv, json or jsonl.",
+                args.flag_format
+            )
+        },
+    };
+
+    if args.flag_date_details && !args.flag_infer_dates {
+        return fail_incorrectusage_clierror!("--date-details requires --infer-dates.");
+    }
+
+    if args.flag_approx_cardinality {
+        match HyperLogLog::precision_for_error(args.flag_cardinality_error / 100.0) {
+            Some(precision) => {
+                let _ = HLL_PRECISION.set(precision);
+            },
+            None => {
+                return fail_incorrectusage_clierror!(
+                    "Invalid --cardinality-error: {}. Must be at least 0.21.",
+                    args.flag_cardinality_error
+                )
+            },
+        }
+        args.flag_cardinality = true;
+    }
+
+    if let Some(ref percentiles) = args.flag_percentiles {
+        match parse_percentiles(percentiles) {
+            Ok(percentiles) => {
+                let _ = PERCENTILES.set(percentiles);
+            },
+            Err(e) => return fail_incorrectusage_clierror!("Invalid --percentiles: {e}"),
+        }
+    }
+
+    let outlier_method = match args.flag_outliers.as_deref() {
+        None => None,
+        Some(method) if method.eq_ignore_ascii_case("iqr") => Some(OutlierMethod::Iqr),
+        Some(method) if method.eq_ignore_ascii_case("zscore") => Some(OutlierMethod::Zscore),
+        Some(method) => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --outliers method: {method}. Must be iqr or zscore."
+            )
+        },
+    };
+    if args.flag_outliers_output.is_some() && outlier_method.is_none() {
+        return fail_incorrectusage_clierror!("--outliers-output requires --outliers.");
+    }
+    // the iqr fences are derived from the quartiles
+    if outlier_method == Some(OutlierMethod::Iqr) {
+        args.flag_quartiles = true;
     }
 
     // inferring boolean requires inferring cardinality
@@ -500,47 +746,94 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     // check prefer_dmy env var
     args.flag_prefer_dmy = args.flag_prefer_dmy || util::get_envvar_flag("QSV_PREFER_DMY");
 
+    // the histogram sidecar, next to the input by default
+    let histogram_path = match (args.flag_histogram, &args.flag_histogram_file) {
+        (Some(0), _) => {
+            return fail_incorrectusage_clierror!("--histogram must be greater than zero.")
+        },
+        (None, Some(_)) => {
+            return fail_incorrectusage_clierror!("--histogram-file requires --histogram.")
+        },
+        (None, None) => None,
+        (Some(_), Some(output)) => Some(PathBuf::from(output)),
+        (Some(_), None) => match args.arg_input {
+            Some(ref input) if input != "-" => {
+                Some(stats_path(Path::new(input), false)?.with_extension("histogram.csv"))
+            },
+            _ => {
+                return fail_incorrectusage_clierror!(
+                    "--histogram-file is required when reading from stdin."
+                )
+            },
+        },
+    };
+
+    // grouped stats are computed in a single pass, and aren't cached
+    if args.flag_group_by.is_some() {
+        if outlier_method.is_some() || histogram_path.is_some() {
+            return fail_incorrectusage_clierror!(
+                "--outliers & --histogram cannot be used with --group-by."
+            );
+        }
+        if args.flag_group_limit == 0 {
+            return fail_incorrectusage_clierror!("--group-limit must be greater than zero.");
+        }
+        return args.grouped_stats(output_format);
+    }
+
     // set stdout output flag
     let stdout_output_flag = args.flag_output.is_none();
 
     // save the current args, we'll use it to generate
     // the stats.csv.json file
     let mut current_stats_args = StatsArgs {
-        arg_input:            format!("{:?}", args.arg_input),
-        flag_select:          format!("{:?}", args.flag_select),
-        flag_everything:      args.flag_everything,
-        flag_typesonly:       args.flag_typesonly,
-        flag_infer_boolean:   args.flag_infer_boolean,
-        flag_mode:            args.flag_mode,
-        flag_cardinality:     args.flag_cardinality,
-        flag_median:          args.flag_median,
-        flag_mad:             args.flag_mad,
-        flag_quartiles:       args.flag_quartiles,
-        flag_round:           args.flag_round,
-        flag_nulls:           args.flag_nulls,
-        flag_infer_dates:     args.flag_infer_dates,
-        flag_dates_whitelist: args.flag_dates_whitelist.clone(),
-        flag_prefer_dmy:      args.flag_prefer_dmy,
-        flag_no_headers:      args.flag_no_headers,
-        flag_delimiter:       format!("{:?}", args.flag_delimiter.clone()),
+        arg_input:               format!("{:?}", args.arg_input),
+        flag_select:             format!("{:?}", args.flag_select),
+        flag_everything:         args.flag_everything,
+        flag_typesonly:          args.flag_typesonly,
+        flag_infer_boolean:      args.flag_infer_boolean,
+        flag_mode:               args.flag_mode,
+        flag_cardinality:        args.flag_cardinality,
+        flag_approx_cardinality: args.flag_approx_cardinality,
+        flag_cardinality_error:  args.flag_cardinality_error,
+        flag_median:             args.flag_median,
+        flag_mad:                args.flag_mad,
+        flag_quartiles:          args.flag_quartiles,
+        flag_percentiles:        args.flag_percentiles.clone().unwrap_or_default(),
+        flag_outliers:           args
+            .flag_outliers
+            .clone()
+            .unwrap_or_default()
+            .to_ascii_lowercase(),
+        flag_weight:             format!("{:?}", args.flag_weight),
+        flag_round:              args.flag_round,
+        flag_nulls:              args.flag_nulls,
+        flag_infer_dates:        args.flag_infer_dates,
+        flag_dates_whitelist:    args.flag_dates_whitelist.clone(),
+        flag_prefer_dmy:         args.flag_prefer_dmy,
+        flag_date_details:       args.flag_date_details,
+        flag_no_headers:         args.flag_no_headers,
+        flag_delimiter:          format!("{:?}", args.flag_delimiter.clone()),
         // when we write to stdout, we don't use snappy compression
         // when we write to a file with the --output option, we use
         // snappy compression if the file ends with ".sz"
-        flag_output_snappy:   if stdout_output_flag {
+        flag_output_snappy:      if stdout_output_flag {
             false
         } else {
             let p = args.flag_output.clone().unwrap();
             p.to_ascii_lowercase().ends_with(".sz")
         },
-        canonical_input_path: String::new(),
-        canonical_stats_path: String::new(),
-        record_count:         0,
-        date_generated:       String::new(),
-        compute_duration_ms:  0,
+        canonical_input_path:    String::new(),
+        canonical_stats_path:    String::new(),
+        record_count:            0,
+        date_generated:          String::new(),
+        compute_duration_ms:     0,
+        input_size:              0,
+        input_hash:              String::new(),
         // save the qsv version in the stats.csv.json file
         // so cached stats are automatically invalidated
         // when the qsv version changes
-        qsv_version:          env!("CARGO_PKG_VERSION").to_string(),
+        qsv_version:             env!("CARGO_PKG_VERSION").to_string(),
     };
 
     // create a temporary file to store the <FILESTEM>.stats.csv file
@@ -595,6 +888,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     }
 
     let mut compute_stats = true;
+    // the cached stats state & the input size it was computed from, to update it incrementally
+    let mut incremental_from: Option<(u64, Vec<Stats>)> = None;
+    // the serialized stats state, to cache it
+    let mut stats_state: Option<Vec<u8>> = None;
     let mut create_cache = args.flag_cache_threshold > 0 || args.flag_stats_jsonl;
     let mut autoindex_set = false;
 
@@ -607,7 +904,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         // If it does, check if it was compiled using the same args.
         // However, if the --force flag is set,
         // recompute the stats even if the args are the same.
-        if stats_file.exists() && !args.flag_force {
+        // the outliers & histograms are only written when the stats are computed
+        if stats_file.exists()
+            && !args.flag_force
+            && args.flag_outliers_output.is_none()
+            && histogram_path.is_none()
+        {
             let stats_args_json_file = stats_file.with_extension("csv.json");
             let existing_stats_args_json_str =
                 match fs::read_to_string(stats_args_json_file.clone()) {
@@ -624,7 +926,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
             let time_saved: u64;
             // deserialize the existing stats args json
-            let existing_stats_args_json: StatsArgs = {
+            let mut existing_stats_args_json: StatsArgs = {
                 let mut json_buffer = existing_stats_args_json_str.into_bytes();
                 match simd_json::to_owned_value(&mut json_buffer) {
                     Ok(value) => {
@@ -667,6 +969,11 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 }
             };
 
+            // the input the cached stats were computed from
+            let prev_input_size = std::mem::take(&mut existing_stats_args_json.input_size);
+            let prev_input_hash = std::mem::take(&mut existing_stats_args_json.input_hash);
+            let stats_state_file = stats_state_path(&path)?;
+
             // check if the cached stats are current (ie the stats file is newer than the input
             // file), use the same args or if the --everything flag was set, and
             // all the other non-stats args are equal. If so, we don't need to recompute the stats
@@ -689,6 +996,20 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         && existing_stats_args_json.flag_delimiter
                             == current_stats_args.flag_delimiter
                         && existing_stats_args_json.flag_nulls == current_stats_args.flag_nulls
+                        && existing_stats_args_json.flag_percentiles
+                            == current_stats_args.flag_percentiles
+                        && existing_stats_args_json.flag_approx_cardinality
+                            == current_stats_args.flag_approx_cardinality
+                        && (!current_stats_args.flag_approx_cardinality
+                            || existing_stats_args_json
+                                .flag_cardinality_error
+                                .total_cmp(&current_stats_args.flag_cardinality_error)
+                                .is_eq())
+                        && existing_stats_args_json.flag_outliers
+                            == current_stats_args.flag_outliers
+                        && existing_stats_args_json.flag_weight == current_stats_args.flag_weight
+                        && existing_stats_args_json.flag_date_details
+                            == current_stats_args.flag_date_details
                         && existing_stats_args_json.qsv_version == current_stats_args.qsv_version)
             {
                 log::info!(
@@ -696,6 +1017,27 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                      and using cached stats instead - {time_saved} secs saved...",
                 );
                 compute_stats = false;
+            } else if existing_stats_args_json == current_stats_args
+                && args.streaming_stats_only()
+                && stats_state_file.exists()
+                && input_appended(&path, prev_input_size, &prev_input_hash)?
+            {
+                match fs::read(&stats_state_file).map(|state| serde_json::from_slice(&state)) {
+                    Ok(Ok(state)) => {
+                        log::info!(
+                            "{path_file_stem}.stats.csv already exists, and the input was only \
+                             appended to. Updating the stats incrementally..."
+                        );
+                        incremental_from = Some((prev_input_size, state));
+                    },
+                    _ => {
+                        log::warn!(
+                            "Could not load {}, recomputing...",
+                            stats_state_file.display()
+                        );
+                    },
+                }
+                fs::remove_file(&stats_file)?;
             } else {
                 log::info!(
                     "{path_file_stem}.stats.csv already exists, but is older than the input file \
@@ -710,10 +1052,11 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             // we're loading the entire file into memory, we need to check avail mem
             if args.flag_everything
                 || args.flag_mode
-                || args.flag_cardinality
+                || (args.flag_cardinality && !args.flag_approx_cardinality)
                 || args.flag_median
                 || args.flag_quartiles
                 || args.flag_mad
+                || (args.flag_weight.is_some() && args.flag_percentiles.is_some())
             {
                 util::mem_file_check(&path, false, args.flag_memcheck)?;
             }
@@ -729,23 +1072,77 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             let record_count = RECORD_COUNT.get_or_init(|| util::count_rows(&rconfig).unwrap());
             log::info!("scanning {record_count} records...");
 
-            let (headers, stats) = match rconfig.indexed()? {
-                None => args.sequential_stats(&args.flag_dates_whitelist),
-                Some(idx) => {
-                    let idx_count = idx.count();
-                    if let Some(num_jobs) = args.flag_jobs {
-                        if num_jobs == 1 {
-                            args.sequential_stats(&args.flag_dates_whitelist)
+            // the size of the input summarized, before rows are possibly appended to it
+            let input_size = fs::metadata(&path)?.len();
+
+            let (headers, mut stats) = if let Some((prev_input_size, state)) = incremental_from {
+                args.incremental_stats(&path, prev_input_size, input_size, state)
+            } else {
+                match rconfig.indexed()? {
+                    None => args.sequential_stats(&args.flag_dates_whitelist),
+                    Some(idx) => {
+                        let idx_count = idx.count();
+                        if let Some(num_jobs) = args.flag_jobs {
+                            if num_jobs == 1 {
+                                args.sequential_stats(&args.flag_dates_whitelist)
+                            } else {
+                                args.parallel_stats(&args.flag_dates_whitelist, idx_count)
+                            }
                         } else {
                             args.parallel_stats(&args.flag_dates_whitelist, idx_count)
                         }
-                    } else {
-                        args.parallel_stats(&args.flag_dates_whitelist, idx_count)
-                    }
-                },
+                    },
+                }
             }?;
 
-            let stats_sr_vec = args.stats_to_records(stats);
+            // the stats state is cached to update the stats incrementally when rows are
+            // appended to the input, which isn't possible with stdin or snappy compression
+            if create_cache
+                && args.streaming_stats_only()
+                && stdin_tempfile_path.is_none()
+                && !path.to_string_lossy().to_ascii_lowercase().ends_with(".sz")
+            {
+                if let Ok(state) = serde_json::to_vec(&stats) {
+                    current_stats_args.input_hash = input_fingerprint(&path, input_size)?;
+                    current_stats_args.input_size = input_size;
+                    stats_state = Some(state);
+                }
+            }
+
+            // the outlier fences of each column, before the stats are turned into records
+            let outlier_fences: Option<Vec<Option<(f64, f64)>>> = outlier_method.map(|method| {
+                stats
+                    .iter_mut()
+                    .map(|stat| stat.outlier_fences(method))
+                    .collect()
+            });
+
+            // the histograms are set up with the min & max of the columns
+            let histograms: Option<Vec<Option<Histogram>>> = args
+                .flag_histogram
+                .map(|bins| stats.iter().map(|stat| stat.new_histogram(bins)).collect());
+
+            let mut stats_sr_vec = args.stats_to_records(stats);
+
+            if let (Some(histograms), Some(histogram_path)) = (histograms, histogram_path.as_ref())
+            {
+                args.write_histograms(histogram_path, &headers, histograms)?;
+            }
+
+            if let Some(fences) = outlier_fences {
+                let counts = args.count_outliers(&headers, &fences)?;
+                for ((record, fence), count) in stats_sr_vec.iter_mut().zip(fences).zip(counts) {
+                    if let Some((lower, upper)) = fence {
+                        record.push_field(&util::round_num(lower, args.flag_round));
+                        record.push_field(&util::round_num(upper, args.flag_round));
+                        record.push_field(&count.to_string());
+                    } else {
+                        record.push_field("");
+                        record.push_field("");
+                        record.push_field("");
+                    }
+                }
+            }
 
             let stats_headers_sr = args.stat_headers();
             wtr.write_record(&stats_headers_sr)?;
@@ -832,6 +1229,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             create_cache = false;
         }
 
+        // the stats state is only kept with the stats cache it was computed with
+        let stats_state_file = stats_state_path(&path)?;
+        match stats_state {
+            Some(ref state) if compute_stats && create_cache => {
+                fs::write(&stats_state_file, state)?;
+            },
+            _ if compute_stats && stats_state_file.exists() => {
+                if fs::remove_file(&stats_state_file).is_err() {
+                    log::warn!(
+                        "Could not remove stats state file: {}",
+                        stats_state_file.display()
+                    );
+                }
+            },
+            _ => {},
+        }
+
         if !create_cache {
             // remove the stats cache file
             if fs::remove_file(stats_pathbuf.clone()).is_err() {
@@ -869,7 +1283,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
-    if stdout_output_flag {
+    if output_format != OutputFormat::Csv {
+        write_json_stats(&currstats_filename, output_format, 0, &args.flag_output)?;
+    } else if stdout_output_flag {
         // if we're outputting to stdout, copy the stats file to stdout
         let currstats = fs::read_to_string(currstats_filename)?;
         io::stdout().write_all(currstats.as_bytes())?;
@@ -889,6 +1305,7 @@ impl Args {
     fn sequential_stats(&self, whitelist: &str) -> CliResult<(csv::ByteRecord, Vec<Stats>)> {
         let mut rdr = self.rconfig().reader()?;
         let (headers, sel) = self.sel_headers(&mut rdr)?;
+        self.init_weight(rdr.byte_headers()?)?;
 
         init_date_inference(self.flag_infer_dates, &headers, whitelist)?;
 
@@ -896,6 +1313,40 @@ impl Args {
         Ok((headers, stats))
     }
 
+    /// the cached stats `state` of the first `prev_input_size` bytes of the input, merged
+    /// with the stats of the rows appended since, up to `input_size` bytes
+    fn incremental_stats(
+        &self,
+        path: &Path,
+        prev_input_size: u64,
+        input_size: u64,
+        mut state: Vec<Stats>,
+    ) -> CliResult<(csv::ByteRecord, Vec<Stats>)> {
+        let mut rdr = self.rconfig().reader()?;
+        let (headers, sel) = self.sel_headers(&mut rdr)?;
+        if state.len() != headers.len() {
+            return fail_clierror!(
+                "The cached stats state has {} columns, but the input has {}.",
+                state.len(),
+                headers.len()
+            );
+        }
+        self.init_weight(rdr.byte_headers()?)?;
+
+        init_date_inference(self.flag_infer_dates, &headers, &self.flag_dates_whitelist)?;
+
+        let mut appended = fs::File::open(path)?;
+        appended.seek(SeekFrom::Start(prev_input_size))?;
+        let mut rconfig = self.rconfig();
+        rconfig.no_headers = true;
+        let appended_rdr = rconfig.from_reader(appended.take(input_size - prev_input_size));
+        let appended_stats = self.compute(&sel, appended_rdr.into_byte_records());
+        for (stat, appended_stat) in state.iter_mut().zip(appended_stats) {
+            stat.merge(appended_stat);
+        }
+        Ok((headers, state))
+    }
+
     fn parallel_stats(
         &self,
         whitelist: &str,
@@ -909,6 +1360,7 @@ impl Args {
 
         let mut rdr = self.rconfig().reader()?;
         let (headers, sel) = self.sel_headers(&mut rdr)?;
+        self.init_weight(rdr.byte_headers()?)?;
 
         init_date_inference(self.flag_infer_dates, &headers, whitelist)?;
 
@@ -970,7 +1422,8 @@ impl Args {
         I: Iterator<Item = csv::Result<csv::ByteRecord>>,
     {
         let sel_len = sel.len();
-        let mut stats = self.new_stats(sel_len);
+        let mut stats = self.new_stats(sel);
+        let weight_column = WEIGHT_COLUMN.get().copied();
 
         // safety: we know INFER_DATE_FLAGS is Some because we called init_date_inference
         let infer_date_flags = INFER_DATE_FLAGS.get().unwrap();
@@ -989,12 +1442,14 @@ impl Args {
             // we know we don't need to bounds check
             unsafe {
                 current_row = row.unwrap_unchecked();
+                let weight = weight_column.map_or(1.0, |idx| row_weight(&current_row, idx));
                 for field in sel.select(&current_row) {
                     stats.get_unchecked_mut(i).add(
                         field,
                         *infer_date_flags.get_unchecked(i),
                         infer_boolean,
                         prefer_dmy,
+                        weight,
                     );
                     i += 1;
                 }
@@ -1003,6 +1458,307 @@ impl Args {
         stats
     }
 
+    /// compute the stats of each group of --group-by values with a hash aggregation
+    fn grouped_stats(&self, output_format: OutputFormat) -> CliResult<()> {
+        let rconfig = self.rconfig();
+        let mut rdr = rconfig.reader()?;
+        let headers = rdr.byte_headers()?.clone();
+        let sel = rconfig.selection(&headers)?;
+        self.init_weight(&headers)?;
+        // safety: grouped_stats is only called with --group-by
+        let group_sel = rconfig
+            .select(self.flag_group_by.clone().unwrap())
+            .selection(&headers)?;
+
+        // the --group-by columns are not summarized
+        let stat_cols: Vec<usize> = sel
+            .iter()
+            .copied()
+            .filter(|idx| !group_sel.contains(idx))
+            .collect();
+        let field_names: csv::ByteRecord = if self.flag_no_headers {
+            stat_cols.iter().map(usize::to_string).collect()
+        } else {
+            stat_cols.iter().map(|&idx| &headers[idx]).collect()
+        };
+        init_date_inference(
+            self.flag_infer_dates,
+            &field_names,
+            &self.flag_dates_whitelist,
+        )?;
+
+        // the grouped stats are converted from a CSV temp file to JSON
+        let json_tempfile = if output_format == OutputFormat::Csv {
+            None
+        } else {
+            Some(NamedTempFile::new()?)
+        };
+        let csv_output = match json_tempfile {
+            Some(ref tempfile) => Some(tempfile.path().to_string_lossy().into_owned()),
+            None => self.flag_output.clone(),
+        };
+        let mut wtr = Config::new(&csv_output).writer()?;
+        let mut out_headers: csv::ByteRecord = if self.flag_no_headers {
+            group_sel.iter().map(usize::to_string).collect()
+        } else {
+            group_sel.select(&headers).collect()
+        };
+        out_headers.extend(self.stat_headers().iter());
+        wtr.write_byte_record(&out_headers)?;
+
+        self.aggregate_groups(rdr, &group_sel, &stat_cols, &field_names, 0, &mut wtr)?;
+        wtr.flush()?;
+        drop(wtr);
+
+        if let Some(tempfile) = json_tempfile {
+            write_json_stats(
+                &tempfile.path().to_string_lossy(),
+                output_format,
+                group_sel.len(),
+                &self.flag_output,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// aggregate the stats of the groups of the records of `rdr` & write them. The records
+    /// of the groups beyond --group-limit are spilled to partitions by the hash of their
+    /// group values, which are then aggregated with a different hash seed.
+    fn aggregate_groups<R: io::Read, W: io::Write>(
+        &self,
+        mut rdr: csv::Reader<R>,
+        group_sel: &Selection,
+        stat_cols: &[usize],
+        field_names: &csv::ByteRecord,
+        depth: u64,
+        wtr: &mut csv::Writer<W>,
+    ) -> CliResult<()> {
+        // safety: we know INFER_DATE_FLAGS is Some because we called init_date_inference
+        let infer_date_flags = INFER_DATE_FLAGS.get().unwrap();
+        let weight_column = WEIGHT_COLUMN.get().copied();
+
+        let mut groups: IndexMap<Vec<u8>, (csv::ByteRecord, Vec<Stats>)> = IndexMap::new();
+        let mut partitions: Vec<csv::Writer<NamedTempFile>> = Vec::new();
+        let mut record = csv::ByteRecord::new();
+        let mut key = Vec::new();
+        while rdr.read_byte_record(&mut record)? {
+            // the length-prefixed group values, so ("ab", "c") and ("a", "bc") differ
+            key.clear();
+            for field in group_sel.select(&record) {
+                key.extend_from_slice(&(field.len() as u64).to_le_bytes());
+                key.extend_from_slice(field);
+            }
+
+            if !groups.contains_key(&key) {
+                if groups.len() >= self.flag_group_limit {
+                    if partitions.is_empty() {
+                        log::info!("spilling the groups beyond --group-limit (depth {depth})");
+                        for _ in 0..GROUP_SPILL_PARTITIONS {
+                            partitions.push(csv::Writer::from_writer(NamedTempFile::new()?));
+                        }
+                    }
+                    let partition =
+                        xxh3_64_with_seed(&key, depth) as usize % GROUP_SPILL_PARTITIONS;
+                    partitions[partition].write_byte_record(&record)?;
+                    continue;
+                }
+                let group_values = group_sel.select(&record).collect();
+                groups.insert(key.clone(), (group_values, self.new_stats(stat_cols)));
+            }
+            // safety: the group is in the map, as it was either there or just inserted
+            let (_, stats) = groups.get_mut(&key).unwrap();
+            let weight = weight_column.map_or(1.0, |idx| row_weight(&record, idx));
+            for ((stat, &idx), &infer_dates) in
+                stats.iter_mut().zip(stat_cols).zip(infer_date_flags)
+            {
+                stat.add(
+                    &record[idx],
+                    infer_dates,
+                    self.flag_infer_boolean,
+                    self.flag_prefer_dmy,
+                    weight,
+                );
+            }
+        }
+
+        let mut out_record = csv::ByteRecord::new();
+        for (_, (group_values, stats)) in groups {
+            for (mut stat, field_name) in stats.into_iter().zip(field_names) {
+                let stat_record = stat.to_record(self.flag_round, self.flag_infer_boolean);
+                out_record.clear();
+                out_record.extend(&group_values);
+                out_record.push_field(field_name);
+                out_record.extend(stat_record.iter());
+                wtr.write_byte_record(&out_record)?;
+            }
+        }
+
+        for partition in partitions {
+            let partition = partition.into_inner().map_err(|e| e.into_error())?;
+            let partition_rdr = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(partition.reopen()?);
+            self.aggregate_groups(
+                partition_rdr,
+                group_sel,
+                stat_cols,
+                field_names,
+                depth + 1,
+                wtr,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// fill the histograms in a second pass over the input, and write them to `path`
+    fn write_histograms(
+        &self,
+        path: &Path,
+        headers: &csv::ByteRecord,
+        mut histograms: Vec<Option<Histogram>>,
+    ) -> CliResult<()> {
+        let mut rdr = self.rconfig().reader()?;
+        let (_, sel) = self.sel_headers(&mut rdr)?;
+        let mut record = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            for (histogram, field) in histograms.iter_mut().zip(sel.select(&record)) {
+                if let Some(histogram) = histogram {
+                    histogram.add(field);
+                }
+            }
+        }
+
+        let bins = self.flag_histogram.unwrap_or_default();
+        let field_names = headers.iter().enumerate().map(|(i, header)| {
+            if self.flag_no_headers {
+                i.to_string()
+            } else {
+                String::from_utf8_lossy(header).into_owned()
+            }
+        });
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        if is_json {
+            let mut json = serde_json::Map::new();
+            for (field_name, histogram) in field_names.zip(histograms) {
+                let Some(histogram) = histogram else {
+                    continue;
+                };
+                let value = match histogram {
+                    Histogram::Bins { min, max, counts } => {
+                        let edges: Vec<f64> = (0..=counts.len())
+                            .map(|i| Histogram::edge(min, max, counts.len(), i))
+                            .collect();
+                        serde_json::json!({"edges": edges, "counts": counts})
+                    },
+                    Histogram::TopValues(counts) => {
+                        let (values, counts): (Vec<String>, Vec<u64>) =
+                            Histogram::top_values(counts, bins)
+                                .into_iter()
+                                .map(|(value, count)| {
+                                    (String::from_utf8_lossy(&value).into_owned(), count)
+                                })
+                                .unzip();
+                        serde_json::json!({"values": values, "counts": counts})
+                    },
+                };
+                json.insert(field_name, value);
+            }
+            let json = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+            fs::write(path, json)?;
+            return Ok(());
+        }
+
+        let mut wtr = Config::new(&Some(path.to_string_lossy().into_owned())).writer()?;
+        wtr.write_record(["field", "lower", "upper", "value", "count"])?;
+        let round_places = self.flag_round;
+        let mut buffer = itoa::Buffer::new();
+        let mut record = csv::ByteRecord::new();
+        for (field_name, histogram) in field_names.zip(histograms) {
+            match histogram {
+                None => {},
+                Some(Histogram::Bins { min, max, counts }) => {
+                    let bins = counts.len();
+                    for (i, count) in counts.into_iter().enumerate() {
+                        record.clear();
+                        record.push_field(field_name.as_bytes());
+                        let lower = Histogram::edge(min, max, bins, i);
+                        record.push_field(util::round_num(lower, round_places).as_bytes());
+                        let upper = Histogram::edge(min, max, bins, i + 1);
+                        record.push_field(util::round_num(upper, round_places).as_bytes());
+                        record.push_field(b"");
+                        record.push_field(buffer.format(count).as_bytes());
+                        wtr.write_byte_record(&record)?;
+                    }
+                },
+                Some(Histogram::TopValues(counts)) => {
+                    for (value, count) in Histogram::top_values(counts, bins) {
+                        record.clear();
+                        record.push_field(field_name.as_bytes());
+                        record.push_field(b"");
+                        record.push_field(b"");
+                        record.push_field(&value);
+                        record.push_field(buffer.format(count).as_bytes());
+                        wtr.write_byte_record(&record)?;
+                    }
+                },
+            }
+        }
+        Ok(wtr.flush()?)
+    }
+
+    /// count the values outside the outlier fences of each column in a second pass over
+    /// the input, writing the outliers to --outliers-output if set
+    fn count_outliers(
+        &self,
+        headers: &csv::ByteRecord,
+        fences: &[Option<(f64, f64)>],
+    ) -> CliResult<Vec<u64>> {
+        let mut rdr = self.rconfig().reader()?;
+        let (_, sel) = self.sel_headers(&mut rdr)?;
+        let mut outliers_wtr = match self.flag_outliers_output {
+            Some(_) => {
+                let mut wtr = Config::new(&self.flag_outliers_output).writer()?;
+                wtr.write_record(["row", "field", "value"])?;
+                Some(wtr)
+            },
+            None => None,
+        };
+
+        let mut counts = vec![0_u64; fences.len()];
+        let mut record = csv::ByteRecord::new();
+        let mut row = 0_u64;
+        let mut buffer = itoa::Buffer::new();
+        while rdr.read_byte_record(&mut record)? {
+            row += 1;
+            for (i, field) in sel.select(&record).enumerate() {
+                let Some((lower, upper)) = fences[i] else {
+                    continue;
+                };
+                let Some(value) = from_bytes::<f64>(field) else {
+                    continue;
+                };
+                if value < lower || value > upper {
+                    counts[i] += 1;
+                    if let Some(ref mut wtr) = outliers_wtr {
+                        let field_name = if self.flag_no_headers {
+                            i.to_string().into_bytes()
+                        } else {
+                            headers[i].to_vec()
+                        };
+                        wtr.write_record([buffer.format(row).as_bytes(), &field_name, field])?;
+                    }
+                }
+            }
+        }
+        if let Some(mut wtr) = outliers_wtr {
+            wtr.flush()?;
+        }
+        Ok(counts)
+    }
+
     #[inline]
     fn sel_headers<R: io::Read>(
         &self,
@@ -1021,24 +1777,58 @@ impl Args {
             .select(self.flag_select.clone())
     }
 
+    /// resolve the index of the --weight column in the input `headers`
+    fn init_weight(&self, headers: &csv::ByteRecord) -> CliResult<()> {
+        let Some(ref weight) = self.flag_weight else {
+            return Ok(());
+        };
+        let weight_sel = self.rconfig().select(weight.clone()).selection(headers)?;
+        if weight_sel.len() != 1 {
+            return fail_incorrectusage_clierror!("--weight must select a single column.");
+        }
+        let _ = WEIGHT_COLUMN.set(weight_sel[0]);
+        Ok(())
+    }
+
+    /// whether all the stats are computed in bounded memory & don't depend on the time of
+    /// the run, so their state can be cached to update them incrementally
+    const fn streaming_stats_only(&self) -> bool {
+        !(self.flag_everything
+            || self.flag_date_details
+            || self.flag_mode
+            || (self.flag_cardinality && !self.flag_approx_cardinality)
+            || self.flag_median
+            || self.flag_mad
+            || self.flag_quartiles
+            || (self.flag_weight.is_some() && self.flag_percentiles.is_some()))
+    }
+
+    /// the empty stats of the columns at these indices of the input records
     #[inline]
-    fn new_stats(&self, record_len: usize) -> Vec<Stats> {
-        let mut stats: Vec<Stats> = Vec::with_capacity(record_len);
-        stats.extend(
-            repeat(Stats::new(WhichStats {
-                include_nulls: self.flag_nulls,
-                sum:           !self.flag_typesonly,
-                range:         !self.flag_typesonly || self.flag_infer_boolean,
-                dist:          !self.flag_typesonly,
-                cardinality:   self.flag_everything || self.flag_cardinality,
-                median:        !self.flag_everything && self.flag_median && !self.flag_quartiles,
-                mad:           self.flag_everything || self.flag_mad,
-                quartiles:     self.flag_everything || self.flag_quartiles,
-                mode:          self.flag_everything || self.flag_mode,
-                typesonly:     self.flag_typesonly,
-            }))
-            .take(record_len),
-        );
+    fn new_stats(&self, columns: &[usize]) -> Vec<Stats> {
+        // the --weight column itself is summarized unweighted
+        let weight_column = WEIGHT_COLUMN.get();
+        let mut stats: Vec<Stats> = Vec::with_capacity(columns.len());
+        stats.extend(columns.iter().map(|idx| {
+            Stats::new(WhichStats {
+                include_nulls:      self.flag_nulls,
+                sum:                !self.flag_typesonly,
+                range:              !self.flag_typesonly || self.flag_infer_boolean,
+                dist:               !self.flag_typesonly,
+                cardinality:        self.flag_everything || self.flag_cardinality,
+                approx_cardinality: self.flag_approx_cardinality,
+                median:             !self.flag_everything
+                    && self.flag_median
+                    && !self.flag_quartiles,
+                mad:                self.flag_everything || self.flag_mad,
+                quartiles:          self.flag_everything || self.flag_quartiles,
+                percentiles:        self.flag_percentiles.is_some(),
+                mode:               self.flag_everything || self.flag_mode,
+                typesonly:          self.flag_typesonly,
+                weighted:           self.flag_weight.is_some() && weight_column != Some(idx),
+                date_details:       self.flag_date_details,
+            })
+        }));
         stats
     }
 
@@ -1102,10 +1892,117 @@ impl Args {
                 "antimode_occurrences",
             ]);
         }
+        let mut fields: Vec<String> = fields.into_iter().map(str::to_string).collect();
+        if let Some(percentiles) = PERCENTILES.get() {
+            fields.extend(percentiles.iter().map(|p| format!("p{p}")));
+        }
+        if self.flag_approx_cardinality {
+            fields.push("cardinality_mode".to_string());
+        }
+        if self.flag_date_details {
+            fields.extend(
+                [
+                    "date_range_human",
+                    "date_granularity",
+                    "date_tz_offsets",
+                    "date_future_count",
+                ]
+                .map(str::to_string),
+            );
+        }
+        if self.flag_outliers.is_some() {
+            fields.extend(
+                [
+                    "outlier_lower_fence",
+                    "outlier_upper_fence",
+                    "outlier_count",
+                ]
+                .map(str::to_string),
+            );
+        }
         csv::StringRecord::from(fields)
     }
 }
 
+/// write the stats CSV `stats_csv` to `output` as --format JSON or JSONL, with an object
+/// per row. The first `string_columns` columns (e.g. the --group-by values) are strings.
+fn write_json_stats(
+    stats_csv: &str,
+    output_format: OutputFormat,
+    string_columns: usize,
+    output: &Option<String>,
+) -> CliResult<()> {
+    let mut rdr = Config::new(&Some(stats_csv.to_string())).reader()?;
+    let headers = rdr.headers()?.clone();
+    let type_idx = headers.iter().position(|header| header == "type");
+
+    let mut wtr = io::BufWriter::new(Config::new(output).io_writer()?);
+    let mut objects = Vec::new();
+    let mut record = csv::StringRecord::new();
+    while rdr.read_record(&mut record)? {
+        let numeric = type_idx.is_some_and(|i| matches!(&record[i], "Integer" | "Float"));
+        let object: serde_json::Map<String, serde_json::Value> = headers
+            .iter()
+            .zip(record.iter())
+            .enumerate()
+            .map(|(i, (header, value))| {
+                let value = if i < string_columns {
+                    serde_json::Value::String(value.to_string())
+                } else {
+                    stat_json_value(header, value, numeric)
+                };
+                (header.to_string(), value)
+            })
+            .collect();
+        if output_format == OutputFormat::Jsonl {
+            serde_json::to_writer(&mut wtr, &object)?;
+            wtr.write_all(b"\n")?;
+        } else {
+            objects.push(serde_json::Value::Object(object));
+        }
+    }
+    if output_format == OutputFormat::Json {
+        serde_json::to_writer_pretty(&mut wtr, &objects)?;
+        wtr.write_all(b"\n")?;
+    }
+    Ok(wtr.flush()?)
+}
+
+/// the JSON value of a stat: null if it's empty, and a number if it's one, except for the
+/// stats that are always strings, and the min & max of columns that aren't numeric
+fn stat_json_value(stat: &str, value: &str, numeric: bool) -> serde_json::Value {
+    if value.is_empty() {
+        return serde_json::Value::Null;
+    }
+    let number = match stat {
+        "is_ascii" => return serde_json::Value::Bool(value == "true"),
+        "field" | "type" | "sort_order" | "mode" | "antimode" | "cardinality_mode"
+        | "date_range_human" | "date_granularity" | "date_tz_offsets" => None,
+        "min" | "max" if !numeric => None,
+        _ => match value.parse::<i64>() {
+            Ok(integer) => Some(serde_json::Number::from(integer)),
+            Err(_) => value
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64),
+        },
+    };
+    match number {
+        Some(number) => serde_json::Value::Number(number),
+        None => serde_json::Value::String(value.to_string()),
+    }
+}
+
+/// parse the comma-separated --percentiles, which must be between 0 and 100
+fn parse_percentiles(arg: &str) -> Result<Vec<f64>, String> {
+    arg.split(',')
+        .map(|p| match p.trim().parse::<f64>() {
+            Ok(percentile) if (0.0..=100.0).contains(&percentile) => Ok(percentile),
+            _ => Err(format!("{p} is not a percentile between 0 and 100.")),
+        })
+        .collect()
+}
+
 /// returns the path to the stats file
 fn stats_path(stats_csv_path: &Path, stdin_flag: bool) -> io::Result<PathBuf> {
     let parent = stats_csv_path
@@ -1124,6 +2021,53 @@ fn stats_path(stats_csv_path: &Path, stdin_flag: bool) -> io::Result<PathBuf> {
     Ok(parent.join(new_fname))
 }
 
+/// the path of the cached stats state of the input, <FILESTEM>.stats.csv.state.json
+fn stats_state_path(input_path: &Path) -> io::Result<PathBuf> {
+    Ok(stats_path(input_path, false)?.with_extension("csv.state.json"))
+}
+
+/// the hash of the first & last INPUT_FINGERPRINT_LEN bytes of the first `size` bytes of the
+/// input, or an empty string if they don't end with a newline - as rows appended to the
+/// input would then not start on a new record
+fn input_fingerprint(path: &Path, size: u64) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let head_len = size.min(INPUT_FINGERPRINT_LEN);
+    let mut bytes = vec![0; head_len as usize];
+    file.read_exact(&mut bytes)?;
+    let tail_start = size.saturating_sub(INPUT_FINGERPRINT_LEN).max(head_len);
+    file.seek(SeekFrom::Start(tail_start))?;
+    let mut tail = vec![0; (size - tail_start) as usize];
+    file.read_exact(&mut tail)?;
+    bytes.extend(tail);
+
+    if bytes.last() == Some(&b'\n') {
+        Ok(format!("{:016x}", xxh3_64(&bytes)))
+    } else {
+        Ok(String::new())
+    }
+}
+
+/// whether the input has only been appended to since it had `prev_size` bytes with the
+/// `prev_hash` fingerprint
+fn input_appended(path: &Path, prev_size: u64, prev_hash: &str) -> io::Result<bool> {
+    if prev_hash.is_empty() || fs::metadata(path)?.len() <= prev_size {
+        return Ok(false);
+    }
+    Ok(input_fingerprint(path, prev_size)? == prev_hash)
+}
+
+/// the --weight of a record: a positive number, or 0 to leave it out of the weighted stats
+#[inline]
+fn row_weight(record: &csv::ByteRecord, idx: usize) -> f64 {
+    match record
+        .get(idx)
+        .and_then(|field| from_bytes::<f64>(field.trim_ascii()))
+    {
+        Some(weight) if weight.is_finite() && weight > 0.0 => weight,
+        _ => 0.0,
+    }
+}
+
 #[inline]
 fn init_date_inference(
     infer_dates: bool,
@@ -1172,18 +2116,93 @@ fn init_date_inference(
     Ok(())
 }
 
+/// the --histogram of a column
+enum Histogram {
+    /// the counts of the values in equal-width bins between the column's min & max
+    Bins {
+        min:    f64,
+        max:    f64,
+        counts: Vec<u64>,
+    },
+    /// the counts of the distinct values, to keep the most frequent ones
+    TopValues(AHashMap<Vec<u8>, u64>),
+}
+
+impl Histogram {
+    fn add(&mut self, field: &[u8]) {
+        if field.is_empty() {
+            return;
+        }
+        match self {
+            Histogram::Bins { min, max, counts } => {
+                let Some(value) = from_bytes::<f64>(field) else {
+                    return;
+                };
+                let last = counts.len() - 1;
+                let width = (*max - *min) / counts.len() as f64;
+                // the max is in the last bin, as are all the values of a constant column
+                let bin = if width > 0.0 {
+                    (((value - *min) / width) as usize).min(last)
+                } else {
+                    0
+                };
+                counts[bin] += 1;
+            },
+            Histogram::TopValues(counts) => *counts.entry(field.to_vec()).or_default() += 1,
+        }
+    }
+
+    /// the i-th edge of the bins, the last one being exactly the max
+    fn edge(min: f64, max: f64, bins: usize, i: usize) -> f64 {
+        if i == bins {
+            max
+        } else {
+            (max - min).mul_add(i as f64 / bins as f64, min)
+        }
+    }
+
+    /// the top `k` values by count, then by value
+    fn top_values(counts: AHashMap<Vec<u8>, u64>, k: usize) -> Vec<(Vec<u8>, u64)> {
+        let mut counts: Vec<(Vec<u8>, u64)> = counts.into_iter().collect();
+        counts.sort_unstable_by(|(value1, count1), (value2, count2)| {
+            count2.cmp(count1).then_with(|| value1.cmp(value2))
+        });
+        counts.truncate(k);
+        counts
+    }
+}
+
+/// the --format of the stats output
+#[derive(Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Csv,
+    Json,
+    Jsonl,
+}
+
+/// how the --outliers fences are computed
+#[derive(Clone, Copy, PartialEq)]
+enum OutlierMethod {
+    Iqr,
+    Zscore,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
 struct WhichStats {
-    include_nulls: bool,
-    sum:           bool,
-    range:         bool,
-    dist:          bool,
-    cardinality:   bool,
-    median:        bool,
-    mad:           bool,
-    quartiles:     bool,
-    mode:          bool,
-    typesonly:     bool,
+    include_nulls:      bool,
+    sum:                bool,
+    range:              bool,
+    dist:               bool,
+    cardinality:        bool,
+    approx_cardinality: bool,
+    median:             bool,
+    mad:                bool,
+    quartiles:          bool,
+    percentiles:        bool,
+    mode:               bool,
+    typesonly:          bool,
+    weighted:           bool,
+    date_details:       bool,
 }
 
 impl Commute for WhichStats {
@@ -1203,9 +2222,13 @@ pub struct Stats {
     nullcount:     u64,
     max_precision: u16,
     modes:         Option<Unsorted<Vec<u8>>>,
+    hll:           Option<HyperLogLog>,
     median:        Option<Unsorted<f64>>,
     mad:           Option<Unsorted<f64>>,
     quartiles:     Option<Unsorted<f64>>,
+    percentiles:   Option<Percentiles>,
+    weighted:      Option<WeightedStats>,
+    date_details:  Option<DateDetails>,
     which:         WhichStats,
 }
 
@@ -1236,17 +2259,52 @@ impl Stats {
         if which.dist {
             online = Some(stats::OnlineStats::default());
         }
-        if which.mode || which.cardinality {
+        // the modes are used for the exact cardinality, unless it's estimated with a sketch
+        let approx_cardinality = which.cardinality && which.approx_cardinality && !which.mode;
+        if which.mode || (which.cardinality && !approx_cardinality) {
             modes = Some(stats::Unsorted::default());
         }
-        if which.quartiles {
-            quartiles = Some(stats::Unsorted::default());
-        } else if which.median {
-            median = Some(stats::Unsorted::default());
+        let hll = if approx_cardinality {
+            // safety: HLL_PRECISION is set in run with --approx-cardinality
+            Some(HyperLogLog::new(*HLL_PRECISION.get().unwrap()))
+        } else {
+            None
+        };
+        // with --weight, the median, quartiles & percentiles are computed from the
+        // weighted values instead
+        let weighted = if which.weighted {
+            Some(WeightedStats::new(
+                which.median || which.quartiles || which.percentiles,
+            ))
+        } else {
+            None
+        };
+        let date_details = if which.date_details {
+            Some(DateDetails::default())
+        } else {
+            None
+        };
+        if !which.weighted {
+            // with --percentiles, the values of the quartiles are kept by the exact
+            // percentiles instead, so they're only loaded in memory once
+            if which.quartiles && !which.percentiles {
+                quartiles = Some(stats::Unsorted::default());
+            } else if which.median && !which.quartiles {
+                median = Some(stats::Unsorted::default());
+            }
         }
         if which.mad {
             mad = Some(stats::Unsorted::default());
         }
+        // when the quartiles are computed, all the values are loaded in memory anyway,
+        // so the percentiles are exact
+        let percentiles = if !which.percentiles || which.weighted {
+            None
+        } else if which.quartiles {
+            Some(Percentiles::Exact(Vec::new()))
+        } else {
+            Some(Percentiles::Estimated(TDigest::default()))
+        };
         Stats {
             typ: FieldType::default(),
             is_ascii: true,
@@ -1256,15 +2314,27 @@ impl Stats {
             nullcount: 0,
             max_precision: 0,
             modes,
+            hll,
             median,
             mad,
             quartiles,
+            percentiles,
+            weighted,
+            date_details,
             which,
         }
     }
 
+    /// add a sample, with the --weight of its row
     #[inline]
-    fn add(&mut self, sample: &[u8], infer_dates: bool, infer_boolean: bool, prefer_dmy: bool) {
+    fn add(
+        &mut self,
+        sample: &[u8],
+        infer_dates: bool,
+        infer_boolean: bool,
+        prefer_dmy: bool,
+        weight: f64,
+    ) {
         let (sample_type, timestamp_val) =
             FieldType::from_sample(infer_dates, prefer_dmy, sample, self.typ);
         self.typ.merge(sample_type);
@@ -1288,9 +2358,15 @@ impl Stats {
                 v.add(t, sample);
             }
         };
+        if let (Some(v), Some(ts_val)) = (self.date_details.as_mut(), timestamp_val) {
+            v.add(sample, ts_val);
+        };
         if let Some(v) = self.modes.as_mut() {
             v.add(sample.to_vec());
         };
+        if let Some(v) = self.hll.as_mut() {
+            v.add(sample);
+        };
         if sample_type == TNull {
             self.nullcount += 1;
         }
@@ -1300,6 +2376,9 @@ impl Stats {
                     if let Some(v) = self.online.as_mut() {
                         v.add_null();
                     };
+                    if let Some(v) = self.weighted.as_mut() {
+                        v.add(0.0, weight);
+                    };
                 }
             },
             TString => {
@@ -1311,6 +2390,9 @@ impl Stats {
                         if let Some(v) = self.online.as_mut() {
                             v.add_null();
                         };
+                        if let Some(v) = self.weighted.as_mut() {
+                            v.add(0.0, weight);
+                        };
                     }
                 } else {
                     let n = from_bytes::<f64>(sample).unwrap();
@@ -1323,9 +2405,15 @@ impl Stats {
                     if let Some(v) = self.quartiles.as_mut() {
                         v.add(n);
                     }
+                    if let Some(v) = self.percentiles.as_mut() {
+                        v.add(n);
+                    }
                     if let Some(v) = self.online.as_mut() {
                         v.add(&n);
                     }
+                    if let Some(v) = self.weighted.as_mut() {
+                        v.add(n, weight);
+                    }
                     if t == TFloat {
                         let mut buffer = ryu::Buffer::new();
                         // safety: we know that n is a valid f64
@@ -1348,6 +2436,9 @@ impl Stats {
                         if let Some(v) = self.online.as_mut() {
                             v.add_null();
                         };
+                        if let Some(v) = self.weighted.as_mut() {
+                            v.add(0.0, weight);
+                        };
                     }
                 // if ts_val.is_some() then we successfully inferred a date from the sample
                 // and the timestamp value is not None
@@ -1365,14 +2456,84 @@ impl Stats {
                     if let Some(v) = self.quartiles.as_mut() {
                         v.add(n);
                     }
+                    if let Some(v) = self.percentiles.as_mut() {
+                        v.add(n);
+                    }
                     if let Some(v) = self.online.as_mut() {
                         v.add(&n);
                     }
+                    if let Some(v) = self.weighted.as_mut() {
+                        v.add(n, weight);
+                    }
                 }
             },
         }
     }
 
+    /// an empty --histogram of the column, with `bins` bins between the min & max of
+    /// Integer & Float columns
+    fn new_histogram(&self, bins: usize) -> Option<Histogram> {
+        match self.typ {
+            TNull => None,
+            TInteger | TFloat => {
+                let floats = &self.minmax.as_ref()?.floats;
+                Some(Histogram::Bins {
+                    min:    *floats.min()?,
+                    max:    *floats.max()?,
+                    counts: vec![0; bins],
+                })
+            },
+            TString | TDate | TDateTime => Some(Histogram::TopValues(AHashMap::new())),
+        }
+    }
+
+    /// the unweighted quartiles, computed from the values of the exact --percentiles
+    /// when they're computed too
+    fn unweighted_quartiles(&mut self) -> Option<(f64, f64, f64)> {
+        match (self.quartiles.as_mut(), self.percentiles.as_ref()) {
+            (Some(v), _) => v.quartiles(),
+            (None, Some(Percentiles::Exact(values))) => values
+                .iter()
+                .copied()
+                .collect::<Unsorted<f64>>()
+                .quartiles(),
+            _ => None,
+        }
+    }
+
+    /// the lower & upper --outliers fences of Integer & Float columns
+    fn outlier_fences(&mut self, method: OutlierMethod) -> Option<(f64, f64)> {
+        if !matches!(self.typ, TInteger | TFloat) {
+            return None;
+        }
+        match method {
+            OutlierMethod::Iqr => {
+                let (q1, _, q3) = match self.weighted.as_mut() {
+                    Some(weighted) => weighted.quartiles()?,
+                    None => self.unweighted_quartiles()?,
+                };
+                let iqr = q3 - q1;
+                Some((1.5f64.mul_add(-iqr, q1), 1.5f64.mul_add(iqr, q3)))
+            },
+            OutlierMethod::Zscore => {
+                let (mean, stddev) = match self.weighted.as_ref() {
+                    Some(weighted) => {
+                        let (mean, _, stddev, _) = weighted.moments()?;
+                        (mean, stddev)
+                    },
+                    None => {
+                        let online = self.online.as_ref()?;
+                        (online.mean(), online.stddev())
+                    },
+                };
+                Some((
+                    OUTLIER_ZSCORE.mul_add(-stddev, mean),
+                    OUTLIER_ZSCORE.mul_add(stddev, mean),
+                ))
+            },
+        }
+    }
+
     #[allow(clippy::wrong_self_convention)]
     pub fn to_record(&mut self, round_places: u32, infer_boolean: bool) -> csv::StringRecord {
         // we're doing typesonly and not inferring boolean, just return the type
@@ -1395,7 +2556,13 @@ impl Stats {
         match self.modes.as_mut() {
             None => {
                 if self.which.cardinality {
-                    mc_pieces.push(empty());
+                    if let Some(ref hll) = self.hll {
+                        cardinality = hll.estimate() as usize;
+                        let mut buffer = itoa::Buffer::new();
+                        mc_pieces.push(buffer.format(cardinality).to_owned());
+                    } else {
+                        mc_pieces.push(empty());
+                    }
                 }
                 if self.which.mode {
                     mc_pieces.extend_from_slice(&[empty(), empty(), empty(), empty()]);
@@ -1512,7 +2679,13 @@ impl Stats {
         }
 
         // sum
-        if let Some(sum) = self.sum.as_ref().and_then(|sum| sum.show(typ)) {
+        if let Some(weighted) = self
+            .weighted
+            .as_ref()
+            .filter(|_| typ == TInteger || typ == TFloat)
+        {
+            pieces.push(util::round_num(weighted.sum, round_places));
+        } else if let Some(sum) = self.sum.as_ref().and_then(|sum| sum.show(typ)) {
             if typ == FieldType::TFloat {
                 if let Ok(f64_val) = sum.parse::<f64>() {
                     pieces.push(util::round_num(f64_val, round_places));
@@ -1544,18 +2717,24 @@ impl Stats {
         // mean, sem, stddev, variance & cv
         if typ == TString || typ == TNull {
             pieces.extend_from_slice(&[empty(), empty(), empty(), empty(), empty()]);
-        } else if let Some(ref v) = self.online {
-            let std_dev = v.stddev();
-            #[allow(clippy::cast_precision_loss)]
-            let sem = std_dev / (v.len() as f64).sqrt();
-            let mean = v.mean();
+        } else if let Some((mean, sem, std_dev, variance)) =
+            self.online.as_ref().and_then(|v| match self.weighted {
+                Some(ref weighted) => weighted.moments(),
+                None => {
+                    let std_dev = v.stddev();
+                    #[allow(clippy::cast_precision_loss)]
+                    let sem = std_dev / (v.len() as f64).sqrt();
+                    Some((v.mean(), sem, std_dev, v.variance()))
+                },
+            })
+        {
             let cv = (std_dev / mean) * 100_f64;
             if self.typ == TFloat || self.typ == TInteger {
                 pieces.extend_from_slice(&[
                     util::round_num(mean, round_places),
                     util::round_num(sem, round_places),
                     util::round_num(std_dev, round_places),
-                    util::round_num(v.variance(), round_places),
+                    util::round_num(variance, round_places),
                     util::round_num(cv, round_places),
                 ]);
             } else {
@@ -1573,7 +2752,7 @@ impl Stats {
                     u32::max(round_places, DAY_DECIMAL_PLACES),
                 ));
                 pieces.push(util::round_num(
-                    v.variance() / (MS_IN_DAY * MS_IN_DAY),
+                    variance / (MS_IN_DAY * MS_IN_DAY),
                     u32::max(round_places, DAY_DECIMAL_PLACES),
                 ));
                 pieces.push(util::round_num(cv, round_places));
@@ -1604,14 +2783,19 @@ impl Stats {
 
         // median
         let mut existing_median = None;
-        if let Some(v) = self.median.as_mut().and_then(|v| {
-            if let TNull | TString = typ {
-                None
-            } else {
-                existing_median = v.median();
-                existing_median
-            }
-        }) {
+        let median = match typ {
+            TNull | TString => None,
+            // the weighted median isn't used for the unweighted MAD
+            _ if self.which.median => match self.weighted.as_mut() {
+                Some(weighted) => weighted.quantiles(&[0.5]).map(|median| median[0]),
+                None => self.median.as_mut().and_then(|v| {
+                    existing_median = v.median();
+                    existing_median
+                }),
+            },
+            _ => None,
+        };
+        if let Some(v) = median {
             if typ == TDateTime || typ == TDate {
                 pieces.push(timestamp_ms_to_rfc3339(v as i64, typ));
             } else {
@@ -1643,10 +2827,16 @@ impl Stats {
         }
 
         // quartiles
-        match self.quartiles.as_mut().and_then(|v| match typ {
-            TInteger | TFloat | TDate | TDateTime => v.quartiles(),
+        let quartiles = match typ {
+            TInteger | TFloat | TDate | TDateTime if self.which.quartiles => {
+                match self.weighted.as_mut() {
+                    Some(weighted) => weighted.quartiles(),
+                    None => self.unweighted_quartiles(),
+                }
+            },
             _ => None,
-        }) {
+        };
+        match quartiles {
             None => {
                 if self.which.quartiles {
                     pieces.extend_from_slice(&[
@@ -1727,6 +2917,57 @@ impl Stats {
         // append it here to preserve legacy ordering of columns
         pieces.extend_from_slice(&mc_pieces);
 
+        // percentiles
+        if let Some(percentiles) = PERCENTILES.get().filter(|_| self.which.percentiles) {
+            let is_numeric = matches!(typ, TInteger | TFloat | TDate | TDateTime);
+            let values: Vec<Option<f64>> = match (self.percentiles.as_mut(), self.weighted.as_mut())
+            {
+                (Some(v), _) if is_numeric => {
+                    percentiles.iter().map(|&p| v.percentile(p)).collect()
+                },
+                (None, Some(weighted)) if is_numeric => {
+                    let quantiles: Vec<f64> = percentiles.iter().map(|p| p / 100.0).collect();
+                    match weighted.quantiles(&quantiles) {
+                        Some(values) => values.into_iter().map(Some).collect(),
+                        None => vec![None; percentiles.len()],
+                    }
+                },
+                _ => vec![None; percentiles.len()],
+            };
+            for value in values {
+                pieces.push(match value {
+                    Some(p) if typ == TDateTime || typ == TDate => {
+                        timestamp_ms_to_rfc3339(p as i64, typ)
+                    },
+                    Some(p) => util::round_num(p, round_places),
+                    None => empty(),
+                });
+            }
+        }
+
+        if self.which.approx_cardinality {
+            pieces.push(if self.hll.is_some() {
+                "approximate".to_string()
+            } else {
+                "exact".to_string()
+            });
+        }
+
+        if self.which.date_details {
+            match (self.date_details.as_ref(), self.minmax.as_ref()) {
+                (Some(details), Some(mm)) if typ == TDate || typ == TDateTime => {
+                    pieces.push(match (mm.dates.min(), mm.dates.max()) {
+                        (Some(min), Some(max)) => humanize_duration(*max - *min),
+                        _ => empty(),
+                    });
+                    pieces.push(details.granularity().to_string());
+                    pieces.push(details.tz_offsets.iter().join(","));
+                    pieces.push(details.future_count.to_string());
+                },
+                _ => pieces.extend_from_slice(&[empty(), empty(), empty(), empty()]),
+            }
+        }
+
         csv::StringRecord::from(pieces)
     }
 }
@@ -1742,16 +2983,555 @@ impl Commute for Stats {
         self.nullcount += other.nullcount;
         self.max_precision = std::cmp::max(self.max_precision, other.max_precision);
         self.modes.merge(other.modes);
+        self.hll.merge(other.hll);
         self.median.merge(other.median);
         self.quartiles.merge(other.quartiles);
+        self.percentiles.merge(other.percentiles);
+        self.weighted.merge(other.weighted);
+        self.date_details.merge(other.date_details);
         self.which.merge(other.which);
     }
 }
 
+/// the values of a column to compute --percentiles with
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+enum Percentiles {
+    /// all the values, to compute exact percentiles - and the quartiles
+    Exact(Vec<f64>),
+    /// a t-digest, to estimate percentiles with constant memory
+    Estimated(TDigest),
+}
+
+impl Percentiles {
+    #[inline]
+    fn add(&mut self, value: f64) {
+        match self {
+            Percentiles::Exact(values) => values.push(value),
+            Percentiles::Estimated(digest) => digest.add(value),
+        }
+    }
+
+    /// the percentile (between 0 and 100) of the values, or None if there are no values.
+    /// Exact percentiles are linearly interpolated between the closest ranks.
+    fn percentile(&mut self, percentile: f64) -> Option<f64> {
+        match self {
+            Percentiles::Exact(values) => {
+                if values.is_empty() {
+                    return None;
+                }
+                values.sort_unstable_by(f64::total_cmp);
+                #[allow(clippy::cast_precision_loss)]
+                let rank = percentile / 100.0 * (values.len() - 1) as f64;
+                let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+                Some((values[upper] - values[lower]).mul_add(rank.fract(), values[lower]))
+            },
+            Percentiles::Estimated(digest) => digest.percentile(percentile),
+        }
+    }
+}
+
+impl Commute for Percentiles {
+    #[inline]
+    fn merge(&mut self, other: Percentiles) {
+        match (self, other) {
+            (Percentiles::Exact(values), Percentiles::Exact(other)) => values.extend(other),
+            (Percentiles::Estimated(digest), Percentiles::Estimated(other)) => digest.merge(other),
+            // the percentiles of values that weren't all kept can only be estimated
+            (Percentiles::Estimated(digest), Percentiles::Exact(values)) => {
+                for value in values {
+                    digest.add(value);
+                }
+            },
+            (this, Percentiles::Estimated(mut digest)) => {
+                if let Percentiles::Exact(values) = this {
+                    for &value in values.iter() {
+                        digest.add(value);
+                    }
+                }
+                *this = Percentiles::Estimated(digest);
+            },
+        }
+    }
+}
+
+/// a t-digest centroid - the mean of `weight` values
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq)]
+struct Centroid {
+    mean:   f64,
+    weight: f64,
+}
+
+/// A merging t-digest (see https://arxiv.org/abs/1902.04023) to estimate percentiles in a
+/// single pass. Values are buffered, then merged into centroids whose maximum weight is
+/// proportional to q * (1 - q), q being their quantile - so the centroids are small at the
+/// extremes, where the estimates are most accurate, and the number of centroids is bounded
+/// by the compression.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+struct TDigest {
+    centroids: Vec<Centroid>,
+    buffer:    Vec<f64>,
+    count:     f64,
+    min:       f64,
+    max:       f64,
+}
+
+impl TDigest {
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.centroids.is_empty() && self.buffer.is_empty()
+    }
+
+    #[inline]
+    fn add(&mut self, value: f64) {
+        if self.is_empty() {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.buffer.push(value);
+        if self.buffer.len() >= TDIGEST_BUFFER_SIZE {
+            self.compress();
+        }
+    }
+
+    /// merge the buffered values into the centroids
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let buffer = std::mem::take(&mut self.buffer);
+        let centroids = std::mem::take(&mut self.centroids);
+        self.merge_centroids(
+            centroids
+                .into_iter()
+                .chain(
+                    buffer
+                        .into_iter()
+                        .map(|mean| Centroid { mean, weight: 1.0 }),
+                )
+                .collect(),
+        );
+    }
+
+    /// replace the centroids with the given ones, merging the adjacent ones that fit
+    /// in the maximum weight of their quantile
+    fn merge_centroids(&mut self, mut centroids: Vec<Centroid>) {
+        centroids.sort_unstable_by(|a, b| a.mean.total_cmp(&b.mean));
+        let total: f64 = centroids.iter().map(|c| c.weight).sum();
+        self.count = total;
+
+        let mut merged: Vec<Centroid> = Vec::with_capacity(centroids.len());
+        let mut centroids = centroids.into_iter();
+        let Some(mut current) = centroids.next() else {
+            self.centroids = merged;
+            return;
+        };
+        // the weight of the merged centroids before the current one
+        let mut weight_before = 0.0;
+        for centroid in centroids {
+            let q_start = weight_before / total;
+            let q_end = (weight_before + current.weight + centroid.weight) / total;
+            let max_weight = 4.0 * total * (q_start * (1.0 - q_start)).min(q_end * (1.0 - q_end))
+                / TDIGEST_COMPRESSION;
+            if current.weight + centroid.weight <= max_weight {
+                let weight = current.weight + centroid.weight;
+                current.mean += (centroid.mean - current.mean) * centroid.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_before += current.weight;
+                merged.push(current);
+                current = centroid;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+
+    /// merge another t-digest of the same column
+    fn merge(&mut self, other: TDigest) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            *self = other;
+            return;
+        }
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        let centroids = std::mem::take(&mut self.centroids)
+            .into_iter()
+            .chain(other.centroids)
+            .chain(
+                std::mem::take(&mut self.buffer)
+                    .into_iter()
+                    .chain(other.buffer)
+                    .map(|mean| Centroid { mean, weight: 1.0 }),
+            )
+            .collect();
+        self.merge_centroids(centroids);
+    }
+
+    /// the estimated percentile (between 0 and 100), interpolating linearly between the
+    /// centers of the centroids - and the min & max at the extremes
+    fn percentile(&mut self, percentile: f64) -> Option<f64> {
+        self.compress();
+        let first = self.centroids.first()?;
+        if self.centroids.len() == 1 {
+            return Some(first.mean);
+        }
+
+        let rank = percentile / 100.0 * self.count;
+        let mut weight_before = 0.0;
+        let mut prev_center: Option<(f64, f64)> = None;
+        for centroid in &self.centroids {
+            let center = weight_before + centroid.weight / 2.0;
+            if rank < center {
+                let (prev_rank, prev_mean) = prev_center.unwrap_or((0.0, self.min));
+                let estimate = (centroid.mean - prev_mean)
+                    .mul_add((rank - prev_rank) / (center - prev_rank), prev_mean);
+                return Some(estimate.clamp(self.min, self.max));
+            }
+            prev_center = Some((center, centroid.mean));
+            weight_before += centroid.weight;
+        }
+        // between the center of the last centroid and the max
+        let (prev_rank, prev_mean) = prev_center?;
+        let estimate = if self.count > prev_rank {
+            (self.max - prev_mean).mul_add((rank - prev_rank) / (self.count - prev_rank), prev_mean)
+        } else {
+            self.max
+        };
+        Some(estimate.clamp(self.min, self.max))
+    }
+}
+
+/// The --weight-ed stats of a column: its weighted sum, and its weighted mean & sum of
+/// squared deviations updated with West's incremental algorithm
+/// (see https://doi.org/10.1145/359146.359153). The values are kept with their weights
+/// for the weighted quantiles, if needed.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+struct WeightedStats {
+    sum_weights:  f64,
+    // the sum of the squared weights, for the effective sample size
+    sum_weights2: f64,
+    sum:          f64,
+    mean:         f64,
+    m2:           f64,
+    values:       Option<Vec<(f64, f64)>>,
+}
+
+impl WeightedStats {
+    fn new(keep_values: bool) -> Self {
+        Self {
+            sum_weights:  0.0,
+            sum_weights2: 0.0,
+            sum:          0.0,
+            mean:         0.0,
+            m2:           0.0,
+            values:       if keep_values { Some(Vec::new()) } else { None },
+        }
+    }
+
+    #[inline]
+    fn add(&mut self, value: f64, weight: f64) {
+        if weight <= 0.0 {
+            return;
+        }
+        self.sum_weights += weight;
+        self.sum_weights2 = weight.mul_add(weight, self.sum_weights2);
+        self.sum = weight.mul_add(value, self.sum);
+        let delta = value - self.mean;
+        self.mean += delta * weight / self.sum_weights;
+        self.m2 += weight * delta * (value - self.mean);
+        if let Some(values) = self.values.as_mut() {
+            values.push((value, weight));
+        }
+    }
+
+    /// the weighted mean, SEM, stddev & variance, if there are weighted values
+    fn moments(&self) -> Option<(f64, f64, f64, f64)> {
+        if self.sum_weights <= 0.0 {
+            return None;
+        }
+        let variance = self.m2 / self.sum_weights;
+        let stddev = variance.sqrt();
+        let effective_len = self.sum_weights * self.sum_weights / self.sum_weights2;
+        Some((self.mean, stddev / effective_len.sqrt(), stddev, variance))
+    }
+
+    /// the weighted quantiles, between 0 & 1, of the values
+    fn quantiles(&mut self, quantiles: &[f64]) -> Option<Vec<f64>> {
+        let values = self.values.as_mut().filter(|values| !values.is_empty())?;
+        values.sort_unstable_by(|(value1, _), (value2, _)| value1.total_cmp(value2));
+        let cumulative_weights: Vec<f64> = values
+            .iter()
+            .scan(0.0, |cumulative, (_, weight)| {
+                *cumulative += weight;
+                Some(*cumulative)
+            })
+            .collect();
+        let total = *cumulative_weights.last()?;
+        // the tolerance of cumulative weights that reach a quantile exactly
+        let epsilon = total * 1e-12;
+
+        Some(
+            quantiles
+                .iter()
+                .map(|quantile| {
+                    let target = quantile * total;
+                    let i = cumulative_weights
+                        .partition_point(|&cumulative| cumulative < target - epsilon)
+                        .min(values.len() - 1);
+                    match values.get(i + 1) {
+                        Some((next, _)) if (cumulative_weights[i] - target).abs() <= epsilon => {
+                            (values[i].0 + next) / 2.0
+                        },
+                        _ => values[i].0,
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn quartiles(&mut self) -> Option<(f64, f64, f64)> {
+        let quartiles = self.quantiles(&[0.25, 0.5, 0.75])?;
+        Some((quartiles[0], quartiles[1], quartiles[2]))
+    }
+}
+
+impl Commute for WeightedStats {
+    #[inline]
+    fn merge(&mut self, other: WeightedStats) {
+        // the parallel algorithm of Chan et al. to combine the sums of squared deviations
+        let sum_weights = self.sum_weights + other.sum_weights;
+        if sum_weights > 0.0 {
+            let delta = other.mean - self.mean;
+            self.m2 += (delta * delta)
+                .mul_add(self.sum_weights * other.sum_weights / sum_weights, other.m2);
+            self.mean = delta.mul_add(other.sum_weights / sum_weights, self.mean);
+        }
+        self.sum_weights = sum_weights;
+        self.sum_weights2 += other.sum_weights2;
+        self.sum += other.sum;
+        if let (Some(values), Some(other_values)) = (self.values.as_mut(), other.values) {
+            values.extend(other_values);
+        }
+    }
+}
+
+/// the --date-details of a Date or DateTime column
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+struct DateDetails {
+    // the number of values of each of the DATE_GRANULARITIES
+    granularities: [u64; 7],
+    tz_offsets:    BTreeSet<String>,
+    future_count:  u64,
+}
+
+/// the precisions of date values, from the coarsest to the finest
+const DATE_GRANULARITIES: [&str; 7] = [
+    "yearly",
+    "monthly",
+    "daily",
+    "hourly",
+    "minutely",
+    "secondly",
+    "subsecond",
+];
+
+impl DateDetails {
+    #[inline]
+    fn add(&mut self, sample: &[u8], timestamp: i64) {
+        let granularity = if timestamp % 1_000 != 0 {
+            6
+        } else if timestamp % 60_000 != 0 {
+            5
+        } else if timestamp % 3_600_000 != 0 {
+            4
+        } else if timestamp % MS_IN_DAY_INT != 0 {
+            3
+        } else {
+            match chrono::DateTime::from_timestamp_millis(timestamp) {
+                Some(date) if date.day() == 1 && date.month() == 1 => 0,
+                Some(date) if date.day() == 1 => 1,
+                _ => 2,
+            }
+        };
+        self.granularities[granularity] += 1;
+
+        if let Some(offset) = tz_offset(sample) {
+            self.tz_offsets.insert(offset);
+        }
+
+        let now = RUN_TIMESTAMP.get_or_init(|| chrono::Utc::now().timestamp_millis());
+        if timestamp > *now {
+            self.future_count += 1;
+        }
+    }
+
+    /// the most common granularity, the finest one in case of a tie
+    fn granularity(&self) -> &'static str {
+        let mut dominant = 0;
+        for (i, &count) in self.granularities.iter().enumerate() {
+            if count >= self.granularities[dominant] {
+                dominant = i;
+            }
+        }
+        DATE_GRANULARITIES[dominant]
+    }
+}
+
+impl Commute for DateDetails {
+    #[inline]
+    fn merge(&mut self, other: DateDetails) {
+        for (count, other_count) in self.granularities.iter_mut().zip(other.granularities) {
+            *count += other_count;
+        }
+        self.tz_offsets.extend(other.tz_offsets);
+        self.future_count += other.future_count;
+    }
+}
+
+/// the UTC offset at the end of a date sample, after its time, normalized to +HH:MM
+fn tz_offset(sample: &[u8]) -> Option<String> {
+    let sample = from_utf8(sample).ok()?.trim();
+    let upper = sample.to_ascii_uppercase();
+    if upper.ends_with("UTC")
+        || upper.ends_with("GMT")
+        || (upper.ends_with('Z')
+            && upper[..upper.len() - 1].ends_with(|c: char| c.is_ascii_digit()))
+    {
+        return sample.contains(':').then(|| "+00:00".to_string());
+    }
+
+    // +HH, +HHMM or +HH:MM, after a time
+    let sign_idx = sample.rfind(['+', '-'])?;
+    let (time, offset) = sample.split_at(sign_idx);
+    if !time.contains(':') {
+        return None;
+    }
+    let (hours, minutes) = match offset.len() {
+        3 => (offset.get(1..3)?, "00"),
+        5 => (offset.get(1..3)?, offset.get(3..5)?),
+        6 if offset.as_bytes()[3] == b':' => (offset.get(1..3)?, offset.get(4..6)?),
+        _ => return None,
+    };
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if !(is_digits(hours) && is_digits(minutes)) {
+        return None;
+    }
+    Some(format!("{}{hours}:{minutes}", &offset[..1]))
+}
+
+/// a duration in milliseconds in words, with its two largest units, e.g. "2 years 45 days"
+fn humanize_duration(duration_ms: i64) -> String {
+    const UNITS: [(&str, i64); 6] = [
+        ("year", 365 * MS_IN_DAY_INT),
+        ("day", MS_IN_DAY_INT),
+        ("hour", 3_600_000),
+        ("minute", 60_000),
+        ("second", 1_000),
+        ("millisecond", 1),
+    ];
+    let Some(largest) = UNITS
+        .iter()
+        .position(|&(_, unit_ms)| duration_ms >= unit_ms)
+    else {
+        return "0 seconds".to_string();
+    };
+
+    let mut remaining = duration_ms;
+    let mut parts = Vec::with_capacity(2);
+    for &(unit, unit_ms) in UNITS.iter().skip(largest).take(2) {
+        let count = remaining / unit_ms;
+        remaining %= unit_ms;
+        if count > 0 {
+            let plural = if count == 1 { "" } else { "s" };
+            parts.push(format!("{count} {unit}{plural}"));
+        }
+    }
+    parts.join(" ")
+}
+
+/// A HyperLogLog sketch (see https://algo.inria.fr/flajolet/Publications/FlFuGaMe07.pdf) to
+/// estimate the cardinality of a column with 2^precision one-byte registers. Each value is
+/// hashed, the first `precision` bits of the hash select a register, which keeps the
+/// maximum position of the first 1 bit of the remaining bits. The relative standard error
+/// of the estimate is about 1.04 / sqrt(2^precision).
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    const MIN_PRECISION: u8 = 4;
+    const MAX_PRECISION: u8 = 18;
+
+    fn new(precision: u8) -> Self {
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// the smallest precision whose standard error is at most `error`
+    fn precision_for_error(error: f64) -> Option<u8> {
+        (Self::MIN_PRECISION..=Self::MAX_PRECISION)
+            .find(|&precision| 1.04 / f64::from(1_u32 << precision).sqrt() <= error)
+    }
+
+    #[inline]
+    fn add(&mut self, sample: &[u8]) {
+        let hash = xxh3_64(sample);
+        let idx = (hash >> (64 - self.precision)) as usize;
+        // the remaining bits, with a sentinel bit so the rank is at most 64 - precision + 1
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if rank > self.registers[idx] {
+            self.registers[idx] = rank;
+        }
+    }
+
+    fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-i32::from(rank)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        // linear counting is more accurate for small cardinalities
+        if estimate <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round()
+        } else {
+            estimate.round()
+        }
+    }
+}
+
+impl Commute for HyperLogLog {
+    #[inline]
+    fn merge(&mut self, other: HyperLogLog) {
+        for (rank, other_rank) in self.registers.iter_mut().zip(other.registers) {
+            *rank = (*rank).max(other_rank);
+        }
+    }
+}
+
 #[allow(clippy::enum_variant_names)]
 #[allow(clippy::unsafe_derive_deserialize)]
 #[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
-enum FieldType {
+pub(crate) enum FieldType {
     // The default - TNull, is the most specific type.
     // Type inference proceeds by assuming the most specific type and then
     // relaxing the type as counter-examples are found.
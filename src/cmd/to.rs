@@ -235,7 +235,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let output;
     let mut arg_input = args.arg_input.clone();
-    let tmpdir = tempfile::tempdir()?;
+    let tmpdir = util::scratch_dir()?;
 
     if args.cmd_postgres {
         debug!("converting to postgres");
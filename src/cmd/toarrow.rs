@@ -0,0 +1,98 @@
+static USAGE: &str = r#"
+Converts a CSV to an Arrow IPC file - also known as Feather v2 - using the Pola.rs engine,
+to exchange data with the Python & R Arrow ecosystems (e.g. pyarrow.feather.read_table,
+pandas.read_feather or arrow::read_feather).
+
+The type of each column is inferred with the stats command (see 'qsv stats --help'),
+reusing the stats cache of the input if it's current: Integer columns are written as
+int64, Float columns as float64, and String & NULL columns as strings. Date & DateTime
+columns are written as date & datetime (in milliseconds) when their values are in a format
+Polars can parse (e.g. ISO 8601), and as strings otherwise.
+
+Examples:
+
+  # convert data.csv to data.feather
+  $ qsv toarrow data.csv --output data.feather
+
+  # with lz4 compression, from stdin
+  $ cat data.csv | qsv toarrow --compression lz4 -o data.arrow
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_toarrow.rs.
+
+Usage:
+    qsv toarrow [options] [<input>]
+    qsv toarrow --help
+
+toarrow options:
+    --compression <arg>        The compression codec: zstd, lz4 or uncompressed.
+                               [default: zstd]
+    --dates-whitelist <list>   The case-insensitive patterns of the names of the columns
+                               to infer dates in. "all" to infer dates in all the
+                               columns, or "none" to write dates as strings.
+                               [default: all]
+    -j, --jobs <arg>           The number of jobs to run in parallel to infer the types.
+                               When not set, the number of jobs is set to the
+                               number of CPUs detected.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use polars::prelude::{IpcCompression, IpcWriter, SerWriter};
+use serde::Deserialize;
+
+use crate::{
+    cmd::toparquet::typed_csv_frame,
+    config::{Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:            Option<String>,
+    flag_compression:     String,
+    flag_dates_whitelist: String,
+    flag_jobs:            Option<usize>,
+    flag_output:          Option<String>,
+    flag_delimiter:       Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let compression = match args.flag_compression.to_ascii_lowercase().as_str() {
+        "uncompressed" => None,
+        "lz4" => Some(IpcCompression::LZ4),
+        "zstd" => Some(IpcCompression::ZSTD),
+        compression => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --compression: {compression}. Expected zstd, lz4 or uncompressed."
+            )
+        },
+    };
+
+    let (mut df, _) = typed_csv_frame(
+        args.arg_input.clone(),
+        args.flag_delimiter,
+        &args.flag_dates_whitelist,
+        args.flag_jobs,
+    )?;
+
+    let w = match args.flag_output {
+        Some(ref path) => Box::new(File::create(path)?) as Box<dyn Write>,
+        None => Box::new(io::stdout()) as Box<dyn Write>,
+    };
+    let mut w = BufWriter::with_capacity(DEFAULT_WTR_BUFFER_CAPACITY, w);
+    IpcWriter::new(&mut w)
+        .with_compression(compression)
+        .finish(&mut df)?;
+    Ok(w.flush()?)
+}
@@ -0,0 +1,106 @@
+static USAGE: &str = r#"
+Converts a CSV to an Apache Avro object container file using the Pola.rs engine, e.g. to
+produce to Kafka-adjacent pipelines.
+
+The Avro schema is built from the type of each column, inferred with the stats command (as
+in the schema command, see 'qsv schema --help'), reusing the stats cache of the input if
+it's current. Every field is a union with null, as CSV values can be empty:
+  Integer:  long
+  Float:    double
+  Date:     int, with the date logical type
+  DateTime: long, with the timestamp-millis logical type
+  String:   string (also for the NULL columns)
+The Date & DateTime columns are only written with their logical types when their values
+are in a format Polars can parse (e.g. ISO 8601), and as strings otherwise.
+
+Examples:
+
+  # convert data.csv to data.avro
+  $ qsv toavro data.csv --output data.avro
+
+  # with snappy compression, without inferring dates
+  $ qsv toavro --compression snappy --dates-whitelist none data.csv -o data.avro
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_toavro.rs.
+
+Usage:
+    qsv toavro [options] [<input>]
+    qsv toavro --help
+
+toavro options:
+    --compression <arg>        The compression codec: deflate, snappy or uncompressed.
+                               [default: uncompressed]
+    --dates-whitelist <list>   The case-insensitive patterns of the names of the columns
+                               to infer dates in. "all" to infer dates in all the
+                               columns, or "none" to write dates as strings.
+                               [default: all]
+    -j, --jobs <arg>           The number of jobs to run in parallel to infer the types.
+                               When not set, the number of jobs is set to the
+                               number of CPUs detected.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use polars::{
+    io::avro::{AvroWriter, Compression as AvroCompression},
+    prelude::SerWriter,
+};
+use serde::Deserialize;
+
+use crate::{
+    cmd::toparquet::typed_csv_frame,
+    config::{Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:            Option<String>,
+    flag_compression:     String,
+    flag_dates_whitelist: String,
+    flag_jobs:            Option<usize>,
+    flag_output:          Option<String>,
+    flag_delimiter:       Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let compression = match args.flag_compression.to_ascii_lowercase().as_str() {
+        "uncompressed" => None,
+        "deflate" => Some(AvroCompression::Deflate),
+        "snappy" => Some(AvroCompression::Snappy),
+        compression => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --compression: {compression}. Expected deflate, snappy or \
+                 uncompressed."
+            )
+        },
+    };
+
+    let (mut df, _) = typed_csv_frame(
+        args.arg_input.clone(),
+        args.flag_delimiter,
+        &args.flag_dates_whitelist,
+        args.flag_jobs,
+    )?;
+
+    let w = match args.flag_output {
+        Some(ref path) => Box::new(File::create(path)?) as Box<dyn Write>,
+        None => Box::new(io::stdout()) as Box<dyn Write>,
+    };
+    let mut w = BufWriter::with_capacity(DEFAULT_WTR_BUFFER_CAPACITY, w);
+    AvroWriter::new(&mut w)
+        .with_compression(compression)
+        .finish(&mut df)?;
+    Ok(w.flush()?)
+}
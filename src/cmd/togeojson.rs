@@ -0,0 +1,331 @@
+static USAGE: &str = r#"
+Converts CSV data with latitude/longitude columns to GeoJSON or WKT.
+
+By default, each record becomes a GeoJSON Point Feature with the other columns as its
+properties, and all the Features are wrapped in a FeatureCollection.
+Property values are written as JSON strings, or null if empty.
+
+With --linestring-by, the points of the records that share the same value in the given
+column are joined, in input order, into a LineString Feature for each value.
+
+With --wkt, a CSV is written instead, with the WKT geometry (POINT, or LINESTRING
+when --linestring-by is set) in a new column.
+
+Records with empty or out-of-range coordinates are skipped with a warning.
+
+Examples:
+
+  # convert to a GeoJSON FeatureCollection
+  $ qsv togeojson --lat latitude --lon longitude data.csv > data.geojson
+
+  # only keep the name and category columns as properties
+  $ qsv togeojson --lat latitude --lon longitude --properties name,category data.csv
+
+  # write newline-delimited GeoJSON Features
+  $ qsv togeojson --lat lat --lon lon --jsonl data.csv > data.geojsonl
+
+  # add a "geom" column with the WKT POINT of each record
+  $ qsv togeojson --lat lat --lon lon --wkt geom data.csv
+
+  # build a LINESTRING track for each vehicle
+  $ qsv togeojson --lat lat --lon lon --linestring-by vehicle_id --wkt track gps.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_togeojson.rs.
+
+Usage:
+    qsv togeojson [options] --lat <column> --lon <column> [<input>]
+    qsv togeojson --help
+
+togeojson options:
+    --lat <column>             The column with the latitudes.
+    --lon <column>             The column with the longitudes.
+    --properties <cols>        The columns to include as Feature properties.
+                               See 'qsv select --help' for the format details.
+                               (default: all the columns except --lat and --lon)
+    --jsonl                    Write newline-delimited GeoJSON Features instead
+                               of a FeatureCollection.
+    --linestring-by <column>   Join the points of the records with the same value
+                               in <column> into a LineString. Only <column> is kept
+                               as a property. Values with less than two points are skipped.
+    --wkt <column>             Write a CSV with the WKT geometry in a new <column>
+                               instead of GeoJSON. With --linestring-by, the CSV has
+                               one row for each --linestring-by value.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -n, --no-headers           When set, the first row will not be interpreted
+                               as headers. Properties are then named by their
+                               1-based column index.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::io::{BufWriter, Write};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    config::{Config, Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:          Option<String>,
+    flag_lat:           SelectColumns,
+    flag_lon:           SelectColumns,
+    flag_properties:    Option<SelectColumns>,
+    flag_jsonl:         bool,
+    flag_linestring_by: Option<SelectColumns>,
+    flag_wkt:           Option<String>,
+    flag_output:        Option<String>,
+    flag_no_headers:    bool,
+    flag_delimiter:     Option<Delimiter>,
+}
+
+/// a position, keeping the original text of the coordinates for WKT
+struct Position {
+    lon:     f64,
+    lat:     f64,
+    lon_str: String,
+    lat_str: String,
+}
+
+impl Position {
+    /// parse a position, returning None if the coordinates are empty,
+    /// not numbers or out of range
+    fn parse(lat: &[u8], lon: &[u8]) -> Option<Position> {
+        let lat_str = simdutf8::basic::from_utf8(lat).ok()?.trim();
+        let lon_str = simdutf8::basic::from_utf8(lon).ok()?.trim();
+        let lat = lat_str.parse::<f64>().ok()?;
+        let lon = lon_str.parse::<f64>().ok()?;
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return None;
+        }
+        Some(Position {
+            lon,
+            lat,
+            lon_str: lon_str.to_string(),
+            lat_str: lat_str.to_string(),
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!([self.lon, self.lat])
+    }
+
+    fn to_wkt(&self) -> String {
+        format!("{} {}", self.lon_str, self.lat_str)
+    }
+}
+
+/// the index of the single column selected by `sel`
+fn single_column(
+    rconfig: &Config,
+    sel: SelectColumns,
+    headers: &csv::ByteRecord,
+    option: &str,
+) -> CliResult<usize> {
+    let selection = rconfig.clone().select(sel).selection(headers)?;
+    if selection.len() != 1 {
+        return fail_incorrectusage_clierror!("{option} must select exactly one column.");
+    }
+    Ok(selection[0])
+}
+
+fn feature(geometry: Value, properties: Map<String, Value>) -> Value {
+    json!({
+        "type": "Feature",
+        "geometry": geometry,
+        "properties": properties,
+    })
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_jsonl && args.flag_wkt.is_some() {
+        return fail_incorrectusage_clierror!("--jsonl and --wkt cannot be used together.");
+    }
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let lat_idx = single_column(&rconfig, args.flag_lat, &headers, "--lat")?;
+    let lon_idx = single_column(&rconfig, args.flag_lon, &headers, "--lon")?;
+    let linestring_idx = match args.flag_linestring_by {
+        Some(sel) => Some(single_column(&rconfig, sel, &headers, "--linestring-by")?),
+        None => None,
+    };
+
+    // the property names, by column index
+    let property_names: Vec<String> = if rconfig.no_headers {
+        (1..=headers.len()).map(|i| i.to_string()).collect()
+    } else {
+        headers
+            .iter()
+            .map(|h| String::from_utf8_lossy(h).to_string())
+            .collect()
+    };
+    let property_idxs: Vec<usize> = match args.flag_properties {
+        Some(sel) => rconfig.clone().select(sel).selection(&headers)?.to_vec(),
+        None => (0..headers.len())
+            .filter(|idx| *idx != lat_idx && *idx != lon_idx)
+            .collect(),
+    };
+
+    let mut record = csv::ByteRecord::new();
+    let mut skipped: u64 = 0;
+    let mut row_number: u64 = 0;
+
+    if let Some(ref wkt_column) = args.flag_wkt {
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+
+        if let Some(group_idx) = linestring_idx {
+            let mut tracks: IndexMap<Vec<u8>, Vec<Position>> = IndexMap::new();
+            while rdr.read_byte_record(&mut record)? {
+                row_number += 1;
+                let Some(position) = Position::parse(&record[lat_idx], &record[lon_idx]) else {
+                    skipped += 1;
+                    continue;
+                };
+                tracks
+                    .entry(record[group_idx].to_vec())
+                    .or_default()
+                    .push(position);
+            }
+
+            if !rconfig.no_headers {
+                wtr.write_record([&headers[group_idx], wkt_column.as_bytes()])?;
+            }
+            for (group, positions) in tracks {
+                if positions.len() < 2 {
+                    skipped += positions.len() as u64;
+                    continue;
+                }
+                let wkt = format!(
+                    "LINESTRING ({})",
+                    positions
+                        .iter()
+                        .map(Position::to_wkt)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                wtr.write_record([group.as_slice(), wkt.as_bytes()])?;
+            }
+        } else {
+            if !rconfig.no_headers {
+                let mut new_headers = headers.clone();
+                new_headers.push_field(wkt_column.as_bytes());
+                wtr.write_record(&new_headers)?;
+            }
+            while rdr.read_byte_record(&mut record)? {
+                row_number += 1;
+                let Some(position) = Position::parse(&record[lat_idx], &record[lon_idx]) else {
+                    skipped += 1;
+                    continue;
+                };
+                record.push_field(format!("POINT ({})", position.to_wkt()).as_bytes());
+                wtr.write_byte_record(&record)?;
+            }
+        }
+        wtr.flush()?;
+    } else {
+        let mut wtr = BufWriter::with_capacity(
+            DEFAULT_WTR_BUFFER_CAPACITY,
+            Config::new(&args.flag_output).io_writer()?,
+        );
+        let mut first_feature = true;
+        let mut write_feature = |wtr: &mut BufWriter<Box<dyn Write>>, feature: &Value| {
+            if args.flag_jsonl {
+                serde_json::to_writer(&mut *wtr, feature)?;
+                wtr.write_all(b"\n")
+            } else {
+                if !first_feature {
+                    wtr.write_all(b",\n")?;
+                }
+                first_feature = false;
+                serde_json::to_writer(&mut *wtr, feature)?;
+                Ok(())
+            }
+        };
+
+        if !args.flag_jsonl {
+            wtr.write_all(b"{\"type\":\"FeatureCollection\",\"features\":[\n")?;
+        }
+
+        if let Some(group_idx) = linestring_idx {
+            let mut tracks: IndexMap<Vec<u8>, Vec<Position>> = IndexMap::new();
+            while rdr.read_byte_record(&mut record)? {
+                row_number += 1;
+                let Some(position) = Position::parse(&record[lat_idx], &record[lon_idx]) else {
+                    skipped += 1;
+                    continue;
+                };
+                tracks
+                    .entry(record[group_idx].to_vec())
+                    .or_default()
+                    .push(position);
+            }
+
+            for (group, positions) in tracks {
+                if positions.len() < 2 {
+                    skipped += positions.len() as u64;
+                    continue;
+                }
+                let mut properties = Map::with_capacity(1);
+                properties.insert(
+                    property_names[group_idx].clone(),
+                    Value::String(String::from_utf8_lossy(&group).to_string()),
+                );
+                let geometry = json!({
+                    "type": "LineString",
+                    "coordinates": positions.iter().map(Position::to_json).collect::<Vec<_>>(),
+                });
+                write_feature(&mut wtr, &feature(geometry, properties))?;
+            }
+        } else {
+            while rdr.read_byte_record(&mut record)? {
+                row_number += 1;
+                let Some(position) = Position::parse(&record[lat_idx], &record[lon_idx]) else {
+                    skipped += 1;
+                    continue;
+                };
+                let mut properties = Map::with_capacity(property_idxs.len());
+                for &idx in &property_idxs {
+                    let value = record.get(idx).unwrap_or_default();
+                    properties.insert(
+                        property_names[idx].clone(),
+                        if value.is_empty() {
+                            Value::Null
+                        } else {
+                            Value::String(String::from_utf8_lossy(value).to_string())
+                        },
+                    );
+                }
+                let geometry = json!({
+                    "type": "Point",
+                    "coordinates": position.to_json(),
+                });
+                write_feature(&mut wtr, &feature(geometry, properties))?;
+            }
+        }
+
+        if !args.flag_jsonl {
+            wtr.write_all(b"\n]}\n")?;
+        }
+        wtr.flush()?;
+    }
+
+    if skipped > 0 {
+        wwarn!("Skipped {skipped} of {row_number} records without valid coordinates.");
+    }
+
+    Ok(())
+}
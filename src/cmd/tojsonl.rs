@@ -13,6 +13,12 @@ The `tojsonl` command will reuse a `stats.csv.data.jsonl` file if it exists and
 current (i.e. stats generated with --cardinality and --infer-dates options) and will
 skip recomputing stats.
 
+To respect the ingestion limits of targets like Kinesis or Pub/Sub, the size of each
+JSONL record can be capped with --max-record-bytes, and the output can be split into
+numbered parts with --split-output. e.g. with `--output out.jsonl --split-output 500`,
+the parts are written to out-0001.jsonl, out-0002.jsonl, etc., each with at most
+500 records.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_tojsonl.rs.
 
 Usage:
@@ -20,26 +26,45 @@ Usage:
     qsv tojsonl --help
 
 Tojsonl options:
-    --trim                 Trim leading and trailing whitespace from fields
-                           before converting to JSON.
-    --no-boolean           Do not infer boolean fields.
-    -j, --jobs <arg>       The number of jobs to run in parallel.
-                           When not set, the number of jobs is set to the
-                           number of CPUs detected.
-    -b, --batch <size>     The number of rows per batch to load into memory,
-                           before running in parallel. Set to 0 to load all
-                           rows in one batch. [default: 50000]                           
+    --trim                  Trim leading and trailing whitespace from fields
+                            before converting to JSON.
+    --no-boolean            Do not infer boolean fields.
+    -j, --jobs <arg>        The number of jobs to run in parallel.
+                            When not set, the number of jobs is set to the
+                            number of CPUs detected.
+    -b, --batch <size>      The number of rows per batch to load into memory,
+                            before running in parallel. Set to 0 to load all
+                            rows in one batch. [default: 50000]                           
+    --max-record-bytes <n>  The maximum size of a JSONL record in bytes, excluding
+                            the newline. Units are supported - e.g. 1K, 256KB or 1M.
+                            What happens to larger records is set by --oversize.
+    --oversize <policy>     What to do with records larger than --max-record-bytes.
+                              error: stop with an error, reporting the record.
+                              truncate: shorten the longest string values of the
+                                        record until it fits. If it can't fit
+                                        (e.g. it has no string values), stop with
+                                        an error.
+                            [default: error]
+    --split-output <size>   Write the output to numbered parts of --output instead.
+                            A number starts a new part after that many records
+                            (e.g. 10000). A size with a unit starts a new part before
+                            it exceeds that many bytes (e.g. 512B, 100K or 5MB).
+                            Requires --output.
 
 Common options:
-    -h, --help             Display this message
-    -d, --delimiter <arg>  The field delimiter for reading CSV data.
-                           Must be a single character. (default: ,)
-    -o, --output <file>    Write output to <file> instead of stdout.
-    --memcheck             Check if there is enough memory to load the entire
-                           CSV into memory using CONSERVATIVE heuristics.
+    -h, --help              Display this message
+    -d, --delimiter <arg>   The field delimiter for reading CSV data.
+                            Must be a single character. (default: ,)
+    -o, --output <file>     Write output to <file> instead of stdout.
+    --memcheck              Check if there is enough memory to load the entire
+                            CSV into memory using CONSERVATIVE heuristics.
 "#;
 
-use std::{fmt::Write, path::PathBuf, str::FromStr};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use rayon::{
     iter::{IndexedParallelIterator, ParallelIterator},
@@ -57,14 +82,17 @@ use crate::{
 
 #[derive(Deserialize, Clone)]
 struct Args {
-    arg_input:       Option<String>,
-    flag_trim:       bool,
-    flag_no_boolean: bool,
-    flag_jobs:       Option<usize>,
-    flag_batch:      usize,
-    flag_delimiter:  Option<Delimiter>,
-    flag_output:     Option<String>,
-    flag_memcheck:   bool,
+    arg_input:             Option<String>,
+    flag_trim:             bool,
+    flag_no_boolean:       bool,
+    flag_jobs:             Option<usize>,
+    flag_batch:            usize,
+    flag_max_record_bytes: Option<String>,
+    flag_oversize:         String,
+    flag_split_output:     Option<String>,
+    flag_delimiter:        Option<Delimiter>,
+    flag_output:           Option<String>,
+    flag_memcheck:         bool,
 }
 
 impl From<std::fmt::Error> for CliError {
@@ -83,10 +111,59 @@ enum JsonlType {
     Null,
 }
 
+#[derive(PartialEq, EnumString)]
+#[strum(ascii_case_insensitive)]
+enum OversizePolicy {
+    Error,
+    Truncate,
+}
+
+#[derive(Clone, Copy)]
+enum SplitBy {
+    Rows(u64),
+    Bytes(u64),
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
-    let tmpdir = tempfile::tempdir()?;
+    let max_record_bytes = match args.flag_max_record_bytes {
+        Some(ref max_bytes) => match util::parse_size(max_bytes) {
+            Ok(0) | Err(_) => {
+                return fail_incorrectusage_clierror!("Invalid --max-record-bytes: {max_bytes}");
+            },
+            Ok(max_bytes) => Some(max_bytes as usize),
+        },
+        None => None,
+    };
+    let Ok(oversize_policy) = OversizePolicy::from_str(&args.flag_oversize) else {
+        return fail_incorrectusage_clierror!(
+            "Invalid --oversize policy: {}. Must be either error or truncate.",
+            args.flag_oversize
+        );
+    };
+    let split_by = match args.flag_split_output {
+        Some(ref size) => {
+            if args.flag_output.is_none() {
+                return fail_incorrectusage_clierror!("--split-output requires --output.");
+            }
+            // a plain number is a number of records, a size with a unit is a number of bytes
+            let split_by = if size.trim().bytes().all(|b| b.is_ascii_digit()) {
+                size.trim().parse::<u64>().ok().map(SplitBy::Rows)
+            } else {
+                util::parse_size(size).ok().map(SplitBy::Bytes)
+            };
+            match split_by {
+                Some(SplitBy::Rows(0) | SplitBy::Bytes(0)) | None => {
+                    return fail_incorrectusage_clierror!("Invalid --split-output: {size}");
+                },
+                split_by => split_by,
+            }
+        },
+        None => None,
+    };
+
+    let tmpdir = util::scratch_dir()?;
     let work_input = util::process_input(
         vec![PathBuf::from(
             // if no input file is specified, read from stdin "-"
@@ -137,6 +214,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         flag_delimiter:       args.flag_delimiter,
         arg_input:            Some(input_filename.clone()),
         flag_memcheck:        args.flag_memcheck,
+        flag_no_enum:         None,
+        enum_threshold_overrides: Vec::new(),
     };
     // build schema for each field by their inferred type, min/max value/length, and unique values
     let properties_map: Map<String, Value> =
@@ -149,13 +228,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let mut rdr = conf.reader()?;
 
-    // TODO: instead of abusing csv writer to write jsonl file
-    // just use a normal buffered writer
-    let mut wtr = Config::new(&args.flag_output)
-        .flexible(true)
-        .no_headers(true)
-        .quote_style(csv::QuoteStyle::Never)
-        .writer()?;
+    let mut wtr = JsonlWriter::new(args.flag_output.clone(), split_by)?;
 
     let headers = rdr.headers()?.clone();
 
@@ -263,6 +336,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     };
     let mut batch = Vec::with_capacity(batchsize);
     let mut batch_results = Vec::with_capacity(batchsize);
+    let mut row_number: u64 = 0;
 
     // set RAYON_NUM_THREADS
     util::njobs(args.flag_jobs);
@@ -297,67 +371,225 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             .par_iter()
             .map(|record_item| {
                 let mut record = record_item.clone();
-                let mut json_string = String::new();
-                let mut temp_string2 = String::new();
-
-                let mut header_key = Value::String(String::new());
-                let mut temp_val = Value::String(String::new());
-
                 if args.flag_trim {
                     record.trim();
                 }
-                write!(json_string, "{{").unwrap();
-                for (idx, field) in record.iter().enumerate() {
-                    let field_val = if let Some(field_type) = field_type_vec.get(idx) {
-                        match field_type {
-                            JsonlType::String => {
-                                if field.is_empty() {
-                                    "null"
-                                } else {
-                                    // we round-trip thru serde_json to escape the str
-                                    // per json spec (https://www.json.org/json-en.html)
-                                    temp_val = field.into();
-                                    temp_string2 = temp_val.to_string();
-                                    &temp_string2
-                                }
-                            },
-                            JsonlType::Null => "null",
-                            JsonlType::Integer | JsonlType::Number => field,
-                            JsonlType::Boolean => {
-                                if let 't' | 'y' | '1' = boolcheck(field, &mut temp_string2) {
-                                    "true"
-                                } else {
-                                    "false"
-                                }
-                            },
+                let json_string = json_object(&record, &headers, &field_type_vec);
+                match max_record_bytes {
+                    Some(max_bytes) if json_string.len() > max_bytes => {
+                        if oversize_policy == OversizePolicy::Truncate {
+                            truncate_record(&record, &headers, &field_type_vec, max_bytes)
+                        } else {
+                            Err(json_string.len())
                         }
-                    } else {
-                        "null"
-                    };
-                    header_key = headers[idx].into();
-                    if field_val.is_empty() {
-                        write!(json_string, r#"{header_key}:null,"#).unwrap();
-                    } else {
-                        write!(json_string, r#"{header_key}:{field_val},"#).unwrap();
-                    }
+                    },
+                    _ => Ok(json_string),
                 }
-                json_string.pop(); // remove last comma
-                json_string.push('}');
-                record.clear();
-                record.push_field(&json_string);
-                record
             })
             .collect_into_vec(&mut batch_results);
 
         // rayon collect() guarantees original order, so we can just append results each batch
-        for result_record in &batch_results {
-            wtr.write_record(result_record)?;
+        for result in &batch_results {
+            row_number += 1;
+            match result {
+                Ok(json_string) => wtr.write(json_string)?,
+                Err(record_bytes) => {
+                    return fail_clierror!(
+                        "Record {row_number} is {record_bytes} bytes, larger than \
+                         --max-record-bytes {}.",
+                        max_record_bytes.unwrap_or_default()
+                    );
+                },
+            }
         }
 
         batch.clear();
     } // end of batch loop
 
-    Ok(wtr.flush()?)
+    wtr.finish()
+}
+
+/// convert a CSV record to a JSON object, using the inferred JSON type of each field
+fn json_object(
+    record: &csv::StringRecord,
+    headers: &csv::StringRecord,
+    field_type_vec: &[JsonlType],
+) -> String {
+    let mut json_string = String::new();
+    let mut temp_string2 = String::new();
+
+    let mut header_key = Value::String(String::new());
+    let mut temp_val = Value::String(String::new());
+
+    write!(json_string, "{{").unwrap();
+    for (idx, field) in record.iter().enumerate() {
+        let field_val = if let Some(field_type) = field_type_vec.get(idx) {
+            match field_type {
+                JsonlType::String => {
+                    if field.is_empty() {
+                        "null"
+                    } else {
+                        // we round-trip thru serde_json to escape the str
+                        // per json spec (https://www.json.org/json-en.html)
+                        temp_val = field.into();
+                        temp_string2 = temp_val.to_string();
+                        &temp_string2
+                    }
+                },
+                JsonlType::Null => "null",
+                JsonlType::Integer | JsonlType::Number => field,
+                JsonlType::Boolean => {
+                    if let 't' | 'y' | '1' = boolcheck(field, &mut temp_string2) {
+                        "true"
+                    } else {
+                        "false"
+                    }
+                },
+            }
+        } else {
+            "null"
+        };
+        header_key = headers[idx].into();
+        if field_val.is_empty() {
+            write!(json_string, r#"{header_key}:null,"#).unwrap();
+        } else {
+            write!(json_string, r#"{header_key}:{field_val},"#).unwrap();
+        }
+    }
+    json_string.pop(); // remove last comma
+    json_string.push('}');
+    json_string
+}
+
+/// shorten the longest string values of an oversized record until its JSON object
+/// fits in max_bytes. Returns the size of the JSON object if it can't fit.
+fn truncate_record(
+    record: &csv::StringRecord,
+    headers: &csv::StringRecord,
+    field_type_vec: &[JsonlType],
+    max_bytes: usize,
+) -> Result<String, usize> {
+    let mut fields: Vec<String> = record.iter().map(ToString::to_string).collect();
+    let mut json_string = json_object(record, headers, field_type_vec);
+    while json_string.len() > max_bytes {
+        let Some(longest) = fields
+            .iter()
+            .enumerate()
+            .filter(|(idx, field)| {
+                !field.is_empty() && field_type_vec.get(*idx) == Some(&JsonlType::String)
+            })
+            .max_by_key(|(_, field)| field.len())
+            .map(|(idx, _)| idx)
+        else {
+            return Err(json_string.len());
+        };
+
+        // each byte removed from a value removes at least one byte from the JSON object
+        let field = &mut fields[longest];
+        let mut new_len = field.len().saturating_sub(json_string.len() - max_bytes);
+        while !field.is_char_boundary(new_len) {
+            new_len -= 1;
+        }
+        field.truncate(new_len);
+        json_string = json_object(
+            &csv::StringRecord::from(fields.clone()),
+            headers,
+            field_type_vec,
+        );
+    }
+    Ok(json_string)
+}
+
+/// writes the JSONL records to --output (or stdout),
+/// or to numbered parts of --output with --split-output
+struct JsonlWriter {
+    output:     Option<String>,
+    split_by:   Option<SplitBy>,
+    // TODO: instead of abusing csv writer to write jsonl file
+    // just use a normal buffered writer
+    wtr:        csv::Writer<Box<dyn std::io::Write>>,
+    part:       usize,
+    part_rows:  u64,
+    part_bytes: u64,
+}
+
+impl JsonlWriter {
+    fn new(output: Option<String>, split_by: Option<SplitBy>) -> CliResult<Self> {
+        let wtr = if split_by.is_some() {
+            jsonl_writer(Some(part_path(output.as_deref().unwrap_or_default(), 1)))?
+        } else {
+            jsonl_writer(output.clone())?
+        };
+        Ok(Self {
+            output,
+            split_by,
+            wtr,
+            part: 1,
+            part_rows: 0,
+            part_bytes: 0,
+        })
+    }
+
+    fn write(&mut self, json_string: &str) -> CliResult<()> {
+        // include the newline
+        let record_bytes = json_string.len() as u64 + 1;
+        let part_full = match self.split_by {
+            Some(SplitBy::Rows(rows)) => self.part_rows >= rows,
+            // a part always has at least one record
+            Some(SplitBy::Bytes(bytes)) => {
+                self.part_rows > 0 && self.part_bytes + record_bytes > bytes
+            },
+            None => false,
+        };
+        if part_full {
+            self.wtr.flush()?;
+            self.part += 1;
+            self.wtr = jsonl_writer(Some(part_path(
+                self.output.as_deref().unwrap_or_default(),
+                self.part,
+            )))?;
+            self.part_rows = 0;
+            self.part_bytes = 0;
+        }
+
+        self.wtr.write_record([json_string])?;
+        self.part_rows += 1;
+        self.part_bytes += record_bytes;
+        Ok(())
+    }
+
+    fn finish(mut self) -> CliResult<()> {
+        self.wtr.flush()?;
+        if self.split_by.is_some() {
+            winfo!("Wrote {} part/s.", self.part);
+        }
+        Ok(())
+    }
+}
+
+fn jsonl_writer(output: Option<String>) -> CliResult<csv::Writer<Box<dyn std::io::Write>>> {
+    Ok(Config::new(&output)
+        .flexible(true)
+        .no_headers(true)
+        .quote_style(csv::QuoteStyle::Never)
+        .writer()?)
+}
+
+/// the path of a numbered part of the output - e.g. out-0002.jsonl for out.jsonl
+fn part_path(output: &str, part: usize) -> String {
+    let output_path = Path::new(output);
+    let stem = output_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let part_name = match output_path.extension() {
+        Some(ext) => format!("{stem}-{part:04}.{}", ext.to_string_lossy()),
+        None => format!("{stem}-{part:04}"),
+    };
+    output_path
+        .with_file_name(part_name)
+        .to_string_lossy()
+        .to_string()
 }
 
 #[inline]
@@ -0,0 +1,244 @@
+static USAGE: &str = r#"
+Converts a CSV to Apache Parquet, the columnar format most data warehouses & lakehouses
+ingest, using the Pola.rs engine.
+
+The type of each column is inferred with the stats command (see 'qsv stats --help'),
+reusing the stats cache of the input if it's current: Integer columns are written as
+int64, Float columns as float64, and String & NULL columns as strings. Date & DateTime
+columns are written as date & datetime (in milliseconds) when their values are in a format
+Polars can parse (e.g. ISO 8601), and as strings otherwise.
+
+Parquet files are written in row groups of --row-group-size rows, each compressed with
+the --compression codec. With --dictionary, the string columns with few distinct values
+- i.e. a cardinality of at most 10% of the rows - are dictionary encoded, which is much
+more compact for categorical columns like countries or statuses.
+
+Examples:
+
+  # convert data.csv to data.parquet, with zstd compression
+  $ qsv toparquet data.csv --output data.parquet
+
+  # with snappy compression, dictionary encoding & column statistics
+  $ qsv toparquet --compression snappy --dictionary --statistics data.csv -o data.parquet
+
+  # with row groups of 50,000 rows, from stdin
+  $ cat data.csv | qsv toparquet --row-group-size 50000 -o data.parquet
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_toparquet.rs.
+
+Usage:
+    qsv toparquet [options] [<input>]
+    qsv toparquet --help
+
+toparquet options:
+    --compression <arg>        The compression codec: zstd, snappy, lz4raw, gzip
+                               or uncompressed.
+                               [default: zstd]
+    --compress-level <arg>     The compression level of zstd (-7 to 22, default 3)
+                               or gzip (1 to 9, default 6).
+    --row-group-size <n>       The number of rows of each row group.
+                               [default: 100000]
+    --dictionary               Dictionary encode the string columns with a cardinality
+                               of at most 10% of the rows.
+    --statistics               Write the min, max, distinct & null count statistics of
+                               each column chunk, so readers can skip row groups.
+    --dates-whitelist <list>   The case-insensitive patterns of the names of the columns
+                               to infer dates in. "all" to infer dates in all the
+                               columns, or "none" to write dates as strings.
+                               [default: all]
+    -j, --jobs <arg>           The number of jobs to run in parallel to infer the types.
+                               When not set, the number of jobs is set to the
+                               number of CPUs detected.
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use polars::prelude::{
+    col, CategoricalOrdering, DataFrame, DataType, GzipLevel, IntoLazy, LazyCsvReader,
+    LazyFileListReader, ParquetCompression, ParquetWriter, Schema, StatisticsOptions, ZstdLevel,
+};
+use serde::Deserialize;
+
+use crate::{
+    cmd::{joinp::tsvssv_delim, stats::StatsData},
+    config::{Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:            Option<String>,
+    flag_compression:     String,
+    flag_compress_level:  Option<i32>,
+    flag_row_group_size:  usize,
+    flag_dictionary:      bool,
+    flag_statistics:      bool,
+    flag_dates_whitelist: String,
+    flag_jobs:            Option<usize>,
+    flag_output:          Option<String>,
+    flag_delimiter:       Option<Delimiter>,
+}
+
+// the maximum ratio of distinct values to rows for --dictionary to dictionary encode a column
+const DICTIONARY_MAX_DISTINCT_RATIO: f64 = 0.1;
+
+/// the Polars type of a column, from its stats type. None for the Date & DateTime columns,
+/// whose type is inferred by Polars, as it can only parse some date formats
+fn polars_type(stats_type: &str) -> Option<DataType> {
+    match stats_type {
+        "Integer" => Some(DataType::Int64),
+        "Float" => Some(DataType::Float64),
+        "Date" | "DateTime" => None,
+        _ => Some(DataType::String),
+    }
+}
+
+/// Load a CSV in a DataFrame, with the types of its columns inferred with the stats command.
+/// Returns the DataFrame and the stats of its columns.
+/// Also used by the other commands that convert CSVs to typed formats.
+pub fn typed_csv_frame(
+    input: Option<String>,
+    delimiter: Option<Delimiter>,
+    dates_whitelist: &str,
+    jobs: Option<usize>,
+) -> CliResult<(DataFrame, Vec<StatsData>)> {
+    let tmpdir = util::scratch_dir()?;
+    let work_input = util::process_input(
+        vec![PathBuf::from(
+            // if no input file is specified, read from stdin "-"
+            input.unwrap_or_else(|| "-".to_string()),
+        )],
+        &tmpdir,
+        "",
+    )?;
+    if work_input.len() > 1 {
+        return fail_incorrectusage_clierror!(
+            "Only a single CSV can be converted, but {} inputs were given.",
+            work_input.len()
+        );
+    }
+    // safety: there's at least one valid element in work_input
+    let input_filename = work_input[0]
+        .canonicalize()?
+        .into_os_string()
+        .into_string()
+        .unwrap();
+
+    // the types of the columns, including dates, are inferred with the stats command
+    let csv_stats =
+        util::get_typed_stats_records(&input_filename, delimiter, dates_whitelist, false, jobs)?;
+
+    let mut schema = Schema::with_capacity(csv_stats.len());
+    for stats in &csv_stats {
+        if let Some(dtype) = polars_type(&stats.r#type) {
+            schema.with_column(stats.field.as_str().into(), dtype);
+        }
+    }
+
+    let delim = delimiter.map_or(b',', |delimiter| delimiter.as_byte());
+    let df = LazyCsvReader::new(&input_filename)
+        .with_has_header(true)
+        .with_missing_is_null(true)
+        .with_separator(tsvssv_delim(&input_filename, delim))
+        .with_dtype_overwrite(Some(Arc::new(schema)))
+        // the Date & DateTime columns are the only ones left to infer, over all the rows
+        .with_infer_schema_length(None)
+        .with_try_parse_dates(true)
+        .finish()?
+        .collect()?;
+    Ok((df, csv_stats))
+}
+
+/// dictionary encode the string columns with a cardinality of at most 10% of the rows,
+/// as categoricals
+fn dictionary_encode(df: DataFrame, csv_stats: &[StatsData]) -> CliResult<DataFrame> {
+    #[allow(clippy::cast_precision_loss)]
+    let max_cardinality = (df.height() as f64 * DICTIONARY_MAX_DISTINCT_RATIO) as u64;
+    let casts: Vec<_> = csv_stats
+        .iter()
+        .filter(|stats| {
+            stats.r#type == "String"
+                && stats.cardinality > 0
+                && stats.cardinality <= max_cardinality
+        })
+        .filter(|stats| {
+            df.column(&stats.field)
+                .is_ok_and(|column| column.dtype() == &DataType::String)
+        })
+        .map(|stats| {
+            col(stats.field.as_str())
+                .cast(DataType::Categorical(None, CategoricalOrdering::Physical))
+        })
+        .collect();
+    if casts.is_empty() {
+        return Ok(df);
+    }
+    Ok(df.lazy().with_columns(casts).collect()?)
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let compression = match args.flag_compression.to_ascii_lowercase().as_str() {
+        "uncompressed" => ParquetCompression::Uncompressed,
+        "snappy" => ParquetCompression::Snappy,
+        "lz4raw" => ParquetCompression::Lz4Raw,
+        "gzip" => {
+            let gzip_level = args.flag_compress_level.unwrap_or(6) as u8;
+            ParquetCompression::Gzip(Some(GzipLevel::try_new(gzip_level)?))
+        },
+        "zstd" => {
+            let zstd_level = args.flag_compress_level.unwrap_or(3);
+            ParquetCompression::Zstd(Some(ZstdLevel::try_new(zstd_level)?))
+        },
+        compression => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --compression: {compression}. Expected zstd, snappy, lz4raw, gzip or \
+                 uncompressed."
+            )
+        },
+    };
+    if args.flag_row_group_size == 0 {
+        return fail_incorrectusage_clierror!("--row-group-size must be greater than 0.");
+    }
+
+    let (mut df, csv_stats) = typed_csv_frame(
+        args.arg_input.clone(),
+        args.flag_delimiter,
+        &args.flag_dates_whitelist,
+        args.flag_jobs,
+    )?;
+    if args.flag_dictionary {
+        df = dictionary_encode(df, &csv_stats)?;
+    }
+
+    let statistics = StatisticsOptions {
+        min_value:      args.flag_statistics,
+        max_value:      args.flag_statistics,
+        distinct_count: args.flag_statistics,
+        null_count:     args.flag_statistics,
+    };
+
+    let w = match args.flag_output {
+        Some(ref path) => Box::new(File::create(path)?) as Box<dyn Write>,
+        None => Box::new(io::stdout()) as Box<dyn Write>,
+    };
+    let mut w = BufWriter::with_capacity(DEFAULT_WTR_BUFFER_CAPACITY, w);
+    ParquetWriter::new(&mut w)
+        .with_compression(compression)
+        .with_row_group_size(Some(args.flag_row_group_size))
+        .with_statistics(statistics)
+        .finish(&mut df)?;
+    Ok(w.flush()?)
+}
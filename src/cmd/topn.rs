@@ -0,0 +1,159 @@
+static USAGE: &str = r#"
+Keeps the top (or bottom) N rows of each group - e.g. the 5 largest orders of each
+category - without sorting the whole CSV.
+
+The rows are streamed, keeping only the best N rows of each group so far, so the memory
+used is proportional to N times the number of groups. The values of the --by column are
+compared as numbers when they're numbers, and as text otherwise. The rows whose --by
+value is empty are skipped.
+
+The groups are written in the order of their first row, and the rows of each group from
+the best one down, the rows with equal values keeping their input order.
+
+Examples:
+
+  # the 5 largest amounts of each category
+  $ qsv topn --group-by category --by amount --n 5 orders.csv
+
+  # the 3 earliest ISO 8601 dates of each id & region
+  $ qsv topn -g id,region --by date --n 3 --bottom events.csv
+
+  # the 10 highest scores of the whole CSV
+  $ qsv topn --by score scores.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_topn.rs.
+
+Usage:
+    qsv topn [options] --by <column> [<input>]
+    qsv topn --help
+
+topn options:
+    -g, --group-by <cols>  The columns to group the rows by. When not set, the top
+                           rows of the whole CSV are kept.
+                           See 'qsv select --help' for the format details.
+    -b, --by <column>      The column to rank the rows by.
+    --n <n>                The number of rows to keep of each group.
+                           [default: 10]
+    --bottom               Keep the rows with the smallest values instead.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::cmp::Ordering;
+
+use csv::ByteRecord;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_group_by:   Option<SelectColumns>,
+    flag_by:         SelectColumns,
+    flag_n:          usize,
+    flag_bottom:     bool,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+/// a kept row, with its --by value
+struct Ranked {
+    number: Option<f64>,
+    value:  Vec<u8>,
+    record: ByteRecord,
+}
+
+impl Ranked {
+    /// compare the --by values, as numbers if they're both numbers, and as text otherwise
+    fn cmp_value(&self, other: &Ranked) -> Ordering {
+        match (self.number, other.number) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            _ => self.value.cmp(&other.value),
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_n == 0 {
+        return fail_incorrectusage_clierror!("--n must be greater than 0.");
+    }
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let by_sel = args.flag_by.selection(&headers, !rconfig.no_headers)?;
+    if by_sel.len() != 1 {
+        return fail_incorrectusage_clierror!("--by must select a single column.");
+    }
+    let by_col = by_sel[0];
+    let group_sel = match args.flag_group_by {
+        Some(ref group_by) => Some(group_by.selection(&headers, !rconfig.no_headers)?),
+        None => None,
+    };
+
+    // whether a is a better row than b
+    let better = |a: &Ranked, b: &Ranked| {
+        let ordering = a.cmp_value(b);
+        if args.flag_bottom {
+            ordering.is_lt()
+        } else {
+            ordering.is_gt()
+        }
+    };
+
+    // the kept rows of each group, from the best one down
+    let mut groups: IndexMap<Vec<Vec<u8>>, Vec<Ranked>> = IndexMap::new();
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        let value = record.get(by_col).unwrap_or_default().trim_ascii();
+        if value.is_empty() {
+            continue;
+        }
+        let ranked = Ranked {
+            number: simdutf8::basic::from_utf8(value)
+                .ok()
+                .and_then(|value| value.parse::<f64>().ok()),
+            value:  value.to_vec(),
+            record: record.clone(),
+        };
+        let key: Vec<Vec<u8>> = match group_sel {
+            Some(ref sel) => sel.select(&record).map(<[u8]>::to_vec).collect(),
+            None => Vec::new(),
+        };
+        let kept = groups.entry(key).or_default();
+        if kept.len() == args.flag_n && !kept.last().is_some_and(|last| better(&ranked, last)) {
+            continue;
+        }
+        // after the rows that are better or equal, so equal rows keep their input order
+        let pos = kept.partition_point(|other| !better(&ranked, other));
+        kept.insert(pos, ranked);
+        kept.truncate(args.flag_n);
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if !rconfig.no_headers {
+        wtr.write_byte_record(&headers)?;
+    }
+    for kept in groups.into_values() {
+        for ranked in kept {
+            wtr.write_byte_record(&ranked.record)?;
+        }
+    }
+    Ok(wtr.flush()?)
+}
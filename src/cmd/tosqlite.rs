@@ -0,0 +1,237 @@
+static USAGE: &str = r#"
+Loads a CSV into a table of a SQLite database, creating the database if it doesn't exist.
+
+The table is created with a column per CSV column, typed from the column types inferred
+with the stats command (as in 'qsv schema --format sql --dialect sqlite'), reusing the
+stats cache of the input if it's current: Integer columns are INTEGER, Float columns are
+REAL, and the other columns - including dates, which SQLite doesn't have a type for - are
+TEXT. The columns without empty values are NOT NULL. Empty values are loaded as NULLs.
+
+The rows are bulk-loaded in a single transaction, so the table is only loaded if all the
+rows are.
+
+Examples:
+
+  # load data.csv into the data table of db.sqlite
+  $ qsv tosqlite db.sqlite data.csv
+
+  # load it into the orders table, replacing it, with indexes on customer_id & day
+  $ qsv tosqlite db.sqlite --table orders --replace --create-index customer_id,day data.csv
+
+  # append the new orders to the orders table
+  $ qsv tosqlite db.sqlite --table orders --append new_orders.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_tosqlite.rs.
+
+Usage:
+    qsv tosqlite [options] <database> [<input>]
+    qsv tosqlite --help
+
+tosqlite options:
+    -t, --table <name>         The name of the table. Defaults to the name of the input
+                               file without its extension, or "stdin".
+    --create-index <cols>      The comma-separated names of the columns to index, with an
+                               index per column.
+    --replace                  Drop the table first if it exists.
+    --append                   Append the rows to the table if it exists, instead of
+                               failing.
+    --dates-whitelist <list>   The case-insensitive patterns of the names of the columns
+                               to infer dates in, for the NOT NULL constraints.
+                               [default: all]
+    -j, --jobs <arg>           The number of jobs to run in parallel to infer the types.
+                               When not set, the number of jobs is set to the
+                               number of CPUs detected.
+
+Common options:
+    -h, --help                 Display this message
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::path::{Path, PathBuf};
+
+use csv::ByteRecord;
+use rusqlite::{types::Value, Connection};
+use serde::Deserialize;
+
+use crate::{
+    cmd::schema::{create_table_sql, sql_identifier, SqlDialect},
+    config::{Config, Delimiter},
+    util, CliError, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_database:         String,
+    arg_input:            Option<String>,
+    flag_table:           Option<String>,
+    flag_create_index:    Option<String>,
+    flag_replace:         bool,
+    flag_append:          bool,
+    flag_dates_whitelist: String,
+    flag_jobs:            Option<usize>,
+    flag_delimiter:       Option<Delimiter>,
+}
+
+impl From<rusqlite::Error> for CliError {
+    fn from(err: rusqlite::Error) -> CliError {
+        CliError::Other(format!("SQLite error: {err}"))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ColumnType {
+    Integer,
+    Real,
+    Text,
+}
+
+/// the SQLite value of a CSV value, NULL when it's empty
+fn sqlite_value(value: &str, column_type: ColumnType) -> Value {
+    if value.is_empty() {
+        return Value::Null;
+    }
+    match column_type {
+        ColumnType::Integer => value
+            .parse::<i64>()
+            .map_or_else(|_| Value::Text(value.to_string()), Value::Integer),
+        ColumnType::Real => value
+            .parse::<f64>()
+            .map_or_else(|_| Value::Text(value.to_string()), Value::Real),
+        ColumnType::Text => Value::Text(value.to_string()),
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_replace && args.flag_append {
+        return fail_incorrectusage_clierror!("--replace and --append are mutually exclusive.");
+    }
+
+    let tmpdir = util::scratch_dir()?;
+    let work_input = util::process_input(
+        vec![PathBuf::from(
+            // if no input file is specified, read from stdin "-"
+            args.arg_input.clone().unwrap_or_else(|| "-".to_string()),
+        )],
+        &tmpdir,
+        "",
+    )?;
+    if work_input.len() > 1 {
+        return fail_incorrectusage_clierror!(
+            "tosqlite loads a single CSV, but {} inputs were given.",
+            work_input.len()
+        );
+    }
+    // safety: there's at least one valid element in work_input
+    let input_filename = work_input[0]
+        .canonicalize()?
+        .into_os_string()
+        .into_string()
+        .unwrap();
+    let table = match args.flag_table {
+        Some(ref table) => table.clone(),
+        None => Path::new(&input_filename)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+
+    // the types of the columns are inferred with the stats command
+    let csv_stats = util::get_typed_stats_records(
+        &input_filename,
+        args.flag_delimiter,
+        &args.flag_dates_whitelist,
+        false,
+        args.flag_jobs,
+    )?;
+
+    let conf = Config::new(&Some(input_filename)).delimiter(args.flag_delimiter);
+    let mut rdr = conf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    if csv_stats.len() != headers.len() {
+        return fail_clierror!("Cannot infer the types of the columns of the CSV.");
+    }
+    let column_types: Vec<ColumnType> = csv_stats
+        .iter()
+        .map(|stats| match stats.r#type.as_str() {
+            "Integer" => ColumnType::Integer,
+            "Float" => ColumnType::Real,
+            _ => ColumnType::Text,
+        })
+        .collect();
+
+    let mut index_columns = Vec::new();
+    if let Some(ref create_index) = args.flag_create_index {
+        for column in create_index.split(',').map(str::trim) {
+            if !headers.iter().any(|header| header == column.as_bytes()) {
+                return fail_incorrectusage_clierror!(
+                    "--create-index column {column} is not in the CSV."
+                );
+            }
+            index_columns.push(column);
+        }
+    }
+
+    let mut conn = Connection::open(&args.arg_database)?;
+    let tx = conn.transaction()?;
+    let table_exists: bool = tx.query_row(
+        "SELECT count(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [&table],
+        |row| row.get(0),
+    )?;
+    let quoted_table = sql_identifier(SqlDialect::Sqlite, &table);
+    if table_exists && args.flag_replace {
+        tx.execute(&format!("DROP TABLE {quoted_table}"), [])?;
+    } else if table_exists && !args.flag_append {
+        return fail_clierror!(
+            "Table {table} already exists. Use --replace to replace it, or --append to append \
+             to it."
+        );
+    }
+    if !table_exists || args.flag_replace {
+        tx.execute(
+            &create_table_sql(SqlDialect::Sqlite, &table, &headers, &csv_stats)?,
+            [],
+        )?;
+    }
+
+    let columns: Vec<String> = headers
+        .iter()
+        .map(|header| sql_identifier(SqlDialect::Sqlite, &String::from_utf8_lossy(header)))
+        .collect();
+    let placeholders = vec!["?"; headers.len()].join(", ");
+    let insert_sql = format!(
+        "INSERT INTO {quoted_table} ({}) VALUES ({placeholders})",
+        columns.join(", ")
+    );
+    let mut row_count = 0_u64;
+    {
+        let mut insert = tx.prepare(&insert_sql)?;
+        let mut record = ByteRecord::new();
+        let mut values: Vec<Value> = Vec::with_capacity(headers.len());
+        while rdr.read_byte_record(&mut record)? {
+            values.clear();
+            for (value, &column_type) in record.iter().zip(&column_types) {
+                values.push(sqlite_value(&String::from_utf8_lossy(value), column_type));
+            }
+            insert.execute(rusqlite::params_from_iter(&values))?;
+            row_count += 1;
+        }
+    }
+
+    for column in index_columns {
+        let index = sql_identifier(SqlDialect::Sqlite, &format!("idx_{table}_{column}"));
+        tx.execute(
+            &format!(
+                "CREATE INDEX IF NOT EXISTS {index} ON {quoted_table} ({})",
+                sql_identifier(SqlDialect::Sqlite, column)
+            ),
+            [],
+        )?;
+    }
+    tx.commit()?;
+
+    winfo!("Loaded {row_count} rows into {table}.");
+    Ok(())
+}
@@ -0,0 +1,414 @@
+static USAGE: &str = r#"
+Converts CSVs to an Excel .xlsx workbook with typed cells - numbers as numbers & dates as
+dates - so results can be handed back to Excel users as is.
+
+Each input is written to its own worksheet, so a whole analysis bundle ships as a single
+workbook. The inputs can be CSV files, a directory - whose .csv, .tsv, .tab & .ssv files
+are written in the order of their names - or a file with the .infile-list extension with
+the paths of the CSVs, one per line.
+
+The type of each column is inferred with the stats command (see 'qsv stats --help'),
+reusing the stats cache of the input if it's current. Integer & Float columns are written
+as numbers, except for the values Excel can't hold exactly - e.g. integers with leading
+zeros like zip codes, or with more than 15 digits - which are written as text. Date &
+DateTime columns are written as dates, formatted as yyyy-mm-dd & yyyy-mm-dd hh:mm:ss.
+The other columns are written as text, and empty values as blank cells.
+
+The worksheets are named after their input files without their extension, without the
+characters Excel doesn't allow in names & cut to 31 characters. Names that are already
+used get a numbered suffix, e.g. "sales (2)".
+
+The header row of each worksheet is bold. With --autofilter, it gets filter dropdowns,
+and with --freeze-header, it stays visible when scrolling.
+
+Note that an Excel worksheet holds at most 1,048,576 rows & 16,384 columns, and that the
+workbook is built in memory before it's saved.
+
+See also 'qsv to xlsx', which guesses the types of the columns on its own and doesn't
+write dates.
+
+Examples:
+
+  # convert data.csv to data.xlsx
+  $ qsv toxlsx data.xlsx data.csv
+
+  # with filter dropdowns & a frozen header row, in a worksheet named "Results"
+  $ qsv toxlsx results.xlsx --autofilter --freeze-header --sheet Results data.csv
+
+  # the worksheets summary, by_region & by_month in a single workbook
+  $ qsv toxlsx report.xlsx summary.csv by_region.csv by_month.csv
+
+  # a worksheet for each CSV of the partitions directory
+  $ qsv toxlsx partitions.xlsx partitions/
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_toxlsx.rs.
+
+Usage:
+    qsv toxlsx [options] <xlsx> [<input>...]
+    qsv toxlsx --help
+
+toxlsx arguments:
+    <xlsx>                     The .xlsx file to write. It's overwritten if it exists.
+
+toxlsx options:
+    --sheet <names>            The comma-separated names of the worksheets, one per
+                               input, instead of the names of the input files
+                               (or "stdin").
+    --autofilter               Add filter dropdowns to the header row.
+    --freeze-header            Freeze the header row, so it stays visible when scrolling.
+    --dates-whitelist <list>   The case-insensitive patterns of the names of the columns
+                               to infer dates in. "all" to infer dates in all the
+                               columns, or "none" to not write dates.
+                               [default: all]
+    --prefer-dmy               Parse ambiguous dates as day/month/year instead of
+                               month/day/year.
+    -j, --jobs <arg>           The number of jobs to run in parallel to infer the types.
+                               When not set, the number of jobs is set to the
+                               number of CPUs detected.
+
+Common options:
+    -h, --help                 Display this message
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use csv::ByteRecord;
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    util, CliError, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_xlsx:             String,
+    arg_input:            Vec<PathBuf>,
+    flag_sheet:           Option<String>,
+    flag_autofilter:      bool,
+    flag_freeze_header:   bool,
+    flag_dates_whitelist: String,
+    flag_prefer_dmy:      bool,
+    flag_jobs:            Option<usize>,
+    flag_delimiter:       Option<Delimiter>,
+}
+
+impl From<XlsxError> for CliError {
+    fn from(err: XlsxError) -> CliError {
+        CliError::Other(format!("Excel error: {err}"))
+    }
+}
+
+/// the maximum number of rows of a worksheet, including the header row
+const MAX_ROWS: u32 = 1_048_576;
+/// the maximum number of columns of a worksheet
+const MAX_COLS: usize = 16_384;
+/// the most significant digits Excel keeps in a number
+const MAX_DIGITS: usize = 15;
+
+#[derive(Clone, Copy)]
+enum CellType {
+    Number,
+    Date,
+    DateTime,
+    Text,
+}
+
+impl CellType {
+    fn from_stats_type(stats_type: &str) -> CellType {
+        match stats_type {
+            "Integer" | "Float" => CellType::Number,
+            "Date" => CellType::Date,
+            "DateTime" => CellType::DateTime,
+            _ => CellType::Text,
+        }
+    }
+}
+
+/// the cell formats of the worksheet
+struct Formats {
+    header:   Format,
+    date:     Format,
+    datetime: Format,
+}
+
+/// the number in a value, None if Excel can't hold it exactly, i.e. if it has leading
+/// zeros or too many digits
+fn excel_number(value: &str) -> Option<f64> {
+    let unsigned = value.trim_start_matches(['-', '+']);
+    if unsigned.len() > 1 && unsigned.starts_with('0') && !unsigned.starts_with("0.") {
+        return None;
+    }
+    let digits = unsigned
+        .bytes()
+        .take_while(|b| *b != b'e' && *b != b'E')
+        .filter(u8::is_ascii_digit)
+        .count();
+    if digits > MAX_DIGITS {
+        return None;
+    }
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
+/// the Excel serial number of a date, i.e. the days since 1899-12-30 with the time of the day
+/// as the fraction. None for the dates before 1900-03-01, which Excel counts differently
+fn excel_serial(value: &str, prefer_dmy: bool) -> Option<f64> {
+    let datetime: NaiveDateTime = qsv_dateparser::parse_with_preference(value, prefer_dmy)
+        .ok()?
+        .naive_utc();
+    // safety: the Excel epoch is a valid date
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    let serial = (datetime - epoch).num_milliseconds() as f64 / 86_400_000.0;
+    (serial >= 61.0).then_some(serial)
+}
+
+/// the default name of the worksheet of an input, i.e. its file name without its extension,
+/// without the characters Excel doesn't allow & at most 31 characters long
+fn default_sheet_name(input: &Path) -> String {
+    let stem = input
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name: String = stem
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            c => c,
+        })
+        .take(31)
+        .collect();
+    let name = name.trim_matches('\'');
+    if name.is_empty() {
+        "Sheet1".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// a name that isn't in use, adding a numbered suffix to the name if it is.
+/// Excel sheet names are case-insensitive
+fn unique_sheet_name(name: &str, used: &mut Vec<String>) -> String {
+    let is_used = |used: &[String], name: &str| used.iter().any(|u| u.eq_ignore_ascii_case(name));
+    let mut unique = name.to_string();
+    let mut suffix_num = 1;
+    while is_used(used, &unique) {
+        suffix_num += 1;
+        let suffix = format!(" ({suffix_num})");
+        let base: String = name.chars().take(31 - suffix.len()).collect();
+        unique = format!("{base}{suffix}");
+    }
+    used.push(unique.clone());
+    unique
+}
+
+/// the CSV files of a directory, sorted by name
+fn dir_csvs(dir: &Path) -> CliResult<Vec<PathBuf>> {
+    let mut csvs = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let file_name = file_name.strip_suffix(".sz").unwrap_or(&file_name);
+        if path.is_file()
+            && [".csv", ".tsv", ".tab", ".ssv"]
+                .iter()
+                .any(|ext| file_name.ends_with(ext))
+        {
+            csvs.push(path);
+        }
+    }
+    csvs.sort_unstable();
+    Ok(csvs)
+}
+
+/// write a value in a cell, as per the type of its column
+fn write_cell(
+    worksheet: &mut Worksheet,
+    (row, col): (u32, u16),
+    value: &str,
+    cell_type: CellType,
+    formats: &Formats,
+    prefer_dmy: bool,
+) -> CliResult<()> {
+    if value.trim().is_empty() {
+        return Ok(());
+    }
+    match cell_type {
+        CellType::Number => {
+            if let Some(number) = excel_number(value.trim()) {
+                worksheet.write_number(row, col, number)?;
+                return Ok(());
+            }
+        },
+        CellType::Date | CellType::DateTime => {
+            if let Some(serial) = excel_serial(value.trim(), prefer_dmy) {
+                let format = if matches!(cell_type, CellType::Date) {
+                    &formats.date
+                } else {
+                    &formats.datetime
+                };
+                worksheet.write_number_with_format(row, col, serial, format)?;
+                return Ok(());
+            }
+        },
+        CellType::Text => {},
+    }
+    worksheet.write_string(row, col, value)?;
+    Ok(())
+}
+
+/// write a CSV to a new worksheet of the workbook
+fn write_sheet(
+    workbook: &mut Workbook,
+    input: &Path,
+    sheet: &str,
+    formats: &Formats,
+    args: &Args,
+) -> CliResult<()> {
+    // safety: the input was checked by process_input
+    let input_filename = input
+        .canonicalize()?
+        .into_os_string()
+        .into_string()
+        .unwrap();
+
+    // the types of the columns, including dates, are inferred with the stats command
+    let csv_stats = util::get_typed_stats_records(
+        &input_filename,
+        args.flag_delimiter,
+        &args.flag_dates_whitelist,
+        args.flag_prefer_dmy,
+        args.flag_jobs,
+    )?;
+
+    let conf = Config::new(&Some(input_filename)).delimiter(args.flag_delimiter);
+    let mut rdr = conf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    if headers.len() > MAX_COLS {
+        return fail_clierror!(
+            "{} has {} columns, more than the {MAX_COLS} columns an Excel worksheet can hold.",
+            input.display(),
+            headers.len()
+        );
+    }
+    let cell_types: Vec<CellType> = if csv_stats.len() == headers.len() {
+        csv_stats
+            .iter()
+            .map(|stats| CellType::from_stats_type(&stats.r#type))
+            .collect()
+    } else {
+        vec![CellType::Text; headers.len()]
+    };
+
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(sheet)?;
+    for (col, name) in headers.iter().enumerate() {
+        let name = String::from_utf8_lossy(name);
+        worksheet.write_string_with_format(0, col as u16, &*name, &formats.header)?;
+    }
+
+    let mut record = ByteRecord::new();
+    let mut row = 0_u32;
+    while rdr.read_byte_record(&mut record)? {
+        row += 1;
+        if row >= MAX_ROWS {
+            return fail_clierror!(
+                "{} has more rows than an Excel worksheet can hold, i.e. {} besides the header \
+                 row.",
+                input.display(),
+                MAX_ROWS - 1
+            );
+        }
+        for (col, (value, &cell_type)) in record.iter().zip(&cell_types).enumerate() {
+            let value = String::from_utf8_lossy(value);
+            write_cell(
+                worksheet,
+                (row, col as u16),
+                &value,
+                cell_type,
+                formats,
+                args.flag_prefer_dmy,
+            )?;
+        }
+    }
+
+    if let Some(last_col) = headers.len().checked_sub(1) {
+        if args.flag_autofilter {
+            worksheet.autofilter(0, 0, row, last_col as u16)?;
+        }
+        if args.flag_freeze_header {
+            worksheet.set_freeze_panes(1, 0)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    // the CSVs of the directories, as the other files of a directory (e.g. stats caches)
+    // aren't worksheets
+    let mut inputs = Vec::with_capacity(args.arg_input.len());
+    for input in &args.arg_input {
+        if input.is_dir() {
+            inputs.extend(dir_csvs(input)?);
+        } else {
+            inputs.push(input.clone());
+        }
+    }
+    if inputs.is_empty() {
+        if args.arg_input.is_empty() {
+            // if no input file is specified, read from stdin "-"
+            inputs.push(PathBuf::from("-"));
+        } else {
+            return fail_clierror!("No CSV files found in the input directory.");
+        }
+    }
+    let tmpdir = util::scratch_dir()?;
+    let work_input = util::process_input(inputs, &tmpdir, "")?;
+
+    let sheets: Vec<String> = match args.flag_sheet {
+        Some(ref sheets) => {
+            let sheets: Vec<String> = sheets.split(',').map(|s| s.trim().to_string()).collect();
+            if sheets.len() != work_input.len() {
+                return fail_incorrectusage_clierror!(
+                    "--sheet has {} names, but there are {} inputs.",
+                    sheets.len(),
+                    work_input.len()
+                );
+            }
+            sheets
+        },
+        None => {
+            let mut used = Vec::with_capacity(work_input.len());
+            work_input
+                .iter()
+                .map(|input| unique_sheet_name(&default_sheet_name(input), &mut used))
+                .collect()
+        },
+    };
+
+    let formats = Formats {
+        header:   Format::new().set_bold(),
+        date:     Format::new().set_num_format("yyyy-mm-dd"),
+        datetime: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+    };
+    let mut workbook = Workbook::new();
+    for (input, sheet) in work_input.iter().zip(&sheets) {
+        write_sheet(&mut workbook, input, sheet, &formats, &args)?;
+    }
+    workbook.save(Path::new(&args.arg_xlsx))?;
+    Ok(())
+}
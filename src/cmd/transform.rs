@@ -0,0 +1,347 @@
+static USAGE: &str = r#"
+Transforms numeric columns - e.g. to normalize or scale features before training a model -
+replacing the selected columns, or appending the transformed columns with --append.
+
+The --op transform is one of:
+  * zscore: the number of standard deviations from the mean of the column,
+    i.e. (value - mean) / stddev, with the population stddev.
+  * minmax: the value scaled to between 0 & 1, i.e. (value - min) / (max - min).
+  * log: the natural logarithm of the value, which must be positive.
+  * abs: the absolute value.
+  * round:<places>: the value rounded to <places> decimal places, e.g. round:2.
+The transformed columns must be numbers. Empty values stay empty.
+
+zscore & minmax need the mean & stddev or the min & max of the columns. When the input has
+a current stats cache - i.e. a <input>.stats.csv.data.jsonl file, created with
+'qsv stats --stats-jsonl' - they're read from it, and the rows are streamed in a single
+pass. Note that the cached stats are rounded as per 'qsv stats --round'. Otherwise, a
+first pass computes them (stdin being spooled to a scratch file). The other transforms
+are always streamed in a single pass.
+
+Examples:
+
+  # scale the price & quantity columns to between 0 & 1
+  $ qsv transform price,quantity --op minmax data.csv
+
+  # append the z-score of the amount, as an amount_zscore column
+  $ qsv transform amount --op zscore --append data.csv
+
+  # round all the columns that start with "rate" to 2 decimal places
+  $ qsv transform /^rate/ --op round:2 data.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_transform.rs.
+
+Usage:
+    qsv transform [options] --op <op> <selection> [<input>]
+    qsv transform --help
+
+transform arguments:
+    <selection>            The numeric columns to transform.
+                           See 'qsv select --help' for the format details.
+
+transform options:
+    --op <op>              The transform: zscore, minmax, log, abs or round:<places>.
+    -a, --append           Append the transformed columns, named <column>_<op>
+                           (e.g. amount_zscore), instead of replacing the columns.
+    --round <places>       Round the zscore, minmax & log results to <places>
+                           decimal places.
+                           [default: 4]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use csv::ByteRecord;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_selection:   SelectColumns,
+    arg_input:       Option<String>,
+    flag_op:         String,
+    flag_append:     bool,
+    flag_round:      u32,
+    flag_output:     Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Zscore,
+    Minmax,
+    Log,
+    Abs,
+    Round(u32),
+}
+
+impl Op {
+    fn parse(op: &str) -> CliResult<Op> {
+        match op.trim().to_ascii_lowercase().as_str() {
+            "zscore" => Ok(Op::Zscore),
+            "minmax" => Ok(Op::Minmax),
+            "log" => Ok(Op::Log),
+            "abs" => Ok(Op::Abs),
+            op => match op.strip_prefix("round:").map(str::parse::<u32>) {
+                Some(Ok(places)) => Ok(Op::Round(places)),
+                _ => fail_incorrectusage_clierror!(
+                    "Invalid --op: {op}. Expected zscore, minmax, log, abs or round:<places>."
+                ),
+            },
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Op::Zscore => "zscore",
+            Op::Minmax => "minmax",
+            Op::Log => "log",
+            Op::Abs => "abs",
+            Op::Round(_) => "round",
+        }
+    }
+
+    /// whether the transform needs the stats of the column
+    fn needs_stats(self) -> bool {
+        matches!(self, Op::Zscore | Op::Minmax)
+    }
+}
+
+/// the stats of a transformed column
+#[derive(Clone, Copy, Default)]
+struct ColumnStats {
+    mean:   f64,
+    stddev: f64,
+    min:    f64,
+    max:    f64,
+}
+
+/// the running stats of a column in the first pass, with Welford's algorithm
+#[derive(Clone, Copy)]
+struct Acc {
+    count: u64,
+    mean:  f64,
+    m2:    f64,
+    min:   f64,
+    max:   f64,
+}
+
+impl Default for Acc {
+    fn default() -> Self {
+        Acc {
+            count: 0,
+            mean:  0.0,
+            m2:    0.0,
+            min:   f64::INFINITY,
+            max:   f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Acc {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn stats(&self) -> ColumnStats {
+        if self.count == 0 {
+            return ColumnStats::default();
+        }
+        ColumnStats {
+            mean:   self.mean,
+            stddev: (self.m2 / self.count as f64).sqrt(),
+            min:    self.min,
+            max:    self.max,
+        }
+    }
+}
+
+/// parse a value of a transformed column, returning its trimmed text too.
+/// None when it's empty
+fn parse_value<'a>(field: &'a [u8], name: &[u8]) -> CliResult<Option<(&'a str, f64)>> {
+    let field = field.trim_ascii();
+    if field.is_empty() {
+        return Ok(None);
+    }
+    let text = simdutf8::basic::from_utf8(field).ok();
+    match text.and_then(|text| Some((text, text.parse::<f64>().ok()?))) {
+        Some(parsed) => Ok(Some(parsed)),
+        None => fail_clierror!(
+            "Invalid value for {}: \"{}\" is not a number.",
+            String::from_utf8_lossy(name),
+            String::from_utf8_lossy(field)
+        ),
+    }
+}
+
+/// the stats of the columns from the stats cache, None if there's no current cache
+fn cached_stats(
+    args: &Args,
+    headers: &ByteRecord,
+    columns: &[usize],
+) -> CliResult<Option<Vec<ColumnStats>>> {
+    // the stats cache is computed with headers
+    if args.flag_no_headers {
+        return Ok(None);
+    }
+    let csv_stats = util::get_cached_stats_records(&args.arg_input, args.flag_delimiter)?;
+    if csv_stats.len() != headers.len() {
+        return Ok(None);
+    }
+    let mut column_stats = Vec::with_capacity(columns.len());
+    for &column in columns {
+        let stats = &csv_stats[column];
+        let parse = |value: &Option<String>| value.as_ref().and_then(|v| v.parse::<f64>().ok());
+        match stats.r#type.as_str() {
+            "Integer" | "Float" => column_stats.push(ColumnStats {
+                mean:   stats.mean.unwrap_or_default(),
+                stddev: stats.stddev.unwrap_or_default(),
+                min:    parse(&stats.min).unwrap_or_default(),
+                max:    parse(&stats.max).unwrap_or_default(),
+            }),
+            // all the values are empty
+            "NULL" => column_stats.push(ColumnStats::default()),
+            column_type => {
+                return fail_clierror!(
+                    "Cannot transform {}: its type is {column_type}, not a number.",
+                    stats.field
+                )
+            },
+        }
+    }
+    Ok(Some(column_stats))
+}
+
+/// the stats of the columns, in a first pass
+fn scan_stats(
+    rconfig: &Config,
+    headers: &ByteRecord,
+    columns: &[usize],
+) -> CliResult<Vec<ColumnStats>> {
+    let mut rdr = rconfig.reader()?;
+    let mut accs = vec![Acc::default(); columns.len()];
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        for (acc, &column) in accs.iter_mut().zip(columns) {
+            let field = record.get(column).unwrap_or_default();
+            if let Some((_, value)) = parse_value(field, &headers[column])? {
+                acc.add(value);
+            }
+        }
+    }
+    Ok(accs.iter().map(Acc::stats).collect())
+}
+
+/// transform a value, with the stats of its column
+fn transform(op: Op, text: &str, value: f64, stats: &ColumnStats, round: u32) -> CliResult<String> {
+    let transformed = match op {
+        Op::Zscore if stats.stddev == 0.0 => 0.0,
+        Op::Zscore => (value - stats.mean) / stats.stddev,
+        Op::Minmax if stats.max == stats.min => 0.0,
+        Op::Minmax => (value - stats.min) / (stats.max - stats.min),
+        Op::Log if value <= 0.0 => {
+            return fail_clierror!("Cannot take the log of {value}: it's not positive.")
+        },
+        Op::Log => value.ln(),
+        // the sign is dropped, so the number keeps its formatting
+        Op::Abs => return Ok(text.trim_start_matches(['-', '+']).to_string()),
+        Op::Round(places) => return Ok(util::round_num(value, places)),
+    };
+    Ok(util::round_num(transformed, round))
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let op = Op::parse(&args.flag_op)?;
+
+    let mut rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_selection.clone());
+
+    // the stats of stdin are computed in a first pass, so it's spooled
+    let _stdin_spool = if rconfig.is_stdin() && op.needs_stats() {
+        let (spooled_rconfig, spool_dir) = util::spool_stdin(&rconfig)?;
+        rconfig = spooled_rconfig;
+        Some(spool_dir)
+    } else {
+        None
+    };
+
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let columns: Vec<usize> = sel.iter().copied().collect();
+
+    let column_stats = if op.needs_stats() {
+        match cached_stats(&args, &headers, &columns)? {
+            Some(column_stats) => column_stats,
+            None => scan_stats(&rconfig, &headers, &columns)?,
+        }
+    } else {
+        vec![ColumnStats::default(); columns.len()]
+    };
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    if !rconfig.no_headers {
+        let mut out_headers = headers.clone();
+        if args.flag_append {
+            for &column in &columns {
+                let name = format!(
+                    "{}_{}",
+                    String::from_utf8_lossy(&headers[column]),
+                    op.name()
+                );
+                out_headers.push_field(name.as_bytes());
+            }
+        }
+        wtr.write_byte_record(&out_headers)?;
+    }
+
+    let mut record = ByteRecord::new();
+    let mut transformed: Vec<String> = Vec::with_capacity(columns.len());
+    let mut out_record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        transformed.clear();
+        for (stats, &column) in column_stats.iter().zip(&columns) {
+            let field = record.get(column).unwrap_or_default();
+            transformed.push(match parse_value(field, &headers[column])? {
+                Some((text, value)) => transform(op, text, value, stats, args.flag_round)?,
+                None => String::new(),
+            });
+        }
+
+        if args.flag_append {
+            for value in &transformed {
+                record.push_field(value.as_bytes());
+            }
+            wtr.write_byte_record(&record)?;
+        } else {
+            out_record.clear();
+            for (idx, field) in record.iter().enumerate() {
+                match columns.iter().position(|&column| column == idx) {
+                    Some(pos) => out_record.push_field(transformed[pos].as_bytes()),
+                    None => out_record.push_field(field),
+                }
+            }
+            wtr.write_byte_record(&out_record)?;
+        }
+    }
+    Ok(wtr.flush()?)
+}
@@ -16,6 +16,9 @@ A "validation-errors.tsv" report is also created with the following columns:
   * field: the field name of the invalid field
   * error: a validation error message detailing why the field is invalid
 
+With --error-format, the errors can also be written as a SARIF file or as GitHub Actions
+annotations, e.g. when running validate as a CI check on data pull requests.
+
 It uses the JSON Schema Validation Specification (draft 2020-12) to validate the CSV.
 It validates not only the structure of the file, but the data types and domain/range of the
 fields as well. See https://json-schema.org/draft/2020-12/json-schema-validation.html
@@ -51,48 +54,280 @@ If piped from stdin, the filenames will use `stdin.csv` as the base filename. Fo
    * stdin.csv.invalid
    * stdin.csv.validation-errors.tsv
 
+FORMAT VALIDATION:
+The JSON Schema "format" keyword is validated for string fields regardless of the schema's
+draft, using the built-in checkers for the standard formats (e.g. email, uri, uuid, date,
+date-time, time, ipv4, ipv6, hostname) and the following custom formats:
+
+  * email: a stricter check than the spec's - local-part@domain.tld
+  * phone: an optionally "+" prefixed phone number with 7 to 15 digits, allowing spaces,
+           dashes, dots & parentheses as separators (e.g. +1 (555) 123-4567)
+  * currency: digits with exactly 2 decimal places (e.g. 1234.56)
+
+How "format" is validated is set with --formats:
+  * strict: validate formats and fail on formats without a checker.
+  * lax: validate formats and ignore formats without a checker (default).
+  * off: don't validate formats, treating them as annotations only.
+
+The `x-date-format` keyword recorded by `qsv schema` for date columns is also validated:
+a string value must parse with its strftime pattern (e.g. "%m/%d/%Y"), or with one of
+its patterns if it's an array.
+
+CONDITIONAL REQUIREMENTS:
+As each CSV record has all the columns, with null for its empty values, the schema's
+`required` keyword is adapted so conditional requirements can be expressed with the
+standard keywords - e.g. "if country is US, then state is required":
+
+  "if": {"properties": {"country": {"const": "US"}}},
+  "then": {"required": ["state"]}
+
+  * the columns `required` in a subschema - i.e. in if/then/else, allOf, anyOf, oneOf,
+    not & dependentSchemas - must be non-empty. The root `required` list is unchanged.
+  * `dependentRequired` (e.g. {"promo_code": ["discount"]}) requires the listed columns
+    to be non-empty when the dependent column is non-empty.
+An empty column that's required by a subschema is reported in the column's error as
+"empty, but required by <schema location>" (or when <column> is not empty, for
+dependentRequired). Errors about the whole record (e.g. when it matches more than one
+of the oneOf subschemas) are reported in the <RECORD> field.
+
+CROSS-FIELD RULES:
+Row-level rules that a single-column schema can't express (e.g. an end date after the
+start date, or a total matching quantity * price) can be checked with --rules.
+The rules file is a JSON object mapping rule names to jq filters (using jaq -
+https://github.com/01mf02/jaq), which are run on each record as a JSON object typed per
+the schema. A record fails a rule if the filter doesn't return true. For example:
+
+  {
+    "dates_in_order": ".end_date >= .start_date",
+    "total_matches": ".total == .qty * .price",
+    "discount_needs_code": ".discount == null or .promo_code != null"
+  }
+
+Rule failures are reported in the validation-errors.tsv file alongside schema errors,
+with the rule name in the field column.
+
+REGEX RULES:
+For simple pattern checks, --regex-rules takes a CSV with column & regex columns instead of
+a JSON Schema - with one row per rule, so a column can have several rules:
+
+  column,regex
+  sku,[A-Z]{3}-\d{4}
+  zip,\d{5}(-\d{4})?
+  email,[^@\s]+@[^@\s]+
+
+Each regex must match the whole value (i.e. it's anchored with ^ and $), and empty values
+are not checked. Without a <json-schema>, the records are only checked against the regex
+rules (and the --rules, --lookup & --unique checks), with the same invalid/valid files
+and error report as when validating with a schema:
+
+  $ qsv validate data.csv --regex-rules patterns.csv
+
+LOOKUP VALIDATION:
+Referential integrity can be checked with --lookup, which validates that each non-empty
+value of a column exists in a column of another CSV - e.g. that every order has a known
+customer_id. The lookup column is streamed into memory once. Specify --lookup as
+<column>:<lookup CSV path>:<lookup column>, repeating it for each column to check:
+
+  $ qsv validate --lookup customer_id:customers.csv:id --lookup sku:products.csv:sku \
+      orders.csv schema.json
+
+UNIQUE KEYS:
+Duplicate keys can be checked with --unique, which validates that no two records have
+the same values in the selected columns - either a single column (e.g. --unique id) or
+a composite key (e.g. --unique order_id,line_no). Each duplicate is reported with the
+row number of the first record with the same key. Records with an empty key column are
+not checked, as with NULLs in a SQL UNIQUE constraint. The keys are kept in memory.
+Repeat --unique for each key to check:
+
+  $ qsv validate orders.csv schema.json --unique order_id --unique customer_id,order_date
+
+REPAIR MODE:
+With --fix <file>, the fixable values are repaired before validating, and a corrected copy
+of the CSV is written to <file>. The values are fixed according to the schema of their column:
+
+  * leading and trailing whitespace is trimmed.
+  * booleans (e.g. yes/no, Y/N, 1/0, on/off, T/F) are normalized to true/false.
+  * integers written as whole floats (e.g. 3.0) are normalized to integers.
+  * dates and datetimes in "format": "date"/"date-time" columns are normalized to
+    YYYY-MM-DD and RFC 3339 respectively (see QSV_PREFER_DMY for ambiguous dates).
+  * values matching an "enum" value except for case are set to the enum value.
+  * numbers outside of the "minimum"/"maximum" of their column are handled per --fix-range.
+
+Each value changed is listed in an <input>.fixes.tsv file with the row_number, field,
+original & fixed values and the fixes made. The rest of the validation is done on the
+fixed values, so the invalid file and the error report only have what couldn't be fixed.
+
+  $ qsv validate data.csv schema.json --fix data-fixed.csv --fix-range null
+
+SPOT CHECKS:
+To cheaply check a massive file before committing to a full run, only validate a subset
+of its rows with --slice (a range of rows), --sample (random rows) or both (random rows
+of the range). The row numbers in the error report are the row numbers in the file, and
+the valid/invalid files only have the rows that were checked. Use --seed to check the
+same sample every time.
+
+  $ qsv validate huge.csv schema.json --slice 1..10000
+  $ qsv validate huge.csv schema.json --sample 5000 --seed 42
+
+BATCH VALIDATION:
+If several <input> files are given, or <input> is a directory or an `.infile-list` file
+(a text file with one file path per line), all the files are validated against the same
+JSON Schema in parallel. The invalid/valid/validation-errors.tsv files (and the --report)
+are written next to each file, and a consolidated summary CSV with the file, records,
+invalid & status columns is sent to stdout. Returns exit code 1 if any file is invalid.
+To validate files matching a glob pattern, let the shell expand it, e.g.
+`qsv validate partners/*.csv schema.json`
+
 RFC 4180 VALIDATION MODE:
 If run without a JSON Schema file, the CSV is validated if it complies with qsv's interpretation of
 the RFC 4180 CSV standard (see https://github.com/jqnatividad/qsv#rfc-4180-csv-standard).
 
 It also confirms if the CSV is UTF-8 encoded.
 
+With --strict-level, the CSV is linted instead, checking all the records and listing every
+finding rather than stopping at the first problem. Each level adds to the checks of the
+levels below it:
+
+  1: field-count consistency - every record has as many fields as the first one.
+  2: UTF-8 validity and unescaped quotes - quotes within unquoted fields, data after
+     a closing quote and quoted fields without a closing quote.
+  3: RFC 4180 pedantry - lines not ending with CRLF, fields with trailing spaces
+     and a UTF-8 BOM.
+
+The findings are written to stdout as CSV with the level, check, line, byte_offset &
+detail columns (or as JSON with --json), and the exit code is 6 if there are any.
+
+  $ qsv validate --strict-level 3 data.csv
+
+VALIDATING STDIN:
+Piped data can be validated in both modes, e.g. `curl -sL $URL | qsv validate - schema.json`.
+In RFC 4180 validation mode, stdin is streamed without buffering. When validating against
+a JSON Schema, stdin is first spooled to a scratch file (in QSV_TMPDIR, or the system temp dir),
+as the invalid records are split out in a second pass. The output files are named after
+"stdin.csv" (e.g. stdin.csv.invalid). With --fix (and --batch > 0), stdin isn't spooled
+as the second pass reads the fixed file instead.
+
 For both modes, returns exit code 0 when the CSV file is valid, exitcode > 0 otherwise.
 If all records are valid, no output files are produced.
 
+EXIT CODES:
+So scripts can tell why validation failed without parsing stderr, the exit code is:
+
+  0: the CSV is valid.
+  1: invalid records were found (schema violations, failed rules, lookups or unique
+     keys), or not all the files of a batch are valid. Also returned with --valid-output
+     when all the records are valid.
+  2: incorrect usage - invalid options or arguments.
+  3: network error - the JSON Schema URL couldn't be downloaded.
+  5: the CSV is not UTF-8 encoded.
+  6: the CSV is structurally broken - it can't be parsed (e.g. records with a different
+     number of fields), or has --strict-level findings.
+  7: the JSON Schema itself is invalid - it's not valid JSON, can't be compiled or
+     has no "properties".
+  8: I/O error - e.g. the CSV or the JSON Schema file can't be read, or an output file
+     can't be written.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_validate.rs.
 
 Usage:
-    qsv validate [options] [<input>] [<json-schema>]
+    qsv validate [--lookup <spec>...] [--unique <cols>...] [options] [<input>] [<json-schema>]
+    qsv validate [--lookup <spec>...] [--unique <cols>...] [options] <input>... <json-schema>
     qsv validate --help
 
 Validate arguments:
-    <input>                    Input CSV file to validate. If not provided, will read from stdin.
-    <json-schema>              JSON Schema file to validate against. If not provided, `validate`
-                               will run in RFC 4180 validation mode. The file can be a local file
-                               or a URL (http and https schemes supported).
+    <input>                    Input CSV file(s) to validate. If not provided or `-`, will
+                               read from stdin.
+                               If several files are given, or it's a directory or an
+                               `.infile-list` file, validates all the files against
+                               <json-schema> in batch mode.
+    <json-schema>              JSON Schema file to validate against. If it's not provided
+                               and there are no --regex-rules, `validate` will run in RFC 4180
+                               validation mode. The file can be a local file
+                               or a URL (http and https schemes supported). Schemas downloaded
+                               from URLs are cached (see --cache-dir).
 
 Validate options:
     --trim                     Trim leading and trailing whitespace from fields before validating.
-    --fail-fast                Stops on first error.
+    --fail-fast                Stops on first invalid record.
+    --max-errors <n>           Stops after <n> invalid records are found. The valid/invalid
+                               files and the error report only cover the records up to
+                               the last invalid record found.
     --valid <suffix>           Valid record output file suffix. [default: valid]
     --invalid <suffix>         Invalid record output file suffix. [default: invalid]
     --json                     When validating without a schema, return the RFC 4180 check
                                as a JSON file instead of a message.
     --pretty-json              Same as --json, but pretty printed.
+    --strict-level <n>         When validating without a schema, lint the CSV instead,
+                               listing all the findings of the checks up to level <n>
+                               (1 to 3). See RFC 4180 VALIDATION MODE above.
     --valid-output <file>      Change validation mode behavior so if ALL rows are valid, to pass it to
                                output, return exit code 1, and set stderr to the number of valid rows.
                                Setting this will override the default behavior of creating
                                a valid file only when there are invalid records.
+                               If there are invalid records, the valid records are written to
+                               <file> instead of the "valid" suffixed file, so pipelines can
+                               continue with only the clean rows.
                                To send valid records to stdout, use `-` as the filename.
     -j, --jobs <arg>           The number of jobs to run in parallel.
                                When not set, the number of jobs is set to the
                                number of CPUs detected.
     -b, --batch <size>         The number of rows per batch to load into memory,
-                               before running in parallel. Set to 0 to load all rows in one batch.
+                               before running in parallel. The next batch is read while
+                               the current one is validated, and errors are always
+                               reported in row order. Set to 0 to load all rows in one batch.
                                [default: 50000]
     --timeout <seconds>        Timeout for downloading json-schemas on URLs.
                                [default: 30]
+    --cache-dir <dir>          The directory to cache json-schemas downloaded from URLs.
+                               A cached schema is revalidated with the server using its
+                               ETag/Last-Modified headers, so it's only downloaded again
+                               when it changed. If the server can't be reached, the cached
+                               schema is used with a warning.
+                               If the QSV_CACHE_DIR envvar is set, it will be used instead.
+                               [default: ~/.qsv-cache]
+    --no-cache                 Do not use or update the json-schema cache.
+    --formats <mode>           How to validate the "format" keyword - strict, lax or off.
+                               See FORMAT VALIDATION above. [default: lax]
+    --rules <file>             A JSON file of cross-field rules to check on each record.
+                               See CROSS-FIELD RULES above.
+    --regex-rules <file>       A CSV of column,regex rules to check on each record, with
+                               or without a JSON Schema. See REGEX RULES above.
+    --lookup <spec>            Check that the values of a column exist in a column of
+                               another CSV, as <column>:<lookup CSV>:<lookup column>.
+                               Can be repeated. See LOOKUP VALIDATION above.
+    --unique <cols>            Check that the selected columns are a unique key. See
+                               'qsv select --help' for the format details.
+                               Can be repeated. See UNIQUE KEYS above.
+    --fix <file>               Write a copy of the CSV with the fixable values repaired to
+                               <file>. See REPAIR MODE above. Cannot be used in batch mode,
+                               or with --fail-fast or --max-errors.
+    --fix-range <policy>       How --fix handles numbers outside of their column's
+                               minimum/maximum:
+                                 clamp: set to the minimum/maximum.
+                                 null: set to empty.
+                                 keep: leave as is, so they're reported as invalid.
+                               [default: clamp]
+    --error-format <format>    The format of the validation errors of invalid records:
+                                 tsv: the <input>.validation-errors.tsv file.
+                                 sarif: a SARIF 2.1.0 <input>.validation-errors.sarif
+                                   file, to upload to code scanning dashboards.
+                                 github: GitHub Actions error annotations on stdout,
+                                   so the errors are shown inline on pull requests.
+                               SARIF & GitHub line numbers assume the records have no
+                               embedded newlines. [default: tsv]
+    --slice <range>            Only validate the rows in <range> - start..end, start.. or
+                               ..end, with 1-based row numbers (as in the error report)
+                               and both ends inclusive. See SPOT CHECKS above.
+    --sample <n>               Only validate a random sample of <n> rows (of the --slice,
+                               if set). The rows are counted first, which is instantaneous
+                               if the CSV is indexed.
+    --seed <number>            The seed of the --sample random number generator.
+                               If not set, the QSV_SEED environment variable is used.
+    --report <format>          Also write an aggregate validation report to
+                               <input>.validation-report.<json|csv|md> with the number of
+                               rows checked and failed, the error count per column,
+                               the top offending values per column and the duration.
+                               Valid formats are json, csv and markdown.
 
 Common options:
     -h, --help                 Display this message
@@ -105,32 +340,44 @@ Common options:
                                requires headers.
     -d, --delimiter <arg>      The field delimiter for reading CSV data.
                                Must be a single character.
-    -p, --progressbar          Show progress bars. Not valid for stdin.
+    -p, --progressbar          Show a progress bar with the throughput & ETA. The progress
+                               is in records if the CSV is indexed (or with --slice or
+                               with --sample), otherwise it's in bytes read, so the
+                               records don't have to be counted first.
     -Q, --quiet                Do not display validation summary message.
 "#;
 
 use std::{
+    collections::hash_map::Entry,
     env,
     fs::File,
-    io::{BufReader, BufWriter, Read, Write},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
     str,
     sync::{
         atomic::{AtomicU16, Ordering},
         OnceLock,
     },
+    time::Instant,
 };
 
+use ahash::{AHashMap, AHashSet};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
 use csv::ByteRecord;
-use indicatif::HumanCount;
+use indexmap::IndexMap;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
-use indicatif::{ProgressBar, ProgressDrawTarget};
+use indicatif::ProgressDrawTarget;
+use indicatif::{HumanCount, ProgressBar};
 use itertools::Itertools;
+use jaq_interpret::{Ctx, Filter, FilterT, ParseCtx, RcIter, Val};
 use jsonschema::{
     output::BasicOutput,
     paths::{JSONPointer, JsonPointerNode, PathChunk},
     ErrorIterator, JSONSchema, Keyword, ValidationError,
 };
 use log::{debug, info, log_enabled};
+use qsv_dateparser::parse_with_preference_and_timezone;
+use rand::{rngs::StdRng, SeedableRng};
 use rayon::{
     iter::{IndexedParallelIterator, ParallelIterator},
     prelude::IntoParallelRefIterator,
@@ -140,12 +387,16 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, value::Number, Map, Value};
 
 use crate::{
-    config::{Config, Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
-    regex_oncelock, util, CliResult,
+    config::{Config, Delimiter, DEFAULT_RDR_BUFFER_CAPACITY, DEFAULT_WTR_BUFFER_CAPACITY},
+    regex_oncelock,
+    select::SelectColumns,
+    util, CliError, CliResult,
 };
 
 // to save on repeated init/allocs
 static NULL_TYPE: OnceLock<Value> = OnceLock::new();
+// the labels of the subschemas with conditional requirements, keyed by their schema location
+static REQUIREMENT_LABELS: OnceLock<Vec<(String, String)>> = OnceLock::new();
 
 static TIMEOUT_SECS: AtomicU16 = AtomicU16::new(30);
 
@@ -154,6 +405,7 @@ static TIMEOUT_SECS: AtomicU16 = AtomicU16::new(30);
 struct Args {
     flag_trim:         bool,
     flag_fail_fast:    bool,
+    flag_max_errors:   Option<u64>,
     flag_valid:        Option<String>,
     flag_invalid:      Option<String>,
     flag_json:         bool,
@@ -165,9 +417,41 @@ struct Args {
     flag_delimiter:    Option<Delimiter>,
     flag_progressbar:  bool,
     flag_quiet:        bool,
-    arg_input:         Option<String>,
+    arg_input:         Vec<String>,
     arg_json_schema:   Option<String>,
     flag_timeout:      u16,
+    flag_cache_dir:    String,
+    flag_no_cache:     bool,
+    flag_report:       Option<String>,
+    flag_formats:      String,
+    flag_rules:        Option<String>,
+    flag_regex_rules:  Option<String>,
+    flag_lookup:       Vec<String>,
+    flag_unique:       Vec<String>,
+    flag_fix:          Option<String>,
+    flag_fix_range:    String,
+    flag_strict_level: Option<u8>,
+    flag_error_format: String,
+    flag_slice:        Option<String>,
+    flag_sample:       Option<u64>,
+    flag_seed:         Option<u64>,
+}
+
+impl Args {
+    /// whether the records are validated, against a JSON Schema and/or regex rules.
+    /// Otherwise, the CSV is validated in RFC 4180 validation mode.
+    fn validates_records(&self) -> bool {
+        self.arg_json_schema.is_some() || self.flag_regex_rules.is_some()
+    }
+
+    /// the number of invalid records after which validation stops, 0 means no limit
+    fn max_errors(&self) -> u64 {
+        if self.flag_fail_fast {
+            1
+        } else {
+            self.flag_max_errors.unwrap_or_default()
+        }
+    }
 }
 
 enum JSONtypes {
@@ -188,6 +472,348 @@ struct RFC4180Struct {
     fields:         Vec<String>,
 }
 
+/// a finding of the --strict-level lint mode
+#[derive(Serialize)]
+struct LintFinding {
+    level:       u8,
+    check:       &'static str,
+    line:        u64,
+    byte_offset: u64,
+    detail:      String,
+}
+
+/// where the lint scanner is within the current field
+#[derive(PartialEq)]
+enum LintState {
+    StartOfField,
+    Unquoted,
+    Quoted,
+    // a quote in a quoted field, either the first half of an escaped quote or the closing quote
+    QuoteInQuoted,
+    AfterClosingQuote,
+}
+
+/// a byte-level scanner for the --strict-level lint mode. The csv reader is lenient about
+/// quotes, line terminators & BOMs, so we scan the raw bytes to find them.
+struct Linter {
+    level:             u8,
+    delimiter:         u8,
+    quote:             u8,
+    state:             LintState,
+    findings:          Vec<LintFinding>,
+    offset:            u64,
+    line:              u64,
+    prev_byte:         u8,
+    record_started:    bool,
+    record_line:       u64,
+    record_offset:     u64,
+    record_bytes:      Vec<u8>,
+    num_fields:        u64,
+    expected_fields:   Option<u64>,
+    num_records:       u64,
+    trailing_spaces:   u64,
+    non_crlf_count:    u64,
+    first_non_crlf_at: (u64, u64),
+}
+
+impl Linter {
+    fn new(level: u8, delimiter: u8, quote: u8) -> Self {
+        Self {
+            level,
+            delimiter,
+            quote,
+            state: LintState::StartOfField,
+            findings: Vec::new(),
+            offset: 0,
+            line: 1,
+            prev_byte: 0,
+            record_started: false,
+            record_line: 1,
+            record_offset: 0,
+            record_bytes: Vec::new(),
+            num_fields: 0,
+            expected_fields: None,
+            num_records: 0,
+            trailing_spaces: 0,
+            non_crlf_count: 0,
+            first_non_crlf_at: (0, 0),
+        }
+    }
+
+    fn add_finding(
+        &mut self,
+        level: u8,
+        check: &'static str,
+        (line, byte_offset): (u64, u64),
+        detail: String,
+    ) {
+        if level <= self.level {
+            self.findings.push(LintFinding {
+                level,
+                check,
+                line,
+                byte_offset,
+                detail,
+            });
+        }
+    }
+
+    /// skip a UTF-8 BOM at the start of the input
+    fn bom(&mut self, len: u64) {
+        self.add_finding(
+            3,
+            "bom",
+            (1, 0),
+            "the file starts with a UTF-8 BOM".to_string(),
+        );
+        self.offset = len;
+    }
+
+    fn push(&mut self, byte: u8) {
+        if self.state == LintState::QuoteInQuoted {
+            if byte == self.quote {
+                // an escaped quote
+                self.state = LintState::Quoted;
+                self.record_byte(byte);
+                self.next_byte(byte);
+                return;
+            }
+            self.state = LintState::AfterClosingQuote;
+        }
+        if self.prev_byte == b'\r' && byte != b'\n' && self.state != LintState::Quoted {
+            self.non_crlf(self.offset - 1);
+        }
+
+        match self.state {
+            LintState::Quoted => {
+                if byte == self.quote {
+                    self.state = LintState::QuoteInQuoted;
+                }
+                self.record_byte(byte);
+            },
+            _ if byte == b'\r' || byte == b'\n' => {
+                if byte == b'\n' && self.prev_byte != b'\r' {
+                    self.non_crlf(self.offset);
+                }
+                self.end_record();
+            },
+            _ if byte == self.delimiter => {
+                self.end_field();
+                self.record_byte(byte);
+            },
+            LintState::StartOfField if byte == self.quote => {
+                self.state = LintState::Quoted;
+                self.record_byte(byte);
+            },
+            LintState::Unquoted | LintState::AfterClosingQuote if byte == self.quote => {
+                self.add_finding(
+                    2,
+                    "unescaped_quote",
+                    (self.line, self.offset),
+                    format!("unescaped quote in field {}", self.num_fields + 1),
+                );
+                self.state = LintState::Unquoted;
+                self.trailing_spaces = 0;
+                self.record_byte(byte);
+            },
+            LintState::AfterClosingQuote => {
+                self.add_finding(
+                    2,
+                    "unescaped_quote",
+                    (self.line, self.offset),
+                    format!(
+                        "data after the closing quote of field {}",
+                        self.num_fields + 1
+                    ),
+                );
+                self.state = LintState::Unquoted;
+                self.trailing_spaces = u64::from(byte == b' ' || byte == b'\t');
+                self.record_byte(byte);
+            },
+            _ => {
+                self.state = LintState::Unquoted;
+                if byte == b' ' || byte == b'\t' {
+                    self.trailing_spaces += 1;
+                } else {
+                    self.trailing_spaces = 0;
+                }
+                self.record_byte(byte);
+            },
+        }
+        self.next_byte(byte);
+    }
+
+    fn next_byte(&mut self, byte: u8) {
+        if byte == b'\n' {
+            self.line += 1;
+        }
+        self.prev_byte = byte;
+        self.offset += 1;
+    }
+
+    fn record_byte(&mut self, byte: u8) {
+        if !self.record_started {
+            self.record_started = true;
+            self.record_line = self.line;
+            self.record_offset = self.offset;
+        }
+        // the record bytes are only needed for the UTF-8 check
+        if self.level >= 2 {
+            self.record_bytes.push(byte);
+        }
+    }
+
+    fn non_crlf(&mut self, offset: u64) {
+        if self.non_crlf_count == 0 {
+            self.first_non_crlf_at = (self.line, offset);
+        }
+        self.non_crlf_count += 1;
+    }
+
+    fn end_field(&mut self) {
+        if self.state == LintState::Unquoted && self.trailing_spaces > 0 {
+            let offset = self.offset - self.trailing_spaces;
+            let detail = format!(
+                "field {} has {} trailing space(s)",
+                self.num_fields + 1,
+                self.trailing_spaces
+            );
+            self.add_finding(3, "trailing_space", (self.line, offset), detail);
+        }
+        self.num_fields += 1;
+        self.trailing_spaces = 0;
+        self.state = LintState::StartOfField;
+    }
+
+    fn end_record(&mut self) {
+        // skip empty lines, like the csv reader does
+        if !self.record_started {
+            return;
+        }
+        self.end_field();
+
+        match self.expected_fields {
+            None => self.expected_fields = Some(self.num_fields),
+            Some(expected) if expected != self.num_fields => {
+                let detail = format!("record has {} fields, expected {expected}", self.num_fields);
+                self.add_finding(
+                    1,
+                    "field_count",
+                    (self.record_line, self.record_offset),
+                    detail,
+                );
+            },
+            Some(_) => {},
+        }
+        if let Err(e) = str::from_utf8(&self.record_bytes) {
+            let offset = self.record_offset + e.valid_up_to() as u64;
+            self.add_finding(
+                2,
+                "utf8",
+                (self.record_line, offset),
+                "invalid UTF-8 sequence".to_string(),
+            );
+        }
+
+        self.num_records += 1;
+        self.num_fields = 0;
+        self.record_started = false;
+        self.record_bytes.clear();
+    }
+
+    /// returns the findings sorted by their position, and the number of records
+    fn finish(mut self) -> (Vec<LintFinding>, u64) {
+        if self.prev_byte == b'\r' && self.state != LintState::Quoted {
+            self.non_crlf(self.offset - 1);
+        }
+        if self.state == LintState::Quoted {
+            self.add_finding(
+                2,
+                "unescaped_quote",
+                (self.record_line, self.record_offset),
+                format!("field {} has no closing quote", self.num_fields + 1),
+            );
+            self.state = LintState::Unquoted;
+        }
+        self.end_record();
+
+        if self.non_crlf_count > 0 {
+            let detail = format!("{} line(s) do not end with CRLF", self.non_crlf_count);
+            self.add_finding(3, "line_terminator", self.first_non_crlf_at, detail);
+        }
+
+        self.findings.sort_by_key(|finding| finding.byte_offset);
+        (self.findings, self.num_records)
+    }
+}
+
+/// lint the CSV's structure per --strict-level, writing the findings to stdout
+fn lint_csv(rconfig: &Config, level: u8, args: &Args) -> CliResult<()> {
+    const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+    let mut linter = Linter::new(level, rconfig.get_delimiter(), rconfig.quote);
+    let mut rdr = rconfig.io_reader()?;
+
+    let mut head = Vec::with_capacity(UTF8_BOM.len());
+    (&mut rdr)
+        .take(UTF8_BOM.len() as u64)
+        .read_to_end(&mut head)?;
+    if head == UTF8_BOM {
+        linter.bom(head.len() as u64);
+    } else {
+        for byte in head {
+            linter.push(byte);
+        }
+    }
+    let mut buffer = vec![0_u8; DEFAULT_RDR_BUFFER_CAPACITY];
+    loop {
+        let bytes_read = rdr.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        for &byte in &buffer[..bytes_read] {
+            linter.push(byte);
+        }
+    }
+    let (findings, mut num_records) = linter.finish();
+    if !rconfig.no_headers {
+        num_records = num_records.saturating_sub(1);
+    }
+
+    if args.flag_json || args.flag_pretty_json {
+        let lint_json = json!({
+            "strict_level": level,
+            "num_records": num_records,
+            "findings": findings,
+        });
+        if args.flag_pretty_json {
+            println!("{}", serde_json::to_string_pretty(&lint_json).unwrap());
+        } else {
+            println!("{lint_json}");
+        }
+    } else if !findings.is_empty() {
+        let mut wtr = Config::new(&None).writer()?;
+        for finding in &findings {
+            wtr.serialize(finding)?;
+        }
+        wtr.flush()?;
+    } else if !args.flag_quiet {
+        woutinfo!(
+            "Valid: no findings at strict level {level}. Records: {};",
+            HumanCount(num_records)
+        );
+    }
+
+    if findings.is_empty() {
+        Ok(())
+    } else {
+        fail_malformedcsv_clierror!(
+            "{} finding(s) at strict level {level}.",
+            HumanCount(findings.len() as u64)
+        )
+    }
+}
+
 struct IsAsciiValidator;
 impl Keyword for IsAsciiValidator {
     fn validate<'instance>(
@@ -216,52 +842,805 @@ impl Keyword for IsAsciiValidator {
                 return false;
             }
         }
-        true
+        true
+    }
+}
+
+fn custom_object_type_factory<'a>(
+    _: &'a Map<String, Value>,
+    schema: &'a Value,
+    path: JSONPointer,
+) -> Result<Box<dyn Keyword>, ValidationError<'a>> {
+    const EXPECTED: &str = "ascii-keys";
+    if schema.as_str().map_or(true, |key| key != EXPECTED) {
+        Err(ValidationError::custom(
+            JSONPointer::default(),
+            path,
+            schema,
+            "Expected 'ascii-keys'",
+        ))
+    } else {
+        Ok(Box::new(IsAsciiValidator))
+    }
+}
+
+/// the `x-date-format` keyword of `qsv schema`: string values must parse with one of the
+/// keyword's strftime patterns
+struct DateFormatValidator {
+    patterns:    Vec<String>,
+    schema_path: JSONPointer,
+}
+impl Keyword for DateFormatValidator {
+    fn validate<'instance>(
+        &self,
+        instance: &'instance Value,
+        instance_path: &JsonPointerNode,
+    ) -> ErrorIterator<'instance> {
+        if self.is_valid(instance) {
+            return Box::new(std::iter::empty());
+        }
+        let error = ValidationError::custom(
+            self.schema_path.clone(),
+            instance_path.into(),
+            instance,
+            format!(
+                "{instance} does not match the date format {}",
+                self.patterns.join(" or ")
+            ),
+        );
+        Box::new(std::iter::once(error))
+    }
+
+    fn is_valid(&self, instance: &Value) -> bool {
+        instance.as_str().map_or(true, |value| {
+            self.patterns
+                .iter()
+                .any(|pattern| util::matches_date_format(value, pattern))
+        })
+    }
+}
+
+fn date_format_keyword_factory<'a>(
+    _: &'a Map<String, Value>,
+    schema: &'a Value,
+    path: JSONPointer,
+) -> Result<Box<dyn Keyword>, ValidationError<'a>> {
+    let patterns: Option<Vec<String>> = match schema {
+        Value::String(pattern) => Some(vec![pattern.clone()]),
+        Value::Array(patterns) => patterns
+            .iter()
+            .map(|pattern| pattern.as_str().map(str::to_string))
+            .collect(),
+        _ => None,
+    };
+    match patterns {
+        Some(patterns) if !patterns.is_empty() => Ok(Box::new(DateFormatValidator {
+            patterns,
+            schema_path: path,
+        })),
+        _ => Err(ValidationError::custom(
+            JSONPointer::default(),
+            path,
+            schema,
+            "Expected a strftime pattern or an array of strftime patterns",
+        )),
+    }
+}
+
+/// Check that a string has some number of digits followed by a dot followed by exactly 2 digits.
+fn currency_format_checker(s: &str) -> bool {
+    let currency_re: &'static Regex = regex_oncelock!("^(0|([1-9]+[0-9]*))(\\.[0-9]{2})$");
+    currency_re.is_match(s)
+}
+
+/// Check that a string is a local-part@domain.tld email address without whitespace.
+/// Stricter than the built-in "email" format, which only checks for an "@".
+fn email_format_checker(s: &str) -> bool {
+    let email_re: &'static Regex =
+        regex_oncelock!(r"^[^@\s]+@[^@\s.]+(\.[^@\s.]+)*\.[A-Za-z]{2,}$");
+    email_re.is_match(s)
+}
+
+/// Check that a string is a phone number - an optional "+" followed by 7 to 15 digits,
+/// with spaces, dashes, dots and parentheses allowed as separators.
+fn phone_format_checker(s: &str) -> bool {
+    let phone_re: &'static Regex = regex_oncelock!(r"^\+?[0-9 ().-]+$");
+    if !phone_re.is_match(s) {
+        return false;
+    }
+    let digits = s.bytes().filter(u8::is_ascii_digit).count();
+    (7..=15).contains(&digits)
+}
+
+/// how the "format" keyword is validated
+#[derive(Clone, Copy, PartialEq)]
+enum FormatsMode {
+    Strict,
+    Lax,
+    Off,
+}
+
+impl std::str::FromStr for FormatsMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "strict" => Ok(FormatsMode::Strict),
+            "lax" => Ok(FormatsMode::Lax),
+            "off" => Ok(FormatsMode::Off),
+            _ => Err(format!(
+                "Invalid --formats mode: {s}. Valid modes are strict, lax and off."
+            )),
+        }
+    }
+}
+
+/// a named cross-field rule - a jq filter that must return true for a record to be valid
+struct Rule {
+    name:   String,
+    filter: Filter,
+}
+
+/// load the rule definitions (name, jq filter) from a --rules JSON file
+fn load_rule_defs(rules_path: &str) -> CliResult<Vec<(String, String)>> {
+    let rules_json = std::fs::read_to_string(rules_path)
+        .map_err(|e| format!("Cannot read rules file {rules_path}: {e}"))?;
+    let Ok(Value::Object(rules_map)) = serde_json::from_str::<Value>(&rules_json) else {
+        return fail_incorrectusage_clierror!(
+            "Rules file {rules_path} must be a JSON object of rule names to jq filters."
+        );
+    };
+    let mut rule_defs = Vec::with_capacity(rules_map.len());
+    for (name, filter) in rules_map {
+        let Value::String(filter) = filter else {
+            return fail_incorrectusage_clierror!("Rule {name} is not a jq filter string.");
+        };
+        rule_defs.push((name, filter));
+    }
+    // compile the rules once upfront, so we fail early on invalid filters
+    compile_rules(&rule_defs)?;
+    Ok(rule_defs)
+}
+
+/// compile the rule definitions. jaq filters are not Sync, so each Rayon worker compiles
+/// its own copy.
+fn compile_rules(rule_defs: &[(String, String)]) -> CliResult<Vec<Rule>> {
+    let mut rules = Vec::with_capacity(rule_defs.len());
+    for (name, filter) in rule_defs {
+        let mut defs = ParseCtx::new(Vec::new());
+        let (main, errs) = jaq_parse::parse(filter, jaq_parse::main());
+        let Some(main) = main.filter(|_| errs.is_empty()) else {
+            return fail_incorrectusage_clierror!(
+                "Cannot parse rule {name}: {}",
+                errs.iter().map(ToString::to_string).join("; ")
+            );
+        };
+        let filter = defs.compile(main);
+        if !defs.errs.is_empty() {
+            return fail_incorrectusage_clierror!(
+                "Cannot compile rule {name}: {}",
+                defs.errs.iter().map(|(e, _)| e.to_string()).join("; ")
+            );
+        }
+        rules.push(Rule {
+            name: name.clone(),
+            filter,
+        });
+    }
+    Ok(rules)
+}
+
+/// run the rules on a JSON instance, returning the (rule name, error) of failed rules
+fn check_rules(instance: &Value, rules: &[Rule]) -> Vec<(String, String)> {
+    let mut failures = Vec::new();
+    for rule in rules {
+        let inputs = RcIter::new(core::iter::empty());
+        let mut out = rule
+            .filter
+            .run((Ctx::new([], &inputs), Val::from(instance.clone())));
+        let failure = match out.next() {
+            Some(Ok(Val::Bool(true))) => continue,
+            Some(Ok(Val::Bool(false))) => "rule failed".to_string(),
+            Some(Ok(val)) => format!("rule returned {val} instead of a boolean"),
+            Some(Err(e)) => format!("rule error: {e}"),
+            None => "rule returned no value".to_string(),
+        };
+        failures.push((rule.name.clone(), failure));
+    }
+    failures
+}
+
+/// a --regex-rules pattern check - the non-empty values of `column` must match `regex`
+struct RegexRule {
+    column:  String,
+    pattern: String,
+    regex:   Regex,
+}
+
+#[derive(Deserialize)]
+struct RegexRuleRow {
+    column: String,
+    regex:  String,
+}
+
+/// load the column,regex rules of a --regex-rules CSV
+fn load_regex_rules(rules_path: &str) -> CliResult<Vec<RegexRule>> {
+    let mut rdr = Config::new(&Some(rules_path.to_string())).reader()?;
+    let mut regex_rules = Vec::new();
+    for row in rdr.deserialize() {
+        let row: RegexRuleRow = match row {
+            Ok(row) => row,
+            Err(e) => {
+                return fail_incorrectusage_clierror!(
+                    "Regex rules file {rules_path} must be a CSV with column & regex columns: {e}"
+                )
+            },
+        };
+        let regex = match Regex::new(&format!("^(?:{})$", row.regex)) {
+            Ok(regex) => regex,
+            Err(e) => {
+                return fail_incorrectusage_clierror!(
+                    "Invalid regex for column {}: {e}",
+                    row.column
+                )
+            },
+        };
+        regex_rules.push(RegexRule {
+            column: row.column,
+            pattern: row.regex,
+            regex,
+        });
+    }
+    Ok(regex_rules)
+}
+
+/// a --lookup foreign-key check - the values of `column` must be in `values`
+struct Lookup {
+    column:        String,
+    lookup_path:   String,
+    lookup_column: String,
+    values:        AHashSet<Vec<u8>>,
+}
+
+impl Lookup {
+    /// parse a <column>:<lookup CSV path>:<lookup column> spec and load the lookup values.
+    /// The path is everything between the first and the last colon, so it can have colons.
+    fn load(spec: &str) -> CliResult<Lookup> {
+        let (Some(first_colon), Some(last_colon)) = (spec.find(':'), spec.rfind(':')) else {
+            return fail_incorrectusage_clierror!(
+                "Invalid --lookup {spec}. Expected <column>:<lookup CSV>:<lookup column>."
+            );
+        };
+        if first_colon == last_colon {
+            return fail_incorrectusage_clierror!(
+                "Invalid --lookup {spec}. Expected <column>:<lookup CSV>:<lookup column>."
+            );
+        }
+        let column = spec[..first_colon].to_string();
+        let lookup_path = spec[first_colon + 1..last_colon].to_string();
+        let lookup_column = spec[last_colon + 1..].to_string();
+
+        let lookup_config =
+            Config::new(&Some(lookup_path.clone())).select(SelectColumns::parse(&lookup_column)?);
+        let mut rdr = lookup_config.reader()?;
+        let lookup_headers = rdr.byte_headers()?.clone();
+        let selection = lookup_config.selection(&lookup_headers)?;
+        if selection.len() != 1 {
+            return fail_incorrectusage_clierror!(
+                "--lookup {spec} must select exactly one lookup column."
+            );
+        }
+        let lookup_idx = selection[0];
+
+        let mut values = AHashSet::new();
+        let mut record = ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            if let Some(value) = record.get(lookup_idx) {
+                if !values.contains(value) {
+                    values.insert(value.to_vec());
+                }
+            }
+        }
+        Ok(Lookup {
+            column,
+            lookup_path,
+            lookup_column,
+            values,
+        })
+    }
+}
+
+/// a --unique key constraint - no two records may have the same values in `columns`
+struct UniqueKey {
+    name:       String,
+    columns:    Vec<usize>,
+    /// the row number of the first record with each key
+    first_rows: AHashMap<Vec<u8>, u64>,
+}
+
+impl UniqueKey {
+    /// check the key of a record, returning the row number of the first record with the
+    /// same key if it's a duplicate. Records with an empty key column are not checked.
+    fn check(&mut self, record: &ByteRecord, row_number: u64) -> Option<u64> {
+        let mut key = Vec::with_capacity(self.columns.len() * 16);
+        for &idx in &self.columns {
+            let value = record.get(idx).unwrap_or_default();
+            if value.is_empty() {
+                return None;
+            }
+            // length-prefix each value, so the keys of different values can't collide
+            key.extend_from_slice(&value.len().to_le_bytes());
+            key.extend_from_slice(value);
+        }
+        match self.first_rows.entry(key) {
+            Entry::Occupied(first_row) => Some(*first_row.get()),
+            Entry::Vacant(first_row) => {
+                first_row.insert(row_number);
+                None
+            },
+        }
+    }
+
+    /// the key of a record for error messages, e.g. "A1" or ("A1", "3") for composite keys
+    fn key_display(&self, record: &ByteRecord) -> String {
+        let values = self
+            .columns
+            .iter()
+            .map(|&idx| format!("{:?}", String::from_utf8_lossy(&record[idx])))
+            .join(", ");
+        if self.columns.len() == 1 {
+            values
+        } else {
+            format!("({values})")
+        }
+    }
+}
+
+/// how --fix handles a number outside of its column's minimum/maximum
+#[derive(Clone, Copy, PartialEq)]
+enum RangePolicy {
+    Clamp,
+    Null,
+    Keep,
+}
+
+impl std::str::FromStr for RangePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "clamp" => Ok(RangePolicy::Clamp),
+            "null" => Ok(RangePolicy::Null),
+            "keep" => Ok(RangePolicy::Keep),
+            _ => Err(format!(
+                "Invalid --fix-range policy: {s}. Valid policies are clamp, null and keep."
+            )),
+        }
+    }
+}
+
+/// the date "format" of a column that --fix normalizes to
+#[derive(Clone, Copy)]
+enum DateFormat {
+    Date,
+    DateTime,
+}
+
+/// the --fix repairs that apply to a column, from its schema definition
+#[derive(Default)]
+struct ColumnFix {
+    boolean:     bool,
+    integer:     bool,
+    date_format: Option<DateFormat>,
+    enum_values: Vec<String>,
+    minimum:     Option<f64>,
+    maximum:     Option<f64>,
+}
+
+impl ColumnFix {
+    fn new(json_type: &JSONtypes, field_def: &Value) -> ColumnFix {
+        ColumnFix {
+            boolean:     matches!(json_type, JSONtypes::Boolean),
+            integer:     matches!(json_type, JSONtypes::Integer),
+            date_format: match field_def.get("format").and_then(Value::as_str) {
+                Some("date") => Some(DateFormat::Date),
+                Some("date-time") => Some(DateFormat::DateTime),
+                _ => None,
+            },
+            enum_values: field_def
+                .get("enum")
+                .and_then(Value::as_array)
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(ToString::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            minimum:     field_def.get("minimum").and_then(Value::as_f64),
+            maximum:     field_def.get("maximum").and_then(Value::as_f64),
+        }
+    }
+
+    /// repair a value, returning the fixed value and the fixes made if it was changed
+    fn fix(
+        &self,
+        value: &str,
+        range_policy: RangePolicy,
+        prefer_dmy: bool,
+    ) -> Option<(String, Vec<&'static str>)> {
+        let mut fixes = Vec::new();
+        let mut fixed = value.trim().to_string();
+        if fixed.len() != value.len() {
+            fixes.push("trimmed");
+        }
+        if fixed.is_empty() {
+            return if fixes.is_empty() {
+                None
+            } else {
+                Some((fixed, fixes))
+            };
+        }
+
+        if self.boolean {
+            let normalized = match fixed.to_ascii_lowercase().as_str() {
+                "true" | "t" | "yes" | "y" | "1" | "on" => Some("true"),
+                "false" | "f" | "no" | "n" | "0" | "off" => Some("false"),
+                _ => None,
+            };
+            if let Some(normalized) = normalized.filter(|normalized| *normalized != fixed) {
+                fixed = normalized.to_string();
+                fixes.push("normalized boolean");
+            }
+        }
+
+        if self.integer && atoi_simd::parse::<i64>(fixed.as_bytes()).is_err() {
+            // only whole floats that an i64 can represent exactly
+            if let Ok(float) = fixed.parse::<f64>() {
+                if float.fract() == 0.0 && float.abs() < 9.0e15 {
+                    fixed = (float as i64).to_string();
+                    fixes.push("normalized integer");
+                }
+            }
+        }
+
+        let normalized_date = match self.date_format {
+            Some(DateFormat::Date) if NaiveDate::parse_from_str(&fixed, "%Y-%m-%d").is_err() => {
+                parse_with_preference_and_timezone(&fixed, prefer_dmy, &Utc)
+                    .ok()
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+            },
+            Some(DateFormat::DateTime) if DateTime::parse_from_rfc3339(&fixed).is_err() => {
+                parse_with_preference_and_timezone(&fixed, prefer_dmy, &Utc)
+                    .ok()
+                    .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            },
+            _ => None,
+        };
+        if let Some(normalized_date) = normalized_date {
+            fixed = normalized_date;
+            fixes.push("normalized date");
+        }
+
+        if !self.enum_values.is_empty() && !self.enum_values.contains(&fixed) {
+            if let Some(enum_value) = self
+                .enum_values
+                .iter()
+                .find(|enum_value| enum_value.eq_ignore_ascii_case(&fixed))
+            {
+                fixed.clone_from(enum_value);
+                fixes.push("matched enum case");
+            }
+        }
+
+        if range_policy != RangePolicy::Keep {
+            if let Ok(number) = fixed.parse::<f64>() {
+                let bound = if self.minimum.is_some_and(|minimum| number < minimum) {
+                    self.minimum
+                } else if self.maximum.is_some_and(|maximum| number > maximum) {
+                    self.maximum
+                } else {
+                    None
+                };
+                if let Some(bound) = bound {
+                    if range_policy == RangePolicy::Clamp {
+                        fixed = bound.to_string();
+                        fixes.push("clamped to range");
+                    } else {
+                        fixed.clear();
+                        fixes.push("nulled out of range value");
+                    }
+                }
+            }
+        }
+
+        if fixes.is_empty() {
+            None
+        } else {
+            Some((fixed, fixes))
+        }
+    }
+}
+
+/// repair the fixable values of a record for --fix, adding the values changed to fixed_values
+fn fix_record(
+    record: &mut ByteRecord,
+    column_fixes: &[ColumnFix],
+    row_number: u64,
+    range_policy: RangePolicy,
+    prefer_dmy: bool,
+    fixed_values: &mut Vec<FixedValue>,
+) {
+    let first_fixed = fixed_values.len();
+    for (column, column_fix) in column_fixes.iter().enumerate() {
+        // non UTF-8 values are left as is, and are reported by the validation
+        let Some(Ok(value)) = record.get(column).map(simdutf8::basic::from_utf8) else {
+            continue;
+        };
+        if let Some((fixed, fixes)) = column_fix.fix(value, range_policy, prefer_dmy) {
+            fixed_values.push(FixedValue {
+                row_number,
+                column,
+                original: value.to_string(),
+                fixed,
+                fixes,
+            });
+        }
+    }
+    if fixed_values.len() == first_fixed {
+        return;
+    }
+
+    let mut fixed_record = ByteRecord::with_capacity(record.as_slice().len(), record.len());
+    let mut record_fixes = fixed_values[first_fixed..].iter().peekable();
+    for (column, value) in record.iter().enumerate() {
+        match record_fixes.next_if(|fixed_value| fixed_value.column == column) {
+            Some(fixed_value) => fixed_record.push_field(fixed_value.fixed.as_bytes()),
+            None => fixed_record.push_field(value),
+        }
+    }
+    *record = fixed_record;
+}
+
+/// a value changed by --fix, for the fixes.tsv log
+struct FixedValue {
+    row_number: u64,
+    column:     usize,
+    original:   String,
+    fixed:      String,
+    fixes:      Vec<&'static str>,
+}
+
+/// the row-level checks done on top of JSON Schema validation
+struct RowChecks {
+    rule_defs:   Vec<(String, String)>,
+    lookups:     Vec<Lookup>,
+    regex_rules: Vec<RegexRule>,
+}
+
+/// compile a JSON Schema with qsv's custom formats & keywords
+fn compile_schema<'a>(
+    schema_json: &'a Value,
+    formats_mode: FormatsMode,
+) -> Result<JSONSchema, ValidationError<'a>> {
+    JSONSchema::options()
+        .with_format("currency", currency_format_checker)
+        .with_format("email", email_format_checker)
+        .with_format("phone", phone_format_checker)
+        .with_keyword("ascii-keys", custom_object_type_factory)
+        .with_keyword("x-date-format", date_format_keyword_factory)
+        .should_validate_formats(formats_mode != FormatsMode::Off)
+        .should_ignore_unknown_formats(formats_mode != FormatsMode::Strict)
+        .compile(schema_json)
+}
+
+/// Adapt the conditional requirements of a schema to CSV records, which have all the
+/// columns with null for the empty values, so `required` alone can't require a value:
+///  * the columns `required` in subschemas (e.g. in if/then/else & oneOf) must be non-null.
+///  * `dependentRequired` is rewritten as if/then subschemas in `allOf`, so its columns must
+///    be non-null when their dependent column is non-null.
+/// Returns the labels of the subschemas with requirements, keyed by their schema location,
+/// to describe their errors.
+fn adapt_conditional_requirements(schema: &mut Value) -> Vec<(String, String)> {
+    // the non-null types of each column
+    let mut column_types: AHashMap<String, Value> = AHashMap::new();
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (column, field_def) in properties {
+            let types: Vec<Value> = match resolve_local_ref(schema, field_def).get("type") {
+                Some(Value::Array(types)) => {
+                    types.iter().filter(|t| *t != "null").cloned().collect()
+                },
+                Some(json_type) if json_type != "null" => vec![json_type.clone()],
+                _ => Vec::new(),
+            };
+            if !types.is_empty() {
+                column_types.insert(column.clone(), Value::Array(types));
+            }
+        }
+    }
+
+    let mut labels: Vec<(String, String)> = Vec::new();
+    adapt_subschema(schema, "", true, &column_types, &mut labels);
+    labels
+}
+
+fn adapt_subschema(
+    subschema: &mut Value,
+    location: &str,
+    is_root: bool,
+    column_types: &AHashMap<String, Value>,
+    labels: &mut Vec<(String, String)>,
+) {
+    let Some(subschema) = subschema.as_object_mut() else {
+        return;
+    };
+    let non_null_type = |column: &str| {
+        column_types
+            .get(column)
+            .cloned()
+            .unwrap_or_else(|| json!(["string", "number", "boolean"]))
+    };
+
+    if let Some(Value::Object(dependent_required)) = subschema.remove("dependentRequired") {
+        if let Some(all_of) = subschema
+            .entry("allOf")
+            .or_insert_with(|| json!([]))
+            .as_array_mut()
+        {
+            for (column, required) in dependent_required {
+                labels.push((
+                    format!("{location}/allOf/{}/then", all_of.len()),
+                    format!("when \"{column}\" is not empty (dependentRequired)"),
+                ));
+                all_of.push(json!({
+                    "if": {"properties": {&column: {"type": non_null_type(&column)}}},
+                    "then": {"required": required}
+                }));
+            }
+        }
+    }
+
+    if !is_root {
+        let required = subschema.get("required").and_then(Value::as_array).cloned();
+        if let Some(required) = required {
+            if let Some(properties) = subschema
+                .entry("properties")
+                .or_insert_with(|| json!({}))
+                .as_object_mut()
+            {
+                for column in required.iter().filter_map(Value::as_str) {
+                    if let Some(field_def) = properties
+                        .entry(column)
+                        .or_insert_with(|| json!({}))
+                        .as_object_mut()
+                    {
+                        field_def
+                            .entry("type")
+                            .or_insert_with(|| non_null_type(column));
+                    }
+                }
+            }
+            if !labels
+                .iter()
+                .any(|(label_location, _)| label_location == location)
+            {
+                labels.push((location.to_string(), format!("by {location}")));
+            }
+        }
+    }
+
+    for (keyword, value) in subschema.iter_mut() {
+        match keyword.as_str() {
+            "if" | "then" | "else" | "not" => {
+                let location = format!("{location}/{keyword}");
+                adapt_subschema(value, &location, false, column_types, labels);
+            },
+            "allOf" | "anyOf" | "oneOf" => {
+                for (i, item) in value.as_array_mut().into_iter().flatten().enumerate() {
+                    let location = format!("{location}/{keyword}/{i}");
+                    adapt_subschema(item, &location, false, column_types, labels);
+                }
+            },
+            "dependentSchemas" => {
+                for (column, item) in value.as_object_mut().into_iter().flatten() {
+                    let location = format!("{location}/{keyword}/{column}");
+                    adapt_subschema(item, &location, false, column_types, labels);
+                }
+            },
+            _ => {},
+        }
     }
 }
 
-fn custom_object_type_factory<'a>(
-    _: &'a Map<String, Value>,
-    schema: &'a Value,
-    path: JSONPointer,
-) -> Result<Box<dyn Keyword>, ValidationError<'a>> {
-    const EXPECTED: &str = "ascii-keys";
-    if schema.as_str().map_or(true, |key| key != EXPECTED) {
-        Err(ValidationError::custom(
-            JSONPointer::default(),
-            path,
-            schema,
-            "Expected 'ascii-keys'",
-        ))
-    } else {
-        Ok(Box::new(IsAsciiValidator))
-    }
+/// Validates CSV records against a JSON Schema, for commands that check the CSV they produce
+/// (e.g. shareprep). Uses the same type casting & custom formats as validate does.
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub(crate) struct RecordValidator {
+    schema_compiled: JSONSchema,
+    header_types:    Vec<(String, JSONtypes)>,
 }
 
-/// Check that a string has some number of digits followed by a dot followed by exactly 2 digits.
-fn currency_format_checker(s: &str) -> bool {
-    let currency_re: &'static Regex = regex_oncelock!("^(0|([1-9]+[0-9]*))(\\.[0-9]{2})$");
-    currency_re.is_match(s)
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+impl RecordValidator {
+    pub(crate) fn new(schema_json: &Value, headers: &ByteRecord) -> CliResult<Self> {
+        NULL_TYPE.get_or_init(|| Value::String("null".to_string()));
+        let mut schema_json = schema_json.clone();
+        let _ = REQUIREMENT_LABELS.set(adapt_conditional_requirements(&mut schema_json));
+        let schema_compiled = match compile_schema(&schema_json, FormatsMode::Lax) {
+            Ok(schema) => schema,
+            Err(e) => {
+                return fail_invalidschema_clierror!("Cannot compile schema json. error: {e}")
+            },
+        };
+        Ok(Self {
+            schema_compiled,
+            header_types: get_json_types(headers, &schema_json)?,
+        })
+    }
+
+    /// the (field, error) pairs of an invalid record, or None if the record is valid
+    pub(crate) fn validate(&self, record: &ByteRecord) -> Option<Vec<(String, String)>> {
+        match to_json_instance(&self.header_types, self.header_types.len(), record) {
+            Ok(instance) => validate_json_instance(&instance, &self.schema_compiled),
+            Err(e) => Some(vec![("<RECORD>".to_string(), e.to_string())]),
+        }
+    }
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    // validate's I/O errors have their own exit code, so scripts can tell them apart
+    // from invalid CSVs. Broken pipes are still reported as such.
+    validate(argv).map_err(|err| match err {
+        CliError::Io(err) if err.kind() != io::ErrorKind::BrokenPipe => CliError::ValidateIo(err),
+        err => err,
+    })
+}
+
+fn validate(argv: &[&str]) -> CliResult<()> {
+    let mut args: Args = util::get_args(USAGE, argv)?;
+    args.flag_seed = util::rng_seed(args.flag_seed)?;
 
     TIMEOUT_SECS.store(
         util::timeout_secs(args.flag_timeout)? as u16,
         Ordering::Relaxed,
     );
 
-    let mut rconfig = Config::new(&args.arg_input).no_headers(args.flag_no_headers);
+    let mut rconfig =
+        Config::new(&args.arg_input.first().cloned()).no_headers(args.flag_no_headers);
 
     if args.flag_delimiter.is_some() {
         rconfig = rconfig.delimiter(args.flag_delimiter);
     }
 
-    let mut rdr = rconfig.reader()?;
+    if let Some(level) = args.flag_strict_level {
+        if !(1..=3).contains(&level) {
+            return fail_incorrectusage_clierror!("--strict-level must be 1, 2 or 3.");
+        }
+        if args.validates_records() {
+            return fail_incorrectusage_clierror!(
+                "--strict-level can only be used when validating without a JSON Schema."
+            );
+        }
+    }
+
+    if !args.validates_records() && (args.flag_slice.is_some() || args.flag_sample.is_some()) {
+        return fail_incorrectusage_clierror!(
+            "--slice and --sample can only be used when validating with a JSON Schema."
+        );
+    }
+    if args.flag_sample == Some(0) {
+        return fail_incorrectusage_clierror!("--sample must be greater than 0.");
+    }
+
+    // if no JSON Schema or regex rules supplied, only let csv reader RFC4180-validate csv file
+    if !args.validates_records() {
+        if let Some(level) = args.flag_strict_level {
+            return lint_csv(&rconfig, level, &args);
+        }
+
+        let mut rdr = rconfig.reader()?;
 
-    // if no JSON Schema supplied, only let csv reader RFC4180-validate csv file
-    if args.arg_json_schema.is_none() {
         // just read csv file and let csv reader report problems
         // since we're using csv::StringRecord, this will also detect non-utf8 sequences
 
@@ -324,7 +1703,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         } else {
                             header_error.to_string()
                         };
-                        return fail_encoding_clierror!("{json_error}");
+                        return fail_malformedcsv_clierror!("{json_error}");
                     }
                     // we're not returning a JSON error, so we can use
                     // a user-friendly error message with suggestions
@@ -337,7 +1716,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         );
                     }
                     // its not a UTF-8 error, report a generic header validation error
-                    return fail_clierror!("Header Validation error: {e}.");
+                    return fail_malformedcsv_clierror!("Header Validation error: {e}.");
                 },
             }
         }
@@ -356,6 +1735,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         'rfc4180_check: loop {
             result = rdr.read_byte_record(&mut record);
             if let Err(e) = result {
+                if e.is_io_error() {
+                    return fail!(e);
+                }
                 // read_byte_record() does not validate utf8, so we know this is not a utf8 error
                 if flag_json {
                     // we're returning a JSON error, so we have more machine-friendly details
@@ -377,7 +1759,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         validation_error.to_string()
                     };
 
-                    return fail!(json_error);
+                    return fail_malformedcsv_clierror!("{json_error}");
                 }
 
                 // we're not returning a JSON error, so we can use a
@@ -388,11 +1770,13 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     pos: _,
                 } = e.kind()
                 {
-                    return fail_clierror!(
+                    return fail_malformedcsv_clierror!(
                         "Validation error: {e}.\nUse `qsv fixlengths` to fix record length issues."
                     );
                 }
-                return fail_clierror!("Validation error: {e}.\nLast valid record: {record_idx}");
+                return fail_malformedcsv_clierror!(
+                    "Validation error: {e}.\nLast valid record: {record_idx}"
+                );
             }
 
             // use SIMD accelerated UTF-8 validation, validate the entire record in one go
@@ -467,255 +1851,1133 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         return Ok(());
     }
 
-    // if we're here, we're validating with a JSON Schema
+    // if we're here, we're validating with a JSON Schema and/or regex rules
+    if let Some(ref report) = args.flag_report {
+        if let Err(e) = report.parse::<ReportFormat>() {
+            return fail_incorrectusage_clierror!("{e}");
+        }
+    }
+
     // JSONSchema validation requires headers
     if args.flag_no_headers {
         return fail_clierror!("Cannot validate CSV without headers against a JSON Schema.");
     }
 
+    let formats_mode: FormatsMode = match args.flag_formats.parse() {
+        Ok(mode) => mode,
+        Err(e) => return fail_incorrectusage_clierror!("{e}"),
+    };
+    if let Err(e) = args.flag_error_format.parse::<ErrorFormat>() {
+        return fail_incorrectusage_clierror!("{e}");
+    }
+
+    if args.flag_fix.is_some() {
+        if let Err(e) = args.flag_fix_range.parse::<RangePolicy>() {
+            return fail_incorrectusage_clierror!("{e}");
+        }
+        // the fixed copy has all the rows, so validation can't stop early
+        if args.max_errors() > 0 {
+            return fail_incorrectusage_clierror!(
+                "--fix cannot be used with --fail-fast or --max-errors."
+            );
+        }
+        if args.flag_slice.is_some() || args.flag_sample.is_some() {
+            return fail_incorrectusage_clierror!("--fix cannot be used with --slice or --sample.");
+        }
+    }
+
+    let cache_dir = if args.flag_no_cache {
+        None
+    } else {
+        Some(schema_cache_dir(&args.flag_cache_dir))
+    };
+
+    // parse and compile supplied JSON Schema. With only --regex-rules, all the columns are
+    // validated as strings against a schema that accepts any record.
+    let (schema_json, schema_compiled): (Value, JSONSchema) = match args.arg_json_schema {
+        Some(ref json_schema) => match load_json(json_schema, cache_dir.as_deref()) {
+            Ok(s) => {
+                // parse JSON string
+                match serde_json::from_str(&s) {
+                    Ok(mut json) => {
+                        let _ = REQUIREMENT_LABELS.set(adapt_conditional_requirements(&mut json));
+                        // compile JSON Schema
+                        match compile_schema(&json, formats_mode) {
+                            Ok(schema) => (json, schema),
+                            Err(e) => {
+                                return fail_invalidschema_clierror!(
+                                    "Cannot compile schema json. error: {e}"
+                                );
+                            },
+                        }
+                    },
+                    Err(e) => {
+                        return fail_invalidschema_clierror!(
+                            "Unable to parse schema json. error: {e}"
+                        );
+                    },
+                }
+            },
+            // the schema couldn't be downloaded or read
+            Err(e) if json_schema.to_lowercase().starts_with("http") => {
+                return fail!(CliError::Network(format!(
+                    "Unable to retrieve json. error: {e}"
+                )));
+            },
+            Err(e) => {
+                return fail!(io::Error::other(format!(
+                    "Unable to retrieve json. error: {e}"
+                )));
+            },
+        },
+        None => {
+            let json = json!({"type": "object", "properties": {}});
+            match compile_schema(&json, formats_mode) {
+                Ok(schema) => (json, schema),
+                Err(e) => {
+                    return fail_invalidschema_clierror!("Cannot compile schema json. error: {e}")
+                },
+            }
+        },
+    };
+
+    if log::log_enabled!(log::Level::Debug) {
+        // only log if debug is enabled
+        // as it can be quite large and expensive to deserialize the schema
+        debug!("schema json: {:?}", &schema_json);
+    }
+
+    // set this once, as this is used repeatedly in a hot loop
+    NULL_TYPE.get_or_init(|| Value::String("null".to_string()));
+
+    let row_checks = RowChecks {
+        rule_defs: match args.flag_rules {
+            Some(ref rules_path) => load_rule_defs(rules_path)?,
+            None => Vec::new(),
+        },
+        lookups:   args
+            .flag_lookup
+            .iter()
+            .map(String::as_str)
+            .map(Lookup::load)
+            .collect::<CliResult<Vec<_>>>()?,
+        regex_rules: match args.flag_regex_rules {
+            Some(ref rules_path) => load_regex_rules(rules_path)?,
+            None => Vec::new(),
+        },
+    };
+
+    // set RAYON_NUM_THREADS
+    util::njobs(args.flag_jobs);
+
+    // batch mode - validate several files, or all the files in a directory or an .infile-list
+    let is_batch = match args.arg_input.as_slice() {
+        [input] => {
+            let input_path = Path::new(input);
+            input_path.is_dir()
+                || input_path
+                    .extension()
+                    .and_then(std::ffi::OsStr::to_str)
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("infile-list"))
+        },
+        inputs => inputs.len() > 1,
+    };
+    if is_batch {
+        if args.flag_fix.is_some() {
+            return fail_incorrectusage_clierror!("--fix cannot be used in batch mode.");
+        }
+        return validate_batch(&args, &schema_json, &schema_compiled, &row_checks);
+    }
+
+    // the invalid records are split out in a second pass over the input, and --batch 0 &
+    // --sample count its rows first, so stdin is spooled - unless the second pass reads the
+    // --fix file instead
+    let _stdin_spool = if rconfig.is_stdin() && (args.flag_fix.is_none() || args.flag_batch == 0) {
+        let (spooled_rconfig, spool_dir) = util::spool_stdin(&rconfig)?;
+        rconfig = spooled_rconfig;
+        Some(spool_dir)
+    } else {
+        None
+    };
+
+    let selection = RowSelection::new(&args, &rconfig)?;
+
     // prep progress bar
-    #[cfg(any(feature = "feature_capable", feature = "lite"))]
-    let progress = ProgressBar::with_draw_target(None, ProgressDrawTarget::stderr_with_hz(5));
+    let progress = ProgressBar::hidden();
 
     #[cfg(any(feature = "feature_capable", feature = "lite"))]
     let show_progress =
         (args.flag_progressbar || util::get_envvar_flag("QSV_PROGRESSBAR")) && !rconfig.is_stdin();
 
+    // counting the records of an unindexed CSV takes another pass over it, so its progress
+    // is in bytes read. Its rows are counted anyway for --sample, and the bytes read aren't
+    // a measure of progress when only a --slice of the file is validated, or when the
+    // file is snappy compressed.
+    let progress_unit = if rconfig.indexed().unwrap_or(None).is_some()
+        || args.flag_slice.is_some()
+        || args.flag_sample.is_some()
+        || rconfig
+            .path
+            .as_ref()
+            .is_some_and(|path| path.extension().is_some_and(|ext| ext == "sz"))
+    {
+        ProgressUnit::Records
+    } else {
+        ProgressUnit::Bytes
+    };
+
+    #[cfg(any(feature = "feature_capable", feature = "lite"))]
+    if show_progress {
+        progress.set_draw_target(ProgressDrawTarget::stderr_with_hz(5));
+        if progress_unit == ProgressUnit::Records {
+            // for full row count, prevent CSV reader from aborting on inconsistent column count
+            rconfig = rconfig.flexible(true);
+            let record_count = util::count_rows(&rconfig)?;
+            rconfig = rconfig.flexible(false);
+            util::prep_progress(&progress, selection.len(record_count));
+        } else {
+            // safety: the input is a file, as stdin was spooled to one
+            let file_size = std::fs::metadata(rconfig.path.as_ref().unwrap())?.len();
+            util::prep_progress_bytes(&progress, file_size);
+        }
+    }
+
+    let input_path = args
+        .arg_input
+        .first()
+        .cloned()
+        .filter(|input| input != "-")
+        .unwrap_or_else(|| "stdin.csv".to_string());
+
+    let outcome = validate_file(
+        &args,
+        &rconfig,
+        &input_path,
+        &schema_json,
+        &schema_compiled,
+        &row_checks,
+        &selection,
+        &progress,
+        progress_unit,
+    )?;
+    let row_number = outcome.row_count;
+    let invalid_count = outcome.invalid_count;
+
     #[cfg(any(feature = "feature_capable", feature = "lite"))]
     if show_progress {
-        // for full row count, prevent CSV reader from aborting on inconsistent column count
-        rconfig = rconfig.flexible(true);
-        let record_count = util::count_rows(&rconfig)?;
-        rconfig = rconfig.flexible(false);
-        util::prep_progress(&progress, record_count);
+        progress.set_message(format!(" validated {} records.", HumanCount(row_number)));
+        if progress_unit == ProgressUnit::Records {
+            util::finish_progress(&progress);
+        } else {
+            util::finish_progress_bytes(&progress);
+        }
+    }
+
+    if invalid_count == 0 {
+        // no invalid records found
+        // see if we need to pass all valid records to output
+        if let Some(valid_output) = args.flag_valid_output {
+            // pass all valid records to output and return exit code 1
+            let valid_path = if valid_output == "-" {
+                // write to stdout
+                None
+            } else {
+                Some(valid_output)
+            };
+
+            let mut valid_wtr = Config::new(&valid_path).writer()?;
+            valid_wtr.write_byte_record(&outcome.headers)?;
+
+            // with --fix, pass the fixed records. --fix can't be used with --slice/--sample,
+            // so all of its records were checked
+            let mut rdr = match args.flag_fix {
+                Some(ref fix_path) => Config::new(&Some(fix_path.clone())).reader()?,
+                None => rconfig.reader()?,
+            };
+            let mut filter = selection.filter();
+            let mut record = csv::ByteRecord::new();
+            let mut record_number: u64 = 0;
+            while rdr.read_byte_record(&mut record)? {
+                record_number += 1;
+                match filter.check(record_number) {
+                    Some(true) => valid_wtr.write_byte_record(&record)?,
+                    Some(false) => {},
+                    None => break,
+                }
+            }
+            valid_wtr.flush()?;
+            // return 1 as an exitcode and the number of valid rows to stderr
+            return fail_clierror!("{row_number}");
+        }
     } else {
-        progress.set_draw_target(ProgressDrawTarget::hidden());
+        // if valid records are going to stdout, don't pollute it with our info message
+        if args.flag_valid_output.as_deref() == Some("-") {
+            winfo!("Wrote invalid/valid/error files.");
+        } else {
+            woutinfo!("Wrote invalid/valid/error files.");
+        }
+
+        // done with validation; print output
+        let max_errors = args.max_errors();
+        let fail_fast_msg = if args.flag_fail_fast {
+            format!(
+                "fail-fast enabled. stopped after row {}.\n",
+                HumanCount(row_number)
+            )
+        } else if max_errors > 0 && invalid_count >= max_errors {
+            format!(
+                "max-errors of {} reached. stopped after row {}.\n",
+                HumanCount(max_errors),
+                HumanCount(row_number)
+            )
+        } else {
+            String::new()
+        };
+
+        return fail_clierror!(
+            "{fail_fast_msg}{} out of {} records invalid.",
+            HumanCount(invalid_count),
+            HumanCount(row_number)
+        );
+    }
+
+    if !args.flag_quiet {
+        winfo!("All {} records valid.", HumanCount(row_number));
+    }
+    Ok(())
+}
+
+/// what the --progressbar counts
+#[derive(Clone, Copy, PartialEq)]
+enum ProgressUnit {
+    Records,
+    Bytes,
+}
+
+/// the rows to validate with --slice and --sample, by 1-based row number
+struct RowSelection {
+    first:  u64,
+    last:   u64,
+    // the --sample row numbers in ascending order. None selects all the rows in first..=last
+    sample: Option<Vec<u64>>,
+}
+
+impl RowSelection {
+    fn new(args: &Args, rconfig: &Config) -> CliResult<Self> {
+        let (first, last) = match args.flag_slice {
+            Some(ref slice) => parse_slice(slice)?,
+            None => (1, u64::MAX),
+        };
+
+        let sample = match args.flag_sample {
+            Some(sample_size) => {
+                // for full row count, prevent CSV reader from aborting on inconsistent column count
+                let row_count = util::count_rows(&rconfig.clone().flexible(true))?;
+                let candidates = last.min(row_count).saturating_sub(first - 1);
+                if sample_size >= candidates {
+                    None
+                } else {
+                    let mut rng: StdRng = match args.flag_seed {
+                        None => StdRng::from_rng(rand::thread_rng()).unwrap(),
+                        Some(seed) => StdRng::seed_from_u64(seed), //DevSkim: ignore DS148264
+                    };
+                    let mut rows: Vec<u64> = rand::seq::index::sample(
+                        &mut rng,
+                        candidates as usize,
+                        sample_size as usize,
+                    )
+                            .into_iter()
+                            .map(|idx| first + idx as u64)
+                            .collect();
+                    rows.sort_unstable();
+                    Some(rows)
+                }
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            first,
+            last,
+            sample,
+        })
+    }
+
+    /// the number of rows selected in a CSV with row_count rows
+    fn len(&self, row_count: u64) -> u64 {
+        match self.sample {
+            Some(ref rows) => rows.len() as u64,
+            None => self.last.min(row_count).saturating_sub(self.first - 1),
+        }
+    }
+
+    fn filter(&self) -> RowFilter<'_> {
+        RowFilter {
+            selection:   self,
+            next_sample: 0,
+        }
+    }
+}
+
+/// goes through the rows of a CSV in order, telling which ones are selected
+struct RowFilter<'a> {
+    selection:   &'a RowSelection,
+    next_sample: usize,
+}
+
+impl RowFilter<'_> {
+    /// whether the row is selected, or None if none of the next rows are
+    fn check(&mut self, row_number: u64) -> Option<bool> {
+        if row_number > self.selection.last {
+            return None;
+        }
+        match self.selection.sample {
+            Some(ref rows) => {
+                let next_row = *rows.get(self.next_sample)?;
+                if row_number == next_row {
+                    self.next_sample += 1;
+                }
+                Some(row_number == next_row)
+            },
+            None => Some(row_number >= self.selection.first),
+        }
+    }
+}
+
+/// parse a --slice range - start..end, start.. or ..end - into its first & last row numbers
+fn parse_slice(slice: &str) -> CliResult<(u64, u64)> {
+    let parsed = slice.split_once("..").and_then(|(start, end)| {
+        let first = if start.trim().is_empty() {
+            1
+        } else {
+            start.trim().parse().ok()?
+        };
+        let last = if end.trim().is_empty() {
+            u64::MAX
+        } else {
+            end.trim().parse().ok()?
+        };
+        (first >= 1 && first <= last).then_some((first, last))
+    });
+    match parsed {
+        Some(range) => Ok(range),
+        None => fail_incorrectusage_clierror!(
+            "Invalid --slice: {slice}. Use start..end, start.. or ..end, with 1-based row \
+             numbers and start <= end."
+        ),
+    }
+}
+
+/// the outcome of validating a CSV file against a JSON Schema
+struct ValidationOutcome {
+    headers:       ByteRecord,
+    row_count:     u64,
+    invalid_count: u64,
+}
+
+/// the number of top offending values listed per column in the --report
+const REPORT_TOP_VALUES: usize = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+enum ReportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(ReportFormat::Json),
+            "csv" => Ok(ReportFormat::Csv),
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            _ => Err(format!(
+                "Invalid --report format: {s}. Valid formats are json, csv and markdown."
+            )),
+        }
+    }
+}
+
+impl ReportFormat {
+    const fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Csv => "csv",
+            ReportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// the validation errors of a column, for the --report
+#[derive(Default)]
+struct ColumnErrors {
+    errors: u64,
+    values: AHashMap<String, u64>,
+}
+
+impl ColumnErrors {
+    /// the most frequent offending values, ties broken by value
+    fn top_values(&self) -> Vec<(&str, u64)> {
+        let mut top: Vec<(&str, u64)> = self
+            .values
+            .iter()
+            .map(|(value, count)| (value.as_str(), *count))
+            .collect();
+        top.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        top.truncate(REPORT_TOP_VALUES);
+        top
+    }
+}
+
+/// aggregate validation results for the --report
+struct ValidationReport {
+    input:         String,
+    rows_checked:  u64,
+    rows_failed:   u64,
+    duration_secs: f64,
+    // in order of first error
+    columns:       IndexMap<String, ColumnErrors>,
+}
+
+impl ValidationReport {
+    /// tally the errors of an invalid record, given its validation error messages
+    /// in the `row_number<TAB>field<TAB>error` format of the error report
+    fn tally(
+        &mut self,
+        validation_error_msg: &str,
+        record: &ByteRecord,
+        header_idx: &AHashMap<&str, usize>,
+    ) {
+        for error_line in validation_error_msg.lines() {
+            let field = error_line.split('\t').nth(1).unwrap_or_default();
+            let field = if field.is_empty() { "<RECORD>" } else { field };
+            let column_errors = self.columns.entry(field.to_string()).or_default();
+            column_errors.errors += 1;
+            if let Some(value) = header_idx.get(field).and_then(|idx| record.get(*idx)) {
+                *column_errors
+                    .values
+                    .entry(String::from_utf8_lossy(value).to_string())
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
+    fn write(&self, format: ReportFormat) -> CliResult<()> {
+        let report_path = format!("{}.validation-report.{}", self.input, format.extension());
+        match format {
+            ReportFormat::Json => {
+                let columns: Vec<Value> = self
+                    .columns
+                    .iter()
+                    .map(|(field, column_errors)| {
+                        json!({
+                            "field": field,
+                            "errors": column_errors.errors,
+                            "top_values": column_errors
+                                .top_values()
+                                .iter()
+                                .map(|(value, count)| json!({"value": value, "count": count}))
+                                .collect::<Vec<Value>>(),
+                        })
+                    })
+                    .collect();
+                let report = json!({
+                    "input": self.input,
+                    "rows_checked": self.rows_checked,
+                    "rows_failed": self.rows_failed,
+                    "duration_secs": self.duration_secs,
+                    "columns": columns,
+                });
+                std::fs::write(report_path, serde_json::to_string_pretty(&report)?)?;
+            },
+            ReportFormat::Csv => {
+                // one row per column with errors, with the summary repeated on each row
+                let mut wtr = Config::new(&Some(report_path)).writer()?;
+                wtr.write_record([
+                    "input",
+                    "rows_checked",
+                    "rows_failed",
+                    "duration_secs",
+                    "field",
+                    "errors",
+                    "top_values",
+                ])?;
+                let rows_checked = self.rows_checked.to_string();
+                let rows_failed = self.rows_failed.to_string();
+                let duration_secs = format!("{:.3}", self.duration_secs);
+                if self.columns.is_empty() {
+                    wtr.write_record([
+                        self.input.as_str(),
+                        rows_checked.as_str(),
+                        rows_failed.as_str(),
+                        duration_secs.as_str(),
+                        "",
+                        "0",
+                        "",
+                    ])?;
+                }
+                for (field, column_errors) in &self.columns {
+                    let top_values = column_errors
+                        .top_values()
+                        .iter()
+                        .map(|(value, count)| format!("{value} ({count})"))
+                        .join("; ");
+                    wtr.write_record([
+                        self.input.as_str(),
+                        rows_checked.as_str(),
+                        rows_failed.as_str(),
+                        duration_secs.as_str(),
+                        field.as_str(),
+                        column_errors.errors.to_string().as_str(),
+                        top_values.as_str(),
+                    ])?;
+                }
+                wtr.flush()?;
+            },
+            ReportFormat::Markdown => {
+                let mut md = format!(
+                    "# Validation report: {}\n\n| rows checked | rows failed | duration (secs) \
+                     |\n|---:|---:|---:|\n| {} | {} | {:.3} |\n",
+                    self.input, self.rows_checked, self.rows_failed, self.duration_secs
+                );
+                if !self.columns.is_empty() {
+                    md.push_str("\n| field | errors | top offending values |\n|---|---:|---|\n");
+                    for (field, column_errors) in &self.columns {
+                        let top_values = column_errors
+                            .top_values()
+                            .iter()
+                            .map(|(value, count)| {
+                                format!("`{}` ({count})", value.replace('|', "\\|"))
+                            })
+                            .join(", ");
+                        md.push_str(&format!(
+                            "| {field} | {} | {top_values} |\n",
+                            column_errors.errors
+                        ));
+                    }
+                }
+                std::fs::write(report_path, md)?;
+            },
+        }
+        Ok(())
     }
+}
 
-    let headers = rdr.byte_headers()?.clone();
+/// validate a CSV file against the compiled JSON Schema.
+/// If there are invalid records, the invalid/valid/error files are written next to input_path.
+fn validate_file(
+    args: &Args,
+    rconfig: &Config,
+    input_path: &str,
+    schema_json: &Value,
+    schema_compiled: &JSONSchema,
+    row_checks: &RowChecks,
+    selection: &RowSelection,
+    progress: &ProgressBar,
+    progress_unit: ProgressUnit,
+) -> CliResult<ValidationOutcome> {
+    let start_time = Instant::now();
+    let mut rdr = rconfig.reader()?;
+    let headers = match rdr.byte_headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) if e.is_io_error() => return fail!(e),
+        Err(e) => return fail_malformedcsv_clierror!("Header Validation error: {e}."),
+    };
     let header_len = headers.len();
 
-    // parse and compile supplied JSON Schema
-    let (schema_json, schema_compiled): (Value, JSONSchema) =
-        match load_json(&args.arg_json_schema.unwrap()) {
-            Ok(s) => {
-                // parse JSON string
-                match serde_json::from_str(&s) {
-                    Ok(json) => {
-                        // compile JSON Schema
-                        match JSONSchema::options()
-                            .with_format("currency", currency_format_checker)
-                            .with_keyword("ascii-keys", custom_object_type_factory)
-                            .compile(&json)
-                        {
-                            Ok(schema) => (json, schema),
-                            Err(e) => {
-                                return fail_clierror!("Cannot compile schema json. error: {e}");
-                            },
-                        }
-                    },
-                    Err(e) => {
-                        return fail_clierror!("Unable to parse schema json. error: {e}");
-                    },
-                }
-            },
-            Err(e) => {
-                return fail_clierror!("Unable to retrieve json. error: {e}");
-            },
+    // get JSON types for each column in CSV file
+    let header_types = get_json_types(&headers, schema_json)?;
+
+    let error_format: ErrorFormat = args.flag_error_format.parse()?;
+
+    // only tally errors per column if we need to write a report
+    let report_format = args
+        .flag_report
+        .as_deref()
+        .map(str::parse::<ReportFormat>)
+        .transpose()?;
+    let mut report = report_format.map(|_| ValidationReport {
+        input:         input_path.to_string(),
+        rows_checked:  0,
+        rows_failed:   0,
+        duration_secs: 0.0,
+        columns:       IndexMap::new(),
+    });
+    let header_idx: AHashMap<&str, usize> = header_types
+        .iter()
+        .enumerate()
+        .map(|(idx, (name, _))| (name.as_str(), idx))
+        .collect();
+
+    // resolve the --lookup columns of this file
+    let mut lookups = Vec::with_capacity(row_checks.lookups.len());
+    for lookup in &row_checks.lookups {
+        let Some(&idx) = header_idx.get(lookup.column.as_str()) else {
+            return fail_incorrectusage_clierror!(
+                "--lookup column {} not found in {input_path}.",
+                lookup.column
+            );
         };
-
-    if log::log_enabled!(log::Level::Debug) {
-        // only log if debug is enabled
-        // as it can be quite large and expensive to deserialize the schema
-        debug!("schema json: {:?}", &schema_json);
+        lookups.push((idx, lookup));
     }
 
-    // set this once, as this is used repeatedly in a hot loop
-    NULL_TYPE.set(Value::String("null".to_string())).unwrap();
+    // resolve the --regex-rules columns of this file
+    let mut regex_rules = Vec::with_capacity(row_checks.regex_rules.len());
+    for regex_rule in &row_checks.regex_rules {
+        let Some(&idx) = header_idx.get(regex_rule.column.as_str()) else {
+            return fail_incorrectusage_clierror!(
+                "--regex-rules column {} not found in {input_path}.",
+                regex_rule.column
+            );
+        };
+        regex_rules.push((idx, regex_rule));
+    }
 
-    // get JSON types for each column in CSV file
-    let header_types = get_json_types(&headers, &schema_json)?;
+    // resolve the --unique keys of this file. The keys seen are tracked per file.
+    let mut unique_keys = Vec::with_capacity(args.flag_unique.len());
+    for spec in &args.flag_unique {
+        let selection = SelectColumns::parse(spec)?.selection(&headers, true)?;
+        unique_keys.push(UniqueKey {
+            name:       selection
+                .iter()
+                .map(|&idx| header_types[idx].0.as_str())
+                .join(","),
+            columns:    selection.iter().copied().collect(),
+            first_rows: AHashMap::new(),
+        });
+    }
 
-    // how many rows read and processed as batches
-    let mut row_number: u64 = 0;
     // how many invalid rows found
     let mut invalid_count: u64 = 0;
 
-    // amortize memory allocation by reusing record
-    let mut record = csv::ByteRecord::new();
-    // reuse batch buffer
     let batch_size = if args.flag_batch == 0 {
-        util::count_rows(&rconfig)? as usize
+        util::count_rows(rconfig)? as usize
     } else {
         args.flag_batch
     };
-    let mut batch = Vec::with_capacity(batch_size);
     let mut validation_results = Vec::with_capacity(batch_size);
     let mut valid_flags: Vec<bool> = Vec::with_capacity(batch_size);
     let mut validation_error_messages: Vec<String> = Vec::with_capacity(50);
     let flag_trim = args.flag_trim;
+    let max_errors = args.max_errors();
+    let mut stopped_early = false;
 
-    // set RAYON_NUM_THREADS
-    util::njobs(args.flag_jobs);
-
-    // main loop to read CSV and construct batches for parallel processing.
-    // each batch is processed via Rayon parallel iterator.
-    // loop exits when batch is empty.
-    'batch_loop: loop {
-        let mut buffer = itoa::Buffer::new();
-        for _ in 0..batch_size {
-            match rdr.read_byte_record(&mut record) {
-                Ok(has_data) => {
-                    if has_data {
-                        row_number += 1;
-                        record.push_field(buffer.format(row_number).as_bytes());
-                        if flag_trim {
-                            record.trim();
-                        }
-                        batch.push(std::mem::take(&mut record));
-                    } else {
+    // with --fix, the records are repaired as they're read, so the fixed values are validated
+    let column_fixes: Option<Vec<ColumnFix>> = args.flag_fix.as_ref().map(|_| {
+        let properties = schema_json.get("properties").unwrap_or(&Value::Null);
+        header_types
+            .iter()
+            .map(|(name, json_type)| {
+                ColumnFix::new(
+                    json_type,
+                    resolve_local_ref(schema_json, properties.get(name).unwrap_or(&Value::Null)),
+                )
+            })
+            .collect()
+    });
+    // safety: --fix-range was already checked in run()
+    let range_policy: RangePolicy = args.flag_fix_range.parse().unwrap_or(RangePolicy::Clamp);
+    let prefer_dmy = util::get_envvar_flag("QSV_PREFER_DMY");
+    let fixes_path = format!("{input_path}.fixes.tsv");
+    let mut fix_wtrs = match args.flag_fix {
+        Some(ref fix_path) => {
+            let mut fixed_wtr = Config::new(&Some(fix_path.clone())).writer()?;
+            fixed_wtr.write_byte_record(&headers)?;
+            let mut fixes_wtr = Config::new(&Some(fixes_path.clone())).writer()?;
+            fixes_wtr.write_record(["row_number", "field", "original", "fixed", "fixes"])?;
+            Some((fixed_wtr, fixes_wtr))
+        },
+        None => None,
+    };
+    let mut fix_count: u64 = 0;
+
+    // the next batch is read in a separate thread while the current batch is validated,
+    // so the validation threads are not left idle while the CSV is being read.
+    // The channel only holds one batch, bounding memory use to about three batches.
+    // Each batch comes with the values fixed in it with --fix.
+    let (batch_send, batch_recv) =
+        channel::bounded::<CliResult<(Vec<ByteRecord>, Vec<FixedValue>)>>(1);
+
+    std::thread::scope(|scope| -> CliResult<()> {
+        scope.spawn(move || {
+            // how many rows read, including the ones not selected by --slice/--sample
+            let mut row_number: u64 = 0;
+            let mut filter = selection.filter();
+            // amortize memory allocation by reusing record
+            let mut record = csv::ByteRecord::new();
+            let mut buffer = itoa::Buffer::new();
+            loop {
+                let mut batch = Vec::with_capacity(batch_size);
+                let mut fixed_values = Vec::new();
+                while batch.len() < batch_size {
+                    match rdr.read_byte_record(&mut record) {
+                        Ok(true) => {
+                            row_number += 1;
+                            match filter.check(row_number) {
+                                Some(true) => {},
+                                Some(false) => continue,
+                                // no more rows to check
+                                None => break,
+                            }
+                            record.push_field(buffer.format(row_number).as_bytes());
+                            if flag_trim {
+                                record.trim();
+                            }
+                            if let Some(ref column_fixes) = column_fixes {
+                                fix_record(
+                                    &mut record,
+                                    column_fixes,
+                                    row_number,
+                                    range_policy,
+                                    prefer_dmy,
+                                    &mut fixed_values,
+                                );
+                            }
+                            batch.push(std::mem::take(&mut record));
+                        },
                         // nothing else to add to batch
-                        break;
+                        Ok(false) => break,
+                        Err(e) => {
+                            // a CSV that can't be parsed is structurally broken
+                            let err = if e.is_io_error() {
+                                CliError::from(e)
+                            } else {
+                                CliError::MalformedCsv(format!(
+                                    "Error reading row: {row_number}: {e}"
+                                ))
+                            };
+                            let _ = batch_send.send(Err(err));
+                            return;
+                        },
                     }
-                },
-                Err(e) => {
-                    return fail_clierror!("Error reading row: {row_number}: {e}");
-                },
+                }
+                // stop at EOF, or when the validation loop stopped early and
+                // dropped the receiving end of the channel
+                if batch.is_empty() || batch_send.send(Ok((batch, fixed_values))).is_err() {
+                    return;
+                }
             }
-        }
+        });
+
+        // main loop to validate the batches as they're read.
+        // each batch is processed via Rayon parallel iterator.
+        // loop exits when the reader thread is done.
+        for batch in batch_recv {
+            let (batch, fixed_values) = match batch {
+                Ok(batch) => batch,
+                Err(e) => return fail!(e),
+            };
 
-        if batch.is_empty() {
-            // break out of infinite loop when at EOF
-            break 'batch_loop;
-        }
+            // do actual validation via Rayon parallel iterator
+            // validation_results vector should have same row count and in same order as input
+            batch
+                .par_iter()
+                .map_init(
+                    // safety: the rules were already compiled successfully in load_rule_defs
+                    || compile_rules(&row_checks.rule_defs).unwrap_or_default(),
+                    |rules, record| {
+                        do_json_validation(
+                            &header_types,
+                            header_len,
+                            record,
+                            schema_compiled,
+                            rules,
+                            &lookups,
+                            &regex_rules,
+                        )
+                    },
+                )
+                .collect_into_vec(&mut validation_results);
+
+            // write to validation error report, but keep Vec<bool> to gen valid/invalid files
+            // later because Rayon collect() guarantees original order, we can sequentially
+            // append results to vector with each batch
+            for (result, record) in validation_results.iter_mut().zip(batch.iter()) {
+                // the keys are checked here, in row order, so the first record with
+                // a key is never reported as the duplicate.
+                // The row number was added as the last column by the reader thread.
+                let row_number = atoi_simd::parse::<u64>(&record[header_len]).unwrap_or_default();
+                for unique_key in &mut unique_keys {
+                    if let Some(first_row) = unique_key.check(record, row_number) {
+                        let duplicate_msg = format!(
+                            "{row_number}\t{}\t{} is a duplicate of row {first_row}",
+                            unique_key.name,
+                            unique_key.key_display(record)
+                        );
+                        match result {
+                            Some(validation_error_msg) => {
+                                validation_error_msg.push('\n');
+                                validation_error_msg.push_str(&duplicate_msg);
+                            },
+                            None => *result = Some(duplicate_msg),
+                        }
+                    }
+                }
 
-        // do actual validation via Rayon parallel iterator
-        // validation_results vector should have same row count and in same order as input CSV
-        batch
-            .par_iter()
-            .map(|record| do_json_validation(&header_types, header_len, record, &schema_compiled))
-            .collect_into_vec(&mut validation_results);
+                if let Some(validation_error_msg) = result {
+                    invalid_count += 1;
+                    valid_flags.push(false);
 
-        // write to validation error report, but keep Vec<bool> to gen valid/invalid files later
-        // because Rayon collect() guarantees original order, we can sequentially append results
-        // to vector with each batch
-        for result in &validation_results {
-            if let Some(validation_error_msg) = result {
-                invalid_count += 1;
-                valid_flags.push(false);
+                    if let Some(ref mut report) = report {
+                        report.tally(validation_error_msg, record, &header_idx);
+                    }
+                    validation_error_messages.push(validation_error_msg.to_string());
+                } else {
+                    valid_flags.push(true);
+                }
 
-                validation_error_messages.push(validation_error_msg.to_string());
-            } else {
-                valid_flags.push(true);
+                // for fail-fast/max-errors, stop at the record that reached the limit
+                if max_errors > 0 && invalid_count >= max_errors {
+                    stopped_early = true;
+                    break;
+                }
             }
-        }
 
-        #[cfg(any(feature = "feature_capable", feature = "lite"))]
-        if show_progress {
-            progress.inc(batch.len() as u64);
-        }
-        batch.clear();
+            if let Some((fixed_wtr, fixes_wtr)) = &mut fix_wtrs {
+                for record in &batch {
+                    // don't write the row number we added as the last column
+                    fixed_wtr.write_record(record.iter().take(header_len))?;
+                }
+                for fixed_value in &fixed_values {
+                    fixes_wtr.write_record([
+                        fixed_value.row_number.to_string().as_str(),
+                        &header_types[fixed_value.column].0,
+                        &fixed_value.original,
+                        &fixed_value.fixed,
+                        &fixed_value.fixes.join(", "),
+                    ])?;
+                }
+                fix_count += fixed_values.len() as u64;
+            }
 
-        // for fail-fast, exit loop if batch has any error
-        if args.flag_fail_fast && invalid_count > 0 {
-            break 'batch_loop;
-        }
-    } // end batch loop
+            match progress_unit {
+                ProgressUnit::Records => progress.inc(batch.len() as u64),
+                ProgressUnit::Bytes => {
+                    // the records/sec, as the bar's throughput is in bytes/sec
+                    let records = valid_flags.len() as u64;
+                    let secs = start_time.elapsed().as_secs_f64();
+                    progress.set_message(format!(
+                        " - {} records ({}/s)",
+                        HumanCount(records),
+                        HumanCount(if secs > 0.0 {
+                            (records as f64 / secs) as u64
+                        } else {
+                            records
+                        })
+                    ));
+                    if let Some(position) = batch.last().and_then(ByteRecord::position) {
+                        progress.set_position(position.byte());
+                    }
+                },
+            }
 
-    #[cfg(any(feature = "feature_capable", feature = "lite"))]
-    if show_progress {
-        progress.set_message(format!(
-            " validated {} records.",
-            HumanCount(progress.length().unwrap())
-        ));
-        util::finish_progress(&progress);
-    }
+            if stopped_early {
+                break;
+            }
+        } // end batch loop
+        Ok(())
+    })?;
 
-    if invalid_count == 0 {
-        // no invalid records found
-        // see if we need to pass all valid records to output
-        if let Some(valid_output) = args.flag_valid_output {
-            // pass all valid records to output and return exit code 1
-            let valid_path = if valid_output == "-" {
-                // write to stdout
-                None
-            } else {
-                Some(valid_output)
-            };
+    // the last batch's position is where its last record starts, not the end of the file
+    if progress_unit == ProgressUnit::Bytes && !stopped_early {
+        progress.set_position(progress.length().unwrap_or_default());
+    }
 
-            let mut valid_wtr = Config::new(&valid_path).writer()?;
-            valid_wtr.write_byte_record(&headers)?;
+    // only count the rows we actually checked
+    let row_number = valid_flags.len() as u64;
 
-            let mut rdr = rconfig.reader()?;
-            let mut record = csv::ByteRecord::new();
-            while rdr.read_byte_record(&mut record)? {
-                valid_wtr.write_byte_record(&record)?;
-            }
-            valid_wtr.flush()?;
-            // return 1 as an exitcode and the number of valid rows to stderr
-            return fail_clierror!("{row_number}");
+    // with --fix, the valid/invalid files are split from the fixed copy
+    let split_rconfig = if let Some((mut fixed_wtr, mut fixes_wtr)) = fix_wtrs {
+        fixed_wtr.flush()?;
+        fixes_wtr.flush()?;
+        // safety: fix_wtrs is only set with --fix
+        let fix_path = args.flag_fix.clone().unwrap();
+        if !args.flag_quiet {
+            winfo!("Fixed {fix_count} values. Wrote {fix_path} and {fixes_path}.");
         }
+        Config::new(&Some(fix_path))
     } else {
+        rconfig.clone()
+    };
+
+    if invalid_count > 0 {
         // there are invalid records. write out invalid/valid/errors output files.
         // if 100% invalid, valid file isn't needed, but this is rare so OK creating empty file.
-        woutinfo!("Writing invalid/valid/error files...");
-
-        let input_path = args
-            .arg_input
-            .clone()
-            .unwrap_or_else(|| "stdin.csv".to_string());
+        write_error_report(input_path, error_format, validation_error_messages)?;
 
-        write_error_report(&input_path, validation_error_messages)?;
-
-        let valid_suffix = args.flag_valid.unwrap_or_else(|| "valid".to_string());
-        let invalid_suffix = args.flag_invalid.unwrap_or_else(|| "invalid".to_string());
+        let valid_suffix = args.flag_valid.as_deref().unwrap_or("valid");
+        let invalid_suffix = args.flag_invalid.as_deref().unwrap_or("invalid");
 
         split_invalid_records(
-            &rconfig,
+            &split_rconfig,
+            selection,
             &valid_flags[..],
             &headers,
-            &input_path,
-            &valid_suffix,
-            &invalid_suffix,
+            input_path,
+            valid_suffix,
+            invalid_suffix,
+            args.flag_valid_output.as_deref(),
         )?;
+    }
 
-        // done with validation; print output
-        let fail_fast_msg = if args.flag_fail_fast {
-            format!(
-                "fail-fast enabled. stopped after row {}.\n",
-                HumanCount(row_number)
-            )
-        } else {
-            String::new()
-        };
+    if let (Some(mut report), Some(report_format)) = (report, report_format) {
+        report.rows_checked = row_number;
+        report.rows_failed = invalid_count;
+        report.duration_secs = start_time.elapsed().as_secs_f64();
+        report.write(report_format)?;
+    }
+
+    Ok(ValidationOutcome {
+        headers,
+        row_count: row_number,
+        invalid_count,
+    })
+}
+
+/// validate several files, or all the files in a directory or an .infile-list, against the
+/// same JSON Schema in parallel, writing the per-file invalid/valid/error files next to
+/// each file and a consolidated summary to stdout.
+fn validate_batch(
+    args: &Args,
+    schema_json: &Value,
+    schema_compiled: &JSONSchema,
+    row_checks: &RowChecks,
+) -> CliResult<()> {
+    if args.flag_valid_output.is_some() {
+        return fail_incorrectusage_clierror!(
+            "--valid-output cannot be used when validating a batch of files."
+        );
+    }
+
+    let valid_suffix = format!(".{}", args.flag_valid.as_deref().unwrap_or("valid"));
+    let invalid_suffix = format!(".{}", args.flag_invalid.as_deref().unwrap_or("invalid"));
+
+    let tmpdir = util::scratch_dir()?;
+    let mut inputs: Vec<PathBuf> = util::process_input(
+        args.arg_input.iter().map(PathBuf::from).collect(),
+        &tmpdir,
+        "",
+    )?
+            .into_iter()
+            // skip the artifacts of previous validation runs
+            .filter(|path| {
+                let path_str = path.to_string_lossy();
+                path.is_file()
+                    && !path_str.ends_with(&valid_suffix)
+                    && !path_str.ends_with(&invalid_suffix)
+                    && !path_str.ends_with(".validation-errors.tsv")
+                    && !path_str.ends_with(".validation-errors.sarif")
+                    && !path_str.contains(".validation-report.")
+            })
+            .collect();
+
+    if inputs.is_empty() {
+        return fail_clierror!("No files to validate in {}.", args.arg_input.join(", "));
+    }
+    // so the summary is in a predictable order
+    inputs.sort_unstable();
+    winfo!("Validating {} files...", inputs.len());
+
+    // validate the files in parallel
+    // each file is in turn validated in parallel batches with Rayon
+    let hidden_progress = ProgressBar::hidden();
+    let outcomes: Vec<(String, CliResult<ValidationOutcome>)> = inputs
+        .par_iter()
+        .map(|path| {
+            let file_path = path.to_string_lossy().to_string();
+            let mut file_rconfig = Config::new(&Some(file_path.clone()));
+            if args.flag_delimiter.is_some() {
+                file_rconfig = file_rconfig.delimiter(args.flag_delimiter);
+            }
+            let outcome = RowSelection::new(args, &file_rconfig).and_then(|selection| {
+                validate_file(
+                    args,
+                    &file_rconfig,
+                    &file_path,
+                    schema_json,
+                    schema_compiled,
+                    row_checks,
+                    &selection,
+                    &hidden_progress,
+                    ProgressUnit::Records,
+                )
+            });
+            (file_path, outcome)
+        })
+        .collect();
+
+    // write consolidated summary
+    let mut summary_wtr = Config::new(&None).writer()?;
+    summary_wtr.write_record(["file", "records", "invalid", "status"])?;
+
+    let mut invalid_files = 0_u64;
+    for (file_path, outcome) in &outcomes {
+        match outcome {
+            Ok(outcome) => {
+                let status = if outcome.invalid_count == 0 {
+                    "valid"
+                } else {
+                    invalid_files += 1;
+                    "invalid"
+                };
+                summary_wtr.write_record([
+                    file_path.as_str(),
+                    outcome.row_count.to_string().as_str(),
+                    outcome.invalid_count.to_string().as_str(),
+                    status,
+                ])?;
+            },
+            Err(e) => {
+                invalid_files += 1;
+                summary_wtr.write_record([
+                    file_path.as_str(),
+                    "",
+                    "",
+                    format!("error: {e}").as_str(),
+                ])?;
+            },
+        }
+    }
+    summary_wtr.flush()?;
 
+    if invalid_files > 0 {
         return fail_clierror!(
-            "{fail_fast_msg}{} out of {} records invalid.",
-            HumanCount(invalid_count),
-            HumanCount(row_number)
+            "{} out of {} files invalid.",
+            HumanCount(invalid_files),
+            HumanCount(outcomes.len() as u64)
         );
     }
 
     if !args.flag_quiet {
-        winfo!("All {} records valid.", HumanCount(row_number));
+        winfo!("All {} files valid.", HumanCount(outcomes.len() as u64));
     }
     Ok(())
 }
 
 fn split_invalid_records(
     rconfig: &Config,
+    selection: &RowSelection,
     valid_flags: &[bool],
     headers: &ByteRecord,
     input_path: &str,
     valid_suffix: &str,
     invalid_suffix: &str,
+    valid_output: Option<&str>,
 ) -> CliResult<()> {
     // track how many rows read for splitting into valid/invalid
     // should not exceed row_number when aborted early due to fail-fast
     let mut split_row_num: usize = 0;
 
     // prepare output writers
-    let mut valid_wtr = Config::new(&Some(input_path.to_owned() + "." + valid_suffix)).writer()?;
+    // if --valid-output is set, valid records go there (stdout if "-")
+    // instead of the valid suffixed file
+    let valid_path = valid_output.map_or_else(
+        || input_path.to_owned() + "." + valid_suffix,
+        std::borrow::ToOwned::to_owned,
+    );
+    let mut valid_wtr = Config::new(&Some(valid_path)).writer()?;
     valid_wtr.write_byte_record(headers)?;
 
     let mut invalid_wtr =
@@ -724,8 +2986,17 @@ fn split_invalid_records(
 
     let mut rdr = rconfig.reader()?;
 
+    // only the rows selected by --slice/--sample were checked
+    let mut filter = selection.filter();
+    let mut row_number: u64 = 0;
     let mut record = csv::ByteRecord::new();
     while rdr.read_byte_record(&mut record)? {
+        row_number += 1;
+        match filter.check(row_number) {
+            Some(true) => {},
+            Some(false) => continue,
+            None => break,
+        }
         split_row_num += 1;
 
         // length of valid_flags is max number of rows we can split
@@ -749,7 +3020,149 @@ fn split_invalid_records(
     Ok(())
 }
 
-fn write_error_report(input_path: &str, validation_error_messages: Vec<String>) -> CliResult<()> {
+/// the format of the validation errors, set with --error-format
+#[derive(Clone, Copy, PartialEq)]
+enum ErrorFormat {
+    Tsv,
+    Sarif,
+    Github,
+}
+
+impl std::str::FromStr for ErrorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "tsv" => Ok(ErrorFormat::Tsv),
+            "sarif" => Ok(ErrorFormat::Sarif),
+            "github" => Ok(ErrorFormat::Github),
+            _ => Err(format!(
+                "Invalid --error-format: {s}. Valid formats are tsv, sarif and github."
+            )),
+        }
+    }
+}
+
+/// the row number, field & error of each line of the validation error messages
+fn parse_error_messages(validation_error_messages: &[String]) -> Vec<(u64, &str, &str)> {
+    validation_error_messages
+        .iter()
+        .flat_map(|error_msg| error_msg.lines())
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let row_number = parts.next()?.parse().ok()?;
+            Some((row_number, parts.next()?, parts.next().unwrap_or_default()))
+        })
+        .collect()
+}
+
+/// escape the data of a GitHub Actions workflow command. Property values also need their
+/// colons and commas escaped.
+fn github_escape(data: &str, is_property: bool) -> String {
+    let escaped = data
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    if is_property {
+        escaped.replace(':', "%3A").replace(',', "%2C")
+    } else {
+        escaped
+    }
+}
+
+/// the line of a row in the input, as the row numbers don't count the header row.
+/// This assumes that the records don't have embedded newlines.
+const fn row_line(row_number: u64) -> u64 {
+    row_number + 1
+}
+
+/// write the validation errors as GitHub Actions error annotations to stdout
+fn write_github_annotations(
+    input_path: &str,
+    validation_error_messages: &[String],
+) -> CliResult<()> {
+    let file = github_escape(input_path, true);
+    let mut stdout = io::stdout().lock();
+    for (row_number, field, error) in parse_error_messages(validation_error_messages) {
+        writeln!(
+            stdout,
+            "::error file={file},line={},title={}::{}",
+            row_line(row_number),
+            github_escape(&format!("qsv validate: {field}"), true),
+            github_escape(error, false)
+        )?;
+    }
+    Ok(stdout.flush()?)
+}
+
+/// write the validation errors as a SARIF 2.1.0 log, for code scanning dashboards
+fn write_sarif_report(input_path: &str, validation_error_messages: &[String]) -> CliResult<()> {
+    let errors = parse_error_messages(validation_error_messages);
+
+    // each field with errors is a rule, so the errors can be grouped by column
+    let fields: Vec<&str> = errors.iter().map(|(_, field, _)| *field).unique().collect();
+    let rules: Vec<Value> = fields
+        .iter()
+        .map(|field| {
+            json!({
+                "id": field,
+                "shortDescription": {"text": format!("Invalid {field} value")},
+            })
+        })
+        .collect();
+    let results: Vec<Value> = errors
+        .iter()
+        .map(|(row_number, field, error)| {
+            json!({
+                "ruleId": field,
+                "ruleIndex": fields.iter().position(|f| f == field),
+                "level": "error",
+                "message": {"text": error},
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {"uri": input_path},
+                        "region": {"startLine": row_line(*row_number)},
+                    }
+                }],
+            })
+        })
+        .collect();
+
+    let sarif = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "qsv validate",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/jqnatividad/qsv",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    });
+
+    let output_file = File::create(input_path.to_owned() + ".validation-errors.sarif")?;
+    let mut output_writer = BufWriter::new(output_file);
+    serde_json::to_writer_pretty(&mut output_writer, &sarif)?;
+    Ok(output_writer.flush()?)
+}
+
+fn write_error_report(
+    input_path: &str,
+    error_format: ErrorFormat,
+    validation_error_messages: Vec<String>,
+) -> CliResult<()> {
+    match error_format {
+        ErrorFormat::Tsv => {},
+        ErrorFormat::Sarif => return write_sarif_report(input_path, &validation_error_messages),
+        ErrorFormat::Github => {
+            return write_github_annotations(input_path, &validation_error_messages)
+        },
+    }
+
     let wtr_capacitys = env::var("QSV_WTR_BUFFER_CAPACITY")
         .unwrap_or_else(|_| DEFAULT_WTR_BUFFER_CAPACITY.to_string());
     let wtr_buffer_size: usize = wtr_capacitys.parse().unwrap_or(DEFAULT_WTR_BUFFER_CAPACITY);
@@ -781,32 +3194,61 @@ fn do_json_validation(
     header_len: usize,
     record: &ByteRecord,
     schema_compiled: &JSONSchema,
+    rules: &[Rule],
+    lookups: &[(usize, &Lookup)],
+    regex_rules: &[(usize, &RegexRule)],
 ) -> Option<String> {
     // safety: row number was added as last column. We use can do unwrap safely since we know its
     // there
     let row_number_string = simdutf8::basic::from_utf8(record.get(header_len).unwrap()).unwrap();
 
-    validate_json_instance(
-        &(match to_json_instance(header_types, header_len, record) {
-            Ok(obj) => obj,
-            Err(e) => {
-                return Some(format!("{row_number_string}\t<RECORD>\t{e}"));
-            },
-        }),
-        schema_compiled,
-    )
-    .map(|validation_errors| {
-        // squash multiple errors into one long String with linebreaks
-        let combined_errors: String = validation_errors
-            .iter()
-            .map(|tuple| {
-                // validation error file format: row_number, field, error
-                format!("{row_number_string}\t{}\t{}", tuple.0, tuple.1)
-            })
-            .join("\n");
+    let instance = match to_json_instance(header_types, header_len, record) {
+        Ok(obj) => obj,
+        Err(e) => {
+            return Some(format!("{row_number_string}\t<RECORD>\t{e}"));
+        },
+    };
 
-        combined_errors
-    })
+    let mut validation_errors =
+        validate_json_instance(&instance, schema_compiled).unwrap_or_default();
+    for (idx, lookup) in lookups {
+        let value = &record[*idx];
+        if !value.is_empty() && !lookup.values.contains(value) {
+            validation_errors.push((
+                lookup.column.clone(),
+                format!(
+                    "\"{}\" not found in {} of {}",
+                    String::from_utf8_lossy(value),
+                    lookup.lookup_column,
+                    lookup.lookup_path
+                ),
+            ));
+        }
+    }
+    for (idx, regex_rule) in regex_rules {
+        let value = String::from_utf8_lossy(&record[*idx]);
+        if !value.is_empty() && !regex_rule.regex.is_match(&value) {
+            validation_errors.push((
+                regex_rule.column.clone(),
+                format!("\"{value}\" does not match {}", regex_rule.pattern),
+            ));
+        }
+    }
+    if !rules.is_empty() {
+        validation_errors.extend(check_rules(&instance, rules));
+    }
+    if validation_errors.is_empty() {
+        return None;
+    }
+
+    // squash multiple errors into one long String with linebreaks
+    // validation error file format: row_number, field, error
+    Some(
+        validation_errors
+            .iter()
+            .map(|tuple| format!("{row_number_string}\t{}\t{}", tuple.0, tuple.1))
+            .join("\n"),
+    )
 }
 
 /// convert CSV Record into JSON instance by referencing JSON types
@@ -878,13 +3320,33 @@ fn to_json_instance(
     Ok(Value::Object(json_object_map))
 }
 
+/// follow the local `$ref`s of a field definition (e.g. {"$ref": "#/$defs/id"}),
+/// as composed schemas (see `qsv schema --compose`) share field definitions in `$defs`
+fn resolve_local_ref<'a>(schema: &'a Value, mut field_def: &'a Value) -> &'a Value {
+    // the depth limit guards against circular references
+    for _ in 0..16 {
+        let Some(pointer) = field_def
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix('#'))
+        else {
+            break;
+        };
+        match schema.pointer(pointer) {
+            Some(def) => field_def = def,
+            None => break,
+        }
+    }
+    field_def
+}
+
 /// get JSON types for each column in CSV file
 /// returns a Vector of tuples of column/header name (String) & JSON type (JSONtypes enum)
 #[inline]
 fn get_json_types(headers: &ByteRecord, schema: &Value) -> CliResult<Vec<(String, JSONtypes)>> {
     // make sure schema has expected structure
     let Some(schema_properties) = schema.get("properties") else {
-        return fail_clierror!("JSON Schema missing 'properties' object");
+        return fail_invalidschema_clierror!("JSON Schema missing 'properties' object");
     };
 
     // safety: we set NULL_TYPE in main() and it's never changed
@@ -906,9 +3368,12 @@ fn get_json_types(headers: &ByteRecord, schema: &Value) -> CliResult<Vec<(String
             return fail_encoding_clierror!("CSV header is not valid UTF-8: {s}");
         };
 
-        field_def = schema_properties
-            .get(key_string.clone())
-            .unwrap_or(&Value::Null);
+        field_def = resolve_local_ref(
+            schema,
+            schema_properties
+                .get(key_string.clone())
+                .unwrap_or(&Value::Null),
+        );
         field_type_def = field_def.get("type").unwrap_or(&Value::Null);
 
         json_type = match field_type_def {
@@ -1075,13 +3540,30 @@ fn validate_json_instance(
             BasicOutput::Invalid(errors) => errors
                 .iter()
                 .map(|e| {
+                    let description = e.error_description().to_string();
                     if let Some(PathChunk::Property(box_str)) = e.instance_location().last() {
-                        (box_str.to_string(), e.error_description().to_string())
+                        // an empty column required by a subschema
+                        let label = if description.starts_with("null is not of type") {
+                            requirement_label(&e.keyword_location().to_string())
+                        } else {
+                            None
+                        };
+                        match label {
+                            Some(label) => {
+                                (box_str.to_string(), format!("empty, but required {label}"))
+                            },
+                            None => (box_str.to_string(), description),
+                        }
+                    } else if e.instance_location().last().is_none() {
+                        // an error about the whole record, which is too long to repeat
+                        let record = instance.to_string();
+                        let description = match description.strip_prefix(&record) {
+                            Some(rest) => format!("record{rest}"),
+                            None => description,
+                        };
+                        ("<RECORD>".to_string(), description)
                     } else {
-                        (
-                            e.instance_location().to_string(),
-                            e.error_description().to_string(),
-                        )
+                        (e.instance_location().to_string(), description)
                     }
                 })
                 .collect(),
@@ -1095,6 +3577,21 @@ fn validate_json_instance(
     }
 }
 
+/// the label of the innermost subschema with conditional requirements that contains
+/// the keyword location of an error, if any
+fn requirement_label(keyword_location: &str) -> Option<&'static str> {
+    REQUIREMENT_LABELS
+        .get()?
+        .iter()
+        .filter(|(location, _)| {
+            keyword_location
+                .strip_prefix(location.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .max_by_key(|(location, _)| location.len())
+        .map(|(_, label)| label.as_str())
+}
+
 #[cfg(test)]
 mod tests_for_schema_validation {
     use super::*;
@@ -1257,10 +3754,72 @@ fn test_validate_currency_validator() {
     assert_eq!(result, None);
 }
 
-fn load_json(uri: &str) -> Result<String, String> {
+/// the directory to cache downloaded schemas in - QSV_CACHE_DIR if set, otherwise
+/// --cache-dir, with a leading ~ expanded to the home directory
+fn schema_cache_dir(flag_cache_dir: &str) -> PathBuf {
+    let cache_dir = env::var("QSV_CACHE_DIR").unwrap_or_else(|_| flag_cache_dir.to_string());
+    let home = env::var("HOME").or_else(|_| env::var("USERPROFILE"));
+    match (cache_dir.strip_prefix('~'), home) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest.trim_start_matches(['/', '\\'])),
+        _ => PathBuf::from(cache_dir),
+    }
+}
+
+/// the validator of a cached schema, used to make conditional requests
+#[derive(Serialize, Deserialize, Default)]
+struct SchemaCacheMeta {
+    url:           String,
+    etag:          Option<String>,
+    last_modified: Option<String>,
+}
+
+/// the paths of the cached schema and its metadata for a url
+fn schema_cache_paths(cache_dir: &Path, url: &str) -> (PathBuf, PathBuf) {
+    let key = format!("{:016x}", xxhash_rust::xxh3::xxh3_64(url.as_bytes()));
+    let schema_dir = cache_dir.join("validate");
+    (
+        schema_dir.join(format!("{key}.schema.json")),
+        schema_dir.join(format!("{key}.meta.json")),
+    )
+}
+
+/// store a downloaded schema in the cache. Caching is best effort - errors are only logged.
+fn write_schema_cache(
+    cache_dir: &Path,
+    url: &str,
+    body: &str,
+    headers: &reqwest::header::HeaderMap,
+) {
+    let (schema_path, meta_path) = schema_cache_paths(cache_dir, url);
+    let header_value = |name: reqwest::header::HeaderName| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let meta = SchemaCacheMeta {
+        url:           url.to_string(),
+        etag:          header_value(reqwest::header::ETAG),
+        last_modified: header_value(reqwest::header::LAST_MODIFIED),
+    };
+    if meta.etag.is_none() && meta.last_modified.is_none() {
+        // nothing to revalidate the cached schema with
+        return;
+    }
+    let result = std::fs::create_dir_all(cache_dir.join("validate"))
+        .and_then(|()| std::fs::write(&schema_path, body))
+        .and_then(|()| std::fs::write(&meta_path, serde_json::to_vec(&meta).unwrap_or_default()));
+    if let Err(e) = result {
+        log::warn!("Cannot cache schema {url} in {}: {e}", cache_dir.display());
+    }
+}
+
+/// load a JSON schema from a file or a URL. Schemas from URLs are cached in `cache_dir`
+/// and revalidated with conditional requests (If-None-Match/If-Modified-Since).
+fn load_json(uri: &str, cache_dir: Option<&Path>) -> Result<String, String> {
     let json_string = match uri {
         url if url.to_lowercase().starts_with("http") => {
-            use reqwest::blocking::Client;
+            use reqwest::{blocking::Client, header, StatusCode};
 
             let client_timeout =
                 std::time::Duration::from_secs(TIMEOUT_SECS.load(Ordering::Relaxed) as u64);
@@ -1287,9 +3846,53 @@ fn load_json(uri: &str) -> Result<String, String> {
                 },
             };
 
-            match client.get(url).send() {
-                Ok(response) => response.text().unwrap_or_default(),
-                Err(e) => return fail_format!("Cannot read JSON at url {url}: {e}."),
+            // the cached schema, if we have a valid one for this url
+            let cached = cache_dir.and_then(|dir| {
+                let (schema_path, meta_path) = schema_cache_paths(dir, url);
+                let meta: SchemaCacheMeta =
+                    serde_json::from_slice(&std::fs::read(meta_path).ok()?).ok()?;
+                let schema = std::fs::read_to_string(schema_path).ok()?;
+                (meta.url == url).then_some((meta, schema))
+            });
+
+            let mut request = client.get(url);
+            if let Some((ref meta, _)) = cached {
+                if let Some(ref etag) = meta.etag {
+                    request = request.header(header::IF_NONE_MATCH, etag);
+                }
+                if let Some(ref last_modified) = meta.last_modified {
+                    request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+
+            match request.send() {
+                Ok(response) if response.status() == StatusCode::NOT_MODIFIED => {
+                    if let Some((_, schema)) = cached {
+                        info!("Using cached schema for {url}.");
+                        schema
+                    } else {
+                        return fail_format!("Cannot read JSON at url {url}: 304 Not Modified.");
+                    }
+                },
+                Ok(response) => {
+                    let headers = response.headers().clone();
+                    let success = response.status().is_success();
+                    let body = response.text().unwrap_or_default();
+                    if success {
+                        if let Some(dir) = cache_dir {
+                            write_schema_cache(dir, url, &body, &headers);
+                        }
+                    }
+                    body
+                },
+                Err(e) => {
+                    if let Some((_, schema)) = cached {
+                        wwarn!("Cannot read JSON at url {url}: {e}. Using the cached schema.");
+                        schema
+                    } else {
+                        return fail_format!("Cannot read JSON at url {url}: {e}.");
+                    }
+                },
             }
         },
         path => {
@@ -1311,10 +3914,27 @@ fn load_json(uri: &str) -> Result<String, String> {
 
 #[test]
 fn test_load_json_via_url() {
-    let json_string_result = load_json("https://geojson.org/schema/FeatureCollection.json");
+    let json_string_result = load_json("https://geojson.org/schema/FeatureCollection.json", None);
     assert!(&json_string_result.is_ok());
 
     let json_result: Result<Value, serde_json::Error> =
         serde_json::from_str(&json_string_result.unwrap());
     assert!(&json_result.is_ok());
 }
+
+#[test]
+fn test_load_json_via_url_uses_cache_when_offline() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    // nothing listens on port 1, so the request fails
+    let url = "http://127.0.0.1:1/schema.json";
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(reqwest::header::ETAG, "\"v1\"".parse().unwrap());
+    write_schema_cache(cache_dir.path(), url, r#"{"type": "object"}"#, &headers);
+
+    let json_string_result = load_json(url, Some(cache_dir.path()));
+    assert_eq!(json_string_result, Ok(r#"{"type": "object"}"#.to_string()));
+
+    // without the cache, the request error is returned
+    assert!(load_json(url, None).is_err());
+}
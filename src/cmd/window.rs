@@ -0,0 +1,361 @@
+static USAGE: &str = r#"
+Adds rolling & cumulative aggregation columns to a CSV - e.g. the 7-row moving average
+or the running total of a column, optionally per partition (e.g. per id).
+
+The aggregations are comma-separated lists of func(column), the functions being count,
+sum, mean, min & max:
+  * --rolling aggregates the last n rows of the partition, the current row included,
+    with the window size n given after a colon, e.g. mean(value):7.
+  * --cum aggregates all the rows of the partition so far, the current row included,
+    e.g. sum(value).
+The aggregated columns must be numbers. Empty values are skipped - count is the number of
+non-empty values, and the other functions are empty when there are no values.
+The new columns are named after their aggregation, e.g. rolling_mean_value_7 or
+cum_sum_value.
+
+The rows are aggregated in the input order, so time series should be sorted by time.
+With --order-by, the rows are sorted by the --order-by columns first (as numbers when
+they're numbers, and as text otherwise - so ISO 8601 dates are sorted chronologically),
+which requires loading the CSV in memory. Otherwise, the rows are streamed.
+
+Examples:
+
+  # the 7-day moving average & the running total of the value of each id, by date
+  $ qsv window -p id --order-by date -r "mean(value):7" -c "sum(value)" data.csv
+
+  # the min & max of the last 3 readings, on a CSV already sorted by time
+  $ qsv window --rolling "min(temp):3,max(temp):3" readings.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_window.rs.
+
+Usage:
+    qsv window [options] [<input>]
+    qsv window --help
+
+window options:
+    -p, --partition-by <cols>  The columns to partition the rows by. The aggregations
+                               of each distinct combination of their values are
+                               computed separately.
+                               See 'qsv select --help' for the format details.
+    --order-by <cols>          The columns to sort the rows by before aggregating them.
+    -r, --rolling <aggs>       The rolling aggregations, e.g. "mean(value):7".
+    -c, --cum <aggs>           The cumulative aggregations, e.g. "sum(value)".
+    --round <places>           Round the aggregates to <places> decimal places.
+                               [default: 4]
+
+Common options:
+    -h, --help                 Display this message
+    -o, --output <file>        Write output to <file> instead of stdout.
+    -d, --delimiter <arg>      The field delimiter for reading CSV data.
+                               Must be a single character. (default: ,)
+"#;
+
+use std::{cmp::Ordering, collections::VecDeque};
+
+use ahash::AHashMap;
+use csv::ByteRecord;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    select::{SelectColumns, Selection},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:         Option<String>,
+    flag_partition_by: Option<SelectColumns>,
+    flag_order_by:     Option<SelectColumns>,
+    flag_rolling:      Option<String>,
+    flag_cum:          Option<String>,
+    flag_round:        u32,
+    flag_output:       Option<String>,
+    flag_delimiter:    Option<Delimiter>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Func {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+/// a rolling or cumulative aggregation of a column
+struct Spec {
+    func:   Func,
+    column: usize,
+    /// the number of rows of the rolling window, None for a cumulative aggregation
+    size:   Option<usize>,
+    name:   String,
+}
+
+/// parse the --rolling or --cum aggregations, e.g. "mean(value):7,max(value):7"
+fn parse_specs(specs: &str, headers: &ByteRecord, rolling: bool) -> CliResult<Vec<Spec>> {
+    let mut parsed = Vec::new();
+    for spec in specs
+        .split(',')
+        .map(str::trim)
+        .filter(|spec| !spec.is_empty())
+    {
+        let invalid = || {
+            if rolling {
+                format!("Invalid --rolling aggregation: {spec}. Expected e.g. mean(value):7.")
+            } else {
+                format!("Invalid --cum aggregation: {spec}. Expected e.g. sum(value).")
+            }
+        };
+        let Some((func, column, size)) = spec.rsplit_once(')').and_then(|(agg, size)| {
+            agg.split_once('(')
+                .map(|(func, column)| (func, column, size))
+        }) else {
+            return fail_incorrectusage_clierror!("{}", invalid());
+        };
+        let size = if rolling {
+            match size
+                .trim()
+                .strip_prefix(':')
+                .map(|size| size.trim().parse::<usize>())
+            {
+                Some(Ok(size)) if size > 0 => Some(size),
+                _ => return fail_incorrectusage_clierror!("{}", invalid()),
+            }
+        } else if size.trim().is_empty() {
+            None
+        } else {
+            return fail_incorrectusage_clierror!("{}", invalid());
+        };
+        let func = match func.trim().to_ascii_lowercase().as_str() {
+            "count" => Func::Count,
+            "sum" => Func::Sum,
+            "mean" => Func::Mean,
+            "min" => Func::Min,
+            "max" => Func::Max,
+            _ => {
+                return fail_incorrectusage_clierror!(
+                    "{} The functions are count, sum, mean, min & max.",
+                    invalid()
+                )
+            },
+        };
+        let sel = SelectColumns::parse(column.trim())?.selection(headers, true)?;
+        if sel.len() != 1 {
+            return fail_incorrectusage_clierror!(
+                "The column of aggregation {spec} must be a single column."
+            );
+        }
+        let column_name = String::from_utf8_lossy(&headers[sel[0]]);
+        parsed.push(Spec {
+            func,
+            column: sel[0],
+            size,
+            name: match size {
+                Some(size) => format!("rolling_{}_{column_name}_{size}", func.name()),
+                None => format!("cum_{}_{column_name}", func.name()),
+            },
+        });
+    }
+    Ok(parsed)
+}
+
+impl Func {
+    fn name(self) -> &'static str {
+        match self {
+            Func::Count => "count",
+            Func::Sum => "sum",
+            Func::Mean => "mean",
+            Func::Min => "min",
+            Func::Max => "max",
+        }
+    }
+}
+
+/// the count, sum, min & max of the values of a window
+#[derive(Clone, Copy)]
+struct Acc {
+    count: u64,
+    sum:   f64,
+    min:   f64,
+    max:   f64,
+}
+
+impl Default for Acc {
+    fn default() -> Self {
+        Acc {
+            count: 0,
+            sum:   0.0,
+            min:   f64::INFINITY,
+            max:   f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Acc {
+    #[inline]
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// the aggregate, None when there are no values (except for count)
+    fn value(&self, func: Func) -> Option<f64> {
+        match func {
+            Func::Count => Some(self.count as f64),
+            _ if self.count == 0 => None,
+            Func::Sum => Some(self.sum),
+            Func::Mean => Some(self.sum / self.count as f64),
+            Func::Min => Some(self.min),
+            Func::Max => Some(self.max),
+        }
+    }
+}
+
+/// the state of an aggregation in a partition
+enum State {
+    /// the values of the last rows, empty values being None
+    Rolling(VecDeque<Option<f64>>),
+    Cum(Acc),
+}
+
+/// the key values of a partition
+type Key = Vec<Vec<u8>>;
+
+struct Windows {
+    specs:         Vec<Spec>,
+    partition_sel: Option<Selection>,
+    partitions:    AHashMap<Key, Vec<State>>,
+    round:         u32,
+}
+
+impl Windows {
+    /// add the aggregates of the record, in its partition, to the output record
+    fn aggregate(&mut self, record: &ByteRecord, out_record: &mut ByteRecord) -> CliResult<()> {
+        let key: Key = match self.partition_sel {
+            Some(ref sel) => sel.select(record).map(<[u8]>::to_vec).collect(),
+            None => Vec::new(),
+        };
+        let states = self.partitions.entry(key).or_insert_with(|| {
+            self.specs
+                .iter()
+                .map(|spec| match spec.size {
+                    Some(size) => State::Rolling(VecDeque::with_capacity(size)),
+                    None => State::Cum(Acc::default()),
+                })
+                .collect()
+        });
+
+        for (spec, state) in self.specs.iter().zip(states.iter_mut()) {
+            let field = record.get(spec.column).unwrap_or_default().trim_ascii();
+            let value = if field.is_empty() {
+                None
+            } else {
+                let value = simdutf8::basic::from_utf8(field)
+                    .ok()
+                    .and_then(|field| field.parse::<f64>().ok());
+                if value.is_none() {
+                    return fail_clierror!(
+                        "Invalid value for {}: \"{}\" is not a number.",
+                        spec.name,
+                        String::from_utf8_lossy(field)
+                    );
+                }
+                value
+            };
+            let aggregate = match state {
+                State::Rolling(window) => {
+                    // safety: the rolling aggregations have a size
+                    if window.len() == spec.size.unwrap() {
+                        window.pop_front();
+                    }
+                    window.push_back(value);
+                    let mut acc = Acc::default();
+                    for &value in window.iter().flatten() {
+                        acc.add(value);
+                    }
+                    acc.value(spec.func)
+                },
+                State::Cum(acc) => {
+                    if let Some(value) = value {
+                        acc.add(value);
+                    }
+                    acc.value(spec.func)
+                },
+            };
+            match aggregate {
+                Some(aggregate) => {
+                    out_record.push_field(util::round_num(aggregate, self.round).as_bytes());
+                },
+                None => out_record.push_field(b""),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconfig = Config::new(&args.arg_input).delimiter(args.flag_delimiter);
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let mut specs = Vec::new();
+    if let Some(ref rolling) = args.flag_rolling {
+        specs.extend(parse_specs(rolling, &headers, true)?);
+    }
+    if let Some(ref cum) = args.flag_cum {
+        specs.extend(parse_specs(cum, &headers, false)?);
+    }
+    if specs.is_empty() {
+        return fail_incorrectusage_clierror!("window requires --rolling or --cum aggregations.");
+    }
+    let partition_sel = match args.flag_partition_by {
+        Some(ref partition_by) => Some(partition_by.selection(&headers, true)?),
+        None => None,
+    };
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut out_record = headers.clone();
+    for spec in &specs {
+        out_record.push_field(spec.name.as_bytes());
+    }
+    wtr.write_byte_record(&out_record)?;
+
+    let mut windows = Windows {
+        specs,
+        partition_sel,
+        partitions: AHashMap::new(),
+        round: args.flag_round,
+    };
+
+    if let Some(ref order_by) = args.flag_order_by {
+        let order_sel = order_by.selection(&headers, true)?;
+        let mut records = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
+        // a stable sort, so rows with the same order keep their input order
+        records.sort_by(|a, b| {
+            order_sel
+                .select(a)
+                .zip(order_sel.select(b))
+                .map(|(a, b)| util::cmp_values(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        for record in records {
+            out_record.clone_from(&record);
+            windows.aggregate(&record, &mut out_record)?;
+            wtr.write_byte_record(&out_record)?;
+        }
+    } else {
+        let mut record = ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            out_record.clone_from(&record);
+            windows.aggregate(&record, &mut out_record)?;
+            wtr.write_byte_record(&out_record)?;
+        }
+    }
+    Ok(wtr.flush()?)
+}
@@ -0,0 +1,275 @@
+static USAGE: &str = r#"
+Converts an XML document to CSV, with a row per record element.
+
+The record elements are the elements named --record-tag, at any depth of the document,
+which is streamed so it doesn't need to fit in memory. The children & attributes of each
+record are flattened into columns named by their path from the record, e.g. for
+  <row id="1"><name>Alice</name><address type="home"><city>Boston</city></address></row>
+the columns are @id, name, address.@type & address.city.
+
+Element names are matched without their namespace prefix, and the xmlns attributes are
+ignored. The values of repeated elements are joined with --separator. Empty elements are
+empty values, and the text of the record element itself is in a #text column.
+
+Without --fields, the columns are those of all the records, in order of first appearance,
+so the records are kept in memory until the end of the document. Set --fields to stream
+large documents.
+
+Examples:
+
+  # the <row> elements of data.xml
+  $ qsv xml data.xml --output data.csv
+
+  # the id attribute, name & city of each <customer>
+  $ qsv xml --record-tag customer --fields @id,name,address.city customers.xml
+
+  # the <item> elements of an RSS feed
+  $ curl -s https://example.com/feed.rss | qsv xml -r item -f title,link,pubDate
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_xml.rs.
+
+Usage:
+    qsv xml [options] [<input>]
+    qsv xml --help
+
+xml options:
+    -r, --record-tag <tag>  The name of the record elements.
+                            [default: row]
+    -f, --fields <list>     The comma-separated paths of the columns to write, in that
+                            order, e.g. @id,name,address.city.
+                            Defaults to all the paths of the records.
+    --separator <sep>       The separator of the values of repeated elements.
+                            [default: |]
+
+Common options:
+    -h, --help              Display this message
+    -o, --output <file>     Write output to <file> instead of stdout.
+    -d, --delimiter <arg>   The field delimiter for writing CSV data.
+                            Must be a single character. (default: ,)
+"#;
+
+use std::io::BufReader;
+
+use indexmap::{IndexMap, IndexSet};
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter, DEFAULT_RDR_BUFFER_CAPACITY},
+    util, CliError, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_record_tag: String,
+    flag_fields:     Option<String>,
+    flag_separator:  String,
+    flag_output:     Option<String>,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+impl From<quick_xml::Error> for CliError {
+    fn from(err: quick_xml::Error) -> CliError {
+        CliError::Other(format!("XML error: {err}"))
+    }
+}
+
+/// the values of a record, by path
+type Record = IndexMap<String, String>;
+
+/// an open element of the current record
+struct Element {
+    path:         String,
+    text:         String,
+    has_children: bool,
+}
+
+fn child_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{parent}.{name}")
+    }
+}
+
+/// add a value to a record, joining the values of repeated paths with the separator
+fn add_value(record: &mut Record, path: String, value: &str, separator: &str) {
+    record
+        .entry(path)
+        .and_modify(|values| {
+            values.push_str(separator);
+            values.push_str(value);
+        })
+        .or_insert_with(|| value.to_string());
+}
+
+/// add the attributes of an element to a record, as @name columns under its path
+fn add_attributes(
+    element: &BytesStart,
+    path: &str,
+    record: &mut Record,
+    separator: &str,
+) -> CliResult<bool> {
+    let mut has_attributes = false;
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        if attribute.key.as_namespace_binding().is_some() {
+            continue;
+        }
+        let name = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+        let value = attribute.unescape_value()?;
+        add_value(
+            record,
+            child_path(path, &format!("@{name}")),
+            &value,
+            separator,
+        );
+        has_attributes = true;
+    }
+    Ok(has_attributes)
+}
+
+/// add the text of a closed element to a record. Empty elements are empty values.
+fn close_element(element: Element, record: &mut Record, separator: &str) {
+    if element.text.is_empty() {
+        if !element.has_children && !element.path.is_empty() {
+            record.entry(element.path).or_default();
+        }
+        return;
+    }
+    let path = if element.path.is_empty() {
+        "#text".to_string()
+    } else {
+        element.path
+    };
+    add_value(record, path, &element.text, separator);
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let record_tag = args.flag_record_tag.as_bytes();
+    let fields: Option<Vec<String>> = args.flag_fields.as_ref().map(|fields| {
+        fields
+            .split(',')
+            .map(|field| field.trim().to_string())
+            .collect()
+    });
+
+    let mut reader = Reader::from_reader(BufReader::with_capacity(
+        DEFAULT_RDR_BUFFER_CAPACITY,
+        Config::new(&args.arg_input).io_reader()?,
+    ));
+    reader.config_mut().trim_text(true);
+
+    let mut wtr = Config::new(&args.flag_output)
+        .delimiter(args.flag_delimiter)
+        .writer()?;
+    if let Some(ref fields) = fields {
+        wtr.write_record(fields)?;
+    }
+
+    let separator = args.flag_separator.as_str();
+    let mut buf = Vec::new();
+    let mut stack: Vec<Element> = Vec::new();
+    let mut record: Option<Record> = None;
+    // without --fields, the records are written at the end, with the columns of all of them
+    let mut records: Vec<Record> = Vec::new();
+    let mut record_count = 0_u64;
+    loop {
+        let mut closed_record = None;
+        let event = reader.read_event_into(&mut buf)?;
+        let is_empty = matches!(event, Event::Empty(_));
+        match event {
+            Event::Start(element) | Event::Empty(element) => {
+                if let Some(ref mut current) = record {
+                    // safety: the record element is at the bottom of the stack
+                    let parent = stack.last_mut().unwrap();
+                    parent.has_children = true;
+                    let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+                    let path = child_path(&parent.path, &name);
+                    let has_attributes = add_attributes(&element, &path, current, separator)?;
+                    let child = Element {
+                        path,
+                        text: String::new(),
+                        has_children: has_attributes,
+                    };
+                    if is_empty {
+                        close_element(child, current, separator);
+                    } else {
+                        stack.push(child);
+                    }
+                } else if element.local_name().as_ref() == record_tag {
+                    let mut current = Record::new();
+                    add_attributes(&element, "", &mut current, separator)?;
+                    if is_empty {
+                        closed_record = Some(current);
+                    } else {
+                        stack.push(Element {
+                            path:         String::new(),
+                            text:         String::new(),
+                            has_children: false,
+                        });
+                        record = Some(current);
+                    }
+                }
+            },
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.unescape()?);
+                }
+            },
+            Event::CData(cdata) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&String::from_utf8_lossy(&cdata));
+                }
+            },
+            Event::End(_) => {
+                if let Some(element) = stack.pop() {
+                    // safety: the stack is only non-empty in a record
+                    close_element(element, record.as_mut().unwrap(), separator);
+                    if stack.is_empty() {
+                        closed_record = record.take();
+                    }
+                }
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+        buf.clear();
+
+        if let Some(closed_record) = closed_record {
+            record_count += 1;
+            match fields {
+                Some(ref fields) => wtr.write_record(
+                    fields
+                        .iter()
+                        .map(|field| closed_record.get(field).map_or("", String::as_str)),
+                )?,
+                None => records.push(closed_record),
+            }
+        }
+    }
+
+    if fields.is_none() {
+        let mut columns: IndexSet<&str> = IndexSet::new();
+        for record in &records {
+            columns.extend(record.keys().map(String::as_str));
+        }
+        wtr.write_record(&columns)?;
+        for record in &records {
+            wtr.write_record(
+                columns
+                    .iter()
+                    .map(|column| record.get(*column).map_or("", String::as_str)),
+            )?;
+        }
+    }
+    if record_count == 0 {
+        wwarn!("No <{}> record elements found.", args.flag_record_tag);
+    }
+    Ok(wtr.flush()?)
+}
@@ -11,6 +11,7 @@ use serde::de::{Deserialize, Deserializer, Error};
 
 use crate::{
     index::Indexed,
+    redaction,
     select::{SelectColumns, Selection},
     util, CliResult,
 };
@@ -323,7 +324,20 @@ impl Config {
     }
 
     pub fn writer(&self) -> io::Result<csv::Writer<Box<dyn io::Write + 'static>>> {
-        Ok(self.from_writer(self.io_writer()?))
+        let wtr = self.io_writer()?;
+        Ok(match redaction::active_profile() {
+            Some(profile) => {
+                let redacting_wtr = redaction::RedactingWriter::new(
+                    profile,
+                    self.from_writer(wtr),
+                    self.delimiter,
+                    self.quote,
+                );
+                self.writer_builder()
+                    .from_writer(Box::new(redacting_wtr) as Box<dyn io::Write + 'static>)
+            },
+            None => self.from_writer(wtr),
+        })
     }
 
     pub fn reader(&self) -> io::Result<csv::Reader<Box<dyn io::Read + Send + 'static>>> {
@@ -540,7 +554,12 @@ impl Config {
             wtr.write_all("\u{FEFF}".as_bytes()).unwrap();
         }
 
-        csv::WriterBuilder::new()
+        self.writer_builder().from_writer(wtr)
+    }
+
+    fn writer_builder(&self) -> csv::WriterBuilder {
+        let mut builder = csv::WriterBuilder::new();
+        builder
             .flexible(self.flexible)
             .delimiter(self.delimiter)
             .terminator(self.terminator)
@@ -548,8 +567,8 @@ impl Config {
             .quote_style(self.quote_style)
             .double_quote(self.double_quote)
             .escape(self.escape.unwrap_or(b'\\'))
-            .buffer_capacity(self.write_buffer as usize)
-            .from_writer(wtr)
+            .buffer_capacity(self.write_buffer as usize);
+        builder
     }
 }
 
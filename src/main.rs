@@ -56,6 +56,7 @@ mod cmd;
 mod config;
 mod index;
 mod odhtcache;
+mod redaction;
 mod select;
 mod util;
 
@@ -93,11 +94,17 @@ fn main() -> QsvExitCode {
 
     enabled_commands.push_str(
         "    behead      Drop header from CSV file
+    bin         Discretize a numeric column into bins
     cat         Concatenate by row or column
+    cleanup     Remove orphaned scratch directories
     clipboard   Provide input from clipboard or output to clipboard
+    colops      Set operations on column values across two files
+    corr        Compute the correlation matrix of numeric columns
     count       Count records
     datefmt     Format date/datetime strings
     dedup       Remove redundant rows
+    dedupstream Remove duplicate rows within a sliding window of a stream
+    delta       Detect inserted, updated & deleted rows against a snapshot
     describegpt Infer extended metadata using a LLM
     diff        Find the difference between two CSVs
     edit        Replace a cell's value specified by row and column
@@ -119,7 +126,8 @@ fn main() -> QsvExitCode {
         "    fill        Fill empty values
     fixlengths  Makes all records have same length
     flatten     Show one field per line
-    fmt         Format CSV output (change field delimiter)\n",
+    fmt         Format CSV output (change field delimiter)
+    follow      Run a command on the records appended to a CSV\n",
     );
 
     #[cfg(all(feature = "foreach", feature = "feature_capable"))]
@@ -127,13 +135,33 @@ fn main() -> QsvExitCode {
 
     enabled_commands.push_str("    frequency   Show frequency tables\n");
 
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    enabled_commands
+        .push_str("    fromarrow   Convert Arrow IPC/Feather to CSV using the Pola.rs engine\n");
+
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    enabled_commands.push_str("    fromavro    Convert Avro to CSV using the Pola.rs engine\n");
+
+    enabled_commands.push_str("    fromgeojson Convert GeoJSON to CSV\n");
+
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    enabled_commands
+        .push_str("    fromparquet Convert Parquet to CSV using the Pola.rs engine\n");
+
+    #[cfg(all(feature = "to", feature = "feature_capable"))]
+    enabled_commands.push_str("    fromsqlite  Dump a SQLite table or query to CSV\n");
+
+    enabled_commands.push_str("    fwf         Convert a fixed-width file to CSV\n");
+
     #[cfg(all(feature = "geocode", not(feature = "lite")))]
     enabled_commands
         .push_str("    geocode     Geocodes a location against the Geonames cities database.\n");
 
     enabled_commands.push_str(
-        "    headers     Show header names
+        "    groupby     Group rows & aggregate columns
+    headers     Show header names
     help        Show this usage message
+    implode     Implode rows, joining a column's values
     index       Create CSV index for faster access
     input       Read CSVs w/ special quoting, skipping, trimming & transcoding rules
     join        Join CSV files\n",
@@ -144,14 +172,20 @@ fn main() -> QsvExitCode {
 
     enabled_commands.push_str(
         "    json        Convert JSON to CSV
-    jsonl       Convert newline-delimited JSON files to CSV\n",
+    jsonl       Convert newline-delimited JSON files to CSV
+    lag         Append the values of the previous or next rows\n",
     );
 
     #[cfg(all(feature = "luau", feature = "feature_capable"))]
     enabled_commands.push_str("    luau        Execute Luau script on CSV data\n");
 
     enabled_commands.push_str(
-        "    partition   Partition CSV data based on a column value
+        "    melt        Reshape wide CSV data to long
+    numformat   Format numbers for presentation
+    onehot      One-hot encode categorical columns
+    package     Create & verify Frictionless data package manifests
+    partition   Partition CSV data based on a column value
+    pivot       Reshape long CSV data to wide
     prompt      Open a file dialog to pick a file
     pseudo      Pseudonymise the values of a column\n",
     );
@@ -160,7 +194,9 @@ fn main() -> QsvExitCode {
     enabled_commands.push_str("    py          Evaluate a Python expression on CSV data\n");
 
     enabled_commands.push_str(
-        "    rename      Rename the columns of CSV data efficiently
+        "    rank        Add a rank or percentile column
+    rename      Rename the columns of CSV data efficiently
+    repl        Try out commands on a sample with a live preview
     replace     Replace patterns in CSV data
     reverse     Reverse rows of CSV data
     safenames   Modify a CSV's header names to db-safe names
@@ -169,6 +205,8 @@ fn main() -> QsvExitCode {
     search      Search CSV data with a regex
     searchset   Search CSV data with a regex set
     select      Select, re-order, duplicate or drop columns
+    shareprep   Package an anonymized, validated sample for sharing
+    sizeest     Estimate the size of a CSV in other formats
     slice       Slice records from CSV
     snappy      Compress/decompress data using the Snappy algorithm
     sniff       Quickly sniff CSV metadata
@@ -185,16 +223,42 @@ fn main() -> QsvExitCode {
     enabled_commands.push_str(
         "    stats       Infer data types and compute summary statistics
     table       Align CSV data into columns
-    tojsonl     Convert CSV to newline-delimited JSON\n",
+    togeojson   Convert CSV with coordinates to GeoJSON or WKT
+    tojsonl     Convert CSV to newline-delimited JSON
+    topn        Keep the top or bottom N rows of each group\n",
     );
 
     #[cfg(all(feature = "to", feature = "feature_capable"))]
     enabled_commands
         .push_str("    to          Convert CSVs to PostgreSQL/XLSX/SQLite/Data Package\n");
 
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    enabled_commands
+        .push_str("    toarrow     Convert a CSV to Arrow IPC/Feather using the Pola.rs engine\n");
+
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    enabled_commands.push_str("    toavro      Convert a CSV to Avro using the Pola.rs engine\n");
+
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    enabled_commands
+        .push_str("    toparquet   Convert a CSV to Parquet using the Pola.rs engine\n");
+
+    #[cfg(all(feature = "to", feature = "feature_capable"))]
+    enabled_commands
+        .push_str("    topostgres  Bulk-load a CSV into PostgreSQL with COPY\n");
+
+    #[cfg(all(feature = "to", feature = "feature_capable"))]
+    enabled_commands.push_str("    tosqlite    Load a CSV into a typed SQLite table\n");
+
+    #[cfg(all(feature = "to", feature = "feature_capable"))]
+    enabled_commands.push_str("    toxlsx      Convert CSVs to a typed Excel workbook\n");
+
     enabled_commands.push_str(
-        "    transpose   Transpose rows/columns of CSV data
-    validate    Validate CSV data for RFC4180-compliance or with JSON Schema",
+        "    transform   Normalize or scale numeric columns
+    transpose   Transpose rows/columns of CSV data
+    validate    Validate CSV data for RFC4180-compliance or with JSON Schema
+    window      Add rolling & cumulative aggregation columns
+    xml         Convert XML to CSV, a row per record element",
     );
     let num_commands = enabled_commands.split('\n').count();
 
@@ -316,6 +380,21 @@ sponsored by datHere - Data Infrastructure Engineering (https://qsv.datHere.com)
                 util::log_end(qsv_args, now);
                 QsvExitCode::EncodingError
             },
+            Err(CliError::MalformedCsv(msg)) => {
+                werr!("{msg}");
+                util::log_end(qsv_args, now);
+                QsvExitCode::MalformedCsv
+            },
+            Err(CliError::InvalidSchema(msg)) => {
+                werr!("{msg}");
+                util::log_end(qsv_args, now);
+                QsvExitCode::InvalidSchema
+            },
+            Err(CliError::ValidateIo(err)) => {
+                werr!("io error: {err}");
+                util::log_end(qsv_args, now);
+                QsvExitCode::IoError
+            },
         },
     }
 }
@@ -326,11 +405,17 @@ enum Command {
     #[cfg(all(feature = "apply", feature = "feature_capable"))]
     Apply,
     Behead,
+    Bin,
     Cat,
+    Cleanup,
     Clipboard,
+    Colops,
+    Corr,
     Count,
     Datefmt,
     Dedup,
+    Dedupstream,
+    Delta,
     Describegpt,
     Diff,
     Edit,
@@ -348,13 +433,26 @@ enum Command {
     FixLengths,
     Flatten,
     Fmt,
+    Follow,
     #[cfg(all(feature = "foreach", not(feature = "lite")))]
     ForEach,
     Frequency,
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    FromArrow,
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    FromAvro,
+    FromGeoJson,
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    FromParquet,
+    #[cfg(all(feature = "to", feature = "feature_capable"))]
+    FromSqlite,
+    Fwf,
     #[cfg(all(feature = "geocode", feature = "feature_capable"))]
     Geocode,
+    GroupBy,
     Headers,
     Help,
+    Implode,
     Index,
     Input,
     Join,
@@ -362,14 +460,22 @@ enum Command {
     JoinP,
     Json,
     Jsonl,
+    Lag,
     #[cfg(all(feature = "luau", feature = "feature_capable"))]
     Luau,
+    Melt,
+    NumFormat,
+    Onehot,
+    Package,
     Partition,
+    Pivot,
     Prompt,
     Pseudo,
     #[cfg(all(feature = "python", feature = "feature_capable"))]
     Py,
+    Rank,
     Rename,
+    Repl,
     Replace,
     Reverse,
     Safenames,
@@ -378,6 +484,8 @@ enum Command {
     Search,
     SearchSet,
     Select,
+    Shareprep,
+    Sizeest,
     Slice,
     Snappy,
     Sniff,
@@ -388,17 +496,35 @@ enum Command {
     SqlP,
     Stats,
     Table,
+    Transform,
     Transpose,
     #[cfg(all(feature = "to", feature = "feature_capable"))]
     To,
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    Toarrow,
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    Toavro,
+    Togeojson,
     Tojsonl,
+    Topn,
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    Toparquet,
+    #[cfg(all(feature = "to", feature = "feature_capable"))]
+    Topostgres,
+    #[cfg(all(feature = "to", feature = "feature_capable"))]
+    Tosqlite,
+    #[cfg(all(feature = "to", feature = "feature_capable"))]
+    Toxlsx,
     Validate,
+    Window,
+    Xml,
 }
 
 impl Command {
     fn run(self) -> CliResult<()> {
         let argv: Vec<_> = env::args().collect();
         let argv: Vec<_> = argv.iter().map(|s| &**s).collect();
+        let argv = redaction::init(&argv)?;
         let argv = &*argv;
 
         assert!(argv.len() > 1);
@@ -412,13 +538,19 @@ impl Command {
         CURRENT_COMMAND.get_or_init(|| argv[1].to_lowercase());
         match self {
             Command::Behead => cmd::behead::run(argv),
+            Command::Bin => cmd::bin::run(argv),
             #[cfg(all(feature = "apply", feature = "feature_capable"))]
             Command::Apply => cmd::apply::run(argv),
             Command::Cat => cmd::cat::run(argv),
+            Command::Cleanup => cmd::cleanup::run(argv),
             Command::Clipboard => cmd::clipboard::run(argv),
+            Command::Colops => cmd::colops::run(argv),
+            Command::Corr => cmd::corr::run(argv),
             Command::Count => cmd::count::run(argv),
             Command::Datefmt => cmd::datefmt::run(argv),
             Command::Dedup => cmd::dedup::run(argv),
+            Command::Dedupstream => cmd::dedupstream::run(argv),
+            Command::Delta => cmd::delta::run(argv),
             Command::Describegpt => cmd::describegpt::run(argv),
             Command::Diff => cmd::diff::run(argv),
             Command::Edit => cmd::edit::run(argv),
@@ -438,15 +570,28 @@ impl Command {
             Command::FixLengths => cmd::fixlengths::run(argv),
             Command::Flatten => cmd::flatten::run(argv),
             Command::Fmt => cmd::fmt::run(argv),
+            Command::Follow => cmd::follow::run(argv),
             Command::Frequency => cmd::frequency::run(argv),
+            #[cfg(all(feature = "polars", feature = "feature_capable"))]
+            Command::FromArrow => cmd::fromarrow::run(argv),
+            #[cfg(all(feature = "polars", feature = "feature_capable"))]
+            Command::FromAvro => cmd::fromavro::run(argv),
+            Command::FromGeoJson => cmd::fromgeojson::run(argv),
+            #[cfg(all(feature = "polars", feature = "feature_capable"))]
+            Command::FromParquet => cmd::fromparquet::run(argv),
+            #[cfg(all(feature = "to", feature = "feature_capable"))]
+            Command::FromSqlite => cmd::fromsqlite::run(argv),
+            Command::Fwf => cmd::fwf::run(argv),
             #[cfg(all(feature = "geocode", feature = "feature_capable"))]
             Command::Geocode => cmd::geocode::run(argv),
+            Command::GroupBy => cmd::groupby::run(argv),
             Command::Headers => cmd::headers::run(argv),
             Command::Help => {
                 wout!("{USAGE}");
                 util::qsv_check_for_update(true, false)?;
                 Ok(())
             },
+            Command::Implode => cmd::implode::run(argv),
             Command::Index => cmd::index::run(argv),
             Command::Input => cmd::input::run(argv),
             Command::Join => cmd::join::run(argv),
@@ -454,14 +599,22 @@ impl Command {
             Command::JoinP => cmd::joinp::run(argv),
             Command::Json => cmd::json::run(argv),
             Command::Jsonl => cmd::jsonl::run(argv),
+            Command::Lag => cmd::lag::run(argv),
             #[cfg(all(feature = "luau", feature = "feature_capable"))]
             Command::Luau => cmd::luau::run(argv),
+            Command::Melt => cmd::melt::run(argv),
+            Command::NumFormat => cmd::numformat::run(argv),
+            Command::Onehot => cmd::onehot::run(argv),
+            Command::Package => cmd::package::run(argv),
             Command::Partition => cmd::partition::run(argv),
+            Command::Pivot => cmd::pivot::run(argv),
             Command::Prompt => cmd::prompt::run(argv),
             Command::Pseudo => cmd::pseudo::run(argv),
             #[cfg(all(feature = "python", feature = "feature_capable"))]
             Command::Py => cmd::python::run(argv),
+            Command::Rank => cmd::rank::run(argv),
             Command::Rename => cmd::rename::run(argv),
+            Command::Repl => cmd::repl::run(argv),
             Command::Replace => cmd::replace::run(argv),
             Command::Reverse => cmd::reverse::run(argv),
             Command::Safenames => cmd::safenames::run(argv),
@@ -470,6 +623,8 @@ impl Command {
             Command::Search => cmd::search::run(argv),
             Command::SearchSet => cmd::searchset::run(argv),
             Command::Select => cmd::select::run(argv),
+            Command::Shareprep => cmd::shareprep::run(argv),
+            Command::Sizeest => cmd::sizeest::run(argv),
             Command::Slice => cmd::slice::run(argv),
             Command::Snappy => cmd::snappy::run(argv),
             Command::Sniff => cmd::sniff::run(argv),
@@ -480,11 +635,28 @@ impl Command {
             Command::SqlP => cmd::sqlp::run(argv),
             Command::Stats => cmd::stats::run(argv),
             Command::Table => cmd::table::run(argv),
+            Command::Transform => cmd::transform::run(argv),
             Command::Transpose => cmd::transpose::run(argv),
             #[cfg(all(feature = "to", feature = "feature_capable"))]
             Command::To => cmd::to::run(argv),
+            #[cfg(all(feature = "polars", feature = "feature_capable"))]
+            Command::Toarrow => cmd::toarrow::run(argv),
+            #[cfg(all(feature = "polars", feature = "feature_capable"))]
+            Command::Toavro => cmd::toavro::run(argv),
+            Command::Togeojson => cmd::togeojson::run(argv),
             Command::Tojsonl => cmd::tojsonl::run(argv),
+            Command::Topn => cmd::topn::run(argv),
+            #[cfg(all(feature = "polars", feature = "feature_capable"))]
+            Command::Toparquet => cmd::toparquet::run(argv),
+            #[cfg(all(feature = "to", feature = "feature_capable"))]
+            Command::Topostgres => cmd::topostgres::run(argv),
+            #[cfg(all(feature = "to", feature = "feature_capable"))]
+            Command::Tosqlite => cmd::tosqlite::run(argv),
+            #[cfg(all(feature = "to", feature = "feature_capable"))]
+            Command::Toxlsx => cmd::toxlsx::run(argv),
             Command::Validate => cmd::validate::run(argv),
+            Command::Window => cmd::window::run(argv),
+            Command::Xml => cmd::xml::run(argv),
         }
     }
 }
@@ -97,6 +97,7 @@ mod cmd;
 mod config;
 mod index;
 mod odhtcache;
+mod redaction;
 mod select;
 mod util;
 
@@ -231,6 +232,21 @@ Please choose one of the following commands:",
                 util::log_end(qsv_args, now);
                 QsvExitCode::EncodingError
             },
+            Err(CliError::MalformedCsv(msg)) => {
+                werr!("{msg}");
+                util::log_end(qsv_args, now);
+                QsvExitCode::MalformedCsv
+            },
+            Err(CliError::InvalidSchema(msg)) => {
+                werr!("{msg}");
+                util::log_end(qsv_args, now);
+                QsvExitCode::InvalidSchema
+            },
+            Err(CliError::ValidateIo(err)) => {
+                werr!("io error: {err}");
+                util::log_end(qsv_args, now);
+                QsvExitCode::IoError
+            },
         },
     }
 }
@@ -280,6 +296,7 @@ impl Command {
     fn run(self) -> CliResult<()> {
         let argv: Vec<_> = env::args().collect();
         let argv: Vec<_> = argv.iter().map(|s| &**s).collect();
+        let argv = redaction::init(&argv)?;
         let argv = &*argv;
 
         assert!(argv.len() > 1);
@@ -20,11 +20,17 @@ macro_rules! command_list {
     () => {
         "
     behead      Drop header from CSV file
+    bin         Discretize a numeric column into bins
     cat         Concatenate by row or column
+    cleanup     Remove orphaned scratch directories
     clipboard   Provide input from clipboard or output to clipboard
+    colops      Set operations on column values across two files
+    corr        Compute the correlation matrix of numeric columns
     count       Count records
     datefmt     Format date/datetime columns
     dedup       Remove redundant rows
+    dedupstream Remove duplicate rows within a sliding window of a stream
+    delta       Detect inserted, updated & deleted rows against a snapshot
     describegpt Infer extended metadata using a LLM
     diff        Find the difference between two CSVs
     edit        Replace a cell's value specified by row and column
@@ -38,18 +44,31 @@ macro_rules! command_list {
     fixlengths  Makes all records have same length
     flatten     Show one field per line
     fmt         Format CSV output (change field delimiter)
+    follow      Run a command on the records appended to a CSV
     frequency   Show frequency tables
+    fromgeojson Convert GeoJSON to CSV
+    fwf         Convert a fixed-width file to CSV
+    groupby     Group rows & aggregate columns
     headers     Show header names
     help        Show this usage message
+    implode     Implode rows, joining a column's values
     index       Create CSV index for faster access
     input       Read CSVs w/ special quoting, skipping, trimming & transcoding rules
     join        Join CSV files
     json        Convert JSON to CSV
     jsonl       Convert newline-delimited JSON files to CSV
+    lag         Append the values of the previous or next rows
+    melt        Reshape wide CSV data to long
+    numformat   Format numbers for presentation
+    onehot      One-hot encode categorical columns
+    package     Create & verify Frictionless data package manifests
     partition   Partition CSV data based on a column value
+    pivot       Reshape long CSV data to wide
     prompt      Open a file dialog to pick a file
     pseudo      Pseudonymise the values of a column
+    rank        Add a rank or percentile column
     rename      Rename the columns of CSV data efficiently
+    repl        Try out commands on a sample with a live preview
     replace     Replace patterns in CSV data
     reverse     Reverse rows of CSV data
     safenames   Modify a CSV's header names to db-safe names
@@ -58,6 +77,8 @@ macro_rules! command_list {
     search      Search CSV data with a regex
     searchset   Search CSV data with a regex set
     select      Select, re-order, duplicate or drop columns
+    shareprep   Package an anonymized, validated sample for sharing
+    sizeest     Estimate the size of a CSV in other formats
     slice       Slice records from CSV
     snappy      Compress/decompress data using the Snappy algorithm
     sniff       Quickly sniff CSV metadata
@@ -66,9 +87,14 @@ macro_rules! command_list {
     split       Split CSV data into many files
     stats       Infer data types and compute summary statistics
     table       Align CSV data into columns
+    togeojson   Convert CSV with coordinates to GeoJSON or WKT
     tojsonl     Convert CSV to newline-delimited JSON
+    topn        Keep the top or bottom N rows of each group
+    transform   Normalize or scale numeric columns
     transpose   Transpose rows/columns of CSV data
     validate    Validate CSV data for RFC4180-compliance or with JSON Schema
+    window      Add rolling & cumulative aggregation columns
+    xml         Convert XML to CSV, a row per record element
 
 sponsored by datHere - Data Infrastructure Engineering (https://qsv.datHere.com)
 "
@@ -79,6 +105,7 @@ mod cmd;
 mod config;
 mod index;
 mod odhtcache;
+mod redaction;
 mod select;
 mod util;
 
@@ -219,6 +246,21 @@ Please choose one of the following commands:",
                 util::log_end(qsv_args, now);
                 QsvExitCode::EncodingError
             },
+            Err(CliError::MalformedCsv(msg)) => {
+                werr!("{msg}");
+                util::log_end(qsv_args, now);
+                QsvExitCode::MalformedCsv
+            },
+            Err(CliError::InvalidSchema(msg)) => {
+                werr!("{msg}");
+                util::log_end(qsv_args, now);
+                QsvExitCode::InvalidSchema
+            },
+            Err(CliError::ValidateIo(err)) => {
+                werr!("io error: {err}");
+                util::log_end(qsv_args, now);
+                QsvExitCode::IoError
+            },
         },
     }
 }
@@ -227,11 +269,17 @@ Please choose one of the following commands:",
 #[serde(rename_all = "lowercase")]
 enum Command {
     Behead,
+    Bin,
     Cat,
+    Cleanup,
     Clipboard,
+    Colops,
+    Corr,
     Count,
     Datefmt,
     Dedup,
+    Dedupstream,
+    Delta,
     Describegpt,
     Diff,
     Edit,
@@ -245,18 +293,31 @@ enum Command {
     FixLengths,
     Flatten,
     Fmt,
+    Follow,
     Frequency,
+    FromGeoJson,
+    Fwf,
+    GroupBy,
     Headers,
     Help,
+    Implode,
     Index,
     Input,
     Join,
     Json,
     Jsonl,
+    Lag,
+    Melt,
+    NumFormat,
+    Onehot,
+    Package,
     Partition,
+    Pivot,
     Prompt,
     Pseudo,
+    Rank,
     Rename,
+    Repl,
     Replace,
     Reverse,
     Safenames,
@@ -265,6 +326,8 @@ enum Command {
     Search,
     SearchSet,
     Select,
+    Shareprep,
+    Sizeest,
     Slice,
     Snappy,
     Sniff,
@@ -273,15 +336,21 @@ enum Command {
     Split,
     Stats,
     Table,
+    Togeojson,
     Tojsonl,
+    Topn,
+    Transform,
     Transpose,
     Validate,
+    Window,
+    Xml,
 }
 
 impl Command {
     fn run(self) -> CliResult<()> {
         let argv: Vec<_> = env::args().collect();
         let argv: Vec<_> = argv.iter().map(|s| &**s).collect();
+        let argv = redaction::init(&argv)?;
         let argv = &*argv;
 
         assert!(argv.len() > 1);
@@ -294,11 +363,17 @@ impl Command {
         CURRENT_COMMAND.get_or_init(|| argv[1].to_lowercase());
         match self {
             Command::Behead => cmd::behead::run(argv),
+            Command::Bin => cmd::bin::run(argv),
             Command::Cat => cmd::cat::run(argv),
+            Command::Cleanup => cmd::cleanup::run(argv),
             Command::Clipboard => cmd::clipboard::run(argv),
+            Command::Colops => cmd::colops::run(argv),
+            Command::Corr => cmd::corr::run(argv),
             Command::Count => cmd::count::run(argv),
             Command::Datefmt => cmd::datefmt::run(argv),
             Command::Dedup => cmd::dedup::run(argv),
+            Command::Dedupstream => cmd::dedupstream::run(argv),
+            Command::Delta => cmd::delta::run(argv),
             Command::Describegpt => cmd::describegpt::run(argv),
             Command::Diff => cmd::diff::run(argv),
             Command::Edit => cmd::edit::run(argv),
@@ -312,22 +387,35 @@ impl Command {
             Command::FixLengths => cmd::fixlengths::run(argv),
             Command::Flatten => cmd::flatten::run(argv),
             Command::Fmt => cmd::fmt::run(argv),
+            Command::Follow => cmd::follow::run(argv),
             Command::Frequency => cmd::frequency::run(argv),
+            Command::FromGeoJson => cmd::fromgeojson::run(argv),
+            Command::Fwf => cmd::fwf::run(argv),
+            Command::GroupBy => cmd::groupby::run(argv),
             Command::Headers => cmd::headers::run(argv),
             Command::Help => {
                 wout!("{USAGE}");
                 util::qsv_check_for_update(true, false)?;
                 Ok(())
             },
+            Command::Implode => cmd::implode::run(argv),
             Command::Index => cmd::index::run(argv),
             Command::Input => cmd::input::run(argv),
             Command::Join => cmd::join::run(argv),
             Command::Json => cmd::json::run(argv),
             Command::Jsonl => cmd::jsonl::run(argv),
+            Command::Lag => cmd::lag::run(argv),
+            Command::Melt => cmd::melt::run(argv),
+            Command::NumFormat => cmd::numformat::run(argv),
+            Command::Onehot => cmd::onehot::run(argv),
+            Command::Package => cmd::package::run(argv),
             Command::Partition => cmd::partition::run(argv),
+            Command::Pivot => cmd::pivot::run(argv),
             Command::Prompt => cmd::prompt::run(argv),
             Command::Pseudo => cmd::pseudo::run(argv),
+            Command::Rank => cmd::rank::run(argv),
             Command::Rename => cmd::rename::run(argv),
+            Command::Repl => cmd::repl::run(argv),
             Command::Replace => cmd::replace::run(argv),
             Command::Reverse => cmd::reverse::run(argv),
             Command::Safenames => cmd::safenames::run(argv),
@@ -336,6 +424,8 @@ impl Command {
             Command::Search => cmd::search::run(argv),
             Command::SearchSet => cmd::searchset::run(argv),
             Command::Select => cmd::select::run(argv),
+            Command::Shareprep => cmd::shareprep::run(argv),
+            Command::Sizeest => cmd::sizeest::run(argv),
             Command::Slice => cmd::slice::run(argv),
             Command::Snappy => cmd::snappy::run(argv),
             Command::Sniff => cmd::sniff::run(argv),
@@ -344,9 +434,14 @@ impl Command {
             Command::Split => cmd::split::run(argv),
             Command::Stats => cmd::stats::run(argv),
             Command::Table => cmd::table::run(argv),
+            Command::Togeojson => cmd::togeojson::run(argv),
             Command::Tojsonl => cmd::tojsonl::run(argv),
+            Command::Topn => cmd::topn::run(argv),
+            Command::Transform => cmd::transform::run(argv),
             Command::Transpose => cmd::transpose::run(argv),
             Command::Validate => cmd::validate::run(argv),
+            Command::Window => cmd::window::run(argv),
+            Command::Xml => cmd::xml::run(argv),
         }
     }
 }
@@ -0,0 +1,394 @@
+// Column-level redaction profiles, applied to the CSV output of every command.
+//
+// A redaction policy is a JSON file mapping profile names to the actions to apply to
+// columns, e.g.:
+//
+//   {
+//     "analyst": {"ssn": "drop", "email": "mask:4", "birth_date": "generalize:year"},
+//     "vendor": {"ssn": "drop", "email": "drop", "age": "generalize:10"}
+//   }
+//
+// The policy file is set with the QSV_REDACTION_POLICY environment variable, and the
+// active profile with the `--profile <name>` option of any command (or the
+// QSV_REDACTION_PROFILE environment variable). The CSV written through
+// `Config::writer` is then parsed back into records as it's written, so the profile
+// is applied to the output of any command, using the column names of its header row.
+// Only the commands that write their output that way, keeping the column names of their
+// input, can be redacted. The other commands, and outputs without a header row, are
+// refused when a profile is active, so nothing is written unredacted.
+use std::{fs, io, sync::OnceLock};
+
+use ahash::AHashMap;
+use csv::ByteRecord;
+use serde_json::Value;
+
+use crate::{util, CliResult};
+
+static ACTIVE_PROFILE: OnceLock<Option<Profile>> = OnceLock::new();
+
+/// the commands that write their output through `Config::writer`, with the column names
+/// of their input - so the profile's columns can be matched to the output's header row.
+/// Every other command is refused while a profile is active.
+const REDACTED_COMMANDS: &[&str] = &[
+    "cat",
+    "colops",
+    "dedup",
+    "dedupstream",
+    "edit",
+    "exclude",
+    "fill",
+    "fixlengths",
+    "input",
+    "join",
+    "numformat",
+    "pseudo",
+    "replace",
+    "reverse",
+    "sample",
+    "search",
+    "searchset",
+    "select",
+    "slice",
+    "sort",
+    "topn",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+    /// remove the column
+    Drop,
+    /// replace all but the last N characters with *
+    Mask(usize),
+    /// keep the year of dates
+    GeneralizeYear,
+    /// keep the year & month of dates
+    GeneralizeMonth,
+    /// replace numbers with the bin of the given width they're in, e.g. 30-39
+    GeneralizeBin(f64),
+    /// keep the first N characters, replacing the others with *
+    GeneralizePrefix(usize),
+}
+
+impl Action {
+    fn parse(s: &str) -> Result<Self, String> {
+        let action = s.trim().to_ascii_lowercase();
+        let invalid = || {
+            format!(
+                "Invalid redaction action: {s}. Valid actions are drop, mask, mask:N, \
+                 generalize:year, generalize:month, generalize:N and generalize:prefix:N."
+            )
+        };
+        match action.as_str() {
+            "drop" => Ok(Action::Drop),
+            "mask" => Ok(Action::Mask(0)),
+            "generalize:year" => Ok(Action::GeneralizeYear),
+            "generalize:month" => Ok(Action::GeneralizeMonth),
+            _ => {
+                if let Some(keep) = action.strip_prefix("mask:") {
+                    keep.parse().map(Action::Mask).map_err(|_| invalid())
+                } else if let Some(keep) = action.strip_prefix("generalize:prefix:") {
+                    keep.parse()
+                        .map(Action::GeneralizePrefix)
+                        .map_err(|_| invalid())
+                } else if let Some(width) = action.strip_prefix("generalize:") {
+                    match width.parse::<f64>() {
+                        Ok(width) if width > 0.0 && width.is_finite() => {
+                            Ok(Action::GeneralizeBin(width))
+                        },
+                        _ => Err(invalid()),
+                    }
+                } else {
+                    Err(invalid())
+                }
+            },
+        }
+    }
+
+    /// the redacted value. Empty values are left empty, and values that can't be
+    /// generalized (e.g. a date column value that's not a date) are emptied.
+    fn apply(self, value: &[u8]) -> Vec<u8> {
+        if value.is_empty() {
+            return Vec::new();
+        }
+        let value = String::from_utf8_lossy(value);
+        let redacted = match self {
+            Action::Drop => String::new(),
+            Action::Mask(keep) => {
+                let len = value.chars().count();
+                let masked_len = if len <= keep { len } else { len - keep };
+                let mut masked = "*".repeat(masked_len);
+                masked.extend(value.chars().skip(masked_len));
+                masked
+            },
+            Action::GeneralizeYear | Action::GeneralizeMonth => {
+                let prefer_dmy = util::get_envvar_flag("QSV_PREFER_DMY");
+                match qsv_dateparser::parse_with_preference(value.trim(), prefer_dmy) {
+                    Ok(date) if self == Action::GeneralizeYear => date.format("%Y").to_string(),
+                    Ok(date) => date.format("%Y-%m").to_string(),
+                    Err(_) => String::new(),
+                }
+            },
+            Action::GeneralizeBin(width) => match value.trim().parse::<f64>() {
+                Ok(number) if number.is_finite() => {
+                    let low = (number / width).floor() * width;
+                    if width.fract() == 0.0 && number.fract() == 0.0 {
+                        // integer bins include their upper bound, e.g. 30-39
+                        format!("{low}-{}", low + width - 1.0)
+                    } else {
+                        format!("{low}-{}", low + width)
+                    }
+                },
+                _ => String::new(),
+            },
+            Action::GeneralizePrefix(keep) => {
+                let len = value.chars().count();
+                let mut generalized: String = value.chars().take(keep).collect();
+                generalized.push_str(&"*".repeat(len.saturating_sub(keep)));
+                generalized
+            },
+        };
+        redacted.into_bytes()
+    }
+}
+
+/// the redaction actions of a profile, keyed by column name
+#[derive(Debug)]
+pub struct Profile {
+    actions: AHashMap<String, Action>,
+}
+
+impl Profile {
+    /// the action of each column of a header row
+    fn column_actions(&self, headers: &ByteRecord) -> Vec<Option<Action>> {
+        headers
+            .iter()
+            .map(|header| {
+                let header = String::from_utf8_lossy(header);
+                self.actions.get(header.as_ref()).copied()
+            })
+            .collect()
+    }
+}
+
+/// load a profile of a redaction policy file
+fn load_profile(policy_path: &str, name: &str) -> CliResult<Profile> {
+    let policy: Value = match fs::read_to_string(policy_path) {
+        Ok(contents) => match serde_json::from_str(&contents) {
+            Ok(policy) => policy,
+            Err(e) => {
+                return fail_incorrectusage_clierror!("Invalid redaction policy {policy_path}: {e}")
+            },
+        },
+        Err(e) => {
+            return fail_incorrectusage_clierror!("Cannot read redaction policy {policy_path}: {e}")
+        },
+    };
+    let Some(profiles) = policy.as_object() else {
+        return fail_incorrectusage_clierror!(
+            "Invalid redaction policy {policy_path}: expected an object of profiles."
+        );
+    };
+    let Some(profile) = profiles.get(name) else {
+        return fail_incorrectusage_clierror!(
+            "Unknown redaction profile \"{name}\". The profiles of {policy_path} are: {}.",
+            profiles
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    };
+    let Some(columns) = profile.as_object() else {
+        return fail_incorrectusage_clierror!(
+            "Invalid redaction profile \"{name}\": expected an object of column actions."
+        );
+    };
+
+    let mut actions = AHashMap::with_capacity(columns.len());
+    for (column, action) in columns {
+        let action = match action.as_str().map(Action::parse) {
+            Some(Ok(action)) => action,
+            Some(Err(e)) => {
+                return fail_incorrectusage_clierror!(
+                    "Column \"{column}\" of redaction profile \"{name}\": {e}"
+                )
+            },
+            None => {
+                return fail_incorrectusage_clierror!(
+                    "Column \"{column}\" of redaction profile \"{name}\": expected an action \
+                     string."
+                )
+            },
+        };
+        actions.insert(column.clone(), action);
+    }
+    Ok(Profile { actions })
+}
+
+/// remove the `--profile <name>` option from a command's arguments (before any `--`),
+/// and activate the profile - or the one of the QSV_REDACTION_PROFILE environment
+/// variable - from the QSV_REDACTION_POLICY file.
+pub fn init<'a>(argv: &[&'a str]) -> CliResult<Vec<&'a str>> {
+    let mut profile_arg: Option<String> = None;
+    let mut args: Vec<&str> = Vec::with_capacity(argv.len());
+    let mut iter = argv.iter();
+    while let Some(&arg) = iter.next() {
+        if arg == "--" {
+            args.push(arg);
+            args.extend(iter);
+            break;
+        }
+        if arg == "--profile" {
+            match iter.next() {
+                Some(name) => profile_arg = Some((*name).to_string()),
+                None => return fail_incorrectusage_clierror!("--profile requires a profile name."),
+            }
+        } else if let Some(name) = arg.strip_prefix("--profile=") {
+            profile_arg = Some(name.to_string());
+        } else {
+            args.push(arg);
+        }
+    }
+
+    let profile_name = profile_arg.or_else(|| {
+        std::env::var("QSV_REDACTION_PROFILE")
+            .ok()
+            .filter(|name| !name.is_empty())
+    });
+    let profile = match profile_name {
+        Some(name) => {
+            let Ok(policy_path) = std::env::var("QSV_REDACTION_POLICY") else {
+                return fail_incorrectusage_clierror!(
+                    "Redaction profile \"{name}\" is set, but the QSV_REDACTION_POLICY \
+                     environment variable with the policy file isn't."
+                );
+            };
+            let command = args.get(1).copied().unwrap_or_default();
+            if !REDACTED_COMMANDS.contains(&command) {
+                return fail_incorrectusage_clierror!(
+                    "Redaction profile \"{name}\" is set, but the {command} command doesn't \
+                     support redaction."
+                );
+            }
+            let profile = load_profile(&policy_path, &name)?;
+            log::info!("redaction profile {name}: {profile:?}");
+            Some(profile)
+        },
+        None => None,
+    };
+    ACTIVE_PROFILE.get_or_init(|| profile);
+    Ok(args)
+}
+
+/// the active redaction profile, if any
+pub fn active_profile() -> Option<&'static Profile> {
+    ACTIVE_PROFILE.get().and_then(Option::as_ref)
+}
+
+/// A writer that applies a redaction profile to the CSV written to it: complete records
+/// are parsed back, redacted and written to the inner CSV writer. The first record is
+/// taken as the header row, whose column names select the columns to redact - outputs
+/// without one are refused by `util::get_args`.
+pub struct RedactingWriter<W: io::Write> {
+    profile:   &'static Profile,
+    wtr:       csv::Writer<W>,
+    delimiter: u8,
+    quote:     u8,
+    // the written bytes of the records that aren't complete yet
+    pending:   Vec<u8>,
+    // the number of pending bytes scanned for record ends, and whether they end in quotes
+    scanned:   usize,
+    in_quotes: bool,
+    // the actions of the columns, once the header row is written
+    actions:   Option<Vec<Option<Action>>>,
+}
+
+impl<W: io::Write> RedactingWriter<W> {
+    pub fn new(profile: &'static Profile, wtr: csv::Writer<W>, delimiter: u8, quote: u8) -> Self {
+        Self {
+            profile,
+            wtr,
+            delimiter,
+            quote,
+            pending: Vec::new(),
+            scanned: 0,
+            in_quotes: false,
+            actions: None,
+        }
+    }
+
+    /// redact the first `len` pending bytes, which are complete records
+    fn redact_pending(&mut self, len: usize) -> io::Result<()> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .from_reader(&self.pending[..len]);
+        let mut record = ByteRecord::new();
+        let mut redacted = ByteRecord::new();
+        while rdr.read_byte_record(&mut record)? {
+            let is_header = self.actions.is_none();
+            let actions = self
+                .actions
+                .get_or_insert_with(|| self.profile.column_actions(&record));
+            redacted.clear();
+            for (idx, field) in record.iter().enumerate() {
+                match actions.get(idx).copied().flatten() {
+                    Some(Action::Drop) => {},
+                    // the header row keeps its column names
+                    Some(action) if !is_header => redacted.push_field(&action.apply(field)),
+                    _ => redacted.push_field(field),
+                }
+            }
+            self.wtr.write_byte_record(&redacted)?;
+        }
+        self.pending.drain(..len);
+        self.scanned -= len;
+        Ok(())
+    }
+}
+
+impl<W: io::Write> io::Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        // the end of the last complete record - a newline that's not in a quoted field
+        let mut complete = 0;
+        for (idx, &byte) in self.pending[self.scanned..].iter().enumerate() {
+            if byte == self.quote {
+                self.in_quotes = !self.in_quotes;
+            } else if byte == b'\n' && !self.in_quotes {
+                complete = self.scanned + idx + 1;
+            }
+        }
+        self.scanned = self.pending.len();
+        if complete > 0 {
+            self.redact_pending(complete)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // a last record without a terminator
+        if !self.pending.is_empty() && !self.in_quotes {
+            self.redact_pending(self.pending.len())?;
+        }
+        self.wtr.flush()
+    }
+}
+
+impl<W: io::Write> Drop for RedactingWriter<W> {
+    fn drop(&mut self) {
+        // the commands flush their output, returning its errors. Drop can't return them,
+        // so the errors of an output that wasn't flushed are reported instead.
+        if !self.pending.is_empty() {
+            if let Err(e) = self.redact_pending(self.pending.len()) {
+                werr!("redaction error: {e}");
+                return;
+            }
+        }
+        if let Err(e) = self.wtr.flush() {
+            werr!("redaction error: {e}");
+        }
+    }
+}
@@ -72,6 +72,23 @@ pub struct SchemaArgs {
     pub flag_delimiter:       Option<Delimiter>,
     pub arg_input:            Option<String>,
     pub flag_memcheck:        bool,
+    /// the columns that never get enum constraints
+    #[serde(default)]
+    pub flag_no_enum:         Option<SelectColumns>,
+    /// the per-column overrides of flag_enum_threshold
+    #[serde(default)]
+    pub enum_threshold_overrides: Vec<(String, u64)>,
+}
+
+impl SchemaArgs {
+    /// the enum cardinality threshold of a column, i.e. its override if any,
+    /// otherwise flag_enum_threshold
+    pub fn enum_threshold(&self, column: &str) -> u64 {
+        self.enum_threshold_overrides
+            .iter()
+            .find(|(name, _)| name == column)
+            .map_or(self.flag_enum_threshold, |(_, threshold)| *threshold)
+    }
 }
 
 #[inline]
@@ -151,6 +168,26 @@ pub fn timeout_secs(timeout: u16) -> Result<u64, String> {
     Ok(timeout as u64)
 }
 
+/// the seed for commands with a --seed option - the --seed option if set, otherwise
+/// the QSV_SEED environment variable, so a pipeline's random output can be reproduced
+/// by setting QSV_SEED once. The seed is logged when logging is enabled.
+pub fn rng_seed(flag_seed: Option<u64>) -> Result<Option<u64>, String> {
+    let seed = match flag_seed {
+        Some(seed) => Some(seed),
+        None => match env::var("QSV_SEED") {
+            Ok(val) => match val.trim().parse::<u64>() {
+                Ok(seed) => Some(seed),
+                Err(_) => return fail_format!("QSV_SEED must be an unsigned integer: {val}"),
+            },
+            Err(_) => None,
+        },
+    };
+    if let Some(seed) = seed {
+        log::info!("SEED: {seed}");
+    }
+    Ok(seed)
+}
+
 /// sets custom user agent
 /// if user agent is not set, then use the default user agent
 /// it supports four special LITERALs: $QSV_BIN_NAME, $QSV_VERSION, $QSV_TARGET, $QSV_KIND
@@ -437,6 +474,23 @@ pub fn prep_progress(progress: &ProgressBar, record_count: u64) {
     log::info!("Progress started... {record_count} records");
 }
 
+/// like prep_progress, but for progress in bytes read of a file of `file_size` bytes,
+/// for when counting its records upfront would take too long
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub fn prep_progress_bytes(progress: &ProgressBar, file_size: u64) {
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "[{elapsed_precise}] [{wide_bar} {percent}%] {bytes}/{total_bytes}{msg} \
+                 ({bytes_per_sec} - {eta})",
+            )
+            .unwrap(),
+    );
+    progress.set_length(file_size);
+
+    log::info!("Progress started... {file_size} bytes");
+}
+
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub fn finish_progress(progress: &ProgressBar) {
     progress.set_style(
@@ -454,6 +508,25 @@ pub fn finish_progress(progress: &ProgressBar) {
     }
 }
 
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub fn finish_progress_bytes(progress: &ProgressBar) {
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "[{elapsed_precise}] [{wide_bar} {percent}%] {total_bytes}{msg} ({bytes_per_sec})",
+            )
+            .unwrap(),
+    );
+
+    if progress.length().unwrap_or_default() == progress.position() {
+        progress.finish();
+        log::info!("Progress done... {}", progress.message());
+    } else {
+        progress.abandon();
+        log::info!("Progress abandoned... {}", progress.message());
+    }
+}
+
 #[cfg(all(any(feature = "fetch", feature = "geocode"), not(feature = "lite")))]
 macro_rules! update_cache_info {
     ($progress:expr, $cache_instance:expr) => {
@@ -500,13 +573,25 @@ pub fn get_args<T>(usage: &str, argv: &[&str]) -> CliResult<T>
 where
     T: DeserializeOwned,
 {
-    Docopt::new(usage)
-        .and_then(|d| {
-            d.argv(argv.iter().copied())
-                .version(Some(version()))
-                .deserialize()
-        })
-        .map_err(From::from)
+    let args = Docopt::new(usage).and_then(|d| {
+        d.argv(argv.iter().copied())
+            .version(Some(version()))
+            .parse()
+    })?;
+    // the columns of an output without a header row can't be matched to a redaction profile
+    if args.get_bool("--no-headers") && crate::redaction::active_profile().is_some() {
+        return fail_incorrectusage_clierror!(
+            "--no-headers can't be used with a redaction profile, as the profile's columns are \
+             matched by the column names of the header row."
+        );
+    }
+    // e.g. search & slice write their --json output as is
+    if args.get_bool("--json") && crate::redaction::active_profile().is_some() {
+        return fail_incorrectusage_clierror!(
+            "--json can't be used with a redaction profile, as the JSON output isn't redacted."
+        );
+    }
+    args.deserialize().map_err(From::from)
 }
 
 #[inline]
@@ -544,6 +629,227 @@ pub fn errif_greater_one_stdin(inps: &[Config]) -> Result<(), String> {
     Ok(())
 }
 
+// the number of rows checked against the column types of a --locked-schema
+const LOCKED_SCHEMA_SAMPLE_ROWS: u64 = 1_000;
+
+/// check that the input of `conf` matches a pinned JSON Schema (as generated by `qsv schema`):
+/// the same columns in the same order, and the first LOCKED_SCHEMA_SAMPLE_ROWS rows
+/// conforming to the declared column types. Returns an error with the differences otherwise.
+pub fn check_locked_schema(conf: &Config, schema_path: &str) -> CliResult<()> {
+    use serde_json::Value;
+
+    if conf.is_stdin() {
+        return fail_incorrectusage_clierror!("--locked-schema cannot be used with <stdin>.");
+    }
+    if conf.no_headers {
+        return fail_incorrectusage_clierror!("--locked-schema cannot be used with --no-headers.");
+    }
+
+    let schema: Value = match fs::read_to_string(schema_path) {
+        Ok(schema) => serde_json::from_str(&schema)
+            .map_err(|e| format!("Cannot parse locked schema {schema_path}: {e}"))?,
+        Err(e) => return fail_clierror!("Cannot read locked schema {schema_path}: {e}"),
+    };
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return fail_clierror!("Locked schema {schema_path} has no \"properties\".");
+    };
+
+    let mut rdr = conf.reader()?;
+    let headers: Vec<String> = rdr
+        .byte_headers()?
+        .iter()
+        .map(|h| String::from_utf8_lossy(h).to_string())
+        .collect();
+
+    let mut differences = Vec::new();
+    for column in properties.keys() {
+        if !headers.contains(column) {
+            differences.push(format!("- missing column \"{column}\""));
+        }
+    }
+    for column in &headers {
+        if !properties.contains_key(column) {
+            differences.push(format!("+ unexpected column \"{column}\""));
+        }
+    }
+    if differences.is_empty() && !properties.keys().eq(headers.iter()) {
+        differences.push(format!(
+            "~ columns reordered - expected {}",
+            properties
+                .keys()
+                .map(|k| format!("\"{k}\""))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    // the types allowed by the schema for each input column, if it's in the schema
+    let column_types: Vec<Option<Vec<&str>>> = headers
+        .iter()
+        .map(|column| {
+            properties
+                .get(column)
+                .and_then(|property| property.get("type"))
+                .map(|types| match types {
+                    Value::String(t) => vec![t.as_str()],
+                    Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                    _ => Vec::new(),
+                })
+                .filter(|types| !types.is_empty())
+        })
+        .collect();
+
+    let mut mismatched = vec![false; headers.len()];
+    let mut record = csv::StringRecord::new();
+    let mut row: u64 = 0;
+    while row < LOCKED_SCHEMA_SAMPLE_ROWS && rdr.read_record(&mut record)? {
+        row += 1;
+        for (idx, value) in record.iter().enumerate() {
+            let Some(Some(types)) = column_types.get(idx) else {
+                continue;
+            };
+            if mismatched[idx] || value_has_type(value, types) {
+                continue;
+            }
+            mismatched[idx] = true;
+            differences.push(format!(
+                "~ column \"{}\" expected {}, got \"{value}\" at row {row}",
+                headers[idx],
+                types.join("|")
+            ));
+        }
+    }
+
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        fail_clierror!(
+            "Input does not match the locked schema {schema_path}:\n{}",
+            differences.join("\n")
+        )
+    }
+}
+
+/// does a CSV value conform to one of the JSON Schema types?
+fn value_has_type(value: &str, types: &[&str]) -> bool {
+    types.iter().any(|t| match *t {
+        "null" => value.is_empty(),
+        "string" => true,
+        "integer" => atoi_simd::parse::<i64>(value.as_bytes()).is_ok(),
+        "number" => value.parse::<f64>().is_ok(),
+        "boolean" => matches!(
+            value.to_ascii_lowercase().as_str(),
+            "true" | "false" | "1" | "0"
+        ),
+        // types we can't check from a CSV value, like object and array
+        _ => true,
+    })
+}
+
+/// does a date or datetime parse with a strftime pattern, e.g. "%m/%d/%Y %H:%M"?
+/// The date must also exist (e.g. no February 30th).
+pub fn matches_date_format(value: &str, pattern: &str) -> bool {
+    use chrono::format::{parse, Parsed, StrftimeItems};
+
+    let mut parsed = Parsed::new();
+    parse(&mut parsed, value, StrftimeItems::new(pattern)).is_ok() && parsed.to_naive_date().is_ok()
+}
+
+/// An atomic in-place edit of an input file, for the --in-place option of transforming
+/// commands: the output is written to a tempfile next to the input, which then replaces
+/// the input with a rename, after keeping a copy of the input if a --backup suffix is given.
+/// The tempfile is removed if the command fails before committing the edit.
+pub struct InPlace {
+    input:    PathBuf,
+    tempfile: tempfile::NamedTempFile,
+    backup:   Option<String>,
+}
+
+impl InPlace {
+    /// check the --in-place options, returning None when not editing in-place
+    pub fn new(
+        in_place: bool,
+        input: Option<&str>,
+        output: Option<&str>,
+        backup: Option<&str>,
+    ) -> CliResult<Option<Self>> {
+        if !in_place {
+            if backup.is_some() {
+                return fail_incorrectusage_clierror!("--backup can only be used with --in-place.");
+            }
+            return Ok(None);
+        }
+        if output.is_some() {
+            return fail_incorrectusage_clierror!(
+                "--in-place and --output are mutually exclusive."
+            );
+        }
+        if backup == Some("") {
+            return fail_incorrectusage_clierror!("--backup suffix cannot be empty.");
+        }
+        let input = match input {
+            None | Some("-") => {
+                return fail_incorrectusage_clierror!("--in-place cannot be used with <stdin>.");
+            },
+            Some(url)
+                if url.to_ascii_lowercase().starts_with("http://")
+                    || url.to_ascii_lowercase().starts_with("https://") =>
+            {
+                return fail_incorrectusage_clierror!(
+                    "--in-place cannot be used with a remote URL."
+                );
+            },
+            Some(input) => PathBuf::from(input),
+        };
+        if !input.is_file() {
+            return fail_clierror!("--in-place input {} is not a file.", input.display());
+        }
+
+        // the tempfile is in the input's directory so the rename is atomic, and has the
+        // input's extensions so it's written with the same delimiter and compression
+        let dir = match input.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        // safety: the input is a file, so it has a file name
+        let file_name = input.file_name().unwrap().to_string_lossy().into_owned();
+        let suffix = file_name.find('.').map_or("", |idx| &file_name[idx..]);
+        let tempfile = tempfile::Builder::new()
+            .prefix(".qsv-in-place-")
+            .suffix(suffix)
+            .tempfile_in(dir)?;
+
+        Ok(Some(Self {
+            input,
+            tempfile,
+            backup: backup.map(str::to_string),
+        }))
+    }
+
+    /// the path of the tempfile the output is written to
+    pub fn output(&self) -> String {
+        self.tempfile.path().to_string_lossy().into_owned()
+    }
+
+    /// replace the input with the output written to the tempfile, which must be flushed
+    pub fn commit(self) -> CliResult<()> {
+        if let Some(suffix) = self.backup {
+            let mut backup = self.input.clone().into_os_string();
+            backup.push(suffix);
+            fs::copy(&self.input, backup)?;
+        }
+        // keep the input's permissions
+        fs::set_permissions(
+            self.tempfile.path(),
+            fs::metadata(&self.input)?.permissions(),
+        )?;
+        self.tempfile
+            .persist(&self.input)
+            .map_err(|e| CliError::from(e.error))?;
+        Ok(())
+    }
+}
+
 pub const fn chunk_size(nitems: usize, njobs: usize) -> usize {
     if nitems < njobs {
         nitems
@@ -563,6 +869,102 @@ pub const fn num_of_chunks(nitems: usize, chunk_size: usize) -> usize {
     n
 }
 
+/// the prefix of the per-run scratch directories created by `scratch_dir()`
+pub const SCRATCH_DIR_PREFIX: &str = "qsv-run-";
+/// the marker file in a scratch directory, with the pid & host of the qsv run that owns it
+pub const SCRATCH_OWNER_FILE: &str = ".qsv-owner";
+
+/// The directory under which qsv creates its per-run scratch directories -
+/// QSV_TMPDIR if set, otherwise the system temp dir.
+pub fn scratch_base_dir() -> PathBuf {
+    match env::var("QSV_TMPDIR") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => env::temp_dir(),
+    }
+}
+
+/// Create a scratch directory for the current run under `scratch_base_dir()`.
+/// It is removed when the returned TempDir is dropped. If qsv crashes or is killed
+/// before then, `qsv cleanup` can remove it later using its owner marker file.
+pub fn scratch_dir() -> std::io::Result<tempfile::TempDir> {
+    let base_dir = scratch_base_dir();
+    fs::create_dir_all(&base_dir)?;
+    let scratch = tempfile::Builder::new()
+        .prefix(SCRATCH_DIR_PREFIX)
+        .tempdir_in(&base_dir)?;
+    fs::write(
+        scratch.path().join(SCRATCH_OWNER_FILE),
+        format!(
+            "{}\n{}\n",
+            std::process::id(),
+            System::host_name().unwrap_or_default()
+        ),
+    )?;
+    Ok(scratch)
+}
+
+/// Spool stdin to a file in a scratch directory, for commands that read their input more
+/// than once. Returns the config with the same options reading the spooled file instead, and
+/// the scratch directory, which removes the file when dropped.
+pub fn spool_stdin(conf: &Config) -> CliResult<(Config, tempfile::TempDir)> {
+    let spool_dir = scratch_dir()?;
+    let spool_path = spool_dir.path().join("stdin.csv");
+    let mut spool_wtr =
+        BufWriter::with_capacity(DEFAULT_WTR_BUFFER_CAPACITY, File::create(&spool_path)?);
+    std::io::copy(&mut std::io::stdin().lock(), &mut spool_wtr)?;
+    spool_wtr.flush()?;
+
+    let mut spooled_conf = conf.clone();
+    spooled_conf.path = Some(spool_path);
+    Ok((spooled_conf, spool_dir))
+}
+
+/// compare values as numbers if they're both numbers, and as text otherwise
+pub fn cmp_values(a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+    let number = |value: &[u8]| {
+        simdutf8::basic::from_utf8(value)
+            .ok()
+            .and_then(|value| value.trim().parse::<f64>().ok())
+    };
+    match (number(a), number(b)) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Parse a size with an optional binary unit suffix - e.g. 4096, 500K, 500M, 2G or 1T.
+pub fn parse_size(size: &str) -> Result<u64, String> {
+    let size = size.trim();
+    let digits_end = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(digits_end);
+    let Ok(number) = number.parse::<u64>() else {
+        return Err(format!("Invalid size: {size}"));
+    };
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().trim_end_matches('B') {
+        "" => 1,
+        "K" | "KI" => 1 << 10,
+        "M" | "MI" => 1 << 20,
+        "G" | "GI" => 1 << 30,
+        "T" | "TI" => 1 << 40,
+        _ => return Err(format!("Invalid size unit: {size}")),
+    };
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("Size is too large: {size}"))
+}
+
+#[test]
+fn test_parse_size() {
+    assert_eq!(parse_size("4096"), Ok(4096));
+    assert_eq!(parse_size("500K"), Ok(500 * 1024));
+    assert_eq!(parse_size("2g"), Ok(2 * 1024 * 1024 * 1024));
+    assert_eq!(parse_size("1 MiB"), Ok(1024 * 1024));
+    assert!(parse_size("1.5G").is_err());
+    assert!(parse_size("10X").is_err());
+}
+
 pub fn file_metadata(md: &fs::Metadata) -> (u64, u64) {
     use filetime::FileTime;
     let last_modified = FileTime::from_last_modification_time(md).unix_seconds() as u64;
@@ -1990,29 +2392,41 @@ pub fn get_stats_records(
 
     if !stats_data_loaded {
         let stats_args = crate::cmd::stats::Args {
-            arg_input:            args.arg_input.clone(),
-            flag_select:          crate::select::SelectColumns::parse("").unwrap(),
-            flag_everything:      false,
-            flag_typesonly:       false,
-            flag_infer_boolean:   false,
-            flag_mode:            false,
-            flag_cardinality:     true,
-            flag_median:          false,
-            flag_quartiles:       false,
-            flag_mad:             false,
-            flag_nulls:           false,
-            flag_round:           4,
-            flag_infer_dates:     true,
-            flag_dates_whitelist: args.flag_dates_whitelist.to_string(),
-            flag_prefer_dmy:      args.flag_prefer_dmy,
-            flag_force:           args.flag_force,
-            flag_jobs:            Some(njobs(args.flag_jobs)),
-            flag_stats_jsonl:     true,
-            flag_cache_threshold: 1, // force the creation of stats cache files
-            flag_output:          None,
-            flag_no_headers:      args.flag_no_headers,
-            flag_delimiter:       args.flag_delimiter,
-            flag_memcheck:        args.flag_memcheck,
+            arg_input:               args.arg_input.clone(),
+            flag_select:             crate::select::SelectColumns::parse("").unwrap(),
+            flag_everything:         false,
+            flag_typesonly:          false,
+            flag_infer_boolean:      false,
+            flag_mode:               false,
+            flag_cardinality:        true,
+            flag_approx_cardinality: false,
+            flag_cardinality_error:  1.0,
+            flag_median:             false,
+            flag_quartiles:          false,
+            flag_percentiles:        None,
+            flag_outliers:           None,
+            flag_outliers_output:    None,
+            flag_histogram:          None,
+            flag_histogram_file:     None,
+            flag_group_by:           None,
+            flag_group_limit:        10_000,
+            flag_weight:             None,
+            flag_mad:                false,
+            flag_nulls:              false,
+            flag_round:              4,
+            flag_infer_dates:        true,
+            flag_dates_whitelist:    args.flag_dates_whitelist.to_string(),
+            flag_prefer_dmy:         args.flag_prefer_dmy,
+            flag_date_details:       false,
+            flag_force:              args.flag_force,
+            flag_jobs:               Some(njobs(args.flag_jobs)),
+            flag_stats_jsonl:        true,
+            flag_format:             "csv".to_string(),
+            flag_cache_threshold:    1, // force the creation of stats cache files
+            flag_output:             None,
+            flag_no_headers:         args.flag_no_headers,
+            flag_delimiter:          args.flag_delimiter,
+            flag_memcheck:           args.flag_memcheck,
         };
 
         // otherwise, run stats command to generate stats.csv.data.jsonl file
@@ -2111,6 +2525,65 @@ pub fn get_stats_records(
     Ok((csv_fields, csv_stats))
 }
 
+/// get the stats records of the input from its stats cache, only if the cache is current -
+/// the stats are never computed. The records are empty for stdin, or without a current cache.
+pub fn get_cached_stats_records(
+    arg_input: &Option<String>,
+    delimiter: Option<Delimiter>,
+) -> CliResult<Vec<StatsData>> {
+    let schema_args = SchemaArgs {
+        flag_enum_threshold:  0,
+        flag_ignore_case:     false,
+        flag_strict_dates:    false,
+        flag_pattern_columns: SelectColumns::parse("").unwrap(),
+        flag_dates_whitelist: String::new(),
+        flag_prefer_dmy:      false,
+        flag_force:           false,
+        flag_stdout:          false,
+        flag_jobs:            None,
+        flag_no_headers:      false,
+        flag_delimiter:       delimiter,
+        arg_input:            arg_input.clone(),
+        flag_memcheck:        false,
+        flag_no_enum:         None,
+        enum_threshold_overrides: Vec::new(),
+    };
+    // StatsMode::Frequency only reads a current stats cache, and never runs stats
+    let (_, csv_stats) = get_stats_records(&schema_args, StatsMode::Frequency)?;
+    Ok(csv_stats)
+}
+
+/// get the stats records of the input with the types of its columns, including the dates of
+/// the columns matching dates_whitelist, computing the stats if its stats cache isn't current.
+/// Used by the commands that convert CSVs to typed formats.
+pub fn get_typed_stats_records(
+    input: &str,
+    delimiter: Option<Delimiter>,
+    dates_whitelist: &str,
+    prefer_dmy: bool,
+    jobs: Option<usize>,
+) -> CliResult<Vec<StatsData>> {
+    let schema_args = SchemaArgs {
+        flag_enum_threshold:  0,
+        flag_ignore_case:     false,
+        flag_strict_dates:    false,
+        flag_pattern_columns: SelectColumns::parse("").unwrap(),
+        flag_dates_whitelist: dates_whitelist.to_string(),
+        flag_prefer_dmy:      prefer_dmy,
+        flag_force:           false,
+        flag_stdout:          false,
+        flag_jobs:            Some(njobs(jobs)),
+        flag_no_headers:      false,
+        flag_delimiter:       delimiter,
+        arg_input:            Some(input.to_string()),
+        flag_memcheck:        false,
+        flag_no_enum:         None,
+        enum_threshold_overrides: Vec::new(),
+    };
+    let (_, csv_stats) = get_stats_records(&schema_args, StatsMode::Schema)?;
+    Ok(csv_stats)
+}
+
 /// simple helper to convert a CSV file to a JSONL file
 /// no type inferencing is done unlike tojsonl, so all fields are strings
 pub fn csv_to_jsonl(
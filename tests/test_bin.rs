@@ -0,0 +1,119 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "amount"],
+            svec!["a", "0"],
+            svec!["b", "5"],
+            svec!["c", "10"],
+            svec!["d", "15"],
+            svec!["e", "20"],
+            svec!["f", ""],
+            svec!["g", "7"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn bin_equal_width() {
+    let wrk = setup("bin_equal_width");
+    let mut cmd = wrk.command("bin");
+    cmd.args(["amount", "--bins", "4"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "amount", "amount_bin"],
+        svec!["a", "0", "1"],
+        svec!["b", "5", "2"],
+        svec!["c", "10", "3"],
+        svec!["d", "15", "4"],
+        svec!["e", "20", "4"],
+        svec!["f", "", ""],
+        svec!["g", "7", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_equal_width_stats_cache() {
+    let wrk = setup("bin_equal_width_stats_cache");
+    let mut stats_cmd = wrk.command("stats");
+    stats_cmd.arg("--stats-jsonl").arg("in.csv");
+    wrk.assert_success(&mut stats_cmd);
+
+    let mut cmd = wrk.command("bin");
+    cmd.args(["amount", "--bins", "2", "--label"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "amount", "amount_bin"],
+        svec!["a", "0", "[0, 10)"],
+        svec!["b", "5", "[0, 10)"],
+        svec!["c", "10", "[10, 20]"],
+        svec!["d", "15", "[10, 20]"],
+        svec!["e", "20", "[10, 20]"],
+        svec!["f", "", ""],
+        svec!["g", "7", "[0, 10)"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_quantile_stdin() {
+    let wrk = setup("bin_quantile_stdin");
+    let mut cmd = wrk.command("bin");
+    cmd.args([
+        "amount", "--bins", "2", "--method", "quantile", "--name", "half",
+    ])
+        .stdin(std::fs::File::open(wrk.path("in.csv")).unwrap());
+
+    // the median of 0, 5, 7, 10, 15 & 20 is 8.5
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "amount", "half"],
+        svec!["a", "0", "1"],
+        svec!["b", "5", "1"],
+        svec!["c", "10", "2"],
+        svec!["d", "15", "2"],
+        svec!["e", "20", "2"],
+        svec!["f", "", ""],
+        svec!["g", "7", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_custom_label() {
+    let wrk = setup("bin_custom_label");
+    let mut cmd = wrk.command("bin");
+    cmd.args(["amount", "--method", "custom:0,10,18", "--label"])
+        .arg("in.csv");
+
+    // 20 is outside of the custom edges
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "amount", "amount_bin"],
+        svec!["a", "0", "[0, 10)"],
+        svec!["b", "5", "[0, 10)"],
+        svec!["c", "10", "[10, 18]"],
+        svec!["d", "15", "[10, 18]"],
+        svec!["e", "20", ""],
+        svec!["f", "", ""],
+        svec!["g", "7", "[0, 10)"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bin_custom_invalid() {
+    let wrk = setup("bin_custom_invalid");
+    let mut cmd = wrk.command("bin");
+    cmd.args(["amount", "--method", "custom:10,5"])
+        .arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
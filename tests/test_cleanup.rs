@@ -0,0 +1,69 @@
+use std::fs;
+
+use crate::workdir::Workdir;
+
+// a pid that is above the maximum pid on Linux, macOS & Windows, so it's never running
+const DEAD_PID: &str = "4294967290";
+
+fn create_scratch_dir(wrk: &Workdir, name: &str, pid: &str) {
+    let scratch_dir = wrk.path("scratch").join(name);
+    fs::create_dir_all(&scratch_dir).unwrap();
+    let host = sysinfo::System::host_name().unwrap_or_default();
+    fs::write(scratch_dir.join(".qsv-owner"), format!("{pid}\n{host}\n")).unwrap();
+    fs::write(scratch_dir.join("segment.tmp"), "spilled data").unwrap();
+}
+
+#[test]
+fn cleanup_removes_orphaned_scratch_dirs() {
+    let wrk = Workdir::new("cleanup_removes_orphaned_scratch_dirs");
+    create_scratch_dir(&wrk, "qsv-run-crashed", DEAD_PID);
+    create_scratch_dir(&wrk, "qsv-run-running", &std::process::id().to_string());
+    create_scratch_dir(&wrk, "not-qsv", DEAD_PID);
+
+    let mut cmd = wrk.command("cleanup");
+    cmd.env("QSV_TMPDIR", wrk.path("scratch"));
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = wrk
+        .path("scratch")
+        .join("qsv-run-crashed")
+        .display()
+        .to_string();
+    assert_eq!(got, expected);
+
+    assert!(!wrk.path("scratch").join("qsv-run-crashed").exists());
+    assert!(wrk.path("scratch").join("qsv-run-running").exists());
+    assert!(wrk.path("scratch").join("not-qsv").exists());
+}
+
+#[test]
+fn cleanup_dry_run() {
+    let wrk = Workdir::new("cleanup_dry_run");
+    create_scratch_dir(&wrk, "qsv-run-crashed", DEAD_PID);
+
+    let mut cmd = wrk.command("cleanup");
+    cmd.env("QSV_TMPDIR", wrk.path("scratch")).arg("--dry-run");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = wrk
+        .path("scratch")
+        .join("qsv-run-crashed")
+        .display()
+        .to_string();
+    assert_eq!(got, expected);
+    assert!(wrk.path("scratch").join("qsv-run-crashed").exists());
+}
+
+#[test]
+fn cleanup_min_age() {
+    let wrk = Workdir::new("cleanup_min_age");
+    create_scratch_dir(&wrk, "qsv-run-crashed", DEAD_PID);
+
+    let mut cmd = wrk.command("cleanup");
+    cmd.env("QSV_TMPDIR", wrk.path("scratch"))
+        .args(["--min-age", "60"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "");
+    assert!(wrk.path("scratch").join("qsv-run-crashed").exists());
+}
@@ -0,0 +1,134 @@
+use crate::workdir::Workdir;
+
+fn create_orders(wrk: &Workdir) {
+    wrk.create(
+        "orders2023.csv",
+        vec![
+            svec!["customer_id", "first", "last"],
+            svec!["3", "Ann", "Lee"],
+            svec!["1", "Bob", "Ray"],
+            svec!["2", "Cy", "Ng"],
+            svec!["3", "Ann", "Lee"],
+            svec!["4", "Di", "Ott"],
+        ],
+    );
+    wrk.create(
+        "orders2024.csv",
+        vec![
+            svec!["id", "first", "last"],
+            svec!["5", "Ed", "Fox"],
+            svec!["2", "Cy", "Ng"],
+            svec!["3", "Ann", "Ray"],
+        ],
+    );
+}
+
+#[test]
+fn colops_intersect() {
+    let wrk = Workdir::new("colops_intersect");
+    create_orders(&wrk);
+
+    let mut cmd = wrk.command("colops");
+    cmd.arg("intersect")
+        .args(["--select", "customer_id"])
+        .args(["--select2", "id"])
+        .arg("orders2023.csv")
+        .arg("orders2024.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["customer_id"], svec!["3"], svec!["2"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn colops_union() {
+    let wrk = Workdir::new("colops_union");
+    create_orders(&wrk);
+
+    let mut cmd = wrk.command("colops");
+    cmd.arg("union")
+        .args(["-s", "1"])
+        .arg("orders2023.csv")
+        .arg("orders2024.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["customer_id"],
+        svec!["3"],
+        svec!["1"],
+        svec!["2"],
+        svec!["4"],
+        svec!["5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn colops_except() {
+    let wrk = Workdir::new("colops_except");
+    create_orders(&wrk);
+
+    let mut cmd = wrk.command("colops");
+    cmd.arg("except")
+        .args(["-s", "customer_id"])
+        .args(["--select2", "id"])
+        .arg("orders2023.csv")
+        .arg("orders2024.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["customer_id"], svec!["1"], svec!["4"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn colops_except_tuple() {
+    let wrk = Workdir::new("colops_except_tuple");
+    create_orders(&wrk);
+
+    let mut cmd = wrk.command("colops");
+    cmd.arg("except")
+        .args(["--select", "first,last"])
+        .arg("orders2023.csv")
+        .arg("orders2024.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["first", "last"],
+        svec!["Ann", "Lee"],
+        svec!["Bob", "Ray"],
+        svec!["Di", "Ott"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn colops_tuple_no_collision() {
+    let wrk = Workdir::new("colops_tuple_no_collision");
+    wrk.create("a.csv", vec![svec!["x", "y"], svec!["a:b", "c"]]);
+    wrk.create("b.csv", vec![svec!["x", "y"], svec!["a", "b:c"]]);
+
+    let mut cmd = wrk.command("colops");
+    cmd.arg("intersect").arg("a.csv").arg("b.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["x", "y"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn colops_selection_mismatch() {
+    let wrk = Workdir::new("colops_selection_mismatch");
+    create_orders(&wrk);
+
+    let mut cmd = wrk.command("colops");
+    cmd.arg("intersect")
+        .args(["-s", "first,last"])
+        .args(["--select2", "id"])
+        .arg("orders2023.csv")
+        .arg("orders2024.csv");
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("usage error: "), "{got}");
+    assert!(got.contains("with 2 and 1 columns."), "{got}");
+    wrk.assert_err(&mut cmd);
+}
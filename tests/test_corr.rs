@@ -0,0 +1,71 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn corr_pearson() {
+    let wrk = Workdir::new("corr_pearson");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["x", "y", "z", "name"],
+            svec!["1", "2", "5", "a"],
+            svec!["2", "4", "4", "b"],
+            svec!["3", "", "3", "c"],
+            svec!["4", "8", "2", "d"],
+            svec!["5", "10", "1", "e"],
+        ],
+    );
+
+    let mut cmd = wrk.command("corr");
+    cmd.arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "x", "y", "z"],
+        svec!["x", "1", "1", "-1"],
+        svec!["y", "1", "1", "-1"],
+        svec!["z", "-1", "-1", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "Skipping non-numeric column name.\n");
+}
+
+#[test]
+fn corr_spearman() {
+    let wrk = Workdir::new("corr_spearman");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["a", "b", "c"],
+            svec!["1", "10", "7"],
+            svec!["2", "20", "7"],
+            svec!["2", "30", "7"],
+            svec!["3", "40", "7"],
+        ],
+    );
+
+    let mut cmd = wrk.command("corr");
+    cmd.arg("data.csv").args(["--method", "spearman"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    // the tied values of a are ranked at their average rank, and c is constant
+    let expected = vec![
+        svec!["field", "a", "b", "c"],
+        svec!["a", "1", "0.9487", ""],
+        svec!["b", "0.9487", "1", ""],
+        svec!["c", "", "", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn corr_not_enough_numeric_columns() {
+    let wrk = Workdir::new("corr_not_enough_numeric_columns");
+    wrk.create(
+        "data.csv",
+        vec![svec!["x", "name"], svec!["1", "a"], svec!["2", "b"]],
+    );
+
+    let mut cmd = wrk.command("corr");
+    cmd.arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
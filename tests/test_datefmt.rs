@@ -717,3 +717,90 @@ fn datefmt_fmtstring_notime() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn datefmt_to_epoch() {
+    let wrk = Workdir::new("datefmt_to_epoch");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["Created Date"],
+            svec!["2021-06-02T06:31:39+00:00"],
+            svec!["2009-01-20 05:00 EST"],
+            svec!["This is not a date and it will not be reformatted"],
+        ],
+    );
+    let mut cmd = wrk.command("datefmt");
+    cmd.arg("Created Date")
+        .args(["--to-epoch", "milli"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["Created Date"],
+        svec!["1622615499000"],
+        svec!["1232445600000"],
+        svec!["This is not a date and it will not be reformatted"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn datefmt_epoch_roundtrip() {
+    let wrk = Workdir::new("datefmt_epoch_roundtrip");
+    wrk.create(
+        "data.csv",
+        vec![svec!["ts"], svec!["1622615499123"], svec!["1232445600000"]],
+    );
+    let mut cmd = wrk.command("datefmt");
+    cmd.arg("ts")
+        .args(["--ts-resolution", "milli"])
+        .args(["--formatstr", "%Y-%m-%d %H:%M:%S%.3f"])
+        .args(["--output-tz", "Asia/Tokyo"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["ts"],
+        svec!["2021-06-02 15:31:39.123"],
+        svec!["2009-01-20 19:00:00.000"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn datefmt_relative() {
+    let wrk = Workdir::new("datefmt_relative");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["LastLogin"],
+            svec!["2024-06-27T00:00:00Z"],
+            svec!["2024-06-29T22:00:00Z"],
+            svec!["2024-06-29T23:59:30Z"],
+            svec!["2024-07-01T00:00:00Z"],
+            svec!["2023-06-29T00:00:00Z"],
+            svec!["2024-09-30T00:00:00Z"],
+            svec![""],
+        ],
+    );
+    let mut cmd = wrk.command("datefmt");
+    cmd.arg("LastLogin")
+        .arg("--relative")
+        .args(["--now", "2024-06-30T00:00:00Z"])
+        .args(["--new-column", "last_seen"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["LastLogin", "last_seen"],
+        svec!["2024-06-27T00:00:00Z", "3 days ago"],
+        svec!["2024-06-29T22:00:00Z", "2 hours ago"],
+        svec!["2024-06-29T23:59:30Z", "just now"],
+        svec!["2024-07-01T00:00:00Z", "in 1 day"],
+        svec!["2023-06-29T00:00:00Z", "1 year ago"],
+        svec!["2024-09-30T00:00:00Z", "in 3 months"],
+        svec!["", ""],
+    ];
+    assert_eq!(got, expected);
+}
@@ -287,3 +287,120 @@ fn dedup_not_sorted2() {
     let got: String = wrk.output_stderr(&mut cmd);
     assert!(got.contains("Aborting! Input not sorted!"));
 }
+
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+#[test]
+fn dedup_similarity() {
+    let wrk = Workdir::new("dedup_similarity");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "Acme Corp"],
+            svec!["2", "Globex Inc"],
+            svec!["3", "ACME Corp."],
+            svec!["4", "Initech"],
+            svec!["5", "Globex Inc."],
+        ],
+    );
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--similarity", "0.85", "--compare", "name", "--ignore-case"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name"],
+        svec!["1", "Acme Corp"],
+        svec!["2", "Globex Inc"],
+        svec!["4", "Initech"],
+    ];
+    assert_eq!(got, expected);
+
+    let got_err = wrk.output_stderr(&mut cmd);
+    assert_eq!(got_err, "2\n");
+}
+
+#[test]
+fn dedup_similarity_jaccard_clusters() {
+    let wrk = Workdir::new("dedup_similarity_jaccard_clusters");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "city"],
+            svec!["1", "new york city"],
+            svec!["2", "boston"],
+            svec!["3", "city of new york"],
+            svec!["4", "york new"],
+        ],
+    );
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--similarity", "0.7", "--compare", "city"])
+        .args(["--metric", "jaccard", "--clusters"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["cluster", "id", "city"],
+        svec!["1", "1", "new york city"],
+        svec!["1", "3", "city of new york"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn dedup_keep_last() {
+    let wrk = Workdir::new("dedup_keep_last");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "value"],
+            svec!["b", "1"],
+            svec!["a", "2"],
+            svec!["b", "3"],
+            svec!["c", "4"],
+            svec!["a", "5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--select", "id", "--keep", "last"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "value"],
+        svec!["b", "3"],
+        svec!["c", "4"],
+        svec!["a", "5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn dedup_keep_max() {
+    let wrk = Workdir::new("dedup_keep_max");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "score"],
+            svec!["b", "9"],
+            svec!["a", "10"],
+            svec!["b", ""],
+            svec!["a", "2"],
+            svec!["b", "9"],
+        ],
+    );
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--select", "id", "--keep", "max:score"])
+        .args(["--dupes-output", "dupes.csv"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "score"], svec!["b", "9"], svec!["a", "10"]];
+    assert_eq!(got, expected);
+
+    let dupes = wrk.read_to_string("dupes.csv");
+    assert_eq!(dupes, "id,score\na,2\nb,\nb,9\n");
+}
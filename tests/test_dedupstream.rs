@@ -0,0 +1,95 @@
+use crate::workdir::Workdir;
+
+fn feed() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "event"],
+        svec!["1", "login"],
+        svec!["2", "click"],
+        svec!["1", "login"],
+        svec!["3", "click"],
+        svec!["4", "logout"],
+        svec!["2", "CLICK"],
+        svec!["1", "login"],
+    ]
+}
+
+#[test]
+fn dedupstream_key() {
+    let wrk = Workdir::new("dedupstream_key");
+    wrk.create("in.csv", feed());
+
+    let mut cmd = wrk.command("dedupstream");
+    cmd.args(["--key", "id"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "event"],
+        svec!["1", "login"],
+        svec!["2", "click"],
+        svec!["3", "click"],
+        svec!["4", "logout"],
+    ];
+    assert_eq!(got, expected);
+
+    let got_err = wrk.output_stderr(&mut cmd);
+    assert_eq!(got_err, "3\n");
+}
+
+#[test]
+fn dedupstream_window() {
+    let wrk = Workdir::new("dedupstream_window");
+    wrk.create("in.csv", feed());
+
+    // with a window of 3 rows, the last "2" and "1" rows are kept, as their keys were
+    // last seen 4 rows earlier
+    let mut cmd = wrk.command("dedupstream");
+    cmd.args(["--key", "id", "--window", "3"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "event"],
+        svec!["1", "login"],
+        svec!["2", "click"],
+        svec!["3", "click"],
+        svec!["4", "logout"],
+        svec!["2", "CLICK"],
+        svec!["1", "login"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn dedupstream_all_columns_ignore_case_dupes_output() {
+    let wrk = Workdir::new("dedupstream_all_columns_ignore_case_dupes_output");
+    wrk.create("in.csv", feed());
+
+    let mut cmd = wrk.command("dedupstream");
+    cmd.args(["--ignore-case", "--dupes-output", "dupes.csv"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "event"],
+        svec!["1", "login"],
+        svec!["2", "click"],
+        svec!["3", "click"],
+        svec!["4", "logout"],
+    ];
+    assert_eq!(got, expected);
+
+    let got_dupes: String = wrk.from_str(&wrk.path("dupes.csv"));
+    assert_eq!(got_dupes, "id,event\n1,login\n2,CLICK\n1,login\n");
+}
+
+#[test]
+fn dedupstream_zero_window() {
+    let wrk = Workdir::new("dedupstream_zero_window");
+    wrk.create("in.csv", feed());
+
+    let mut cmd = wrk.command("dedupstream");
+    cmd.args(["--window", "0"]).arg("in.csv");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "usage error: --window must be greater than 0.\n");
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,151 @@
+use crate::workdir::Workdir;
+
+fn day1() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "name", "city"],
+        svec!["1", "Alice", "Boston"],
+        svec!["2", "Bob", "Chicago"],
+        svec!["3", "Carol", "Denver"],
+    ]
+}
+
+fn day2() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "name", "city"],
+        svec!["1", "Alice", "Boston"],
+        svec!["3", "Carol", "Seattle"],
+        svec!["4", "Dave", "Austin"],
+    ]
+}
+
+#[test]
+fn delta_state() {
+    let wrk = Workdir::new("delta_state");
+    wrk.create("day1.csv", day1());
+    wrk.create("day2.csv", day2());
+
+    // without a state file, all the rows are inserted
+    let mut cmd = wrk.command("delta");
+    cmd.args(["--state", "state.csv", "--key", "id", "-Q"])
+        .arg("day1.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["change_type", "id", "name", "city"],
+        svec!["inserted", "1", "Alice", "Boston"],
+        svec!["inserted", "2", "Bob", "Chicago"],
+        svec!["inserted", "3", "Carol", "Denver"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("delta");
+    cmd.args(["--state", "state.csv", "--key", "id", "--dry-run"])
+        .arg("day2.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["change_type", "id", "name", "city"],
+        svec!["updated", "3", "Carol", "Seattle"],
+        svec!["inserted", "4", "Dave", "Austin"],
+        svec!["deleted", "2", "", ""],
+    ];
+    assert_eq!(got, expected);
+    let got_err = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got_err,
+        "inserted: 1, updated: 1, deleted: 1, unchanged: 1\n"
+    );
+
+    // the snapshot is updated once the changes are written
+    let mut cmd = wrk.command("delta");
+    cmd.args(["--state", "state.csv", "--key", "id", "-Q"])
+        .arg("day2.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 4);
+
+    let mut cmd = wrk.command("delta");
+    cmd.args(["--state", "state.csv", "--key", "id", "-Q"])
+        .arg("day2.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["change_type", "id", "name", "city"]]);
+}
+
+#[test]
+fn delta_composite_key() {
+    let wrk = Workdir::new("delta_composite_key");
+    wrk.create(
+        "stock1.csv",
+        vec![
+            svec!["store", "sku", "qty"],
+            svec!["a", "x1", "5"],
+            svec!["a", "x2", "3"],
+            svec!["b", "x1", "7"],
+        ],
+    );
+    wrk.create(
+        "stock2.csv",
+        vec![
+            svec!["store", "sku", "qty"],
+            svec!["a", "x1", "4"],
+            svec!["b", "x1", "7"],
+        ],
+    );
+
+    let mut cmd = wrk.command("delta");
+    cmd.args(["--state", "stock.state.csv", "--key", "store,sku", "-Q"])
+        .arg("stock1.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("delta");
+    cmd.args(["--state", "stock.state.csv", "--key", "store,sku", "-Q"])
+        .args(["--change-column", "op"])
+        .arg("stock2.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["op", "store", "sku", "qty"],
+        svec!["updated", "a", "x1", "4"],
+        svec!["deleted", "a", "x2", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn delta_invalid() {
+    let wrk = Workdir::new("delta_invalid");
+    wrk.create("day1.csv", day1());
+    wrk.create(
+        "dupes.csv",
+        vec![
+            svec!["id", "name", "city"],
+            svec!["1", "Alice", "Boston"],
+            svec!["1", "Alice", "Denver"],
+        ],
+    );
+
+    let mut cmd = wrk.command("delta");
+    cmd.args(["--state", "state.csv", "--key", "id", "-Q"])
+        .arg("day1.csv");
+    wrk.assert_success(&mut cmd);
+    let state = wrk.read_to_string("state.csv");
+
+    // a duplicate key fails without updating the state file
+    let mut cmd = wrk.command("delta");
+    cmd.args(["--state", "state.csv", "--key", "id"])
+        .arg("dupes.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("Duplicate key in row 2: 1."), "{got}");
+    wrk.assert_err(&mut cmd);
+    assert_eq!(wrk.read_to_string("state.csv"), state);
+
+    // the key columns must be the ones of the state file
+    let mut cmd = wrk.command("delta");
+    cmd.args(["--state", "state.csv", "--key", "name"])
+        .arg("day1.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with(
+            "usage error: The --key columns (name) are not the key columns of the state file \
+             state.csv (id)."
+        ),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
@@ -547,6 +547,89 @@ diffresult;h1;h2;h3
     assert_eq!(got.as_str(), expected);
 }
 
+#[test]
+fn diff_positional_show_changes() {
+    let wrk = Workdir::new("diff_positional_show_changes");
+
+    let left = vec![
+        svec!["h1", "h2", "h3"],
+        svec!["1", "foo", "bar"],
+        svec!["2", "fooz", "bart"],
+        svec!["3", "x", "y"],
+    ];
+    wrk.create("left.csv", left);
+
+    let right = vec![
+        svec!["h1", "h2", "h3"],
+        svec!["1", "foo", "bar"],
+        svec!["2", "booz", "bart"],
+    ];
+    wrk.create("right.csv", right);
+
+    let mut cmd = wrk.command("diff");
+    cmd.args(["--positional", "--show-changes", "left.csv", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["diffresult", "h1", "h2", "h3", "changed_columns"],
+        svec!["-", "2", "fooz", "bart", "h2"],
+        svec!["+", "2", "booz", "bart", "h2"],
+        svec!["-", "3", "x", "y", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn diff_json() {
+    let wrk = Workdir::new("diff_json");
+
+    let left = vec![svec!["id", "name", "age"], svec!["1", "ann", "30"]];
+    wrk.create("left.csv", left);
+
+    let right = vec![
+        svec!["id", "name", "age"],
+        svec!["1", "ann", "31"],
+        svec!["3", "cy", "20"],
+    ];
+    wrk.create("right.csv", right);
+
+    let mut cmd = wrk.command("diff");
+    cmd.args(["--format", "json", "left.csv", "right.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"[
+  {"op":"replace","key":["1"],"changes":{"age":{"old":"30","new":"31"}}},
+  {"op":"add","key":["3"],"record":{"id":"3","name":"cy","age":"20"}}
+]"#;
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn diff_json_positional() {
+    let wrk = Workdir::new("diff_json_positional");
+
+    wrk.create("left.csv", vec![svec!["a"], svec!["x"], svec!["y"]]);
+    wrk.create(
+        "right.csv",
+        vec![svec!["a"], svec!["x"], svec!["z"], svec!["w"]],
+    );
+
+    let mut cmd = wrk.command("diff");
+    cmd.args(["--positional", "--format", "json", "left.csv", "right.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"[
+  {"op":"replace","key":2,"changes":{"a":{"old":"y","new":"z"}}},
+  {"op":"add","key":3,"record":{"a":"w"}}
+]"#;
+    assert_eq!(got, expected);
+
+    // the positional diff has no key
+    let mut cmd = wrk.command("diff");
+    cmd.args(["--positional", "--key", "a", "left.csv", "right.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
 fn create_file_with_delim(wrk: &Workdir, file_path_new: &str, file_path: &str, delimiter: u8) {
     let mut select_cmd = wrk.command("select");
     select_cmd.args(["1-", file_path]);
@@ -78,3 +78,32 @@ fn explode_no_headers() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn explode_trim_skip_empty() {
+    let wrk = Workdir::new("explode_trim_skip_empty");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "tags"],
+            svec!["Mary", "a; b;"],
+            svec!["John", " ; "],
+            svec!["Jack", "c"],
+        ],
+    );
+    let mut cmd = wrk.command("explode");
+    cmd.arg("tags")
+        .arg(";")
+        .args(["--trim", "--skip-empty"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "tags"],
+        svec!["Mary", "a"],
+        svec!["Mary", "b"],
+        svec!["John", ""],
+        svec!["Jack", "c"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -24,3 +24,29 @@ fn extsort() {
 
     assert_eq!(dos2unix(&sorted_output), dos2unix(&expected_csv));
 }
+
+#[test]
+fn extsort_max_temp_exceeded() {
+    let wrk = Workdir::new("extsort_max_temp_exceeded");
+    wrk.create(
+        "in.csv",
+        vec![svec!["letter"], svec!["c"], svec!["a"], svec!["b"]],
+    );
+
+    let mut cmd = wrk.command("extsort");
+    cmd.env("QSV_TMPDIR", wrk.path("scratch"))
+        .args(["--max-temp", "8"])
+        .arg("in.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("extsort");
+    cmd.env("QSV_TMPDIR", wrk.path("scratch"))
+        .args(["--max-temp", "1K"])
+        .arg("in.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["letter"], svec!["a"], svec!["b"], svec!["c"]];
+    assert_eq!(got, expected);
+
+    // the scratch dir of the run is removed when the sort is done
+    assert_eq!(std::fs::read_dir(wrk.path("scratch")).unwrap().count(), 0);
+}
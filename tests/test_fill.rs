@@ -154,3 +154,50 @@ fn fill_default() {
     let expected = svec!["dat", "dat", "abc", "dat", "zap", "bar", "bongo", "dat", "dat", "dat"];
     compare_column(&got, &expected, 0, true);
 }
+
+#[test]
+fn fill_direction_up() {
+    let wrk = Workdir::new("fill_direction_up").flexible(true);
+    wrk.create("in.csv", example());
+
+    let mut cmd = wrk.command("fill");
+    cmd.args(["--direction", "up"])
+        .args(["--default", "dat"])
+        .arg("--")
+        .arg("1")
+        .arg("in.csv");
+
+    let got: Vec<CsvRecord> = wrk.read_stdout(&mut cmd);
+    let expected = svec!["abc", "abc", "abc", "zap", "zap", "bar", "bongo", "dat", "dat", "dat"];
+    compare_column(&got, &expected, 0, true);
+}
+
+#[test]
+fn fill_interpolate_groupby() {
+    let wrk = Workdir::new("fill_interpolate_groupby");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "value"],
+            svec!["a", ""],
+            svec!["a", "1"],
+            svec!["b", "10"],
+            svec!["a", ""],
+            svec!["b", ""],
+            svec!["a", ""],
+            svec!["a", "4"],
+            svec!["b", "11"],
+            svec!["b", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("fill");
+    cmd.args(["--interpolate", "linear"])
+        .args(["--groupby", "id"])
+        .arg("value")
+        .arg("in.csv");
+
+    let got: Vec<CsvRecord> = wrk.read_stdout(&mut cmd);
+    let expected = svec!["", "1", "10", "2", "10.5", "3", "4", "11", ""];
+    compare_column(&got, &expected, 1, true);
+}
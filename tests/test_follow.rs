@@ -0,0 +1,51 @@
+use std::{fs::OpenOptions, io::Write, process::Stdio, thread, time::Duration};
+
+use crate::workdir::Workdir;
+
+fn append(wrk: &Workdir, name: &str, data: &str) {
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(wrk.path(name))
+        .unwrap();
+    file.write_all(data.as_bytes()).unwrap();
+}
+
+#[test]
+fn follow_from_start_holds_back_partial_record() {
+    let wrk = Workdir::new("follow_from_start_holds_back_partial_record");
+    wrk.create_from_string("log.csv", "id,name\n1,a\n2,\"b\nb\"\n3,c");
+
+    let mut cmd = wrk.command("follow");
+    cmd.args(["--from-start", "--interval", "100", "--idle-timeout", "1"])
+        .arg("log.csv")
+        .args(["--", "select", "id"]);
+
+    // the last record isn't complete yet, so it's not passed on
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "id\n1\n2");
+}
+
+#[test]
+fn follow_appended_records() {
+    let wrk = Workdir::new("follow_appended_records");
+    wrk.create_from_string("log.csv", "id,name\n1,a\n");
+
+    let mut cmd = wrk.command("follow");
+    cmd.args(["--csv-output", "--interval", "100", "--idle-timeout", "2"])
+        .arg("log.csv")
+        .args(["--", "select", "name"])
+        .stdout(Stdio::piped());
+    let child = cmd.spawn().unwrap();
+
+    thread::sleep(Duration::from_millis(500));
+    append(&wrk, "log.csv", "2,b\n3,c\n4,");
+    thread::sleep(Duration::from_millis(500));
+    append(&wrk, "log.csv", "d\n");
+
+    // the records that were already in the file are skipped, and the header row
+    // of the output is only written once
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let got = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(got, "name\nb\nc\nd\n");
+}
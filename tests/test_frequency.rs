@@ -431,6 +431,38 @@ fn frequency_other_text_none() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn frequency_other_threshold() {
+    let (wrk, mut cmd) = setup("frequency_other_threshold");
+    cmd.args(["--select", "h2"])
+        .args(["--other-threshold", "2"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["h2", "z", "3", "42.85714"],
+        svec!["h2", "y", "2", "28.57143"],
+        svec!["h2", "Other (2)", "2", "28.57143"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_suppress_below() {
+    let (wrk, mut cmd) = setup("frequency_suppress_below");
+    cmd.args(["--select", "h2"])
+        .args(["--suppress-below", "2"])
+        .args(["--limit", "1"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["h2", "z", "3", "42.85714"],
+        svec!["h2", "Other (1)", "2", "28.57143"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn frequency_select() {
     let (wrk, mut cmd) = setup("frequency_select");
@@ -626,6 +658,260 @@ fn frequency_issue1962() {
     assert_eq!(got, expected);
 }
 
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+#[test]
+fn frequency_unicode_normalize() {
+    let wrk = Workdir::new("frequency_unicode_normalize");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["word"],
+            // composed & decomposed forms of "café"
+            svec!["caf\u{e9}"],
+            svec!["cafe\u{301}"],
+            svec![" Caf\u{e9} "],
+            // the "fi" ligature
+            svec!["\u{fb01}ne"],
+            svec!["fine"],
+        ],
+    );
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--unicode-normalize", "NFC"])
+        .arg("--ignore-case")
+        .arg("in.csv");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort_unstable();
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["word", "caf\u{e9}", "3", "60"],
+        svec!["word", "fine", "1", "20"],
+        svec!["word", "\u{fb01}ne", "1", "20"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--unicode-normalize", "nfkc"])
+        .arg("--ignore-case")
+        .arg("in.csv");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort_unstable();
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["word", "caf\u{e9}", "3", "60"],
+        svec!["word", "fine", "2", "40"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--unicode-normalize", "NFD"]).arg("in.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Invalid --unicode-normalize form: NFD. Must be NFC or NFKC.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn frequency_tokens() {
+    let wrk = Workdir::new("frequency_tokens");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "comment"],
+            svec!["1", "Great service, the food was great!"],
+            svec!["2", "great food, the service was slow"],
+            svec!["3", ""],
+            svec!["4", "Great service"],
+        ],
+    );
+    wrk.create_from_string("stopwords.txt", "the\nwas\n");
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--select", "comment"])
+        .arg("--tokens")
+        .args(["--stopwords", "stopwords.txt"])
+        .arg("--ignore-case")
+        .arg("in.csv");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort_unstable();
+    let expected = vec![
+        svec!["comment", "food", "2", "20"],
+        svec!["comment", "great", "4", "40"],
+        svec!["comment", "service", "3", "30"],
+        svec!["comment", "slow", "1", "10"],
+        svec!["field", "value", "count", "percentage"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--select", "comment"])
+        .arg("--tokens")
+        .args(["--ngram", "2"])
+        .args(["--stopwords", "stopwords.txt"])
+        .args(["--limit", "1"])
+        .arg("--ignore-case")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["comment", "great service", "2", "28.57143"],
+        svec!["comment", "Other (5)", "5", "71.42857"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_cum_pct_rank() {
+    let wrk = Workdir::new("frequency_cum_pct_rank");
+    let mut rows = vec![svec!["letter"]];
+    for (letter, count) in [("x", 5), ("y", 3), ("z", 3), ("w", 1)] {
+        for _ in 0..count {
+            rows.push(svec![letter]);
+        }
+    }
+    wrk.create("in.csv", rows);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--limit", "3"])
+        .arg("--cum-pct")
+        .arg("--rank")
+        .arg("in.csv");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    // the tied values are in no particular order, but have the same rank
+    let mut tied: Vec<String> = got[2..4].iter().map(|row| row[1].clone()).collect();
+    tied.sort_unstable();
+    assert_eq!(tied, svec!["y", "z"]);
+    for row in &mut got[2..4] {
+        row[1] = "y|z".to_string();
+    }
+    let expected = vec![
+        svec![
+            "field",
+            "value",
+            "count",
+            "percentage",
+            "cum_percentage",
+            "rank"
+        ],
+        svec!["letter", "x", "5", "41.66667", "41.66667", "1"],
+        svec!["letter", "y|z", "3", "25", "66.66667", "2"],
+        svec!["letter", "y|z", "3", "25", "91.66667", "2"],
+        svec!["letter", "Other (1)", "1", "8.33333", "100", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_weight() {
+    let wrk = Workdir::new("frequency_weight");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["region", "weight"],
+            svec!["east", "1.5"],
+            svec!["west", "2"],
+            svec!["East ", "2.5"],
+            svec!["north", "0"],
+            svec!["west", "abc"],
+            svec!["south", "4"],
+            svec!["", "0.25"],
+        ],
+    );
+
+    // rows with a zero or invalid weight are not counted
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--select", "region"])
+        .args(["--weight", "weight"])
+        .arg("--ignore-case")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["region", "east", "4", "39.02439"],
+        svec!["region", "south", "4", "39.02439"],
+        svec!["region", "west", "2", "19.5122"],
+        svec!["region", "(NULL)", "0.25", "2.43902"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--select", "region"])
+        .args(["--weight", "weight"])
+        .args(["--limit", "1"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["region", "south", "4", "39.02439"],
+        svec!["region", "Other (4)", "6.25", "60.97561"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--weight", "1-2"]).arg("in.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "usage error: --weight must select a single column.\n");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn frequency_joint() {
+    let wrk = Workdir::new("frequency_joint");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["region", "product", "qty"],
+            svec!["east", "apples", "1"],
+            svec!["west", "pears", "2"],
+            svec!["east", "apples", "3"],
+            svec!["east", "pears", "4"],
+            svec!["west", "", "5"],
+            svec!["east", "apples", "6"],
+        ],
+    );
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--select", "region,product"])
+        .arg("--joint")
+        .arg("in.csv");
+
+    // the combinations with the same count are in no particular order
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got[1..].sort_unstable();
+    let expected = vec![
+        svec!["region", "product", "count", "percentage"],
+        svec!["east", "apples", "3", "50"],
+        svec!["east", "pears", "1", "16.66667"],
+        svec!["west", "(NULL)", "1", "16.66667"],
+        svec!["west", "pears", "1", "16.66667"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--select", "region,product"])
+        .arg("--joint")
+        .arg("--no-nulls")
+        .args(["--limit", "1"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "product", "count", "percentage"],
+        svec!["east", "apples", "3", "60"],
+        svec!["Other (2)", "", "2", "40"],
+    ];
+    assert_eq!(got, expected);
+}
+
 // This tests that a frequency table computed by `qsv` is always the same
 // as the frequency table computed in memory.
 #[test]
@@ -0,0 +1,51 @@
+use crate::workdir::Workdir;
+
+fn create_arrow(wrk: &Workdir) {
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "city", "price"],
+            svec!["1", "Denver", "10.5"],
+            svec!["2", "Boston", "3.25"],
+            svec!["3", "Denver", ""],
+        ],
+    );
+    let mut cmd = wrk.command("toarrow");
+    cmd.arg("data.csv").args(["--output", "data.feather"]);
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn fromarrow() {
+    let wrk = Workdir::new("fromarrow");
+    create_arrow(&wrk);
+
+    let mut cmd = wrk.command("fromarrow");
+    cmd.arg("data.feather");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "city", "price"],
+        svec!["1", "Denver", "10.5"],
+        svec!["2", "Boston", "3.25"],
+        svec!["3", "Denver", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fromarrow_select_where() {
+    let wrk = Workdir::new("fromarrow_select_where");
+    create_arrow(&wrk);
+
+    let mut cmd = wrk.command("fromarrow");
+    cmd.arg("data.feather")
+        .args(["--select", "city,price"])
+        .args(["--where", "price < 5 OR price IS NULL"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "price"],
+        svec!["Boston", "3.25"],
+        svec!["Denver", ""],
+    ];
+    assert_eq!(got, expected);
+}
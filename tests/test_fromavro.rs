@@ -0,0 +1,35 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn fromavro_select_where() {
+    let wrk = Workdir::new("fromavro_select_where");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "city", "price"],
+            svec!["1", "Denver", "10.5"],
+            svec!["2", "Boston", "3.25"],
+            svec!["3", "Denver", ""],
+        ],
+    );
+    let mut cmd = wrk.command("toavro");
+    cmd.arg("data.csv").args(["--output", "data.avro"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromavro");
+    cmd.arg("data.avro")
+        .args(["--select", "price,id"])
+        .args(["--where", "city = 'Denver'"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["price", "id"], svec!["10.5", "1"], svec!["", "3"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fromavro_missing_file() {
+    let wrk = Workdir::new("fromavro_missing_file");
+
+    let mut cmd = wrk.command("fromavro");
+    cmd.arg("nope.avro");
+    wrk.assert_err(&mut cmd);
+}
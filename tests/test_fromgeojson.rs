@@ -0,0 +1,115 @@
+use crate::workdir::Workdir;
+
+fn features() -> &'static str {
+    r#"{
+  "type": "FeatureCollection",
+  "features": [
+    {
+      "type": "Feature",
+      "geometry": { "type": "Point", "coordinates": [-0.1278, 51.5074] },
+      "properties": { "name": "Alpha", "address": { "city": "London" } }
+    },
+    {
+      "type": "Feature",
+      "geometry": { "type": "LineString", "coordinates": [[2.3522, 48.8566], [2.3376, 48.8606]] },
+      "properties": { "name": "Bravo", "lanes": 2 }
+    },
+    {
+      "type": "Feature",
+      "geometry": null,
+      "properties": { "name": "Charlie", "address": { "city": "Paris" } }
+    }
+  ]
+}"#
+}
+
+#[test]
+fn fromgeojson_wkt() {
+    let wrk = Workdir::new("fromgeojson_wkt");
+    wrk.create_from_string("features.geojson", features());
+
+    let mut cmd = wrk.command("fromgeojson");
+    cmd.arg("features.geojson");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "address.city", "lanes", "geometry"],
+        svec!["Alpha", "London", "", "POINT (-0.1278 51.5074)"],
+        svec![
+            "Bravo",
+            "",
+            "2",
+            "LINESTRING (2.3522 48.8566, 2.3376 48.8606)"
+        ],
+        svec!["Charlie", "Paris", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fromgeojson_latlon() {
+    let wrk = Workdir::new("fromgeojson_latlon");
+    wrk.create_from_string("features.geojson", features());
+
+    let mut cmd = wrk.command("fromgeojson");
+    cmd.args(["--geometry", "latlon"]).arg("features.geojson");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "address.city", "lanes", "lat", "lon"],
+        svec!["Alpha", "London", "", "51.5074", "-0.1278"],
+        svec!["Bravo", "", "2", "", ""],
+        svec!["Charlie", "Paris", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let got_err = wrk.output_stderr(&mut cmd);
+    assert_eq!(got_err, "1 non-Point geometries were left empty.\n");
+}
+
+#[test]
+fn fromgeojson_drop_geojsonseq() {
+    let wrk = Workdir::new("fromgeojson_drop_geojsonseq");
+    wrk.create_from_string(
+        "features.geojsonl",
+        r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[1.5,2.5]},"properties":{"id":"a"}}
+{"type":"Feature","geometry":{"type":"Point","coordinates":[3.5,4.5]},"properties":{"id":"b"}}
+"#,
+    );
+
+    let mut cmd = wrk.command("fromgeojson");
+    cmd.args(["--geometry", "drop"]).arg("features.geojsonl");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id"], svec!["a"], svec!["b"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fromgeojson_togeojson_roundtrip() {
+    let wrk = Workdir::new("fromgeojson_togeojson_roundtrip");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["name", "lat", "lon"],
+            svec!["Alpha", "51.5074", "-0.1278"],
+            svec!["Bravo", "51.5155", "-0.0922"],
+        ],
+    );
+
+    let mut cmd = wrk.command("togeojson");
+    cmd.args(["--lat", "lat", "--lon", "lon", "--output", "out.geojson"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromgeojson");
+    cmd.args(["--geometry", "latlon"]).arg("out.geojson");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "lat", "lon"],
+        svec!["Alpha", "51.5074", "-0.1278"],
+        svec!["Bravo", "51.5155", "-0.0922"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,89 @@
+use crate::workdir::Workdir;
+
+fn create_parquet(wrk: &Workdir) {
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "city", "price"],
+            svec!["1", "Denver", "10.5"],
+            svec!["2", "Boston", "3.25"],
+            svec!["3", "Denver", ""],
+        ],
+    );
+    let mut cmd = wrk.command("toparquet");
+    cmd.arg("data.csv").args(["--output", "data.parquet"]);
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn fromparquet() {
+    let wrk = Workdir::new("fromparquet");
+    create_parquet(&wrk);
+
+    let mut cmd = wrk.command("fromparquet");
+    cmd.arg("data.parquet");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "city", "price"],
+        svec!["1", "Denver", "10.5"],
+        svec!["2", "Boston", "3.25"],
+        svec!["3", "Denver", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fromparquet_select_where() {
+    let wrk = Workdir::new("fromparquet_select_where");
+    create_parquet(&wrk);
+
+    let mut cmd = wrk.command("fromparquet");
+    cmd.arg("data.parquet")
+        .args(["--select", "price,id"])
+        .args(["--where", "city = 'Denver'"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["price", "id"], svec!["10.5", "1"], svec!["", "3"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fromparquet_where_numeric() {
+    let wrk = Workdir::new("fromparquet_where_numeric");
+    create_parquet(&wrk);
+
+    let mut cmd = wrk.command("fromparquet");
+    cmd.arg("data.parquet")
+        .args(["--where", "price > 5 OR id = 2"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "city", "price"],
+        svec!["1", "Denver", "10.5"],
+        svec!["2", "Boston", "3.25"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fromparquet_tsv_output() {
+    let wrk = Workdir::new("fromparquet_tsv_output");
+    create_parquet(&wrk);
+
+    let mut cmd = wrk.command("fromparquet");
+    cmd.arg("data.parquet")
+        .args(["--select", "id,city"])
+        .args(["--output", "data.tsv"]);
+    wrk.assert_success(&mut cmd);
+
+    let got = wrk.read_to_string("data.tsv");
+    assert_eq!(got, "id\tcity\n1\tDenver\n2\tBoston\n3\tDenver\n");
+}
+
+#[test]
+fn fromparquet_unknown_column() {
+    let wrk = Workdir::new("fromparquet_unknown_column");
+    create_parquet(&wrk);
+
+    let mut cmd = wrk.command("fromparquet");
+    cmd.arg("data.parquet").args(["--select", "nope"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,53 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn fromsqlite_query() {
+    let wrk = Workdir::new("fromsqlite_query");
+    wrk.create(
+        "orders.csv",
+        vec![
+            svec!["customer", "amount"],
+            svec!["a", "10"],
+            svec!["b", "5"],
+            svec!["a", "7"],
+        ],
+    );
+
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite").arg("orders.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromsqlite");
+    cmd.arg("db.sqlite")
+        .args([
+            "--query",
+            "SELECT customer, sum(amount) AS total, NULL AS note FROM orders GROUP BY 1 ORDER \
+             BY 1",
+        ])
+        .args(["--delimiter", ";"]);
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "customer;total;note\na;17;\nb;5;");
+}
+
+#[test]
+fn fromsqlite_table_or_query() {
+    let wrk = Workdir::new("fromsqlite_table_or_query");
+    wrk.create("data.csv", vec![svec!["a"], svec!["1"]]);
+
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite").arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromsqlite");
+    cmd.arg("db.sqlite");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("fromsqlite");
+    cmd.arg("db.sqlite")
+        .args(["--table", "data", "--query", "SELECT 1"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("fromsqlite");
+    cmd.arg("db.sqlite").args(["--table", "missing"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,122 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn fwf_widths() {
+    let wrk = Workdir::new("fwf_widths");
+    wrk.create_from_string(
+        "data.txt",
+        "0000000001NY   Alice Smith\n0000000002CA   Bob Jones\r\n\n0000000003TX\n",
+    );
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "10,5,20"]).arg("data.txt");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["_col_1", "_col_2", "_col_3"],
+        svec!["0000000001", "NY", "Alice Smith"],
+        svec!["0000000002", "CA", "Bob Jones"],
+        svec!["0000000003", "TX", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fwf_names_trim_skip_lines() {
+    let wrk = Workdir::new("fwf_names_trim_skip_lines");
+    wrk.create_from_string(
+        "data.txt",
+        "REPORT 2024-01-31\n------\n  1 NY   Alice\n 22 CA   Bob\n",
+    );
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "3,5,6"])
+        .args(["--names", "id,state,name"])
+        .args(["--trim", "none", "--skip-lines", "2"])
+        .arg("data.txt");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "state", "name"],
+        svec!["  1", " NY  ", " Alice"],
+        svec![" 22", " CA  ", " Bob"],
+    ];
+    assert_eq!(got, expected);
+
+    // one name per width
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "3,5,6"])
+        .args(["--names", "id,state"])
+        .arg("data.txt");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn fwf_spec() {
+    let wrk = Workdir::new("fwf_spec");
+    wrk.create_from_string("data.txt", "0001NY  Alice\n0002CA  Bob\n");
+    wrk.create(
+        "spec.csv",
+        vec![
+            svec!["name", "start", "length"],
+            svec!["name", "9", "10"],
+            svec!["id", "1", "4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--spec", "spec.csv"]).arg("data.txt");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "id"],
+        svec!["Alice", "0001"],
+        svec!["Bob", "0002"],
+    ];
+    assert_eq!(got, expected);
+
+    // a start of 0 is invalid, as starts are 1-based
+    wrk.create(
+        "bad_spec.csv",
+        vec![svec!["name", "start", "length"], svec!["id", "0", "4"]],
+    );
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--spec", "bad_spec.csv"]).arg("data.txt");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn fwf_encoding() {
+    let wrk = Workdir::new("fwf_encoding");
+    // "Zoë" & "José" in latin1
+    std::fs::write(wrk.path("data.txt"), b"01Zo\xeb  \n02Jos\xe9 \n").unwrap();
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "2,5", "--encoding", "latin1"])
+        .arg("data.txt");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["_col_1", "_col_2"],
+        svec!["01", "Zoë"],
+        svec!["02", "José"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "2,5", "--encoding", "klingon"])
+        .arg("data.txt");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn fwf_widths_or_spec() {
+    let wrk = Workdir::new("fwf_widths_or_spec");
+    wrk.create_from_string("data.txt", "0001NY\n");
+    wrk.create("spec.csv", vec![svec!["name", "start", "length"]]);
+
+    let mut cmd = wrk.command("fwf");
+    cmd.arg("data.txt");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "4,2", "--spec", "spec.csv"])
+        .arg("data.txt");
+    wrk.assert_err(&mut cmd);
+}
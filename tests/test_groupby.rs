@@ -0,0 +1,112 @@
+use crate::workdir::Workdir;
+
+fn orders() -> Vec<Vec<String>> {
+    vec![
+        svec!["customer", "store", "amount", "date"],
+        svec!["bob", "north", "10", "2024-03-01"],
+        svec!["alice", "south", "2.5", "2024-01-15"],
+        svec!["bob", "south", "5", "2023-12-31"],
+        svec!["alice", "south", "", "2024-02-01"],
+        svec!["carol", "north", "7", ""],
+        svec!["alice", "north", "4", "2024-01-02"],
+    ]
+}
+
+#[test]
+fn groupby() {
+    let wrk = Workdir::new("groupby");
+    wrk.create("orders.csv", orders());
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("customer")
+        .args([
+            "--agg",
+            "count(),count(amount),sum(amount),mean(amount),min(date),max(date)",
+        ])
+        .arg("orders.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "customer",
+            "count",
+            "count_amount",
+            "sum_amount",
+            "mean_amount",
+            "min_date",
+            "max_date"
+        ],
+        svec!["alice", "3", "2", "6.5", "3.25", "2024-01-02", "2024-02-01"],
+        svec!["bob", "2", "2", "15", "7.5", "2023-12-31", "2024-03-01"],
+        svec!["carol", "1", "1", "7", "7", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_spill() {
+    let wrk = Workdir::new("groupby_spill");
+    wrk.create("orders.csv", orders());
+
+    // the same results when the groups are spilled to disk every group
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("store,customer")
+        .args([
+            "--agg",
+            "sum(amount),mean(amount),first(date),last(date),max(amount)",
+        ])
+        .args(["--spill-groups", "1"])
+        .arg("orders.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "store",
+            "customer",
+            "sum_amount",
+            "mean_amount",
+            "first_date",
+            "last_date",
+            "max_amount"
+        ],
+        svec!["north", "alice", "4", "4", "2024-01-02", "2024-01-02", "4"],
+        svec!["north", "bob", "10", "10", "2024-03-01", "2024-03-01", "10"],
+        svec!["north", "carol", "7", "7", "", "", "7"],
+        svec![
+            "south",
+            "alice",
+            "2.5",
+            "2.5",
+            "2024-01-15",
+            "2024-02-01",
+            "2.5"
+        ],
+        svec!["south", "bob", "5", "5", "2023-12-31", "2023-12-31", "5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_invalid() {
+    let wrk = Workdir::new("groupby_invalid");
+    wrk.create("orders.csv", orders());
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("store")
+        .args(["--agg", "sum(customer)"])
+        .arg("orders.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "Invalid value in row 1 for sum: \"bob\" is not a number.\n"
+    );
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("store")
+        .args(["--agg", "median(amount)"])
+        .arg("orders.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("usage error: Invalid aggregation: median(amount)."));
+    wrk.assert_err(&mut cmd);
+}
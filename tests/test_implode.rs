@@ -0,0 +1,71 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn implode() {
+    let wrk = Workdir::new("implode");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "colors"],
+            svec!["John", "blue"],
+            svec!["Mary", "red"],
+            svec!["John", "yellow"],
+        ],
+    );
+    let mut cmd = wrk.command("implode");
+    cmd.arg("colors").arg("|").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "colors"],
+        svec!["John", "blue|yellow"],
+        svec!["Mary", "red"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn implode_group_by() {
+    let wrk = Workdir::new("implode_group_by");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "tag", "date"],
+            svec!["1", "a", "2024-01-01"],
+            svec!["2", "b", "2024-01-02"],
+            svec!["1", "", "2024-01-03"],
+            svec!["1", "c", "2024-01-04"],
+            svec!["1", "a", "2024-01-05"],
+        ],
+    );
+    let mut cmd = wrk.command("implode");
+    cmd.arg("tag")
+        .arg(";")
+        .args(["--group-by", "id"])
+        .args(["--rename", "tags"])
+        .args(["--unique", "--skip-empty"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "tags", "date"],
+        svec!["1", "a;c", "2024-01-01"],
+        svec!["2", "b", "2024-01-02"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn implode_no_headers() {
+    let wrk = Workdir::new("implode_no_headers");
+    wrk.create(
+        "data.csv",
+        vec![svec!["Mary", "yellow"], svec!["Mary", "blue"]],
+    );
+    let mut cmd = wrk.command("implode");
+    cmd.arg("2").arg("|").arg("--no-headers").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["Mary", "yellow|blue"]];
+    assert_eq!(got, expected);
+}
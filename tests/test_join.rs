@@ -301,3 +301,204 @@ fn join_cross_no_headers() {
     ];
     assert_eq!(got, expected);
 }
+
+fn setup_asof(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "trades.csv",
+        vec![
+            svec!["sym", "time", "qty"],
+            svec!["A", "2024-01-01 10:00:05", "1"],
+            svec!["B", "2024-01-01 10:00:10", "2"],
+            svec!["A", "2024-01-01 10:02:00", "3"],
+            svec!["A", "2024-01-01 09:00:00", "4"],
+        ],
+    );
+    wrk.create(
+        "quotes.csv",
+        vec![
+            svec!["sym", "time", "price"],
+            svec!["A", "2024-01-01 10:01:00", "101"],
+            svec!["A", "2024-01-01 10:00:00", "100"],
+            svec!["B", "2024-01-01 10:00:12", "50"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn join_asof_backward() {
+    let wrk = setup_asof("join_asof_backward");
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--asof", "backward"])
+        .args(["sym,time", "trades.csv", "sym,time", "quotes.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["sym", "time", "qty", "sym", "time", "price"],
+        svec![
+            "A",
+            "2024-01-01 10:00:05",
+            "1",
+            "A",
+            "2024-01-01 10:00:00",
+            "100"
+        ],
+        svec!["B", "2024-01-01 10:00:10", "2", "", "", ""],
+        svec![
+            "A",
+            "2024-01-01 10:02:00",
+            "3",
+            "A",
+            "2024-01-01 10:01:00",
+            "101"
+        ],
+        svec!["A", "2024-01-01 09:00:00", "4", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_asof_nearest_tolerance() {
+    let wrk = setup_asof("join_asof_nearest_tolerance");
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--asof", "nearest"])
+        .args(["--tolerance", "30s"])
+        .args(["sym,time", "trades.csv", "sym,time", "quotes.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["sym", "time", "qty", "sym", "time", "price"],
+        svec![
+            "A",
+            "2024-01-01 10:00:05",
+            "1",
+            "A",
+            "2024-01-01 10:00:00",
+            "100"
+        ],
+        svec![
+            "B",
+            "2024-01-01 10:00:10",
+            "2",
+            "B",
+            "2024-01-01 10:00:12",
+            "50"
+        ],
+        svec!["A", "2024-01-01 10:02:00", "3", "", "", ""],
+        svec!["A", "2024-01-01 09:00:00", "4", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+join_test!(
+    join_right_semi,
+    |wrk: Workdir, mut cmd: process::Command, headers: bool| {
+    cmd.arg("--right-semi");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let mut expected = vec![
+        svec!["Boston", "Logan Airport"],
+        svec!["Boston", "Boston Garden"],
+        svec!["Buffalo", "Ralph Wilson Stadium"],
+    ];
+    if headers {
+        expected.insert(0, svec!["city", "place"]);
+    }
+    assert_eq!(got, expected);
+    }
+);
+
+join_test!(
+    join_right_anti,
+    |wrk: Workdir, mut cmd: process::Command, headers: bool| {
+    cmd.arg("--right-anti");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let mut expected = vec![
+        svec!["Orlando", "Disney World"],
+        svec!["BOSTON", "BOSTON COMMON"],
+    ];
+    if headers {
+        expected.insert(0, svec!["city", "place"]);
+    }
+    assert_eq!(got, expected);
+    }
+);
+
+#[test]
+fn join_on_three_inputs() {
+    let wrk = setup("join_on_three_inputs", true);
+    wrk.create(
+        "mayors.csv",
+        vec![
+            svec!["city", "mayor"],
+            svec!["Buffalo", "Sean Ryan"],
+            svec!["Boston", "Michelle Wu"],
+        ],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--on", "city", "cities.csv", "places.csv", "mayors.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state", "city", "place", "city", "mayor"],
+        svec![
+            "Boston",
+            "MA",
+            "Boston",
+            "Logan Airport",
+            "Boston",
+            "Michelle Wu"
+        ],
+        svec![
+            "Boston",
+            "MA",
+            "Boston",
+            "Boston Garden",
+            "Boston",
+            "Michelle Wu"
+        ],
+        svec![
+            "Buffalo",
+            "NY",
+            "Buffalo",
+            "Ralph Wilson Stadium",
+            "Buffalo",
+            "Sean Ryan"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_on_right_invalid() {
+    let wrk = setup("join_on_right_invalid", true);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--right", "--on", "city"])
+        .args(["cities.csv", "places.csv", "cities.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+join_test!(
+    join_partitions_full,
+    |wrk: Workdir, mut cmd: process::Command, headers: bool| {
+        cmd.args(["--full", "--partitions", "3"]);
+        let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        // the rows are written partition by partition
+        got[usize::from(headers)..].sort();
+        let expected = make_rows(
+            headers,
+            false,
+            vec![
+                svec!["", "", "BOSTON", "BOSTON COMMON"],
+                svec!["", "", "Orlando", "Disney World"],
+                svec!["Boston", "MA", "Boston", "Boston Garden"],
+                svec!["Boston", "MA", "Boston", "Logan Airport"],
+                svec!["Buffalo", "NY", "Buffalo", "Ralph Wilson Stadium"],
+                svec!["New York", "NY", "", ""],
+                svec!["San Francisco", "CA", "", ""],
+            ],
+        );
+        assert_eq!(got, expected);
+    }
+);
@@ -0,0 +1,85 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "ts", "value"],
+            svec!["a", "2024-01-02", "12"],
+            svec!["b", "2024-01-01", "7"],
+            svec!["a", "2024-01-01", "10"],
+            svec!["b", "2024-01-02", "9"],
+            svec!["a", "2024-01-03", "15"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn lag_streaming() {
+    let wrk = setup("lag_streaming");
+    let mut cmd = wrk.command("lag");
+    cmd.args(["--columns", "value"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "ts", "value", "value_lag1"],
+        svec!["a", "2024-01-02", "12", ""],
+        svec!["b", "2024-01-01", "7", "12"],
+        svec!["a", "2024-01-01", "10", "7"],
+        svec!["b", "2024-01-02", "9", "10"],
+        svec!["a", "2024-01-03", "15", "9"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn lag_partition_order_by() {
+    let wrk = setup("lag_partition_order_by");
+    let mut cmd = wrk.command("lag");
+    cmd.args(["--columns", "value", "--partition-by", "id"])
+        .args(["--order-by", "ts"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "ts", "value", "value_lag1"],
+        svec!["b", "2024-01-01", "7", ""],
+        svec!["a", "2024-01-01", "10", ""],
+        svec!["a", "2024-01-02", "12", "10"],
+        svec!["b", "2024-01-02", "9", "7"],
+        svec!["a", "2024-01-03", "15", "12"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn lag_lead_partition() {
+    let wrk = setup("lag_lead_partition");
+    let mut cmd = wrk.command("lag");
+    cmd.args(["--columns", "ts,value", "--shift=-1"])
+        .args(["--partition-by", "id"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "ts", "value", "ts_lead1", "value_lead1"],
+        svec!["a", "2024-01-02", "12", "2024-01-01", "10"],
+        svec!["b", "2024-01-01", "7", "2024-01-02", "9"],
+        svec!["a", "2024-01-01", "10", "2024-01-03", "15"],
+        svec!["b", "2024-01-02", "9", "", ""],
+        svec!["a", "2024-01-03", "15", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn lag_zero_shift() {
+    let wrk = setup("lag_zero_shift");
+    let mut cmd = wrk.command("lag");
+    cmd.args(["--columns", "value", "--shift", "0"])
+        .arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
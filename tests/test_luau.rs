@@ -2133,6 +2133,65 @@ fn luau_filter() {
     assert_eq!(got, expected);
 }
 
+fn create_similar_names(wrk: &Workdir) {
+    wrk.create(
+        "names.csv",
+        vec![
+            svec!["name", "alias"],
+            svec!["Robert", "Rupert"],
+            svec!["kitten", "sitting"],
+            svec!["Smith", "Smyth"],
+            svec!["Jon Smith", "Smith Jon"],
+        ],
+    );
+}
+
+#[test]
+fn luau_map_similarity() {
+    let wrk = Workdir::new("luau_map_similarity");
+    create_similar_names(&wrk);
+
+    let mut cmd = wrk.command("luau");
+    cmd.arg("map")
+        .arg("distance,alike,cosine")
+        .arg(
+            "return {qsv_levenshtein(name, alias), qsv_soundex_eq(name, alias), \
+             string.format('%.3f', qsv_ngram_cosine(name, alias))}",
+        )
+        .arg("names.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "alias", "distance", "alike", "cosine"],
+        svec!["Robert", "Rupert", "2", "true", "0.400"],
+        svec!["kitten", "sitting", "3", "false", "0.365"],
+        svec!["Smith", "Smyth", "1", "true", "0.500"],
+        svec!["Jon Smith", "Smith Jon", "8", "false", "0.750"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn luau_filter_similarity() {
+    let wrk = Workdir::new("luau_filter_similarity");
+    create_similar_names(&wrk);
+
+    let mut cmd = wrk.command("luau");
+    cmd.arg("filter")
+        .arg("qsv_jaro_winkler(name, alias) >= 0.85")
+        .arg("names.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name", "alias"], svec!["Smith", "Smyth"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("luau");
+    cmd.arg("filter")
+        .arg("qsv_ngram_cosine(name, alias, 0) > 0")
+        .arg("names.csv");
+    wrk.assert_err(&mut cmd);
+}
+
 #[test]
 fn luau_filter_error() {
     let wrk = Workdir::new("luau");
@@ -0,0 +1,51 @@
+use crate::workdir::Workdir;
+
+fn wide() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "region", "y2023", "y2024"],
+        svec!["1", "east", "15", "7"],
+        svec!["2", "west", "", "3"],
+    ]
+}
+
+#[test]
+fn melt() {
+    let wrk = Workdir::new("melt");
+    wrk.create("in.csv", wide());
+
+    let mut cmd = wrk.command("melt");
+    cmd.args(["--id-vars", "id,region"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "region", "variable", "value"],
+        svec!["1", "east", "y2023", "15"],
+        svec!["1", "east", "y2024", "7"],
+        svec!["2", "west", "y2023", ""],
+        svec!["2", "west", "y2024", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn melt_value_vars() {
+    let wrk = Workdir::new("melt_value_vars");
+    wrk.create("in.csv", wide());
+
+    let mut cmd = wrk.command("melt");
+    cmd.args(["--id-vars", "region"])
+        .args(["--value-vars", "y2023,y2024"])
+        .args(["--variable-name", "year"])
+        .args(["--value-name", "amount"])
+        .arg("--skip-empty")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "year", "amount"],
+        svec!["east", "y2023", "15"],
+        svec!["east", "y2024", "7"],
+        svec!["west", "y2024", "3"],
+    ];
+    assert_eq!(got, expected);
+}
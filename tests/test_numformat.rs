@@ -0,0 +1,117 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["item", "price", "qty"],
+        svec!["apple", "3.14159", "1234567"],
+        svec!["banana", "2", "12"],
+        svec!["cherry", "-0.001", "n/a"],
+        svec!["durian", "1234.5", ""],
+    ]
+}
+
+#[test]
+fn numformat_decimals() {
+    let wrk = Workdir::new("numformat_decimals");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("numformat");
+    cmd.args(["--decimals", "2"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["item", "price", "qty"],
+        svec!["apple", "3.14", "1234567.00"],
+        svec!["banana", "2.00", "12.00"],
+        svec!["cherry", "0.00", "n/a"],
+        svec!["durian", "1234.50", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn numformat_sig_figs_select() {
+    let wrk = Workdir::new("numformat_sig_figs_select");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "value"],
+            svec!["1", "123456"],
+            svec!["2", "0.012345"],
+            svec!["3", "1.5"],
+            svec!["4", "9.996"],
+            svec!["5", "1.2e3"],
+        ],
+    );
+
+    let mut cmd = wrk.command("numformat");
+    cmd.args(["--sig-figs", "3", "--select", "value"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "value"],
+        svec!["1", "123000"],
+        svec!["2", "0.0123"],
+        svec!["3", "1.50"],
+        svec!["4", "10.0"],
+        svec!["5", "1200"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn numformat_thousands() {
+    let wrk = Workdir::new("numformat_thousands");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("numformat");
+    cmd.args(["--thousands", ","]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["item", "price", "qty"],
+        svec!["apple", "3.14159", "1,234,567"],
+        svec!["banana", "2", "12"],
+        svec!["cherry", "-0.001", "n/a"],
+        svec!["durian", "1,234.5", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn numformat_pad_align() {
+    let wrk = Workdir::new("numformat_pad_align");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("numformat");
+    cmd.args(["--thousands", ",", "--pad-align", "--select", "price"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["item", "price", "qty"],
+        svec!["apple", "    3.14159", "1234567"],
+        svec!["banana", "    2      ", "12"],
+        svec!["cherry", "   -0.001  ", "n/a"],
+        svec!["durian", "1,234.5    ", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn numformat_decimals_and_sig_figs() {
+    let wrk = Workdir::new("numformat_decimals_and_sig_figs");
+    wrk.create("in.csv", data());
+
+    let mut cmd = wrk.command("numformat");
+    cmd.args(["--decimals", "2", "--sig-figs", "3"])
+        .arg("in.csv");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --decimals and --sig-figs cannot be used together.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,67 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "color", "size"],
+            svec!["1", "red", "S"],
+            svec!["2", "blue", "M"],
+            svec!["3", "red", ""],
+            svec!["4", "green", "M"],
+            svec!["5", "blue", "L"],
+            svec!["6", "red", "M"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn onehot_columns() {
+    let wrk = setup("onehot_columns");
+    let mut cmd = wrk.command("onehot");
+    cmd.arg("color,size").arg("in.csv");
+
+    // the categories from the most frequent one down, ties sorted by value
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "id",
+            "color_red",
+            "color_blue",
+            "color_green",
+            "size_M",
+            "size_L",
+            "size_S"
+        ],
+        svec!["1", "1", "0", "0", "0", "0", "1"],
+        svec!["2", "0", "1", "0", "1", "0", "0"],
+        svec!["3", "1", "0", "0", "0", "0", "0"],
+        svec!["4", "0", "0", "1", "1", "0", "0"],
+        svec!["5", "0", "1", "0", "0", "1", "0"],
+        svec!["6", "1", "0", "0", "1", "0", "0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn onehot_top_k_drop_first_stdin() {
+    let wrk = setup("onehot_top_k_drop_first_stdin");
+    let mut cmd = wrk.command("onehot");
+    cmd.args(["color", "--top-k", "2", "--drop-first"])
+        .stdin(std::fs::File::open(wrk.path("in.csv")).unwrap());
+
+    // red is dropped, and green isn't in the top 2
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "color_blue", "size"],
+        svec!["1", "0", "S"],
+        svec!["2", "1", "M"],
+        svec!["3", "0", ""],
+        svec!["4", "0", "M"],
+        svec!["5", "1", "L"],
+        svec!["6", "0", "M"],
+    ];
+    assert_eq!(got, expected);
+}
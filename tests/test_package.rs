@@ -0,0 +1,141 @@
+use std::fs;
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::workdir::Workdir;
+
+fn create_delivery(wrk: &Workdir) {
+    wrk.create_subdir("delivery").unwrap();
+    wrk.create_subdir("delivery/lookups").unwrap();
+    wrk.create(
+        "delivery/sales.csv",
+        vec![
+            svec!["id", "amount", "region"],
+            svec!["1", "10.5", "north"],
+            svec!["2", "7", ""],
+            svec!["3", "12.25", "south"],
+        ],
+    );
+    wrk.create_with_delim(
+        "delivery/lookups/regions.tsv",
+        vec![svec!["region", "manager"], svec!["north", "Ann"]],
+        b'\t',
+    );
+    // not CSV files
+    wrk.create_from_string("delivery/README.md", "# Q3 sales\n");
+    wrk.create_from_string("delivery/.hidden.csv", "a\n1\n");
+}
+
+#[test]
+fn package_create() {
+    let wrk = Workdir::new("package_create");
+    create_delivery(&wrk);
+
+    let mut cmd = wrk.command("package");
+    cmd.arg("create")
+        .args(["--name", "Q3 Sales"])
+        .arg("delivery");
+    wrk.assert_success(&mut cmd);
+
+    let package: Value =
+        serde_json::from_str(&wrk.read_to_string("delivery/datapackage.json")).unwrap();
+    assert_eq!(package["profile"], "tabular-data-package");
+    assert_eq!(package["name"], "q3-sales");
+
+    let resources = package["resources"].as_array().unwrap();
+    assert_eq!(resources.len(), 2);
+
+    let lookups = &resources[0];
+    assert_eq!(lookups["name"], "lookups-regions");
+    assert_eq!(lookups["path"], "lookups/regions.tsv");
+    assert_eq!(lookups["mediatype"], "text/tab-separated-values");
+    assert_eq!(lookups["dialect"], json!({"delimiter": "\t"}));
+
+    let sales = &resources[1];
+    let sales_csv = fs::read(wrk.path("delivery/sales.csv")).unwrap();
+    assert_eq!(sales["name"], "sales");
+    assert_eq!(sales["path"], "sales.csv");
+    assert_eq!(sales["format"], "csv");
+    assert_eq!(sales["bytes"], sales_csv.len());
+    assert_eq!(
+        sales["hash"],
+        format!("sha256:{:x}", Sha256::digest(&sales_csv))
+    );
+    assert_eq!(sales["stats"]["rows"], 3);
+    assert!(sales.get("dialect").is_none());
+    assert_eq!(
+        sales["schema"],
+        json!({"fields": [
+            {"name": "id", "type": "integer", "constraints": {"required": true}},
+            {"name": "amount", "type": "number", "constraints": {"required": true}},
+            {"name": "region", "type": "string"}
+        ]})
+    );
+
+    // the manifest isn't overwritten without --force
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("usage error: "), "{got}");
+    assert!(got.contains("already exists. Use --force to overwrite it."));
+    cmd.arg("--force");
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn package_verify() {
+    let wrk = Workdir::new("package_verify");
+    create_delivery(&wrk);
+
+    let mut cmd = wrk.command("package");
+    cmd.arg("create").arg("delivery");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("package");
+    cmd.arg("verify").arg("delivery/datapackage.json");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["resource", "path", "rows", "status", "errors"],
+        svec!["lookups-regions", "lookups/regions.tsv", "1", "valid", ""],
+        svec!["sales", "sales.csv", "3", "valid", ""],
+    ];
+    assert_eq!(got, expected);
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn package_verify_changed_files() {
+    let wrk = Workdir::new("package_verify_changed_files");
+    create_delivery(&wrk);
+
+    let mut cmd = wrk.command("package");
+    cmd.arg("create").arg("delivery");
+    wrk.assert_success(&mut cmd);
+
+    // a row with an invalid amount is appended, and the lookups are lost
+    let mut sales_csv = wrk.read_to_string("delivery/sales.csv");
+    sales_csv.push_str("4,n/a,east\n");
+    wrk.create_from_string("delivery/sales.csv", &sales_csv);
+    fs::remove_file(wrk.path("delivery/lookups/regions.tsv")).unwrap();
+
+    // verify also accepts the directory of the manifest
+    let mut cmd = wrk.command("package");
+    cmd.arg("verify").arg("delivery");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        got[1],
+        svec!["lookups-regions", "lookups/regions.tsv", "", "missing", ""]
+    );
+    assert_eq!(got[2][..4], svec!["sales", "sales.csv", "4", "invalid"]);
+    let errors: Vec<&str> = got[2][4].split("; ").collect();
+    assert_eq!(errors[0], "amount: \"n/a\" in row 4 is not a number");
+    assert!(errors[1].starts_with("bytes: expected "), "{errors:?}");
+    assert!(
+        errors[2].starts_with("hash: expected sha256:"),
+        "{errors:?}"
+    );
+    assert_eq!(errors[3], "rows: expected 3, found 4");
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "2 of 2 resources failed verification.\n");
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,132 @@
+use crate::workdir::Workdir;
+
+fn sales() -> Vec<Vec<String>> {
+    vec![
+        svec!["region", "year", "amount"],
+        svec!["east", "2023", "10"],
+        svec!["west", "2024", "3"],
+        svec!["east", "2023", "5.5"],
+        svec!["east", "2024", "7"],
+        svec!["north", "", "1"],
+        svec!["west", "2024", ""],
+    ]
+}
+
+#[test]
+fn pivot_sum() {
+    let wrk = Workdir::new("pivot_sum");
+    wrk.create("sales.csv", sales());
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args(["--index", "region"])
+        .args(["--columns", "year"])
+        .args(["--values", "amount"])
+        .args(["--agg", "sum"])
+        .arg("sales.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "(NULL)", "2023", "2024"],
+        svec!["east", "", "15.5", "7"],
+        svec!["north", "1", "", ""],
+        svec!["west", "", "", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_count_first() {
+    let wrk = Workdir::new("pivot_count_first");
+    wrk.create("sales.csv", sales());
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args(["-i", "region", "-c", "year", "-v", "amount"])
+        .args(["--agg", "count"])
+        .arg("sales.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "(NULL)", "2023", "2024"],
+        svec!["east", "", "2", "1"],
+        svec!["north", "1", "", ""],
+        svec!["west", "", "", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args(["-i", "region", "-c", "year", "-v", "amount"])
+        .arg("sales.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "(NULL)", "2023", "2024"],
+        svec!["east", "", "10", "7"],
+        svec!["north", "1", "", ""],
+        svec!["west", "", "", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_spill() {
+    let wrk = Workdir::new("pivot_spill");
+    wrk.create("sales.csv", sales());
+
+    // the same results when the cells are spilled to disk every 2 cells
+    for (agg, expected) in [
+        (
+            "sum",
+            vec![
+                svec!["region", "(NULL)", "2023", "2024"],
+                svec!["east", "", "15.5", "7"],
+                svec!["north", "1", "", ""],
+                svec!["west", "", "", "3"],
+            ],
+        ),
+        (
+            "first",
+            vec![
+                svec!["region", "(NULL)", "2023", "2024"],
+                svec!["east", "", "10", "7"],
+                svec!["north", "1", "", ""],
+                svec!["west", "", "", "3"],
+            ],
+        ),
+    ] {
+        let mut cmd = wrk.command("pivot");
+        cmd.args(["-i", "region", "-c", "year", "-v", "amount"])
+            .args(["--agg", agg])
+            .args(["--spill-cells", "2"])
+            .arg("sales.csv");
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        assert_eq!(got, expected);
+    }
+}
+
+#[test]
+fn pivot_invalid() {
+    let wrk = Workdir::new("pivot_invalid");
+    wrk.create("sales.csv", sales());
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args(["-i", "amount", "-c", "year", "-v", "region"])
+        .args(["--agg", "sum"])
+        .arg("sales.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "Invalid --values value in row 1: \"east\" is not a number.\n"
+    );
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args(["-i", "region", "-c", "year,amount", "-v", "amount"])
+        .arg("sales.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --columns and --values must each select a single column.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
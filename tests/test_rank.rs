@@ -0,0 +1,93 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["region", "amount"],
+            svec!["east", "10"],
+            svec!["west", "7"],
+            svec!["east", "30"],
+            svec!["east", "10"],
+            svec!["west", ""],
+            svec!["east", "5"],
+            svec!["west", "12"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn rank_min() {
+    let wrk = setup("rank_min");
+    let mut cmd = wrk.command("rank");
+    cmd.args(["--by", "amount"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "amount", "rank"],
+        svec!["east", "10", "3"],
+        svec!["west", "7", "2"],
+        svec!["east", "30", "6"],
+        svec!["east", "10", "3"],
+        svec!["west", "", ""],
+        svec!["east", "5", "1"],
+        svec!["west", "12", "5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn rank_dense_partition_desc() {
+    let wrk = setup("rank_dense_partition_desc");
+    let mut cmd = wrk.command("rank");
+    cmd.args(["--by", "amount", "--partition-by", "region"])
+        .args(["--method", "dense", "--desc"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "amount", "rank"],
+        svec!["east", "10", "2"],
+        svec!["west", "7", "2"],
+        svec!["east", "30", "1"],
+        svec!["east", "10", "2"],
+        svec!["west", "", ""],
+        svec!["east", "5", "3"],
+        svec!["west", "12", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn rank_percent_stdin() {
+    let wrk = setup("rank_percent_stdin");
+    let mut cmd = wrk.command("rank");
+    cmd.args(["--by", "amount", "--partition-by", "region"])
+        .args(["--method", "percent", "--name", "pct"])
+        .stdin(std::fs::File::open(wrk.path("in.csv")).unwrap());
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "amount", "pct"],
+        svec!["east", "10", "0.3333"],
+        svec!["west", "7", "0"],
+        svec!["east", "30", "1"],
+        svec!["east", "10", "0.3333"],
+        svec!["west", "", ""],
+        svec!["east", "5", "0"],
+        svec!["west", "12", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn rank_invalid_method() {
+    let wrk = setup("rank_invalid_method");
+    let mut cmd = wrk.command("rank");
+    cmd.args(["--by", "amount", "--method", "average"])
+        .arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,163 @@
+use crate::workdir::Workdir;
+
+fn create_redaction_data(wrk: &Workdir) {
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "ssn", "email", "birth_date", "age", "zip"],
+            svec![
+                "Alice",
+                "123-45-6789",
+                "alice@example.com",
+                "1987-06-15",
+                "37",
+                "02134"
+            ],
+            svec!["Bob", "", "bob@example.org", "not a date", "42.5", "10001"],
+        ],
+    );
+    wrk.create_from_string(
+        "policy.json",
+        r#"{
+  "analyst": {
+    "ssn": "drop",
+    "email": "mask:4",
+    "birth_date": "generalize:year",
+    "age": "generalize:10",
+    "zip": "generalize:prefix:3"
+  },
+  "vendor": {"ssn": "drop", "email": "drop", "birth_date": "drop"}
+}"#,
+    );
+}
+
+#[test]
+fn redaction_profile() {
+    let wrk = Workdir::new("redaction_profile");
+    create_redaction_data(&wrk);
+
+    let mut cmd = wrk.command("select");
+    cmd.env("QSV_REDACTION_POLICY", wrk.path("policy.json"))
+        .arg("1-")
+        .arg("data.csv")
+        .args(["--profile", "analyst"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "email", "birth_date", "age", "zip"],
+        svec!["Alice", "*************.com", "1987", "30-39", "021**"],
+        svec!["Bob", "***********.org", "", "40-50", "100**"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn redaction_profile_envvar() {
+    let wrk = Workdir::new("redaction_profile_envvar");
+    create_redaction_data(&wrk);
+
+    let mut cmd = wrk.command("sort");
+    cmd.env("QSV_REDACTION_POLICY", wrk.path("policy.json"))
+        .env("QSV_REDACTION_PROFILE", "vendor")
+        .args(["--select", "name", "--reverse"])
+        .arg("data.csv")
+        .args(["--output", "out.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    assert_eq!(
+        wrk.read_to_string("out.csv"),
+        "name,age,zip\nBob,42.5,10001\nAlice,37,02134\n"
+    );
+}
+
+#[test]
+fn redaction_profile_invalid() {
+    let wrk = Workdir::new("redaction_profile_invalid");
+    create_redaction_data(&wrk);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("1-").arg("data.csv").args(["--profile", "analyst"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Redaction profile \"analyst\" is set, but the QSV_REDACTION_POLICY \
+         environment variable with the policy file isn't.\n"
+    );
+
+    let mut cmd = wrk.command("select");
+    cmd.env("QSV_REDACTION_POLICY", wrk.path("policy.json"))
+        .arg("1-")
+        .arg("data.csv")
+        .args(["--profile", "auditor"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: Unknown redaction profile \"auditor\"."),
+        "{got}"
+    );
+    assert!(got.ends_with("are: analyst, vendor.\n"), "{got}");
+}
+
+#[test]
+fn redaction_profile_unsupported() {
+    let wrk = Workdir::new("redaction_profile_unsupported");
+    create_redaction_data(&wrk);
+
+    let mut cmd = wrk.command("extsort");
+    cmd.env("QSV_REDACTION_POLICY", wrk.path("policy.json"))
+        .arg("data.csv")
+        .args(["--profile", "analyst"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Redaction profile \"analyst\" is set, but the extsort command doesn't \
+         support redaction.\n"
+    );
+
+    // the JSONL output isn't CSV, so it can't be redacted
+    let mut cmd = wrk.command("tojsonl");
+    cmd.env("QSV_REDACTION_POLICY", wrk.path("policy.json"))
+        .arg("data.csv")
+        .args(["--profile", "analyst"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Redaction profile \"analyst\" is set, but the tojsonl command doesn't \
+         support redaction.\n"
+    );
+
+    // the values of frequency's value column come from any column
+    let mut cmd = wrk.command("frequency");
+    cmd.env("QSV_REDACTION_POLICY", wrk.path("policy.json"))
+        .env("QSV_REDACTION_PROFILE", "vendor")
+        .arg("data.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Redaction profile \"vendor\" is set, but the frequency command doesn't \
+         support redaction.\n"
+    );
+
+    let mut cmd = wrk.command("slice");
+    cmd.env("QSV_REDACTION_POLICY", wrk.path("policy.json"))
+        .arg("--json")
+        .arg("data.csv")
+        .args(["--profile", "analyst"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --json can't be used with a redaction profile, as the JSON output isn't \
+         redacted.\n"
+    );
+
+    let mut cmd = wrk.command("select");
+    cmd.env("QSV_REDACTION_POLICY", wrk.path("policy.json"))
+        .arg("1-")
+        .arg("--no-headers")
+        .arg("data.csv")
+        .args(["--profile", "analyst"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: --no-headers can't be used with a redaction profile"),
+        "{got}"
+    );
+}
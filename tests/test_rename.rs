@@ -93,3 +93,84 @@ fn rename_noheaders_generic() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn rename_in_place() {
+    let wrk = Workdir::new("rename_in_place");
+    wrk.create(
+        "in.csv",
+        vec![svec!["R", "S"], svec!["1", "b"], svec!["2", "a"]],
+    );
+
+    let mut cmd = wrk.command("rename");
+    cmd.arg("cola,colb")
+        .arg("in.csv")
+        .arg("--in-place")
+        .args(["--backup", ".bak"]);
+    wrk.assert_success(&mut cmd);
+
+    assert_eq!(wrk.read_to_string("in.csv"), "cola,colb\n1,b\n2,a\n");
+    assert_eq!(wrk.read_to_string("in.csv.bak"), "R,S\n1,b\n2,a\n");
+
+    // the tempfile was renamed over the input
+    let leftovers = std::fs::read_dir(wrk.path(""))
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with(".qsv-in-place-")
+        })
+        .count();
+    assert_eq!(leftovers, 0);
+}
+
+#[test]
+fn rename_in_place_invalid() {
+    let wrk = Workdir::new("rename_in_place_invalid");
+    wrk.create("in.csv", vec![svec!["R", "S"], svec!["1", "b"]]);
+
+    let mut cmd = wrk.command("rename");
+    cmd.arg("cola,colb").arg("--in-place");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --in-place cannot be used with <stdin>.\n"
+    );
+
+    let mut cmd = wrk.command("rename");
+    cmd.arg("cola,colb")
+        .arg("https://example.com/in.csv")
+        .arg("--in-place");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --in-place cannot be used with a remote URL.\n"
+    );
+
+    let mut cmd = wrk.command("rename");
+    cmd.arg("cola,colb")
+        .arg("in.csv")
+        .arg("--in-place")
+        .args(["--output", "out.csv"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --in-place and --output are mutually exclusive.\n"
+    );
+
+    let mut cmd = wrk.command("rename");
+    cmd.arg("cola,colb")
+        .arg("in.csv")
+        .args(["--backup", ".bak"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --backup can only be used with --in-place.\n"
+    );
+
+    // the input is untouched
+    assert_eq!(wrk.read_to_string("in.csv"), "R,S\n1,b\n");
+}
@@ -0,0 +1,122 @@
+use std::{
+    io::Write,
+    process::{self, Stdio},
+};
+
+use crate::workdir::Workdir;
+
+fn create_sales(wrk: &Workdir) {
+    let mut rows = vec![svec!["id", "name", "amount"]];
+    for i in 1..=12 {
+        rows.push(vec![
+            i.to_string(),
+            format!("name{i}"),
+            ((i * 7) % 13).to_string(),
+        ]);
+    }
+    wrk.create("sales.csv", rows);
+}
+
+/// run the repl with a script of REPL lines on stdin
+fn run_script(cmd: &mut process::Command, script: &str) -> process::Output {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn repl_preview() {
+    let wrk = Workdir::new("repl_preview");
+    create_sales(&wrk);
+
+    let mut cmd = wrk.command("repl");
+    cmd.arg("sales.csv");
+    let output = run_script(&mut cmd, "select name,amount\n");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    // the header row, 10 previewed rows & the number of rows left
+    assert_eq!(lines.len(), 12, "{stdout}");
+    assert!(lines[0].starts_with("name"), "{stdout}");
+    assert!(lines[0].trim_end().ends_with("amount"), "{stdout}");
+    assert!(lines[1].starts_with("name1 "), "{stdout}");
+    assert_eq!(lines[11], "... 2 more rows");
+}
+
+#[test]
+fn repl_sample_size() {
+    let wrk = Workdir::new("repl_sample_size");
+    create_sales(&wrk);
+
+    let mut cmd = wrk.command("repl");
+    cmd.args(["--sample-size", "5"]).arg("sales.csv");
+    let output = run_script(&mut cmd, "count\n");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "5\n");
+}
+
+#[test]
+fn repl_keep_pipeline_run() {
+    let wrk = Workdir::new("repl_keep_pipeline_run");
+    create_sales(&wrk);
+
+    let mut cmd = wrk.command("repl");
+    cmd.args(["--sample-size", "3"]).arg("sales.csv");
+    let script = "select name,amount\n:keep\nsort -s amount -N\n:keep\n:pipeline\n:run \
+                  sorted.csv\n";
+    let output = run_script(&mut cmd, script);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("\nqsv select name,amount < sales.csv | qsv sort -s amount -N\n"),
+        "{stdout}"
+    );
+
+    // the pipeline is run on the whole input, not only the sample
+    let got = wrk.read_to_string("sorted.csv");
+    let lines: Vec<&str> = got.lines().collect();
+    assert_eq!(lines.len(), 13);
+    assert_eq!(lines[..3], ["name,amount", "name2,1", "name4,2"]);
+    assert_eq!(lines[12], "name11,12");
+}
+
+#[test]
+fn repl_undo() {
+    let wrk = Workdir::new("repl_undo");
+    create_sales(&wrk);
+
+    let mut cmd = wrk.command("repl");
+    cmd.arg("sales.csv");
+    let output = run_script(
+        &mut cmd,
+        ":keep
+select 1
+:keep
+:undo
+:pipeline
+:bogus
+",
+    );
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("There's no successful command output to keep."),
+        "{stderr}"
+    );
+    assert!(stderr.contains("Removed `select 1`."), "{stderr}");
+    assert!(stderr.contains("The pipeline is empty."), "{stderr}");
+    assert!(stderr.contains("Unknown REPL command: :bogus."), "{stderr}");
+}
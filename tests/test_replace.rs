@@ -292,3 +292,27 @@ fn replace_groups() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn replace_in_place_tsv() {
+    let wrk = Workdir::new("replace_in_place_tsv");
+    wrk.create_with_delim(
+        "data.tsv",
+        vec![
+            svec!["identifier", "color"],
+            svec!["164.0", "yellow"],
+            svec!["165.0", "yellow.0"],
+        ],
+        b'\t',
+    );
+    let mut cmd = wrk.command("replace");
+    cmd.arg("\\.0$").arg("").arg("data.tsv").arg("--in-place");
+    wrk.assert_success(&mut cmd);
+
+    // the output keeps the tab delimiter of the input's extension
+    assert_eq!(
+        wrk.read_to_string("data.tsv"),
+        "identifier\tcolor\n164\tyellow\n165\tyellow\n"
+    );
+    assert!(!wrk.path("data.tsv.bak").exists());
+}
@@ -33,6 +33,63 @@ fn sample_seed() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn sample_seed_envvar() {
+    let wrk = Workdir::new("sample_seed_envvar");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["R", "S"],
+            svec!["1", "b"],
+            svec!["2", "a"],
+            svec!["3", "d"],
+            svec!["4", "c"],
+            svec!["5", "f"],
+            svec!["6", "e"],
+            svec!["7", "i"],
+            svec!["8", "h"],
+        ],
+    );
+
+    // same sample as with --seed 42
+    let mut cmd = wrk.command("sample");
+    cmd.env("QSV_SEED", "42").arg("5").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["R", "S"],
+        svec!["1", "b"],
+        svec!["2", "a"],
+        svec!["3", "d"],
+        svec!["7", "i"],
+        svec!["5", "f"],
+    ];
+    assert_eq!(got, expected);
+
+    // --seed takes precedence over QSV_SEED
+    let mut cmd = wrk.command("sample");
+    cmd.env("QSV_SEED", "7")
+        .args(["--seed", "42"])
+        .arg("5")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sample_seed_envvar_invalid() {
+    let wrk = Workdir::new("sample_seed_envvar_invalid");
+    wrk.create("in.csv", vec![svec!["R"], svec!["1"]]);
+
+    let mut cmd = wrk.command("sample");
+    cmd.env("QSV_SEED", "forty-two").arg("1").arg("in.csv");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "QSV_SEED must be an unsigned integer: forty-two\n");
+    wrk.assert_err(&mut cmd);
+}
+
 #[test]
 fn sample_seed_delimiter() {
     let wrk = Workdir::new("sample_seed_delimiter");
@@ -483,3 +540,76 @@ fn sample_percentage_negative_sample_size_error() {
 
     wrk.assert_err(&mut cmd);
 }
+
+fn stratify_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "group"],
+        svec!["1", "a"],
+        svec!["2", "b"],
+        svec!["3", "a"],
+        svec!["4", "a"],
+        svec!["5", "c"],
+        svec!["6", "b"],
+        svec!["7", "a"],
+        svec!["8", "a"],
+    ]
+}
+
+/// the number of sampled rows of each group, checking they're in input order
+fn group_counts(rows: &[Vec<String>]) -> Vec<(String, usize)> {
+    let ids: Vec<u32> = rows[1..]
+        .iter()
+        .map(|row| row[0].parse().unwrap())
+        .collect();
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for row in &rows[1..] {
+        match counts.iter_mut().find(|(group, _)| *group == row[1]) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((row[1].clone(), 1)),
+        }
+    }
+    counts.sort();
+    counts
+}
+
+#[test]
+fn sample_stratify_fixed_n() {
+    let wrk = Workdir::new("sample_stratify_fixed_n");
+    wrk.create("in.csv", stratify_data());
+
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--stratify", "group", "--seed", "42"])
+        .arg("2")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0], svec!["id", "group"]);
+    assert_eq!(
+        group_counts(&got),
+        vec![
+            ("a".to_string(), 2),
+            ("b".to_string(), 2),
+            ("c".to_string(), 1)
+        ]
+    );
+}
+
+#[test]
+fn sample_stratify_percentage_stdin() {
+    let wrk = Workdir::new("sample_stratify_percentage_stdin");
+    wrk.create("in.csv", stratify_data());
+
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--stratify", "group", "--seed", "42"])
+        .arg("0.4")
+        .stdin(std::fs::File::open(wrk.path("in.csv")).unwrap());
+
+    // 40% of the 5 a rows, of the 2 b rows & of the c row, rounded
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        group_counts(&got),
+        vec![("a".to_string(), 2), ("b".to_string(), 1)]
+    );
+}
@@ -257,3 +257,837 @@ fn generate_schema_with_defaults_to_stdout() {
 
     assert_json_eq!(expected_schema_json, output_schema_json);
 }
+
+#[test]
+#[file_serial]
+fn generate_schema_multi_file_union() {
+    let wrk = Workdir::new("generate_schema_multi_file_union").flexible(true);
+    wrk.clear_contents().unwrap();
+
+    wrk.create(
+        "a.csv",
+        vec![
+            svec!["id", "name", "amount"],
+            svec!["1", "Alice", "10"],
+            svec!["2", "Bob", "20"],
+        ],
+    );
+    wrk.create(
+        "b.csv",
+        vec![
+            svec!["id", "name", "amount", "extra"],
+            svec!["3", "Carol", "1.5", "x"],
+            svec!["4", "Dan", "2.5", "y"],
+        ],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("a.csv")
+        .arg("b.csv")
+        .args(["--output", "union.schema.json"]);
+    wrk.assert_success(&mut cmd);
+
+    let output_schema_string: String = wrk.from_str(&wrk.path("union.schema.json"));
+    let output_schema_json: Value =
+        serde_json::from_str(&output_schema_string).expect("parse schema json");
+
+    // make sure it's a valid JSON Schema by compiling with jsonschema library
+    jsonschema::JSONSchema::options()
+        .compile(&output_schema_json)
+        .expect("valid JSON Schema");
+
+    let properties = &output_schema_json["properties"];
+    assert_eq!(properties["id"]["type"], serde_json::json!(["integer"]));
+    assert_eq!(properties["id"]["minimum"], 1);
+    assert_eq!(properties["id"]["maximum"], 4);
+    assert_eq!(properties["id"]["enum"], serde_json::json!([1, 2, 3, 4]));
+    assert_eq!(
+        properties["name"]["enum"],
+        serde_json::json!(["Alice", "Bob", "Carol", "Dan"])
+    );
+    // integer in a.csv + number in b.csv is widened to number
+    assert_eq!(properties["amount"]["type"], serde_json::json!(["number"]));
+    assert_eq!(properties["amount"]["minimum"], 1.5);
+    assert_eq!(properties["amount"]["maximum"], 20);
+    assert!(properties["amount"].get("enum").is_none());
+    assert_eq!(
+        properties["id"]["description"],
+        "id column from a.csv, b.csv"
+    );
+    assert_eq!(
+        properties["extra"]["description"],
+        "extra column from b.csv"
+    );
+    // extra is only in b.csv, so it's not required
+    assert_eq!(
+        output_schema_json["required"],
+        serde_json::json!(["id", "name", "amount"])
+    );
+
+    let deviations: String = wrk.from_str(&wrk.path("union.schema.json.deviations.tsv"));
+    assert_eq!(
+        deviations,
+        "file\tfield\tdeviation\na.csv\tamount\ttype conflict: integer, unified as \
+         number\na.csv\textra\tmissing column\n"
+    );
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_multi_file_requires_output() {
+    let wrk = Workdir::new("generate_schema_multi_file_requires_output").flexible(true);
+    wrk.clear_contents().unwrap();
+
+    wrk.create("a.csv", vec![svec!["id"], svec!["1"]]);
+    wrk.create("b.csv", vec![svec!["id"], svec!["2"]]);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("a.csv").arg("b.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_datapackage() {
+    let wrk = Workdir::new("generate_schema_datapackage").flexible(true);
+    wrk.clear_contents().unwrap();
+
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "color", "amount", "note"],
+            svec!["1", "red", "1.5", ""],
+            svec!["2", "blue", "2.5", "x"],
+            svec!["3", "red", "10.25", "y"],
+        ],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv")
+        .args(["--format", "datapackage"])
+        .args(["--enum-threshold", "2"]);
+    wrk.assert_success(&mut cmd);
+
+    let package: Value =
+        serde_json::from_str(&wrk.read_to_string("datapackage.json")).expect("parse package json");
+    assert_eq!(package["profile"], "tabular-data-package");
+    assert_eq!(package["name"], "data");
+
+    let resources = package["resources"].as_array().unwrap();
+    assert_eq!(resources.len(), 1);
+    let resource = &resources[0];
+    assert_eq!(resource["name"], "data");
+    assert_eq!(resource["path"], "data.csv");
+    assert_eq!(resource["mediatype"], "text/csv");
+    assert_eq!(
+        resource["dialect"],
+        serde_json::json!({"delimiter": ",", "header": true})
+    );
+
+    let fields = resource["schema"]["fields"].as_array().unwrap();
+    assert_eq!(fields.len(), 4);
+    assert_eq!(fields[0]["name"], "id");
+    assert_eq!(fields[0]["type"], "integer");
+    assert_eq!(
+        fields[0]["constraints"],
+        serde_json::json!({"required": true, "minimum": 1, "maximum": 3})
+    );
+    assert_eq!(fields[1]["type"], "string");
+    assert_eq!(
+        fields[1]["constraints"]["enum"],
+        serde_json::json!(["blue", "red"])
+    );
+    assert_eq!(fields[2]["type"], "number");
+    assert_eq!(fields[2]["constraints"]["minimum"], 1.5);
+    // note has an empty value, so it's not required
+    assert_eq!(fields[3]["type"], "string");
+    assert!(fields[3]["constraints"].get("required").is_none());
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_datapackage_multi_file() {
+    let wrk = Workdir::new("generate_schema_datapackage_multi_file").flexible(true);
+    wrk.clear_contents().unwrap();
+
+    wrk.create("a.csv", vec![svec!["id", "name"], svec!["1", "Alice"]]);
+    wrk.create_with_delim(
+        "b.tsv",
+        vec![svec!["id", "name"], svec!["2.5", "Bob"]],
+        b'\t',
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("a.csv")
+        .arg("b.tsv")
+        .args(["--format", "datapackage"])
+        .arg("--stdout");
+    let package: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+
+    let resources = package["resources"].as_array().unwrap();
+    assert_eq!(resources[0]["path"], "a.csv");
+    assert_eq!(resources[1]["path"], "b.tsv");
+    assert_eq!(resources[1]["mediatype"], "text/tab-separated-values");
+    assert_eq!(resources[1]["dialect"]["delimiter"], "\t");
+    // the resources share the unified schema
+    assert_eq!(resources[0]["schema"], resources[1]["schema"]);
+    assert_eq!(resources[0]["schema"]["fields"][0]["type"], "number");
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_invalid_format() {
+    let wrk = Workdir::new("generate_schema_invalid_format").flexible(true);
+    wrk.clear_contents().unwrap();
+
+    wrk.create("a.csv", vec![svec!["id"], svec!["1"]]);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("a.csv").args(["--format", "xsd"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: Invalid --format: xsd."),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_multi_file_compose() {
+    let wrk = Workdir::new("generate_schema_multi_file_compose").flexible(true);
+    wrk.clear_contents().unwrap();
+
+    wrk.create(
+        "a.csv",
+        vec![
+            svec!["id", "name", "amount"],
+            svec!["1", "Alice", "10"],
+            svec!["2", "Bob", "20"],
+        ],
+    );
+    wrk.create(
+        "b.csv",
+        vec![
+            svec!["id", "name", "extra"],
+            svec!["3", "Carol", "x"],
+            svec!["4", "Dan", "y"],
+        ],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("a.csv")
+        .arg("b.csv")
+        .arg("--compose")
+        .args(["--output", "composed.schema.json"]);
+    wrk.assert_success(&mut cmd);
+
+    let output_schema_string: String = wrk.from_str(&wrk.path("composed.schema.json"));
+    let output_schema_json: Value =
+        serde_json::from_str(&output_schema_string).expect("parse schema json");
+
+    // make sure it's a valid JSON Schema by compiling with jsonschema library
+    jsonschema::JSONSchema::options()
+        .compile(&output_schema_json)
+        .expect("valid JSON Schema");
+
+    // shared columns are referenced, the others are inlined
+    let properties = &output_schema_json["properties"];
+    assert_eq!(properties["id"], serde_json::json!({"$ref": "#/$defs/id"}));
+    assert_eq!(
+        properties["name"],
+        serde_json::json!({"$ref": "#/$defs/name"})
+    );
+    assert_eq!(properties["amount"]["type"], serde_json::json!(["integer"]));
+
+    let defs = &output_schema_json["$defs"];
+    assert_eq!(defs["id"]["type"], serde_json::json!(["integer"]));
+    assert_eq!(
+        defs["common"]["required"],
+        serde_json::json!(["id", "name"])
+    );
+    assert_eq!(
+        defs["b.csv"],
+        serde_json::json!({
+            "type": "object",
+            "properties": {"extra": properties["extra"]},
+            "required": ["extra"],
+            "allOf": [{"$ref": "#/$defs/common"}]
+        })
+    );
+
+    // validate resolves the references to get the column types,
+    // otherwise the integer ids would be invalid strings
+    let mut cmd = wrk.command("validate");
+    cmd.arg("a.csv").arg("composed.schema.json");
+    wrk.assert_success(&mut cmd);
+}
+
+fn create_sql_data(wrk: &Workdir) {
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "big", "price", "name", "note", "created"],
+            svec!["1", "3000000000", "1.5", "Al", "", "2024-01-05"],
+            svec!["2", "1", "123.25", "Bobby", "x", "2024-02-10"],
+            svec!["3", "2", "7", "Cy", "y", "2024-03-15"],
+        ],
+    );
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_sql() {
+    let wrk = Workdir::new("generate_schema_sql").flexible(true);
+    wrk.clear_contents().unwrap();
+    create_sql_data(&wrk);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv").args(["--format", "sql"]);
+    wrk.assert_success(&mut cmd);
+
+    let got = wrk.read_to_string("data.csv.sql");
+    let expected = r#"CREATE TABLE "data" (
+    "id" SMALLINT NOT NULL,
+    "big" BIGINT NOT NULL,
+    "price" NUMERIC(5, 2) NOT NULL,
+    "name" VARCHAR(5) NOT NULL,
+    "note" VARCHAR(1),
+    "created" DATE NOT NULL
+);
+"#;
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_sql_dialects() {
+    let wrk = Workdir::new("generate_schema_sql_dialects").flexible(true);
+    wrk.clear_contents().unwrap();
+    create_sql_data(&wrk);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv")
+        .args(["--format", "sql"])
+        .args(["--dialect", "mysql"])
+        .arg("--stdout");
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"CREATE TABLE `data` (
+    `id` SMALLINT NOT NULL,
+    `big` BIGINT NOT NULL,
+    `price` DECIMAL(5, 2) NOT NULL,
+    `name` VARCHAR(5) NOT NULL,
+    `note` VARCHAR(1),
+    `created` DATE NOT NULL
+);"#;
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv")
+        .args(["--format", "sql"])
+        .args(["--dialect", "sqlite"])
+        .arg("--stdout");
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"CREATE TABLE "data" (
+    "id" INTEGER NOT NULL,
+    "big" INTEGER NOT NULL,
+    "price" REAL NOT NULL,
+    "name" TEXT NOT NULL,
+    "note" TEXT,
+    "created" TEXT NOT NULL
+);"#;
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv")
+        .args(["--format", "sql"])
+        .args(["--dialect", "oracle"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_avro() {
+    let wrk = Workdir::new("generate_schema_avro").flexible(true);
+    wrk.clear_contents().unwrap();
+    create_sql_data(&wrk);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv").args(["--format", "avro"]);
+    wrk.assert_success(&mut cmd);
+
+    let got: Value = serde_json::from_str(&wrk.read_to_string("data.csv.avsc")).unwrap();
+    let expected: Value = serde_json::from_str(
+        r#"{
+  "type": "record",
+  "name": "data",
+  "fields": [
+    {"name": "id", "type": "int"},
+    {"name": "big", "type": "long"},
+    {"name": "price", "type": "double"},
+    {"name": "name", "type": "string"},
+    {"name": "note", "type": ["null", "string"], "default": null},
+    {"name": "created", "type": {"type": "int", "logicalType": "date"}}
+  ]
+}"#,
+    )
+    .unwrap();
+    assert_json_eq!(got, expected);
+
+    // column & file names are sanitized into Avro names
+    wrk.create(
+        "2024-sales.csv",
+        vec![svec!["unit price", "unit-price"], svec!["1.5", "2"]],
+    );
+    let mut cmd = wrk.command("schema");
+    cmd.arg("2024-sales.csv")
+        .args(["--format", "avro"])
+        .arg("--stdout");
+    let got: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+    let expected: Value = serde_json::from_str(
+        r#"{
+  "type": "record",
+  "name": "_2024_sales",
+  "fields": [
+    {"name": "unit_price", "type": "double", "doc": "unit price"},
+    {"name": "unit_price_2", "type": "int", "doc": "unit-price"}
+  ]
+}"#,
+    )
+    .unwrap();
+    assert_json_eq!(got, expected);
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_arrow() {
+    let wrk = Workdir::new("generate_schema_arrow").flexible(true);
+    wrk.clear_contents().unwrap();
+    create_sql_data(&wrk);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv").args(["--format", "arrow"]);
+    wrk.assert_success(&mut cmd);
+
+    let got: Value =
+        serde_json::from_str(&wrk.read_to_string("data.csv.arrow-schema.json")).unwrap();
+    let expected: Value = serde_json::from_str(
+        r#"{
+  "fields": [
+    {"name": "id", "nullable": false,
+     "type": {"name": "int", "bitWidth": 16, "isSigned": true}, "children": []},
+    {"name": "big", "nullable": false,
+     "type": {"name": "int", "bitWidth": 64, "isSigned": true}, "children": []},
+    {"name": "price", "nullable": false,
+     "type": {"name": "floatingpoint", "precision": "DOUBLE"}, "children": []},
+    {"name": "name", "nullable": false, "type": {"name": "utf8"}, "children": []},
+    {"name": "note", "nullable": true, "type": {"name": "utf8"}, "children": []},
+    {"name": "created", "nullable": false,
+     "type": {"name": "date", "unit": "DAY"}, "children": []}
+  ]
+}"#,
+    )
+    .unwrap();
+    assert_json_eq!(got, expected);
+
+    // only single files are supported
+    wrk.create("data2.csv", vec![svec!["id"], svec!["1"]]);
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv")
+        .arg("data2.csv")
+        .args(["--format", "arrow"])
+        .arg("--stdout");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: --format arrow only supports a single input file."),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+fn create_ids(wrk: &Workdir) {
+    let mut rows = vec![svec!["id", "parity"]];
+    for i in 1..=20 {
+        rows.push(vec![
+            i.to_string(),
+            (if i % 2 == 0 { "even" } else { "odd" }).to_string(),
+        ]);
+    }
+    wrk.create("ids.csv", rows);
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_sample() {
+    let wrk = Workdir::new("generate_schema_sample").flexible(true);
+    wrk.clear_contents().unwrap();
+    create_ids(&wrk);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("ids.csv")
+        .args(["--sample", "5"])
+        .args(["--seed", "42"])
+        .arg("--stdout");
+    let got: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+    assert_eq!(got["title"], "JSON Schema for ids.csv");
+    // only the 5 sampled ids are seen
+    let id_enum = got["properties"]["id"]["enum"].as_array().unwrap();
+    assert_eq!(id_enum.len(), 5);
+    assert!(id_enum
+        .iter()
+        .all(|id| (1..=20).contains(&id.as_i64().unwrap())));
+
+    // the same seed samples the same records
+    let got_again: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+    assert_eq!(got, got_again);
+
+    // a sample larger than the file has all the records
+    let mut cmd = wrk.command("schema");
+    cmd.arg("ids.csv").arg("--stdout");
+    let full: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+    let mut cmd = wrk.command("schema");
+    cmd.arg("ids.csv").args(["--sample", "100"]).arg("--stdout");
+    let got: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+    assert_eq!(got, full);
+    let stderr = wrk.output_stderr(&mut cmd);
+    assert!(
+        stderr.contains("Inferring schema from a sample of 20 records of ids.csv."),
+        "{stderr}"
+    );
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_sample_pct_indexed() {
+    let wrk = Workdir::new("generate_schema_sample_pct_indexed").flexible(true);
+    wrk.clear_contents().unwrap();
+    create_ids(&wrk);
+
+    let mut cmd = wrk.command("index");
+    cmd.arg("ids.csv");
+    wrk.assert_success(&mut cmd);
+
+    // the sampled records are read using the index
+    let mut cmd = wrk.command("schema");
+    cmd.arg("ids.csv")
+        .args(["--sample-pct", "25"])
+        .arg("--stdout")
+        .env("QSV_SEED", "7");
+    let got: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+    let id_enum = got["properties"]["id"]["enum"].as_array().unwrap();
+    assert_eq!(id_enum.len(), 5);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("ids.csv")
+        .args(["--sample", "5"])
+        .args(["--sample-pct", "25"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: --sample and --sample-pct are mutually exclusive."),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("ids.csv").args(["--sample-pct", "150"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_enum_threshold_overrides() {
+    let wrk = Workdir::new("generate_schema_enum_threshold_overrides").flexible(true);
+    wrk.clear_contents().unwrap();
+    wrk.create(
+        "orders.csv",
+        vec![
+            svec!["id", "status", "country"],
+            svec!["1", "new", "US"],
+            svec!["2", "paid", "FR"],
+            svec!["3", "new", "DE"],
+            svec!["4", "shipped", "JP"],
+            svec!["5", "paid", "BR"],
+            svec!["6", "new", "CA"],
+        ],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("orders.csv")
+        .args(["--enum-threshold", "5"])
+        .arg("--stdout");
+    let got: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+    assert!(got["properties"]["id"].get("enum").is_none());
+    assert_eq!(
+        got["properties"]["status"]["enum"],
+        serde_json::json!(["new", "paid", "shipped"])
+    );
+    assert!(got["properties"]["country"].get("enum").is_none());
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("orders.csv")
+        .args(["--enum-threshold", "5,country:10,id:0"])
+        .args(["--no-enum", "status"])
+        .arg("--stdout");
+    let got: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+    assert!(got["properties"]["id"].get("enum").is_none());
+    assert!(got["properties"]["status"].get("enum").is_none());
+    assert_eq!(
+        got["properties"]["country"]["enum"],
+        serde_json::json!(["BR", "CA", "DE", "FR", "JP", "US"])
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("orders.csv")
+        .args(["--enum-threshold", "5,status:x"])
+        .arg("--stdout");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: Invalid --enum-threshold: x is not a valid threshold."),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_date_formats() {
+    let wrk = Workdir::new("generate_schema_date_formats").flexible(true);
+    wrk.clear_contents().unwrap();
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "created_date", "due_date", "open_time"],
+            svec!["1", "25/12/2023", "2024-01-31", "2024-01-31T10:00:00Z"],
+            svec!["2", "01/02/2024", "2024-02-29", "2024-02-29T11:30:00.5Z"],
+            svec!["3", "15/03/2024", "2024-03-15", "2024-03-15T09:00:00Z"],
+        ],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv").arg("--prefer-dmy");
+    wrk.assert_success(&mut cmd);
+
+    let schema: Value = serde_json::from_str(&wrk.read_to_string("data.csv.schema.json")).unwrap();
+    let properties = &schema["properties"];
+    assert_eq!(properties["created_date"]["x-date-format"], "%d/%m/%Y");
+    assert!(properties["created_date"].get("format").is_none());
+    assert_eq!(properties["due_date"]["x-date-format"], "%Y-%m-%d");
+    assert_eq!(properties["due_date"]["format"], "date");
+    assert_eq!(
+        properties["open_time"]["x-date-format"],
+        "%Y-%m-%dT%H:%M:%S%.fZ"
+    );
+    assert_eq!(properties["open_time"]["format"], "date-time");
+
+    // the detected formats are validated
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").arg("data.csv.schema.json");
+    wrk.assert_success(&mut cmd);
+
+    wrk.create(
+        "data2.csv",
+        vec![
+            svec!["id", "created_date", "due_date", "open_time"],
+            svec!["1", "2024-03-15", "2024-01-31", "2024-01-31T10:00:00Z"],
+        ],
+    );
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data2.csv").arg("data.csv.schema.json");
+    wrk.assert_err(&mut cmd);
+    let validation_error_output: String =
+        wrk.from_str(&wrk.path("data2.csv.validation-errors.tsv"));
+    assert_eq!(
+        validation_error_output,
+        "row_number\tfield\terror\n1\tcreated_date\t\"2024-03-15\" does not match the date \
+         format %d/%m/%Y\n"
+    );
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_update() {
+    let wrk = Workdir::new("generate_schema_update").flexible(true);
+    wrk.clear_contents().unwrap();
+    wrk.create(
+        "orders.csv",
+        vec![
+            svec!["id", "status", "amount"],
+            svec!["1", "new", "10.5"],
+            svec!["2", "paid", "20"],
+            svec!["3", "shipped", "7.25"],
+            svec!["4", "paid", "12"],
+        ],
+    );
+    wrk.create_from_string(
+        "orders.schema.json",
+        r#"{
+  "$schema": "https://json-schema.org/draft-07/schema",
+  "title": "Orders",
+  "description": "The orders of the web shop",
+  "type": "object",
+  "properties": {
+    "id": {
+      "description": "The order number",
+      "type": ["integer"],
+      "minimum": 1,
+      "maximum": 2,
+      "x-owner": "sales"
+    },
+    "status": {
+      "description": "The order status",
+      "type": ["string"],
+      "enum": ["new", "paid"],
+      "pattern": "^[a-z]+$"
+    },
+    "discount": {
+      "type": ["number"]
+    }
+  },
+  "required": ["id"]
+}"#,
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("orders.csv")
+        .args(["--update", "orders.schema.json"])
+        .args(["--no-enum", "id"]);
+    wrk.assert_success(&mut cmd);
+
+    let got: Value = serde_json::from_str(&wrk.read_to_string("orders.schema.json")).unwrap();
+    assert_eq!(got["title"], "Orders");
+    assert_eq!(got["description"], "The orders of the web shop");
+    assert_eq!(
+        got["properties"]["id"],
+        serde_json::json!({
+            "description": "The order number",
+            "type": ["integer"],
+            "minimum": 1,
+            "maximum": 4,
+            "x-owner": "sales"
+        })
+    );
+    assert_eq!(
+        got["properties"]["status"],
+        serde_json::json!({
+            "description": "The order status",
+            "type": ["string"],
+            "enum": ["new", "paid", "shipped"],
+            "pattern": "^[a-z]+$",
+            "minLength": 3,
+            "maxLength": 7
+        })
+    );
+    assert_eq!(
+        got["properties"]["amount"]["type"],
+        serde_json::json!(["number"])
+    );
+    assert_eq!(got["properties"]["amount"]["minimum"], 7.25);
+    assert!(got["properties"].get("discount").is_none());
+    // status was made optional by hand, amount is a new column
+    assert_eq!(got["required"], serde_json::json!(["id", "amount"]));
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("orders.csv")
+        .args(["--update", "orders.schema.json"])
+        .arg("--compose");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: --compose only applies"),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("orders.csv")
+        .args(["--update", "orders.schema.json"])
+        .args(["--format", "sql"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: --update only applies to JSON Schemas"),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+#[file_serial]
+fn generate_schema_diff() {
+    let wrk = Workdir::new("generate_schema_diff").flexible(true);
+    wrk.clear_contents().unwrap();
+    wrk.create_from_string(
+        "old.schema.json",
+        r#"{
+  "type": "object",
+  "properties": {
+    "id": {"type": ["integer"], "minimum": 1, "maximum": 100},
+    "status": {"type": ["string"], "enum": ["new", "paid"]},
+    "discount": {"type": ["number", "null"]}
+  },
+  "required": ["id", "status", "discount"]
+}"#,
+    );
+    wrk.create_from_string(
+        "new.schema.json",
+        r#"{
+  "type": "object",
+  "properties": {
+    "id": {"type": ["number"], "minimum": 1, "maximum": 50},
+    "status": {"type": ["string", "null"], "enum": ["new", "paid", "shipped", null]},
+    "amount": {"type": ["number"]}
+  },
+  "required": ["id", "amount"]
+}"#,
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("new.schema.json")
+        .args(["--diff", "old.schema.json"]);
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"removed    discount
+type       id: type ["integer"] -> ["number"]
+tightened  id: maximum 100 -> 50
+loosened   status: nullable false -> true
+loosened   status: required true -> false
+loosened   status: enum ["new","paid"] -> ["new","paid","shipped",null]
+added      amount"#;
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("new.schema.json")
+        .args(["--diff", "old.schema.json"])
+        .args(["--diff-format", "json"]);
+    let got: Value = serde_json::from_str(&wrk.stdout::<String>(&mut cmd)).unwrap();
+    assert_eq!(got.as_array().unwrap().len(), 7);
+    assert_eq!(
+        got[2],
+        serde_json::json!({
+            "column": "id",
+            "change": "tightened",
+            "keyword": "maximum",
+            "old": 100,
+            "new": 50
+        })
+    );
+
+    // compare the schema inferred from a CSV
+    wrk.create(
+        "orders.csv",
+        vec![
+            svec!["id", "status", "amount"],
+            svec!["1", "new", "10.5"],
+            svec!["2", "paid", "20"],
+        ],
+    );
+    let mut cmd = wrk.command("schema");
+    cmd.arg("orders.csv")
+        .args(["--diff", "new.schema.json"])
+        .args(["--no-enum", "id"]);
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(
+        got.starts_with("type       id: type [\"number\"] -> [\"integer\"]\n"),
+        "{got}"
+    );
+    assert!(!wrk.path("orders.csv.schema.json").exists());
+}
@@ -317,3 +317,65 @@ fn test_select_random_seeded_subset() {
     ];
     assert_eq!(got, expected);
 }
+
+fn locked_schema() -> &'static str {
+    r#"{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {
+        "id": { "type": "integer" },
+        "name": { "type": ["string", "null"] },
+        "price": { "type": ["number", "null"] }
+    }
+}"#
+}
+
+#[test]
+fn test_select_locked_schema() {
+    let wrk = Workdir::new("test_select_locked_schema");
+    wrk.create_from_string("schema.json", locked_schema());
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name", "price"],
+            svec!["1", "apple", "1.5"],
+            svec!["2", "", ""],
+        ],
+    );
+    let mut cmd = wrk.command("select");
+    cmd.arg("name")
+        .args(["--locked-schema", "schema.json"])
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    let expected = vec![svec!["name"], svec!["apple"], svec![""]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn test_select_locked_schema_mismatch() {
+    let wrk = Workdir::new("test_select_locked_schema_mismatch");
+    wrk.create_from_string("schema.json", locked_schema());
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "price", "category"],
+            svec!["1", "1.5", "fruit"],
+            svec!["x2", "n/a", "fruit"],
+        ],
+    );
+    let mut cmd = wrk.command("select");
+    cmd.arg("id")
+        .args(["--locked-schema", "schema.json"])
+        .arg("data.csv");
+
+    let got = wrk.output_stderr(&mut cmd);
+    let expected = r#"Input does not match the locked schema schema.json:
+- missing column "name"
++ unexpected column "category"
+~ column "id" expected integer, got "x2" at row 2
+~ column "price" expected number|null, got "n/a" at row 2
+"#;
+    assert_eq!(got, expected);
+    wrk.assert_err(&mut cmd);
+}
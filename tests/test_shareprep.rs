@@ -0,0 +1,205 @@
+use std::{fs::File, io::Read};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::workdir::Workdir;
+
+fn customers_schema() -> &'static str {
+    r#"{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {
+        "id": { "type": "integer" },
+        "name": { "type": "string" },
+        "email": { "type": "string", "format": "email", "description": "Contact email" },
+        "card": { "type": ["string", "null"] },
+        "age": { "type": "integer", "minimum": 18 }
+    },
+    "required": ["id", "name", "email"]
+}"#
+}
+
+fn read_zip_entry(wrk: &Workdir, zip_name: &str, entry: &str) -> String {
+    let mut archive = zip::ZipArchive::new(File::open(wrk.path(zip_name)).unwrap()).unwrap();
+    let mut contents = String::new();
+    archive
+        .by_name(entry)
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    contents
+}
+
+#[test]
+fn shareprep_anonymize() {
+    let wrk = Workdir::new("shareprep_anonymize");
+    wrk.create_from_string("customers.schema.json", customers_schema());
+    wrk.create(
+        "customers.csv",
+        vec![
+            svec!["id", "name", "email", "card", "age"],
+            svec!["1", "Ann", "ann@example.com", "4111222233334444", "34"],
+            svec!["2", "Bob", "bob@example.com", "5500111122223333", "41"],
+            svec!["3", "Cy", "ann@example.com", "", "29"],
+        ],
+    );
+    wrk.create(
+        "spec.csv",
+        vec![
+            svec!["column", "method"],
+            svec!["name", "drop"],
+            svec!["email", "hash"],
+            svec!["card", "mask:4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("shareprep");
+    cmd.args(["--anonymize", "spec.csv", "--salt", "pepper"])
+        .arg("customers.csv")
+        .arg("customers.schema.json");
+    wrk.assert_success(&mut cmd);
+
+    let data = read_zip_entry(&wrk, "customers-share.zip", "data.csv");
+    assert_eq!(
+        data,
+        "id,email,card,age\n1,831740710d6a8225,************4444,34\n2,40f27281a6f2d75f,\
+         ************3333,41\n3,831740710d6a8225,,29\n"
+    );
+
+    // the schema describes the shared data
+    let schema: Value =
+        serde_json::from_str(&read_zip_entry(&wrk, "customers-share.zip", "schema.json")).unwrap();
+    assert!(schema["properties"].get("name").is_none());
+    assert_eq!(schema["required"], serde_json::json!(["id", "email"]));
+    assert_eq!(
+        schema["properties"]["email"],
+        serde_json::json!({
+            "type": ["string", "null"],
+            "$comment": "anonymized with hash",
+            "description": "Contact email"
+        })
+    );
+
+    let manifest: Value = serde_json::from_str(&read_zip_entry(
+        &wrk,
+        "customers-share.zip",
+        "manifest.json",
+    ))
+            .unwrap();
+    assert_eq!(manifest["source"]["file"], "customers.csv");
+    assert_eq!(manifest["source"]["rows"], 3);
+    assert_eq!(manifest["sample"]["rows"], 3);
+    assert_eq!(
+        manifest["anonymized"],
+        serde_json::json!([
+            {"column": "name", "method": "drop"},
+            {"column": "email", "method": "hash"},
+            {"column": "card", "method": "mask:4"}
+        ])
+    );
+    assert_eq!(manifest["files"][0]["name"], "data.csv");
+    assert_eq!(manifest["files"][0]["bytes"], data.len());
+    assert_eq!(
+        manifest["files"][0]["sha256"],
+        format!("{:x}", Sha256::digest(data.as_bytes()))
+    );
+}
+
+#[test]
+fn shareprep_stratify() {
+    let wrk = Workdir::new("shareprep_stratify");
+    wrk.create_from_string("schema.json", r#"{"type": "object", "properties": {}}"#);
+    let mut rows = vec![svec!["id", "region"]];
+    for (i, region) in [
+        "north", "north", "south", "north", "east", "south", "north", "north", "south", "north",
+    ]
+    .iter()
+    .enumerate()
+    {
+        rows.push(vec![i.to_string(), region.to_string()]);
+    }
+    wrk.create("in.csv", rows);
+
+    let mut cmd = wrk.command("shareprep");
+    cmd.args(["--sample-size", "5", "--stratify", "region", "--seed", "42"])
+        .args(["--output", "sample.zip"])
+        .arg("in.csv")
+        .arg("schema.json");
+    wrk.assert_success(&mut cmd);
+
+    // 6 north, 3 south & 1 east rows - every region gets a row, the rest is proportional
+    let manifest: Value =
+        serde_json::from_str(&read_zip_entry(&wrk, "sample.zip", "manifest.json")).unwrap();
+    assert_eq!(manifest["source"]["rows"], 10);
+    assert_eq!(manifest["sample"]["stratify"], "region");
+    assert_eq!(
+        manifest["sample"]["strata"],
+        serde_json::json!({"north": 2, "south": 2, "east": 1})
+    );
+
+    let data = read_zip_entry(&wrk, "sample.zip", "data.csv");
+    let regions: Vec<&str> = data
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').nth(1).unwrap())
+        .collect();
+    assert_eq!(regions.len(), 5);
+    for (region, count) in [("north", 2), ("south", 2), ("east", 1)] {
+        assert_eq!(regions.iter().filter(|&&r| r == region).count(), count);
+    }
+}
+
+#[test]
+fn shareprep_invalid_sample() {
+    let wrk = Workdir::new("shareprep_invalid_sample");
+    wrk.create_from_string("customers.schema.json", customers_schema());
+    wrk.create(
+        "customers.csv",
+        vec![
+            svec!["id", "name", "email", "card", "age"],
+            svec!["1", "Ann", "ann@example.com", "", "34"],
+            svec!["2", "Bob", "bob@example.com", "", "16"],
+        ],
+    );
+
+    let mut cmd = wrk.command("shareprep");
+    cmd.arg("customers.csv").arg("customers.schema.json");
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.contains("No package was written."), "{got}");
+    assert!(
+        got.contains("row 2: age: 16 is less than the minimum of 18"),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+    assert!(!wrk.path("customers-share.zip").exists());
+}
+
+#[test]
+fn shareprep_spec_unknown_column() {
+    let wrk = Workdir::new("shareprep_spec_unknown_column");
+    wrk.create_from_string("customers.schema.json", customers_schema());
+    wrk.create(
+        "customers.csv",
+        vec![
+            svec!["id", "name", "email", "card", "age"],
+            svec!["1", "Ann", "ann@example.com", "", "34"],
+        ],
+    );
+    wrk.create(
+        "spec.csv",
+        vec![svec!["column", "method"], svec!["phone", "redact"]],
+    );
+
+    let mut cmd = wrk.command("shareprep");
+    cmd.args(["--anonymize", "spec.csv"])
+        .arg("customers.csv")
+        .arg("customers.schema.json");
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Column \"phone\" of the anonymization spec is not in the input.\n"
+    );
+}
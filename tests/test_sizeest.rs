@@ -0,0 +1,103 @@
+use crate::workdir::Workdir;
+
+fn data(rows: usize) -> Vec<Vec<String>> {
+    let mut data = vec![svec!["id", "city", "status"]];
+    for i in 0..rows {
+        data.push(vec![
+            format!("{i:05}"),
+            "Springfield".to_string(),
+            "active".to_string(),
+        ]);
+    }
+    data
+}
+
+#[test]
+fn sizeest_formats() {
+    let wrk = Workdir::new("sizeest_formats");
+    wrk.create("in.csv", data(500));
+    let file_size = std::fs::metadata(wrk.path("in.csv")).unwrap().len();
+
+    let mut cmd = wrk.command("sizeest");
+    cmd.args(["--formats", "csv,csv.gz,csv.sz,zstd"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        got[0],
+        svec![
+            "format",
+            "estimated_bytes",
+            "estimated_size",
+            "ratio",
+            "estimated_secs"
+        ]
+    );
+    let formats: Vec<&str> = got[1..].iter().map(|row| row[0].as_str()).collect();
+    assert_eq!(formats, vec!["csv", "csv.gz", "csv.sz", "csv.zst"]);
+
+    // the whole file was sampled, so the csv estimate is the file size
+    assert_eq!(got[1][1], file_size.to_string());
+    assert_eq!(got[1][3], "1.000");
+    // the repetitive data compresses well
+    for row in &got[2..] {
+        assert!(row[1].parse::<u64>().unwrap() < file_size / 2, "{row:?}");
+    }
+}
+
+#[test]
+fn sizeest_extrapolates_sample() {
+    let wrk = Workdir::new("sizeest_extrapolates_sample");
+    wrk.create("in.csv", data(1000));
+    let file_size = std::fs::metadata(wrk.path("in.csv")).unwrap().len();
+
+    let mut cmd = wrk.command("sizeest");
+    cmd.args(["--formats", "csv", "--sample-size", "100"])
+        .arg("in.csv");
+
+    // all the records have the same length, so the estimate from the first 100 is exact
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][0], "csv");
+    assert_eq!(got[1][1], file_size.to_string());
+}
+
+#[test]
+fn sizeest_columns() {
+    let wrk = Workdir::new("sizeest_columns");
+    wrk.create("in.csv", data(500));
+
+    let mut cmd = wrk.command("sizeest");
+    cmd.args(["--columns", "--formats", "csv"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "column",
+            "format",
+            "estimated_bytes",
+            "estimated_size",
+            "share"
+        ],
+        // the values of each column, one per line, including the header
+        svec!["id", "csv", "3003", "2.93 KiB", "0.240"],
+        svec!["city", "csv", "6005", "5.86 KiB", "0.480"],
+        svec!["status", "csv", "3507", "3.42 KiB", "0.280"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sizeest_invalid_format() {
+    let wrk = Workdir::new("sizeest_invalid_format");
+    wrk.create("in.csv", data(10));
+
+    let mut cmd = wrk.command("sizeest");
+    cmd.args(["--formats", "csv.bz2"]).arg("in.csv");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: Invalid format: csv.bz2."),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
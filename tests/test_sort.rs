@@ -516,6 +516,182 @@ fn sort_random_secure() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn sort_external() {
+    let wrk = Workdir::new("sort_external");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["N", "S"],
+            svec!["10", "a"],
+            svec!["2", "b"],
+            svec!["10", "c"],
+            svec!["33", "d"],
+            svec!["2", "e"],
+            svec!["1", "f"],
+        ],
+    );
+
+    // a tiny memory limit, so each row is a sorted run of its own
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--external", "--memory-limit", "10"])
+        .args(["--select", "N", "--numeric", "--reverse"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["N", "S"],
+        svec!["33", "d"],
+        svec!["10", "a"],
+        svec!["10", "c"],
+        svec!["2", "b"],
+        svec!["2", "e"],
+        svec!["1", "f"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_external_unique() {
+    let wrk = Workdir::new("sort_external_unique");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["S"],
+            svec!["c"],
+            svec!["a"],
+            svec!["b"],
+            svec!["a"],
+            svec!["c"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--external", "--memory-limit", "150", "--unique"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["S"], svec!["a"], svec!["b"], svec!["c"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_natural() {
+    let wrk = Workdir::new("sort_natural");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["file"],
+            svec!["file10"],
+            svec!["file2"],
+            svec!["File1b"],
+            svec!["file1"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--natural", "--ignore-case"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["file"],
+        svec!["file1"],
+        svec!["File1b"],
+        svec!["file2"],
+        svec!["file10"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+#[test]
+fn sort_locale() {
+    let wrk = Workdir::new("sort_locale");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["word"],
+            svec!["zebra"],
+            svec!["öl"],
+            svec!["apple"],
+            svec!["Äpfel"],
+            svec!["Ofen"],
+        ],
+    );
+
+    // in German, the umlauts are sorted with their base letters
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--locale", "de"]).arg("in.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["word"],
+        svec!["Äpfel"],
+        svec!["apple"],
+        svec!["Ofen"],
+        svec!["öl"],
+        svec!["zebra"],
+    ];
+    assert_eq!(got, expected);
+
+    // in Swedish, they're letters of their own, after z
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--locale", "sv"]).arg("in.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["word"],
+        svec!["apple"],
+        svec!["Ofen"],
+        svec!["zebra"],
+        svec!["Äpfel"],
+        svec!["öl"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_keys() {
+    let wrk = Workdir::new("sort_keys");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["date", "amount", "name"],
+            svec!["2024-01-02", "9", "b"],
+            svec!["2024-01-10", "10", "a"],
+            svec!["2024-01-02", "10", "c"],
+            svec!["2024-01-02", "9", "a"],
+            svec!["2023-12-31", "1", "d"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--key", "date:desc:date,amount:numeric,name"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["date", "amount", "name"],
+        svec!["2024-01-10", "10", "a"],
+        svec!["2024-01-02", "9", "a"],
+        svec!["2024-01-02", "9", "b"],
+        svec!["2024-01-02", "10", "c"],
+        svec!["2023-12-31", "1", "d"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_keys_invalid() {
+    let wrk = Workdir::new("sort_keys_invalid");
+    wrk.create(
+        "in.csv",
+        vec![svec!["date", "amount"], svec!["2024-01-02", "9"]],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--key", "amount:descending"]).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
 /// Order `a` and `b` lexicographically using `Ord`
 pub fn iter_cmp<A, L, R>(mut a: L, mut b: R) -> cmp::Ordering
 where
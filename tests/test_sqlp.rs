@@ -2797,3 +2797,129 @@ IT	Rome,Milan,Turin,Naples,Venice
 
 //     assert_eq!(got_dot, expected_dot);
 // }
+
+#[test]
+#[cfg(feature = "luau")]
+fn sqlp_luau_udf() {
+    let wrk = Workdir::new("sqlp_luau_udf");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "amount"],
+            svec!["Alice", "1500"],
+            svec!["Bob", "20"],
+            svec!["Carol", ""],
+        ],
+    );
+    wrk.create_from_string(
+        "udf.luau",
+        r#"
+function tier(amount)
+    if amount == nil then return nil end
+    if tonumber(amount) > 1000 then return "gold" end
+    return "standard"
+end
+
+function greet(name, greeting)
+    return greeting .. ", " .. name
+end
+"#,
+    );
+
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("data.csv")
+        .arg("select name, tier(amount) as tier, greet(name, 'Hi') as hello from data")
+        .args(["--udf", "udf.luau"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "tier", "hello"],
+        svec!["Alice", "gold", "Hi, Alice"],
+        svec!["Bob", "standard", "Hi, Bob"],
+        svec!["Carol", "", "Hi, Carol"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sqlp_parquet_auto_encode() {
+    let wrk = Workdir::new("sqlp_parquet_auto_encode");
+    let mut rows = vec![svec!["id", "city", "name", "price"]];
+    for i in 1..=20 {
+        rows.push(vec![
+            i.to_string(),
+            if i % 2 == 0 { "Boston" } else { "Denver" }.to_string(),
+            format!("name{i}"),
+            format!("{i}.5"),
+        ]);
+    }
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("data.csv")
+        .arg("select * from data")
+        .args(["--format", "parquet", "--output", "data.parquet"])
+        .arg("--auto-encode");
+
+    let got = wrk.output_stderr(&mut cmd);
+    let expected = r#"Parquet column encodings:
+  id: narrow (i64 -> u8)
+  city: dictionary (2 distinct values)
+  name: plain
+  price: plain
+(20, 4)
+"#;
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("SKIP_INPUT")
+        .arg("select id, city from read_parquet('data.parquet') where id <= 2");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "city"],
+        svec!["1", "Denver"],
+        svec!["2", "Boston"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sqlp_parquet_codec_override() {
+    let wrk = Workdir::new("sqlp_parquet_codec_override");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "city"],
+            svec!["1", "Boston"],
+            svec!["2", "Denver"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("data.csv")
+        .arg("select * from data")
+        .args(["--format", "parquet", "--output", "data.parquet"])
+        .args(["--codec", "city=dictionary"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    let expected = r#"Parquet column encodings:
+  city: dictionary (2 distinct values)
+(2, 2)
+"#;
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("data.csv")
+        .arg("select * from data")
+        .args(["--format", "parquet", "--output", "data.parquet"])
+        .args(["--codec", "city=zip"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Invalid column encoding: zip. Valid encodings are dictionary, narrow and \
+         plain.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
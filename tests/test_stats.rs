@@ -1410,3 +1410,452 @@ mod stats_header_fields {
     stats_test_headers!(stats_header_field_name, "field", &["a"], "header");
     stats_test_no_headers!(stats_header_no_field_name, "field", &["a"], "0");
 }
+
+#[test]
+fn stats_percentiles() {
+    let wrk = Workdir::new("stats_percentiles");
+    let mut rows = vec![svec!["latency", "name"]];
+    for latency in (1..=100).rev() {
+        rows.push(vec![latency.to_string(), format!("req{latency}")]);
+    }
+    wrk.create("data.csv", rows);
+
+    let percentile_columns = |got: &[Vec<String>]| -> Vec<Vec<String>> {
+        let p_columns: Vec<usize> = got[0]
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| *header == "field" || header.starts_with('p'))
+            .map(|(i, _)| i)
+            .collect();
+        got.iter()
+            .map(|row| p_columns.iter().map(|&i| row[i].clone()).collect())
+            .collect()
+    };
+
+    // estimated with a t-digest
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--percentiles", "50,99"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "p50", "p99"],
+        svec!["latency", "50.5", "99.5"],
+        svec!["name", "", ""],
+    ];
+    assert_eq!(percentile_columns(&got), expected);
+
+    // exact, as the values are loaded in memory for the quartiles
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .args(["--percentiles", "50,99,99.9"])
+        .arg("--quartiles");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "p50", "p99", "p99.9"],
+        svec!["latency", "50.5", "99.01", "99.901"],
+        svec!["name", "", "", ""],
+    ];
+    assert_eq!(percentile_columns(&got), expected);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--percentiles", "50,101"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with(
+            "usage error: Invalid --percentiles: 101 is not a percentile between 0 and 100."
+        ),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn stats_outliers() {
+    let wrk = Workdir::new("stats_outliers");
+    let mut rows = vec![svec!["amount", "name"]];
+    for amount in 1..=20 {
+        rows.push(vec![amount.to_string(), format!("order{amount}")]);
+    }
+    rows.push(svec!["1000", "order21"]);
+    wrk.create("data.csv", rows);
+
+    let outlier_columns = |got: &[Vec<String>]| -> Vec<Vec<String>> {
+        got.iter()
+            .map(|row| vec![row[0].clone(), row[row.len() - 1].clone()])
+            .collect()
+    };
+
+    for method in ["iqr", "zscore"] {
+        let mut cmd = wrk.command("stats");
+        cmd.arg("data.csv")
+            .args(["--outliers", method])
+            .args(["--outliers-output", "outliers.csv"]);
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        let headers = &got[0];
+        assert_eq!(
+            headers[headers.len() - 3..],
+            svec![
+                "outlier_lower_fence",
+                "outlier_upper_fence",
+                "outlier_count"
+            ]
+        );
+        let expected = vec![
+            svec!["field", "outlier_count"],
+            svec!["amount", "1"],
+            svec!["name", ""],
+        ];
+        assert_eq!(outlier_columns(&got), expected, "{method}");
+
+        let got = wrk.read_to_string("outliers.csv");
+        assert_eq!(got, "row,field,value\n21,amount,1000\n", "{method}");
+    }
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--outliers", "mad"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: Invalid --outliers method: mad. Must be iqr or zscore."),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn stats_group_by() {
+    let wrk = Workdir::new("stats_group_by");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["region", "amount"],
+            svec!["east", "1"],
+            svec!["west", "10"],
+            svec!["east", "3"],
+            svec!["west", "20.5"],
+            svec!["north", "5"],
+        ],
+    );
+
+    let summary_columns = |got: &[Vec<String>]| -> Vec<Vec<String>> {
+        let columns: Vec<usize> = ["region", "field", "type", "sum", "min", "max"]
+            .iter()
+            .map(|name| got[0].iter().position(|header| header == name).unwrap())
+            .collect();
+        got.iter()
+            .map(|row| columns.iter().map(|&i| row[i].clone()).collect())
+            .collect()
+    };
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--group-by", "region"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "field", "type", "sum", "min", "max"],
+        svec!["east", "amount", "Integer", "4", "1", "3"],
+        svec!["west", "amount", "Float", "30.5", "10", "20.5"],
+        svec!["north", "amount", "Integer", "5", "5", "5"],
+    ];
+    assert_eq!(summary_columns(&got), expected);
+
+    // the groups beyond the limit are spilled to disk, and written one partition at a time
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .args(["--group-by", "region"])
+        .args(["--group-limit", "1"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let mut got = summary_columns(&got);
+    got[1..].sort();
+    let expected = vec![
+        svec!["region", "field", "type", "sum", "min", "max"],
+        svec!["east", "amount", "Integer", "4", "1", "3"],
+        svec!["north", "amount", "Integer", "5", "5", "5"],
+        svec!["west", "amount", "Float", "30.5", "10", "20.5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_histogram() {
+    let wrk = Workdir::new("stats_histogram");
+    let mut rows = vec![svec!["amount", "name"]];
+    for amount in 1..=10 {
+        let name = match amount {
+            1..=5 => "x",
+            6..=8 => "y",
+            _ => "z",
+        };
+        rows.push(vec![amount.to_string(), name.to_string()]);
+    }
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--histogram", "3"]);
+    wrk.assert_success(&mut cmd);
+
+    let got = wrk.read_to_string("data.stats.histogram.csv");
+    let expected = "field,lower,upper,value,count
+amount,1,4,,3
+amount,4,7,,3
+amount,7,10,,4
+name,,,x,5
+name,,,y,3
+name,,,z,2
+";
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .args(["--histogram", "2"])
+        .args(["--histogram-file", "histogram.json"]);
+    wrk.assert_success(&mut cmd);
+
+    let got: serde_json::Value =
+        serde_json::from_str(&wrk.read_to_string("histogram.json")).unwrap();
+    let expected = serde_json::json!({
+        "amount": {"edges": [1.0, 5.5, 10.0], "counts": [5, 5]},
+        "name": {"values": ["x", "y"], "counts": [5, 3]}
+    });
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_approx_cardinality() {
+    let wrk = Workdir::new("stats_approx_cardinality");
+    let mut rows = vec![svec!["id", "flag"]];
+    for id in 0..5000 {
+        rows.push(vec![format!("id{id}"), (id % 2).to_string()]);
+    }
+    wrk.create("data.csv", rows);
+
+    let column = |got: &[Vec<String>], name: &str| -> Vec<String> {
+        let i = got[0].iter().position(|header| header == name).unwrap();
+        got[1..].iter().map(|row| row[i].clone()).collect()
+    };
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").arg("--approx-cardinality");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0].last().unwrap(), "cardinality_mode");
+    assert_eq!(
+        column(&got, "cardinality_mode"),
+        svec!["approximate", "approximate"]
+    );
+    let cardinalities = column(&got, "cardinality");
+    let id_cardinality: f64 = cardinalities[0].parse().unwrap();
+    assert!(
+        (id_cardinality - 5000.0).abs() <= 5000.0 * 0.03,
+        "{id_cardinality}"
+    );
+    assert_eq!(cardinalities[1], "2");
+
+    // the modes load the values in memory, so the cardinality is exact
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .arg("--approx-cardinality")
+        .arg("--mode");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(column(&got, "cardinality_mode"), svec!["exact", "exact"]);
+    assert_eq!(column(&got, "cardinality"), svec!["5000", "2"]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .arg("--approx-cardinality")
+        .args(["--cardinality-error", "0.1"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: Invalid --cardinality-error: 0.1. Must be at least 0.21."),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn stats_incremental_append() {
+    use std::io::Write;
+
+    let wrk = Workdir::new("stats_incremental_append");
+    wrk.create_from_string("log.csv", "level,latency\ninfo,10\nwarn,25\ninfo,7\n");
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("log.csv").args(["--cache-threshold", "1"]);
+    wrk.assert_success(&mut cmd);
+    assert!(wrk.path("log.stats.csv.state.json").exists());
+
+    // append rows, and make sure the input is newer than the cached stats
+    let mut log = std::fs::OpenOptions::new()
+        .append(true)
+        .open(wrk.path("log.csv"))
+        .unwrap();
+    log.write_all(b"error,120.5\ninfo,3\n").unwrap();
+    log.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+        .unwrap();
+    drop(log);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("log.csv").args(["--cache-threshold", "1"]);
+    let incremental: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("log.csv")
+        .args(["--cache-threshold", "1"])
+        .arg("--force");
+    let recomputed: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(incremental, recomputed);
+
+    let latency: Vec<&str> = ["type", "sum", "min", "max"]
+        .iter()
+        .map(|name| {
+            let i = incremental[0].iter().position(|h| h == name).unwrap();
+            incremental[2][i].as_str()
+        })
+        .collect();
+    assert_eq!(latency, ["Float", "165.5", "3", "120.5"]);
+}
+
+#[test]
+fn stats_weighted() {
+    let wrk = Workdir::new("stats_weighted");
+    // the last row has no weight, so it's left out of the weighted stats
+    wrk.create_from_string("survey.csv", "x,w\n10,1\n20,1\n30,2\n1000,\n");
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("survey.csv")
+        .args(["--weight", "w"])
+        .arg("--median")
+        .args(["--percentiles", "25,75"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    let stats = |row: usize, names: &[&str]| -> Vec<String> {
+        names
+            .iter()
+            .map(|name| {
+                let i = got[0].iter().position(|h| h == name).unwrap();
+                got[row][i].clone()
+            })
+            .collect()
+    };
+    assert_eq!(
+        stats(
+            1,
+            &[
+                "field", "sum", "max", "mean", "sem", "stddev", "variance", "cv", "median", "p25",
+                "p75"
+            ]
+        ),
+        svec!["x", "90", "1000", "22.5", "5.0775", "8.2916", "68.75", "36.8514", "25", "15", "30"]
+    );
+    // the weight column itself is summarized unweighted
+    assert_eq!(
+        stats(2, &["field", "sum", "mean", "median"]),
+        svec!["w", "4", "1.3333", "1"]
+    );
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("survey.csv").args(["--weight", "x,w"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: --weight must select a single column."),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn stats_date_details() {
+    let wrk = Workdir::new("stats_date_details");
+    wrk.create_from_string(
+        "events.csv",
+        "event_date,event_time\n2024-01-01,2024-01-01T10:00:00+02:00\n2024-01-02,\
+         2024-01-01T11:00:00Z\n2024-03-01,2024-01-02T12:30:00-05:00\n2999-06-15,\n",
+    );
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("events.csv")
+        .arg("--infer-dates")
+        .arg("--date-details");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let columns = [
+        "field",
+        "type",
+        "date_range_human",
+        "date_granularity",
+        "date_tz_offsets",
+        "date_future_count",
+    ];
+    let details: Vec<Vec<String>> = got[1..]
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|name| {
+                    let i = got[0].iter().position(|h| h == name).unwrap();
+                    row[i].clone()
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        details,
+        vec![
+            svec!["event_date", "Date", "976 years 37 days", "daily", "", "1"],
+            svec![
+                "event_time",
+                "DateTime",
+                "1 day 9 hours",
+                "hourly",
+                "+00:00,+02:00,-05:00",
+                "0"
+            ],
+        ]
+    );
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("events.csv").arg("--date-details");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.starts_with("usage error: --date-details requires --infer-dates."),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn stats_format_json() {
+    let wrk = Workdir::new("stats_format_json");
+    wrk.create_from_string("data.csv", "name,age\nAlice,30\nBob,40\n");
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--format", "jsonl"]);
+    let got: String = wrk.stdout(&mut cmd);
+    let objects: Vec<serde_json::Value> = got
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(objects.len(), 2);
+    assert_eq!(objects[0]["field"], serde_json::json!("name"));
+    assert_eq!(objects[0]["is_ascii"], serde_json::json!(true));
+    assert_eq!(objects[0]["min"], serde_json::json!("Alice"));
+    assert_eq!(objects[0]["mean"], serde_json::Value::Null);
+    assert_eq!(objects[1]["type"], serde_json::json!("Integer"));
+    assert_eq!(objects[1]["sum"], serde_json::json!(70));
+    assert_eq!(objects[1]["min"], serde_json::json!(30));
+    assert_eq!(objects[1]["mean"], serde_json::json!(35));
+    assert_eq!(objects[1]["nullcount"], serde_json::json!(0));
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--format", "json"]);
+    let got: String = wrk.stdout(&mut cmd);
+    let array: serde_json::Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(array, serde_json::Value::Array(objects));
+
+    // the --group-by values are strings
+    wrk.create_from_string("grouped.csv", "zip,amount\n01234,1\n01234,3\n");
+    let mut cmd = wrk.command("stats");
+    cmd.arg("grouped.csv")
+        .args(["--group-by", "zip"])
+        .args(["--format", "json"]);
+    let got: String = wrk.stdout(&mut cmd);
+    let array: serde_json::Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(array[0]["zip"], serde_json::json!("01234"));
+    assert_eq!(array[0]["field"], serde_json::json!("amount"));
+    assert_eq!(array[0]["sum"], serde_json::json!(4));
+}
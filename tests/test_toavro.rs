@@ -0,0 +1,71 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "city", "price", "day"],
+        svec!["1", "Denver", "10.5", "2023-01-15"],
+        svec!["2", "Boston", "3.25", "2023-02-28"],
+        svec!["3", "Denver", "", "2023-12-01"],
+    ]
+}
+
+#[test]
+fn toavro_roundtrip() {
+    let wrk = Workdir::new("toavro_roundtrip");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("toavro");
+    cmd.arg("data.csv").args(["--output", "data.avro"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromavro");
+    cmd.arg("data.avro");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data());
+}
+
+#[test]
+fn toavro_typed_columns() {
+    let wrk = Workdir::new("toavro_typed_columns");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("toavro");
+    cmd.arg("data.csv")
+        .args(["--compression", "deflate"])
+        .args(["--output", "data.avro"]);
+    wrk.assert_success(&mut cmd);
+
+    // the ids & prices are numbers and the days are dates, so they can be compared as such
+    let mut cmd = wrk.command("fromavro");
+    cmd.arg("data.avro")
+        .args(["--select", "id,day"])
+        .args(["--where", "price > 5 OR day > CAST('2023-06-01' AS DATE)"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "day"],
+        svec!["1", "2023-01-15"],
+        svec!["3", "2023-12-01"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn toavro_no_dates() {
+    let wrk = Workdir::new("toavro_no_dates");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("toavro");
+    cmd.arg("data.csv")
+        .args(["--dates-whitelist", "none"])
+        .args(["--output", "data.avro"]);
+    wrk.assert_success(&mut cmd);
+
+    // the days are strings, so they're compared as text
+    let mut cmd = wrk.command("fromavro");
+    cmd.arg("data.avro")
+        .args(["--select", "day"])
+        .args(["--where", "day LIKE '2023-0%'"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["day"], svec!["2023-01-15"], svec!["2023-02-28"]];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,150 @@
+use crate::workdir::Workdir;
+
+fn stops() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "lat", "lon", "route"],
+        svec!["Alpha", "51.5074", "-0.1278", "r1"],
+        svec!["Bravo", "51.5155", "-0.0922", "r1"],
+        svec!["Charlie", "", "", "r2"],
+        svec!["Delta", "48.8566", "2.3522", "r2"],
+        svec!["Echo", "48.8606", "2.3376", "r2"],
+    ]
+}
+
+#[test]
+fn togeojson_feature_collection() {
+    let wrk = Workdir::new("togeojson_feature_collection");
+    wrk.create("in.csv", stops());
+
+    let mut cmd = wrk.command("togeojson");
+    cmd.args(["--lat", "lat", "--lon", "lon", "--properties", "name"])
+        .arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"{"type":"FeatureCollection","features":[
+{"type":"Feature","geometry":{"type":"Point","coordinates":[-0.1278,51.5074]},"properties":{"name":"Alpha"}},
+{"type":"Feature","geometry":{"type":"Point","coordinates":[-0.0922,51.5155]},"properties":{"name":"Bravo"}},
+{"type":"Feature","geometry":{"type":"Point","coordinates":[2.3522,48.8566]},"properties":{"name":"Delta"}},
+{"type":"Feature","geometry":{"type":"Point","coordinates":[2.3376,48.8606]},"properties":{"name":"Echo"}}
+]}"#;
+    assert_eq!(got, expected);
+
+    let got_err = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got_err,
+        "Skipped 1 of 5 records without valid coordinates.\n"
+    );
+}
+
+#[test]
+fn togeojson_jsonl_default_properties() {
+    let wrk = Workdir::new("togeojson_jsonl_default_properties");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["name", "lat", "lon", "note"],
+            svec!["Alpha", "51.5074", "-0.1278", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("togeojson");
+    cmd.args(["--lat", "lat", "--lon", "lon", "--jsonl"])
+        .arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[-0.1278,51.5074]},"properties":{"name":"Alpha","note":null}}"#;
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn togeojson_wkt_point() {
+    let wrk = Workdir::new("togeojson_wkt_point");
+    wrk.create("in.csv", stops());
+
+    let mut cmd = wrk.command("togeojson");
+    cmd.args(["--lat", "lat", "--lon", "lon", "--wkt", "geom"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "lat", "lon", "route", "geom"],
+        svec![
+            "Alpha",
+            "51.5074",
+            "-0.1278",
+            "r1",
+            "POINT (-0.1278 51.5074)"
+        ],
+        svec![
+            "Bravo",
+            "51.5155",
+            "-0.0922",
+            "r1",
+            "POINT (-0.0922 51.5155)"
+        ],
+        svec!["Delta", "48.8566", "2.3522", "r2", "POINT (2.3522 48.8566)"],
+        svec!["Echo", "48.8606", "2.3376", "r2", "POINT (2.3376 48.8606)"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn togeojson_wkt_linestring() {
+    let wrk = Workdir::new("togeojson_wkt_linestring");
+    wrk.create("in.csv", stops());
+
+    let mut cmd = wrk.command("togeojson");
+    cmd.args([
+        "--lat",
+        "lat",
+        "--lon",
+        "lon",
+        "--linestring-by",
+        "route",
+        "--wkt",
+        "track",
+    ])
+    .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["route", "track"],
+        svec!["r1", "LINESTRING (-0.1278 51.5074, -0.0922 51.5155)"],
+        svec!["r2", "LINESTRING (2.3522 48.8566, 2.3376 48.8606)"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn togeojson_linestring() {
+    let wrk = Workdir::new("togeojson_linestring");
+    wrk.create("in.csv", stops());
+
+    let mut cmd = wrk.command("togeojson");
+    cmd.args([
+        "--lat",
+        "lat",
+        "--lon",
+        "lon",
+        "--linestring-by",
+        "route",
+        "--jsonl",
+    ])
+    .arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"{"type":"Feature","geometry":{"type":"LineString","coordinates":[[-0.1278,51.5074],[-0.0922,51.5155]]},"properties":{"route":"r1"}}
+{"type":"Feature","geometry":{"type":"LineString","coordinates":[[2.3522,48.8566],[2.3376,48.8606]]},"properties":{"route":"r2"}}"#;
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn togeojson_multiple_lat_columns() {
+    let wrk = Workdir::new("togeojson_multiple_lat_columns");
+    wrk.create("in.csv", stops());
+
+    let mut cmd = wrk.command("togeojson");
+    cmd.args(["--lat", "lat,lon", "--lon", "lon"]).arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -448,3 +448,104 @@ fn tojsonl_issue_1649_false_positive_tf_3recs() {
 
     assert_eq!(got, expected);
 }
+
+fn create_names(wrk: &Workdir) {
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "Al"],
+            svec!["2", "Bartholomew"],
+            svec!["3", "Cy"],
+        ],
+    );
+}
+
+#[test]
+fn tojsonl_max_record_bytes_error() {
+    let wrk = Workdir::new("tojsonl_max_record_bytes_error");
+    create_names(&wrk);
+
+    let mut cmd = wrk.command("tojsonl");
+    cmd.arg("in.csv").args(["--max-record-bytes", "25"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(
+        got.ends_with("Record 2 is 29 bytes, larger than --max-record-bytes 25.\n"),
+        "{got}"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn tojsonl_max_record_bytes_truncate() {
+    let wrk = Workdir::new("tojsonl_max_record_bytes_truncate");
+    create_names(&wrk);
+
+    let mut cmd = wrk.command("tojsonl");
+    cmd.arg("in.csv")
+        .args(["--max-record-bytes", "25"])
+        .args(["--oversize", "truncate"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"{"id":1,"name":"Al"}
+{"id":2,"name":"Barthol"}
+{"id":3,"name":"Cy"}"#;
+    assert_eq!(got, expected);
+
+    // there are no string values left to truncate
+    let mut cmd = wrk.command("tojsonl");
+    cmd.arg("in.csv")
+        .args(["--max-record-bytes", "10"])
+        .args(["--oversize", "truncate"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn tojsonl_split_output() {
+    let wrk = Workdir::new("tojsonl_split_output");
+    create_names(&wrk);
+
+    let mut cmd = wrk.command("tojsonl");
+    cmd.arg("in.csv")
+        .args(["--output", "rows.jsonl"])
+        .args(["--split-output", "2"]);
+    wrk.assert_success(&mut cmd);
+
+    assert_eq!(
+        dos2unix(&wrk.read_to_string("rows-0001.jsonl")),
+        "{\"id\":1,\"name\":\"Al\"}\n{\"id\":2,\"name\":\"Bartholomew\"}\n"
+    );
+    assert_eq!(
+        dos2unix(&wrk.read_to_string("rows-0002.jsonl")),
+        "{\"id\":3,\"name\":\"Cy\"}\n"
+    );
+    assert!(!wrk.path("rows.jsonl").exists());
+
+    // each part is at most 45 bytes, so every record gets its own part
+    let mut cmd = wrk.command("tojsonl");
+    cmd.arg("in.csv")
+        .args(["--output", "bytes.jsonl"])
+        .args(["--split-output", "45B"]);
+    wrk.assert_success(&mut cmd);
+
+    assert_eq!(
+        dos2unix(&wrk.read_to_string("bytes-0002.jsonl")),
+        "{\"id\":2,\"name\":\"Bartholomew\"}\n"
+    );
+    assert!(wrk.path("bytes-0003.jsonl").exists());
+    assert!(!wrk.path("bytes-0004.jsonl").exists());
+}
+
+#[test]
+fn tojsonl_split_output_requires_output() {
+    let wrk = Workdir::new("tojsonl_split_output_requires_output");
+    create_names(&wrk);
+
+    let mut cmd = wrk.command("tojsonl");
+    cmd.arg("in.csv").args(["--split-output", "2"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "usage error: --split-output requires --output.\n");
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,83 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "city", "price", "day"],
+        svec!["1", "Denver", "10.5", "2023-01-15"],
+        svec!["2", "Boston", "3.25", "2023-02-28"],
+        svec!["3", "Denver", "", "2023-12-01"],
+    ]
+}
+
+#[test]
+fn toparquet_roundtrip() {
+    let wrk = Workdir::new("toparquet_roundtrip");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("toparquet");
+    cmd.arg("data.csv").args(["--output", "data.parquet"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("SKIP_INPUT")
+        .arg("select * from read_parquet('data.parquet')");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data());
+}
+
+#[test]
+fn toparquet_typed_columns() {
+    let wrk = Workdir::new("toparquet_typed_columns");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("toparquet");
+    cmd.arg("data.csv").args(["--output", "data.parquet"]);
+    wrk.assert_success(&mut cmd);
+
+    // the ids & prices are numbers and the days are dates
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("SKIP_INPUT").arg(
+        "select id * 10 as id10, price * 2 as price2, strftime(day, '%d/%m/%Y') as day from \
+         read_parquet('data.parquet')",
+    );
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id10", "price2", "day"],
+        svec!["10", "21.0", "15/01/2023"],
+        svec!["20", "6.5", "28/02/2023"],
+        svec!["30", "", "01/12/2023"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn toparquet_options() {
+    let wrk = Workdir::new("toparquet_options");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("toparquet");
+    cmd.arg("data.csv")
+        .args(["--compression", "snappy"])
+        .args(["--row-group-size", "2"])
+        .args(["--dictionary", "--statistics"])
+        .args(["--output", "data.parquet"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("SKIP_INPUT")
+        .arg("select * from read_parquet('data.parquet')");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data());
+}
+
+#[test]
+fn toparquet_invalid_compression() {
+    let wrk = Workdir::new("toparquet_invalid_compression");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("toparquet");
+    cmd.arg("data.csv")
+        .args(["--compression", "brotli"])
+        .args(["--output", "data.parquet"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,63 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["category", "item", "amount"],
+            svec!["fruit", "apple", "3"],
+            svec!["veg", "leek", "10"],
+            svec!["fruit", "kiwi", "12"],
+            svec!["fruit", "pear", "3"],
+            svec!["veg", "kale", ""],
+            svec!["veg", "corn", "2"],
+            svec!["fruit", "plum", "9"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn topn_group_by() {
+    let wrk = setup("topn_group_by");
+    let mut cmd = wrk.command("topn");
+    cmd.args(["--group-by", "category", "--by", "amount", "--n", "3"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["category", "item", "amount"],
+        svec!["fruit", "kiwi", "12"],
+        svec!["fruit", "plum", "9"],
+        svec!["fruit", "apple", "3"],
+        svec!["veg", "leek", "10"],
+        svec!["veg", "corn", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn topn_bottom() {
+    let wrk = setup("topn_bottom");
+    let mut cmd = wrk.command("topn");
+    cmd.args(["--by", "amount", "--n", "3", "--bottom"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["category", "item", "amount"],
+        svec!["veg", "corn", "2"],
+        svec!["fruit", "apple", "3"],
+        svec!["fruit", "pear", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn topn_invalid_by() {
+    let wrk = setup("topn_invalid_by");
+    let mut cmd = wrk.command("topn");
+    cmd.args(["--by", "category,amount"]).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
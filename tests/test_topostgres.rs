@@ -0,0 +1,60 @@
+use crate::workdir::Workdir;
+
+// there's no PostgreSQL server to load into in the tests, so they only cover the
+// checks done before connecting, and the connection error
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "city"],
+        svec!["1", "Denver"],
+        svec!["2", "Boston"],
+    ]
+}
+
+#[test]
+fn topostgres_invalid_options() {
+    let wrk = Workdir::new("topostgres_invalid_options");
+    wrk.create("data.csv", data());
+
+    let url = "postgres://qsv@127.0.0.1:1/qsv";
+    let mut cmd = wrk.command("topostgres");
+    cmd.arg(url).args(["--on-error", "retry"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.contains("Invalid --on-error: retry"));
+
+    let mut cmd = wrk.command("topostgres");
+    cmd.arg(url).args(["--batch-size", "0"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("topostgres");
+    cmd.arg(url).arg("--drop").arg("data.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.contains("--drop requires --create."));
+}
+
+#[test]
+fn topostgres_env_url() {
+    let wrk = Workdir::new("topostgres_env_url");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("topostgres");
+    cmd.env_remove("QSV_TEST_MISSING_URL")
+        .arg("env=QSV_TEST_MISSING_URL")
+        .arg("data.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.contains("Environment variable QSV_TEST_MISSING_URL is not set."));
+}
+
+#[test]
+fn topostgres_connection_error() {
+    let wrk = Workdir::new("topostgres_connection_error");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("topostgres");
+    cmd.arg("postgres://qsv@127.0.0.1:1/qsv")
+        .args(["--table", "cities", "--create"])
+        .arg("data.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.contains("PostgreSQL error"));
+}
@@ -0,0 +1,112 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "city", "price", "day"],
+        svec!["1", "Denver", "10.5", "2023-01-15"],
+        svec!["2", "Boston", "3.25", "2023-02-28"],
+        svec!["3", "Denver", "", "2023-12-01"],
+    ]
+}
+
+#[test]
+fn tosqlite_roundtrip() {
+    let wrk = Workdir::new("tosqlite_roundtrip");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite").arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromsqlite");
+    cmd.arg("db.sqlite").args(["--table", "data"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data());
+}
+
+#[test]
+fn tosqlite_typed_columns() {
+    let wrk = Workdir::new("tosqlite_typed_columns");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite")
+        .args(["--table", "orders"])
+        .arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromsqlite");
+    cmd.arg("db.sqlite").args([
+        "--query",
+        "SELECT typeof(id), typeof(price), typeof(day) FROM orders ORDER BY id",
+    ]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["typeof(id)", "typeof(price)", "typeof(day)"],
+        svec!["integer", "real", "text"],
+        svec!["integer", "real", "text"],
+        svec!["integer", "null", "text"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn tosqlite_create_index() {
+    let wrk = Workdir::new("tosqlite_create_index");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite")
+        .args(["--create-index", "city,day"])
+        .arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromsqlite");
+    cmd.arg("db.sqlite").args([
+        "--query",
+        "SELECT name FROM sqlite_master WHERE type = 'index' ORDER BY name",
+    ]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name"], svec!["idx_data_city"], svec!["idx_data_day"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite")
+        .args(["--table", "other", "--create-index", "country"])
+        .arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn tosqlite_existing_table() {
+    let wrk = Workdir::new("tosqlite_existing_table");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite").arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    // the table already exists
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite").arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite").arg("--append").arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromsqlite");
+    cmd.arg("db.sqlite")
+        .args(["--query", "SELECT count(*) AS n FROM data"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["n"], svec!["6"]]);
+
+    let mut cmd = wrk.command("tosqlite");
+    cmd.arg("db.sqlite").arg("--replace").arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("fromsqlite");
+    cmd.arg("db.sqlite").args(["--table", "data"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data());
+}
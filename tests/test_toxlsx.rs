@@ -0,0 +1,180 @@
+use crate::workdir::Workdir;
+
+fn typed_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["zip", "amount", "day", "name"],
+        svec!["00501", "1.5", "2023-01-15", "Alice"],
+        svec!["02134", "20", "2023-02-28", "Bob"],
+        svec!["10001", "3.25", "2023-12-01", ""],
+    ]
+}
+
+#[test]
+fn toxlsx_roundtrip() {
+    let wrk = Workdir::new("toxlsx_roundtrip");
+    wrk.create("in.csv", typed_data());
+
+    let xlsx_file = wrk.path("out.xlsx").to_string_lossy().to_string();
+    let mut cmd = wrk.command("toxlsx");
+    cmd.arg(&xlsx_file).arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("excel");
+    cmd.arg(&xlsx_file).args(["--sheet", "in"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, typed_data());
+}
+
+#[test]
+fn toxlsx_typed_cells() {
+    let wrk = Workdir::new("toxlsx_typed_cells");
+    wrk.create("in.csv", typed_data());
+
+    let xlsx_file = wrk.path("out.xlsx").to_string_lossy().to_string();
+    let mut cmd = wrk.command("toxlsx");
+    cmd.arg(&xlsx_file).arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    // the dates are date cells, so they're reformatted. The zip codes with leading
+    // zeros are text, so they're kept as is.
+    let mut cmd = wrk.command("excel");
+    cmd.arg(&xlsx_file).args(["--date-format", "%d/%m/%Y"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["zip", "amount", "day", "name"],
+        svec!["00501", "1.5", "15/01/2023", "Alice"],
+        svec!["02134", "20", "28/02/2023", "Bob"],
+        svec!["10001", "3.25", "01/12/2023", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn toxlsx_no_dates() {
+    let wrk = Workdir::new("toxlsx_no_dates");
+    wrk.create("in.csv", typed_data());
+
+    let xlsx_file = wrk.path("out.xlsx").to_string_lossy().to_string();
+    let mut cmd = wrk.command("toxlsx");
+    cmd.arg(&xlsx_file)
+        .args(["--dates-whitelist", "none"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("excel");
+    cmd.arg(&xlsx_file).args(["--date-format", "%d/%m/%Y"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, typed_data());
+}
+
+#[test]
+fn toxlsx_sheet_autofilter_freeze_header() {
+    let wrk = Workdir::new("toxlsx_sheet_autofilter_freeze_header");
+    wrk.create("in.csv", typed_data());
+
+    let xlsx_file = wrk.path("out.xlsx").to_string_lossy().to_string();
+    let mut cmd = wrk.command("toxlsx");
+    cmd.arg(&xlsx_file)
+        .args(["--sheet", "Results"])
+        .args(["--autofilter", "--freeze-header"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("excel");
+    cmd.arg(&xlsx_file).args(["--sheet", "Results"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, typed_data());
+}
+
+#[test]
+fn toxlsx_multiple_sheets() {
+    let wrk = Workdir::new("toxlsx_multiple_sheets");
+    let cities = vec![
+        svec!["city", "population"],
+        svec!["Boston", "675647"],
+        svec!["Denver", "715522"],
+    ];
+    let places = vec![svec!["place", "visits"], svec!["Museum", "12"]];
+    wrk.create("cities.csv", cities.clone());
+    wrk.create("places.csv", places.clone());
+
+    let xlsx_file = wrk.path("out.xlsx").to_string_lossy().to_string();
+    let mut cmd = wrk.command("toxlsx");
+    cmd.arg(&xlsx_file).arg("cities.csv").arg("places.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("excel");
+    cmd.arg(&xlsx_file).args(["--sheet", "cities"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, cities);
+
+    let mut cmd = wrk.command("excel");
+    cmd.arg(&xlsx_file).args(["--sheet", "places"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, places);
+}
+
+#[test]
+fn toxlsx_sheet_names() {
+    let wrk = Workdir::new("toxlsx_sheet_names");
+    let data = vec![svec!["a"], svec!["1"]];
+    wrk.create("data.csv", data.clone());
+    wrk.create("other.csv", data.clone());
+
+    let xlsx_file = wrk.path("out.xlsx").to_string_lossy().to_string();
+    let mut cmd = wrk.command("toxlsx");
+    cmd.arg(&xlsx_file)
+        .args(["--sheet", "First,Second"])
+        .arg("data.csv")
+        .arg("other.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("excel");
+    cmd.arg(&xlsx_file).args(["--sheet", "Second"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data);
+
+    // the same file twice gets a numbered worksheet name
+    let mut cmd = wrk.command("toxlsx");
+    cmd.arg(&xlsx_file).arg("data.csv").arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("excel");
+    cmd.arg(&xlsx_file).args(["--sheet", "data (2)"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data);
+
+    // one name per input
+    let mut cmd = wrk.command("toxlsx");
+    cmd.arg(&xlsx_file)
+        .args(["--sheet", "First"])
+        .arg("data.csv")
+        .arg("other.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn toxlsx_dir() {
+    let wrk = Workdir::new("toxlsx_dir");
+    let data = vec![svec!["a", "b"], svec!["1", "x"]];
+    wrk.create_subdir("parts").unwrap();
+    wrk.create("parts/part1.csv", data.clone());
+    wrk.create("parts/part2.csv", data.clone());
+    wrk.create_from_string("parts/notes.txt", "not a CSV");
+
+    let xlsx_file = wrk.path("out.xlsx").to_string_lossy().to_string();
+    let mut cmd = wrk.command("toxlsx");
+    cmd.arg(&xlsx_file).arg("parts");
+    wrk.assert_success(&mut cmd);
+
+    for sheet in ["part1", "part2"] {
+        let mut cmd = wrk.command("excel");
+        cmd.arg(&xlsx_file).args(["--sheet", sheet]);
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        assert_eq!(got, data);
+    }
+
+    let mut cmd = wrk.command("excel");
+    cmd.arg(&xlsx_file).args(["--sheet", "notes"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,113 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "x", "y"],
+            svec!["a", "1", "-2.50"],
+            svec!["b", "2", ""],
+            svec!["c", "3", "8"],
+            svec!["d", "4", "0.125"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn transform_minmax() {
+    let wrk = setup("transform_minmax");
+    let mut cmd = wrk.command("transform");
+    cmd.args(["x,y", "--op", "minmax"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "x", "y"],
+        svec!["a", "0", "0"],
+        svec!["b", "0.3333", ""],
+        svec!["c", "0.6667", "1"],
+        svec!["d", "1", "0.25"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn transform_zscore_append() {
+    let wrk = setup("transform_zscore_append");
+    let mut cmd = wrk.command("transform");
+    cmd.args(["x", "--op", "zscore", "--append"]).arg("in.csv");
+
+    // the mean is 2.5 & the population stddev is sqrt(1.25)
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "x", "y", "x_zscore"],
+        svec!["a", "1", "-2.50", "-1.3416"],
+        svec!["b", "2", "", "-0.4472"],
+        svec!["c", "3", "8", "0.4472"],
+        svec!["d", "4", "0.125", "1.3416"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn transform_zscore_stats_cache() {
+    let wrk = setup("transform_zscore_stats_cache");
+    let mut stats_cmd = wrk.command("stats");
+    stats_cmd.arg("--stats-jsonl").arg("in.csv");
+    wrk.assert_success(&mut stats_cmd);
+
+    let mut cmd = wrk.command("transform");
+    cmd.args(["x", "--op", "zscore", "--round", "2"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "x", "y"],
+        svec!["a", "-1.34", "-2.50"],
+        svec!["b", "-0.45", ""],
+        svec!["c", "0.45", "8"],
+        svec!["d", "1.34", "0.125"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn transform_abs_round_stdin() {
+    let wrk = setup("transform_abs_round_stdin");
+    let mut cmd = wrk.command("transform");
+    cmd.args(["y", "--op", "abs"])
+        .stdin(std::fs::File::open(wrk.path("in.csv")).unwrap());
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "x", "y"],
+        svec!["a", "1", "2.50"],
+        svec!["b", "2", ""],
+        svec!["c", "3", "8"],
+        svec!["d", "4", "0.125"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("transform");
+    cmd.args(["y", "--op", "round:1"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "x", "y"],
+        svec!["a", "1", "-2.5"],
+        svec!["b", "2", ""],
+        svec!["c", "3", "8"],
+        svec!["d", "4", "0.1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn transform_log_not_positive() {
+    let wrk = setup("transform_log_not_positive");
+    let mut cmd = wrk.command("transform");
+    cmd.args(["y", "--op", "log"]).arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
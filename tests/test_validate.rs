@@ -1,3 +1,5 @@
+use std::{io::Write, process::Stdio};
+
 use crate::workdir::Workdir;
 
 #[test]
@@ -233,6 +235,66 @@ fn validate_bad_csv_prettyjson() {
     wrk.assert_err(&mut cmd);
 }
 
+#[test]
+fn validate_strict_level_2() {
+    let wrk = Workdir::new("validate_strict_level_2");
+    wrk.create_from_string(
+        "data.csv",
+        "id,name\n1,Alice \n2,\"Bob\" Jr\n3,O\"Brien\n4\n",
+    );
+    let mut cmd = wrk.command("validate");
+    cmd.args(["--strict-level", "2"]).arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"level,check,line,byte_offset,detail
+2,unescaped_quote,3,24,data after the closing quote of field 2
+2,unescaped_quote,4,31,unescaped quote in field 2
+1,field_count,5,38,"record has 1 fields, expected 2""#;
+    assert_eq!(got, expected);
+
+    let got_err: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(got_err, "3 finding(s) at strict level 2.\n");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_strict_level_3_json() {
+    let wrk = Workdir::new("validate_strict_level_3_json");
+    wrk.create_from_string("data.csv", "\u{feff}id,name\r\n1,Alice \r\n2,Bob\n");
+
+    // the same file is valid at level 1
+    let mut cmd = wrk.command("validate");
+    cmd.args(["--strict-level", "1"]).arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("validate");
+    cmd.args(["--strict-level", "3", "--json"]).arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"{"strict_level":3,"num_records":2,"findings":[{"level":3,"check":"bom","line":1,"byte_offset":0,"detail":"the file starts with a UTF-8 BOM"},{"level":3,"check":"trailing_space","line":2,"byte_offset":19,"detail":"field 2 has 1 trailing space(s)"},{"level":3,"check":"line_terminator","line":3,"byte_offset":27,"detail":"1 line(s) do not end with CRLF"}]}"#;
+    assert_eq!(got, expected);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_strict_level_with_schema() {
+    let wrk = Workdir::new("validate_strict_level_with_schema");
+    wrk.create_from_string("data.csv", "id\n1\n");
+    wrk.create_from_string("schema.json", "{}");
+    let mut cmd = wrk.command("validate");
+    cmd.args(["--strict-level", "2"])
+        .arg("data.csv")
+        .arg("schema.json");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --strict-level can only be used when validating without a JSON \
+         Schema.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
 fn adur_errors() -> &'static str {
     r#"row_number	field	error
 1	ExtractDate	null is not of type "string"
@@ -374,3 +436,1163 @@ fn validate_adur_public_toilets_dataset_with_json_schema_url() {
     assert_eq!(adur_errors(), validation_error_output);
     wrk.assert_err(&mut cmd);
 }
+
+#[test]
+fn validate_adur_public_toilets_dataset_with_json_schema_valid_output_with_invalids() {
+    let wrk = Workdir::new("validate_valid_output_with_invalids").flexible(true);
+
+    // copy schema file to workdir
+    let schema: String = wrk.load_test_resource("public-toilets-schema.json");
+    wrk.create_from_string("schema.json", &schema);
+
+    // copy csv file to workdir
+    let csv: String = wrk.load_test_resource("adur-public-toilets.csv");
+    wrk.create_from_string("data.csv", &csv);
+
+    // run validate command
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--valid-output", "-"]);
+
+    // valid records are sent to stdout - 12 valid records plus the header
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 13);
+    assert_eq!(got[0][0], "ExtractDate");
+
+    // invalid records and errors are still written to their files
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(adur_invalids().to_string(), invalid_output);
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    assert_eq!(adur_errors(), validation_error_output);
+
+    // no valid suffixed file is created
+    assert!(!wrk.path("data.csv.valid").exists());
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_adur_public_toilets_dataset_with_json_schema_fail_fast() {
+    let wrk = Workdir::new("validate_fail_fast").flexible(true);
+
+    let schema: String = wrk.load_test_resource("public-toilets-schema.json");
+    wrk.create_from_string("schema.json", &schema);
+
+    let csv: String = wrk.load_test_resource("adur-public-toilets.csv");
+    wrk.create_from_string("data.csv", &csv);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").arg("schema.json").arg("--fail-fast");
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "fail-fast enabled. stopped after row 1.\n1 out of 1 records invalid.\n"
+    );
+
+    // only the errors of the first invalid record are reported
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+1	ExtractDate	null is not of type "string"
+1	OrganisationLabel	null is not of type "string"
+"#;
+    assert_eq!(validation_error_output, expected);
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_adur_public_toilets_dataset_with_json_schema_max_errors() {
+    let wrk = Workdir::new("validate_max_errors").flexible(true);
+
+    let schema: String = wrk.load_test_resource("public-toilets-schema.json");
+    wrk.create_from_string("schema.json", &schema);
+
+    let csv: String = wrk.load_test_resource("adur-public-toilets.csv");
+    wrk.create_from_string("data.csv", &csv);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--max-errors", "2"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "max-errors of 2 reached. stopped after row 3.\n2 out of 3 records invalid.\n"
+    );
+
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(adur_invalids().to_string(), invalid_output);
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    assert_eq!(adur_errors(), validation_error_output);
+
+    // only the one valid record checked before stopping is written
+    let valid_output: String = wrk.from_str(&wrk.path("data.csv.valid"));
+    assert_eq!(valid_output.lines().count(), 2);
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_adur_public_toilets_dataset_with_json_schema_small_batches() {
+    let wrk = Workdir::new("validate_small_batches").flexible(true);
+
+    let schema: String = wrk.load_test_resource("public-toilets-schema.json");
+    wrk.create_from_string("schema.json", &schema);
+
+    let csv: String = wrk.load_test_resource("adur-public-toilets.csv");
+    wrk.create_from_string("data.csv", &csv);
+
+    // many small batches validated in parallel still report errors in row order
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--batch", "2", "--jobs", "4"]);
+    wrk.assert_err(&mut cmd);
+
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(adur_invalids().to_string(), invalid_output);
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    assert_eq!(adur_errors(), validation_error_output);
+
+    // stopping early doesn't wait for the rest of the file to be read
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--batch", "1", "--fail-fast"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "fail-fast enabled. stopped after row 1.\n1 out of 1 records invalid.\n"
+    );
+}
+
+#[test]
+fn validate_adur_public_toilets_dataset_with_json_schema_report_json() {
+    let wrk = Workdir::new("validate_report_json").flexible(true);
+
+    let schema: String = wrk.load_test_resource("public-toilets-schema.json");
+    wrk.create_from_string("schema.json", &schema);
+
+    let csv: String = wrk.load_test_resource("adur-public-toilets.csv");
+    wrk.create_from_string("data.csv", &csv);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--report", "json"]);
+    wrk.assert_err(&mut cmd);
+
+    let report: String = wrk.from_str(&wrk.path("data.csv.validation-report.json"));
+    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+    assert_eq!(report["input"], "data.csv");
+    assert_eq!(report["rows_checked"], 14);
+    assert_eq!(report["rows_failed"], 2);
+    assert!(report["duration_secs"].is_f64());
+    assert_eq!(
+        report["columns"],
+        serde_json::json!([
+            {"field": "ExtractDate", "errors": 1, "top_values": [{"value": "", "count": 1}]},
+            {"field": "OrganisationLabel", "errors": 1, "top_values": [{"value": "", "count": 1}]},
+            {
+                "field": "CoordinateReferenceSystem",
+                "errors": 1,
+                "top_values": [{"value": "OSGB3", "count": 1}]
+            },
+            {"field": "Category", "errors": 1, "top_values": [{"value": "Mens", "count": 1}]},
+        ])
+    );
+}
+
+#[test]
+fn validate_adur_public_toilets_dataset_with_json_schema_report_markdown() {
+    let wrk = Workdir::new("validate_report_markdown").flexible(true);
+
+    let schema: String = wrk.load_test_resource("public-toilets-schema.json");
+    wrk.create_from_string("schema.json", &schema);
+
+    let csv: String = wrk.load_test_resource("adur-public-toilets-valid.csv");
+    wrk.create_from_string("data.csv", &csv);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--report", "markdown"]);
+    wrk.assert_success(&mut cmd);
+
+    let report: String = wrk.from_str(&wrk.path("data.csv.validation-report.md"));
+    assert!(report.starts_with(
+        "# Validation report: data.csv\n\n| rows checked | rows failed | duration (secs) |\n"
+    ));
+    assert!(report.contains("| 13 | 0 | "));
+    // no per-column table when all records are valid
+    assert!(!report.contains("| field | errors |"));
+}
+
+#[test]
+fn validate_report_invalid_format() {
+    let wrk = Workdir::new("validate_report_invalid_format").flexible(true);
+
+    let schema: String = wrk.load_test_resource("public-toilets-schema.json");
+    wrk.create_from_string("schema.json", &schema);
+
+    let csv: String = wrk.load_test_resource("adur-public-toilets-valid.csv");
+    wrk.create_from_string("data.csv", &csv);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--report", "xml"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Invalid --report format: xml. Valid formats are json, csv and markdown.\n"
+    );
+}
+
+fn formats_schema(extra_format: &str) -> String {
+    format!(
+        r#"{{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {{
+        "email": {{ "type": "string", "format": "email" }},
+        "phone": {{ "type": "string", "format": "phone" }},
+        "id": {{ "type": "string", "format": "uuid" }},
+        "note": {{ "type": "string", "format": "{extra_format}" }}
+    }}
+}}"#
+    )
+}
+
+fn formats_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["email", "phone", "id", "note"],
+        svec![
+            "jane@example.com",
+            "+1 (555) 123-4567",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "ok"
+        ],
+        svec!["jane@example", "555-1234", "not-a-uuid", "ok"],
+        svec![
+            "j ane@example.com",
+            "12",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "ok"
+        ],
+    ]
+}
+
+#[test]
+fn validate_formats_lax() {
+    let wrk = Workdir::new("validate_formats_lax").flexible(true);
+    wrk.create_from_string("schema.json", &formats_schema("no-such-format"));
+    wrk.create("data.csv", formats_data());
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").arg("schema.json");
+    wrk.assert_err(&mut cmd);
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+2	email	"jane@example" is not a "email"
+2	id	"not-a-uuid" is not a "uuid"
+3	email	"j ane@example.com" is not a "email"
+3	phone	"12" is not a "phone"
+"#;
+    assert_eq!(validation_error_output, expected);
+}
+
+#[test]
+fn validate_progressbar() {
+    let wrk = Workdir::new("validate_progressbar").flexible(true);
+    wrk.create_from_string("schema.json", &formats_schema("no-such-format"));
+    wrk.create("data.csv", formats_data());
+    let expected = r#"row_number	field	error
+2	email	"jane@example" is not a "email"
+2	id	"not-a-uuid" is not a "uuid"
+3	email	"j ane@example.com" is not a "email"
+3	phone	"12" is not a "phone"
+"#;
+
+    // the progress is in bytes read without an index, and in records with one
+    for indexed in [false, true] {
+        if indexed {
+            let mut cmd = wrk.command("index");
+            cmd.arg("data.csv");
+            wrk.assert_success(&mut cmd);
+        }
+
+        let mut cmd = wrk.command("validate");
+        cmd.arg("data.csv").arg("schema.json").arg("--progressbar");
+
+        let got: String = wrk.output_stderr(&mut cmd);
+        assert_eq!(got, "2 out of 3 records invalid.\n");
+        wrk.assert_err(&mut cmd);
+
+        let validation_error_output: String =
+            wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+        assert_eq!(validation_error_output, expected);
+    }
+}
+
+#[test]
+fn validate_formats_off() {
+    let wrk = Workdir::new("validate_formats_off").flexible(true);
+    wrk.create_from_string("schema.json", &formats_schema("no-such-format"));
+    wrk.create("data.csv", formats_data());
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--formats", "off"]);
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn validate_formats_strict_unknown_format() {
+    let wrk = Workdir::new("validate_formats_strict").flexible(true);
+    wrk.create_from_string("schema.json", &formats_schema("no-such-format"));
+    wrk.create("data.csv", formats_data());
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--formats", "strict"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("Cannot compile schema json. error: "));
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_cross_field_rules() {
+    let wrk = Workdir::new("validate_cross_field_rules").flexible(true);
+    wrk.create_from_string(
+        "schema.json",
+        r#"{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {
+        "start_date": { "type": "string" },
+        "end_date": { "type": "string" },
+        "qty": { "type": "integer" },
+        "price": { "type": "number" },
+        "total": { "type": "number", "minimum": 0 }
+    }
+}"#,
+    );
+    wrk.create_from_string(
+        "rules.json",
+        r#"{
+    "dates_in_order": ".end_date >= .start_date",
+    "total_matches": ".total == .qty * .price"
+}"#,
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["start_date", "end_date", "qty", "price", "total"],
+            svec!["2024-01-01", "2024-01-31", "2", "1.5", "3"],
+            svec!["2024-02-10", "2024-02-01", "4", "2.5", "10"],
+            svec!["2024-03-01", "2024-03-02", "3", "2", "-7"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--rules", "rules.json"]);
+    wrk.assert_err(&mut cmd);
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+2	dates_in_order	rule failed
+3	total	-7.0 is less than the minimum of 0
+3	total_matches	rule failed
+"#;
+    assert_eq!(validation_error_output, expected);
+}
+
+#[test]
+fn validate_cross_field_rules_invalid_filter() {
+    let wrk = Workdir::new("validate_cross_field_rules_invalid_filter").flexible(true);
+    wrk.create_from_string("schema.json", &formats_schema("date"));
+    wrk.create_from_string("rules.json", r#"{"broken": ".end_date >="}"#);
+    wrk.create("data.csv", formats_data());
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--rules", "rules.json"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("usage error: Cannot parse rule broken: "));
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_lookup() {
+    let wrk = Workdir::new("validate_lookup").flexible(true);
+    wrk.create_from_string(
+        "schema.json",
+        r#"{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {
+        "order_id": { "type": "integer" },
+        "customer_id": { "type": "string" },
+        "sku": { "type": "string" }
+    }
+}"#,
+    );
+    wrk.create(
+        "customers.csv",
+        vec![svec!["id", "name"], svec!["c1", "Ann"], svec!["c2", "Bob"]],
+    );
+    wrk.create(
+        "products.csv",
+        vec![svec!["sku"], svec!["apple"], svec!["pear"]],
+    );
+    wrk.create(
+        "orders.csv",
+        vec![
+            svec!["order_id", "customer_id", "sku"],
+            svec!["1", "c1", "apple"],
+            svec!["2", "c3", "pear"],
+            svec!["3", "", "plum"],
+            svec!["4", "c9", "kiwi"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("orders.csv")
+        .arg("schema.json")
+        .args(["--lookup", "customer_id:customers.csv:id"])
+        .args(["--lookup", "sku:products.csv:sku"]);
+    wrk.assert_err(&mut cmd);
+
+    let validation_error_output: String =
+        wrk.from_str(&wrk.path("orders.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+2	customer_id	"c3" not found in id of customers.csv
+3	sku	"plum" not found in sku of products.csv
+4	customer_id	"c9" not found in id of customers.csv
+4	sku	"kiwi" not found in sku of products.csv
+"#;
+    assert_eq!(validation_error_output, expected);
+}
+
+#[test]
+fn validate_unique() {
+    let wrk = Workdir::new("validate_unique").flexible(true);
+    wrk.create_from_string(
+        "schema.json",
+        r#"{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {
+        "order_id": { "type": "string" },
+        "line_no": { "type": "integer" },
+        "sku": { "type": "string" }
+    }
+}"#,
+    );
+    wrk.create(
+        "orders.csv",
+        vec![
+            svec!["order_id", "line_no", "sku"],
+            svec!["A1", "1", "apple"],
+            svec!["A1", "2", "pear"],
+            svec!["A2", "1", "apple"],
+            svec!["A1", "2", "plum"],
+            svec!["", "1", "pear"],
+            svec!["", "1", "kiwi"],
+            svec!["A2", "2", "apple"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("orders.csv")
+        .arg("schema.json")
+        .args(["--unique", "order_id,line_no"])
+        .args(["--unique", "sku"]);
+    wrk.assert_err(&mut cmd);
+
+    let validation_error_output: String =
+        wrk.from_str(&wrk.path("orders.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+3	sku	"apple" is a duplicate of row 1
+4	order_id,line_no	("A1", "2") is a duplicate of row 2
+5	sku	"pear" is a duplicate of row 2
+7	sku	"apple" is a duplicate of row 1
+"#;
+    assert_eq!(validation_error_output, expected);
+
+    let invalid_output: String = wrk.from_str(&wrk.path("orders.csv.invalid"));
+    let expected_invalid = "order_id,line_no,sku\nA2,1,apple\nA1,2,plum\n,1,pear\nA2,2,apple\n";
+    assert_eq!(invalid_output, expected_invalid);
+}
+
+fn error_format_schema() -> &'static str {
+    r#"{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {
+        "id": { "type": "integer" },
+        "status": { "type": "string", "enum": ["Active", "Inactive"] }
+    }
+}"#
+}
+
+#[test]
+fn validate_error_format_github() {
+    let wrk = Workdir::new("validate_error_format_github").flexible(true);
+    wrk.create_from_string("schema.json", error_format_schema());
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "status"],
+            svec!["1", "Active"],
+            svec!["2", "Pending"],
+            svec!["3", "Inactive"],
+            svec!["4", "Gone"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--error-format", "github"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"::error file=data.csv,line=3,title=qsv validate%3A status::"Pending" is not one of ["Active","Inactive"]
+::error file=data.csv,line=5,title=qsv validate%3A status::"Gone" is not one of ["Active","Inactive"]"#;
+    assert_eq!(got, expected);
+    wrk.assert_err(&mut cmd);
+
+    // the annotations replace the error report
+    assert!(!wrk.path("data.csv.validation-errors.tsv").exists());
+    assert!(wrk.path("data.csv.invalid").exists());
+}
+
+#[test]
+fn validate_error_format_sarif() {
+    let wrk = Workdir::new("validate_error_format_sarif").flexible(true);
+    wrk.create_from_string("schema.json", error_format_schema());
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "status"],
+            svec!["1", "Active"],
+            svec!["2", "Pending"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--error-format", "sarif"]);
+    wrk.assert_err(&mut cmd);
+
+    let sarif: serde_json::Value =
+        serde_json::from_str(&wrk.read_to_string("data.csv.validation-errors.sarif")).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "qsv validate");
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "status");
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "status");
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(
+        results[0]["message"]["text"],
+        r#""Pending" is not one of ["Active","Inactive"]"#
+    );
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "data.csv");
+    assert_eq!(location["region"]["startLine"], 3);
+}
+
+#[test]
+fn validate_error_format_invalid() {
+    let wrk = Workdir::new("validate_error_format_invalid").flexible(true);
+    wrk.create_from_string("schema.json", error_format_schema());
+    wrk.create(
+        "data.csv",
+        vec![svec!["id", "status"], svec!["1", "Active"]],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--error-format", "junit"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Invalid --error-format: junit. Valid formats are tsv, sarif and github.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_fix() {
+    let wrk = Workdir::new("validate_fix").flexible(true);
+    wrk.create_from_string(
+        "schema.json",
+        r#"{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {
+        "id": { "type": "integer", "minimum": 1 },
+        "active": { "type": "boolean" },
+        "joined": { "type": "string", "format": "date" },
+        "status": { "type": "string", "enum": ["Active", "Inactive"] },
+        "score": { "type": "number", "minimum": 0, "maximum": 100 }
+    }
+}"#,
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "active", "joined", "status", "score"],
+            svec!["1", " yes ", "2024-01-05", "active", "50"],
+            svec!["2.0", "N", "01/06/2024", "Inactive", "150"],
+            svec!["3", "1", "2024-01-07", "Pending", "-5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--fix", "fixed.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let fixed_output: String = wrk.from_str(&wrk.path("fixed.csv"));
+    let expected =
+        "id,active,joined,status,score\n1,true,2024-01-05,Active,50\n2,false,2024-01-06,\
+         Inactive,100\n3,true,2024-01-07,Pending,0\n";
+    assert_eq!(fixed_output, expected);
+
+    let fixes_output: String = wrk.from_str(&wrk.path("data.csv.fixes.tsv"));
+    let expected = r#"row_number	field	original	fixed	fixes
+1	active	 yes 	true	trimmed, normalized boolean
+1	status	active	Active	matched enum case
+2	id	2.0	2	normalized integer
+2	active	N	false	normalized boolean
+2	joined	01/06/2024	2024-01-06	normalized date
+2	score	150	100	clamped to range
+3	active	1	true	normalized boolean
+3	score	-5	0	clamped to range
+"#;
+    assert_eq!(fixes_output, expected);
+
+    // only what couldn't be fixed is invalid
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+3	status	"Pending" is not one of ["Active","Inactive"]
+"#;
+    assert_eq!(validation_error_output, expected);
+
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(
+        invalid_output,
+        "id,active,joined,status,score\n3,true,2024-01-07,Pending,0\n"
+    );
+}
+
+#[test]
+fn validate_fix_range_null() {
+    let wrk = Workdir::new("validate_fix_range_null").flexible(true);
+    wrk.create_from_string(
+        "schema.json",
+        r#"{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {
+        "score": { "type": ["number", "null"], "minimum": 0, "maximum": 100 }
+    }
+}"#,
+    );
+    wrk.create("data.csv", vec![svec!["score"], svec!["50"], svec!["150"]]);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--fix", "fixed.csv", "--fix-range", "null"]);
+    wrk.assert_success(&mut cmd);
+
+    let fixed_output: String = wrk.from_str(&wrk.path("fixed.csv"));
+    assert_eq!(fixed_output, "score\n50\n\"\"\n");
+}
+
+#[test]
+fn validate_lookup_invalid_spec() {
+    let wrk = Workdir::new("validate_lookup_invalid_spec").flexible(true);
+    wrk.create_from_string("schema.json", &formats_schema("date"));
+    wrk.create("data.csv", formats_data());
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--lookup", "email:emails.csv"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Invalid --lookup email:emails.csv. Expected <column>:<lookup CSV>:<lookup \
+         column>.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_regex_rules() {
+    let wrk = Workdir::new("validate_regex_rules").flexible(true);
+    wrk.create(
+        "rules.csv",
+        vec![
+            svec!["column", "regex"],
+            svec!["sku", r"[A-Z]{3}-\d{4}"],
+            svec!["zip", r"\d{5}(-\d{4})?"],
+            svec!["zip", r"[1-9].*"],
+        ],
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["sku", "zip"],
+            svec!["ABC-1234", "12345"],
+            svec!["abc-1234", "12345-678"],
+            svec!["XYZ-0001", ""],
+            svec!["XYZ-00012", "02134"],
+        ],
+    );
+
+    // without a JSON Schema, only the regex rules are checked
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").args(["--regex-rules", "rules.csv"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "2 out of 4 records invalid.\n");
+    wrk.assert_err(&mut cmd);
+
+    let valid_output: String = wrk.from_str(&wrk.path("data.csv.valid"));
+    assert_eq!(valid_output, "sku,zip\nABC-1234,12345\nXYZ-0001,\n");
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(
+        invalid_output,
+        "sku,zip\nabc-1234,12345-678\nXYZ-00012,02134\n"
+    );
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+2	sku	"abc-1234" does not match [A-Z]{3}-\d{4}
+2	zip	"12345-678" does not match \d{5}(-\d{4})?
+4	sku	"XYZ-00012" does not match [A-Z]{3}-\d{4}
+4	zip	"02134" does not match [1-9].*
+"#;
+    assert_eq!(validation_error_output, expected);
+}
+
+#[test]
+fn validate_regex_rules_with_schema() {
+    let wrk = Workdir::new("validate_regex_rules_with_schema").flexible(true);
+    wrk.create_from_string(
+        "schema.json",
+        r#"{
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "properties": {
+        "id": { "type": "integer", "minimum": 1 },
+        "sku": { "type": "string" }
+    }
+}"#,
+    );
+    wrk.create(
+        "rules.csv",
+        vec![svec!["column", "regex"], svec!["sku", r"[A-Z]{3}-\d{4}"]],
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "sku"],
+            svec!["1", "ABC-1234"],
+            svec!["0", "abc"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--regex-rules", "rules.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+2	id	0 is less than the minimum of 1
+2	sku	"abc" does not match [A-Z]{3}-\d{4}
+"#;
+    assert_eq!(validation_error_output, expected);
+}
+
+#[test]
+fn validate_regex_rules_unknown_column() {
+    let wrk = Workdir::new("validate_regex_rules_unknown_column").flexible(true);
+    wrk.create(
+        "rules.csv",
+        vec![svec!["column", "regex"], svec!["phone", r"\d+"]],
+    );
+    wrk.create("data.csv", vec![svec!["sku"], svec!["ABC-1234"]]);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").args(["--regex-rules", "rules.csv"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --regex-rules column phone not found in data.csv.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_exit_codes() {
+    let wrk = Workdir::new("validate_exit_codes").flexible(true);
+    wrk.create_from_string("schema.json", &formats_schema("date"));
+    wrk.create_from_string("broken-schema.json", r#"{"type": "object", "#);
+    wrk.create("data.csv", formats_data());
+    wrk.create(
+        "ragged.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["3"]],
+    );
+
+    let exit_code = |args: &[&str]| {
+        let mut cmd = wrk.command("validate");
+        cmd.args(args);
+        cmd.output().unwrap().status.code()
+    };
+
+    // schema violations
+    assert_eq!(exit_code(&["data.csv", "schema.json"]), Some(1));
+    // incorrect usage
+    assert_eq!(
+        exit_code(&["data.csv", "schema.json", "--formats", "fuzzy"]),
+        Some(2)
+    );
+    // structurally broken CSV, with & without a schema
+    assert_eq!(exit_code(&["ragged.csv"]), Some(6));
+    assert_eq!(exit_code(&["ragged.csv", "schema.json"]), Some(6));
+    assert_eq!(exit_code(&["ragged.csv", "--strict-level", "1"]), Some(6));
+    // the schema itself is invalid
+    assert_eq!(exit_code(&["data.csv", "broken-schema.json"]), Some(7));
+    // I/O errors
+    assert_eq!(exit_code(&["missing.csv"]), Some(8));
+    assert_eq!(exit_code(&["data.csv", "missing-schema.json"]), Some(8));
+}
+
+#[test]
+fn validate_batch_directory_with_json_schema() {
+    let wrk = Workdir::new("validate_batch_directory").flexible(true);
+
+    let schema: String = wrk.load_test_resource("public-toilets-schema.json");
+    wrk.create_from_string("schema.json", &schema);
+
+    wrk.create_subdir("partners").unwrap();
+    let valid_csv: String = wrk.load_test_resource("adur-public-toilets-valid.csv");
+    wrk.create_from_string("partners/a-valid.csv", &valid_csv);
+    let csv: String = wrk.load_test_resource("adur-public-toilets.csv");
+    wrk.create_from_string("partners/b-invalid.csv", &csv);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("partners").arg("schema.json");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["file", "records", "invalid", "status"],
+        svec!["partners/a-valid.csv", "13", "0", "valid"],
+        svec!["partners/b-invalid.csv", "14", "2", "invalid"],
+    ];
+    assert_eq!(got, expected);
+
+    // per-file error artifacts are only written for the invalid file
+    let invalid_output: String = wrk.from_str(&wrk.path("partners/b-invalid.csv.invalid"));
+    assert_eq!(adur_invalids().to_string(), invalid_output);
+    let validation_error_output: String =
+        wrk.from_str(&wrk.path("partners/b-invalid.csv.validation-errors.tsv"));
+    assert_eq!(adur_errors(), validation_error_output);
+    assert!(!wrk.path("partners/a-valid.csv.invalid").exists());
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_batch_multiple_inputs_with_json_schema() {
+    let wrk = Workdir::new("validate_batch_multiple_inputs").flexible(true);
+
+    let schema: String = wrk.load_test_resource("public-toilets-schema.json");
+    wrk.create_from_string("schema.json", &schema);
+
+    let valid_csv: String = wrk.load_test_resource("adur-public-toilets-valid.csv");
+    wrk.create_from_string("a-valid.csv", &valid_csv);
+    let csv: String = wrk.load_test_resource("adur-public-toilets.csv");
+    wrk.create_from_string("b-invalid.csv", &csv);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("b-invalid.csv")
+        .arg("a-valid.csv")
+        .arg("schema.json")
+        .args(["--report", "json"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["file", "records", "invalid", "status"],
+        svec!["a-valid.csv", "13", "0", "valid"],
+        svec!["b-invalid.csv", "14", "2", "invalid"],
+    ];
+    assert_eq!(got, expected);
+
+    // one report per file
+    assert!(wrk.path("a-valid.csv.validation-report.json").exists());
+    assert!(wrk.path("b-invalid.csv.validation-report.json").exists());
+    let invalid_output: String = wrk.from_str(&wrk.path("b-invalid.csv.invalid"));
+    assert_eq!(adur_invalids().to_string(), invalid_output);
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn validate_stdin_with_schema() {
+    let wrk = Workdir::new("validate_stdin_with_schema").flexible(true);
+    wrk.create_from_string("schema.json", error_format_schema());
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("-")
+        .arg("schema.json")
+        .env("QSV_TMPDIR", wrk.path("scratch"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped());
+    let mut child = cmd.spawn().unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"id,status\n1,Active\n2,Pending\n3,Inactive\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success());
+
+    // the invalid records are split out of the spooled stdin
+    let invalid_output: String = wrk.from_str(&wrk.path("stdin.csv.invalid"));
+    assert_eq!(invalid_output, "id,status\n2,Pending\n");
+    let valid_output: String = wrk.from_str(&wrk.path("stdin.csv.valid"));
+    assert_eq!(valid_output, "id,status\n1,Active\n3,Inactive\n");
+    assert!(wrk.path("stdin.csv.validation-errors.tsv").exists());
+
+    // the spool file is removed when validate is done
+    assert_eq!(std::fs::read_dir(wrk.path("scratch")).unwrap().count(), 0);
+}
+
+#[test]
+fn validate_slice() {
+    let wrk = Workdir::new("validate_slice").flexible(true);
+    wrk.create_from_string("schema.json", error_format_schema());
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "status"],
+            svec!["1", "Active"],
+            svec!["2", "Pending"],
+            svec!["3", "Active"],
+            svec!["4", "Inactive"],
+            svec!["5", "Gone"],
+            svec!["6", "Active"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--slice", "3..6"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "1 out of 4 records invalid.\n");
+    wrk.assert_err(&mut cmd);
+
+    // the row numbers are the row numbers in the file
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+5	status	"Gone" is not one of ["Active","Inactive"]
+"#;
+    assert_eq!(validation_error_output, expected);
+
+    // only the checked rows are split
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(invalid_output, "id,status\n5,Gone\n");
+    let valid_output: String = wrk.from_str(&wrk.path("data.csv.valid"));
+    assert_eq!(valid_output, "id,status\n3,Active\n4,Inactive\n6,Active\n");
+}
+
+#[test]
+fn validate_sample_seeded() {
+    let wrk = Workdir::new("validate_sample_seeded").flexible(true);
+    wrk.create_from_string("schema.json", error_format_schema());
+    let mut rows = vec![svec!["id", "status"]];
+    for i in 1..=100 {
+        rows.push(vec![i.to_string(), "Gone".to_string()]);
+    }
+    wrk.create("data.csv", rows);
+
+    let sampled_rows = |seed: &str| -> Vec<u64> {
+        let mut cmd = wrk.command("validate");
+        cmd.arg("data.csv")
+            .arg("schema.json")
+            .args(["--sample", "10", "--seed", seed]);
+        let got = wrk.output_stderr(&mut cmd);
+        assert_eq!(got, "10 out of 10 records invalid.\n");
+
+        let validation_error_output: String =
+            wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+        validation_error_output
+            .lines()
+            .skip(1)
+            .map(|line| line.split('\t').next().unwrap().parse().unwrap())
+            .collect()
+    };
+
+    let rows = sampled_rows("42");
+    assert_eq!(rows.len(), 10);
+    assert!(rows.windows(2).all(|w| w[0] < w[1]), "{rows:?}");
+    // the same seed checks the same sample
+    assert_eq!(sampled_rows("42"), rows);
+}
+
+#[test]
+fn validate_slice_invalid() {
+    let wrk = Workdir::new("validate_slice_invalid").flexible(true);
+    wrk.create_from_string("schema.json", error_format_schema());
+    wrk.create(
+        "data.csv",
+        vec![svec!["id", "status"], svec!["1", "Active"]],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv")
+        .arg("schema.json")
+        .args(["--slice", "5..2"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Invalid --slice: 5..2. Use start..end, start.. or ..end, with 1-based row \
+         numbers and start <= end.\n"
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").args(["--sample", "10"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --slice and --sample can only be used when validating with a JSON Schema.\n"
+    );
+}
+
+#[test]
+fn validate_conditional_requirements() {
+    let wrk = Workdir::new("validate_conditional_requirements").flexible(true);
+    wrk.create_from_string(
+        "schema.json",
+        r#"{
+  "type": "object",
+  "properties": {
+    "id": {"type": "string"},
+    "country": {"type": "string"},
+    "state": {"type": ["string", "null"]},
+    "promo_code": {"type": ["string", "null"]},
+    "discount": {"type": ["string", "null"]}
+  },
+  "required": ["id", "country", "state", "promo_code", "discount"],
+  "if": {"properties": {"country": {"const": "US"}}},
+  "then": {"required": ["state"]},
+  "dependentRequired": {"promo_code": ["discount"]}
+}"#,
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "country", "state", "promo_code", "discount"],
+            svec!["1", "US", "CA", "", ""],
+            svec!["2", "US", "", "", ""],
+            svec!["3", "FR", "", "", ""],
+            svec!["4", "FR", "", "SAVE10", ""],
+            svec!["5", "FR", "", "SAVE10", "10%"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").arg("schema.json");
+    wrk.assert_err(&mut cmd);
+
+    let invalid_output: String = wrk.from_str(&wrk.path("data.csv.invalid"));
+    assert_eq!(
+        invalid_output,
+        "id,country,state,promo_code,discount\n2,US,,,\n4,FR,,SAVE10,\n"
+    );
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+2	state	empty, but required by /then
+4	discount	empty, but required when "promo_code" is not empty (dependentRequired)
+"#;
+    assert_eq!(validation_error_output, expected);
+}
+
+#[test]
+fn validate_one_of_requirements() {
+    let wrk = Workdir::new("validate_one_of_requirements").flexible(true);
+    wrk.create_from_string(
+        "schema.json",
+        r#"{
+  "type": "object",
+  "properties": {
+    "name": {"type": "string"},
+    "email": {"type": ["string", "null"]},
+    "phone": {"type": ["string", "null"]}
+  },
+  "oneOf": [{"required": ["email"]}, {"required": ["phone"]}]
+}"#,
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "email", "phone"],
+            svec!["Alice", "alice@example.com", ""],
+            svec!["Bob", "", "555-0100"],
+            svec!["Carol", "", ""],
+            svec!["Dave", "dave@example.com", "555-0199"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("data.csv").arg("schema.json");
+    wrk.assert_err(&mut cmd);
+
+    let validation_error_output: String = wrk.from_str(&wrk.path("data.csv.validation-errors.tsv"));
+    let expected = r#"row_number	field	error
+3	email	empty, but required by /oneOf/0
+3	phone	empty, but required by /oneOf/1
+4	<RECORD>	more than one subschema succeeded
+"#;
+    assert_eq!(validation_error_output, expected);
+}
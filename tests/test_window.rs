@@ -0,0 +1,101 @@
+use crate::workdir::Workdir;
+
+fn readings() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "date", "value"],
+        svec!["a", "2024-01-03", "3"],
+        svec!["b", "2024-01-01", "10"],
+        svec!["a", "2024-01-01", "1"],
+        svec!["a", "2024-01-02", ""],
+        svec!["b", "2024-01-02", "20"],
+        svec!["a", "2024-01-04", "5"],
+    ]
+}
+
+#[test]
+fn window_partition_order_by() {
+    let wrk = Workdir::new("window_partition_order_by");
+    wrk.create("readings.csv", readings());
+
+    let mut cmd = wrk.command("window");
+    cmd.args(["--partition-by", "id"])
+        .args(["--order-by", "date"])
+        .args(["--rolling", "mean(value):2,count(value):2"])
+        .args(["--cum", "sum(value)"])
+        .arg("readings.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "id",
+            "date",
+            "value",
+            "rolling_mean_value_2",
+            "rolling_count_value_2",
+            "cum_sum_value"
+        ],
+        svec!["b", "2024-01-01", "10", "10", "1", "10"],
+        svec!["a", "2024-01-01", "1", "1", "1", "1"],
+        svec!["a", "2024-01-02", "", "1", "1", "1"],
+        svec!["b", "2024-01-02", "20", "15", "2", "30"],
+        svec!["a", "2024-01-03", "3", "3", "1", "4"],
+        svec!["a", "2024-01-04", "5", "4", "2", "9"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn window_streaming() {
+    let wrk = Workdir::new("window_streaming");
+    wrk.create("readings.csv", readings());
+
+    let mut cmd = wrk.command("window");
+    cmd.args(["--rolling", "min(value):3,max(value):3"])
+        .args(["--cum", "mean(value),count(value)"])
+        .arg("readings.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "id",
+            "date",
+            "value",
+            "rolling_min_value_3",
+            "rolling_max_value_3",
+            "cum_mean_value",
+            "cum_count_value"
+        ],
+        svec!["a", "2024-01-03", "3", "3", "3", "3", "1"],
+        svec!["b", "2024-01-01", "10", "3", "10", "6.5", "2"],
+        svec!["a", "2024-01-01", "1", "1", "10", "4.6667", "3"],
+        svec!["a", "2024-01-02", "", "1", "10", "4.6667", "3"],
+        svec!["b", "2024-01-02", "20", "1", "20", "8.5", "4"],
+        svec!["a", "2024-01-04", "5", "5", "20", "7.8", "5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn window_invalid() {
+    let wrk = Workdir::new("window_invalid");
+    wrk.create("readings.csv", readings());
+
+    let mut cmd = wrk.command("window");
+    cmd.args(["--rolling", "mean(value)"]).arg("readings.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Invalid --rolling aggregation: mean(value). Expected e.g. \
+         mean(value):7.\n"
+    );
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("window");
+    cmd.args(["--cum", "sum(date)"]).arg("readings.csv");
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "Invalid value for cum_sum_date: \"2024-01-03\" is not a number.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
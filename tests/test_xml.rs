@@ -0,0 +1,97 @@
+use crate::workdir::Workdir;
+
+const CUSTOMERS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<export xmlns:c="urn:customers">
+  <c:customer id="1" c:status="active">
+    <name>Alice &amp; Co</name>
+    <address type="home"><city>Boston</city></address>
+    <tag>vip</tag>
+    <tag>early</tag>
+  </c:customer>
+  <c:customer id="2">
+    <name><![CDATA[Bob <Jr>]]></name>
+    <notes/>
+  </c:customer>
+</export>
+"#;
+
+#[test]
+fn xml_all_paths() {
+    let wrk = Workdir::new("xml_all_paths");
+    wrk.create_from_string("customers.xml", CUSTOMERS);
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--record-tag", "customer"]).arg("customers.xml");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "@id",
+            "@status",
+            "name",
+            "address.@type",
+            "address.city",
+            "tag",
+            "notes"
+        ],
+        svec![
+            "1",
+            "active",
+            "Alice & Co",
+            "home",
+            "Boston",
+            "vip|early",
+            ""
+        ],
+        svec!["2", "", "Bob <Jr>", "", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn xml_fields() {
+    let wrk = Workdir::new("xml_fields");
+    wrk.create_from_string("customers.xml", CUSTOMERS);
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["-r", "customer"])
+        .args(["--fields", "name,@id,address.city,missing"])
+        .args(["--separator", ";"])
+        .arg("customers.xml");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "@id", "address.city", "missing"],
+        svec!["Alice & Co", "1", "Boston", ""],
+        svec!["Bob <Jr>", "2", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn xml_default_record_tag() {
+    let wrk = Workdir::new("xml_default_record_tag");
+    wrk.create_from_string(
+        "data.xml",
+        r#"<rows><row a="1"/><row><b>x</b></row><row>text</row></rows>"#,
+    );
+
+    let mut cmd = wrk.command("xml");
+    cmd.arg("data.xml");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["@a", "b", "#text"],
+        svec!["1", "", ""],
+        svec!["", "x", ""],
+        svec!["", "", "text"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn xml_malformed() {
+    let wrk = Workdir::new("xml_malformed");
+    wrk.create_from_string("data.xml", "<rows><row><a>1</b></row></rows>");
+
+    let mut cmd = wrk.command("xml");
+    cmd.arg("data.xml");
+    wrk.assert_err(&mut cmd);
+}
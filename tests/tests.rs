@@ -38,14 +38,26 @@ mod test_applydp;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_behead;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_bin;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_cat;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_cleanup;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_clipboard;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_colops;
 mod test_combos;
 mod test_comments;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_corr;
 mod test_count;
 mod test_datefmt;
 mod test_dedup;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_dedupstream;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_delta;
 mod test_describegpt;
 mod test_diff;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
@@ -69,12 +81,30 @@ mod test_fixlengths;
 mod test_flatten;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_fmt;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_follow;
 #[cfg(all(feature = "foreach"))]
 mod test_foreach;
 mod test_frequency;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+mod test_fromarrow;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+mod test_fromavro;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_fromgeojson;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+mod test_fromparquet;
+#[cfg(all(feature = "to", feature = "feature_capable"))]
+mod test_fromsqlite;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_fwf;
 #[cfg(all(feature = "feature_capable", feature = "geocode"))]
 mod test_geocode;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_groupby;
 mod test_headers;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_implode;
 mod test_index;
 mod test_input;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
@@ -85,15 +115,33 @@ mod test_joinp;
 mod test_json;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_jsonl;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_lag;
 #[cfg(feature = "luau")]
 mod test_luau;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_melt;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_numformat;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_onehot;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_package;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_partition;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_pivot;
 mod test_prompt;
 mod test_pseudo;
 #[cfg(feature = "python")]
 mod test_py;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_rank;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_redaction;
 mod test_rename;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_repl;
 mod test_replace;
 mod test_reverse;
 mod test_safenames;
@@ -103,6 +151,10 @@ mod test_schema;
 mod test_search;
 mod test_searchset;
 mod test_select;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_shareprep;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_sizeest;
 mod test_slice;
 mod test_snappy;
 mod test_sniff;
@@ -117,11 +169,33 @@ mod test_stats;
 mod test_table;
 #[cfg(all(feature = "to", feature = "feature_capable"))]
 mod test_to;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+mod test_toarrow;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+mod test_toavro;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_togeojson;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_tojsonl;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_topn;
+#[cfg(all(feature = "polars", feature = "feature_capable"))]
+mod test_toparquet;
+#[cfg(all(feature = "to", feature = "feature_capable"))]
+mod test_topostgres;
+#[cfg(all(feature = "to", feature = "feature_capable"))]
+mod test_tosqlite;
+#[cfg(all(feature = "to", feature = "feature_capable"))]
+mod test_toxlsx;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_transform;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_transpose;
 mod test_validate;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_window;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_xml;
 
 fn qcheck<T: Testable>(p: T) {
     env::set_var("QSV_SKIPUTF8_CHECK", "1");